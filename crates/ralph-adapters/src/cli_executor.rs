@@ -10,6 +10,7 @@ use crate::cli_backend::{OutputFormat, PromptMode};
 use nix::sys::signal::{Signal, kill};
 #[cfg(unix)]
 use nix::unistd::Pid;
+use ralph_core::FailureClass;
 use std::env;
 use std::io::Write;
 use std::process::Stdio;
@@ -31,10 +32,20 @@ pub struct ExecutionResult {
     pub timed_out: bool,
 }
 
+impl ExecutionResult {
+    /// Classifies a failed execution for backend failover.
+    ///
+    /// Returns `None` when the execution succeeded.
+    pub fn failure_class(&self) -> Option<FailureClass> {
+        FailureClass::classify(self.success, self.timed_out, &self.output)
+    }
+}
+
 /// Executor for running prompts through CLI backends.
 #[derive(Debug)]
 pub struct CliExecutor {
     backend: CliBackend,
+    working_dir: Option<std::path::PathBuf>,
 }
 
 enum StreamEvent {
@@ -52,7 +63,16 @@ enum StreamKind {
 impl CliExecutor {
     /// Creates a new executor with the given backend.
     pub fn new(backend: CliBackend) -> Self {
-        Self { backend }
+        Self {
+            backend,
+            working_dir: None,
+        }
+    }
+
+    /// Runs the backend in `dir` instead of the current directory.
+    pub fn with_working_dir(mut self, dir: impl Into<std::path::PathBuf>) -> Self {
+        self.working_dir = Some(dir.into());
+        self
     }
 
     /// Executes a prompt and streams output to the provided writer.
@@ -82,8 +102,12 @@ impl CliExecutor {
 
         // Set working directory to current directory (mirrors PTY executor behavior)
         // Use fallback to "." if current_dir fails (e.g., E2E test workspaces)
-        let cwd = std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("."));
+        let cwd = self.working_dir.clone().unwrap_or_else(|| {
+            std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("."))
+        });
         command.current_dir(&cwd);
+        // An abandoned execution (e.g. a timed-out fan-out branch) must not leave the agent running.
+        command.kill_on_drop(true);
         inject_ralph_runtime_env(&mut command, &cwd);
 
         // Apply backend-specific environment variables (e.g., Agent Teams env var)
@@ -323,6 +347,7 @@ mod tests {
         assert!(result.success);
         assert!(!result.timed_out);
         assert!(result.output.contains("hello world"));
+        assert_eq!(result.failure_class(), None);
     }
 
     #[tokio::test]
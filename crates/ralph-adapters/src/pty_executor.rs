@@ -27,6 +27,7 @@ use nix::sys::signal::{Signal, kill};
 #[cfg(unix)]
 use nix::unistd::Pid;
 use portable_pty::{CommandBuilder, PtyPair, PtySize, native_pty_system};
+use ralph_core::FailureClass;
 use std::env;
 use std::io::{self, Read, Write};
 use std::sync::Arc;
@@ -66,6 +67,22 @@ pub struct PtyExecutionResult {
     pub cache_write_tokens: u64,
 }
 
+impl PtyExecutionResult {
+    /// Classifies a failed execution for backend failover.
+    ///
+    /// User-initiated terminations (Ctrl+C, Ctrl+\) are never classified, so
+    /// interrupting an iteration doesn't switch backends.
+    pub fn failure_class(&self) -> Option<FailureClass> {
+        match self.termination {
+            TerminationType::UserInterrupt | TerminationType::ForceKill => None,
+            TerminationType::IdleTimeout => FailureClass::classify(false, true, ""),
+            TerminationType::Natural => {
+                FailureClass::classify(self.success, false, &self.stripped_output)
+            }
+        }
+    }
+}
+
 /// How the PTY process was terminated.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum TerminationType {
@@ -2095,6 +2112,30 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_failure_class_ignores_user_interrupts() {
+        let mut result = PtyExecutionResult {
+            output: String::new(),
+            stripped_output: String::from("Error: 429 Too Many Requests"),
+            extracted_text: String::new(),
+            success: false,
+            exit_code: Some(1),
+            termination: TerminationType::Natural,
+            total_cost_usd: 0.0,
+            input_tokens: 0,
+            output_tokens: 0,
+            cache_read_tokens: 0,
+            cache_write_tokens: 0,
+        };
+        assert_eq!(result.failure_class(), Some(FailureClass::RateLimit));
+
+        result.termination = TerminationType::IdleTimeout;
+        assert_eq!(result.failure_class(), Some(FailureClass::Timeout));
+
+        result.termination = TerminationType::UserInterrupt;
+        assert_eq!(result.failure_class(), None);
+    }
+
     #[test]
     fn test_build_result_includes_extracted_text() {
         // Test that build_result properly handles extracted_text
@@ -0,0 +1,274 @@
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, FixedOffset};
+use ralph_core::FileLock;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use tracing::warn;
+
+use crate::errors::ApiError;
+use crate::loop_support::now_ts;
+
+const DEFAULT_LIST_LIMIT: usize = 100;
+
+/// Outcome of an audited mutation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditResult {
+    Ok,
+    Error,
+    /// Served from the idempotency store without re-running the mutation.
+    Replayed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditEntry {
+    pub timestamp: String,
+    pub principal: String,
+    pub method: String,
+    pub request_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resource_id: Option<String>,
+    pub params_digest: String,
+    pub idempotency_key: String,
+    pub result: AuditResult,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_code: Option<String>,
+}
+
+impl AuditEntry {
+    pub fn new(
+        principal: &str,
+        method: &str,
+        request_id: &str,
+        params: &Value,
+        idempotency_key: &str,
+        result: AuditResult,
+    ) -> Self {
+        Self {
+            timestamp: now_ts(),
+            principal: principal.to_string(),
+            method: method.to_string(),
+            request_id: request_id.to_string(),
+            resource_id: params
+                .get("id")
+                .and_then(Value::as_str)
+                .map(ToString::to_string),
+            params_digest: params_digest(params),
+            idempotency_key: idempotency_key.to_string(),
+            result,
+            error_code: None,
+        }
+    }
+
+    #[must_use]
+    pub fn with_error_code(mut self, code: impl Into<String>) -> Self {
+        self.error_code = Some(code.into());
+        self
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditListParams {
+    pub since: Option<String>,
+    pub until: Option<String>,
+    pub principal: Option<String>,
+    pub method: Option<String>,
+    pub resource_id: Option<String>,
+    pub limit: Option<usize>,
+}
+
+/// Append-only audit log of control-plane mutations (`.ralph/api/audit.jsonl`).
+///
+/// Appends take an exclusive `FileLock` so entries from concurrent API and
+/// MCP processes serving the same workspace never interleave.
+#[derive(Debug, Clone)]
+pub struct AuditLog {
+    path: PathBuf,
+}
+
+impl AuditLog {
+    pub fn new(workspace_root: impl AsRef<Path>) -> Self {
+        Self {
+            path: workspace_root.as_ref().join(".ralph/api/audit.jsonl"),
+        }
+    }
+
+    pub fn record(&self, entry: &AuditEntry) {
+        if let Err(error) = self.try_append(entry) {
+            warn!(path = %self.path.display(), %error, "failed appending audit entry");
+        }
+    }
+
+    /// Returns matching entries in chronological order, keeping the newest
+    /// `limit` of them.
+    pub fn list(&self, params: &AuditListParams) -> Result<Vec<AuditEntry>, ApiError> {
+        let since = params.since.as_deref().map(parse_bound).transpose()?;
+        let until = params.until.as_deref().map(parse_bound).transpose()?;
+
+        let entries = self
+            .read_entries()
+            .map_err(|error| ApiError::internal(format!("failed reading audit log: {error}")))?;
+
+        let mut matches: Vec<AuditEntry> = entries
+            .into_iter()
+            .filter(|entry| {
+                params
+                    .principal
+                    .as_ref()
+                    .is_none_or(|principal| entry.principal == *principal)
+                    && params
+                        .method
+                        .as_ref()
+                        .is_none_or(|method| entry.method == *method)
+                    && params
+                        .resource_id
+                        .as_ref()
+                        .is_none_or(|id| entry.resource_id.as_ref() == Some(id))
+            })
+            .filter(|entry| {
+                if since.is_none() && until.is_none() {
+                    return true;
+                }
+                let Ok(timestamp) = DateTime::parse_from_rfc3339(&entry.timestamp) else {
+                    return false;
+                };
+                since.is_none_or(|since| timestamp >= since)
+                    && until.is_none_or(|until| timestamp <= until)
+            })
+            .collect();
+
+        let limit = params.limit.unwrap_or(DEFAULT_LIST_LIMIT);
+        if matches.len() > limit {
+            matches.drain(..matches.len() - limit);
+        }
+        Ok(matches)
+    }
+
+    fn try_append(&self, entry: &AuditEntry) -> io::Result<()> {
+        let lock = FileLock::new(&self.path)?;
+        let _guard = lock.exclusive()?;
+
+        let mut line = serde_json::to_vec(entry).map_err(io::Error::other)?;
+        line.push(b'\n');
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?
+            .write_all(&line)
+    }
+
+    fn read_entries(&self) -> io::Result<Vec<AuditEntry>> {
+        let lock = FileLock::new(&self.path)?;
+        let _guard = lock.shared()?;
+        let content = match fs::read_to_string(&self.path) {
+            Ok(content) => content,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(error) => return Err(error),
+        };
+
+        Ok(content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .filter_map(|line| match serde_json::from_str::<AuditEntry>(line) {
+                Ok(entry) => Some(entry),
+                Err(error) => {
+                    warn!(path = %self.path.display(), %error, "skipping unreadable audit entry");
+                    None
+                }
+            })
+            .collect())
+    }
+}
+
+/// Stable digest of request params, so the log records what was asked
+/// without persisting payload contents.
+pub fn params_digest(params: &Value) -> String {
+    let bytes = serde_json::to_vec(params).unwrap_or_default();
+    format!("sha256:{:x}", Sha256::digest(&bytes))
+}
+
+fn parse_bound(value: &str) -> Result<DateTime<FixedOffset>, ApiError> {
+    DateTime::parse_from_rfc3339(value).map_err(|error| {
+        ApiError::invalid_params(format!("invalid RFC3339 timestamp '{value}': {error}"))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{AuditEntry, AuditListParams, AuditLog, AuditResult, params_digest};
+
+    #[test]
+    fn records_and_filters_entries() {
+        let workspace = tempfile::tempdir().expect("workspace tempdir");
+        let log = AuditLog::new(workspace.path());
+
+        log.record(&AuditEntry::new(
+            "alice",
+            "loop.discard",
+            "req-1",
+            &json!({ "id": "loop-1" }),
+            "idem-1",
+            AuditResult::Ok,
+        ));
+        log.record(
+            &AuditEntry::new(
+                "bob",
+                "task.delete",
+                "req-2",
+                &json!({ "id": "task-1" }),
+                "idem-2",
+                AuditResult::Error,
+            )
+            .with_error_code("TASK_NOT_FOUND"),
+        );
+
+        let reopened = AuditLog::new(workspace.path());
+        let all = reopened.list(&AuditListParams::default()).expect("list");
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[0].resource_id.as_deref(), Some("loop-1"));
+        assert_eq!(all[1].error_code.as_deref(), Some("TASK_NOT_FOUND"));
+
+        let by_principal = reopened
+            .list(&AuditListParams {
+                principal: Some("alice".to_string()),
+                ..AuditListParams::default()
+            })
+            .expect("list by principal");
+        assert_eq!(by_principal.len(), 1);
+        assert_eq!(by_principal[0].method, "loop.discard");
+
+        let future = reopened
+            .list(&AuditListParams {
+                since: Some("2999-01-01T00:00:00Z".to_string()),
+                ..AuditListParams::default()
+            })
+            .expect("list since");
+        assert!(future.is_empty());
+
+        assert!(
+            reopened
+                .list(&AuditListParams {
+                    until: Some("yesterday".to_string()),
+                    ..AuditListParams::default()
+                })
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn digest_is_stable_and_hides_params() {
+        let digest = params_digest(&json!({ "id": "secret-loop" }));
+        assert_eq!(digest, params_digest(&json!({ "id": "secret-loop" })));
+        assert_ne!(digest, params_digest(&json!({ "id": "other" })));
+        assert!(digest.starts_with("sha256:"));
+        assert!(!digest.contains("secret"));
+    }
+}
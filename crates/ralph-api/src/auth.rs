@@ -3,9 +3,9 @@ use std::sync::Arc;
 use anyhow::Result;
 use axum::http::{HeaderMap, header};
 
-use crate::config::{ApiConfig, AuthMode};
+use crate::config::{ApiConfig, ApiTokenConfig, AuthMode, DEFAULT_TOKEN_PRINCIPAL, TokenRole};
 use crate::errors::ApiError;
-use crate::protocol::RpcRequestEnvelope;
+use crate::protocol::{RpcRequestEnvelope, is_mutating_method};
 
 pub trait Authenticator: Send + Sync {
     fn authorize(
@@ -33,14 +33,26 @@ impl Authenticator for TrustedLocalAuthenticator {
     }
 }
 
+/// Authenticates bearer tokens and authorizes the request method against the
+/// matching token's scope. The principal is the token's name.
 #[derive(Debug, Clone)]
 pub struct TokenAuthenticator {
-    expected_token: String,
+    tokens: Vec<ApiTokenConfig>,
 }
 
 impl TokenAuthenticator {
+    /// Creates an authenticator for a single full-access token.
     pub fn new(expected_token: String) -> Self {
-        Self { expected_token }
+        Self::with_tokens(vec![ApiTokenConfig {
+            name: DEFAULT_TOKEN_PRINCIPAL.to_string(),
+            token: expected_token,
+            role: TokenRole::Full,
+            methods: vec!["*".to_string()],
+        }])
+    }
+
+    pub fn with_tokens(tokens: Vec<ApiTokenConfig>) -> Self {
+        Self { tokens }
     }
 }
 
@@ -64,13 +76,32 @@ impl Authenticator for TokenAuthenticator {
                 })
         });
 
-        match provided_token {
-            Some(token) if token == self.expected_token => Ok(token),
-            Some(_) => Err(ApiError::unauthorized("invalid token")),
-            None => Err(ApiError::unauthorized(
+        let Some(provided_token) = provided_token else {
+            return Err(ApiError::unauthorized(
                 "token auth is enabled and no token was provided",
-            )),
+            ));
+        };
+
+        let Some(token) = self
+            .tokens
+            .iter()
+            .find(|token| token.token == provided_token)
+        else {
+            return Err(ApiError::unauthorized("invalid token"));
+        };
+
+        if !token_allows(token, &request.method) {
+            return Err(ApiError::forbidden(format!(
+                "token '{}' is not allowed to call '{}'",
+                token.name, request.method
+            ))
+            .with_details(serde_json::json!({
+                "principal": token.name,
+                "method": request.method
+            })));
         }
+
+        Ok(token.name.clone())
     }
 
     fn mode(&self) -> AuthMode {
@@ -78,16 +109,46 @@ impl Authenticator for TokenAuthenticator {
     }
 }
 
+/// Returns whether a token's role and method patterns permit `method`.
+pub fn token_allows(token: &ApiTokenConfig, method: &str) -> bool {
+    if token.role == TokenRole::ReadOnly && is_mutating_method(method) {
+        return false;
+    }
+
+    token
+        .methods
+        .iter()
+        .any(|pattern| method_matches(pattern, method))
+}
+
+fn method_matches(pattern: &str, method: &str) -> bool {
+    if pattern == "*" || pattern == method {
+        return true;
+    }
+
+    pattern
+        .strip_suffix(".*")
+        .and_then(|family| method.strip_prefix(family))
+        .is_some_and(|rest| rest.starts_with('.'))
+}
+
 pub fn from_config(config: &ApiConfig) -> Result<Arc<dyn Authenticator>> {
     match config.auth_mode {
         AuthMode::TrustedLocal => Ok(Arc::new(TrustedLocalAuthenticator)),
         AuthMode::Token => {
-            let token = config
+            let mut tokens = Vec::new();
+            if let Some(token) = config
                 .token
                 .clone()
                 .filter(|token| !token.trim().is_empty())
-                .ok_or_else(|| anyhow::anyhow!("token auth mode requires RALPH_API_TOKEN"))?;
-            Ok(Arc::new(TokenAuthenticator::new(token)))
+            {
+                tokens.extend(TokenAuthenticator::new(token).tokens);
+            }
+            tokens.extend(config.tokens.iter().cloned());
+            if tokens.is_empty() {
+                anyhow::bail!("token auth mode requires RALPH_API_TOKEN or RALPH_API_TOKENS_FILE");
+            }
+            Ok(Arc::new(TokenAuthenticator::with_tokens(tokens)))
         }
     }
 }
@@ -107,10 +168,31 @@ mod tests {
     use axum::http::HeaderMap;
     use serde_json::json;
 
-    use super::{Authenticator, TokenAuthenticator, from_config};
-    use crate::config::{ApiConfig, AuthMode};
+    use super::{Authenticator, TokenAuthenticator, from_config, token_allows};
+    use crate::config::{ApiConfig, ApiTokenConfig, AuthMode, TokenRole};
+    use crate::errors::RpcErrorCode;
     use crate::protocol::parse_request;
 
+    fn scoped_token(name: &str, role: TokenRole, methods: &[&str]) -> ApiTokenConfig {
+        ApiTokenConfig {
+            name: name.to_string(),
+            token: format!("{name}-secret"),
+            role,
+            methods: methods.iter().map(ToString::to_string).collect(),
+        }
+    }
+
+    fn request_for(method: &str, token: &str) -> crate::protocol::RpcRequestEnvelope {
+        parse_request(&json!({
+            "apiVersion": "v1",
+            "id": "req-scoped",
+            "method": method,
+            "params": {},
+            "meta": { "auth": { "mode": "token", "token": token } }
+        }))
+        .expect("request should parse")
+    }
+
     #[test]
     fn token_auth_allows_meta_token() {
         let request = parse_request(&json!({
@@ -140,4 +222,48 @@ mod tests {
         let result = from_config(&config);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn method_patterns_match_exact_methods_and_families() {
+        let token = scoped_token("ci", TokenRole::Full, &["task.*", "loop.status"]);
+        assert!(token_allows(&token, "task.run"));
+        assert!(token_allows(&token, "loop.status"));
+        assert!(!token_allows(&token, "loop.discard"));
+        assert!(!token_allows(&token, "taskx.run"));
+    }
+
+    #[test]
+    fn read_only_role_rejects_mutations_even_when_patterns_match() {
+        let token = scoped_token("dashboard", TokenRole::ReadOnly, &["*"]);
+        assert!(token_allows(&token, "task.list"));
+        assert!(token_allows(&token, "stream.subscribe"));
+        assert!(!token_allows(&token, "task.clear"));
+        assert!(!token_allows(&token, "config.update"));
+    }
+
+    #[test]
+    fn scoped_tokens_authenticate_as_their_name() {
+        let auth = TokenAuthenticator::with_tokens(vec![
+            scoped_token("dashboard", TokenRole::ReadOnly, &["*"]),
+            scoped_token("ci", TokenRole::Full, &["task.*"]),
+        ]);
+
+        let principal = auth
+            .authorize(
+                &request_for("task.list", "dashboard-secret"),
+                &HeaderMap::new(),
+            )
+            .expect("read-only token may list tasks");
+        assert_eq!(principal, "dashboard");
+
+        let error = auth
+            .authorize(&request_for("loop.discard", "ci-secret"), &HeaderMap::new())
+            .expect_err("ci token is scoped to task.*");
+        assert_eq!(error.code, RpcErrorCode::Forbidden);
+
+        let error = auth
+            .authorize(&request_for("task.list", "unknown"), &HeaderMap::new())
+            .expect_err("unknown token");
+        assert_eq!(error.code, RpcErrorCode::Unauthorized);
+    }
 }
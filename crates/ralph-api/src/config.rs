@@ -1,8 +1,10 @@
+use std::collections::HashSet;
 use std::env;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
 use anyhow::{Context, Result};
+use serde::Deserialize;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum AuthMode {
@@ -33,6 +35,76 @@ impl FromStr for AuthMode {
     }
 }
 
+/// Principal name used for the unnamed `RALPH_API_TOKEN`.
+pub const DEFAULT_TOKEN_PRINCIPAL: &str = "default";
+
+/// What a named API token may do regardless of its method patterns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TokenRole {
+    /// Any method matched by the token's patterns.
+    #[default]
+    Full,
+    /// Only non-mutating methods matched by the token's patterns.
+    ReadOnly,
+}
+
+/// A named API token scoped to a set of method patterns.
+///
+/// Patterns are exact method names (`loop.status`), family wildcards
+/// (`task.*`), or `*` for every method.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ApiTokenConfig {
+    pub name: String,
+    pub token: String,
+    #[serde(default)]
+    pub role: TokenRole,
+    #[serde(default = "default_token_methods")]
+    pub methods: Vec<String>,
+}
+
+fn default_token_methods() -> Vec<String> {
+    vec!["*".to_string()]
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TokensFile {
+    tokens: Vec<ApiTokenConfig>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IdempotencyBackend {
+    Memory,
+    File,
+}
+
+impl IdempotencyBackend {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Memory => "memory",
+            Self::File => "file",
+        }
+    }
+}
+
+impl FromStr for IdempotencyBackend {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "memory" | "in_memory" | "in-memory" => Ok(Self::Memory),
+            "file" => Ok(Self::File),
+            other => {
+                anyhow::bail!(
+                    "invalid idempotency backend '{other}'. expected one of: memory, file"
+                )
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct ApiConfig {
     pub host: String,
@@ -40,10 +112,19 @@ pub struct ApiConfig {
     pub served_by: String,
     pub auth_mode: AuthMode,
     pub token: Option<String>,
+    /// Named, scoped tokens accepted in token auth mode (alongside `token`).
+    pub tokens: Vec<ApiTokenConfig>,
     pub idempotency_ttl_secs: u64,
+    pub idempotency_backend: IdempotencyBackend,
     pub workspace_root: PathBuf,
     pub loop_process_interval_ms: u64,
     pub ralph_command: String,
+    /// Maximum number of queued tasks executed at once; `0` disables the
+    /// background task worker.
+    pub task_concurrency: usize,
+    /// Shared HMAC secret for `/hooks/human-response`; the endpoint is
+    /// disabled when unset.
+    pub webhook_secret: Option<String>,
 }
 
 impl Default for ApiConfig {
@@ -56,10 +137,14 @@ impl Default for ApiConfig {
             served_by: "ralph-api".to_string(),
             auth_mode: AuthMode::TrustedLocal,
             token: None,
+            tokens: Vec::new(),
             idempotency_ttl_secs: 60 * 60,
+            idempotency_backend: IdempotencyBackend::File,
             workspace_root,
             loop_process_interval_ms: 30_000,
             ralph_command: "ralph".to_string(),
+            task_concurrency: 1,
+            webhook_secret: None,
         }
     }
 }
@@ -92,12 +177,22 @@ impl ApiConfig {
             config.token = Some(token);
         }
 
+        if let Ok(tokens_file) = env::var("RALPH_API_TOKENS_FILE")
+            && !tokens_file.trim().is_empty()
+        {
+            config.tokens = load_tokens_file(Path::new(&tokens_file))?;
+        }
+
         if let Ok(ttl) = env::var("RALPH_API_IDEMPOTENCY_TTL_SECS") {
             config.idempotency_ttl_secs = ttl.parse::<u64>().with_context(|| {
                 format!("failed parsing RALPH_API_IDEMPOTENCY_TTL_SECS='{ttl}' as u64")
             })?;
         }
 
+        if let Ok(backend) = env::var("RALPH_API_IDEMPOTENCY_BACKEND") {
+            config.idempotency_backend = backend.parse::<IdempotencyBackend>()?;
+        }
+
         if let Ok(workspace_root) = env::var("RALPH_API_WORKSPACE_ROOT") {
             config.workspace_root = PathBuf::from(workspace_root);
         }
@@ -114,18 +209,45 @@ impl ApiConfig {
             config.ralph_command = ralph_command;
         }
 
+        if let Ok(concurrency) = env::var("RALPH_API_TASK_CONCURRENCY") {
+            config.task_concurrency = concurrency.parse::<usize>().with_context(|| {
+                format!("failed parsing RALPH_API_TASK_CONCURRENCY='{concurrency}' as usize")
+            })?;
+        }
+
+        if let Ok(secret) = env::var("RALPH_WEBHOOK_SECRET")
+            && !secret.trim().is_empty()
+        {
+            config.webhook_secret = Some(secret);
+        }
+
         config.validate()?;
         Ok(config)
     }
 
     pub fn validate(&self) -> Result<()> {
         if self.auth_mode == AuthMode::Token
+            && self.tokens.is_empty()
             && self
                 .token
                 .as_deref()
                 .is_none_or(|token| token.trim().is_empty())
         {
-            anyhow::bail!("RALPH_API_TOKEN must be configured when auth mode is token");
+            anyhow::bail!(
+                "RALPH_API_TOKEN or RALPH_API_TOKENS_FILE must be configured when auth mode is token"
+            );
+        }
+
+        validate_tokens(&self.tokens)?;
+        if self.token.is_some()
+            && self
+                .tokens
+                .iter()
+                .any(|token| token.name == DEFAULT_TOKEN_PRINCIPAL)
+        {
+            anyhow::bail!(
+                "API token name '{DEFAULT_TOKEN_PRINCIPAL}' is reserved for RALPH_API_TOKEN"
+            );
         }
 
         if self.auth_mode == AuthMode::TrustedLocal && !is_loopback_host(&self.host) {
@@ -138,6 +260,48 @@ impl ApiConfig {
     }
 }
 
+fn load_tokens_file(path: &Path) -> Result<Vec<ApiTokenConfig>> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("failed reading RALPH_API_TOKENS_FILE '{}'", path.display()))?;
+    let file: TokensFile = serde_yaml::from_str(&content)
+        .with_context(|| format!("failed parsing RALPH_API_TOKENS_FILE '{}'", path.display()))?;
+    Ok(file.tokens)
+}
+
+fn validate_tokens(tokens: &[ApiTokenConfig]) -> Result<()> {
+    let mut names = HashSet::new();
+    let mut secrets = HashSet::new();
+
+    for token in tokens {
+        if token.name.trim().is_empty() {
+            anyhow::bail!("API token names must not be empty");
+        }
+        if !names.insert(token.name.as_str()) {
+            anyhow::bail!("duplicate API token name '{}'", token.name);
+        }
+        if token.token.trim().is_empty() {
+            anyhow::bail!("API token '{}' has an empty token value", token.name);
+        }
+        if !secrets.insert(token.token.as_str()) {
+            anyhow::bail!("API token '{}' reuses another token's value", token.name);
+        }
+        if token.methods.is_empty() {
+            anyhow::bail!("API token '{}' must allow at least one method", token.name);
+        }
+        if let Some(pattern) = token.methods.iter().find(|pattern| {
+            let family = pattern.strip_suffix(".*").unwrap_or(pattern);
+            family.is_empty() || (family.contains('*') && pattern.as_str() != "*")
+        }) {
+            anyhow::bail!(
+                "API token '{}' has invalid method pattern '{pattern}'. expected '*', 'family.*', or an exact method",
+                token.name
+            );
+        }
+    }
+
+    Ok(())
+}
+
 fn is_loopback_host(host: &str) -> bool {
     let normalized = host
         .trim()
@@ -150,16 +314,30 @@ fn is_loopback_host(host: &str) -> bool {
 
 #[cfg(test)]
 mod tests {
-    use super::{ApiConfig, AuthMode};
+    use super::{ApiConfig, ApiTokenConfig, AuthMode, IdempotencyBackend, TokenRole};
 
     #[test]
     fn defaults_are_localhost_and_trusted_local() {
         let config = ApiConfig::default();
         assert_eq!(config.host, "127.0.0.1");
         assert_eq!(config.auth_mode, AuthMode::TrustedLocal);
+        assert_eq!(config.idempotency_backend, IdempotencyBackend::File);
         assert!(config.validate().is_ok());
     }
 
+    #[test]
+    fn parses_idempotency_backend() {
+        assert_eq!(
+            "memory".parse::<IdempotencyBackend>().unwrap(),
+            IdempotencyBackend::Memory
+        );
+        assert_eq!(
+            " FILE ".parse::<IdempotencyBackend>().unwrap(),
+            IdempotencyBackend::File
+        );
+        assert!("sqlite".parse::<IdempotencyBackend>().is_err());
+    }
+
     #[test]
     fn trusted_local_allows_ipv6_loopback() {
         let mut config = ApiConfig::default();
@@ -196,4 +374,49 @@ mod tests {
 
         assert!(config.validate().is_ok());
     }
+
+    #[test]
+    fn token_mode_accepts_named_tokens_without_shared_token() {
+        let mut config = ApiConfig::default();
+        config.auth_mode = AuthMode::Token;
+        config.tokens = serde_yaml::from_str::<super::TokensFile>(
+            r#"
+tokens:
+  - name: dashboard
+    token: dash-secret
+    role: read_only
+  - name: ci
+    token: ci-secret
+    methods: ["task.*", "loop.status"]
+"#,
+        )
+        .expect("tokens file should parse")
+        .tokens;
+
+        assert!(config.validate().is_ok());
+        assert_eq!(config.tokens[0].role, TokenRole::ReadOnly);
+        assert_eq!(config.tokens[0].methods, vec!["*"]);
+        assert_eq!(config.tokens[1].role, TokenRole::Full);
+    }
+
+    #[test]
+    fn rejects_invalid_named_tokens() {
+        let token = |name: &str, secret: &str, methods: &[&str]| ApiTokenConfig {
+            name: name.to_string(),
+            token: secret.to_string(),
+            role: TokenRole::Full,
+            methods: methods.iter().map(ToString::to_string).collect(),
+        };
+
+        let mut config = ApiConfig::default();
+        config.tokens = vec![token("a", "s1", &["*"]), token("a", "s2", &["*"])];
+        assert!(config.validate().is_err(), "duplicate names");
+
+        config.tokens = vec![token("a", "s1", &["task*"])];
+        assert!(config.validate().is_err(), "invalid pattern");
+
+        config.tokens = vec![token("default", "s1", &["*"])];
+        config.token = Some("shared".to_string());
+        assert!(config.validate().is_err(), "reserved name");
+    }
 }
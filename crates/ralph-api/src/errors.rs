@@ -23,6 +23,7 @@ pub enum RpcErrorCode {
     ConfigInvalid,
     IdempotencyConflict,
     BackpressureDropped,
+    CursorExpired,
 }
 
 impl RpcErrorCode {
@@ -47,6 +48,7 @@ impl RpcErrorCode {
             Self::ConfigInvalid => "CONFIG_INVALID",
             Self::IdempotencyConflict => "IDEMPOTENCY_CONFLICT",
             Self::BackpressureDropped => "BACKPRESSURE_DROPPED",
+            Self::CursorExpired => "CURSOR_EXPIRED",
         }
     }
 
@@ -71,6 +73,7 @@ impl RpcErrorCode {
             "CONFIG_INVALID" => Some(Self::ConfigInvalid),
             "IDEMPOTENCY_CONFLICT" => Some(Self::IdempotencyConflict),
             "BACKPRESSURE_DROPPED" => Some(Self::BackpressureDropped),
+            "CURSOR_EXPIRED" => Some(Self::CursorExpired),
             _ => None,
         }
     }
@@ -188,6 +191,10 @@ impl ApiError {
         Self::new(RpcErrorCode::IdempotencyConflict, message)
     }
 
+    pub fn cursor_expired(message: impl Into<String>) -> Self {
+        Self::new(RpcErrorCode::CursorExpired, message)
+    }
+
     pub fn service_unavailable(message: impl Into<String>) -> Self {
         Self::new(RpcErrorCode::ServiceUnavailable, message)
     }
@@ -225,6 +232,7 @@ const fn status_for_code(code: RpcErrorCode) -> StatusCode {
             StatusCode::SERVICE_UNAVAILABLE
         }
         RpcErrorCode::ConfigInvalid => StatusCode::BAD_REQUEST,
+        RpcErrorCode::CursorExpired => StatusCode::GONE,
         RpcErrorCode::Internal => StatusCode::INTERNAL_SERVER_ERROR,
     }
 }
@@ -0,0 +1,154 @@
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use axum::http::HeaderMap;
+use chrono::Utc;
+use ralph_core::LoopRegistry;
+use ralph_webhook::signing::{SIGNATURE_HEADER, TIMESTAMP_HEADER, verify};
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::errors::ApiError;
+use crate::loop_support::loop_not_found_error;
+
+/// Loop ID that always targets the workspace's in-place (primary) loop.
+const PRIMARY_LOOP_ALIAS: &str = "main";
+
+/// Body accepted by `POST /hooks/human-response`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HumanResponseBody {
+    /// Loop that asked the question (`loopId` from the webhook message).
+    #[serde(default)]
+    pub loop_id: Option<String>,
+    pub response: String,
+}
+
+/// Verifies the webhook signature headers against `body`.
+pub fn verify_signature(secret: &str, headers: &HeaderMap, body: &[u8]) -> Result<(), ApiError> {
+    let header = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .ok_or_else(|| ApiError::unauthorized(format!("missing {name} header")))
+    };
+
+    let timestamp = header(TIMESTAMP_HEADER)?
+        .parse::<i64>()
+        .map_err(|_| ApiError::unauthorized(format!("invalid {TIMESTAMP_HEADER} header")))?;
+    let signature = header(SIGNATURE_HEADER)?;
+
+    if verify(secret, timestamp, body, signature, Utc::now().timestamp()) {
+        Ok(())
+    } else {
+        Err(ApiError::unauthorized(
+            "webhook signature is invalid or expired",
+        ))
+    }
+}
+
+/// Appends a `human.response` event to the loop's active events file.
+///
+/// Returns the events file that was written.
+pub fn write_human_response(
+    workspace_root: &Path,
+    body: &HumanResponseBody,
+) -> Result<PathBuf, ApiError> {
+    let loop_id = body.loop_id.as_deref().unwrap_or(PRIMARY_LOOP_ALIAS);
+    let events_path = events_path_for_loop(workspace_root, loop_id)?;
+
+    if let Some(parent) = events_path.parent() {
+        fs::create_dir_all(parent).map_err(|error| {
+            ApiError::internal(format!("failed creating {}: {error}", parent.display()))
+        })?;
+    }
+
+    let line = json!({
+        "topic": "human.response",
+        "payload": body.response,
+        "ts": Utc::now().to_rfc3339(),
+    });
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&events_path)
+        .and_then(|mut file| writeln!(file, "{line}"))
+        .map_err(|error| {
+            ApiError::internal(format!(
+                "failed writing human.response to {}: {error}",
+                events_path.display()
+            ))
+        })?;
+
+    Ok(events_path)
+}
+
+/// Resolves the active events file of a loop: the registry entry's worktree
+/// for parallel loops, the workspace root for the primary loop.
+fn events_path_for_loop(workspace_root: &Path, loop_id: &str) -> Result<PathBuf, ApiError> {
+    let loop_root = if loop_id == PRIMARY_LOOP_ALIAS || is_primary_loop(workspace_root, loop_id) {
+        workspace_root.to_path_buf()
+    } else {
+        let entry = LoopRegistry::new(workspace_root)
+            .get(loop_id)
+            .map_err(|error| ApiError::internal(format!("failed reading loops: {error}")))?
+            .ok_or_else(|| loop_not_found_error(loop_id))?;
+        entry
+            .worktree_path
+            .map_or_else(|| workspace_root.to_path_buf(), PathBuf::from)
+    };
+
+    let ralph_dir = loop_root.join(".ralph");
+    Ok(match fs::read_to_string(ralph_dir.join("current-events")) {
+        Ok(marker) if !marker.trim().is_empty() => loop_root.join(marker.trim()),
+        _ => ralph_dir.join("events.jsonl"),
+    })
+}
+
+fn is_primary_loop(workspace_root: &Path, loop_id: &str) -> bool {
+    fs::read_to_string(workspace_root.join(".ralph/current-loop-id"))
+        .is_ok_and(|current| current.trim() == loop_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HumanResponseBody, write_human_response};
+
+    #[test]
+    fn writes_to_marker_events_file_and_rejects_unknown_loops() {
+        let workspace = tempfile::tempdir().expect("workspace tempdir");
+        let ralph_dir = workspace.path().join(".ralph");
+        std::fs::create_dir_all(&ralph_dir).expect("ralph dir");
+        std::fs::write(
+            ralph_dir.join("current-events"),
+            ".ralph/events-20260101-000000.jsonl",
+        )
+        .expect("marker");
+        std::fs::write(ralph_dir.join("current-loop-id"), "primary-20260101-000000")
+            .expect("loop id");
+
+        let path = write_human_response(
+            workspace.path(),
+            &HumanResponseBody {
+                loop_id: Some("primary-20260101-000000".to_string()),
+                response: "ship it".to_string(),
+            },
+        )
+        .expect("write response");
+        assert_eq!(path, ralph_dir.join("events-20260101-000000.jsonl"));
+        let content = std::fs::read_to_string(path).expect("events file");
+        assert!(content.contains("\"topic\":\"human.response\""));
+        assert!(content.contains("ship it"));
+
+        let error = write_human_response(
+            workspace.path(),
+            &HumanResponseBody {
+                loop_id: Some("missing-loop".to_string()),
+                response: "hello".to_string(),
+            },
+        )
+        .expect_err("unknown loop");
+        assert_eq!(error.code.as_str(), "LOOP_NOT_FOUND");
+    }
+}
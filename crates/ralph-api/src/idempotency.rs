@@ -1,10 +1,17 @@
 use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 
+use chrono::Utc;
+use ralph_core::FileLock;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use tracing::warn;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StoredResponse {
     pub status: u16,
     pub envelope: Value,
@@ -47,10 +54,6 @@ impl InMemoryIdempotencyStore {
         let ttl = self.ttl;
         entries.retain(|_, entry| entry.created_at.elapsed() <= ttl);
     }
-
-    fn make_key(method: &str, key: &str) -> String {
-        format!("{method}:{key}")
-    }
 }
 
 impl IdempotencyStore for InMemoryIdempotencyStore {
@@ -61,8 +64,7 @@ impl IdempotencyStore for InMemoryIdempotencyStore {
             .expect("idempotency store mutex should not be poisoned");
         self.cleanup(&mut guard);
 
-        let store_key = Self::make_key(method, key);
-        match guard.get(&store_key) {
+        match guard.get(&store_key(method, key)) {
             None => IdempotencyCheck::New,
             Some(entry) if entry.params == *params => {
                 IdempotencyCheck::Replay(entry.response.clone())
@@ -78,7 +80,7 @@ impl IdempotencyStore for InMemoryIdempotencyStore {
             .expect("idempotency store mutex should not be poisoned");
         self.cleanup(&mut guard);
         guard.insert(
-            Self::make_key(method, key),
+            store_key(method, key),
             Entry {
                 params: params.clone(),
                 response: response.clone(),
@@ -88,13 +90,130 @@ impl IdempotencyStore for InMemoryIdempotencyStore {
     }
 }
 
+/// Idempotency store persisted to `.ralph/api/idempotency.jsonl`.
+///
+/// Every operation re-reads the file under a `FileLock`, so stored responses
+/// survive API restarts and are shared with other processes serving the same
+/// workspace (for example `ralph mcp serve`). Writes drop expired entries and
+/// atomically replace the file.
+#[derive(Debug, Clone)]
+pub struct FileIdempotencyStore {
+    path: PathBuf,
+    ttl: Duration,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FileEntry {
+    key: String,
+    params: Value,
+    response: StoredResponse,
+    created_at_ms: i64,
+}
+
+impl FileIdempotencyStore {
+    pub fn new(workspace_root: impl AsRef<Path>, ttl: Duration) -> Self {
+        Self {
+            path: workspace_root.as_ref().join(".ralph/api/idempotency.jsonl"),
+            ttl,
+        }
+    }
+
+    fn read_live_entries(&self) -> io::Result<Vec<FileEntry>> {
+        let content = match fs::read_to_string(&self.path) {
+            Ok(content) => content,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(error) => return Err(error),
+        };
+
+        let ttl_ms = i64::try_from(self.ttl.as_millis()).unwrap_or(i64::MAX);
+        let now_ms = Utc::now().timestamp_millis();
+        Ok(content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .filter_map(|line| match serde_json::from_str::<FileEntry>(line) {
+                Ok(entry) => Some(entry),
+                Err(error) => {
+                    warn!(path = %self.path.display(), %error, "skipping unreadable idempotency entry");
+                    None
+                }
+            })
+            .filter(|entry| now_ms.saturating_sub(entry.created_at_ms) <= ttl_ms)
+            .collect())
+    }
+
+    fn write_entries(&self, entries: &[FileEntry]) -> io::Result<()> {
+        let mut payload = Vec::new();
+        for entry in entries {
+            serde_json::to_writer(&mut payload, entry).map_err(io::Error::other)?;
+            payload.push(b'\n');
+        }
+
+        let tmp_path = self.path.with_extension("jsonl.tmp");
+        fs::write(&tmp_path, payload)?;
+        fs::rename(tmp_path, &self.path)
+    }
+
+    fn try_check(&self, entry_key: &str, params: &Value) -> io::Result<IdempotencyCheck> {
+        let lock = FileLock::new(&self.path)?;
+        let _guard = lock.shared()?;
+        let entries = self.read_live_entries()?;
+
+        Ok(match entries.iter().find(|entry| entry.key == entry_key) {
+            None => IdempotencyCheck::New,
+            Some(entry) if entry.params == *params => {
+                IdempotencyCheck::Replay(entry.response.clone())
+            }
+            Some(_) => IdempotencyCheck::Conflict,
+        })
+    }
+
+    fn try_store(&self, entry: FileEntry) -> io::Result<()> {
+        let lock = FileLock::new(&self.path)?;
+        let _guard = lock.exclusive()?;
+        let mut entries = self.read_live_entries()?;
+        entries.retain(|existing| existing.key != entry.key);
+        entries.push(entry);
+        self.write_entries(&entries)
+    }
+}
+
+impl IdempotencyStore for FileIdempotencyStore {
+    fn check(&self, method: &str, key: &str, params: &Value) -> IdempotencyCheck {
+        self.try_check(&store_key(method, key), params)
+            .unwrap_or_else(|error| {
+                warn!(path = %self.path.display(), %error, "failed reading idempotency store");
+                IdempotencyCheck::New
+            })
+    }
+
+    fn store(&self, method: &str, key: &str, params: &Value, response: &StoredResponse) {
+        let entry = FileEntry {
+            key: store_key(method, key),
+            params: params.clone(),
+            response: response.clone(),
+            created_at_ms: Utc::now().timestamp_millis(),
+        };
+        if let Err(error) = self.try_store(entry) {
+            warn!(path = %self.path.display(), %error, "failed persisting idempotency entry");
+        }
+    }
+}
+
+fn store_key(method: &str, key: &str) -> String {
+    format!("{method}:{key}")
+}
+
 #[cfg(test)]
 mod tests {
     use std::time::Duration;
 
     use serde_json::json;
 
-    use super::{IdempotencyCheck, IdempotencyStore, InMemoryIdempotencyStore, StoredResponse};
+    use super::{
+        FileIdempotencyStore, IdempotencyCheck, IdempotencyStore, InMemoryIdempotencyStore,
+        StoredResponse,
+    };
 
     #[test]
     fn replays_same_method_key_and_params() {
@@ -137,4 +256,34 @@ mod tests {
             IdempotencyCheck::Conflict
         ));
     }
+
+    #[test]
+    fn file_store_replays_across_instances_and_evicts_expired_entries() {
+        let workspace = tempfile::tempdir().expect("workspace tempdir");
+        let params = json!({ "value": 1 });
+        let response = StoredResponse {
+            status: 200,
+            envelope: json!({ "result": { "ok": true } }),
+        };
+
+        FileIdempotencyStore::new(workspace.path(), Duration::from_mins(1))
+            .store("task.run", "idem-3", &params, &response);
+
+        let reopened = FileIdempotencyStore::new(workspace.path(), Duration::from_mins(1));
+        match reopened.check("task.run", "idem-3", &params) {
+            IdempotencyCheck::Replay(actual) => assert_eq!(actual.envelope, response.envelope),
+            _ => panic!("expected replay from persisted entry"),
+        }
+        assert!(matches!(
+            reopened.check("task.run", "idem-3", &json!({ "value": 2 })),
+            IdempotencyCheck::Conflict
+        ));
+
+        let expired = FileIdempotencyStore::new(workspace.path(), Duration::ZERO);
+        std::thread::sleep(Duration::from_millis(5));
+        assert!(matches!(
+            expired.check("task.run", "idem-3", &params),
+            IdempotencyCheck::New
+        ));
+    }
 }
@@ -1,8 +1,10 @@
+pub mod audit;
 pub mod auth;
 pub mod collection_domain;
 pub mod config;
 pub mod config_domain;
 pub mod errors;
+pub mod human_response;
 pub mod idempotency;
 pub mod loop_domain;
 pub mod loop_side_effects;
@@ -14,9 +16,10 @@ pub mod protocol;
 pub mod runtime;
 pub mod stream_domain;
 pub mod task_domain;
+pub mod task_worker;
 pub mod transport;
 
-pub use config::{ApiConfig, AuthMode};
+pub use config::{ApiConfig, AuthMode, IdempotencyBackend};
 pub use mcp::serve_stdio;
 pub use runtime::RpcRuntime;
 pub use transport::{router, serve, serve_with_listener};
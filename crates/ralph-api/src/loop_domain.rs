@@ -5,15 +5,20 @@ use std::process::Command;
 
 use chrono::Utc;
 use ralph_core::{
-    LoopLock, LoopRegistry, MergeButtonState, MergeQueue, MergeState, RegistryError,
-    merge_button_state, remove_worktree,
+    LoopLock, LoopNameGenerator, LoopNamingConfig, LoopRegistry, LoopSchedule, MergeButtonState,
+    MergeExecutor, MergeExecutorMode, MergeQueue, MergeState, RegistryError, ScheduleAction,
+    ScheduleStatus, ScheduledLoop, SlotState, StartOn, merge_button_state, remove_worktree,
+    spawn_scheduled_loop, worktree_exists,
 };
 use serde::{Deserialize, Serialize};
 
 use crate::errors::ApiError;
-use crate::loop_side_effects::{resolve_discard_target, resolve_loop_root, spawn_retry_merge_flow};
+use crate::loop_side_effects::{
+    resolve_discard_target, resolve_loop_root, spawn_merge_flow, spawn_retry_merge_flow,
+};
 use crate::loop_support::{
-    current_commit, is_pid_alive, loop_not_found_error, map_merge_error, map_worktree_error, now_ts,
+    is_pid_alive, load_workspace_config, loop_not_found_error, map_merge_error,
+    map_merge_executor_error, map_schedule_error, map_worktree_error, now_ts,
 };
 use crate::task_domain::{TaskCreateParams, TaskDomain};
 
@@ -24,6 +29,14 @@ pub struct LoopListParams {
 }
 #[derive(Debug, Clone, Deserialize)]
 #[serde(rename_all = "camelCase")]
+pub struct LoopScheduleParams {
+    pub after: String,
+    pub prompt: String,
+    pub start_on: Option<StartOn>,
+    pub backend: Option<String>,
+}
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct LoopRetryParams {
     pub id: String,
     pub steering_input: Option<String>,
@@ -49,6 +62,20 @@ pub struct LoopRecord {
     pub prompt: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub merge_commit: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base_branch: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after: Option<String>,
+}
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoopScheduleResult {
+    pub id: String,
+    pub after: String,
+    pub start_on: StartOn,
+    pub status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
 }
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -113,6 +140,8 @@ impl LoopDomain {
                 location: "(in-place)".to_string(),
                 prompt: Some(metadata.prompt),
                 merge_commit: None,
+                base_branch: None,
+                after: None,
             });
             listed_ids.insert("(primary)".to_string());
         }
@@ -123,7 +152,10 @@ impl LoopDomain {
 
         for entry in registry_entries {
             let status = if entry.is_alive() {
-                "running"
+                match entry.state {
+                    SlotState::Running => "running",
+                    SlotState::Queued => "queued",
+                }
             } else if entry.is_pid_alive() {
                 "orphan"
             } else {
@@ -142,6 +174,8 @@ impl LoopDomain {
                 location,
                 prompt: Some(entry.prompt),
                 merge_commit: None,
+                base_branch: entry.base_branch,
+                after: None,
             });
         }
 
@@ -170,11 +204,45 @@ impl LoopDomain {
                     .unwrap_or_else(|| "-".to_string()),
                 prompt: Some(entry.prompt),
                 merge_commit: entry.merge_commit,
+                base_branch: entry.base_branch,
+                after: None,
+            });
+        }
+
+        for entry in LoopSchedule::new(&self.workspace_root)
+            .list()
+            .map_err(map_schedule_error)?
+        {
+            if let Some(listed) = loops.iter_mut().find(|loop_info| loop_info.id == entry.id) {
+                listed.after = Some(entry.after);
+                continue;
+            }
+
+            let status = match entry.status {
+                ScheduleStatus::Pending => "scheduled",
+                ScheduleStatus::Cancelled => "cancelled",
+                // Started loops are listed through the registry or merge queue.
+                ScheduleStatus::Started => continue,
+            };
+
+            loops.push(LoopRecord {
+                id: entry.id,
+                status: status.to_string(),
+                location: "-".to_string(),
+                prompt: Some(entry.prompt),
+                merge_commit: None,
+                base_branch: entry.base_branch,
+                after: Some(entry.after),
             });
         }
 
         if !include_terminal {
-            loops.retain(|loop_info| !matches!(loop_info.status.as_str(), "merged" | "discarded"));
+            loops.retain(|loop_info| {
+                !matches!(
+                    loop_info.status.as_str(),
+                    "merged" | "discarded" | "cancelled"
+                )
+            });
         }
 
         Ok(loops)
@@ -217,8 +285,72 @@ impl LoopDomain {
         }
 
         self.last_processed_at = Some(now_ts());
+        self.reconcile_schedule()?;
         Ok(())
     }
+    pub fn schedule(&self, params: LoopScheduleParams) -> Result<LoopScheduleResult, ApiError> {
+        if params.prompt.trim().is_empty() {
+            return Err(ApiError::invalid_params("prompt must not be empty"));
+        }
+
+        let schedule = LoopSchedule::new(&self.workspace_root);
+        let worktree_config = ralph_core::WorktreeConfig::default();
+        let id = LoopNameGenerator::from_config(&LoopNamingConfig::default())
+            .generate_memorable_unique(|name| {
+                worktree_exists(&self.workspace_root, name, &worktree_config)
+                    || matches!(schedule.get(name), Ok(Some(_)))
+            });
+
+        let mut args = vec![
+            "run".to_string(),
+            "--no-tui".to_string(),
+            "-p".to_string(),
+            params.prompt.clone(),
+        ];
+        if let Some(backend) = params.backend.filter(|backend| !backend.trim().is_empty()) {
+            args.push("-b".to_string());
+            args.push(backend);
+        }
+
+        let start_on = params.start_on.unwrap_or_default();
+        schedule
+            .add(ScheduledLoop::new(
+                &id,
+                &params.after,
+                start_on,
+                params.prompt,
+                args,
+            ))
+            .map_err(map_schedule_error)?;
+
+        // The parent may already be in the requested state.
+        self.reconcile_schedule()?;
+
+        let entry = schedule
+            .get(&id)
+            .map_err(map_schedule_error)?
+            .ok_or_else(|| loop_not_found_error(&id))?;
+        let status = match entry.status {
+            ScheduleStatus::Pending => "scheduled",
+            ScheduleStatus::Started => "started",
+            ScheduleStatus::Cancelled => "cancelled",
+        };
+
+        Ok(LoopScheduleResult {
+            id: entry.id,
+            after: entry.after,
+            start_on: entry.start_on,
+            status: status.to_string(),
+            reason: entry.reason,
+        })
+    }
+    fn reconcile_schedule(&self) -> Result<Vec<ScheduleAction>, ApiError> {
+        LoopSchedule::new(&self.workspace_root)
+            .reconcile(|entry| {
+                spawn_scheduled_loop(&self.ralph_command, &self.workspace_root, entry)
+            })
+            .map_err(map_schedule_error)
+    }
     pub fn prune(&self) -> Result<(), ApiError> {
         let registry = LoopRegistry::new(&self.workspace_root);
         registry
@@ -264,6 +396,16 @@ impl LoopDomain {
     }
 
     pub fn discard(&self, id: &str) -> Result<(), ApiError> {
+        let schedule = LoopSchedule::new(&self.workspace_root);
+        if let Some(entry) = schedule.get(id).map_err(map_schedule_error)?
+            && entry.is_pending()
+        {
+            schedule
+                .cancel(&entry.id, "User requested discard")
+                .map_err(map_schedule_error)?;
+            return Ok(());
+        }
+
         let resolved = resolve_discard_target(&self.workspace_root, id)?;
         let queue = MergeQueue::new(&self.workspace_root);
         let registry = LoopRegistry::new(&self.workspace_root);
@@ -293,6 +435,7 @@ impl LoopDomain {
                 .map_err(|error| map_worktree_error(&resolved.id, error))?;
         }
 
+        self.reconcile_schedule()?;
         Ok(())
     }
     pub fn stop(&self, params: LoopStopMergeParams) -> Result<(), ApiError> {
@@ -377,15 +520,42 @@ impl LoopDomain {
             _ => {}
         }
 
-        if entry.state != MergeState::Merging {
-            queue
-                .mark_merging(&params.id, std::process::id())
-                .map_err(map_merge_error)?;
+        let config = load_workspace_config(&self.workspace_root)?;
+        if config.merge.executor == MergeExecutorMode::Llm {
+            return spawn_merge_flow(
+                &self.workspace_root,
+                &self.ralph_command,
+                &params.id,
+                params.force.unwrap_or(false),
+            );
+        }
+
+        let report = MergeExecutor::from_config(&self.workspace_root, &config)
+            .process_loop(&params.id)
+            .map_err(map_merge_executor_error)?
+            .ok_or_else(|| {
+                ApiError::conflict(format!(
+                    "Loop '{}' is being merged by another executor",
+                    params.id
+                ))
+            })?;
+
+        if report.outcome.is_merged() {
+            return Ok(());
+        }
+        if report.outcome.is_conflict() && config.merge.llm_fallback {
+            return spawn_retry_merge_flow(&self.workspace_root, &self.ralph_command, &params.id);
         }
 
-        queue
-            .mark_merged(&params.id, &current_commit(&self.workspace_root))
-            .map_err(map_merge_error)
+        Err(ApiError::precondition_failed(format!(
+            "Loop '{}' needs review: {}",
+            params.id,
+            report.outcome.summary()
+        ))
+        .with_details(serde_json::json!({
+            "loopId": params.id,
+            "reason": report.outcome.review_reason(),
+        })))
     }
     pub fn merge_button_state(&self, id: &str) -> Result<MergeButtonStateResult, ApiError> {
         match merge_button_state(&self.workspace_root, id).map_err(map_merge_error)? {
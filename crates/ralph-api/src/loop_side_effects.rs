@@ -35,6 +35,35 @@ pub fn spawn_retry_merge_flow(
     Ok(())
 }
 
+pub fn spawn_merge_flow(
+    workspace_root: &Path,
+    ralph_command: &str,
+    loop_id: &str,
+    force: bool,
+) -> Result<(), ApiError> {
+    let mut args = vec!["loops", "merge", loop_id];
+    if force {
+        args.push("--force");
+    }
+    let status = Command::new(ralph_command)
+        .args(&args)
+        .current_dir(workspace_root)
+        .status()
+        .map_err(|error| {
+            ApiError::internal(format!(
+                "failed invoking '{ralph_command}' for loop merge '{loop_id}': {error}"
+            ))
+        })?;
+
+    if !status.success() {
+        return Err(ApiError::internal(format!(
+            "loop merge command exited with status {status} for loop '{loop_id}'"
+        )));
+    }
+
+    Ok(())
+}
+
 pub fn resolve_discard_target(
     workspace_root: &Path,
     loop_id: &str,
@@ -2,7 +2,7 @@ use std::path::Path;
 use std::process::Command;
 
 use chrono::{SecondsFormat, Utc};
-use ralph_core::{MergeQueueError, WorktreeError};
+use ralph_core::{MergeExecutorError, MergeQueueError, RalphConfig, ScheduleError, WorktreeError};
 
 use crate::errors::ApiError;
 
@@ -26,6 +26,19 @@ pub fn map_merge_error(error: MergeQueueError) -> ApiError {
     }
 }
 
+pub fn map_schedule_error(error: ScheduleError) -> ApiError {
+    match error {
+        ScheduleError::UnknownParent(loop_id) | ScheduleError::NotFound(loop_id) => {
+            loop_not_found_error(&loop_id)
+        }
+        ScheduleError::NotPending(loop_id) => ApiError::precondition_failed(format!(
+            "Scheduled loop '{loop_id}' has already started or been cancelled"
+        ))
+        .with_details(serde_json::json!({ "loopId": loop_id })),
+        other => ApiError::internal(format!("loop schedule operation failed: {other}")),
+    }
+}
+
 pub fn map_worktree_error(loop_id: &str, error: WorktreeError) -> ApiError {
     ApiError::internal(format!(
         "worktree cleanup failed for loop '{loop_id}': {error}"
@@ -33,22 +46,20 @@ pub fn map_worktree_error(loop_id: &str, error: WorktreeError) -> ApiError {
     .with_details(serde_json::json!({ "loopId": loop_id }))
 }
 
-pub fn current_commit(workspace_root: &Path) -> String {
-    let output = Command::new("git")
-        .args(["rev-parse", "--short", "HEAD"])
-        .current_dir(workspace_root)
-        .output();
+pub fn load_workspace_config(workspace_root: &Path) -> Result<RalphConfig, ApiError> {
+    let path = workspace_root.join("ralph.yml");
+    if !path.exists() {
+        return Ok(RalphConfig::default());
+    }
+    RalphConfig::from_file(&path).map_err(|error| {
+        ApiError::config_invalid(format!("failed loading '{}': {error}", path.display()))
+    })
+}
 
-    match output {
-        Ok(output) if output.status.success() => {
-            let sha = String::from_utf8_lossy(&output.stdout).trim().to_string();
-            if sha.is_empty() {
-                "manual".to_string()
-            } else {
-                sha
-            }
-        }
-        _ => "manual".to_string(),
+pub fn map_merge_executor_error(error: MergeExecutorError) -> ApiError {
+    match error {
+        MergeExecutorError::Queue(error) => map_merge_error(error),
+        other => ApiError::internal(format!("merge executor failed: {other}")),
     }
 }
 
@@ -571,11 +571,17 @@ fn build_tool(
         "system.capabilities" => {
             "List supported Ralph control-plane methods and stream topics.".into()
         }
+        "system.audit.list" => {
+            "List audited control-plane mutations, filtered by time, principal, method, or resource.".into()
+        }
         "task.list" => "List Ralph tasks, with optional filters.".into(),
-        "task.ready" => "List open tasks that are ready to run.".into(),
-        "task.run_all" => "Enqueue every open or queued Ralph task.".into(),
+        "task.ready" => "List open API-created tasks that are ready to run.".into(),
+        "task.run_all" => "Enqueue every ready task created through the API.".into(),
         "loop.status" => "Return the current primary loop and merge status.".into(),
         "loop.trigger_merge_task" => "Create a merge task for a completed loop.".into(),
+        "loop.schedule" => {
+            "Schedule a loop to start after another loop is queued or merged.".into()
+        }
         "planning.get_artifact" => "Read a generated planning artifact by filename.".into(),
         "config.get" => "Read the current Ralph YAML configuration.".into(),
         "config.update" => "Replace the Ralph YAML configuration after validation.".into(),
@@ -14,6 +14,7 @@ pub const KNOWN_METHODS: &[&str] = &[
     "system.health",
     "system.version",
     "system.capabilities",
+    "system.audit.list",
     "task.list",
     "task.get",
     "task.ready",
@@ -32,6 +33,7 @@ pub const KNOWN_METHODS: &[&str] = &[
     "loop.list",
     "loop.status",
     "loop.process",
+    "loop.schedule",
     "loop.prune",
     "loop.retry",
     "loop.discard",
@@ -74,6 +76,7 @@ pub const MUTATING_METHODS: &[&str] = &[
     "task.retry",
     "task.cancel",
     "loop.process",
+    "loop.schedule",
     "loop.prune",
     "loop.retry",
     "loop.discard",
@@ -9,13 +9,16 @@ use serde::de::DeserializeOwned;
 use serde_json::{Value, json};
 use tracing::debug;
 
+use crate::audit::{AuditEntry, AuditLog, AuditResult};
 use crate::auth::{Authenticator, from_config};
 use crate::collection_domain::CollectionDomain;
-use crate::config::ApiConfig;
+use crate::config::{ApiConfig, IdempotencyBackend};
 use crate::config_domain::ConfigDomain;
 use crate::errors::{ApiError, RpcErrorCode};
+use crate::human_response::{HumanResponseBody, verify_signature, write_human_response};
 use crate::idempotency::{
-    IdempotencyCheck, IdempotencyStore, InMemoryIdempotencyStore, StoredResponse,
+    FileIdempotencyStore, IdempotencyCheck, IdempotencyStore, InMemoryIdempotencyStore,
+    StoredResponse,
 };
 use crate::loop_domain::LoopDomain;
 use crate::planning_domain::PlanningDomain;
@@ -27,6 +30,7 @@ use crate::protocol::{
 };
 use crate::stream_domain::StreamDomain;
 use crate::task_domain::TaskDomain;
+use crate::task_worker::TaskWorker;
 
 #[derive(Debug, Clone, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -39,7 +43,9 @@ pub struct RpcRuntime {
     pub(crate) config: ApiConfig,
     auth: Arc<dyn Authenticator>,
     idempotency: Arc<dyn IdempotencyStore>,
+    audit: AuditLog,
     tasks: Arc<Mutex<TaskDomain>>,
+    task_worker: TaskWorker,
     loops: Arc<Mutex<LoopDomain>>,
     planning: Arc<Mutex<PlanningDomain>>,
     collections: Arc<Mutex<CollectionDomain>>,
@@ -53,9 +59,13 @@ impl RpcRuntime {
         config.validate()?;
 
         let auth = from_config(&config)?;
-        let idempotency = Arc::new(InMemoryIdempotencyStore::new(Duration::from_secs(
-            config.idempotency_ttl_secs,
-        )));
+        let ttl = Duration::from_secs(config.idempotency_ttl_secs);
+        let idempotency: Arc<dyn IdempotencyStore> = match config.idempotency_backend {
+            IdempotencyBackend::Memory => Arc::new(InMemoryIdempotencyStore::new(ttl)),
+            IdempotencyBackend::File => {
+                Arc::new(FileIdempotencyStore::new(&config.workspace_root, ttl))
+            }
+        };
 
         Ok(Self::with_components(config, auth, idempotency))
     }
@@ -73,15 +83,27 @@ impl RpcRuntime {
         )));
         let planning = Arc::new(Mutex::new(PlanningDomain::new(&config.workspace_root)));
         let collections = Arc::new(Mutex::new(CollectionDomain::new(&config.workspace_root)));
-        let streams = StreamDomain::new();
+        let streams = StreamDomain::persistent(&config.workspace_root);
         let config_domain = ConfigDomain::new(&config.workspace_root);
         let preset_domain = PresetDomain::new(&config.workspace_root);
+        let audit = AuditLog::new(&config.workspace_root);
+        let task_worker = TaskWorker::new(
+            &tasks,
+            streams.clone(),
+            &config.workspace_root,
+            config.ralph_command.clone(),
+            config.task_concurrency,
+        );
+        task_worker.drain();
+        task_worker.watch();
 
         Self {
             config,
             auth,
             idempotency,
+            audit,
             tasks,
+            task_worker,
             loops,
             planning,
             collections,
@@ -108,7 +130,8 @@ impl RpcRuntime {
             },
             "idempotency": {
                 "requiredForMutations": true,
-                "retentionSeconds": self.config.idempotency_ttl_secs
+                "retentionSeconds": self.config.idempotency_ttl_secs,
+                "backend": self.config.idempotency_backend.as_str()
             }
         })
     }
@@ -176,6 +199,41 @@ impl RpcRuntime {
         (status, envelope)
     }
 
+    /// Handles `POST /hooks/human-response` from webhook receivers.
+    pub fn handle_human_response(&self, body: &[u8], headers: &HeaderMap) -> (StatusCode, Value) {
+        let result = self
+            .config
+            .webhook_secret
+            .as_deref()
+            .ok_or_else(|| {
+                ApiError::service_unavailable(
+                    "human responses via webhook are disabled; set RALPH_WEBHOOK_SECRET",
+                )
+            })
+            .and_then(|secret| verify_signature(secret, headers, body))
+            .and_then(|()| {
+                serde_json::from_slice::<HumanResponseBody>(body).map_err(|error| {
+                    ApiError::invalid_params(format!("invalid human response body: {error}"))
+                })
+            })
+            .and_then(|response| {
+                write_human_response(&self.config.workspace_root, &response)?;
+                Ok(response)
+            });
+
+        match result {
+            Ok(response) => (
+                StatusCode::OK,
+                json!({
+                    "accepted": true,
+                    "loopId": response.loop_id,
+                    "topic": "human.response"
+                }),
+            ),
+            Err(error) => (error.status, error_envelope(&error, &self.config.served_by)),
+        }
+    }
+
     pub fn authenticate_websocket(&self, headers: &HeaderMap) -> Result<String, ApiError> {
         let dummy_request = crate::protocol::RpcRequestEnvelope {
             api_version: "v1".to_string(),
@@ -196,6 +254,10 @@ impl RpcRuntime {
             .map_err(|_| ApiError::internal("task domain lock poisoned"))
     }
 
+    pub(crate) fn task_worker(&self) -> &TaskWorker {
+        &self.task_worker
+    }
+
     pub(crate) fn loop_domain_mut(&self) -> Result<MutexGuard<'_, LoopDomain>, ApiError> {
         self.loops
             .lock()
@@ -220,6 +282,10 @@ impl RpcRuntime {
         self.streams.clone()
     }
 
+    pub(crate) fn audit_log(&self) -> &AuditLog {
+        &self.audit
+    }
+
     pub(crate) fn config_domain(&self) -> &ConfigDomain {
         &self.config_domain
     }
@@ -319,17 +385,27 @@ impl RpcRuntime {
                         request_id = %request.id,
                         "idempotency replay"
                     );
+                    self.audit.record(&AuditEntry::new(
+                        principal,
+                        &request.method,
+                        &request.id,
+                        &request.params,
+                        key,
+                        AuditResult::Replayed,
+                    ));
                     return self.replay_stored_response(request, response);
                 }
                 IdempotencyCheck::Conflict => {
-                    return Err(ApiError::idempotency_conflict(
+                    let error = ApiError::idempotency_conflict(
                         "idempotency key was already used with different parameters",
                     )
                     .with_context(request.id.clone(), Some(request.method.clone()))
                     .with_details(json!({
                         "method": request.method.clone(),
                         "idempotencyKey": key
-                    })));
+                    }));
+                    self.record_audit(request, principal, key, Err(&error));
+                    return Err(error);
                 }
                 IdempotencyCheck::New => {
                     idempotency_context = Some(key.to_string());
@@ -358,11 +434,38 @@ impl RpcRuntime {
                 &request.params,
                 &StoredResponse { status, envelope },
             );
+            self.record_audit(request, principal, &key, result.as_ref().map(|_| ()));
         }
 
         result
     }
 
+    fn record_audit(
+        &self,
+        request: &RpcRequestEnvelope,
+        principal: &str,
+        idempotency_key: &str,
+        outcome: Result<(), &ApiError>,
+    ) {
+        let result = if outcome.is_ok() {
+            AuditResult::Ok
+        } else {
+            AuditResult::Error
+        };
+        let mut entry = AuditEntry::new(
+            principal,
+            &request.method,
+            &request.id,
+            &request.params,
+            idempotency_key,
+            result,
+        );
+        if let Err(error) = outcome {
+            entry = entry.with_error_code(error.code.as_str());
+        }
+        self.audit.record(&entry);
+    }
+
     fn replay_stored_response(
         &self,
         request: &RpcRequestEnvelope,
@@ -2,13 +2,15 @@ use serde_json::{Value, json};
 use tracing::warn;
 
 use super::{IdOnlyParams, RpcRuntime};
+use crate::audit::AuditListParams;
 use crate::collection_domain::{
     CollectionCreateParams, CollectionImportParams, CollectionUpdateParams,
 };
 use crate::config_domain::ConfigUpdateParams;
 use crate::errors::ApiError;
 use crate::loop_domain::{
-    LoopListParams, LoopRetryParams, LoopStopMergeParams, LoopTriggerMergeTaskParams,
+    LoopListParams, LoopRetryParams, LoopScheduleParams, LoopStopMergeParams,
+    LoopTriggerMergeTaskParams,
 };
 use crate::planning_domain::{
     PlanningGetArtifactParams, PlanningRespondParams, PlanningStartParams,
@@ -30,6 +32,11 @@ impl RpcRuntime {
                 "serverVersion": env!("CARGO_PKG_VERSION")
             })),
             "system.capabilities" => Ok(self.capabilities_payload()),
+            "system.audit.list" => {
+                let params: AuditListParams = self.parse_params(request)?;
+                let entries = self.audit_log().list(&params)?;
+                Ok(json!({ "entries": entries }))
+            }
             method if method.starts_with("task.") => self.dispatch_task(request),
             method if method.starts_with("loop.") => self.dispatch_loop(request),
             method if method.starts_with("planning.") => self.dispatch_planning(request),
@@ -57,6 +64,12 @@ impl RpcRuntime {
                 .publish_rpc_side_effect(&request.method, &request.params, payload);
         }
 
+        if result.is_ok()
+            && (request.method.starts_with("task.") || request.method == "loop.trigger_merge_task")
+        {
+            self.task_worker().drain();
+        }
+
         result
     }
 
@@ -64,7 +77,7 @@ impl RpcRuntime {
         match request.method.as_str() {
             "task.list" => {
                 let params: TaskListParams = self.parse_params(request)?;
-                let tasks = self.task_domain_mut()?.list(params);
+                let tasks = self.task_domain_mut()?.list(params)?;
                 Ok(json!({ "tasks": tasks }))
             }
             "task.get" => {
@@ -73,7 +86,7 @@ impl RpcRuntime {
                 Ok(json!({ "task": task }))
             }
             "task.ready" => {
-                let tasks = self.task_domain_mut()?.ready();
+                let tasks = self.task_domain_mut()?.ready()?;
                 Ok(json!({ "tasks": tasks }))
             }
             "task.create" => {
@@ -116,7 +129,7 @@ impl RpcRuntime {
                 Ok(json!(result))
             }
             "task.run_all" => {
-                let result = self.task_domain_mut()?.run_all();
+                let result = self.task_domain_mut()?.run_all()?;
                 Ok(json!(result))
             }
             "task.retry" => {
@@ -127,11 +140,15 @@ impl RpcRuntime {
             "task.cancel" => {
                 let params: IdOnlyParams = self.parse_params(request)?;
                 let task = self.task_domain_mut()?.cancel(&params.id)?;
+                self.task_worker().stop(&params.id);
                 Ok(json!({ "task": task }))
             }
             "task.status" => {
                 let params: IdOnlyParams = self.parse_params(request)?;
-                let status = self.task_domain_mut()?.status(&params.id);
+                let mut status = self.task_domain_mut()?.status(&params.id)?;
+                if let Some(pid) = self.task_worker().runner_pid(&params.id) {
+                    status.runner_pid = Some(pid);
+                }
                 Ok(json!(status))
             }
             _ => Err(ApiError::service_unavailable(format!(
@@ -156,6 +173,11 @@ impl RpcRuntime {
                 self.loop_domain_mut()?.process()?;
                 Ok(json!({ "success": true }))
             }
+            "loop.schedule" => {
+                let params: LoopScheduleParams = self.parse_params(request)?;
+                let result = self.loop_domain_mut()?.schedule(params)?;
+                Ok(json!(result))
+            }
             "loop.prune" => {
                 self.loop_domain_mut()?.prune()?;
                 Ok(json!({ "success": true }))
@@ -1,13 +1,16 @@
 mod filters;
 mod rpc_side_effects;
+mod segment_log;
 
 use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::Path;
 use std::sync::{Arc, Mutex};
 
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use serde_json::{Value, json};
 use tokio::sync::broadcast;
+use tracing::warn;
 
 use crate::errors::ApiError;
 use crate::loop_support::now_ts;
@@ -16,6 +19,7 @@ use crate::protocol::{API_VERSION, STREAM_NAME, STREAM_TOPICS};
 use self::filters::{
     SubscriptionFilters, cursor_is_older, cursor_sequence, normalize_topics, validate_cursor,
 };
+use self::segment_log::SegmentLog;
 
 pub const KEEPALIVE_INTERVAL_MS: u64 = 15_000;
 
@@ -59,7 +63,7 @@ pub struct ReplayBatch {
     pub dropped_count: usize,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct StreamEventEnvelope {
     pub api_version: String,
@@ -73,14 +77,14 @@ pub struct StreamEventEnvelope {
     pub payload: Value,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StreamResource {
     #[serde(rename = "type")]
     pub kind: String,
     pub id: String,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct StreamReplay {
     pub mode: String,
@@ -110,19 +114,54 @@ struct StreamState {
     sequence: u64,
     subscription_counter: u64,
     history: VecDeque<StreamEventEnvelope>,
+    /// Highest sequence that has fallen out of the retention window.
+    evicted_through: u64,
+    log: Option<SegmentLog>,
     subscriptions: HashMap<String, SubscriptionRecord>,
 }
 
 impl StreamDomain {
     pub fn new() -> Self {
-        let (live_tx, _) = broadcast::channel(LIVE_BUFFER_CAPACITY);
-        Self {
-            state: Arc::new(Mutex::new(StreamState {
-                sequence: 1,
+        Self::from_state(StreamState {
+            sequence: 1,
+            subscription_counter: 0,
+            history: VecDeque::with_capacity(HISTORY_LIMIT),
+            evicted_through: 0,
+            log: None,
+            subscriptions: HashMap::new(),
+        })
+    }
+
+    /// Creates a stream domain whose history is persisted under
+    /// `.ralph/api/stream/`, so cursors and sequences survive restarts.
+    ///
+    /// Falls back to in-memory history when the log cannot be opened.
+    pub fn persistent(workspace_root: impl AsRef<Path>) -> Self {
+        let log_dir = workspace_root.as_ref().join(".ralph/api/stream");
+        match SegmentLog::open(&log_dir, HISTORY_LIMIT) {
+            Ok((log, loaded)) => Self::from_state(StreamState {
+                sequence: loaded.next_sequence,
                 subscription_counter: 0,
-                history: VecDeque::with_capacity(HISTORY_LIMIT),
+                history: loaded.events.into(),
+                evicted_through: loaded.evicted_through,
+                log: Some(log),
                 subscriptions: HashMap::new(),
-            })),
+            }),
+            Err(error) => {
+                warn!(
+                    path = %log_dir.display(),
+                    %error,
+                    "failed opening stream history log; history will not persist"
+                );
+                Self::new()
+            }
+        }
+    }
+
+    fn from_state(state: StreamState) -> Self {
+        let (live_tx, _) = broadcast::channel(LIVE_BUFFER_CAPACITY);
+        Self {
+            state: Arc::new(Mutex::new(state)),
             live_tx,
         }
     }
@@ -135,6 +174,10 @@ impl StreamDomain {
         let accepted_topics = normalize_topics(&params.topics, STREAM_TOPICS)?;
         let cursor = if let Some(cursor) = &params.cursor {
             validate_cursor(cursor)?;
+            let evicted_through = self.lock_state()?.evicted_through;
+            if cursor_sequence(cursor)? < evicted_through {
+                return Err(cursor_expired_error(None, cursor, evicted_through));
+            }
             cursor.clone()
         } else {
             self.latest_cursor_or_now()?
@@ -281,6 +324,15 @@ impl StreamDomain {
         };
 
         let cursor_sequence = cursor_sequence(&subscription.cursor)?;
+        // Implicit cursors fall back to the retained tail (with a dropped count);
+        // a cursor the client supplied or acked must not silently skip events.
+        if subscription.explicit_cursor && cursor_sequence < state.evicted_through {
+            return Err(cursor_expired_error(
+                Some(subscription_id),
+                &subscription.cursor,
+                state.evicted_through,
+            ));
+        }
         let current_cursor = subscription.cursor.clone();
         let mut events = state
             .history
@@ -355,6 +407,24 @@ impl StreamDomain {
         )
     }
 
+    /// Builds an `error.raised` event for a subscription-level failure.
+    pub fn error_event(&self, subscription_id: &str, error: &ApiError) -> StreamEventEnvelope {
+        self.ephemeral_event(
+            "error.raised",
+            "stream",
+            subscription_id,
+            json!({
+                "code": error.code.as_str(),
+                "message": error.message,
+                "retryable": error.retryable,
+                "details": error.details
+            }),
+            "live",
+            None,
+            None,
+        )
+    }
+
     pub fn publish(&self, topic: &str, resource_type: &str, resource_id: &str, payload: Value) {
         if !STREAM_TOPICS.contains(&topic) {
             return;
@@ -375,8 +445,22 @@ impl StreamDomain {
             None,
         );
 
-        if state.history.len() >= HISTORY_LIMIT {
-            state.history.pop_front();
+        if let Some(log) = state.log.as_mut()
+            && let Err(error) = log.append(&event)
+        {
+            warn!(%error, sequence = event.sequence, "failed persisting stream event");
+        }
+
+        if state.history.len() >= HISTORY_LIMIT
+            && let Some(evicted) = state.history.pop_front()
+        {
+            state.evicted_through = state.evicted_through.max(evicted.sequence);
+            let evicted_through = state.evicted_through;
+            if let Some(log) = state.log.as_mut()
+                && let Err(error) = log.evict_through(evicted_through)
+            {
+                warn!(%error, "failed compacting stream history log");
+            }
         }
         state.history.push_back(event.clone());
         let _ = self.live_tx.send(event);
@@ -457,6 +541,25 @@ impl SubscriptionRecord {
     }
 }
 
+fn cursor_expired_error(
+    subscription_id: Option<&str>,
+    cursor: &str,
+    evicted_through: u64,
+) -> ApiError {
+    let mut details = json!({
+        "cursor": cursor,
+        "oldestSequence": evicted_through.saturating_add(1)
+    });
+    if let Some(subscription_id) = subscription_id {
+        details["subscriptionId"] = json!(subscription_id);
+    }
+
+    ApiError::cursor_expired(format!(
+        "stream cursor '{cursor}' is outside the retained history; resubscribe without a cursor"
+    ))
+    .with_details(details)
+}
+
 fn next_event(
     state: &mut StreamState,
     topic: &str,
@@ -469,6 +572,11 @@ fn next_event(
 ) -> StreamEventEnvelope {
     let sequence = state.sequence;
     state.sequence = state.sequence.saturating_add(1);
+    if let Some(log) = state.log.as_mut()
+        && let Err(error) = log.reserve(sequence)
+    {
+        warn!(%error, sequence, "failed reserving stream sequence");
+    }
 
     StreamEventEnvelope {
         api_version: API_VERSION.to_string(),
@@ -489,3 +597,120 @@ fn next_event(
         payload,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{HISTORY_LIMIT, StreamDomain, StreamSubscribeParams};
+    use crate::errors::RpcErrorCode;
+
+    fn subscribe_params(cursor: Option<String>) -> StreamSubscribeParams {
+        StreamSubscribeParams {
+            topics: vec!["task.status.changed".to_string()],
+            cursor,
+            replay_limit: Some(u16::MAX),
+            filters: None,
+        }
+    }
+
+    fn publish_task_events(streams: &StreamDomain, count: usize) {
+        for index in 0..count {
+            streams.publish(
+                "task.status.changed",
+                "task",
+                &format!("task-{index}"),
+                json!({ "to": "running" }),
+            );
+        }
+    }
+
+    #[test]
+    fn history_and_sequences_survive_restart() {
+        let workspace = tempfile::tempdir().expect("workspace tempdir");
+
+        let streams = StreamDomain::persistent(workspace.path());
+        publish_task_events(&streams, 3);
+        let keepalive = streams.keepalive_event("sub-1", 15_000);
+        let first = streams
+            .subscribe(subscribe_params(Some("0-0".to_string())), "local")
+            .expect("subscribe");
+        let before_restart = streams
+            .replay_for_subscription(&first.subscription_id)
+            .expect("replay");
+        assert_eq!(before_restart.events.len(), 3);
+        drop(streams);
+
+        let restarted = StreamDomain::persistent(workspace.path());
+        let resumed = restarted
+            .subscribe(
+                subscribe_params(Some(before_restart.events[0].cursor.clone())),
+                "local",
+            )
+            .expect("subscribe after restart");
+        let replay = restarted
+            .replay_for_subscription(&resumed.subscription_id)
+            .expect("replay after restart");
+        let replayed_cursors = replay
+            .events
+            .iter()
+            .map(|event| event.cursor.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            replayed_cursors,
+            vec![
+                before_restart.events[1].cursor.as_str(),
+                before_restart.events[2].cursor.as_str()
+            ]
+        );
+
+        publish_task_events(&restarted, 1);
+        let replay = restarted
+            .replay_for_subscription(&resumed.subscription_id)
+            .expect("replay with new event");
+        let newest = replay.events.last().expect("new event");
+        assert!(newest.sequence > keepalive.sequence);
+    }
+
+    #[test]
+    fn cursor_outside_retention_is_rejected() {
+        let workspace = tempfile::tempdir().expect("workspace tempdir");
+        let streams = StreamDomain::persistent(workspace.path());
+        publish_task_events(&streams, 1);
+        let stale = streams
+            .subscribe(subscribe_params(Some("0-0".to_string())), "local")
+            .expect("subscribe");
+        let stale_cursor = streams
+            .replay_for_subscription(&stale.subscription_id)
+            .expect("replay")
+            .events[0]
+            .cursor
+            .clone();
+        let stale_sub = streams
+            .subscribe(subscribe_params(Some(stale_cursor.clone())), "local")
+            .expect("subscribe at first event");
+
+        publish_task_events(&streams, HISTORY_LIMIT + 1);
+
+        let error = streams
+            .replay_for_subscription(&stale_sub.subscription_id)
+            .expect_err("stale cursor should expire");
+        assert_eq!(error.code, RpcErrorCode::CursorExpired);
+
+        drop(streams);
+        let restarted = StreamDomain::persistent(workspace.path());
+        let error = restarted
+            .subscribe(subscribe_params(Some(stale_cursor)), "local")
+            .expect_err("stale cursor should stay expired after restart");
+        assert_eq!(error.code, RpcErrorCode::CursorExpired);
+
+        let fresh = restarted
+            .subscribe(subscribe_params(None), "local")
+            .expect("subscribe without cursor");
+        assert!(
+            restarted
+                .replay_for_subscription(&fresh.subscription_id)
+                .is_ok()
+        );
+    }
+}
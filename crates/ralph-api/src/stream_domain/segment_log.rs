@@ -0,0 +1,224 @@
+use std::collections::VecDeque;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use super::StreamEventEnvelope;
+
+/// Events per segment file before a new segment is started.
+const SEGMENT_EVENTS: usize = 512;
+/// Sequences are reserved in blocks so a restart never reissues a sequence
+/// that was handed out (including to ephemeral keepalive/error events).
+const SEQUENCE_BLOCK: u64 = 1_024;
+const META_FILE: &str = "meta.json";
+const SEGMENT_PREFIX: &str = "segment-";
+const SEGMENT_SUFFIX: &str = ".jsonl";
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LogMeta {
+    /// Sequences below this value may have been issued.
+    reserved_sequence: u64,
+    /// Highest sequence that has been evicted from the retention window.
+    evicted_through: u64,
+}
+
+#[derive(Debug)]
+struct Segment {
+    first_sequence: u64,
+    path: PathBuf,
+    events: usize,
+}
+
+/// Stream history recovered from disk.
+pub(super) struct LoadedHistory {
+    pub(super) events: Vec<StreamEventEnvelope>,
+    pub(super) next_sequence: u64,
+    pub(super) evicted_through: u64,
+}
+
+/// Append-only segment log for stream history (`.ralph/api/stream/`).
+///
+/// Events are appended as JSONL to `segment-<first sequence>.jsonl` files.
+/// Whole segments are deleted once every event in them has left the
+/// retention window.
+#[derive(Debug)]
+pub(super) struct SegmentLog {
+    dir: PathBuf,
+    meta: LogMeta,
+    segments: VecDeque<Segment>,
+}
+
+impl SegmentLog {
+    /// Opens (or creates) the log and loads at most `retain` of the newest events.
+    pub(super) fn open(dir: impl AsRef<Path>, retain: usize) -> io::Result<(Self, LoadedHistory)> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)?;
+
+        let meta = read_meta(&dir.join(META_FILE));
+        let mut segments = list_segments(&dir)?;
+
+        let mut events = Vec::new();
+        for segment in &mut segments {
+            let content = fs::read_to_string(&segment.path)?;
+            for line in content.lines().filter(|line| !line.trim().is_empty()) {
+                match serde_json::from_str::<StreamEventEnvelope>(line) {
+                    Ok(event) => {
+                        events.push(event);
+                        segment.events += 1;
+                    }
+                    Err(error) => warn!(
+                        path = %segment.path.display(),
+                        %error,
+                        "skipping unreadable stream history entry"
+                    ),
+                }
+            }
+            // Never append after a torn write; continue in a fresh segment instead.
+            if !content.is_empty() && !content.ends_with('\n') {
+                segment.events = SEGMENT_EVENTS;
+            }
+        }
+
+        let last_sequence = events.last().map_or(0, |event| event.sequence);
+        let mut evicted_through = meta.evicted_through;
+        if events.len() > retain {
+            let cut = events.len() - retain;
+            evicted_through = evicted_through.max(events[cut - 1].sequence);
+            events.drain(..cut);
+        }
+
+        let next_sequence = meta
+            .reserved_sequence
+            .max(last_sequence.saturating_add(1))
+            .max(1);
+
+        let log = Self {
+            dir,
+            meta: LogMeta {
+                reserved_sequence: next_sequence,
+                evicted_through,
+            },
+            segments,
+        };
+
+        Ok((
+            log,
+            LoadedHistory {
+                events,
+                next_sequence,
+                evicted_through,
+            },
+        ))
+    }
+
+    /// Records that `sequence` is about to be issued.
+    pub(super) fn reserve(&mut self, sequence: u64) -> io::Result<()> {
+        if sequence < self.meta.reserved_sequence {
+            return Ok(());
+        }
+
+        self.meta.reserved_sequence = sequence.saturating_add(SEQUENCE_BLOCK);
+        self.write_meta()
+    }
+
+    pub(super) fn append(&mut self, event: &StreamEventEnvelope) -> io::Result<()> {
+        if self
+            .segments
+            .back()
+            .is_none_or(|segment| segment.events >= SEGMENT_EVENTS)
+        {
+            self.segments.push_back(Segment {
+                first_sequence: event.sequence,
+                path: self.dir.join(format!(
+                    "{SEGMENT_PREFIX}{:020}{SEGMENT_SUFFIX}",
+                    event.sequence
+                )),
+                events: 0,
+            });
+        }
+
+        let segment = self
+            .segments
+            .back_mut()
+            .expect("active segment was just ensured");
+        let mut line = serde_json::to_vec(event).map_err(io::Error::other)?;
+        line.push(b'\n');
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&segment.path)?;
+        file.write_all(&line)?;
+        segment.events += 1;
+        Ok(())
+    }
+
+    /// Marks everything up to `sequence` as outside the retention window and
+    /// deletes segments that no longer hold retained events.
+    pub(super) fn evict_through(&mut self, sequence: u64) -> io::Result<()> {
+        self.meta.evicted_through = self.meta.evicted_through.max(sequence);
+
+        let mut removed = false;
+        while self.segments.len() > 1
+            && self.segments[1].first_sequence <= self.meta.evicted_through.saturating_add(1)
+        {
+            if let Some(segment) = self.segments.pop_front() {
+                fs::remove_file(&segment.path)?;
+                removed = true;
+            }
+        }
+
+        if removed {
+            self.write_meta()?;
+        }
+        Ok(())
+    }
+
+    fn write_meta(&self) -> io::Result<()> {
+        let payload = serde_json::to_vec(&self.meta).map_err(io::Error::other)?;
+        let path = self.dir.join(META_FILE);
+        let tmp_path = path.with_extension("json.tmp");
+        fs::write(&tmp_path, payload)?;
+        fs::rename(tmp_path, path)
+    }
+}
+
+fn read_meta(path: &Path) -> LogMeta {
+    let Ok(content) = fs::read_to_string(path) else {
+        return LogMeta::default();
+    };
+
+    serde_json::from_str(&content).unwrap_or_else(|error| {
+        warn!(path = %path.display(), %error, "failed parsing stream log metadata");
+        LogMeta::default()
+    })
+}
+
+fn list_segments(dir: &Path) -> io::Result<VecDeque<Segment>> {
+    let mut segments = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let Some(first_sequence) = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_prefix(SEGMENT_PREFIX))
+            .and_then(|name| name.strip_suffix(SEGMENT_SUFFIX))
+            .and_then(|sequence| sequence.parse::<u64>().ok())
+        else {
+            continue;
+        };
+
+        segments.push(Segment {
+            first_sequence,
+            path,
+            events: 0,
+        });
+    }
+
+    segments.sort_by_key(|segment| segment.first_sequence);
+    Ok(segments.into())
+}
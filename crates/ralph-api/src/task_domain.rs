@@ -1,7 +1,8 @@
-use std::collections::{BTreeMap, HashSet};
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 
 use chrono::Utc;
+use ralph_core::{Task, TaskStatus, TaskStore};
 use serde::{Deserialize, Serialize};
 
 use crate::errors::ApiError;
@@ -9,6 +10,12 @@ use crate::loop_support::now_ts;
 
 mod storage;
 
+/// [`Task::origin`] of tasks created through the API.
+///
+/// Bulk operations (`clear`, `ready`, `run_all`) only touch these tasks, so
+/// they never remove or queue tasks that belong to running loops.
+const API_ORIGIN: &str = "api";
+
 #[derive(Debug, Clone, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TaskListParams {
@@ -58,6 +65,8 @@ pub struct TaskRecord {
     pub completed_at: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error_message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cost_usd: Option<f64>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -86,27 +95,50 @@ pub struct TaskStatusResult {
     pub runner_pid: Option<u32>,
 }
 
+/// A task status transition made outside the API, e.g. by an agent inside a loop.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaskStatusChange {
+    pub task_id: String,
+    pub from: String,
+    pub to: String,
+}
+
+/// API view over the core task store at `.ralph/agent/tasks.jsonl`.
+///
+/// Records are mapped onto core tasks so that tasks created through the API
+/// are visible to agents and vice versa. The API's `pending` and `running`
+/// statuses map to a queued `open` task and an `in_progress` task.
+/// Tasks created through the API are marked with [`API_ORIGIN`].
 pub struct TaskDomain {
     store_path: PathBuf,
-    tasks: BTreeMap<String, TaskRecord>,
+    legacy_path: PathBuf,
     queue_counter: u64,
+    /// Statuses as last seen by the API, used to detect external changes.
+    known_statuses: HashMap<String, String>,
+    /// External changes noticed while applying an API mutation.
+    unreported_changes: Vec<TaskStatusChange>,
 }
 
 impl TaskDomain {
     pub fn new(workspace_root: impl AsRef<Path>) -> Self {
-        let store_path = workspace_root.as_ref().join(".ralph/api/tasks-v1.json");
+        let workspace_root = workspace_root.as_ref();
         let mut domain = Self {
-            store_path,
-            tasks: BTreeMap::new(),
+            store_path: workspace_root.join(".ralph/agent/tasks.jsonl"),
+            legacy_path: workspace_root.join(".ralph/api/tasks-v1.json"),
             queue_counter: 0,
+            known_statuses: HashMap::new(),
+            unreported_changes: Vec::new(),
         };
-        domain.load();
+        domain.migrate_legacy_snapshot();
+        if let Ok(store) = domain.load_store() {
+            domain.known_statuses = status_map(store.all());
+        }
         domain
     }
 
-    pub fn list(&self, params: TaskListParams) -> Vec<TaskRecord> {
+    pub fn list(&self, params: TaskListParams) -> Result<Vec<TaskRecord>, ApiError> {
         let include_archived = params.include_archived.unwrap_or(false);
-        let mut tasks = self.sorted_tasks();
+        let mut tasks = self.sorted_tasks()?;
 
         if let Some(status) = params.status {
             tasks.retain(|task| task.status == status);
@@ -116,42 +148,39 @@ impl TaskDomain {
             tasks.retain(|task| task.archived_at.is_none());
         }
 
-        tasks
+        Ok(tasks)
     }
 
     pub fn get(&self, id: &str) -> Result<TaskRecord, ApiError> {
-        self.tasks
+        let store = self.load_store()?;
+        store
             .get(id)
-            .cloned()
+            .map(to_record)
             .ok_or_else(|| task_not_found_error(id))
     }
 
-    pub fn ready(&self) -> Vec<TaskRecord> {
-        let unblocking_ids = self.unblocking_ids();
-        let mut tasks: Vec<_> = self
-            .tasks
-            .values()
+    /// Lists open, unblocked tasks created through the API.
+    pub fn ready(&self) -> Result<Vec<TaskRecord>, ApiError> {
+        let store = self.load_store()?;
+        let unblocking_ids = unblocking_ids(store.all());
+        let mut tasks: Vec<_> = store
+            .all()
+            .iter()
+            .filter(|task| is_api_task(task))
+            .map(to_record)
             .filter(|task| task.status == "open" && task.archived_at.is_none())
             .filter(|task| {
                 task.blocked_by
                     .as_ref()
                     .is_none_or(|blocker_id| unblocking_ids.contains(blocker_id))
             })
-            .cloned()
             .collect();
 
         tasks.sort_by(|a, b| a.created_at.cmp(&b.created_at));
-        tasks
+        Ok(tasks)
     }
 
     pub fn create(&mut self, params: TaskCreateParams) -> Result<TaskRecord, ApiError> {
-        if self.tasks.contains_key(&params.id) {
-            return Err(
-                ApiError::conflict(format!("Task with id '{}' already exists", params.id))
-                    .with_details(serde_json::json!({ "taskId": params.id })),
-            );
-        }
-
         let requested_status = params.status.unwrap_or_else(|| "open".to_string());
         let auto_execute = params.auto_execute.unwrap_or(true);
 
@@ -166,78 +195,61 @@ impl TaskDomain {
             })));
         }
 
-        let now = now_ts();
-        let completed_at = is_terminal_status(&requested_status).then_some(now.clone());
-
-        let task = TaskRecord {
-            id: params.id.clone(),
-            title: params.title,
-            status: requested_status,
-            priority: params.priority.unwrap_or(2).clamp(1, 5),
-            blocked_by: params.blocked_by,
-            archived_at: None,
-            queued_task_id: None,
-            merge_loop_prompt: params.merge_loop_prompt,
-            created_at: now.clone(),
-            updated_at: now,
-            completed_at,
-            error_message: None,
-        };
-
-        let task_id = task.id.clone();
-        self.tasks.insert(task_id.clone(), task);
-
-        let should_auto_execute = auto_execute
-            && self
-                .tasks
-                .get(&task_id)
-                .is_some_and(|task| task.blocked_by.is_none() && task.status == "open");
+        let should_auto_execute = auto_execute && params.blocked_by.is_none();
+        let queued_task_id = self.next_queued_task_id();
+        let task_id = params.id.clone();
+
+        self.modify(|store| {
+            if store.get(&params.id).is_some() {
+                return Err(ApiError::conflict(format!(
+                    "Task with id '{}' already exists",
+                    params.id
+                ))
+                .with_details(serde_json::json!({ "taskId": params.id })));
+            }
 
-        if should_auto_execute {
-            let _ = self.run(&task_id)?;
-        } else {
-            self.persist()?;
-        }
+            let now = now_ts();
+            let mut task = Task::new(params.title, params.priority.unwrap_or(2));
+            task.id = params.id;
+            task.description = params.merge_loop_prompt;
+            task.blocked_by = params.blocked_by.into_iter().collect();
+            task.created = now.clone();
+            task.origin = Some(API_ORIGIN.to_string());
+            set_status(&mut task, &requested_status, &queued_task_id, &now)?;
+            if should_auto_execute {
+                queue(&mut task, queued_task_id, &now)?;
+            }
+            store.add(task);
+            Ok(())
+        })?;
 
         self.get(&task_id)
     }
 
     pub fn update(&mut self, input: TaskUpdateInput) -> Result<TaskRecord, ApiError> {
-        let now = now_ts();
-        let task = self
-            .tasks
-            .get_mut(&input.id)
-            .ok_or_else(|| task_not_found_error(&input.id))?;
-
-        if let Some(title) = input.title {
-            task.title = title;
-        }
-        if let Some(status) = input.status {
-            task.status = status;
-
-            if is_terminal_status(&task.status) {
-                task.completed_at = Some(now.clone());
-                task.queued_task_id = None;
-            } else {
-                task.completed_at = None;
-                if !matches!(task.status.as_str(), "pending" | "running") {
-                    task.queued_task_id = None;
-                }
-            }
+        let queued_task_id = self.next_queued_task_id();
+        self.modify(|store| {
+            let task = store
+                .get_mut(&input.id)
+                .ok_or_else(|| task_not_found_error(&input.id))?;
+            let now = now_ts();
 
-            if task.status != "failed" {
-                task.error_message = None;
+            if let Some(status) = &input.status {
+                set_status(task, status, &queued_task_id, &now)?;
+            }
+            if let Some(title) = input.title {
+                task.title = title;
+            }
+            if let Some(priority) = input.priority {
+                task.priority = priority.clamp(1, 5);
+            }
+            if let Some(blocked_by) = input.blocked_by {
+                task.blocked_by = blocked_by.into_iter().collect();
             }
-        }
-        if let Some(priority) = input.priority {
-            task.priority = priority.clamp(1, 5);
-        }
-        if let Some(blocked_by) = input.blocked_by {
-            task.blocked_by = blocked_by;
-        }
 
-        task.updated_at = now;
-        self.persist()?;
+            task.updated = Some(now);
+            Ok(())
+        })?;
         self.get(&input.id)
     }
 
@@ -246,53 +258,60 @@ impl TaskDomain {
     }
 
     pub fn archive(&mut self, id: &str) -> Result<TaskRecord, ApiError> {
-        let task = self
-            .tasks
-            .get_mut(id)
-            .ok_or_else(|| task_not_found_error(id))?;
-
-        task.archived_at = Some(now_ts());
-        task.updated_at = now_ts();
-        self.persist()?;
+        self.modify(|store| {
+            let task = store.get_mut(id).ok_or_else(|| task_not_found_error(id))?;
+            task.archived = Some(now_ts());
+            task.updated = Some(now_ts());
+            Ok(())
+        })?;
         self.get(id)
     }
 
     pub fn unarchive(&mut self, id: &str) -> Result<TaskRecord, ApiError> {
-        let task = self
-            .tasks
-            .get_mut(id)
-            .ok_or_else(|| task_not_found_error(id))?;
-
-        task.archived_at = None;
-        task.updated_at = now_ts();
-        self.persist()?;
+        self.modify(|store| {
+            let task = store.get_mut(id).ok_or_else(|| task_not_found_error(id))?;
+            task.archived = None;
+            task.updated = Some(now_ts());
+            Ok(())
+        })?;
         self.get(id)
     }
 
     pub fn delete(&mut self, id: &str) -> Result<(), ApiError> {
-        let task = self.tasks.get(id).ok_or_else(|| task_not_found_error(id))?;
-
-        if !matches!(task.status.as_str(), "failed" | "closed") {
-            return Err(ApiError::precondition_failed(format!(
-                "Cannot delete task in '{}' state. Only failed or closed tasks can be deleted.",
-                task.status
-            ))
-            .with_details(serde_json::json!({
-                "taskId": id,
-                "status": task.status,
-                "allowedStatuses": ["failed", "closed"]
-            })));
-        }
+        self.modify(|store| {
+            let task = store.get(id).ok_or_else(|| task_not_found_error(id))?;
+            let status = api_status(task);
+
+            if !matches!(status, "failed" | "closed") {
+                return Err(ApiError::precondition_failed(format!(
+                    "Cannot delete task in '{status}' state. Only failed or closed tasks can be deleted."
+                ))
+                .with_details(serde_json::json!({
+                    "taskId": id,
+                    "status": status,
+                    "allowedStatuses": ["failed", "closed"]
+                })));
+            }
 
-        self.tasks.remove(id);
-        self.persist()?;
-        Ok(())
+            store.remove(id);
+            Ok(())
+        })
     }
 
+    /// Removes every task created through the API, leaving agent tasks alone.
     pub fn clear(&mut self) -> Result<(), ApiError> {
-        self.tasks.clear();
-        self.persist()?;
-        Ok(())
+        self.modify(|store| {
+            let api_task_ids: Vec<String> = store
+                .all()
+                .iter()
+                .filter(|task| is_api_task(task))
+                .map(|task| task.id.clone())
+                .collect();
+            for id in &api_task_ids {
+                store.remove(id);
+            }
+            Ok(())
+        })
     }
 
     pub fn run(&mut self, id: &str) -> Result<TaskRunResult, ApiError> {
@@ -304,8 +323,9 @@ impl TaskDomain {
         })
     }
 
-    pub fn run_all(&mut self) -> TaskRunAllResult {
-        let ready_task_ids: Vec<String> = self.ready().into_iter().map(|task| task.id).collect();
+    /// Queues every task returned by [`Self::ready`].
+    pub fn run_all(&mut self) -> Result<TaskRunAllResult, ApiError> {
+        let ready_task_ids: Vec<String> = self.ready()?.into_iter().map(|task| task.id).collect();
         let mut enqueued = 0_u64;
         let mut errors = Vec::new();
 
@@ -320,187 +340,261 @@ impl TaskDomain {
             }
         }
 
-        TaskRunAllResult { enqueued, errors }
+        Ok(TaskRunAllResult { enqueued, errors })
     }
 
     pub fn retry(&mut self, id: &str) -> Result<TaskRunResult, ApiError> {
-        {
-            let task = self
-                .tasks
-                .get_mut(id)
-                .ok_or_else(|| task_not_found_error(id))?;
+        self.modify(|store| {
+            let task = store.get_mut(id).ok_or_else(|| task_not_found_error(id))?;
 
-            if task.status != "failed" {
+            if task.status != TaskStatus::Failed {
                 return Err(
                     ApiError::precondition_failed("Only failed tasks can be retried").with_details(
                         serde_json::json!({
                             "taskId": id,
-                            "status": task.status,
+                            "status": api_status(task),
                         }),
                     ),
                 );
             }
 
-            let now = now_ts();
-            task.status = "open".to_string();
-            task.queued_task_id = None;
-            task.completed_at = None;
-            task.error_message = None;
-            task.updated_at = now;
-        }
+            task.reopen();
+            task.queued = None;
+            task.error = None;
+            task.updated = Some(now_ts());
+            Ok(())
+        })?;
 
         self.run(id)
     }
 
     pub fn cancel(&mut self, id: &str) -> Result<TaskRecord, ApiError> {
-        let task = self
-            .tasks
-            .get_mut(id)
-            .ok_or_else(|| task_not_found_error(id))?;
-
-        if !matches!(task.status.as_str(), "pending" | "running") {
-            return Err(ApiError::precondition_failed(
-                "Only running or pending tasks can be cancelled",
-            )
-            .with_details(serde_json::json!({
-                "taskId": id,
-                "status": task.status,
-            })));
-        }
+        self.modify(|store| {
+            let task = store.get_mut(id).ok_or_else(|| task_not_found_error(id))?;
+            let status = api_status(task);
 
-        let now = now_ts();
-        task.status = "failed".to_string();
-        task.completed_at = Some(now.clone());
-        task.updated_at = now;
-        task.error_message = Some("Task cancelled by user".to_string());
-        task.queued_task_id = None;
+            if !matches!(status, "pending" | "running") {
+                return Err(ApiError::precondition_failed(
+                    "Only running or pending tasks can be cancelled",
+                )
+                .with_details(serde_json::json!({
+                    "taskId": id,
+                    "status": status,
+                })));
+            }
 
-        self.persist()?;
+            let now = now_ts();
+            task.status = TaskStatus::Failed;
+            task.closed = Some(now.clone());
+            task.updated = Some(now);
+            task.error = Some("Task cancelled by user".to_string());
+            task.queued = None;
+            Ok(())
+        })?;
         self.get(id)
     }
 
-    pub fn status(&self, id: &str) -> TaskStatusResult {
-        let Some(task) = self.tasks.get(id) else {
-            return TaskStatusResult {
+    pub fn status(&self, id: &str) -> Result<TaskStatusResult, ApiError> {
+        let store = self.load_store()?;
+        let Some(task) = store.get(id) else {
+            return Ok(TaskStatusResult {
                 is_queued: false,
                 queue_position: None,
                 runner_pid: None,
-            };
+            });
         };
 
-        let is_queued =
-            task.queued_task_id.is_some() && matches!(task.status.as_str(), "pending" | "running");
+        let status = api_status(task);
+        let is_queued = task.queued.is_some() && matches!(status, "pending" | "running");
 
         let queue_position = if is_queued {
-            self.queue_position(id)
+            queued_tasks(store.all())
+                .iter()
+                .position(|task| task.id == id)
+                .map(|index| index as u64)
         } else {
             None
         };
 
-        let runner_pid = if task.status == "running" {
+        let runner_pid = if status == "running" {
             Some(std::process::id())
         } else {
             None
         };
 
-        TaskStatusResult {
+        Ok(TaskStatusResult {
             is_queued,
             queue_position,
             runner_pid,
-        }
+        })
     }
 
-    fn transition_task(&mut self, id: &str, status: &str) -> Result<TaskRecord, ApiError> {
-        let task = self
-            .tasks
-            .get_mut(id)
-            .ok_or_else(|| task_not_found_error(id))?;
-
-        let now = now_ts();
-        task.status = status.to_string();
-        task.updated_at = now.clone();
-
-        if is_terminal_status(status) {
-            task.completed_at = Some(now);
-            task.queued_task_id = None;
-        } else {
-            task.completed_at = None;
-            if !matches!(status, "pending" | "running") {
-                task.queued_task_id = None;
+    /// Marks up to `limit` queued tasks as running and returns them.
+    ///
+    /// Tasks are taken in queue order; a task whose blocker has not closed
+    /// stays pending without holding up the tasks behind it.
+    pub fn claim_runnable(&mut self, limit: usize) -> Result<Vec<TaskRecord>, ApiError> {
+        let claimed = self.modify(|store| {
+            let unblocking_ids = unblocking_ids(store.all());
+            let claimable: Vec<String> = queued_tasks(store.all())
+                .into_iter()
+                .filter(|task| api_status(task) == "pending" && task.archived.is_none())
+                .filter(|task| {
+                    task.blocked_by
+                        .iter()
+                        .all(|blocker_id| unblocking_ids.contains(blocker_id))
+                })
+                .take(limit)
+                .map(|task| task.id.clone())
+                .collect();
+
+            let now = now_ts();
+            for id in &claimable {
+                if let Some(task) = store.get_mut(id) {
+                    task.start();
+                    task.updated = Some(now.clone());
+                }
             }
-        }
+            Ok(claimable)
+        })?;
 
-        if status != "failed" {
-            task.error_message = None;
+        claimed.iter().map(|id| self.get(id)).collect()
+    }
+
+    /// Records the outcome of a task run.
+    ///
+    /// Returns `None` when the task is no longer running, e.g. because it was
+    /// cancelled while its loop was still executing.
+    pub fn finish_run(
+        &mut self,
+        id: &str,
+        status: &str,
+        error_message: Option<String>,
+        cost_usd: Option<f64>,
+    ) -> Result<Option<TaskRecord>, ApiError> {
+        let finished = self.modify(|store| {
+            let Some(task) = store.get_mut(id) else {
+                return Ok(false);
+            };
+            if task.status != TaskStatus::InProgress {
+                return Ok(false);
+            }
+
+            let now = now_ts();
+            set_status(task, status, "", &now)?;
+            task.error = error_message;
+            task.cost_usd = cost_usd;
+            task.updated = Some(now);
+            Ok(true)
+        })?;
+
+        if finished {
+            self.get(id).map(Some)
+        } else {
+            Ok(None)
         }
+    }
+
+    /// Fails queued tasks left running by a previous API process.
+    pub fn fail_interrupted(&mut self) -> Result<Vec<String>, ApiError> {
+        self.modify(|store| {
+            let now = now_ts();
+            let interrupted: Vec<String> = store
+                .all()
+                .iter()
+                .filter(|task| task.status == TaskStatus::InProgress && task.queued.is_some())
+                .map(|task| task.id.clone())
+                .collect();
+
+            for id in &interrupted {
+                if let Some(task) = store.get_mut(id) {
+                    task.status = TaskStatus::Failed;
+                    task.queued = None;
+                    task.closed = Some(now.clone());
+                    task.updated = Some(now.clone());
+                    task.error = Some("Task runner stopped before the task finished".to_string());
+                }
+            }
+            Ok(interrupted)
+        })
+    }
 
-        self.persist()?;
+    /// Returns status changes made to the task store outside the API since
+    /// the last call.
+    pub fn poll_external_changes(&mut self) -> Result<Vec<TaskStatusChange>, ApiError> {
+        let store = self.load_store()?;
+        let mut changes = std::mem::take(&mut self.unreported_changes);
+        changes.extend(self.observe(store.all()));
+        Ok(changes)
+    }
+
+    fn transition_task(&mut self, id: &str, status: &str) -> Result<TaskRecord, ApiError> {
+        self.modify(|store| {
+            let task = store.get_mut(id).ok_or_else(|| task_not_found_error(id))?;
+            let now = now_ts();
+            set_status(task, status, "", &now)?;
+            task.updated = Some(now);
+            Ok(())
+        })?;
         self.get(id)
     }
 
     fn queue_task(&mut self, id: &str) -> Result<String, ApiError> {
         let queued_task_id = self.next_queued_task_id();
-        let now = now_ts();
 
-        let task = self
-            .tasks
-            .get_mut(id)
-            .ok_or_else(|| task_not_found_error(id))?;
-
-        if task.archived_at.is_some() {
-            return Err(
-                ApiError::precondition_failed("Cannot run archived task").with_details(
-                    serde_json::json!({
-                        "taskId": id,
-                    }),
-                ),
-            );
-        }
-
-        if matches!(task.status.as_str(), "pending" | "running") {
-            return Err(
-                ApiError::precondition_failed("Task is already queued or running").with_details(
-                    serde_json::json!({
-                        "taskId": id,
-                        "status": task.status
-                    }),
-                ),
-            );
-        }
-
-        task.status = "pending".to_string();
-        task.queued_task_id = Some(queued_task_id.clone());
-        task.completed_at = None;
-        task.error_message = None;
-        task.updated_at = now;
-        self.persist()?;
+        self.modify(|store| {
+            let task = store.get_mut(id).ok_or_else(|| task_not_found_error(id))?;
+            queue(task, queued_task_id.clone(), &now_ts())
+        })?;
 
         Ok(queued_task_id)
     }
 
-    fn queue_position(&self, id: &str) -> Option<u64> {
-        let mut queued: Vec<&TaskRecord> = self
-            .tasks
-            .values()
-            .filter(|task| {
-                task.queued_task_id.is_some()
-                    && matches!(task.status.as_str(), "pending" | "running")
+    /// Applies `f` to the store under its exclusive lock and saves the result.
+    fn modify<T>(
+        &mut self,
+        f: impl FnOnce(&mut TaskStore) -> Result<T, ApiError>,
+    ) -> Result<T, ApiError> {
+        let mut store = self.load_store()?;
+        let mut external = Vec::new();
+        let result = store
+            .with_exclusive_lock(|store| {
+                external = self.observe(store.all());
+                let snapshot = store.all().to_vec();
+                let result = f(store);
+                if result.is_err() {
+                    restore(store, snapshot);
+                }
+                result
             })
-            .collect();
-        queued.sort_by(|a, b| a.updated_at.cmp(&b.updated_at));
+            .map_err(|error| store_error(&self.store_path, &error))?;
 
-        queued
+        self.unreported_changes.extend(external);
+        self.known_statuses = status_map(store.all());
+        result
+    }
+
+    /// Diffs `tasks` against the known statuses and records them as known.
+    fn observe(&mut self, tasks: &[Task]) -> Vec<TaskStatusChange> {
+        let current = status_map(tasks);
+        let mut changes: Vec<TaskStatusChange> = current
             .iter()
-            .position(|task| task.id == id)
-            .map(|index| index as u64)
+            .filter_map(|(id, status)| {
+                let from = self.known_statuses.get(id).map_or("none", String::as_str);
+                (from != status).then(|| TaskStatusChange {
+                    task_id: id.clone(),
+                    from: from.to_string(),
+                    to: status.clone(),
+                })
+            })
+            .collect();
+        changes.sort_by(|a, b| a.task_id.cmp(&b.task_id));
+        self.known_statuses = current;
+        changes
     }
 
-    fn unblocking_ids(&self) -> HashSet<String> {
-        self.tasks
-            .values()
-            .filter(|task| task.status == "closed" || task.archived_at.is_some())
-            .map(|task| task.id.clone())
-            .collect()
+    fn load_store(&self) -> Result<TaskStore, ApiError> {
+        TaskStore::load(&self.store_path).map_err(|error| store_error(&self.store_path, &error))
     }
 
     fn next_queued_task_id(&mut self) -> String {
@@ -512,11 +606,176 @@ impl TaskDomain {
         )
     }
 
-    fn sorted_tasks(&self) -> Vec<TaskRecord> {
-        let mut tasks: Vec<_> = self.tasks.values().cloned().collect();
+    fn sorted_tasks(&self) -> Result<Vec<TaskRecord>, ApiError> {
+        let store = self.load_store()?;
+        let mut tasks: Vec<_> = store.all().iter().map(to_record).collect();
         tasks.sort_by(|a, b| a.created_at.cmp(&b.created_at));
-        tasks
+        Ok(tasks)
+    }
+}
+
+/// Maps a core task onto the API record shape.
+fn to_record(task: &Task) -> TaskRecord {
+    let updated_at = task
+        .updated
+        .clone()
+        .or_else(|| task.closed.clone())
+        .or_else(|| task.started.clone())
+        .unwrap_or_else(|| task.created.clone());
+
+    TaskRecord {
+        id: task.id.clone(),
+        title: task.title.clone(),
+        status: api_status(task).to_string(),
+        priority: task.priority,
+        blocked_by: task.blocked_by.first().cloned(),
+        archived_at: task.archived.clone(),
+        queued_task_id: task.queued.clone(),
+        merge_loop_prompt: task.description.clone(),
+        created_at: task.created.clone(),
+        updated_at,
+        completed_at: task.closed.clone(),
+        error_message: task.error.clone(),
+        cost_usd: task.cost_usd,
+    }
+}
+
+fn is_api_task(task: &Task) -> bool {
+    task.origin.as_deref() == Some(API_ORIGIN)
+}
+
+fn api_status(task: &Task) -> &'static str {
+    match task.status {
+        TaskStatus::Open if task.queued.is_some() => "pending",
+        TaskStatus::Open => "open",
+        TaskStatus::InProgress => "running",
+        TaskStatus::Closed => "closed",
+        TaskStatus::Failed => "failed",
+    }
+}
+
+/// Applies an API status to a core task.
+///
+/// `pending` keeps the task's queue ticket, or assigns `queued_task_id` when
+/// it has none.
+fn set_status(
+    task: &mut Task,
+    status: &str,
+    queued_task_id: &str,
+    now: &str,
+) -> Result<(), ApiError> {
+    match status {
+        "open" => task.reopen(),
+        "pending" => {
+            task.reopen();
+            if task.queued.is_none() {
+                task.queued = Some(queued_task_id.to_string());
+            }
+        }
+        "running" => task.start(),
+        "closed" | "done" => {
+            task.status = TaskStatus::Closed;
+            task.closed = Some(now.to_string());
+        }
+        "failed" => {
+            task.status = TaskStatus::Failed;
+            task.closed = Some(now.to_string());
+        }
+        other => {
+            return Err(
+                ApiError::invalid_params(format!("unknown task status '{other}'")).with_details(
+                    serde_json::json!({
+                        "taskId": task.id,
+                        "status": other,
+                        "allowedStatuses": ["open", "pending", "running", "closed", "failed"]
+                    }),
+                ),
+            );
+        }
+    }
+
+    if !matches!(status, "pending" | "running") {
+        task.queued = None;
     }
+    if status != "failed" {
+        task.error = None;
+    }
+    Ok(())
+}
+
+fn queue(task: &mut Task, queued_task_id: String, now: &str) -> Result<(), ApiError> {
+    if task.archived.is_some() {
+        return Err(
+            ApiError::precondition_failed("Cannot run archived task").with_details(
+                serde_json::json!({
+                    "taskId": task.id,
+                }),
+            ),
+        );
+    }
+
+    let status = api_status(task);
+    if matches!(status, "pending" | "running") {
+        return Err(
+            ApiError::precondition_failed("Task is already queued or running").with_details(
+                serde_json::json!({
+                    "taskId": task.id,
+                    "status": status
+                }),
+            ),
+        );
+    }
+
+    task.reopen();
+    task.queued = Some(queued_task_id);
+    task.error = None;
+    task.cost_usd = None;
+    task.updated = Some(now.to_string());
+    Ok(())
+}
+
+fn queued_tasks(tasks: &[Task]) -> Vec<&Task> {
+    let mut queued: Vec<(&Task, String)> = tasks
+        .iter()
+        .filter(|task| task.queued.is_some() && matches!(api_status(task), "pending" | "running"))
+        .map(|task| (task, to_record(task).updated_at))
+        .collect();
+    queued.sort_by(|(a, a_updated), (b, b_updated)| {
+        a_updated
+            .cmp(b_updated)
+            .then_with(|| a.queued.cmp(&b.queued))
+    });
+    queued.into_iter().map(|(task, _)| task).collect()
+}
+
+fn unblocking_ids(tasks: &[Task]) -> HashSet<String> {
+    tasks
+        .iter()
+        .filter(|task| task.status == TaskStatus::Closed || task.archived.is_some())
+        .map(|task| task.id.clone())
+        .collect()
+}
+
+fn status_map(tasks: &[Task]) -> HashMap<String, String> {
+    tasks
+        .iter()
+        .map(|task| (task.id.clone(), api_status(task).to_string()))
+        .collect()
+}
+
+/// Puts the store back to `snapshot` after a failed mutation.
+fn restore(store: &mut TaskStore, snapshot: Vec<Task>) {
+    store.clear();
+    for task in snapshot {
+        store.add(task);
+    }
+}
+
+fn store_error(path: &Path, error: &std::io::Error) -> ApiError {
+    ApiError::internal(format!(
+        "failed to access task store '{}': {error}",
+        path.display()
+    ))
 }
 
 fn task_not_found_error(task_id: &str) -> ApiError {
@@ -524,6 +783,90 @@ fn task_not_found_error(task_id: &str) -> ApiError {
         .with_details(serde_json::json!({ "taskId": task_id }))
 }
 
-fn is_terminal_status(status: &str) -> bool {
-    matches!(status, "closed" | "failed")
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_params(id: &str) -> TaskCreateParams {
+        TaskCreateParams {
+            id: id.to_string(),
+            title: format!("Task {id}"),
+            status: None,
+            priority: None,
+            blocked_by: None,
+            auto_execute: Some(false),
+            merge_loop_prompt: None,
+        }
+    }
+
+    #[test]
+    fn poll_reports_only_changes_made_outside_the_api() {
+        let workspace = tempfile::tempdir().expect("workspace tempdir");
+        let mut domain = TaskDomain::new(workspace.path());
+        domain.create(create_params("task-api")).unwrap();
+        assert!(domain.poll_external_changes().unwrap().is_empty());
+
+        let store_path = workspace.path().join(".ralph/agent/tasks.jsonl");
+        let mut store = TaskStore::load(&store_path).unwrap();
+        let agent_task_id = store
+            .with_exclusive_lock(|store| {
+                store.start("task-api");
+                store.add(Task::new("Agent task".to_string(), 1)).id.clone()
+            })
+            .unwrap();
+
+        // An API mutation in between must not swallow the agent's changes.
+        domain.create(create_params("task-api-2")).unwrap();
+
+        let changes = domain.poll_external_changes().unwrap();
+        assert_eq!(
+            changes,
+            vec![
+                TaskStatusChange {
+                    task_id: agent_task_id,
+                    from: "none".to_string(),
+                    to: "open".to_string(),
+                },
+                TaskStatusChange {
+                    task_id: "task-api".to_string(),
+                    from: "open".to_string(),
+                    to: "running".to_string(),
+                },
+            ]
+        );
+        assert!(domain.poll_external_changes().unwrap().is_empty());
+    }
+
+    #[test]
+    fn bulk_operations_leave_agent_tasks_alone() {
+        let workspace = tempfile::tempdir().expect("workspace tempdir");
+        let mut domain = TaskDomain::new(workspace.path());
+        domain.create(create_params("task-api")).unwrap();
+
+        let store_path = workspace.path().join(".ralph/agent/tasks.jsonl");
+        let mut store = TaskStore::load(&store_path).unwrap();
+        let agent_task_id = store
+            .with_exclusive_lock(|store| {
+                store
+                    .add(
+                        Task::new("Agent subtask".to_string(), 1)
+                            .with_loop_id(Some("loop-1".into())),
+                    )
+                    .id
+                    .clone()
+            })
+            .unwrap();
+
+        let ready: Vec<String> = domain.ready().unwrap().into_iter().map(|t| t.id).collect();
+        assert_eq!(ready, vec!["task-api".to_string()]);
+
+        let result = domain.run_all().unwrap();
+        assert_eq!(result.enqueued, 1);
+        assert_eq!(domain.get("task-api").unwrap().status, "pending");
+        assert_eq!(domain.get(&agent_task_id).unwrap().status, "open");
+
+        domain.clear().unwrap();
+        assert!(domain.get("task-api").is_err());
+        assert_eq!(domain.get(&agent_task_id).unwrap().status, "open");
+    }
 }
@@ -1,12 +1,14 @@
 use std::fs;
 
-use serde::{Deserialize, Serialize};
-use tracing::warn;
+use ralph_core::{Task, TaskStatus};
+use serde::Deserialize;
+use tracing::{info, warn};
 
-use super::{TaskDomain, TaskRecord};
-use crate::errors::ApiError;
+use super::{API_ORIGIN, TaskDomain, TaskRecord};
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+/// Snapshot format used by `.ralph/api/tasks-v1.json` before tasks moved to
+/// the core task store.
+#[derive(Debug, Clone, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 struct TaskSnapshot {
     tasks: Vec<TaskRecord>,
@@ -14,18 +16,22 @@ struct TaskSnapshot {
 }
 
 impl TaskDomain {
-    pub(super) fn load(&mut self) {
-        if !self.store_path.exists() {
+    /// Moves tasks from a legacy `tasks-v1.json` snapshot into the core task
+    /// store, then renames the snapshot so the migration runs once.
+    ///
+    /// Tasks whose id already exists in the core store are left untouched.
+    pub(super) fn migrate_legacy_snapshot(&mut self) {
+        if !self.legacy_path.exists() {
             return;
         }
 
-        let content = match fs::read_to_string(&self.store_path) {
+        let content = match fs::read_to_string(&self.legacy_path) {
             Ok(content) => content,
             Err(error) => {
                 warn!(
-                    path = %self.store_path.display(),
+                    path = %self.legacy_path.display(),
                     %error,
-                    "failed reading task domain snapshot"
+                    "failed reading legacy task snapshot"
                 );
                 return;
             }
@@ -35,45 +41,76 @@ impl TaskDomain {
             Ok(snapshot) => snapshot,
             Err(error) => {
                 warn!(
-                    path = %self.store_path.display(),
+                    path = %self.legacy_path.display(),
                     %error,
-                    "failed parsing task domain snapshot"
+                    "failed parsing legacy task snapshot"
                 );
                 return;
             }
         };
 
-        self.tasks = snapshot
-            .tasks
-            .into_iter()
-            .map(|task| (task.id.clone(), task))
-            .collect();
-        self.queue_counter = snapshot.queue_counter;
-    }
+        let migrated = self.modify(|store| {
+            let mut migrated = 0_usize;
+            for record in snapshot.tasks {
+                if store.get(&record.id).is_none() {
+                    store.add(task_from_legacy(record));
+                    migrated += 1;
+                }
+            }
+            Ok(migrated)
+        });
 
-    pub(super) fn persist(&self) -> Result<(), ApiError> {
-        if let Some(parent) = self.store_path.parent() {
-            fs::create_dir_all(parent).map_err(|error| {
-                ApiError::internal(format!(
-                    "failed to create task snapshot directory '{}': {error}",
-                    parent.display()
-                ))
-            })?;
+        match migrated {
+            Ok(migrated) => {
+                self.queue_counter = snapshot.queue_counter;
+                let archived_path = self.legacy_path.with_extension("json.migrated");
+                if let Err(error) = fs::rename(&self.legacy_path, &archived_path) {
+                    warn!(
+                        path = %self.legacy_path.display(),
+                        %error,
+                        "failed renaming migrated task snapshot"
+                    );
+                }
+                info!(
+                    migrated,
+                    path = %self.store_path.display(),
+                    "migrated legacy API tasks into the task store"
+                );
+            }
+            Err(error) => {
+                warn!(
+                    path = %self.legacy_path.display(),
+                    error = %error.message,
+                    "failed migrating legacy task snapshot"
+                );
+            }
         }
+    }
+}
 
-        let snapshot = TaskSnapshot {
-            tasks: self.sorted_tasks(),
-            queue_counter: self.queue_counter,
-        };
-
-        let payload = serde_json::to_string_pretty(&snapshot)
-            .map_err(|error| ApiError::internal(format!("failed to serialize tasks: {error}")))?;
+fn task_from_legacy(record: TaskRecord) -> Task {
+    let mut task = Task::new(record.title, record.priority);
+    task.id = record.id;
+    task.description = record.merge_loop_prompt;
+    task.blocked_by = record.blocked_by.into_iter().collect();
+    task.created = record.created_at;
+    task.origin = Some(API_ORIGIN.to_string());
+    task.updated = Some(record.updated_at.clone());
+    task.closed = record.completed_at;
+    task.archived = record.archived_at;
+    task.error = record.error_message;
+    task.cost_usd = record.cost_usd;
 
-        fs::write(&self.store_path, payload).map_err(|error| {
-            ApiError::internal(format!(
-                "failed to write task snapshot '{}': {error}",
-                self.store_path.display()
-            ))
-        })
+    match record.status.as_str() {
+        "pending" => task.queued = record.queued_task_id,
+        "running" => {
+            task.status = TaskStatus::InProgress;
+            task.started = Some(record.updated_at);
+            task.queued = record.queued_task_id;
+        }
+        "closed" | "done" => task.status = TaskStatus::Closed,
+        "failed" => task.status = TaskStatus::Failed,
+        _ => {}
     }
+    task
 }
@@ -0,0 +1,621 @@
+//! Background execution of queued tasks.
+//!
+//! `task.run` and `task.run_all` only queue tasks. The worker picks queued
+//! tasks up in queue order, runs each one as a `ralph run --rpc` loop, streams
+//! the loop's output as `task.log.line` events and writes the outcome back to
+//! the task record. `ralph run` itself moves the loop into a worktree when the
+//! primary loop lock is held, so concurrent tasks never share a checkout.
+//!
+//! The worker also watches the task store, so status changes that agents make
+//! from inside a loop are published as `task.status.changed` events.
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read};
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use std::thread;
+use std::time::Duration;
+
+use ralph_core::LoopLock;
+use ralph_proto::{RpcEvent, TerminationReason};
+use serde_json::json;
+use tracing::warn;
+
+use crate::stream_domain::StreamDomain;
+use crate::task_domain::{TaskDomain, TaskRecord};
+
+/// How often the task store is checked for changes made outside the API.
+const STORE_WATCH_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Clone)]
+pub struct TaskWorker {
+    /// Held weakly so worker threads end once the runtime is dropped.
+    tasks: Weak<Mutex<TaskDomain>>,
+    streams: StreamDomain,
+    workspace_root: PathBuf,
+    ralph_command: String,
+    concurrency: usize,
+    /// Tasks currently executing, with the pid of their loop once spawned.
+    runners: Arc<Mutex<HashMap<String, Option<u32>>>>,
+}
+
+impl TaskWorker {
+    pub fn new(
+        tasks: &Arc<Mutex<TaskDomain>>,
+        streams: StreamDomain,
+        workspace_root: impl Into<PathBuf>,
+        ralph_command: impl Into<String>,
+        concurrency: usize,
+    ) -> Self {
+        let worker = Self {
+            tasks: Arc::downgrade(tasks),
+            streams,
+            workspace_root: workspace_root.into(),
+            ralph_command: ralph_command.into(),
+            concurrency,
+            runners: Arc::new(Mutex::new(HashMap::new())),
+        };
+        worker.fail_interrupted();
+        worker
+    }
+
+    /// Starts queued tasks until the concurrency limit is reached.
+    pub fn drain(&self) {
+        if self.concurrency == 0 {
+            return;
+        }
+
+        let Ok(mut runners) = self.runners.lock() else {
+            warn!("task runner registry lock poisoned");
+            return;
+        };
+        let free_slots = self.concurrency.saturating_sub(runners.len());
+        if free_slots == 0 {
+            return;
+        }
+
+        let Some(tasks) = self.tasks.upgrade() else {
+            return;
+        };
+        let claimed = match tasks.lock() {
+            Ok(mut tasks) => tasks.claim_runnable(free_slots),
+            Err(_) => {
+                warn!("task domain lock poisoned");
+                return;
+            }
+        };
+        let claimed = match claimed {
+            Ok(claimed) => claimed,
+            Err(error) => {
+                warn!(error = %error.message, "failed claiming queued tasks");
+                return;
+            }
+        };
+
+        for task in claimed {
+            runners.insert(task.id.clone(), None);
+            self.publish_status(&task.id, "pending", "running");
+
+            let worker = self.clone();
+            thread::spawn(move || worker.run_task(task));
+        }
+    }
+
+    /// Publishes status changes made to the task store outside the API,
+    /// starting queued tasks they unblock.
+    pub fn watch(&self) {
+        let worker = self.clone();
+        thread::spawn(move || {
+            loop {
+                thread::sleep(STORE_WATCH_INTERVAL);
+                let Some(tasks) = worker.tasks.upgrade() else {
+                    return;
+                };
+                let changes = match tasks.lock() {
+                    Ok(mut tasks) => tasks.poll_external_changes(),
+                    Err(_) => return,
+                };
+                drop(tasks);
+
+                match changes {
+                    Ok(changes) if !changes.is_empty() => {
+                        for change in changes {
+                            worker.publish_status(&change.task_id, &change.from, &change.to);
+                        }
+                        worker.drain();
+                    }
+                    Ok(_) => {}
+                    Err(error) => warn!(error = %error.message, "failed polling task store"),
+                }
+            }
+        });
+    }
+
+    /// Stops the loop executing `task_id`, if any.
+    pub fn stop(&self, task_id: &str) {
+        let Some(pid) = self.runner_pid(task_id) else {
+            return;
+        };
+
+        if let Err(error) = Command::new("kill").arg(pid.to_string()).status() {
+            warn!(task_id, pid, %error, "failed stopping task loop");
+        }
+    }
+
+    /// Returns the pid of the loop executing `task_id`.
+    pub fn runner_pid(&self, task_id: &str) -> Option<u32> {
+        self.runners
+            .lock()
+            .ok()
+            .and_then(|runners| runners.get(task_id).copied().flatten())
+    }
+
+    fn run_task(self, task: TaskRecord) {
+        let log = TaskLog::new(self.streams.clone(), &task.id);
+        let result = match self.spawn_loop(&task, &log) {
+            Ok(child) => self.follow_loop(&task.id, child, &log),
+            Err(error) => RunResult::failed(format!("failed to start ralph: {error}"), None),
+        };
+
+        if let Ok(mut runners) = self.runners.lock() {
+            runners.remove(&task.id);
+        }
+
+        let Some(tasks) = self.tasks.upgrade() else {
+            return;
+        };
+        let finished = match tasks.lock() {
+            Ok(mut tasks) => tasks.finish_run(
+                &task.id,
+                result.status,
+                result.error_message,
+                result.cost_usd,
+            ),
+            Err(_) => {
+                warn!(task_id = %task.id, "task domain lock poisoned");
+                return;
+            }
+        };
+        match finished {
+            Ok(Some(record)) => self.publish_status(&record.id, "running", &record.status),
+            Ok(None) => {}
+            Err(error) => {
+                warn!(task_id = %task.id, error = %error.message, "failed recording task outcome");
+            }
+        }
+
+        self.drain();
+    }
+
+    fn spawn_loop(&self, task: &TaskRecord, log: &TaskLog) -> std::io::Result<Child> {
+        if LoopLock::is_locked(&self.workspace_root).unwrap_or(false) {
+            log.line("stdout", "Primary loop lock is held; running in a worktree");
+        }
+
+        let prompt = task
+            .merge_loop_prompt
+            .clone()
+            .unwrap_or_else(|| task.title.clone());
+
+        Command::new(&self.ralph_command)
+            .args(["run", "--rpc", "-p", &prompt])
+            .current_dir(&self.workspace_root)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+    }
+
+    fn follow_loop(&self, task_id: &str, mut child: Child, log: &TaskLog) -> RunResult {
+        if let Ok(mut runners) = self.runners.lock() {
+            runners.insert(task_id.to_string(), Some(child.id()));
+        }
+
+        let stderr_reader = child.stderr.take().map(|stderr| {
+            let log = log.clone();
+            thread::spawn(move || {
+                for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+                    log.line("stderr", &line);
+                }
+            })
+        });
+
+        let mut output = LoopOutput::default();
+        if let Some(stdout) = child.stdout.take() {
+            output.read(stdout, log);
+        }
+        if let Some(reader) = stderr_reader {
+            let _ = reader.join();
+        }
+
+        let exit_error = match child.wait() {
+            Ok(status) if status.success() => None,
+            Ok(status) => Some(format!("ralph exited with {status}")),
+            Err(error) => Some(format!("failed waiting for ralph: {error}")),
+        };
+        output.into_result(exit_error)
+    }
+
+    fn fail_interrupted(&self) {
+        let Some(tasks) = self.tasks.upgrade() else {
+            return;
+        };
+        let interrupted = match tasks.lock() {
+            Ok(mut tasks) => tasks.fail_interrupted(),
+            Err(_) => return,
+        };
+        match interrupted {
+            Ok(ids) => {
+                for id in ids {
+                    self.publish_status(&id, "running", "failed");
+                }
+            }
+            Err(error) => warn!(error = %error.message, "failed resetting interrupted tasks"),
+        }
+    }
+
+    fn publish_status(&self, task_id: &str, from: &str, to: &str) {
+        self.streams.publish(
+            "task.status.changed",
+            "task",
+            task_id,
+            json!({ "from": from, "to": to }),
+        );
+    }
+}
+
+/// Publishes a task's output as numbered `task.log.line` events.
+#[derive(Clone)]
+struct TaskLog {
+    streams: StreamDomain,
+    task_id: String,
+    line_number: Arc<AtomicU64>,
+}
+
+impl TaskLog {
+    fn new(streams: StreamDomain, task_id: &str) -> Self {
+        Self {
+            streams,
+            task_id: task_id.to_string(),
+            line_number: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    fn line(&self, source: &str, line: &str) {
+        let line_number = self.line_number.fetch_add(1, Ordering::Relaxed);
+        self.streams.publish(
+            "task.log.line",
+            "task",
+            &self.task_id,
+            json!({
+                "taskId": self.task_id,
+                "line": line,
+                "source": source,
+                "lineNumber": line_number
+            }),
+        );
+    }
+}
+
+/// Outcome of a task run as written back to the task record.
+#[derive(Debug, PartialEq)]
+struct RunResult {
+    status: &'static str,
+    error_message: Option<String>,
+    cost_usd: Option<f64>,
+}
+
+impl RunResult {
+    fn failed(message: String, cost_usd: Option<f64>) -> Self {
+        Self {
+            status: "failed",
+            error_message: Some(message),
+            cost_usd,
+        }
+    }
+}
+
+/// Accumulates what a `ralph run --rpc` loop reports on stdout.
+#[derive(Debug, Default)]
+struct LoopOutput {
+    /// Streamed text not yet terminated by a newline.
+    partial_line: String,
+    termination: Option<TerminationReason>,
+    last_error: Option<String>,
+    cost_usd: Option<f64>,
+}
+
+impl LoopOutput {
+    fn read(&mut self, stdout: impl Read, log: &TaskLog) {
+        for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+            for output_line in self.observe(&line) {
+                log.line("stdout", &output_line);
+            }
+        }
+        if let Some(rest) = self.flush() {
+            log.line("stdout", &rest);
+        }
+    }
+
+    /// Consumes one stdout line and returns the log lines it completes.
+    fn observe(&mut self, line: &str) -> Vec<String> {
+        let Ok(event) = serde_json::from_str::<RpcEvent>(line) else {
+            let mut lines: Vec<String> = self.flush().into_iter().collect();
+            lines.push(line.to_string());
+            return lines;
+        };
+
+        match event {
+            RpcEvent::TextDelta { delta, .. } => {
+                self.partial_line.push_str(&delta);
+                let mut lines = Vec::new();
+                while let Some(index) = self.partial_line.find('\n') {
+                    let rest = self.partial_line.split_off(index + 1);
+                    let complete = std::mem::replace(&mut self.partial_line, rest);
+                    lines.push(complete.trim_end_matches(['\r', '\n']).to_string());
+                }
+                lines
+            }
+            RpcEvent::ToolCallStart { tool_name, .. } => {
+                let mut lines: Vec<String> = self.flush().into_iter().collect();
+                lines.push(format!("[tool] {tool_name}"));
+                lines
+            }
+            RpcEvent::Error { message, .. } => {
+                let mut lines: Vec<String> = self.flush().into_iter().collect();
+                lines.push(format!("[error] {message}"));
+                self.last_error = Some(message);
+                lines
+            }
+            RpcEvent::IterationEnd { cost_usd, .. } => {
+                *self.cost_usd.get_or_insert(0.0) += cost_usd;
+                self.flush().into_iter().collect()
+            }
+            RpcEvent::LoopTerminated {
+                reason,
+                total_cost_usd,
+                ..
+            } => {
+                self.termination = Some(reason);
+                self.cost_usd = Some(total_cost_usd);
+                self.flush().into_iter().collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    fn flush(&mut self) -> Option<String> {
+        if self.partial_line.is_empty() {
+            return None;
+        }
+        Some(std::mem::take(&mut self.partial_line))
+    }
+
+    fn into_result(self, exit_error: Option<String>) -> RunResult {
+        match self.termination {
+            Some(TerminationReason::Completed | TerminationReason::AllTasksClosed) => RunResult {
+                status: "closed",
+                error_message: None,
+                cost_usd: self.cost_usd,
+            },
+            Some(reason) => {
+                let message = self
+                    .last_error
+                    .unwrap_or_else(|| format!("Loop ended without completing: {reason:?}"));
+                RunResult::failed(message, self.cost_usd)
+            }
+            None => {
+                let message = self
+                    .last_error
+                    .or(exit_error)
+                    .unwrap_or_else(|| "Loop exited without reporting a result".to_string());
+                RunResult::failed(message, self.cost_usd)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use serde_json::json;
+
+    use super::*;
+    use crate::stream_domain::StreamSubscribeParams;
+
+    #[test]
+    fn loop_output_splits_text_into_lines_and_reads_outcome() {
+        let mut output = LoopOutput::default();
+        let mut lines = Vec::new();
+        lines.extend(output.observe(
+            &json!({ "type": "text_delta", "iteration": 1, "delta": "Running " }).to_string(),
+        ));
+        lines.extend(
+            output.observe(
+                &json!({ "type": "text_delta", "iteration": 1, "delta": "tests...\nAll passed" })
+                    .to_string(),
+            ),
+        );
+        lines.extend(
+            output.observe(
+                &json!({
+                    "type": "tool_call_start",
+                    "iteration": 1,
+                    "tool_name": "Bash",
+                    "tool_call_id": "t1",
+                    "input": {}
+                })
+                .to_string(),
+            ),
+        );
+        lines.extend(output.observe("plain output"));
+        lines.extend(
+            output.observe(
+                &json!({
+                    "type": "loop_terminated",
+                    "reason": "completed",
+                    "total_iterations": 2,
+                    "duration_ms": 10,
+                    "total_cost_usd": 0.42,
+                    "terminated_at": 0
+                })
+                .to_string(),
+            ),
+        );
+
+        assert_eq!(
+            lines,
+            vec![
+                "Running tests...",
+                "All passed",
+                "[tool] Bash",
+                "plain output"
+            ]
+        );
+        assert_eq!(
+            output.into_result(None),
+            RunResult {
+                status: "closed",
+                error_message: None,
+                cost_usd: Some(0.42),
+            }
+        );
+    }
+
+    #[test]
+    fn loop_output_without_completion_fails_with_last_error() {
+        let mut output = LoopOutput::default();
+        output.observe(
+            &json!({
+                "type": "iteration_end",
+                "iteration": 1,
+                "duration_ms": 10,
+                "cost_usd": 0.1,
+                "input_tokens": 1,
+                "output_tokens": 1,
+                "cache_read_tokens": 0,
+                "cache_write_tokens": 0,
+                "loop_complete_triggered": false
+            })
+            .to_string(),
+        );
+        output.observe(
+            &json!({
+                "type": "error",
+                "iteration": 1,
+                "code": "API_ERROR",
+                "message": "backend unavailable",
+                "recoverable": false
+            })
+            .to_string(),
+        );
+
+        let result = output.into_result(Some("ralph exited with exit status: 1".to_string()));
+        assert_eq!(result.status, "failed");
+        assert_eq!(result.error_message.as_deref(), Some("backend unavailable"));
+        assert_eq!(result.cost_usd, Some(0.1));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn worker_runs_queued_tasks_and_streams_output() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let workspace = tempfile::tempdir().expect("workspace tempdir");
+        let command = workspace.path().join("fake-ralph");
+        std::fs::write(
+            &command,
+            concat!(
+                "#!/bin/sh\n",
+                "echo '{\"type\":\"text_delta\",\"iteration\":1,\"delta\":\"working\\\\n\"}'\n",
+                "echo 'backend warning' >&2\n",
+                "echo '{\"type\":\"loop_terminated\",\"reason\":\"completed\",",
+                "\"total_iterations\":1,\"duration_ms\":5,\"total_cost_usd\":0.25,",
+                "\"terminated_at\":0}'\n",
+            ),
+        )
+        .expect("write fake ralph");
+        std::fs::set_permissions(&command, std::fs::Permissions::from_mode(0o755))
+            .expect("chmod fake ralph");
+
+        let tasks = Arc::new(Mutex::new(TaskDomain::new(workspace.path())));
+        let streams = StreamDomain::new();
+        {
+            let mut domain = tasks.lock().unwrap();
+            for (id, blocked_by) in [("task-a", None), ("task-b", Some("task-a"))] {
+                domain
+                    .create(crate::task_domain::TaskCreateParams {
+                        id: id.to_string(),
+                        title: format!("Do {id}"),
+                        status: None,
+                        priority: None,
+                        blocked_by: blocked_by.map(str::to_string),
+                        auto_execute: Some(false),
+                        merge_loop_prompt: None,
+                    })
+                    .unwrap();
+                domain.run(id).unwrap();
+            }
+        }
+
+        let worker = TaskWorker::new(
+            &tasks,
+            streams.clone(),
+            workspace.path(),
+            command.to_string_lossy(),
+            2,
+        );
+        worker.drain();
+
+        let deadline = Instant::now() + Duration::from_secs(10);
+        loop {
+            let statuses: Vec<String> = ["task-a", "task-b"]
+                .iter()
+                .map(|id| tasks.lock().unwrap().get(id).unwrap().status)
+                .collect();
+            if statuses.iter().all(|status| status == "closed") {
+                break;
+            }
+            assert!(
+                Instant::now() < deadline,
+                "tasks did not finish: {statuses:?}"
+            );
+            thread::sleep(Duration::from_millis(20));
+        }
+
+        let task = tasks.lock().unwrap().get("task-a").unwrap();
+        assert_eq!(task.cost_usd, Some(0.25));
+        assert!(task.queued_task_id.is_none());
+
+        let subscription = streams
+            .subscribe(
+                StreamSubscribeParams {
+                    topics: vec!["task.log.line".to_string()],
+                    cursor: Some("0-0".to_string()),
+                    replay_limit: Some(100),
+                    filters: None,
+                },
+                "local",
+            )
+            .unwrap();
+        let replay = streams
+            .replay_for_subscription(&subscription.subscription_id)
+            .unwrap();
+        let task_a_lines: Vec<(String, String)> = replay
+            .events
+            .iter()
+            .filter(|event| event.resource.id == "task-a")
+            .map(|event| {
+                (
+                    event.payload["source"].as_str().unwrap().to_string(),
+                    event.payload["line"].as_str().unwrap().to_string(),
+                )
+            })
+            .collect();
+        assert!(task_a_lines.contains(&("stdout".to_string(), "working".to_string())));
+        assert!(task_a_lines.contains(&("stderr".to_string(), "backend warning".to_string())));
+    }
+}
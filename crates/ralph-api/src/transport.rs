@@ -35,6 +35,7 @@ pub fn router(runtime: RpcRuntime) -> Router {
         .route("/rpc/v1", post(rpc_handler))
         .route("/rpc/v1/capabilities", get(capabilities_handler))
         .route("/rpc/v1/stream", get(stream_handler))
+        .route("/hooks/human-response", post(human_response_handler))
         .with_state(AppState { runtime })
 }
 
@@ -108,6 +109,15 @@ async fn rpc_handler(
     (status, Json(payload))
 }
 
+async fn human_response_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> impl IntoResponse {
+    let (status, payload) = state.runtime.handle_human_response(&body, &headers);
+    (status, Json(payload))
+}
+
 async fn stream_handler(
     ws: WebSocketUpgrade,
     Query(query): Query<StreamQuery>,
@@ -162,6 +172,8 @@ async fn stream_connection(
         Ok(replay) => replay,
         Err(error) => {
             warn!(subscription_id, error = %error.message, "failed preparing replay batch");
+            let event = streams.error_event(&subscription_id, &error);
+            let _ = send_stream_event(&mut socket, &event).await;
             let _ = socket.close().await;
             return;
         }
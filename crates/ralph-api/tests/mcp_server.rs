@@ -8,6 +8,7 @@ use ralph_api::{ApiConfig, mcp::RalphMcpServer};
 fn test_config(workspace_root: std::path::PathBuf) -> ApiConfig {
     ApiConfig {
         workspace_root,
+        task_concurrency: 0,
         ..ApiConfig::default()
     }
 }
@@ -5,19 +5,23 @@ use tempfile::TempDir;
 use tokio::net::TcpListener;
 use tokio::sync::oneshot;
 
+use ralph_api::config::{ApiTokenConfig, TokenRole};
 use ralph_api::{ApiConfig, AuthMode, RpcRuntime, serve_with_listener};
+use ralph_webhook::signing::{SIGNATURE_HEADER, TIMESTAMP_HEADER, sign};
 
 struct TestServer {
     base_url: String,
     shutdown: Option<oneshot::Sender<()>>,
     join: tokio::task::JoinHandle<anyhow::Result<()>>,
-    _workspace: TempDir,
+    workspace: TempDir,
 }
 
 impl TestServer {
     async fn start(mut config: ApiConfig) -> Self {
         let workspace = tempfile::tempdir().expect("workspace tempdir should be created");
         config.workspace_root = workspace.path().to_path_buf();
+        // Queued tasks stay pending so tests can observe queue state.
+        config.task_concurrency = 0;
 
         let listener = TcpListener::bind("127.0.0.1:0")
             .await
@@ -39,7 +43,7 @@ impl TestServer {
             base_url: format!("http://{local_addr}"),
             shutdown: Some(shutdown_tx),
             join,
-            _workspace: workspace,
+            workspace,
         }
     }
 
@@ -142,6 +146,234 @@ async fn rejects_requests_when_token_auth_is_enabled() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn scoped_tokens_are_limited_to_allowed_methods() -> Result<()> {
+    let mut config = ApiConfig::default();
+    config.auth_mode = AuthMode::Token;
+    config.tokens = vec![
+        ApiTokenConfig {
+            name: "dashboard".to_string(),
+            token: "dashboard-token".to_string(),
+            role: TokenRole::ReadOnly,
+            methods: vec!["*".to_string()],
+        },
+        ApiTokenConfig {
+            name: "ci".to_string(),
+            token: "ci-token".to_string(),
+            role: TokenRole::Full,
+            methods: vec!["task.*".to_string()],
+        },
+    ];
+
+    let server = TestServer::start(config).await;
+    let client = Client::new();
+
+    let list = json!({
+        "apiVersion": "v1",
+        "id": "req-scope-1",
+        "method": "task.list",
+        "params": {}
+    });
+    let (status, _) = post_rpc(&client, &server, &list, Some("dashboard-token")).await?;
+    assert_eq!(status, 200);
+
+    let clear = json!({
+        "apiVersion": "v1",
+        "id": "req-scope-2",
+        "method": "task.clear",
+        "params": {},
+        "meta": { "idempotencyKey": "idem-scope-clear" }
+    });
+    let (status, payload) = post_rpc(&client, &server, &clear, Some("dashboard-token")).await?;
+    assert_eq!(status, 403);
+    assert_eq!(payload["error"]["code"], "FORBIDDEN");
+    assert_eq!(payload["error"]["details"]["principal"], "dashboard");
+
+    let (status, _) = post_rpc(&client, &server, &clear, Some("ci-token")).await?;
+    assert_eq!(status, 200);
+
+    let config_update = json!({
+        "apiVersion": "v1",
+        "id": "req-scope-3",
+        "method": "config.update",
+        "params": { "content": "cli:\n  backend: claude\n" },
+        "meta": { "idempotencyKey": "idem-scope-config" }
+    });
+    let (status, payload) = post_rpc(&client, &server, &config_update, Some("ci-token")).await?;
+    assert_eq!(status, 403);
+    assert_eq!(payload["error"]["code"], "FORBIDDEN");
+
+    server.stop().await;
+    Ok(())
+}
+
+#[tokio::test]
+async fn audit_log_records_mutations_by_principal() -> Result<()> {
+    let mut config = ApiConfig::default();
+    config.auth_mode = AuthMode::Token;
+    config.tokens = vec![
+        ApiTokenConfig {
+            name: "ops".to_string(),
+            token: "ops-token".to_string(),
+            role: TokenRole::Full,
+            methods: vec!["*".to_string()],
+        },
+        ApiTokenConfig {
+            name: "ci".to_string(),
+            token: "ci-token".to_string(),
+            role: TokenRole::Full,
+            methods: vec!["task.*".to_string()],
+        },
+    ];
+
+    let server = TestServer::start(config).await;
+    let client = Client::new();
+
+    let delete = json!({
+        "apiVersion": "v1",
+        "id": "req-audit-1",
+        "method": "task.delete",
+        "params": { "id": "task-missing" },
+        "meta": { "idempotencyKey": "idem-audit-delete" }
+    });
+    let (status, _) = post_rpc(&client, &server, &delete, Some("ops-token")).await?;
+    assert_ne!(status, 200);
+
+    let clear = json!({
+        "apiVersion": "v1",
+        "id": "req-audit-2",
+        "method": "task.clear",
+        "params": {},
+        "meta": { "idempotencyKey": "idem-audit-clear" }
+    });
+    let (status, _) = post_rpc(&client, &server, &clear, Some("ci-token")).await?;
+    assert_eq!(status, 200);
+    let (status, _) = post_rpc(&client, &server, &clear, Some("ci-token")).await?;
+    assert_eq!(status, 200);
+
+    let list = json!({
+        "apiVersion": "v1",
+        "id": "req-audit-3",
+        "method": "system.audit.list",
+        "params": { "resourceId": "task-missing" }
+    });
+    let (status, payload) = post_rpc(&client, &server, &list, Some("ops-token")).await?;
+    assert_eq!(status, 200);
+    let entries = payload["result"]["entries"].as_array().expect("entries");
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0]["principal"], "ops");
+    assert_eq!(entries[0]["method"], "task.delete");
+    assert_eq!(entries[0]["result"], "error");
+    assert_eq!(entries[0]["idempotencyKey"], "idem-audit-delete");
+    assert!(
+        entries[0]["paramsDigest"]
+            .as_str()
+            .is_some_and(|digest| digest.starts_with("sha256:"))
+    );
+
+    let list = json!({
+        "apiVersion": "v1",
+        "id": "req-audit-4",
+        "method": "system.audit.list",
+        "params": { "principal": "ci", "since": "2000-01-01T00:00:00Z" }
+    });
+    let (status, payload) = post_rpc(&client, &server, &list, Some("ops-token")).await?;
+    assert_eq!(status, 200);
+    let results: Vec<&str> = payload["result"]["entries"]
+        .as_array()
+        .expect("entries")
+        .iter()
+        .filter_map(|entry| entry["result"].as_str())
+        .collect();
+    assert_eq!(results, ["ok", "replayed"]);
+
+    server.stop().await;
+    Ok(())
+}
+
+#[tokio::test]
+async fn human_response_endpoint_verifies_signature_and_writes_event() -> Result<()> {
+    let config = ApiConfig {
+        webhook_secret: Some("hook-secret".to_string()),
+        ..ApiConfig::default()
+    };
+    let server = TestServer::start(config).await;
+    let client = Client::new();
+    let url = format!("{}/hooks/human-response", server.base_url);
+    let body = serde_json::to_vec(&json!({ "loopId": "main", "response": "use option B" }))?;
+    let timestamp = chrono::Utc::now().timestamp();
+
+    let response = client
+        .post(&url)
+        .header(TIMESTAMP_HEADER, timestamp.to_string())
+        .header(SIGNATURE_HEADER, sign("wrong-secret", timestamp, &body))
+        .body(body.clone())
+        .send()
+        .await?;
+    assert_eq!(response.status().as_u16(), 401);
+
+    let response = client
+        .post(&url)
+        .header(TIMESTAMP_HEADER, timestamp.to_string())
+        .header(SIGNATURE_HEADER, sign("hook-secret", timestamp, &body))
+        .body(body)
+        .send()
+        .await?;
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(response.json::<Value>().await?["accepted"], true);
+
+    let events = std::fs::read_to_string(server.workspace.path().join(".ralph/events.jsonl"))?;
+    let event: Value = serde_json::from_str(events.lines().next().expect("one event"))?;
+    assert_eq!(event["topic"], "human.response");
+    assert_eq!(event["payload"], "use option B");
+
+    server.stop().await;
+    Ok(())
+}
+
+#[tokio::test]
+async fn replays_stored_response_after_runtime_restart() -> Result<()> {
+    let workspace = tempfile::tempdir()?;
+    let config = ApiConfig {
+        workspace_root: workspace.path().to_path_buf(),
+        task_concurrency: 0,
+        ..ApiConfig::default()
+    };
+    let params = json!({
+        "id": "task-restart-1",
+        "title": "survives restart"
+    });
+
+    let runtime = RpcRuntime::new(config.clone())?;
+    let first = runtime
+        .invoke_method(
+            "req-restart-1",
+            "task.create",
+            params.clone(),
+            "local",
+            Some("idem-restart-1".to_string()),
+        )
+        .map_err(|error| anyhow::anyhow!(error.message))?;
+    drop(runtime);
+
+    // A fresh runtime would reject a second create for the same task id, so a
+    // successful identical result proves the response was replayed from disk.
+    let restarted = RpcRuntime::new(config)?;
+    let replayed = restarted
+        .invoke_method(
+            "req-restart-2",
+            "task.create",
+            params,
+            "local",
+            Some("idem-restart-1".to_string()),
+        )
+        .map_err(|error| anyhow::anyhow!(error.message))?;
+
+    assert_eq!(replayed, first);
+    assert_eq!(replayed["task"]["id"], "task-restart-1");
+    Ok(())
+}
+
 #[tokio::test]
 async fn deduplicates_mutating_requests_by_idempotency_key() -> Result<()> {
     let server = TestServer::start(ApiConfig::default()).await;
@@ -366,7 +366,8 @@ fn create_ralph_run_interceptor() -> Option<(TempDir, PathBuf)> {
 ///   - API returns HTTP 200 with `result.success = true`.
 ///   - `loop.status` shows `lastProcessedAt` was set (confirming the domain
 ///     completed the flow, not an early-return short-circuit).
-///   - The queue entry remains in `Queued` state because no actual merge ran.
+///   - The deterministic merge executor processed the entry: with no
+///     `ralph/<id>` branch to merge it lands in `NeedsReview` with the reason.
 #[cfg(unix)]
 #[tokio::test]
 async fn loop_process_real_ralph_flow_succeeds_with_queued_entry() -> Result<()> {
@@ -417,18 +418,78 @@ async fn loop_process_real_ralph_flow_succeeds_with_queued_entry() -> Result<()>
         "lastProcessedAt must be set after loop.process: {status_payload}"
     );
 
-    // Queue entry must still be Queued: the interceptor exited the spawned
-    // merge-ralph immediately, so no state transition occurred.
+    // The executor claimed the entry and, finding no loop branch to merge,
+    // recorded it for review instead of spawning merge-ralph.
     let entry = merge_queue
         .get_entry("loop-real-flow-1")?
         .expect("queue entry must still exist");
     assert_eq!(
         entry.state,
-        MergeState::Queued,
-        "entry should remain Queued after intercepted run"
+        MergeState::NeedsReview,
+        "entry should need review when its branch is missing"
+    );
+    assert!(
+        entry
+            .failure_reason
+            .as_deref()
+            .is_some_and(|reason| reason.contains("ralph/loop-real-flow-1")),
+        "failure reason should name the missing branch: {:?}",
+        entry.failure_reason
     );
 
     drop(_bin_dir);
     server.stop().await;
     Ok(())
 }
+
+#[tokio::test]
+async fn loop_merge_conflict_without_fallback_parks_entry_for_review() -> Result<()> {
+    let server = TestServer::start(ApiConfig::default()).await;
+    let client = Client::new();
+    let workspace = server.workspace_path();
+
+    init_git_repo(workspace)?;
+    fs::write(workspace.join(".gitignore"), ".ralph/\n.worktrees/\n")?;
+    fs::write(
+        workspace.join("ralph.yml"),
+        "merge:\n  llm_fallback: false\n",
+    )?;
+    run_git(workspace, &["add", ".gitignore", "ralph.yml"])?;
+    run_git(workspace, &["commit", "-m", "chore: config"])?;
+    run_git(workspace, &["checkout", "-b", "ralph/loop-conflict-1"])?;
+    fs::write(workspace.join("README.md"), "# Loop\n")?;
+    run_git(workspace, &["commit", "-am", "docs: loop readme"])?;
+    run_git(workspace, &["checkout", "main"])?;
+    fs::write(workspace.join("README.md"), "# Main\n")?;
+    run_git(workspace, &["commit", "-am", "docs: main readme"])?;
+
+    let merge_queue = MergeQueue::new(workspace);
+    merge_queue.enqueue_with_base("loop-conflict-1", "Conflicting loop", Some("main"), None)?;
+
+    let merge = rpc_request(
+        "req-loop-merge-conflict-1",
+        "loop.merge",
+        json!({ "id": "loop-conflict-1", "force": false }),
+        Some("idem-loop-merge-conflict-1"),
+    );
+    let (status, payload) = post_rpc(&client, &server, &merge).await?;
+
+    assert_eq!(status, 412, "{payload}");
+    assert_eq!(payload["error"]["code"], "PRECONDITION_FAILED");
+    let entry = merge_queue
+        .get_entry("loop-conflict-1")?
+        .expect("queue entry must still exist");
+    assert_eq!(entry.state, MergeState::NeedsReview);
+    assert!(
+        entry
+            .failure_reason
+            .as_deref()
+            .is_some_and(|reason| reason.contains("README.md")),
+        "failure reason should name the conflicted file: {:?}",
+        entry.failure_reason
+    );
+    assert_eq!(fs::read_to_string(workspace.join("README.md"))?, "# Main\n");
+
+    server.stop().await;
+    Ok(())
+}
@@ -26,6 +26,8 @@ impl TestServer {
     async fn start(mut config: ApiConfig) -> Self {
         let workspace = tempfile::tempdir().expect("workspace tempdir should be created");
         config.workspace_root = workspace.path().to_path_buf();
+        // Queued tasks stay pending so tests can observe queue state.
+        config.task_concurrency = 0;
 
         let listener = TcpListener::bind("127.0.0.1:0")
             .await
@@ -388,10 +390,11 @@ async fn replay_overflow_emits_backpressure_error_and_bounds_batch() -> Result<(
     for index in 0..8 {
         let request_id = format!("req-stream-overflow-update-{index}");
         let idempotency_key = format!("idem-stream-overflow-update-{index}");
+        let next_status = if index % 2 == 0 { "running" } else { "open" };
         let update = rpc_request(
             &request_id,
             "task.update",
-            json!({ "id": task_id, "status": format!("state-{index}") }),
+            json!({ "id": task_id, "status": next_status }),
             Some(&idempotency_key),
         );
         let (status, _) = post_rpc(&client, &server, &update).await?;
@@ -1,7 +1,7 @@
 use std::path::Path;
 
 use anyhow::Result;
-use ralph_core::{LoopEntry, LoopRegistry, MergeQueue};
+use ralph_core::{LoopEntry, LoopRegistry, MergeQueue, Task, TaskStatus, TaskStore};
 use reqwest::Client;
 use serde_json::{Value, json};
 use tempfile::TempDir;
@@ -21,6 +21,8 @@ impl TestServer {
     async fn start(mut config: ApiConfig) -> Self {
         let workspace = tempfile::tempdir().expect("workspace tempdir should be created");
         config.workspace_root = workspace.path().to_path_buf();
+        // Queued tasks stay pending so tests can observe queue state.
+        config.task_concurrency = 0;
 
         let listener = TcpListener::bind("127.0.0.1:0")
             .await
@@ -72,6 +74,15 @@ async fn post_rpc(client: &Client, server: &TestServer, body: &Value) -> Result<
     Ok((status, payload))
 }
 
+fn run_git(path: &Path, args: &[&str]) -> Result<()> {
+    let status = std::process::Command::new("git")
+        .args(args)
+        .current_dir(path)
+        .status()?;
+    anyhow::ensure!(status.success(), "git {:?} failed", args);
+    Ok(())
+}
+
 fn rpc_request(id: &str, method: &str, params: Value, idempotency_key: Option<&str>) -> Value {
     let mut request = json!({
         "apiVersion": "v1",
@@ -417,8 +428,21 @@ async fn loop_methods_and_trigger_merge_task_parity() -> Result<()> {
     let server = TestServer::start(ApiConfig::default()).await;
     let client = Client::new();
 
+    let workspace = server.workspace_path();
+    run_git(workspace, &["init", "-q", "--initial-branch=main"])?;
+    run_git(workspace, &["config", "user.email", "test@test.local"])?;
+    run_git(workspace, &["config", "user.name", "Test User"])?;
+    std::fs::write(workspace.join(".gitignore"), ".ralph/\n.worktrees/\n")?;
+    run_git(workspace, &["add", ".gitignore"])?;
+    run_git(workspace, &["commit", "-q", "-m", "Initial commit"])?;
+    run_git(workspace, &["checkout", "-q", "-b", "ralph/loop-queued-1"])?;
+    std::fs::write(workspace.join("feature.txt"), "feature\n")?;
+    run_git(workspace, &["add", "feature.txt"])?;
+    run_git(workspace, &["commit", "-q", "-m", "feat: loop work"])?;
+    run_git(workspace, &["checkout", "-q", "main"])?;
+
     let merge_queue = MergeQueue::new(server.workspace_path());
-    merge_queue.enqueue("loop-queued-1", "Queued loop prompt")?;
+    merge_queue.enqueue_with_base("loop-queued-1", "Queued loop prompt", Some("main"), None)?;
     merge_queue.enqueue("loop-review-1", "Needs review loop")?;
     merge_queue.mark_merging("loop-review-1", std::process::id())?;
     merge_queue.mark_needs_review("loop-review-1", "conflict in src/lib.rs")?;
@@ -473,6 +497,12 @@ async fn loop_methods_and_trigger_merge_task_parity() -> Result<()> {
     let (status, merge_payload) = post_rpc(&client, &server, &merge).await?;
     assert_eq!(status, 200);
     assert_eq!(merge_payload["result"]["success"], true);
+    // The executor merged the loop branch into its base.
+    run_git(
+        workspace,
+        &["merge-base", "--is-ancestor", "ralph/loop-queued-1", "main"],
+    )?;
+    assert!(workspace.join("feature.txt").exists());
 
     let list_non_terminal = rpc_request(
         "req-loop-list-2",
@@ -536,3 +566,190 @@ async fn loop_methods_and_trigger_merge_task_parity() -> Result<()> {
     server.stop().await;
     Ok(())
 }
+
+#[tokio::test]
+async fn loop_schedule_waits_on_parent_and_cancels_on_discard() -> Result<()> {
+    let server = TestServer::start(ApiConfig::default()).await;
+    let client = Client::new();
+
+    // loop.discard resolves worktrees through git.
+    std::process::Command::new("git")
+        .args(["init", "-q"])
+        .current_dir(server.workspace_path())
+        .status()?;
+    MergeQueue::new(server.workspace_path()).enqueue("loop-parent-1", "Parent loop")?;
+
+    let unknown_parent = rpc_request(
+        "req-loop-schedule-unknown",
+        "loop.schedule",
+        json!({ "after": "loop-missing", "prompt": "Follow up" }),
+        Some("idem-loop-schedule-unknown"),
+    );
+    let (_, unknown_payload) = post_rpc(&client, &server, &unknown_parent).await?;
+    assert_eq!(unknown_payload["error"]["code"], "LOOP_NOT_FOUND");
+
+    let schedule = rpc_request(
+        "req-loop-schedule-1",
+        "loop.schedule",
+        json!({ "after": "loop-parent-1", "prompt": "Follow up", "startOn": "merged" }),
+        Some("idem-loop-schedule-1"),
+    );
+    let (status, schedule_payload) = post_rpc(&client, &server, &schedule).await?;
+    assert_eq!(status, 200);
+    assert_eq!(schedule_payload["result"]["status"], "scheduled");
+    assert_eq!(schedule_payload["result"]["after"], "loop-parent-1");
+    assert_eq!(schedule_payload["result"]["startOn"], "merged");
+    let child_id = schedule_payload["result"]["id"]
+        .as_str()
+        .expect("scheduled loop id should be present")
+        .to_string();
+
+    let list = rpc_request(
+        "req-loop-schedule-list-1",
+        "loop.list",
+        json!({ "includeTerminal": false }),
+        None,
+    );
+    let (_, list_payload) = post_rpc(&client, &server, &list).await?;
+    assert!(
+        list_payload["result"]["loops"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|entry| entry["id"] == child_id.as_str()
+                && entry["status"] == "scheduled"
+                && entry["after"] == "loop-parent-1")
+    );
+
+    let discard_parent = rpc_request(
+        "req-loop-schedule-discard-1",
+        "loop.discard",
+        json!({ "id": "loop-parent-1" }),
+        Some("idem-loop-schedule-discard-1"),
+    );
+    let (status, _) = post_rpc(&client, &server, &discard_parent).await?;
+    assert_eq!(status, 200);
+
+    let list_all = rpc_request(
+        "req-loop-schedule-list-2",
+        "loop.list",
+        json!({ "includeTerminal": true }),
+        None,
+    );
+    let (_, list_all_payload) = post_rpc(&client, &server, &list_all).await?;
+    assert!(
+        list_all_payload["result"]["loops"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|entry| entry["id"] == child_id.as_str() && entry["status"] == "cancelled")
+    );
+
+    server.stop().await;
+    Ok(())
+}
+
+#[test]
+fn tasks_are_shared_with_the_core_store_and_migrated_from_the_api_snapshot() -> Result<()> {
+    let workspace = tempfile::tempdir()?;
+    let legacy_path = workspace.path().join(".ralph/api/tasks-v1.json");
+    std::fs::create_dir_all(legacy_path.parent().unwrap())?;
+    std::fs::write(
+        &legacy_path,
+        serde_json::to_string(&json!({
+            "tasks": [
+                {
+                    "id": "task-legacy-pending",
+                    "title": "Queued before the upgrade",
+                    "status": "pending",
+                    "priority": 2,
+                    "queuedTaskId": "queued-1-0001",
+                    "createdAt": "2026-01-01T00:00:00Z",
+                    "updatedAt": "2026-01-01T00:00:01Z"
+                },
+                {
+                    "id": "task-legacy-closed",
+                    "title": "Done and archived",
+                    "status": "closed",
+                    "priority": 3,
+                    "archivedAt": "2026-01-02T00:00:00Z",
+                    "createdAt": "2026-01-01T00:00:00Z",
+                    "updatedAt": "2026-01-02T00:00:00Z",
+                    "completedAt": "2026-01-01T12:00:00Z"
+                }
+            ],
+            "queueCounter": 1
+        }))?,
+    )?;
+
+    let config = ApiConfig {
+        workspace_root: workspace.path().to_path_buf(),
+        task_concurrency: 0,
+        ..ApiConfig::default()
+    };
+    let runtime = RpcRuntime::new(config)?;
+    assert!(!legacy_path.exists());
+
+    let store_path = workspace.path().join(".ralph/agent/tasks.jsonl");
+    let store = TaskStore::load(&store_path)?;
+    let pending = store
+        .get("task-legacy-pending")
+        .expect("pending task migrated");
+    assert_eq!(pending.status, TaskStatus::Open);
+    assert_eq!(pending.queued.as_deref(), Some("queued-1-0001"));
+    let closed = store
+        .get("task-legacy-closed")
+        .expect("closed task migrated");
+    assert_eq!(closed.status, TaskStatus::Closed);
+    assert!(closed.archived.is_some());
+
+    // A task added by an agent is visible through the API.
+    let mut store = TaskStore::load(&store_path)?;
+    let agent_task_id = store.with_exclusive_lock(|store| {
+        store
+            .add(Task::new("Written by an agent".to_string(), 1))
+            .id
+            .clone()
+    })?;
+
+    let listed = runtime
+        .invoke_method(
+            "req-shared-list-1",
+            "task.list",
+            json!({ "includeArchived": true }),
+            "local",
+            None,
+        )
+        .map_err(|error| anyhow::anyhow!(error.message))?;
+    let statuses: Vec<(String, String)> = listed["tasks"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|task| {
+            (
+                task["id"].as_str().unwrap().to_string(),
+                task["status"].as_str().unwrap().to_string(),
+            )
+        })
+        .collect();
+    assert!(statuses.contains(&("task-legacy-pending".to_string(), "pending".to_string())));
+    assert!(statuses.contains(&("task-legacy-closed".to_string(), "closed".to_string())));
+    assert!(statuses.contains(&(agent_task_id, "open".to_string())));
+
+    // A task created through the API is visible to agents.
+    runtime
+        .invoke_method(
+            "req-shared-create-1",
+            "task.create",
+            json!({ "id": "task-from-api", "title": "From the dashboard", "autoExecute": false }),
+            "local",
+            Some("idem-shared-create-1".to_string()),
+        )
+        .map_err(|error| anyhow::anyhow!(error.message))?;
+    let store = TaskStore::load(&store_path)?;
+    assert_eq!(
+        store.get("task-from-api").map(|task| task.status),
+        Some(TaskStatus::Open)
+    );
+    Ok(())
+}
@@ -18,6 +18,8 @@ impl TestServer {
     async fn start(mut config: ApiConfig) -> Self {
         let workspace = tempfile::tempdir().expect("workspace tempdir should be created");
         config.workspace_root = workspace.path().to_path_buf();
+        // Queued tasks stay pending so tests can observe queue state.
+        config.task_concurrency = 0;
 
         let listener = TcpListener::bind("127.0.0.1:0")
             .await
@@ -343,6 +343,8 @@ async fn run_task_loop(
     config.event_loop.max_iterations = task.max_iterations;
     config.event_loop.completion_promise = task.completion_promise.clone();
     config.event_loop.max_runtime_seconds = task.timeout_seconds;
+    config.core.workspace_root = workspace.path().to_path_buf();
+    config.hooks = task.hooks.clone();
 
     // Auto-detect backend
     let priority = config.get_agent_priority();
@@ -364,6 +366,7 @@ async fn run_task_loop(
 
     // Initialize event loop
     let mut event_loop = EventLoop::new(config.clone());
+    event_loop.set_hook_loop_id(&task.name);
     event_loop.initialize(&prompt_content);
 
     // Create CLI executor
@@ -409,18 +412,22 @@ async fn run_task_loop(
     const MAX_FALLBACK_ATTEMPTS: u32 = 3;
 
     loop {
-        // Check termination before execution
+        // Wait out any suspended hook, then check termination before execution
+        event_loop.await_hook_resume().await;
         if let Some(reason) = event_loop.check_termination() {
             termination_reason = reason;
             break;
         }
 
+        let iteration = event_loop.state().iteration + 1;
+
         // Get next hat to execute, with fallback recovery if no pending events
-        let hat_id = match event_loop.next_hat() {
+        let hat_id = match event_loop.next_hat().cloned() {
             Some(id) => {
                 consecutive_fallbacks = 0;
-                id.clone()
+                id
             }
+            None if event_loop.is_hook_suspended() => continue,
             None => {
                 // No pending events - try to recover by injecting a fallback event
                 consecutive_fallbacks += 1;
@@ -445,7 +452,6 @@ async fn run_task_loop(
             }
         };
 
-        let iteration = event_loop.state().iteration + 1;
         info!("Task '{}' iteration {}", task.name, iteration);
 
         // Build prompt for this hat
@@ -491,6 +497,19 @@ async fn run_task_loop(
             break;
         }
 
+        // Read events the agent emitted during this iteration
+        if let Err(e) = event_loop.await_events_from_jsonl().await {
+            warn!(error = %e, "Failed to read events from JSONL");
+        }
+
+        if let Some(reason) = event_loop
+            .check_cancellation_event()
+            .or_else(|| event_loop.check_completion_event())
+        {
+            termination_reason = reason;
+            break;
+        }
+
         // Precheck validation: Warn if no pending events after processing output
         if !event_loop.has_pending_events() {
             let expected = event_loop.get_hat_publishes(&hat_id);
@@ -502,6 +521,8 @@ async fn run_task_loop(
         }
     }
 
+    let (termination_reason, _) = event_loop.publish_terminate_event(termination_reason).await;
+
     // Restore original directory
     std::env::set_current_dir(original_dir)?;
 
@@ -533,6 +554,7 @@ fn format_termination_reason(reason: &TerminationReason) -> String {
         TerminationReason::RestartRequested => "RestartRequested".to_string(),
         TerminationReason::WorkspaceGone => "WorkspaceGone".to_string(),
         TerminationReason::Cancelled => "Cancelled".to_string(),
+        TerminationReason::HookBlocked => "HookBlocked".to_string(),
     }
 }
 
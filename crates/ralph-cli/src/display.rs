@@ -120,6 +120,7 @@ pub fn print_termination(
         TerminationReason::RestartRequested => (CYAN, "↻", "Restarting by human request"),
         TerminationReason::WorkspaceGone => (RED, "?", "Workspace directory removed"),
         TerminationReason::Cancelled => (CYAN, "⏹", "Cancelled gracefully"),
+        TerminationReason::HookBlocked => (RED, "?", "Blocked by a lifecycle hook"),
     };
 
     let separator = "-".repeat(58);
@@ -601,20 +601,29 @@ fn print_check_line(check: &CheckResult, name_width: usize, use_colors: bool) {
 mod tests {
     use super::*;
     use ralph_core::HatConfig;
+    use std::collections::HashMap;
 
     fn base_hat(name: &str, backend: Option<HatBackend>) -> HatConfig {
         HatConfig {
             name: name.to_string(),
             description: Some("Test hat".to_string()),
             triggers: vec!["work.start".to_string()],
+            trigger_conditions: HashMap::new(),
             publishes: vec![],
             instructions: String::new(),
             extra_instructions: vec![],
+            failover: None,
             backend_args: None,
             backend,
             default_publishes: None,
             max_activations: None,
+            max_cost_usd: None,
+            max_tokens: None,
             disallowed_tools: vec![],
+            quality: None,
+            retry: None,
+            subloop: None,
+            fan_out: None,
         }
     }
 
@@ -14,7 +14,8 @@ use anyhow::{Context, Result};
 use clap::{Parser, Subcommand, ValueEnum};
 use indicatif::{ProgressBar, ProgressStyle};
 use ralph_adapters::{CliBackend, detect_backend_default};
-use ralph_core::{HatRegistry, RalphConfig, truncate_with_ellipsis};
+use ralph_core::{HatRegistry, IssueSeverity, RalphConfig, TopologyReport, truncate_with_ellipsis};
+use ralph_proto::{Hat, Topic};
 use std::collections::HashSet;
 use std::io::Write;
 use std::process::{Command, Stdio};
@@ -30,7 +31,11 @@ pub struct HatsArgs {
 #[derive(Subcommand, Debug)]
 pub enum HatsCommands {
     /// Validate hat topology and report issues
-    Validate,
+    Validate {
+        /// Output format (human, json)
+        #[arg(long, default_value = "human")]
+        format: ValidateFormat,
+    },
     /// Display hat topology graph
     Graph {
         /// Output format (unicode, ascii, compact, mermaid)
@@ -70,6 +75,13 @@ pub enum ListFormat {
     Json,
 }
 
+#[derive(ValueEnum, Clone, Debug, Default)]
+pub enum ValidateFormat {
+    #[default]
+    Human,
+    Json,
+}
+
 #[derive(Parser, Debug)]
 pub struct ShowArgs {
     /// Name of the hat to show (ID or display name)
@@ -101,7 +113,12 @@ pub async fn execute(
         Some(HatsCommands::Show(show_args)) => {
             show_hat(&mut stdout, &registry, &show_args.name, use_colors)
         }
-        Some(HatsCommands::Validate) => validate_hats(&mut stdout, &config, &registry, use_colors),
+        Some(HatsCommands::Validate {
+            format: ValidateFormat::Human,
+        }) => validate_hats(&mut stdout, &config, &registry, use_colors),
+        Some(HatsCommands::Validate {
+            format: ValidateFormat::Json,
+        }) => validate_hats_json(&mut stdout, &config, &registry),
         Some(HatsCommands::Graph { format, backend }) => {
             graph_hats(&mut stdout, &config, &registry, format, backend.as_deref())
         }
@@ -195,44 +212,28 @@ fn validate_hats<W: Write>(
         }
     }
 
-    // 2. Orphan event detection (published but no subscribers)
-    for hat in registry.all() {
-        for pub_event in &hat.publishes {
-            let topic = pub_event.as_str();
-            // Ignore loop completion promise
-            if topic == config.event_loop.completion_promise {
-                continue;
-            }
-            // Ignore if Ralph subscribes (task.start, etc - though Ralph usually PUBLISHES task.start)
-            // Ralph conceptually subscribes to everything as fallback, but we want to warn if no SPECIFIC hat handles it.
-            if !registry.has_subscriber(topic) {
-                print_check(
-                    writer,
-                    CheckResult::Warn,
-                    &format!(
-                        "Event '{}' published by '{}' has no hat subscribers",
-                        topic, hat.name
-                    ),
-                    use_colors,
-                )?;
+    // 2. Static topology analysis (reachability, orphan events, closed cycles)
+    let report = TopologyReport::analyze(config, registry);
+    for issue in &report.issues {
+        let result = match issue.severity {
+            IssueSeverity::Warning => {
                 warnings += 1;
+                CheckResult::Warn
             }
-        }
-    }
-
-    // 3. Dead end detection
-    let mut dead_ends = 0;
-    for hat in registry.all() {
-        if hat.publishes.is_empty() {
-            // It's okay to be a dead end if it's the Summarizer (which outputs completion promise via stdout/file, not event)
-            // But usually they publish something.
-            // Just info.
-            // print_check(CheckResult::Ok, &format!("Hat '{}' is a dead end (publishes nothing)", hat.name), use_colors);
-            dead_ends += 1;
-        }
+            IssueSeverity::Error => {
+                errors += 1;
+                CheckResult::Error
+            }
+        };
+        print_check(writer, result, &issue.message, use_colors)?;
     }
-    if dead_ends == 0 {
-        print_check(writer, CheckResult::Ok, "No dead-end hats", use_colors)?;
+    if report.issues.is_empty() {
+        print_check(
+            writer,
+            CheckResult::Ok,
+            "All hats reachable and able to complete",
+            use_colors,
+        )?;
     }
 
     writeln!(writer)?;
@@ -252,6 +253,23 @@ fn validate_hats<W: Write>(
     Ok(())
 }
 
+fn validate_hats_json<W: Write>(
+    writer: &mut W,
+    config: &RalphConfig,
+    registry: &HatRegistry,
+) -> Result<()> {
+    let report = TopologyReport::analyze(config, registry);
+    serde_json::to_writer_pretty(&mut *writer, &report)?;
+    writeln!(writer)?;
+    if report.has_errors() {
+        return Err(anyhow::anyhow!(
+            "Validation failed with {} errors",
+            report.errors
+        ));
+    }
+    Ok(())
+}
+
 enum CheckResult {
     Ok,
     Warn,
@@ -456,7 +474,7 @@ fn build_diagram_prompt(registry: &HatRegistry) -> String {
             prompt.push_str(&format!(
                 "- Ralph → {} (triggers on: {})\n",
                 hat.name,
-                sub.as_str()
+                trigger_label(hat, sub)
             ));
         }
     }
@@ -488,7 +506,7 @@ fn build_diagram_prompt(registry: &HatRegistry) -> String {
                         "- {} → {} (via event: {})\n",
                         source.name,
                         target.name,
-                        pub_event.as_str()
+                        trigger_label(target, pub_event)
                     ));
                 }
             }
@@ -549,7 +567,11 @@ fn generate_compact_graph(registry: &HatRegistry) -> String {
         }
 
         for subscription in &hat.subscriptions {
-            output.push_str(&format!("    {} <= {}\n", hat.name, subscription.as_str()));
+            output.push_str(&format!(
+                "    {} <= {}\n",
+                hat.name,
+                trigger_label(hat, subscription)
+            ));
         }
     }
 
@@ -578,7 +600,11 @@ fn generate_mermaid_string(registry: &HatRegistry) -> String {
     for hat in registry.all() {
         let node_id = sanitize_id(&hat.name);
         for sub in &hat.subscriptions {
-            output.push_str(&format!("    Ralph -->|{}| {}\n", sub.as_str(), node_id));
+            output.push_str(&format!(
+                "    Ralph -->|{}| {}\n",
+                mermaid_label(&trigger_label(hat, sub)),
+                node_id
+            ));
         }
     }
 
@@ -613,7 +639,7 @@ fn generate_mermaid_string(registry: &HatRegistry) -> String {
                     output.push_str(&format!(
                         "    {} -.->|{}| {}\n",
                         source_id,
-                        pub_event.as_str(),
+                        mermaid_label(&trigger_label(target, pub_event)),
                         target_id
                     ));
                 }
@@ -628,6 +654,23 @@ fn sanitize_id(name: &str) -> String {
     name.chars().filter(|c| c.is_alphanumeric()).collect()
 }
 
+/// Formats a subscription, appending its payload condition in brackets.
+fn trigger_label(hat: &Hat, subscription: &Topic) -> String {
+    match hat.condition_for(subscription) {
+        Some(condition) => format!("{} [{}]", subscription.as_str(), condition),
+        None => subscription.as_str().to_string(),
+    }
+}
+
+/// Quotes a Mermaid edge label when it contains characters Mermaid treats as syntax.
+fn mermaid_label(label: &str) -> String {
+    if label.contains(['|', '[', ']', '"', '(', ')']) {
+        format!("\"{}\"", label.replace('"', "#quot;"))
+    } else {
+        label.to_string()
+    }
+}
+
 fn show_hat<W: Write>(
     writer: &mut W,
     registry: &HatRegistry,
@@ -659,7 +702,7 @@ fn show_hat<W: Write>(
         writeln!(writer, "  (none)")?;
     } else {
         for trigger in &hat.subscriptions {
-            writeln!(writer, "  - {}", trigger.as_str())?;
+            writeln!(writer, "  - {}", trigger_label(hat, trigger))?;
         }
     }
 
@@ -685,7 +728,6 @@ fn show_hat<W: Write>(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use ralph_proto::Hat;
 
     fn mock_hat(name: &str, subs: &[&str], pubs: &[&str]) -> Hat {
         let mut hat = Hat::new(sanitize_id(name), name);
@@ -767,6 +809,26 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_graph_hats_shows_trigger_conditions() {
+        let mut registry = HatRegistry::new();
+        registry.register(mock_hat("Reviewer", &["review.start"], &["review.done"]));
+        registry.register(mock_hat("Fixer", &[], &["review.start"]).subscribe_when(
+            "review.done",
+            ralph_proto::PayloadCondition::parse("$.verdict == reject").unwrap(),
+        ));
+
+        let config = RalphConfig::default();
+        let mut buf = Vec::new();
+        graph_hats(&mut buf, &config, &registry, GraphFormat::Compact, None).unwrap();
+        let output = String::from_utf8(buf).unwrap();
+        assert!(output.contains("Fixer <= review.done [$.verdict == reject]"));
+
+        let mermaid = generate_mermaid_string(&registry);
+        assert!(mermaid.contains("Ralph -->|\"review.done [$.verdict == reject]\"| Fixer"));
+        assert!(mermaid.contains("Reviewer -.->|\"review.done [$.verdict == reject]\"| Fixer"));
+    }
+
     #[test]
     #[ignore = "requires live AI backend"]
     fn test_graph_hats_ascii() {
@@ -869,6 +931,44 @@ mod tests {
         assert!(output.contains("No dead-end hats") || output.contains("Result: Valid"));
     }
 
+    #[test]
+    fn test_validate_hats_closed_cycle_fails() {
+        let mut registry = HatRegistry::new();
+        registry.register(mock_hat(
+            "Builder",
+            &["build.task", "review.rejected"],
+            &["review.ready"],
+        ));
+        registry.register(mock_hat("Critic", &["review.ready"], &["review.rejected"]));
+
+        let config = RalphConfig::default();
+        let mut buf = Vec::new();
+
+        let result = validate_hats(&mut buf, &config, &registry, false);
+        let output = String::from_utf8(buf).unwrap();
+
+        assert!(result.is_err());
+        assert!(output.contains("[err] Hats 'Builder' -> 'Critic' hand off only to each other"));
+        assert!(output.contains("Result: Invalid (2 errors, 0 warnings)"));
+    }
+
+    #[test]
+    fn test_validate_hats_json() {
+        let mut registry = HatRegistry::new();
+        registry.register(mock_hat("Builder", &["build.task"], &["build.done"]));
+
+        let config = RalphConfig::default();
+        let mut buf = Vec::new();
+
+        validate_hats_json(&mut buf, &config, &registry).unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&buf).unwrap();
+
+        assert_eq!(json["entry"], "task.start");
+        assert_eq!(json["warnings"], 1);
+        assert_eq!(json["issues"][0]["kind"], "unconsumed_topic");
+        assert_eq!(json["issues"][0]["topic"], "build.done");
+    }
+
     #[test]
     fn test_list_hats_json() {
         let mut registry = HatRegistry::new();
@@ -8,16 +8,16 @@ use anyhow::{Context, Result};
 use ralph_adapters::{
     AcpExecutor, ClaudeStreamEvent, ClaudeStreamParser, CliBackend, CliExecutor,
     ConsoleStreamHandler, ContentBlock, JsonRpcStreamHandler, OutputFormat as BackendOutputFormat,
-    PiAssistantEvent, PiStreamEvent, PiStreamParser, PrettyStreamHandler, PtyConfig, PtyExecutor,
-    QuietStreamHandler, TuiStreamHandler,
+    PiAssistantEvent, PiStreamEvent, PiStreamParser, PiTurnMessage, PrettyStreamHandler, PtyConfig,
+    PtyExecutor, QuietStreamHandler, StreamHandler, TuiStreamHandler,
 };
-use ralph_core::diagnostics::{HookDisposition, HookRunTelemetryEntry};
 use ralph_core::{
-    CompletionAction, EventLogger, EventLoop, EventParser, EventRecord, HatRegistry, HookEngine,
-    HookExecutor, HookExecutorContract, HookMutationConfig, HookOnError, HookPayloadBuilderInput,
-    HookPayloadContextInput, HookPhaseEvent, HookRunRequest, HookRunResult, HookSuspendMode,
-    LoopCompletionHandler, LoopContext, LoopHistory, LoopRegistry, MergeQueue, RalphConfig, Record,
-    SessionRecorder, SummaryWriter, SuspendStateRecord, SuspendStateStore, TerminationReason,
+    BranchOutcome, BranchStatus, CompletionAction, EventLogger, EventLoop, EventParser,
+    EventRecord, FailureClass, FanOutConfig, FanOutIsolation, HatRegistry, HatUsage,
+    LoopCompletionHandler, LoopContext, LoopHistory, LoopRegistry, LoopSchedule, MergeExecutor,
+    MergeExecutorMode, MergeQueue, MergeReport, RalphConfig, Record, ScheduleAction,
+    SessionRecorder, SubloopConfig, SummaryWriter, TerminationReason, join_payload,
+    spawn_scheduled_loop,
 };
 use ralph_proto::{Event, GuidanceTarget, HatId, RpcEvent, RpcState, RpcTaskCounts};
 use ralph_tui::Tui;
@@ -45,6 +45,19 @@ pub(crate) struct ExecutionOutcome {
     pub output_tokens: u64,
     pub cache_read_tokens: u64,
     pub cache_write_tokens: u64,
+    /// Why the backend failed, when the failure is eligible for backend failover.
+    pub failure: Option<FailureClass>,
+}
+
+impl ExecutionOutcome {
+    /// Adds another attempt's cost and token usage to this outcome.
+    fn absorb_usage(&mut self, other: &ExecutionOutcome) {
+        self.total_cost_usd += other.total_cost_usd;
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+        self.cache_read_tokens += other.cache_read_tokens;
+        self.cache_write_tokens += other.cache_write_tokens;
+    }
 }
 
 /// Shared atomic state written by the main loop and read by the RPC `get_state` handler.
@@ -96,6 +109,7 @@ fn resolve_loop_id(
 ///   (equivalent to `--no-auto-merge`). If `None`, uses `config.features.auto_merge`.
 /// * `resume_loop_id` - Explicit loop ID to use when resuming (`--loop-id`).
 ///   If `None` and `resume` is true, reuses the existing `current-loop-id` marker.
+#[allow(clippy::too_many_arguments)]
 pub async fn run_loop_impl(
     config: RalphConfig,
     color_mode: ColorMode,
@@ -109,6 +123,52 @@ pub async fn run_loop_impl(
     auto_merge_override: Option<bool>,
     resume_loop_id: Option<String>,
 ) -> Result<TerminationReason> {
+    run_loop(
+        config,
+        color_mode,
+        resume,
+        enable_tui,
+        enable_rpc,
+        verbosity,
+        record_session,
+        loop_context,
+        custom_args,
+        auto_merge_override,
+        resume_loop_id,
+        None,
+    )
+    .await
+}
+
+/// Output sinks a sub-loop borrows from its parent loop.
+///
+/// When the parent renders to the TUI or speaks RPC on stdout, the child loop
+/// streams its agent output into the parent's current TUI iteration or RPC
+/// stream and skips its own console output.
+#[derive(Clone)]
+struct ParentOutput {
+    tui_lines: Option<Arc<std::sync::Mutex<Vec<ratatui::text::Line<'static>>>>>,
+    rpc_stdout: Option<Arc<std::sync::Mutex<std::io::Stdout>>>,
+}
+
+/// [`run_loop_impl`], optionally embedded in a parent loop's output.
+#[allow(clippy::too_many_arguments)]
+async fn run_loop(
+    config: RalphConfig,
+    color_mode: ColorMode,
+    resume: bool,
+    enable_tui: bool,
+    enable_rpc: bool,
+    verbosity: Verbosity,
+    record_session: Option<PathBuf>,
+    loop_context: Option<LoopContext>,
+    custom_args: Vec<String>,
+    auto_merge_override: Option<bool>,
+    resume_loop_id: Option<String>,
+    parent_output: Option<ParentOutput>,
+) -> Result<TerminationReason> {
+    // Embedded sub-loops must not write to the terminal the parent owns.
+    let embedded = parent_output.is_some();
     // Set up process group leadership per spec
     // "The orchestrator must run as a process group leader"
     process_management::setup_process_group();
@@ -118,7 +178,8 @@ pub async fn run_loop_impl(
     // Determine effective execution mode (with fallback logic)
     // Per spec: Claude backend requires PTY mode to avoid hangs
     // TUI mode is observation-only - uses streaming mode, not interactive
-    let interactive_requested = config.cli.default_mode == "interactive" && !enable_tui;
+    let interactive_requested =
+        config.cli.default_mode == "interactive" && !enable_tui && !embedded;
     let user_interactive = if interactive_requested {
         if stdout().is_terminal() {
             true
@@ -132,7 +193,8 @@ pub async fn run_loop_impl(
     // PTY is required for TUI/RPC observation and true interactive sessions.
     // Headless `ralph run --no-tui` should use CliExecutor so backends get their
     // non-interactive prompt forms (for example `claude -p` or `codex exec`).
-    let use_pty = enable_tui || enable_rpc || user_interactive;
+    // Embedded sub-loops stream through the parent's TUI/RPC handlers, which need PTY.
+    let use_pty = enable_tui || enable_rpc || user_interactive || embedded;
 
     // Set up interrupt channel for signal handling
     // Per spec:
@@ -177,9 +239,18 @@ pub async fn run_loop_impl(
         let run_id = chrono::Utc::now().format("%Y%m%d-%H%M%S").to_string();
         // Use relative path in marker file for portability across agents
         // The actual file is at ctx.ralph_dir()/events-{run_id}.jsonl
-        let relative_events_path = format!(".ralph/events-{}.jsonl", run_id);
+        // Sub-loops share the parent's .ralph directory, so they keep their events
+        // next to their own state instead of racing the parent's file name.
+        let relative_events_path = if ctx.is_subloop() {
+            format!(".ralph/subloops/{}/events-{}.jsonl", loop_id, run_id)
+        } else {
+            format!(".ralph/events-{}.jsonl", run_id)
+        };
 
         fs::create_dir_all(ctx.ralph_dir()).context("Failed to create .ralph directory")?;
+        if let Some(dir) = ctx.subloop_dir() {
+            fs::create_dir_all(dir).context("Failed to create sub-loop directory")?;
+        }
         fs::write(ctx.current_events_marker(), &relative_events_path)
             .context("Failed to write current-events marker file")?;
 
@@ -214,70 +285,14 @@ pub async fn run_loop_impl(
     // Capture the robot service shutdown flag so signal handlers can interrupt wait_for_response()
     let robot_shutdown = event_loop.robot_shutdown_flag();
 
-    let hooks_dispatch_enabled = config.hooks.enabled && !config.hooks.events.is_empty();
-    let hook_engine = HookEngine::new(&config.hooks);
-    let hook_executor = HookExecutor::new();
-    let suspend_state_store = SuspendStateStore::new(ctx.workspace());
-    let mut accumulated_hook_metadata = serde_json::Map::new();
-
-    let pre_loop_start_outcomes = dispatch_phase_event_hooks(
-        &event_loop,
-        hooks_dispatch_enabled,
-        &loop_id,
-        &hook_engine,
-        &hook_executor,
-        HookPhaseEvent::PreLoopStart,
-        build_loop_start_payload_input(
-            &loop_id,
-            &ctx,
-            config.event_loop.max_iterations,
-            event_loop.state().iteration,
-            None,
-            &accumulated_hook_metadata,
-        ),
-    );
-    merge_accumulated_hook_metadata_from_outcomes(
-        &mut accumulated_hook_metadata,
-        &pre_loop_start_outcomes,
-    );
-    fail_if_blocking_loop_start_outcomes(&pre_loop_start_outcomes)?;
-    let mut pending_suspend_termination_reason =
-        wait_for_resume_if_suspended(&pre_loop_start_outcomes, &loop_id, &suspend_state_store)
-            .await?;
-
-    if pending_suspend_termination_reason.is_none() {
-        // For resume mode, we initialize with a different event topic
-        // This tells the planner to read existing scratchpad rather than creating a new one
-        if resume {
-            event_loop.initialize_resume(&prompt_content);
-        } else {
-            event_loop.initialize(&prompt_content);
-        }
+    event_loop.set_hook_loop_id(&loop_id);
 
-        let post_loop_start_outcomes = dispatch_phase_event_hooks(
-            &event_loop,
-            hooks_dispatch_enabled,
-            &loop_id,
-            &hook_engine,
-            &hook_executor,
-            HookPhaseEvent::PostLoopStart,
-            build_loop_start_payload_input(
-                &loop_id,
-                &ctx,
-                config.event_loop.max_iterations,
-                event_loop.state().iteration,
-                Some(event_loop.get_active_hat_id().as_str().to_string()),
-                &accumulated_hook_metadata,
-            ),
-        );
-        merge_accumulated_hook_metadata_from_outcomes(
-            &mut accumulated_hook_metadata,
-            &post_loop_start_outcomes,
-        );
-        fail_if_blocking_loop_start_outcomes(&post_loop_start_outcomes)?;
-        pending_suspend_termination_reason =
-            wait_for_resume_if_suspended(&post_loop_start_outcomes, &loop_id, &suspend_state_store)
-                .await?;
+    // For resume mode, we initialize with a different event topic
+    // This tells the planner to read existing scratchpad rather than creating a new one
+    if resume {
+        event_loop.initialize_resume(&prompt_content);
+    } else {
+        event_loop.initialize(&prompt_content);
     }
 
     // Set up session recording if requested
@@ -392,7 +407,9 @@ pub async fn run_loop_impl(
     let rpc_stdout: Option<Arc<std::sync::Mutex<std::io::Stdout>>> = if enable_rpc {
         Some(Arc::new(std::sync::Mutex::new(std::io::stdout())))
     } else {
-        None
+        parent_output
+            .as_ref()
+            .and_then(|output| output.rpc_stdout.clone())
     };
 
     // RPC mode: spawn stdin reader and stdout emitter tasks
@@ -623,7 +640,11 @@ pub async fn run_loop_impl(
 
     // Detect merge loop on startup via RALPH_MERGE_LOOP_ID env var
     // Per spec: If set, mark entry as "merging" with current PID
-    let merge_loop_id: Option<String> = std::env::var("RALPH_MERGE_LOOP_ID").ok();
+    let merge_loop_id: Option<String> = if ctx.is_subloop() {
+        None
+    } else {
+        std::env::var("RALPH_MERGE_LOOP_ID").ok()
+    };
     if let Some(ref loop_id) = merge_loop_id {
         let repo_root = loop_context
             .as_ref()
@@ -658,7 +679,10 @@ pub async fn run_loop_impl(
                               auto_merge: bool,
                               prompt: &str| {
         // Per spec: Write summary file on termination
-        let summary_writer = SummaryWriter::default();
+        let summary_writer = match context {
+            Some(ctx) if ctx.is_subloop() => SummaryWriter::new(ctx.summary_path()),
+            _ => SummaryWriter::default(),
+        };
         let scratchpad_path = std::path::Path::new(scratchpad);
         let scratchpad_opt = if scratchpad_path.exists() {
             Some(scratchpad_path)
@@ -690,6 +714,7 @@ pub async fn run_loop_impl(
                 TerminationReason::RestartRequested => "restart_requested",
                 TerminationReason::WorkspaceGone => "workspace_gone",
                 TerminationReason::Cancelled => "cancelled",
+                TerminationReason::HookBlocked => "hook_blocked",
             };
 
             if matches!(reason, TerminationReason::Interrupted) {
@@ -761,6 +786,7 @@ pub async fn run_loop_impl(
                     TerminationReason::RestartRequested => "restart requested",
                     TerminationReason::WorkspaceGone => "workspace directory removed",
                     TerminationReason::Cancelled => "cancelled by human",
+                    TerminationReason::HookBlocked => "blocked by a lifecycle hook",
                 };
                 if let Err(e) = queue.mark_needs_review(loop_id, reason_str) {
                     warn!(loop_id = %loop_id, error = %e, "Failed to mark merge as needs-review");
@@ -772,7 +798,10 @@ pub async fn run_loop_impl(
 
         // Handle completion for all loops (landing + merge queue for worktrees)
         // Per spec: merge loops do NOT enqueue themselves, even if run in worktree context
-        if let Some(ctx) = context {
+        // Sub-loops leave landing, merging and registry bookkeeping to their parent.
+        if let Some(ctx) = context
+            && !ctx.is_subloop()
+        {
             if merge_loop_id.is_none() && matches!(reason, TerminationReason::CompletionPromise) {
                 let handler = LoopCompletionHandler::new(auto_merge);
                 match handler.handle_completion(ctx, prompt) {
@@ -828,7 +857,7 @@ pub async fn run_loop_impl(
 
             // Handle merge queue processing for primary loop completion
             if ctx.is_primary() && matches!(reason, TerminationReason::CompletionPromise) {
-                process_pending_merges(ctx.repo_root());
+                process_pending_merges(ctx.repo_root(), config.merge.executor);
             }
 
             // Always deregister from registry — process is exiting regardless of reason.
@@ -837,11 +866,14 @@ pub async fn run_loop_impl(
             if let Err(e) = registry.deregister_current_process() {
                 warn!("Failed to deregister loop from registry: {}", e);
             }
+
+            // Start or cancel loops scheduled after this one
+            reconcile_scheduled_loops(ctx.repo_root());
         }
 
         // Print termination info to console (skip in TUI mode - TUI handles display)
         // Skip in RPC mode - JSON events replace console output
-        if !enable_tui && !enable_rpc {
+        if !enable_tui && !enable_rpc && !embedded {
             print_termination(reason, state, use_colors);
         }
 
@@ -888,60 +920,6 @@ pub async fn run_loop_impl(
         }
     };
 
-    if let Some(reason) = pending_suspend_termination_reason.take() {
-        let reason = dispatch_pre_loop_termination_hooks(
-            &event_loop,
-            hooks_dispatch_enabled,
-            &loop_id,
-            &hook_engine,
-            &hook_executor,
-            &suspend_state_store,
-            &ctx,
-            config.event_loop.max_iterations,
-            &mut accumulated_hook_metadata,
-            reason,
-        )
-        .await?;
-
-        let terminate_event = event_loop.publish_terminate_event(&reason);
-        log_terminate_event(
-            &mut event_logger,
-            event_loop.state().iteration,
-            &terminate_event,
-        );
-
-        let reason = dispatch_post_loop_termination_hooks(
-            &event_loop,
-            hooks_dispatch_enabled,
-            &loop_id,
-            &hook_engine,
-            &hook_executor,
-            &suspend_state_store,
-            &ctx,
-            config.event_loop.max_iterations,
-            &mut accumulated_hook_metadata,
-            reason,
-        )
-        .await?;
-
-        handle_termination(
-            &reason,
-            event_loop.state(),
-            &config.core.scratchpad,
-            &loop_history,
-            &loop_context,
-            auto_merge,
-            &prompt_content,
-        );
-
-        // Wait for user to exit TUI (press 'q') on natural completion
-        if let Some(handle) = tui_handle.take() {
-            let _ = handle.await;
-        }
-
-        return Ok(reason);
-    }
-
     // Main orchestration loop
     loop {
         // Check for interrupt signal at start of each iteration
@@ -960,41 +938,15 @@ pub async fn run_loop_impl(
                 tokio::time::sleep(Duration::from_millis(250)).await;
                 let _ = killpg(pgid, Signal::SIGKILL);
             }
-            let reason = dispatch_pre_loop_termination_hooks(
-                &event_loop,
-                hooks_dispatch_enabled,
-                &loop_id,
-                &hook_engine,
-                &hook_executor,
-                &suspend_state_store,
-                &ctx,
-                config.event_loop.max_iterations,
-                &mut accumulated_hook_metadata,
-                TerminationReason::Interrupted,
-            )
-            .await?;
-
-            let terminate_event = event_loop.publish_terminate_event(&reason);
+            let (reason, terminate_event) = event_loop
+                .publish_terminate_event(TerminationReason::Interrupted)
+                .await;
             log_terminate_event(
                 &mut event_logger,
                 event_loop.state().iteration,
                 &terminate_event,
             );
 
-            let reason = dispatch_post_loop_termination_hooks(
-                &event_loop,
-                hooks_dispatch_enabled,
-                &loop_id,
-                &hook_engine,
-                &hook_executor,
-                &suspend_state_store,
-                &ctx,
-                config.event_loop.max_iterations,
-                &mut accumulated_hook_metadata,
-                reason,
-            )
-            .await?;
-
             handle_termination(
                 &reason,
                 event_loop.state(),
@@ -1080,44 +1032,18 @@ pub async fn run_loop_impl(
             );
         }
 
-        // Check termination before execution
+        // Wait out any hook suspended at the previous boundary, then check
+        // termination before execution
+        event_loop.await_hook_resume().await;
         if let Some(reason) = event_loop.check_termination() {
-            let reason = dispatch_pre_loop_termination_hooks(
-                &event_loop,
-                hooks_dispatch_enabled,
-                &loop_id,
-                &hook_engine,
-                &hook_executor,
-                &suspend_state_store,
-                &ctx,
-                config.event_loop.max_iterations,
-                &mut accumulated_hook_metadata,
-                reason,
-            )
-            .await?;
-
             // Per spec: Publish loop.terminate event to observers
-            let terminate_event = event_loop.publish_terminate_event(&reason);
+            let (reason, terminate_event) = event_loop.publish_terminate_event(reason).await;
             log_terminate_event(
                 &mut event_logger,
                 event_loop.state().iteration,
                 &terminate_event,
             );
 
-            let reason = dispatch_post_loop_termination_hooks(
-                &event_loop,
-                hooks_dispatch_enabled,
-                &loop_id,
-                &hook_engine,
-                &hook_executor,
-                &suspend_state_store,
-                &ctx,
-                config.event_loop.max_iterations,
-                &mut accumulated_hook_metadata,
-                reason,
-            )
-            .await?;
-
             handle_termination(
                 &reason,
                 event_loop.state(),
@@ -1136,97 +1062,15 @@ pub async fn run_loop_impl(
 
         let iteration = event_loop.state().iteration + 1;
 
-        if event_loop.has_pending_events() {
-            let pre_iteration_start_outcomes = dispatch_phase_event_hooks(
-                &event_loop,
-                hooks_dispatch_enabled,
-                &loop_id,
-                &hook_engine,
-                &hook_executor,
-                HookPhaseEvent::PreIterationStart,
-                build_iteration_start_payload_input(
-                    &loop_id,
-                    &ctx,
-                    config.event_loop.max_iterations,
-                    iteration,
-                    Some(event_loop.get_active_hat_id().as_str().to_string()),
-                    None,
-                    None,
-                    &accumulated_hook_metadata,
-                ),
-            );
-            merge_accumulated_hook_metadata_from_outcomes(
-                &mut accumulated_hook_metadata,
-                &pre_iteration_start_outcomes,
-            );
-            fail_if_blocking_iteration_start_outcomes(&pre_iteration_start_outcomes)?;
-
-            if let Some(reason) = wait_for_resume_if_suspended(
-                &pre_iteration_start_outcomes,
-                &loop_id,
-                &suspend_state_store,
-            )
-            .await?
-            {
-                let reason = dispatch_pre_loop_termination_hooks(
-                    &event_loop,
-                    hooks_dispatch_enabled,
-                    &loop_id,
-                    &hook_engine,
-                    &hook_executor,
-                    &suspend_state_store,
-                    &ctx,
-                    config.event_loop.max_iterations,
-                    &mut accumulated_hook_metadata,
-                    reason,
-                )
-                .await?;
-
-                let terminate_event = event_loop.publish_terminate_event(&reason);
-                log_terminate_event(
-                    &mut event_logger,
-                    event_loop.state().iteration,
-                    &terminate_event,
-                );
-
-                let reason = dispatch_post_loop_termination_hooks(
-                    &event_loop,
-                    hooks_dispatch_enabled,
-                    &loop_id,
-                    &hook_engine,
-                    &hook_executor,
-                    &suspend_state_store,
-                    &ctx,
-                    config.event_loop.max_iterations,
-                    &mut accumulated_hook_metadata,
-                    reason,
-                )
-                .await?;
-
-                handle_termination(
-                    &reason,
-                    event_loop.state(),
-                    &config.core.scratchpad,
-                    &loop_history,
-                    &loop_context,
-                    auto_merge,
-                    &prompt_content,
-                );
-                // Wait for user to exit TUI (press 'q') on natural completion
-                if let Some(handle) = tui_handle.take() {
-                    let _ = handle.await;
-                }
-                return Ok(reason);
-            }
-        }
-
         // Get next hat to execute, with fallback recovery if no pending events
-        let hat_id = match event_loop.next_hat() {
+        let hat_id = match event_loop.next_hat().cloned() {
             Some(id) => {
                 // Reset fallback counter on successful event routing
                 consecutive_fallbacks = 0;
-                id.clone()
+                id
             }
+            // A suspended hook parked hat selection; resume at the loop top.
+            None if event_loop.is_hook_suspended() => continue,
             None => {
                 match recover_late_events_before_fallback(&mut event_loop)
                     .inspect_err(
@@ -1242,41 +1086,14 @@ pub async fn run_loop_impl(
                         continue;
                     }
                     Some(LateEventRecovery::Terminate(reason)) => {
-                        let reason = dispatch_pre_loop_termination_hooks(
-                            &event_loop,
-                            hooks_dispatch_enabled,
-                            &loop_id,
-                            &hook_engine,
-                            &hook_executor,
-                            &suspend_state_store,
-                            &ctx,
-                            config.event_loop.max_iterations,
-                            &mut accumulated_hook_metadata,
-                            reason,
-                        )
-                        .await?;
-
-                        let terminate_event = event_loop.publish_terminate_event(&reason);
+                        let (reason, terminate_event) =
+                            event_loop.publish_terminate_event(reason).await;
                         log_terminate_event(
                             &mut event_logger,
                             event_loop.state().iteration,
                             &terminate_event,
                         );
 
-                        let reason = dispatch_post_loop_termination_hooks(
-                            &event_loop,
-                            hooks_dispatch_enabled,
-                            &loop_id,
-                            &hook_engine,
-                            &hook_executor,
-                            &suspend_state_store,
-                            &ctx,
-                            config.event_loop.max_iterations,
-                            &mut accumulated_hook_metadata,
-                            reason,
-                        )
-                        .await?;
-
                         handle_termination(
                             &reason,
                             event_loop.state(),
@@ -1304,41 +1121,15 @@ pub async fn run_loop_impl(
                         "Fallback recovery exhausted after {} attempts, terminating",
                         MAX_FALLBACK_ATTEMPTS
                     );
-                    let reason = dispatch_pre_loop_termination_hooks(
-                        &event_loop,
-                        hooks_dispatch_enabled,
-                        &loop_id,
-                        &hook_engine,
-                        &hook_executor,
-                        &suspend_state_store,
-                        &ctx,
-                        config.event_loop.max_iterations,
-                        &mut accumulated_hook_metadata,
-                        TerminationReason::Stopped,
-                    )
-                    .await?;
-
-                    let terminate_event = event_loop.publish_terminate_event(&reason);
+                    let (reason, terminate_event) = event_loop
+                        .publish_terminate_event(TerminationReason::Stopped)
+                        .await;
                     log_terminate_event(
                         &mut event_logger,
                         event_loop.state().iteration,
                         &terminate_event,
                     );
 
-                    let reason = dispatch_post_loop_termination_hooks(
-                        &event_loop,
-                        hooks_dispatch_enabled,
-                        &loop_id,
-                        &hook_engine,
-                        &hook_executor,
-                        &suspend_state_store,
-                        &ctx,
-                        config.event_loop.max_iterations,
-                        &mut accumulated_hook_metadata,
-                        reason,
-                    )
-                    .await?;
-
                     handle_termination(
                         &reason,
                         event_loop.state(),
@@ -1366,42 +1157,16 @@ pub async fn run_loop_impl(
 
                 // Fallback not possible (no planner hat or doesn't subscribe to task.resume)
                 warn!("No hats with pending events and fallback not available, terminating");
-                let reason = dispatch_pre_loop_termination_hooks(
-                    &event_loop,
-                    hooks_dispatch_enabled,
-                    &loop_id,
-                    &hook_engine,
-                    &hook_executor,
-                    &suspend_state_store,
-                    &ctx,
-                    config.event_loop.max_iterations,
-                    &mut accumulated_hook_metadata,
-                    TerminationReason::Stopped,
-                )
-                .await?;
-
                 // Per spec: Publish loop.terminate event to observers
-                let terminate_event = event_loop.publish_terminate_event(&reason);
+                let (reason, terminate_event) = event_loop
+                    .publish_terminate_event(TerminationReason::Stopped)
+                    .await;
                 log_terminate_event(
                     &mut event_logger,
                     event_loop.state().iteration,
                     &terminate_event,
                 );
 
-                let reason = dispatch_post_loop_termination_hooks(
-                    &event_loop,
-                    hooks_dispatch_enabled,
-                    &loop_id,
-                    &hook_engine,
-                    &hook_executor,
-                    &suspend_state_store,
-                    &ctx,
-                    config.event_loop.max_iterations,
-                    &mut accumulated_hook_metadata,
-                    reason,
-                )
-                .await?;
-
                 handle_termination(
                     &reason,
                     event_loop.state(),
@@ -1434,93 +1199,11 @@ pub async fn run_loop_impl(
             hat_id.clone()
         };
 
-        let post_iteration_start_outcomes = dispatch_phase_event_hooks(
-            &event_loop,
-            hooks_dispatch_enabled,
-            &loop_id,
-            &hook_engine,
-            &hook_executor,
-            HookPhaseEvent::PostIterationStart,
-            build_iteration_start_payload_input(
-                &loop_id,
-                &ctx,
-                config.event_loop.max_iterations,
-                iteration,
-                Some(preview_display_hat.as_str().to_string()),
-                Some(preview_display_hat.as_str().to_string()),
-                None,
-                &accumulated_hook_metadata,
-            ),
-        );
-        merge_accumulated_hook_metadata_from_outcomes(
-            &mut accumulated_hook_metadata,
-            &post_iteration_start_outcomes,
-        );
-        fail_if_blocking_iteration_start_outcomes(&post_iteration_start_outcomes)?;
-
-        if let Some(reason) = wait_for_resume_if_suspended(
-            &post_iteration_start_outcomes,
-            &loop_id,
-            &suspend_state_store,
-        )
-        .await?
-        {
-            let reason = dispatch_pre_loop_termination_hooks(
-                &event_loop,
-                hooks_dispatch_enabled,
-                &loop_id,
-                &hook_engine,
-                &hook_executor,
-                &suspend_state_store,
-                &ctx,
-                config.event_loop.max_iterations,
-                &mut accumulated_hook_metadata,
-                reason,
-            )
-            .await?;
-
-            let terminate_event = event_loop.publish_terminate_event(&reason);
-            log_terminate_event(
-                &mut event_logger,
-                event_loop.state().iteration,
-                &terminate_event,
-            );
-
-            let reason = dispatch_post_loop_termination_hooks(
-                &event_loop,
-                hooks_dispatch_enabled,
-                &loop_id,
-                &hook_engine,
-                &hook_executor,
-                &suspend_state_store,
-                &ctx,
-                config.event_loop.max_iterations,
-                &mut accumulated_hook_metadata,
-                reason,
-            )
-            .await?;
-
-            handle_termination(
-                &reason,
-                event_loop.state(),
-                &config.core.scratchpad,
-                &loop_history,
-                &loop_context,
-                auto_merge,
-                &prompt_content,
-            );
-            // Wait for user to exit TUI (press 'q') on natural completion
-            if let Some(handle) = tui_handle.take() {
-                let _ = handle.await;
-            }
-            return Ok(reason);
-        }
-
         // Log hat changes with appropriate messaging
         // Skip in TUI mode - TUI shows hat info in header, and stdout would corrupt display
         // Skip in RPC mode - JSON events replace console output
         if last_hat.as_ref() != Some(&hat_id) {
-            if tui_state.is_none() && !enable_rpc {
+            if tui_state.is_none() && !enable_rpc && !embedded {
                 if hat_id.as_str() == "ralph" {
                     info!("I'm Ralph. Let's do this.");
                 } else {
@@ -1589,7 +1272,7 @@ pub async fn run_loop_impl(
         // visually distinguish where one iteration ends and another begins."
         // Skip when TUI is enabled - TUI has its own header showing iteration info
         // Skip in RPC mode - JSON events replace console output
-        if tui_state.is_none() && !enable_rpc {
+        if tui_state.is_none() && !enable_rpc && !embedded {
             print_iteration_separator(
                 iteration,
                 display_hat.as_str(),
@@ -1600,7 +1283,7 @@ pub async fn run_loop_impl(
         }
 
         // In verbose mode, print the full prompt before execution
-        if verbosity == Verbosity::Verbose {
+        if verbosity == Verbosity::Verbose && !embedded {
             eprintln!("\n{}", "=".repeat(80));
             eprintln!("PROMPT FOR {} (iteration {})", hat_id, iteration);
             eprintln!("{}", "-".repeat(80));
@@ -1618,77 +1301,85 @@ pub async fn run_loop_impl(
         let hat_backend_opt = hat_config_opt.and_then(|c| c.backend.as_ref());
         let hat_backend_args = hat_config_opt.and_then(|c| c.backend_args.clone());
 
-        // Step 2: Resolve effective backend and determine backend name for timeout
-        // Note: backend_name_for_timeout is owned String to avoid lifetime issues with hat_backend reference
-        let (mut effective_backend, backend_name_for_timeout): (CliBackend, String) =
-            match hat_backend_opt {
-                Some(hat_backend) => {
-                    // Hat has custom backend configuration
-                    match CliBackend::from_hat_backend(hat_backend) {
-                        Ok(hat_backend_instance) => {
-                            debug!(
-                                "Using hat-level backend for '{}': {:?}",
-                                display_hat, hat_backend
-                            );
+        // Sub-loop hats run a nested loop instead of a backend; its objective is the
+        // hat's instructions plus the events that activated it.
+        let subloop_run = hat_config_opt.and_then(|c| {
+            let subloop = c.subloop.clone()?;
+            let trigger_events = event_loop
+                .state()
+                .last_activation_events
+                .get(&display_hat)
+                .map(Vec::as_slice)
+                .unwrap_or_default();
+            let objective = build_subloop_objective(&c.instructions, trigger_events);
+            Some((subloop, objective, event_loop.state().cumulative_cost))
+        });
 
-                            // Determine backend name for timeout based on hat backend type
-                            // Use owned String to avoid borrowing issues and improve code clarity
-                            let backend_name = match hat_backend {
-                                ralph_core::HatBackend::Named(name) => name.clone(),
-                                ralph_core::HatBackend::NamedWithArgs { backend_type, .. } => {
-                                    backend_type.clone()
-                                }
-                                ralph_core::HatBackend::KiroAgent { backend_type, .. } => {
-                                    backend_type.clone()
-                                }
-                                // For Custom backends, extract command name from path
-                                // Handles both Unix ("/usr/bin/codex") and commands with args ("ollama run llama3")
-                                ralph_core::HatBackend::Custom { command, .. } => {
-                                    // First split by whitespace to handle commands with arguments
-                                    // e.g., "ollama run llama3" -> "ollama"
-                                    let base_command =
-                                        command.split_whitespace().next().unwrap_or(command);
-                                    // Then extract filename from path
-                                    // e.g., "/usr/bin/codex" -> "codex"
-                                    std::path::Path::new(base_command)
-                                        .file_name()
-                                        .and_then(|s| s.to_str())
-                                        .unwrap_or("custom")
-                                        .to_string()
-                                }
-                            };
-
-                            (hat_backend_instance, backend_name)
-                        }
-                        Err(e) => {
-                            // Failed to create backend from hat config - fall back to global
-                            warn!(
-                                "Failed to create backend from hat configuration for '{}': {}. Falling back to global backend.",
-                                display_hat, e
-                            );
-                            // IMPORTANT: Use global backend name for timeout since we're using global backend
-                            (backend.clone(), config.cli.backend.clone())
+        // Fan-out hats run their branch hats concurrently on the events that activated them.
+        let fan_out_run = hat_config_opt
+            .and_then(|c| c.fan_out.clone())
+            .map(|fan_out| {
+                let trigger_events = event_loop
+                    .state()
+                    .last_activation_events
+                    .get(&display_hat)
+                    .map(Vec::as_slice)
+                    .unwrap_or_default();
+                let branches: Vec<FanOutBranch> = fan_out
+                    .hats
+                    .iter()
+                    .filter_map(|hat| {
+                        let hat_id = HatId::new(hat);
+                        let prompt = event_loop.build_branch_prompt(&hat_id, trigger_events)?;
+                        let branch_config = event_loop.registry().get_config(&hat_id);
+                        let (mut branch_backend, backend_name) = resolve_hat_backend(
+                            branch_config.and_then(|c| c.backend.as_ref()),
+                            &hat_id,
+                            &backend,
+                            &config,
+                        );
+                        if let Some(args) = branch_config.and_then(|c| c.backend_args.clone()) {
+                            branch_backend.args.extend(args);
                         }
-                    }
-                }
-                None => {
-                    // No custom backend - use global configuration
-                    debug!(
-                        "Using global backend for '{}': {}",
-                        display_hat, config.cli.backend
-                    );
-                    (backend.clone(), config.cli.backend.clone())
-                }
-            };
-
-        // Step 2.5: Apply custom hat backend args if configured
-        if let Some(args) = hat_backend_args {
+                        Some(FanOutBranch {
+                            hat: hat.clone(),
+                            prompt,
+                            idle_timeout: Duration::from_secs(
+                                config.adapter_settings(&backend_name).timeout,
+                            ),
+                            backend: branch_backend,
+                        })
+                    })
+                    .collect();
+                (fan_out, branches)
+            });
+        let mut fan_out_branches: Vec<BranchOutcome> = Vec::new();
+
+        // Step 2: Resolve effective backend and determine backend name for timeout.
+        // Retries escalated to another backend run there; a hat that already failed
+        // over keeps using its failover backend for the rest of the run.
+        let failover_backend = event_loop
+            .escalation_backend(&display_hat)
+            .or_else(|| event_loop.active_failover_backend(&display_hat))
+            .cloned();
+        let (mut effective_backend, mut backend_name_for_timeout) = resolve_hat_backend(
+            failover_backend.as_ref().or(hat_backend_opt),
+            &display_hat,
+            &backend,
+            &config,
+        );
+
+        // Step 2.5: Apply custom hat backend args if configured (primary backend only)
+        if failover_backend.is_none()
+            && let Some(args) = hat_backend_args
+        {
             effective_backend.args.extend(args);
         }
 
         // Step 3: Get timeout from config based on actual backend being used
-        let timeout_secs = config.adapter_settings(&backend_name_for_timeout).timeout;
-        let timeout = Some(Duration::from_secs(timeout_secs));
+        let mut timeout = Some(Duration::from_secs(
+            config.adapter_settings(&backend_name_for_timeout).timeout,
+        ));
 
         // For TUI mode, get the shared lines buffer for this iteration.
         // The buffer is owned by TuiState's IterationBuffer, so writes from
@@ -1705,156 +1396,207 @@ pub async fn run_loop_impl(
                     config.event_loop.max_iterations,
                 )
             } else {
-                None
+                parent_output
+                    .as_ref()
+                    .and_then(|output| output.tui_lines.clone())
             };
 
-        // Race execution against interrupt signal for immediate termination on Ctrl+C
-        let mut interrupt_rx_clone = interrupt_rx.clone();
-        let interrupt_rx_for_pty = interrupt_rx.clone();
-        let tui_lines_for_pty = tui_lines.clone();
-        let rpc_stdout_for_pty = rpc_stdout.clone();
-        let execute_future = async {
-            if effective_backend.output_format == BackendOutputFormat::Acp {
-                execute_acp(
-                    &effective_backend,
-                    &config,
-                    &prompt,
-                    verbosity,
-                    tui_lines_for_pty,
-                    rpc_stdout_for_pty,
-                    iteration,
-                    display_hat.as_str(),
-                    &backend_name_for_timeout,
-                )
-                .await
-            } else if use_pty {
-                execute_pty(
-                    pty_executor.as_mut(),
-                    &effective_backend,
-                    &config,
-                    &prompt,
-                    user_interactive,
-                    interrupt_rx_for_pty,
-                    verbosity,
-                    tui_lines_for_pty,
-                    rpc_stdout_for_pty,
-                    iteration,
-                    display_hat.as_str(),
-                    &backend_name_for_timeout,
-                )
-                .await
-            } else {
-                let executor = CliExecutor::new(effective_backend.clone());
-                let result = executor
-                    .execute(&prompt, stdout(), timeout, verbosity == Verbosity::Verbose)
+        // Failed attempts that fail over to another backend retry the same prompt
+        // within this iteration; their usage is carried into the final outcome.
+        let mut failed_attempt_usage: Option<ExecutionOutcome> = None;
+        let mut outcome = loop {
+            // Race execution against interrupt signal for immediate termination on Ctrl+C
+            let mut interrupt_rx_clone = interrupt_rx.clone();
+            let interrupt_rx_for_pty = interrupt_rx.clone();
+            let tui_lines_for_pty = tui_lines.clone();
+            let rpc_stdout_for_pty = rpc_stdout.clone();
+            let execute_future = async {
+                if let Some((subloop, objective, parent_cost_usd)) = &subloop_run {
+                    execute_subloop(
+                        subloop,
+                        objective,
+                        &config,
+                        &ctx,
+                        &loop_id,
+                        display_hat.as_str(),
+                        iteration,
+                        *parent_cost_usd,
+                        loop_history.as_ref(),
+                        color_mode,
+                        verbosity,
+                        tui_lines_for_pty,
+                        rpc_stdout_for_pty,
+                    )
+                    .await
+                } else if let Some((fan_out, branches)) = &fan_out_run {
+                    let (outcome, branch_outcomes) = execute_fan_out(
+                        fan_out,
+                        branches,
+                        &ctx,
+                        &loop_id,
+                        iteration,
+                        display_hat.as_str(),
+                        verbosity,
+                        tui_lines_for_pty,
+                        rpc_stdout_for_pty,
+                        &FanOutProgress {
+                            tui_state: tui_state.clone(),
+                            rpc_event_tx: rpc_event_tx.clone(),
+                        },
+                    )
                     .await?;
-                Ok(ExecutionOutcome {
-                    output: normalize_cli_output_for_parsing(
-                        effective_backend.output_format,
-                        &result.output,
-                    ),
-                    success: result.success,
-                    termination: None,
-                    total_cost_usd: 0.0,
-                    input_tokens: 0,
-                    output_tokens: 0,
-                    cache_read_tokens: 0,
-                    cache_write_tokens: 0,
-                })
-            }
-        };
+                    fan_out_branches = branch_outcomes;
+                    Ok(outcome)
+                } else if effective_backend.output_format == BackendOutputFormat::Acp {
+                    execute_acp(
+                        &effective_backend,
+                        &config,
+                        &prompt,
+                        verbosity,
+                        tui_lines_for_pty,
+                        rpc_stdout_for_pty,
+                        iteration,
+                        display_hat.as_str(),
+                        &backend_name_for_timeout,
+                    )
+                    .await
+                } else if use_pty {
+                    execute_pty(
+                        pty_executor.as_mut(),
+                        &effective_backend,
+                        &config,
+                        &prompt,
+                        user_interactive,
+                        interrupt_rx_for_pty,
+                        verbosity,
+                        tui_lines_for_pty,
+                        rpc_stdout_for_pty,
+                        iteration,
+                        display_hat.as_str(),
+                        &backend_name_for_timeout,
+                    )
+                    .await
+                } else {
+                    let executor = CliExecutor::new(effective_backend.clone());
+                    let result = executor
+                        .execute(&prompt, stdout(), timeout, verbosity == Verbosity::Verbose)
+                        .await?;
+                    Ok(ExecutionOutcome {
+                        output: normalize_cli_output_for_parsing(
+                            effective_backend.output_format,
+                            &result.output,
+                        ),
+                        success: result.success,
+                        termination: None,
+                        total_cost_usd: 0.0,
+                        input_tokens: 0,
+                        output_tokens: 0,
+                        cache_read_tokens: 0,
+                        cache_write_tokens: 0,
+                        failure: result.failure_class(),
+                    })
+                }
+            };
 
-        let outcome = tokio::select! {
-            result = execute_future => result?,
-            _ = interrupt_rx_clone.changed() => {
-                // Immediately terminate children via process group signal
-                #[cfg(unix)]
-                {
-                    use nix::sys::signal::{killpg, Signal};
-                    use nix::unistd::getpgrp;
-                    let pgid = getpgrp();
-                    debug!("Sending SIGTERM to process group {}", pgid);
-                    let _ = killpg(pgid, Signal::SIGTERM);
-
-                    // Wait briefly for graceful exit, then SIGKILL
-                    tokio::time::sleep(Duration::from_millis(250)).await;
-                    let _ = killpg(pgid, Signal::SIGKILL);
+            let attempt = tokio::select! {
+                result = execute_future => result?,
+                _ = interrupt_rx_clone.changed() => {
+                    // Immediately terminate children via process group signal
+                    #[cfg(unix)]
+                    {
+                        use nix::sys::signal::{killpg, Signal};
+                        use nix::unistd::getpgrp;
+                        let pgid = getpgrp();
+                        debug!("Sending SIGTERM to process group {}", pgid);
+                        let _ = killpg(pgid, Signal::SIGTERM);
+
+                        // Wait briefly for graceful exit, then SIGKILL
+                        tokio::time::sleep(Duration::from_millis(250)).await;
+                        let _ = killpg(pgid, Signal::SIGKILL);
+                    }
+
+                    let (reason, terminate_event) =
+                        event_loop.publish_terminate_event(TerminationReason::Interrupted).await;
+                    log_terminate_event(&mut event_logger, event_loop.state().iteration, &terminate_event);
+
+                    handle_termination(&reason, event_loop.state(), &config.core.scratchpad, &loop_history, &loop_context, auto_merge, &prompt_content);
+                    // Signal TUI to exit immediately on interrupt
+                    let _ = terminated_tx.send(true);
+                    return Ok(reason);
                 }
+            };
 
-                let reason = dispatch_pre_loop_termination_hooks(
-                    &event_loop,
-                    hooks_dispatch_enabled,
-                    &loop_id,
-                    &hook_engine,
-                    &hook_executor,
-                    &suspend_state_store,
-                    &ctx,
-                    config.event_loop.max_iterations,
-                    &mut accumulated_hook_metadata,
-                    TerminationReason::Interrupted,
-                )
-                .await?;
-
-                let terminate_event = event_loop.publish_terminate_event(&reason);
-                log_terminate_event(&mut event_logger, event_loop.state().iteration, &terminate_event);
-
-                let reason = dispatch_post_loop_termination_hooks(
-                    &event_loop,
-                    hooks_dispatch_enabled,
-                    &loop_id,
-                    &hook_engine,
-                    &hook_executor,
-                    &suspend_state_store,
-                    &ctx,
-                    config.event_loop.max_iterations,
-                    &mut accumulated_hook_metadata,
-                    reason,
+            let Some(failure) = attempt.failure else {
+                break attempt;
+            };
+            let Some(next_backend) = event_loop.advance_failover(&display_hat, failure) else {
+                break attempt;
+            };
+            let (next_cli_backend, next_backend_name) =
+                resolve_hat_backend(Some(&next_backend), &display_hat, &backend, &config);
+            if let Some(ref history) = loop_history
+                && let Err(e) = history.record_backend_failover(
+                    iteration,
+                    display_hat.as_str(),
+                    &backend_name_for_timeout,
+                    &next_backend_name,
+                    failure,
                 )
-                .await?;
+            {
+                warn!("Failed to record backend failover in history: {}", e);
+            }
+            if tui_state.is_none() && !enable_rpc && !embedded {
+                eprintln!(
+                    "Backend '{}' failed ({}); retrying iteration {} on '{}'",
+                    backend_name_for_timeout, failure, iteration, next_backend_name
+                );
+            }
 
-                handle_termination(&reason, event_loop.state(), &config.core.scratchpad, &loop_history, &loop_context, auto_merge, &prompt_content);
-                // Signal TUI to exit immediately on interrupt
-                let _ = terminated_tx.send(true);
-                return Ok(reason);
+            match failed_attempt_usage {
+                Some(ref mut carried) => carried.absorb_usage(&attempt),
+                None => failed_attempt_usage = Some(attempt),
             }
+            effective_backend = next_cli_backend;
+            backend_name_for_timeout = next_backend_name;
+            timeout = Some(Duration::from_secs(
+                config.adapter_settings(&backend_name_for_timeout).timeout,
+            ));
         };
+        if let Some(ref carried) = failed_attempt_usage {
+            outcome.absorb_usage(carried);
+        }
 
-        if let Some(reason) = outcome.termination {
-            let reason = dispatch_pre_loop_termination_hooks(
-                &event_loop,
-                hooks_dispatch_enabled,
-                &loop_id,
-                &hook_engine,
-                &hook_executor,
-                &suspend_state_store,
-                &ctx,
-                config.event_loop.max_iterations,
-                &mut accumulated_hook_metadata,
-                reason,
+        // Attribute stream usage to the hats that ran this iteration (per-hat budgets).
+        // Fan-out branches keep their own usage instead of sharing the coordinator's.
+        let hat_shares = if fan_out_branches.is_empty() {
+            event_loop.add_hat_usage(
+                outcome.total_cost_usd,
+                outcome.input_tokens + outcome.output_tokens,
             )
-            .await?;
+        } else {
+            event_loop.add_branch_usage(&fan_out_branches)
+        };
+        if let Some(ref history) = loop_history {
+            for (usage_hat_id, share) in &hat_shares {
+                if let Err(e) = history.record_hat_usage(
+                    iteration,
+                    usage_hat_id.as_str(),
+                    share.cost_usd,
+                    share.tokens,
+                ) {
+                    warn!("Failed to record hat usage in history: {}", e);
+                }
+            }
+        }
 
-            let terminate_event = event_loop.publish_terminate_event(&reason);
+        if let Some(reason) = outcome.termination {
+            let (reason, terminate_event) = event_loop.publish_terminate_event(reason).await;
             log_terminate_event(
                 &mut event_logger,
                 event_loop.state().iteration,
                 &terminate_event,
             );
 
-            let reason = dispatch_post_loop_termination_hooks(
-                &event_loop,
-                hooks_dispatch_enabled,
-                &loop_id,
-                &hook_engine,
-                &hook_executor,
-                &suspend_state_store,
-                &ctx,
-                config.event_loop.max_iterations,
-                &mut accumulated_hook_metadata,
-                reason,
-            )
-            .await?;
-
             handle_termination(
                 &reason,
                 event_loop.state(),
@@ -1914,6 +1656,7 @@ pub async fn run_loop_impl(
         );
 
         // Process output
+        event_loop.record_failure_class(outcome.failure);
         if let Some(reason) = event_loop.process_output(&hat_id, &output, success) {
             // Per spec: Log "All done! {promise} detected." when completion promise found
             if reason == TerminationReason::CompletionPromise {
@@ -1923,42 +1666,14 @@ pub async fn run_loop_impl(
                 );
             }
 
-            let reason = dispatch_pre_loop_termination_hooks(
-                &event_loop,
-                hooks_dispatch_enabled,
-                &loop_id,
-                &hook_engine,
-                &hook_executor,
-                &suspend_state_store,
-                &ctx,
-                config.event_loop.max_iterations,
-                &mut accumulated_hook_metadata,
-                reason,
-            )
-            .await?;
-
             // Per spec: Publish loop.terminate event to observers
-            let terminate_event = event_loop.publish_terminate_event(&reason);
+            let (reason, terminate_event) = event_loop.publish_terminate_event(reason).await;
             log_terminate_event(
                 &mut event_logger,
                 event_loop.state().iteration,
                 &terminate_event,
             );
 
-            let reason = dispatch_post_loop_termination_hooks(
-                &event_loop,
-                hooks_dispatch_enabled,
-                &loop_id,
-                &hook_engine,
-                &hook_executor,
-                &suspend_state_store,
-                &ctx,
-                config.event_loop.max_iterations,
-                &mut accumulated_hook_metadata,
-                reason,
-            )
-            .await?;
-
             handle_termination(
                 &reason,
                 event_loop.state(),
@@ -1975,379 +1690,32 @@ pub async fn run_loop_impl(
             return Ok(reason);
         }
 
-        // Check for planning session user responses (if in planning mode)
-        if let Err(e) = check_planning_session_responses(&mut event_loop) {
-            warn!(error = %e, "Failed to check planning session responses");
-        }
-
-        let should_dispatch_plan_created_hooks = event_loop
-            .has_pending_plan_events_in_jsonl()
-            .inspect_err(|e| {
-                warn!(
-                    error = %e,
-                    "Failed to inspect unread JSONL events for semantic plan.* topics"
-                )
-            })
-            .unwrap_or(false);
-
-        if should_dispatch_plan_created_hooks {
-            let pre_plan_created_outcomes = dispatch_phase_event_hooks(
-                &event_loop,
-                hooks_dispatch_enabled,
-                &loop_id,
-                &hook_engine,
-                &hook_executor,
-                HookPhaseEvent::PrePlanCreated,
-                build_plan_created_payload_input(
-                    &loop_id,
-                    &ctx,
-                    config.event_loop.max_iterations,
-                    event_loop.state().iteration,
-                    Some(display_hat.as_str().to_string()),
-                    Some(display_hat.as_str().to_string()),
-                    None,
-                    &accumulated_hook_metadata,
-                ),
-            );
-            merge_accumulated_hook_metadata_from_outcomes(
-                &mut accumulated_hook_metadata,
-                &pre_plan_created_outcomes,
-            );
-            fail_if_blocking_plan_created_outcomes(&pre_plan_created_outcomes)?;
-
-            if let Some(reason) = wait_for_resume_if_suspended(
-                &pre_plan_created_outcomes,
-                &loop_id,
-                &suspend_state_store,
-            )
-            .await?
-            {
-                let reason = dispatch_pre_loop_termination_hooks(
-                    &event_loop,
-                    hooks_dispatch_enabled,
-                    &loop_id,
-                    &hook_engine,
-                    &hook_executor,
-                    &suspend_state_store,
-                    &ctx,
-                    config.event_loop.max_iterations,
-                    &mut accumulated_hook_metadata,
-                    reason,
-                )
-                .await?;
-
-                let terminate_event = event_loop.publish_terminate_event(&reason);
-                log_terminate_event(
-                    &mut event_logger,
-                    event_loop.state().iteration,
-                    &terminate_event,
-                );
-
-                let reason = dispatch_post_loop_termination_hooks(
-                    &event_loop,
-                    hooks_dispatch_enabled,
-                    &loop_id,
-                    &hook_engine,
-                    &hook_executor,
-                    &suspend_state_store,
-                    &ctx,
-                    config.event_loop.max_iterations,
-                    &mut accumulated_hook_metadata,
-                    reason,
-                )
-                .await?;
-
-                handle_termination(
-                    &reason,
-                    event_loop.state(),
-                    &config.core.scratchpad,
-                    &loop_history,
-                    &loop_context,
-                    auto_merge,
-                    &prompt_content,
-                );
-                if let Some(handle) = tui_handle.take() {
-                    let _ = handle.await;
-                }
-                return Ok(reason);
+        // Wait out the backoff of a hat retry scheduled by process_output.
+        if let Some(backoff) = event_loop.take_retry_backoff()
+            && !backoff.is_zero()
+        {
+            if tui_state.is_none() && !enable_rpc && !embedded {
+                eprintln!("Retrying failed hat in {}s", backoff.as_secs());
+            }
+            let mut interrupt_rx_clone = interrupt_rx.clone();
+            tokio::select! {
+                () = tokio::time::sleep(backoff) => {}
+                _ = interrupt_rx_clone.changed() => {}
             }
         }
 
-        let pending_human_interact_context = event_loop
-            .pending_human_interact_context_in_jsonl()
-            .inspect_err(|e| {
-                warn!(
-                    error = %e,
-                    "Failed to inspect unread JSONL events for human.interact boundary"
-                )
-            })
-            .ok()
-            .flatten();
-
-        if let Some(human_interact_context) = pending_human_interact_context {
-            let pre_human_interact_outcomes = dispatch_phase_event_hooks(
-                &event_loop,
-                hooks_dispatch_enabled,
-                &loop_id,
-                &hook_engine,
-                &hook_executor,
-                HookPhaseEvent::PreHumanInteract,
-                build_human_interact_payload_input(
-                    &loop_id,
-                    &ctx,
-                    config.event_loop.max_iterations,
-                    event_loop.state().iteration,
-                    Some(display_hat.as_str().to_string()),
-                    Some(display_hat.as_str().to_string()),
-                    None,
-                    Some(human_interact_context),
-                    &accumulated_hook_metadata,
-                ),
-            );
-            merge_accumulated_hook_metadata_from_outcomes(
-                &mut accumulated_hook_metadata,
-                &pre_human_interact_outcomes,
-            );
-            fail_if_blocking_human_interact_outcomes(&pre_human_interact_outcomes)?;
-
-            if let Some(reason) = wait_for_resume_if_suspended(
-                &pre_human_interact_outcomes,
-                &loop_id,
-                &suspend_state_store,
-            )
-            .await?
-            {
-                let reason = dispatch_pre_loop_termination_hooks(
-                    &event_loop,
-                    hooks_dispatch_enabled,
-                    &loop_id,
-                    &hook_engine,
-                    &hook_executor,
-                    &suspend_state_store,
-                    &ctx,
-                    config.event_loop.max_iterations,
-                    &mut accumulated_hook_metadata,
-                    reason,
-                )
-                .await?;
-
-                let terminate_event = event_loop.publish_terminate_event(&reason);
-                log_terminate_event(
-                    &mut event_logger,
-                    event_loop.state().iteration,
-                    &terminate_event,
-                );
-
-                let reason = dispatch_post_loop_termination_hooks(
-                    &event_loop,
-                    hooks_dispatch_enabled,
-                    &loop_id,
-                    &hook_engine,
-                    &hook_executor,
-                    &suspend_state_store,
-                    &ctx,
-                    config.event_loop.max_iterations,
-                    &mut accumulated_hook_metadata,
-                    reason,
-                )
-                .await?;
-
-                handle_termination(
-                    &reason,
-                    event_loop.state(),
-                    &config.core.scratchpad,
-                    &loop_history,
-                    &loop_context,
-                    auto_merge,
-                    &prompt_content,
-                );
-                if let Some(handle) = tui_handle.take() {
-                    let _ = handle.await;
-                }
-                return Ok(reason);
-            }
+        // Check for planning session user responses (if in planning mode)
+        if let Err(e) = check_planning_session_responses(&mut event_loop) {
+            warn!(error = %e, "Failed to check planning session responses");
         }
 
         // Read events from JSONL that agent may have written
         let processed_events = event_loop
-            .process_events_from_jsonl()
+            .await_events_from_jsonl()
+            .await
             .inspect_err(|e| warn!(error = %e, "Failed to read events from JSONL"))
             .ok();
 
-        if let Some(human_interact_context) = processed_events
-            .as_ref()
-            .and_then(|events| events.human_interact_context.clone())
-        {
-            let post_human_interact_outcomes = dispatch_phase_event_hooks(
-                &event_loop,
-                hooks_dispatch_enabled,
-                &loop_id,
-                &hook_engine,
-                &hook_executor,
-                HookPhaseEvent::PostHumanInteract,
-                build_human_interact_payload_input(
-                    &loop_id,
-                    &ctx,
-                    config.event_loop.max_iterations,
-                    event_loop.state().iteration,
-                    Some(display_hat.as_str().to_string()),
-                    Some(display_hat.as_str().to_string()),
-                    None,
-                    Some(human_interact_context),
-                    &accumulated_hook_metadata,
-                ),
-            );
-            merge_accumulated_hook_metadata_from_outcomes(
-                &mut accumulated_hook_metadata,
-                &post_human_interact_outcomes,
-            );
-            fail_if_blocking_human_interact_outcomes(&post_human_interact_outcomes)?;
-
-            if let Some(reason) = wait_for_resume_if_suspended(
-                &post_human_interact_outcomes,
-                &loop_id,
-                &suspend_state_store,
-            )
-            .await?
-            {
-                let reason = dispatch_pre_loop_termination_hooks(
-                    &event_loop,
-                    hooks_dispatch_enabled,
-                    &loop_id,
-                    &hook_engine,
-                    &hook_executor,
-                    &suspend_state_store,
-                    &ctx,
-                    config.event_loop.max_iterations,
-                    &mut accumulated_hook_metadata,
-                    reason,
-                )
-                .await?;
-
-                let terminate_event = event_loop.publish_terminate_event(&reason);
-                log_terminate_event(
-                    &mut event_logger,
-                    event_loop.state().iteration,
-                    &terminate_event,
-                );
-
-                let reason = dispatch_post_loop_termination_hooks(
-                    &event_loop,
-                    hooks_dispatch_enabled,
-                    &loop_id,
-                    &hook_engine,
-                    &hook_executor,
-                    &suspend_state_store,
-                    &ctx,
-                    config.event_loop.max_iterations,
-                    &mut accumulated_hook_metadata,
-                    reason,
-                )
-                .await?;
-
-                handle_termination(
-                    &reason,
-                    event_loop.state(),
-                    &config.core.scratchpad,
-                    &loop_history,
-                    &loop_context,
-                    auto_merge,
-                    &prompt_content,
-                );
-                if let Some(handle) = tui_handle.take() {
-                    let _ = handle.await;
-                }
-                return Ok(reason);
-            }
-        }
-
-        if processed_events
-            .as_ref()
-            .map(|events| events.had_plan_events)
-            .unwrap_or(false)
-        {
-            let post_plan_created_outcomes = dispatch_phase_event_hooks(
-                &event_loop,
-                hooks_dispatch_enabled,
-                &loop_id,
-                &hook_engine,
-                &hook_executor,
-                HookPhaseEvent::PostPlanCreated,
-                build_plan_created_payload_input(
-                    &loop_id,
-                    &ctx,
-                    config.event_loop.max_iterations,
-                    event_loop.state().iteration,
-                    Some(display_hat.as_str().to_string()),
-                    Some(display_hat.as_str().to_string()),
-                    None,
-                    &accumulated_hook_metadata,
-                ),
-            );
-            merge_accumulated_hook_metadata_from_outcomes(
-                &mut accumulated_hook_metadata,
-                &post_plan_created_outcomes,
-            );
-            fail_if_blocking_plan_created_outcomes(&post_plan_created_outcomes)?;
-
-            if let Some(reason) = wait_for_resume_if_suspended(
-                &post_plan_created_outcomes,
-                &loop_id,
-                &suspend_state_store,
-            )
-            .await?
-            {
-                let reason = dispatch_pre_loop_termination_hooks(
-                    &event_loop,
-                    hooks_dispatch_enabled,
-                    &loop_id,
-                    &hook_engine,
-                    &hook_executor,
-                    &suspend_state_store,
-                    &ctx,
-                    config.event_loop.max_iterations,
-                    &mut accumulated_hook_metadata,
-                    reason,
-                )
-                .await?;
-
-                let terminate_event = event_loop.publish_terminate_event(&reason);
-                log_terminate_event(
-                    &mut event_logger,
-                    event_loop.state().iteration,
-                    &terminate_event,
-                );
-
-                let reason = dispatch_post_loop_termination_hooks(
-                    &event_loop,
-                    hooks_dispatch_enabled,
-                    &loop_id,
-                    &hook_engine,
-                    &hook_executor,
-                    &suspend_state_store,
-                    &ctx,
-                    config.event_loop.max_iterations,
-                    &mut accumulated_hook_metadata,
-                    reason,
-                )
-                .await?;
-
-                handle_termination(
-                    &reason,
-                    event_loop.state(),
-                    &config.core.scratchpad,
-                    &loop_history,
-                    &loop_context,
-                    auto_merge,
-                    &prompt_content,
-                );
-                if let Some(handle) = tui_handle.take() {
-                    let _ = handle.await;
-                }
-                return Ok(reason);
-            }
-        }
-
         let mut agent_wrote_events = processed_events
             .as_ref()
             .map(|events| events.had_events)
@@ -2396,7 +1764,7 @@ pub async fn run_loop_impl(
         if let Some(reason) = event_loop.check_cancellation_event() {
             info!("Loop cancelled gracefully via loop.cancel event.");
 
-            let terminate_event = event_loop.publish_terminate_event(&reason);
+            let (reason, terminate_event) = event_loop.publish_terminate_event(reason).await;
             log_terminate_event(
                 &mut event_logger,
                 event_loop.state().iteration,
@@ -2423,41 +1791,13 @@ pub async fn run_loop_impl(
                 config.event_loop.completion_promise
             );
 
-            let reason = dispatch_pre_loop_termination_hooks(
-                &event_loop,
-                hooks_dispatch_enabled,
-                &loop_id,
-                &hook_engine,
-                &hook_executor,
-                &suspend_state_store,
-                &ctx,
-                config.event_loop.max_iterations,
-                &mut accumulated_hook_metadata,
-                reason,
-            )
-            .await?;
-
-            let terminate_event = event_loop.publish_terminate_event(&reason);
+            let (reason, terminate_event) = event_loop.publish_terminate_event(reason).await;
             log_terminate_event(
                 &mut event_logger,
                 event_loop.state().iteration,
                 &terminate_event,
             );
 
-            let reason = dispatch_post_loop_termination_hooks(
-                &event_loop,
-                hooks_dispatch_enabled,
-                &loop_id,
-                &hook_engine,
-                &hook_executor,
-                &suspend_state_store,
-                &ctx,
-                config.event_loop.max_iterations,
-                &mut accumulated_hook_metadata,
-                reason,
-            )
-            .await?;
-
             handle_termination(
                 &reason,
                 event_loop.state(),
@@ -2484,41 +1824,13 @@ pub async fn run_loop_impl(
                 config.event_loop.completion_promise
             );
 
-            let reason = dispatch_pre_loop_termination_hooks(
-                &event_loop,
-                hooks_dispatch_enabled,
-                &loop_id,
-                &hook_engine,
-                &hook_executor,
-                &suspend_state_store,
-                &ctx,
-                config.event_loop.max_iterations,
-                &mut accumulated_hook_metadata,
-                reason,
-            )
-            .await?;
-
-            let terminate_event = event_loop.publish_terminate_event(&reason);
+            let (reason, terminate_event) = event_loop.publish_terminate_event(reason).await;
             log_terminate_event(
                 &mut event_logger,
                 event_loop.state().iteration,
                 &terminate_event,
             );
 
-            let reason = dispatch_post_loop_termination_hooks(
-                &event_loop,
-                hooks_dispatch_enabled,
-                &loop_id,
-                &hook_engine,
-                &hook_executor,
-                &suspend_state_store,
-                &ctx,
-                config.event_loop.max_iterations,
-                &mut accumulated_hook_metadata,
-                reason,
-            )
-            .await?;
-
             handle_termination(
                 &reason,
                 event_loop.state(),
@@ -2580,6 +1892,11 @@ fn poll_for_late_events(
     for poll_attempt in 0..=max_polls {
         let processed = event_loop.process_events_from_jsonl()?;
 
+        // A suspended hook holds the batch until the loop awaits the resume.
+        if processed.suspended {
+            return Ok(LateEventRecovery::PendingWork);
+        }
+
         if let Some(reason) = event_loop.check_cancellation_event() {
             return Ok(LateEventRecovery::Terminate(reason));
         }
@@ -2657,5521 +1974,1902 @@ fn resolve_display_hat_for_execution(
         .unwrap_or_else(|| preview_display_hat.clone())
 }
 
-fn build_loop_start_payload_input(
-    loop_id: &str,
-    ctx: &LoopContext,
-    max_iterations: u32,
-    iteration_current: u32,
-    active_hat: Option<String>,
-    accumulated_metadata: &serde_json::Map<String, serde_json::Value>,
-) -> HookPayloadBuilderInput {
-    HookPayloadBuilderInput {
-        loop_id: loop_id.to_string(),
-        is_primary: ctx.is_primary(),
-        workspace: ctx.workspace().to_path_buf(),
-        repo_root: ctx.repo_root().to_path_buf(),
-        pid: std::process::id(),
-        iteration_current,
-        iteration_max: max_iterations,
-        context: HookPayloadContextInput {
-            active_hat,
-            metadata: accumulated_metadata.clone(),
-            ..HookPayloadContextInput::default()
-        },
+/// Resolves the CLI backend for a hat and the backend name used to look up its timeout.
+///
+/// Falls back to the global backend when the hat has no backend (or it fails to resolve).
+fn resolve_hat_backend(
+    hat_backend: Option<&ralph_core::HatBackend>,
+    hat: &HatId,
+    global_backend: &CliBackend,
+    config: &RalphConfig,
+) -> (CliBackend, String) {
+    match hat_backend {
+        Some(hat_backend) => {
+            // Hat has custom backend configuration
+            match CliBackend::from_hat_backend(hat_backend) {
+                Ok(hat_backend_instance) => {
+                    debug!("Using hat-level backend for '{}': {:?}", hat, hat_backend);
+
+                    // Determine backend name for timeout based on hat backend type
+                    // Use owned String to avoid borrowing issues and improve code clarity
+                    let backend_name = match hat_backend {
+                        ralph_core::HatBackend::Named(name) => name.clone(),
+                        ralph_core::HatBackend::NamedWithArgs { backend_type, .. } => {
+                            backend_type.clone()
+                        }
+                        ralph_core::HatBackend::KiroAgent { backend_type, .. } => {
+                            backend_type.clone()
+                        }
+                        // For Custom backends, extract command name from path
+                        // Handles both Unix ("/usr/bin/codex") and commands with args ("ollama run llama3")
+                        ralph_core::HatBackend::Custom { command, .. } => {
+                            // First split by whitespace to handle commands with arguments
+                            // e.g., "ollama run llama3" -> "ollama"
+                            let base_command = command.split_whitespace().next().unwrap_or(command);
+                            // Then extract filename from path
+                            // e.g., "/usr/bin/codex" -> "codex"
+                            std::path::Path::new(base_command)
+                                .file_name()
+                                .and_then(|s| s.to_str())
+                                .unwrap_or("custom")
+                                .to_string()
+                        }
+                    };
+
+                    (hat_backend_instance, backend_name)
+                }
+                Err(e) => {
+                    // Failed to create backend from hat config - fall back to global
+                    warn!(
+                        "Failed to create backend from hat configuration for '{}': {}. Falling back to global backend.",
+                        hat, e
+                    );
+                    // IMPORTANT: Use global backend name for timeout since we're using global backend
+                    (global_backend.clone(), config.cli.backend.clone())
+                }
+            }
+        }
+        None => {
+            // No custom backend - use global configuration
+            debug!("Using global backend for '{}': {}", hat, config.cli.backend);
+            (global_backend.clone(), config.cli.backend.clone())
+        }
     }
 }
 
-fn build_iteration_start_payload_input(
-    loop_id: &str,
-    ctx: &LoopContext,
-    max_iterations: u32,
-    iteration_current: u32,
-    active_hat: Option<String>,
-    selected_hat: Option<String>,
-    selected_task: Option<String>,
-    accumulated_metadata: &serde_json::Map<String, serde_json::Value>,
-) -> HookPayloadBuilderInput {
-    HookPayloadBuilderInput {
-        loop_id: loop_id.to_string(),
-        is_primary: ctx.is_primary(),
-        workspace: ctx.workspace().to_path_buf(),
-        repo_root: ctx.repo_root().to_path_buf(),
-        pid: std::process::id(),
-        iteration_current,
-        iteration_max: max_iterations,
-        context: HookPayloadContextInput {
-            active_hat,
-            selected_hat,
-            selected_task,
-            metadata: accumulated_metadata.clone(),
-            ..HookPayloadContextInput::default()
-        },
+/// Executes a prompt in PTY mode with raw terminal handling.
+/// Converts PTY termination type to loop termination reason.
+///
+/// In interactive mode, idle timeout signals "iteration complete" rather than
+/// "loop stopped", allowing the event loop to process output and continue.
+///
+/// # Arguments
+/// * `termination_type` - The PTY executor's termination type
+/// * `interactive` - Whether running in interactive mode
+///
+/// # Returns
+/// * `None` - Continue processing (iteration complete)
+/// * `Some(TerminationReason)` - Stop the loop
+fn convert_termination_type(
+    termination_type: ralph_adapters::TerminationType,
+    interactive: bool,
+) -> Option<TerminationReason> {
+    match termination_type {
+        ralph_adapters::TerminationType::Natural => None,
+        ralph_adapters::TerminationType::IdleTimeout => {
+            if interactive {
+                // In interactive mode, idle timeout signals iteration complete,
+                // not loop termination. Let output be processed for events.
+                info!("PTY idle timeout in interactive mode, iteration complete");
+                None
+            } else {
+                warn!("PTY idle timeout reached, terminating loop");
+                Some(TerminationReason::Stopped)
+            }
+        }
+        ralph_adapters::TerminationType::UserInterrupt
+        | ralph_adapters::TerminationType::ForceKill => Some(TerminationReason::Interrupted),
     }
 }
 
-fn build_plan_created_payload_input(
-    loop_id: &str,
-    ctx: &LoopContext,
-    max_iterations: u32,
-    iteration_current: u32,
-    active_hat: Option<String>,
-    selected_hat: Option<String>,
-    selected_task: Option<String>,
-    accumulated_metadata: &serde_json::Map<String, serde_json::Value>,
-) -> HookPayloadBuilderInput {
-    HookPayloadBuilderInput {
-        loop_id: loop_id.to_string(),
-        is_primary: ctx.is_primary(),
-        workspace: ctx.workspace().to_path_buf(),
-        repo_root: ctx.repo_root().to_path_buf(),
-        pid: std::process::id(),
-        iteration_current,
-        iteration_max: max_iterations,
-        context: HookPayloadContextInput {
-            active_hat,
-            selected_hat,
-            selected_task,
-            metadata: accumulated_metadata.clone(),
-            ..HookPayloadContextInput::default()
-        },
-    }
+fn detect_solo_output_completion(
+    registry: &HatRegistry,
+    output: &str,
+    completion_promise: &str,
+) -> bool {
+    registry.is_empty() && EventParser::contains_promise(output, completion_promise)
 }
 
-fn build_human_interact_payload_input(
-    loop_id: &str,
-    ctx: &LoopContext,
-    max_iterations: u32,
-    iteration_current: u32,
-    active_hat: Option<String>,
-    selected_hat: Option<String>,
-    selected_task: Option<String>,
-    human_interact: Option<serde_json::Value>,
-    accumulated_metadata: &serde_json::Map<String, serde_json::Value>,
-) -> HookPayloadBuilderInput {
-    HookPayloadBuilderInput {
-        loop_id: loop_id.to_string(),
-        is_primary: ctx.is_primary(),
-        workspace: ctx.workspace().to_path_buf(),
-        repo_root: ctx.repo_root().to_path_buf(),
-        pid: std::process::id(),
-        iteration_current,
-        iteration_max: max_iterations,
-        context: HookPayloadContextInput {
-            active_hat,
-            selected_hat,
-            selected_task,
-            human_interact,
-            metadata: accumulated_metadata.clone(),
-            ..HookPayloadContextInput::default()
-        },
+fn normalize_cli_output_for_parsing(
+    output_format: BackendOutputFormat,
+    raw_output: &str,
+) -> String {
+    match output_format {
+        BackendOutputFormat::StreamJson => extract_claude_stream_text(raw_output),
+        BackendOutputFormat::PiStreamJson => extract_pi_stream_text(raw_output),
+        _ => raw_output.to_string(),
     }
 }
 
-fn build_loop_termination_payload_input(
-    loop_id: &str,
-    ctx: &LoopContext,
-    max_iterations: u32,
-    iteration_current: u32,
-    active_hat: Option<String>,
-    selected_hat: Option<String>,
-    selected_task: Option<String>,
-    termination_reason: &TerminationReason,
-    accumulated_metadata: &serde_json::Map<String, serde_json::Value>,
-) -> HookPayloadBuilderInput {
-    HookPayloadBuilderInput {
-        loop_id: loop_id.to_string(),
-        is_primary: ctx.is_primary(),
-        workspace: ctx.workspace().to_path_buf(),
-        repo_root: ctx.repo_root().to_path_buf(),
-        pid: std::process::id(),
-        iteration_current,
-        iteration_max: max_iterations,
-        context: HookPayloadContextInput {
-            active_hat,
-            selected_hat,
-            selected_task,
-            termination_reason: Some(termination_reason.as_str().to_string()),
-            metadata: accumulated_metadata.clone(),
-            ..HookPayloadContextInput::default()
-        },
+fn extract_claude_stream_text(raw_output: &str) -> String {
+    let mut extracted = String::new();
+
+    for line in raw_output.lines() {
+        let Some(event) = ClaudeStreamParser::parse_line(line) else {
+            continue;
+        };
+
+        if let ClaudeStreamEvent::Assistant { message, .. } = event {
+            for block in message.content {
+                if let ContentBlock::Text { text } = block {
+                    extracted.push_str(&text);
+                    extracted.push('\n');
+                }
+            }
+        }
     }
-}
 
-fn loop_termination_phase_events(reason: &TerminationReason) -> (HookPhaseEvent, HookPhaseEvent) {
-    if reason.is_success() {
-        (
-            HookPhaseEvent::PreLoopComplete,
-            HookPhaseEvent::PostLoopComplete,
-        )
+    if extracted.is_empty() {
+        raw_output.to_string()
     } else {
-        (HookPhaseEvent::PreLoopError, HookPhaseEvent::PostLoopError)
+        extracted
     }
 }
 
-#[allow(clippy::too_many_arguments)]
-fn dispatch_pre_loop_termination_hooks(
-    event_loop: &EventLoop,
-    hooks_dispatch_enabled: bool,
-    loop_id: &str,
-    hook_engine: &HookEngine,
-    hook_executor: &HookExecutor,
-    suspend_state_store: &SuspendStateStore,
-    ctx: &LoopContext,
-    max_iterations: u32,
-    accumulated_hook_metadata: &mut serde_json::Map<String, serde_json::Value>,
-    reason: TerminationReason,
-) -> impl std::future::Future<Output = Result<TerminationReason>> + Send {
-    let outcomes = collect_loop_termination_hook_outcomes(
-        event_loop,
-        hooks_dispatch_enabled,
-        loop_id,
-        hook_engine,
-        hook_executor,
-        ctx,
-        max_iterations,
-        accumulated_hook_metadata,
-        &reason,
-        true,
-    );
-    let loop_id = loop_id.to_string();
-    let suspend_state_store = suspend_state_store.clone();
+fn extract_pi_stream_text(raw_output: &str) -> String {
+    let mut extracted = String::new();
 
-    async move {
-        resolve_loop_termination_hook_outcomes(&outcomes, &loop_id, &suspend_state_store, reason)
-            .await
-    }
-}
+    for line in raw_output.lines() {
+        let Some(event) = PiStreamParser::parse_line(line) else {
+            continue;
+        };
 
-#[allow(clippy::too_many_arguments)]
-fn dispatch_post_loop_termination_hooks(
-    event_loop: &EventLoop,
-    hooks_dispatch_enabled: bool,
-    loop_id: &str,
-    hook_engine: &HookEngine,
-    hook_executor: &HookExecutor,
-    suspend_state_store: &SuspendStateStore,
-    ctx: &LoopContext,
-    max_iterations: u32,
-    accumulated_hook_metadata: &mut serde_json::Map<String, serde_json::Value>,
-    reason: TerminationReason,
-) -> impl std::future::Future<Output = Result<TerminationReason>> + Send {
-    let outcomes = collect_loop_termination_hook_outcomes(
-        event_loop,
-        hooks_dispatch_enabled,
-        loop_id,
-        hook_engine,
-        hook_executor,
-        ctx,
-        max_iterations,
-        accumulated_hook_metadata,
-        &reason,
-        false,
-    );
-    let loop_id = loop_id.to_string();
-    let suspend_state_store = suspend_state_store.clone();
+        if let PiStreamEvent::MessageUpdate {
+            assistant_message_event,
+        } = event
+            && let PiAssistantEvent::TextDelta { delta } = assistant_message_event
+        {
+            extracted.push_str(&delta);
+        }
+    }
 
-    async move {
-        resolve_loop_termination_hook_outcomes(&outcomes, &loop_id, &suspend_state_store, reason)
-            .await
+    if extracted.is_empty() {
+        raw_output.to_string()
+    } else {
+        extracted
     }
 }
 
-#[allow(clippy::too_many_arguments)]
-fn collect_loop_termination_hook_outcomes(
-    event_loop: &EventLoop,
-    hooks_dispatch_enabled: bool,
-    loop_id: &str,
-    hook_engine: &HookEngine,
-    hook_executor: &HookExecutor,
-    ctx: &LoopContext,
+/// Resolves the active timestamped events JSONL file path for this run.
+///
+/// The authoritative source is `.ralph/current-events`, which contains a
+/// relative path like `.ralph/events-YYYYMMDD-HHMMSS.jsonl`.
+///
+/// Falls back to `ctx.events_path()` if the marker is missing/unreadable.
+fn resolve_current_events_path(ctx: &LoopContext) -> PathBuf {
+    fs::read_to_string(ctx.current_events_marker())
+        .ok()
+        .map(|relative| {
+            let relative = relative.trim().to_string();
+            if std::path::Path::new(&relative).is_relative() {
+                ctx.workspace().join(relative)
+            } else {
+                PathBuf::from(relative)
+            }
+        })
+        .unwrap_or_else(|| ctx.events_path())
+}
+
+fn prepare_tui_iteration(
+    tui_state: &Arc<std::sync::Mutex<ralph_tui::TuiState>>,
+    hat_display: String,
+    backend: String,
     max_iterations: u32,
-    accumulated_hook_metadata: &mut serde_json::Map<String, serde_json::Value>,
-    reason: &TerminationReason,
-    is_pre_phase: bool,
-) -> Vec<HookDispatchOutcome> {
-    let (pre_phase_event, post_phase_event) = loop_termination_phase_events(reason);
-    let phase_event = if is_pre_phase {
-        pre_phase_event
-    } else {
-        post_phase_event
+) -> Option<Arc<std::sync::Mutex<Vec<ratatui::text::Line<'static>>>>> {
+    let Ok(mut state) = tui_state.lock() else {
+        return None;
     };
-
-    let active_hat = event_loop.get_active_hat_id().as_str().to_string();
-    let outcomes = dispatch_phase_event_hooks(
-        event_loop,
-        hooks_dispatch_enabled,
-        loop_id,
-        hook_engine,
-        hook_executor,
-        phase_event,
-        build_loop_termination_payload_input(
-            loop_id,
-            ctx,
-            max_iterations,
-            event_loop.state().iteration,
-            Some(active_hat.clone()),
-            Some(active_hat),
-            None,
-            reason,
-            accumulated_hook_metadata,
-        ),
-    );
-    merge_accumulated_hook_metadata_from_outcomes(accumulated_hook_metadata, &outcomes);
-    outcomes
+    // Ensure max_iterations is always available for header display, even if
+    // state was reset by earlier events.
+    state.max_iterations = Some(max_iterations);
+    state.start_new_iteration_with_metadata(Some(hat_display), Some(backend));
+    state.latest_iteration_lines_handle()
 }
 
-async fn resolve_loop_termination_hook_outcomes(
-    outcomes: &[HookDispatchOutcome],
-    loop_id: &str,
-    suspend_state_store: &SuspendStateStore,
-    reason: TerminationReason,
-) -> Result<TerminationReason> {
-    fail_if_blocking_loop_termination_outcomes(outcomes)?;
-
-    if let Some(termination_reason) =
-        wait_for_resume_if_suspended(outcomes, loop_id, suspend_state_store).await?
-    {
-        return Ok(termination_reason);
+/// Builds the objective of a sub-loop from the delegating hat's instructions
+/// and the events that activated it.
+fn build_subloop_objective(instructions: &str, trigger_events: &[Event]) -> String {
+    let mut objective = instructions.trim().to_string();
+    if !trigger_events.is_empty() {
+        if !objective.is_empty() {
+            objective.push_str("\n\n");
+        }
+        objective.push_str("## Delegated Events\n");
+        for event in trigger_events {
+            objective.push_str(&format!("\n- `{}`: {}", event.topic, event.payload));
+        }
     }
-
-    Ok(reason)
+    objective
 }
 
-const RETRY_BACKOFF_DELAYS_MS: [u64; 3] = [100, 200, 400];
-const RETRY_BACKOFF_SIGNAL_POLL_INTERVAL_MS: u64 = 100;
-const SUSPEND_WAIT_SIGNAL_POLL_INTERVAL_MS: u64 = 250;
-const HOOK_MUTATION_PAYLOAD_METADATA_KEY: &str = "metadata";
-const HOOK_MUTATION_METADATA_NAMESPACE_KEY: &str = "hook_metadata";
-
-#[derive(Debug, Clone, PartialEq)]
-enum HookMutationParseOutcome {
-    Disabled,
-    Parsed {
-        namespaced_metadata: serde_json::Map<String, serde_json::Value>,
-    },
-    Invalid(HookMutationParseError),
-}
+type SubloopFuture<'a> =
+    std::pin::Pin<Box<dyn std::future::Future<Output = Result<ExecutionOutcome>> + Send + 'a>>;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-enum HookMutationParseError {
-    InvalidJson { message: String },
-    InvalidSchema { message: String },
-}
+/// Runs a hat's sub-loop to termination and reports it as the hat's iteration.
+///
+/// The child loop runs in its own [`LoopContext`] under `.ralph/subloops/<id>`.
+/// Its termination is published in the parent as `on_complete`/`on_failure`,
+/// and its cost is returned so it counts toward the parent's budget. When the
+/// parent renders to the TUI or RPC, the child's agent output is streamed into
+/// the parent's current iteration instead of the terminal.
+#[allow(clippy::too_many_arguments)]
+fn execute_subloop<'a>(
+    subloop: &'a SubloopConfig,
+    objective: &'a str,
+    parent_config: &'a RalphConfig,
+    parent_ctx: &'a LoopContext,
+    parent_loop_id: &'a str,
+    hat: &'a str,
+    iteration: u32,
+    parent_cost_usd: f64,
+    parent_history: Option<&'a LoopHistory>,
+    color_mode: ColorMode,
+    verbosity: Verbosity,
+    tui_lines: Option<Arc<std::sync::Mutex<Vec<ratatui::text::Line<'static>>>>>,
+    rpc_stdout: Option<Arc<std::sync::Mutex<std::io::Stdout>>>,
+) -> SubloopFuture<'a> {
+    // Boxed with an explicit `Send` bound: the child loop is a recursive call into
+    // the loop runner, whose future type cannot be inferred through the cycle.
+    Box::pin(async move {
+        let failed = |output: String| ExecutionOutcome {
+            output,
+            success: false,
+            termination: None,
+            total_cost_usd: 0.0,
+            input_tokens: 0,
+            output_tokens: 0,
+            cache_read_tokens: 0,
+            cache_write_tokens: 0,
+            failure: None,
+        };
 
-fn format_hook_mutation_parse_error(error: &HookMutationParseError) -> String {
-    match error {
-        HookMutationParseError::InvalidJson { message }
-        | HookMutationParseError::InvalidSchema { message } => message.clone(),
-    }
-}
+        let mut child_config =
+            match crate::preflight::load_subloop_config(parent_config, &subloop.preset).await {
+                Ok(config) => config,
+                Err(e) => {
+                    return Ok(failed(format!(
+                        "Failed to load sub-loop preset '{}': {:#}",
+                        subloop.preset, e
+                    )));
+                }
+            };
 
-fn parse_hook_mutation_stdout(
-    mutate: &HookMutationConfig,
-    hook_name: &str,
-    stdout: &str,
-) -> HookMutationParseOutcome {
-    if !mutate.enabled {
-        return HookMutationParseOutcome::Disabled;
-    }
+        let child_id = format!("{}-sub-{}-{}", parent_loop_id, hat, iteration);
+        let child_ctx = LoopContext::subloop(parent_ctx, &child_id);
 
-    let parsed = match serde_json::from_str::<serde_json::Value>(stdout.trim()) {
-        Ok(parsed) => parsed,
-        Err(error) => {
-            return HookMutationParseOutcome::Invalid(HookMutationParseError::InvalidJson {
-                message: format!("mutation stdout is not valid JSON: {error}"),
-            });
+        if let Some(max_iterations) = subloop.max_iterations {
+            child_config.event_loop.max_iterations = max_iterations;
         }
-    };
+        // The child may spend at most what is left of the parent's budget.
+        let remaining_budget = parent_config
+            .event_loop
+            .max_cost_usd
+            .map(|max| (max - parent_cost_usd).max(0.0));
+        child_config.event_loop.max_cost_usd = match (subloop.max_cost_usd, remaining_budget) {
+            (Some(cap), Some(remaining)) => Some(cap.min(remaining)),
+            (cap, remaining) => cap.or(remaining),
+        };
+        child_config.event_loop.prompt = Some(objective.to_string());
+        child_config.event_loop.prompt_file = String::new();
+        child_config.core.scratchpad = format!(".ralph/subloops/{}/scratchpad.md", child_id);
 
-    let Some(payload_object) = parsed.as_object() else {
-        return HookMutationParseOutcome::Invalid(HookMutationParseError::InvalidSchema {
-            message: "mutation payload must be a JSON object".to_string(),
-        });
-    };
+        if let Some(history) = parent_history
+            && let Err(e) = history.record_subloop_started(iteration, hat, &child_id)
+        {
+            warn!("Failed to record sub-loop start in history: {}", e);
+        }
+        info!(loop_id = %child_id, preset = %subloop.preset, "Starting sub-loop for hat '{}'", hat);
 
-    if payload_object.len() != 1 || !payload_object.contains_key(HOOK_MUTATION_PAYLOAD_METADATA_KEY)
-    {
-        let keys = payload_object.keys().cloned().collect::<Vec<_>>();
-        return HookMutationParseOutcome::Invalid(HookMutationParseError::InvalidSchema {
-            message: format!(
-                "mutation payload supports only '{{\"{HOOK_MUTATION_PAYLOAD_METADATA_KEY}\": {{...}}}}'; found keys: {keys:?}"
+        // The child rewrites the markers agent tooling uses to find the active loop.
+        let loop_id_marker = parent_ctx.ralph_dir().join("current-loop-id");
+        let saved_markers = [
+            (loop_id_marker.clone(), fs::read(&loop_id_marker).ok()),
+            (
+                parent_ctx.current_events_marker(),
+                fs::read(parent_ctx.current_events_marker()).ok(),
             ),
-        });
-    }
+        ];
 
-    let Some(metadata) = payload_object
-        .get(HOOK_MUTATION_PAYLOAD_METADATA_KEY)
-        .and_then(serde_json::Value::as_object)
-        .cloned()
-    else {
-        return HookMutationParseOutcome::Invalid(HookMutationParseError::InvalidSchema {
-            message: "mutation payload key 'metadata' must contain a JSON object".to_string(),
+        let parent_output = (tui_lines.is_some() || rpc_stdout.is_some()).then_some(ParentOutput {
+            tui_lines,
+            rpc_stdout,
         });
-    };
+        let result = run_loop(
+            child_config,
+            color_mode,
+            false,
+            false,
+            false,
+            verbosity,
+            None,
+            Some(child_ctx.clone()),
+            Vec::new(),
+            None,
+            None,
+            parent_output,
+        )
+        .await;
 
-    let mut namespaced_metadata = serde_json::Map::new();
-    if let Err(error) = merge_hook_metadata_namespace(&mut namespaced_metadata, hook_name, metadata)
-    {
-        return HookMutationParseOutcome::Invalid(error);
-    }
+        for (path, contents) in saved_markers {
+            if let Some(contents) = contents
+                && let Err(e) = fs::write(&path, contents)
+            {
+                warn!(path = ?path, error = %e, "Failed to restore marker after sub-loop");
+            }
+        }
 
-    HookMutationParseOutcome::Parsed {
-        namespaced_metadata,
-    }
-}
+        let reason = match result {
+            Ok(reason) => reason,
+            Err(e) => return Ok(failed(format!("Sub-loop '{}' failed: {:#}", child_id, e))),
+        };
 
-fn merge_hook_metadata_namespace(
-    accumulated_metadata: &mut serde_json::Map<String, serde_json::Value>,
-    hook_name: &str,
-    metadata: serde_json::Map<String, serde_json::Value>,
-) -> std::result::Result<(), HookMutationParseError> {
-    if hook_name.trim().is_empty() {
-        return Err(HookMutationParseError::InvalidSchema {
-            message: "hook metadata namespace requires non-empty hook name".to_string(),
-        });
-    }
+        let summary = LoopHistory::new(child_ctx.history_path())
+            .summary()
+            .unwrap_or_default();
+        let usage =
+            summary
+                .hat_usage
+                .values()
+                .fold(ralph_core::HatUsage::default(), |mut total, usage| {
+                    total.add(*usage);
+                    total
+                });
+        let completed = reason.is_success();
 
-    let namespace = accumulated_metadata
-        .entry(HOOK_MUTATION_METADATA_NAMESPACE_KEY.to_string())
-        .or_insert_with(|| serde_json::Value::Object(serde_json::Map::new()));
+        if let Some(history) = parent_history
+            && let Err(e) = history.record_subloop_completed(
+                iteration,
+                hat,
+                &child_id,
+                reason.as_str(),
+                summary.iterations_completed,
+                usage.cost_usd,
+            )
+        {
+            warn!("Failed to record sub-loop completion in history: {}", e);
+        }
 
-    let Some(namespace_object) = namespace.as_object_mut() else {
-        return Err(HookMutationParseError::InvalidSchema {
-            message: format!(
-                "metadata namespace '{HOOK_MUTATION_METADATA_NAMESPACE_KEY}' must be a JSON object"
-            ),
+        let topic = subloop.result_topic(completed);
+        let event = serde_json::json!({
+            "topic": topic,
+            "payload": {
+                "loop_id": child_id,
+                "reason": reason.as_str(),
+                "iterations": summary.iterations_completed,
+                "cost_usd": usage.cost_usd,
+            },
+            "ts": chrono::Utc::now().to_rfc3339(),
         });
-    };
-
-    namespace_object.insert(hook_name.to_string(), serde_json::Value::Object(metadata));
-    Ok(())
-}
+        let events_path = resolve_current_events_path(parent_ctx);
+        {
+            use std::io::Write;
+            let mut file = fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&events_path)
+                .with_context(|| format!("Failed to open events file: {:?}", events_path))?;
+            writeln!(file, "{}", event)
+                .with_context(|| format!("Failed to write sub-loop result to {:?}", events_path))?;
+        }
 
-fn merge_namespaced_hook_metadata(
-    accumulated_metadata: &mut serde_json::Map<String, serde_json::Value>,
-    namespaced_metadata: &serde_json::Map<String, serde_json::Value>,
-) -> std::result::Result<(), HookMutationParseError> {
-    let Some(namespace_object) = namespaced_metadata
-        .get(HOOK_MUTATION_METADATA_NAMESPACE_KEY)
-        .and_then(serde_json::Value::as_object)
-    else {
-        return Err(HookMutationParseError::InvalidSchema {
-            message: format!(
-                "parsed mutation metadata must contain object key '{HOOK_MUTATION_METADATA_NAMESPACE_KEY}'"
+        Ok(ExecutionOutcome {
+            output: format!(
+                "Sub-loop {} stopped ({}) after {} iterations; published {}",
+                child_id,
+                reason.as_str(),
+                summary.iterations_completed,
+                topic
             ),
-        });
-    };
-
-    for (hook_name, metadata_value) in namespace_object {
-        let Some(metadata_object) = metadata_value.as_object().cloned() else {
-            return Err(HookMutationParseError::InvalidSchema {
-                message: format!(
-                    "parsed metadata entry for hook '{hook_name}' must be a JSON object"
-                ),
-            });
-        };
-
-        merge_hook_metadata_namespace(accumulated_metadata, hook_name, metadata_object)?;
-    }
+            success: true,
+            termination: None,
+            total_cost_usd: usage.cost_usd,
+            input_tokens: 0,
+            output_tokens: usage.tokens,
+            cache_read_tokens: 0,
+            cache_write_tokens: 0,
+            failure: None,
+        })
+    })
+}
 
-    Ok(())
+/// A fan-out branch ready to run: the branch hat's prompt and backend.
+struct FanOutBranch {
+    hat: String,
+    prompt: String,
+    backend: CliBackend,
+    idle_timeout: Duration,
 }
 
-fn merge_accumulated_hook_metadata_from_outcomes(
-    accumulated_hook_metadata: &mut serde_json::Map<String, serde_json::Value>,
-    outcomes: &[HookDispatchOutcome],
-) {
-    for outcome in outcomes {
-        let HookMutationParseOutcome::Parsed {
-            namespaced_metadata,
-        } = &outcome.mutation_parse_outcome
-        else {
-            continue;
-        };
+/// Where a fan-out reports each branch's progress as it starts and finishes.
+struct FanOutProgress {
+    tui_state: Option<Arc<std::sync::Mutex<ralph_tui::TuiState>>>,
+    rpc_event_tx: Option<tokio::sync::mpsc::Sender<RpcEvent>>,
+}
 
-        if let Err(error) =
-            merge_namespaced_hook_metadata(accumulated_hook_metadata, namespaced_metadata)
-        {
-            warn!(
-                phase_event = %outcome.phase_event,
-                hook_name = %outcome.hook_name,
-                error = ?error,
-                "Failed to merge parsed hook mutation metadata; ignoring mutation output"
-            );
+impl FanOutProgress {
+    fn report(&self, iteration: u32, hat: &str, status: &str, usage: HatUsage) {
+        if let Some(mut state) = self.tui_state.as_ref().and_then(|s| s.lock().ok()) {
+            state.update_fan_out_branch(ralph_tui::state::FanOutBranchStatus::new(
+                hat,
+                status,
+                usage.cost_usd,
+                usage.tokens,
+            ));
+        }
+        if let Some(tx) = &self.rpc_event_tx {
+            let _ = tx.try_send(RpcEvent::FanOutBranch {
+                iteration,
+                hat: hat.to_string(),
+                status: status.to_string(),
+                cost_usd: usage.cost_usd,
+                tokens: usage.tokens,
+            });
         }
     }
 }
 
-fn mutation_parse_failure(
-    mutation_parse_outcome: &HookMutationParseOutcome,
-) -> Option<HookDispatchFailure> {
-    let HookMutationParseOutcome::Invalid(error) = mutation_parse_outcome else {
-        return None;
-    };
+/// Collects a branch's raw output so it can still be read if the branch times out.
+#[derive(Clone, Default)]
+struct BranchOutputBuffer(Arc<std::sync::Mutex<Vec<u8>>>);
 
-    Some(HookDispatchFailure::InvalidMutationOutput {
-        message: format_hook_mutation_parse_error(error),
-    })
+impl BranchOutputBuffer {
+    fn contents(&self) -> String {
+        self.0
+            .lock()
+            .map(|buf| String::from_utf8_lossy(&buf).into_owned())
+            .unwrap_or_default()
+    }
 }
 
-fn max_retry_attempts_for_suspend_mode(suspend_mode: HookSuspendMode) -> u32 {
-    match suspend_mode {
-        HookSuspendMode::WaitForResume => 1,
-        HookSuspendMode::RetryBackoff => RETRY_BACKOFF_DELAYS_MS.len() as u32 + 1,
-        HookSuspendMode::WaitThenRetry => 2,
+impl std::io::Write for BranchOutputBuffer {
+    fn write(&mut self, data: &[u8]) -> std::io::Result<usize> {
+        if let Ok(mut buf) = self.0.lock() {
+            buf.extend_from_slice(data);
+        }
+        Ok(data.len())
     }
-}
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum SuspendWaitOutcome {
-    Resume,
-    Stop,
-    Restart,
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
 }
 
-#[derive(Debug, Clone, PartialEq)]
-struct HookDispatchOutcome {
-    phase_event: HookPhaseEvent,
-    hook_name: String,
-    disposition: HookDisposition,
-    suspend_mode: HookSuspendMode,
-    failure: Option<HookDispatchFailure>,
-    mutation_parse_outcome: HookMutationParseOutcome,
-}
+/// Runs a hat's fan-out branches concurrently and joins them into one event.
+///
+/// Each branch publishes into its own events file (via `RALPH_EVENTS_FILE`),
+/// optionally inside a throwaway worktree. Once every branch has finished or
+/// timed out, `join` is appended to the loop's events file with the branches'
+/// events and usage. A timed-out branch keeps the usage reported in the output
+/// it streamed before being stopped. Returns the outcomes so usage is
+/// attributed per branch.
+#[allow(clippy::too_many_arguments)]
+async fn execute_fan_out(
+    fan_out: &FanOutConfig,
+    branches: &[FanOutBranch],
+    ctx: &LoopContext,
+    loop_id: &str,
+    iteration: u32,
+    hat: &str,
+    verbosity: Verbosity,
+    tui_lines: Option<Arc<std::sync::Mutex<Vec<ratatui::text::Line<'static>>>>>,
+    rpc_stdout: Option<Arc<std::sync::Mutex<std::io::Stdout>>>,
+    progress: &FanOutProgress,
+) -> Result<(ExecutionOutcome, Vec<BranchOutcome>)> {
+    let fan_out_id = format!("{}-fan-{}-{}", loop_id, hat, iteration);
+    let fan_out_dir = ctx.ralph_dir().join("fan-out").join(&fan_out_id);
+    fs::create_dir_all(&fan_out_dir)
+        .with_context(|| format!("Failed to create fan-out directory: {:?}", fan_out_dir))?;
+
+    let mut tasks = tokio::task::JoinSet::new();
+    for (index, branch) in branches.iter().enumerate() {
+        progress.report(iteration, &branch.hat, "running", HatUsage::default());
+        let events_path = fan_out_dir.join(format!("{}.jsonl", branch.hat));
+        let worktree = match fan_out.isolation {
+            FanOutIsolation::Shared => None,
+            FanOutIsolation::Worktree => {
+                let worktree_id = format!("{}-{}", fan_out_id, branch.hat);
+                match ralph_core::worktree::create_worktree(
+                    ctx.repo_root(),
+                    &worktree_id,
+                    &ralph_core::worktree::WorktreeConfig::default(),
+                ) {
+                    Ok(worktree) => Some(worktree.path),
+                    Err(e) => {
+                        let outcome = BranchOutcome::collect(
+                            &branch.hat,
+                            BranchStatus::Failed,
+                            &events_path,
+                            HatUsage::default(),
+                            &format!("Failed to create worktree: {}", e),
+                        );
+                        tasks.spawn(async move { (index, outcome) });
+                        continue;
+                    }
+                }
+            }
+        };
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-enum HookDispatchFailure {
-    HookRunFailed {
-        exit_code: Option<i32>,
-        timed_out: bool,
-    },
-    HookExecutionError {
-        message: String,
-    },
-    InvalidMutationOutput {
-        message: String,
-    },
-}
+        let mut backend = branch.backend.clone();
+        backend.env_vars.push((
+            "RALPH_EVENTS_FILE".to_string(),
+            events_path.to_string_lossy().to_string(),
+        ));
+        let mut executor = CliExecutor::new(backend);
+        if let Some(dir) = &worktree {
+            executor = executor.with_working_dir(dir);
+        }
+        let prompt = branch.prompt.clone();
+        let hat = branch.hat.clone();
+        let idle_timeout = branch.idle_timeout;
+        let timeout = fan_out.timeout();
+        let output_format = branch.backend.output_format;
+        let repo_root = ctx.repo_root().to_path_buf();
+
+        tasks.spawn(async move {
+            if output_format == BackendOutputFormat::Acp {
+                let outcome = BranchOutcome::collect(
+                    &hat,
+                    BranchStatus::Failed,
+                    &events_path,
+                    HatUsage::default(),
+                    "ACP backends cannot run as fan-out branches",
+                );
+                return (index, outcome);
+            }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum RetryBackoffDelayOutcome {
-    Elapsed,
-    StopRequested,
-    RestartRequested,
-}
+            let buffer = BranchOutputBuffer::default();
+            let run = executor.execute(&prompt, buffer.clone(), Some(idle_timeout), false);
+            let result = match timeout {
+                Some(limit) => tokio::time::timeout(limit, run).await.ok(),
+                None => Some(run.await),
+            };
+            let outcome = match result {
+                Some(Ok(result)) => {
+                    let (cost_usd, tokens) =
+                        extract_cli_stream_usage(output_format, &result.output);
+                    let status = if result.success {
+                        BranchStatus::Completed
+                    } else {
+                        BranchStatus::Failed
+                    };
+                    BranchOutcome::collect(
+                        &hat,
+                        status,
+                        &events_path,
+                        HatUsage {
+                            cost_usd,
+                            tokens,
+                            iterations: 1,
+                        },
+                        &normalize_cli_output_for_parsing(output_format, &result.output),
+                    )
+                }
+                Some(Err(e)) => BranchOutcome::collect(
+                    &hat,
+                    BranchStatus::Failed,
+                    &events_path,
+                    HatUsage::default(),
+                    &format!("Failed to run branch: {}", e),
+                ),
+                None => {
+                    let partial = buffer.contents();
+                    let (cost_usd, tokens) = extract_cli_stream_usage(output_format, &partial);
+                    BranchOutcome::collect(
+                        &hat,
+                        BranchStatus::TimedOut,
+                        &events_path,
+                        HatUsage {
+                            cost_usd,
+                            tokens,
+                            iterations: 1,
+                        },
+                        &normalize_cli_output_for_parsing(output_format, &partial),
+                    )
+                }
+            };
 
-fn dispatch_phase_event_hooks(
-    event_loop: &EventLoop,
-    hooks_enabled: bool,
-    loop_id: &str,
-    hook_engine: &HookEngine,
-    hook_executor: &HookExecutor,
-    phase_event: HookPhaseEvent,
-    payload_input: HookPayloadBuilderInput,
-) -> Vec<HookDispatchOutcome> {
-    if !hooks_enabled {
-        return Vec::new();
+            if let Some(dir) = worktree
+                && let Err(e) = ralph_core::worktree::remove_worktree(&repo_root, &dir)
+            {
+                warn!(path = ?dir, error = %e, "Failed to remove fan-out worktree");
+            }
+            (index, outcome)
+        });
     }
 
-    let resolved_hooks = hook_engine.resolve_phase_event(phase_event);
-    if resolved_hooks.is_empty() {
-        return Vec::new();
+    let mut indexed: Vec<(usize, BranchOutcome)> = Vec::with_capacity(branches.len());
+    while let Some(joined) = tasks.join_next().await {
+        let (index, outcome) = joined.context("Fan-out branch panicked")?;
+        progress.report(
+            iteration,
+            &outcome.hat,
+            outcome.status.as_str(),
+            outcome.usage,
+        );
+        indexed.push((index, outcome));
     }
+    indexed.sort_by_key(|(index, _)| *index);
+    let outcomes: Vec<BranchOutcome> = indexed.into_iter().map(|(_, outcome)| outcome).collect();
 
-    let workspace_root = payload_input.workspace.clone();
-    let payload = hook_engine.build_payload(phase_event, payload_input);
-    let stdin_payload = match serde_json::to_value(&payload) {
-        Ok(value) => value,
-        Err(error) => {
-            warn!(
-                phase_event = %phase_event,
-                error = %error,
-                "Failed to serialize lifecycle hook payload; skipping phase-event dispatch"
-            );
-            return Vec::new();
+    let event = serde_json::json!({
+        "topic": fan_out.join,
+        "payload": join_payload(&outcomes),
+        "ts": chrono::Utc::now().to_rfc3339(),
+    });
+    let events_path = resolve_current_events_path(ctx);
+    {
+        use std::io::Write;
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&events_path)
+            .with_context(|| format!("Failed to open events file: {:?}", events_path))?;
+        writeln!(file, "{}", event)
+            .with_context(|| format!("Failed to write fan-out join to {:?}", events_path))?;
+    }
+
+    let mut summary = String::new();
+    for outcome in &outcomes {
+        let topics: Vec<&str> = outcome.events.iter().map(|e| e.topic.as_str()).collect();
+        summary.push_str(&format!(
+            "── {} · {} · ${:.4} · {} tokens · [{}]\n",
+            outcome.hat,
+            outcome.status.as_str(),
+            outcome.usage.cost_usd,
+            outcome.usage.tokens,
+            topics.join(", ")
+        ));
+        let tail = outcome.output_tail.trim();
+        if !tail.is_empty() {
+            summary.push_str(tail);
+            summary.push('\n');
         }
-    };
-
-    let mut outcomes = Vec::with_capacity(resolved_hooks.len());
-
-    for hook in resolved_hooks {
-        let hook_name = hook.name.clone();
-        let phase_event_key = hook.phase_event.as_str().to_string();
-
-        let request = HookRunRequest {
-            phase_event: phase_event_key.clone(),
-            hook_name: hook_name.clone(),
-            command: hook.command.clone(),
-            workspace_root: workspace_root.clone(),
-            cwd: hook.cwd.clone(),
-            env: hook.env.clone(),
-            timeout_seconds: hook.timeout_seconds,
-            max_output_bytes: hook.max_output_bytes,
-            stdin_payload: stdin_payload.clone(),
-        };
-
-        let outcome = dispatch_hook_with_suspend_policy(
-            event_loop,
-            hook_executor,
-            loop_id,
-            &phase_event_key,
-            hook.phase_event,
-            &hook_name,
-            hook.on_error,
-            hook.suspend_mode,
-            &hook.mutate,
-            &request,
-        );
-        outcomes.push(outcome);
-    }
-
-    outcomes
-}
-
-#[allow(clippy::too_many_arguments)]
-fn dispatch_hook_with_suspend_policy(
-    event_loop: &EventLoop,
-    hook_executor: &HookExecutor,
-    loop_id: &str,
-    phase_event_key: &str,
-    phase_event: HookPhaseEvent,
-    hook_name: &str,
-    on_error: HookOnError,
-    suspend_mode: HookSuspendMode,
-    mutate: &HookMutationConfig,
-    request: &HookRunRequest,
-) -> HookDispatchOutcome {
-    let retry_max_attempts = max_retry_attempts_for_suspend_mode(suspend_mode);
-    let outcome = execute_hook_attempt(
-        event_loop,
-        hook_executor,
-        loop_id,
-        phase_event_key,
-        phase_event,
-        hook_name,
-        on_error,
-        suspend_mode,
-        mutate,
-        1,
-        retry_max_attempts,
-        request,
-    );
-
-    if outcome.disposition != HookDisposition::Suspend {
-        return outcome;
-    }
-
-    match suspend_mode {
-        HookSuspendMode::WaitForResume => outcome,
-        HookSuspendMode::RetryBackoff => dispatch_retry_backoff_suspend_policy(
-            event_loop,
-            hook_executor,
-            loop_id,
-            phase_event_key,
-            phase_event,
-            hook_name,
-            on_error,
-            suspend_mode,
-            mutate,
-            retry_max_attempts,
-            request,
-            outcome,
-        ),
-        HookSuspendMode::WaitThenRetry => dispatch_wait_then_retry_suspend_policy(
-            event_loop,
-            hook_executor,
-            loop_id,
-            phase_event_key,
-            phase_event,
-            hook_name,
-            on_error,
-            suspend_mode,
-            mutate,
-            retry_max_attempts,
-            request,
-            outcome,
-        ),
-    }
-}
-
-#[allow(clippy::too_many_arguments)]
-fn dispatch_retry_backoff_suspend_policy(
-    event_loop: &EventLoop,
-    hook_executor: &HookExecutor,
-    loop_id: &str,
-    phase_event_key: &str,
-    phase_event: HookPhaseEvent,
-    hook_name: &str,
-    on_error: HookOnError,
-    suspend_mode: HookSuspendMode,
-    mutate: &HookMutationConfig,
-    retry_max_attempts: u32,
-    request: &HookRunRequest,
-    outcome: HookDispatchOutcome,
-) -> HookDispatchOutcome {
-    run_retry_backoff_policy(
-        phase_event_key,
-        hook_name,
-        &RETRY_BACKOFF_DELAYS_MS,
-        |backoff_delay, _retry_attempt| {
-            wait_for_retry_backoff_delay_with_signal_poll(
-                request.workspace_root.as_path(),
-                backoff_delay,
-            )
-        },
-        |retry_attempt| {
-            execute_hook_attempt(
-                event_loop,
-                hook_executor,
-                loop_id,
-                phase_event_key,
-                phase_event,
-                hook_name,
-                on_error,
-                suspend_mode,
-                mutate,
-                retry_attempt,
-                retry_max_attempts,
-                request,
-            )
-        },
-        outcome,
-    )
-}
-
-#[allow(clippy::too_many_arguments)]
-fn dispatch_wait_then_retry_suspend_policy(
-    event_loop: &EventLoop,
-    hook_executor: &HookExecutor,
-    loop_id: &str,
-    phase_event_key: &str,
-    phase_event: HookPhaseEvent,
-    hook_name: &str,
-    on_error: HookOnError,
-    suspend_mode: HookSuspendMode,
-    mutate: &HookMutationConfig,
-    retry_max_attempts: u32,
-    request: &HookRunRequest,
-    outcome: HookDispatchOutcome,
-) -> HookDispatchOutcome {
-    let suspend_state_store = SuspendStateStore::new(&request.workspace_root);
-    let reason = format_suspending_hook_reason(&outcome);
-    let suspend_state = SuspendStateRecord::new(
-        loop_id,
-        phase_event,
-        hook_name,
-        reason,
-        suspend_mode,
-        chrono::Utc::now(),
-    );
-
-    if let Err(error) = suspend_state_store.write_suspend_state(&suspend_state) {
-        warn!(
-            phase_event = %phase_event_key,
-            hook_name = %hook_name,
-            error = %error,
-            "Failed to persist suspend-state for wait_then_retry; deferring to standard suspend handling"
-        );
-        return outcome;
     }
+    summary.push_str(&format!("Published {}\n", fan_out.join));
 
-    warn!(
-        phase_event = %phase_event_key,
-        hook_name = %hook_name,
-        "Lifecycle hook requested suspend(wait_then_retry); entering wait-for-resume gate before single retry"
-    );
+    let mut handler: Box<dyn StreamHandler> = if let Some(lines) = tui_lines {
+        Box::new(TuiStreamHandler::with_lines(
+            verbosity == Verbosity::Verbose,
+            lines,
+        ))
+    } else if let Some(stdout_writer) = rpc_stdout {
+        Box::new(JsonRpcStreamHandler::new(
+            stdout_writer,
+            iteration,
+            Some(hat.to_string()),
+            None,
+        ))
+    } else if verbosity == Verbosity::Quiet {
+        Box::new(QuietStreamHandler)
+    } else {
+        Box::new(ConsoleStreamHandler::new(verbosity == Verbosity::Verbose))
+    };
+    handler.on_text(&summary);
 
-    run_wait_then_retry_policy(
-        phase_event_key,
-        hook_name,
-        || wait_for_suspend_signal_with_poll(&suspend_state_store),
-        || {
-            suspend_state_store
-                .clear_suspend_state()
-                .context("Failed to clear wait_then_retry suspend-state after resume")?;
-            Ok(())
-        },
-        || {
-            execute_hook_attempt(
-                event_loop,
-                hook_executor,
-                loop_id,
-                phase_event_key,
-                phase_event,
-                hook_name,
-                on_error,
-                suspend_mode,
-                mutate,
-                2,
-                retry_max_attempts,
-                request,
-            )
-        },
-        outcome,
-    )
+    let total = outcomes
+        .iter()
+        .fold(HatUsage::default(), |mut total, outcome| {
+            total.add(outcome.usage);
+            total
+        });
+    let outcome = ExecutionOutcome {
+        output: summary,
+        success: outcomes
+            .iter()
+            .any(|outcome| outcome.status == BranchStatus::Completed),
+        termination: None,
+        total_cost_usd: total.cost_usd,
+        input_tokens: 0,
+        output_tokens: total.tokens,
+        cache_read_tokens: 0,
+        cache_write_tokens: 0,
+        failure: None,
+    };
+    Ok((outcome, outcomes))
 }
 
-fn run_retry_backoff_policy<FWaitForDelay, FRunRetryAttempt>(
-    phase_event_key: &str,
-    hook_name: &str,
-    backoff_delays_ms: &[u64],
-    mut wait_for_delay: FWaitForDelay,
-    mut run_retry_attempt: FRunRetryAttempt,
-    mut outcome: HookDispatchOutcome,
-) -> HookDispatchOutcome
-where
-    FWaitForDelay: FnMut(Duration, usize) -> RetryBackoffDelayOutcome,
-    FRunRetryAttempt: FnMut(u32) -> HookDispatchOutcome,
-{
-    for (retry_attempt, backoff_delay_ms) in backoff_delays_ms.iter().copied().enumerate() {
-        match wait_for_delay(Duration::from_millis(backoff_delay_ms), retry_attempt + 1) {
-            RetryBackoffDelayOutcome::Elapsed => {}
-            RetryBackoffDelayOutcome::StopRequested => {
-                info!(
-                    phase_event = %phase_event_key,
-                    hook_name = %hook_name,
-                    retry_attempt = retry_attempt + 1,
-                    "Stop requested while waiting for retry_backoff retry; deferring to suspend termination handling"
-                );
-                break;
-            }
-            RetryBackoffDelayOutcome::RestartRequested => {
-                info!(
-                    phase_event = %phase_event_key,
-                    hook_name = %hook_name,
-                    retry_attempt = retry_attempt + 1,
-                    "Restart requested while waiting for retry_backoff retry; deferring to suspend termination handling"
-                );
-                break;
+/// Sums the cost and tokens reported in a Claude or Pi stream-json transcript.
+///
+/// Works on partial transcripts: Claude reports tokens per assistant message
+/// and cost only in its final result, while Pi reports both per turn. Other
+/// output formats don't report usage in headless mode.
+fn extract_cli_stream_usage(output_format: BackendOutputFormat, raw_output: &str) -> (f64, u64) {
+    let mut cost_usd = 0.0;
+    let mut tokens = 0;
+    match output_format {
+        BackendOutputFormat::StreamJson => {
+            for line in raw_output.lines() {
+                match ClaudeStreamParser::parse_line(line) {
+                    Some(ClaudeStreamEvent::Assistant {
+                        usage: Some(usage), ..
+                    }) => tokens += usage.input_tokens + usage.output_tokens,
+                    Some(ClaudeStreamEvent::Result { total_cost_usd, .. }) => {
+                        cost_usd += total_cost_usd;
+                    }
+                    _ => {}
+                }
             }
         }
-
-        outcome = run_retry_attempt(retry_attempt as u32 + 2);
-
-        if outcome.disposition == HookDisposition::Pass {
-            info!(
-                phase_event = %phase_event_key,
-                hook_name = %hook_name,
-                retry_attempt = retry_attempt + 1,
-                "Lifecycle hook recovered under retry_backoff"
-            );
-            return outcome;
-        }
-
-        if outcome.disposition != HookDisposition::Suspend {
-            return outcome;
-        }
-    }
-
-    warn!(
-        phase_event = %phase_event_key,
-        hook_name = %hook_name,
-        retry_attempts = backoff_delays_ms.len(),
-        "Lifecycle hook retry_backoff policy exhausted; entering suspended wait_for_resume fallback"
-    );
-
-    outcome
-}
-
-fn run_wait_then_retry_policy<FWaitForSignal, FClearSuspendState, FRunRetryAttempt>(
-    phase_event_key: &str,
-    hook_name: &str,
-    mut wait_for_signal: FWaitForSignal,
-    mut clear_suspend_state: FClearSuspendState,
-    mut run_retry_attempt: FRunRetryAttempt,
-    outcome: HookDispatchOutcome,
-) -> HookDispatchOutcome
-where
-    FWaitForSignal: FnMut() -> Result<SuspendWaitOutcome>,
-    FClearSuspendState: FnMut() -> Result<()>,
-    FRunRetryAttempt: FnMut() -> HookDispatchOutcome,
-{
-    let wait_outcome = match wait_for_signal() {
-        Ok(wait_outcome) => wait_outcome,
-        Err(error) => {
-            warn!(
-                phase_event = %phase_event_key,
-                hook_name = %hook_name,
-                error = %error,
-                "wait_then_retry gate failed while polling suspend signals; deferring to standard suspend handling"
-            );
-            return outcome;
-        }
-    };
-
-    match wait_outcome {
-        SuspendWaitOutcome::Stop => {
-            info!(
-                phase_event = %phase_event_key,
-                hook_name = %hook_name,
-                "Stop requested while waiting under wait_then_retry; deferring to suspend termination handling"
-            );
-            outcome
-        }
-        SuspendWaitOutcome::Restart => {
-            info!(
-                phase_event = %phase_event_key,
-                hook_name = %hook_name,
-                "Restart requested while waiting under wait_then_retry; deferring to suspend termination handling"
-            );
-            outcome
-        }
-        SuspendWaitOutcome::Resume => {
-            if let Err(error) = clear_suspend_state() {
-                warn!(
-                    phase_event = %phase_event_key,
-                    hook_name = %hook_name,
-                    error = %error,
-                    "Failed to clear wait_then_retry suspend-state after resume; deferring to standard suspend handling"
-                );
-                return outcome;
-            }
-
-            let retry_outcome = run_retry_attempt();
-
-            if retry_outcome.disposition == HookDisposition::Pass {
-                info!(
-                    phase_event = %phase_event_key,
-                    hook_name = %hook_name,
-                    "Lifecycle hook recovered under wait_then_retry"
-                );
+        BackendOutputFormat::PiStreamJson => {
+            for line in raw_output.lines() {
+                if let Some(PiStreamEvent::TurnEnd {
+                    message:
+                        Some(PiTurnMessage {
+                            usage: Some(usage), ..
+                        }),
+                }) = PiStreamParser::parse_line(line)
+                {
+                    tokens += usage.input + usage.output;
+                    cost_usd += usage.cost.map_or(0.0, |cost| cost.total);
+                }
             }
-
-            retry_outcome
         }
+        _ => {}
     }
+    (cost_usd, tokens)
 }
 
-#[allow(clippy::too_many_arguments)]
-fn execute_hook_attempt(
-    event_loop: &EventLoop,
-    hook_executor: &HookExecutor,
-    loop_id: &str,
-    phase_event_key: &str,
-    phase_event: HookPhaseEvent,
-    hook_name: &str,
-    on_error: HookOnError,
-    suspend_mode: HookSuspendMode,
-    mutate: &HookMutationConfig,
-    retry_attempt: u32,
-    retry_max_attempts: u32,
-    request: &HookRunRequest,
-) -> HookDispatchOutcome {
-    match hook_executor.run(request.clone()) {
-        Ok(run_result) => {
-            let run_disposition = classify_hook_disposition(on_error, &run_result);
-            let mutation_parse_outcome =
-                parse_hook_mutation_stdout(mutate, hook_name, &run_result.stdout.content);
-            let mutation_failure = if run_disposition == HookDisposition::Pass {
-                mutation_parse_failure(&mutation_parse_outcome)
-            } else {
-                None
-            };
-
-            let disposition = if mutation_failure.is_some() {
-                disposition_from_on_error(on_error)
-            } else {
-                run_disposition
-            };
-
-            let failure = if let Some(mutation_failure) = mutation_failure {
-                Some(mutation_failure)
-            } else if run_disposition == HookDisposition::Pass {
-                None
-            } else {
-                Some(HookDispatchFailure::HookRunFailed {
-                    exit_code: run_result.exit_code,
-                    timed_out: run_result.timed_out,
-                })
-            };
-
-            event_loop.log_hook_run_telemetry(HookRunTelemetryEntry::from_run_result(
-                loop_id,
-                phase_event_key,
-                hook_name,
-                disposition,
-                suspend_mode,
-                retry_attempt,
-                retry_max_attempts,
-                &run_result,
-            ));
+/// Execute a prompt via ACP (Agent Client Protocol) for kiro-acp backend.
+async fn execute_acp(
+    backend: &CliBackend,
+    config: &RalphConfig,
+    prompt: &str,
+    verbosity: Verbosity,
+    tui_lines: Option<Arc<std::sync::Mutex<Vec<ratatui::text::Line<'static>>>>>,
+    rpc_stdout: Option<Arc<std::sync::Mutex<std::io::Stdout>>>,
+    iteration: u32,
+    hat: &str,
+    backend_name: &str,
+) -> Result<ExecutionOutcome> {
+    let executor = AcpExecutor::new(backend.clone(), config.core.workspace_root.clone());
 
-            if disposition == HookDisposition::Pass {
-                debug!(
-                    phase_event = %phase_event_key,
-                    hook_name = %hook_name,
-                    duration_ms = run_result.duration_ms,
-                    "Lifecycle hook executed successfully"
-                );
-            } else {
-                let failure_detail = format_hook_failure_detail(failure.as_ref());
-                warn!(
-                    phase_event = %phase_event_key,
-                    hook_name = %hook_name,
-                    disposition = ?disposition,
-                    exit_code = ?run_result.exit_code,
-                    timed_out = run_result.timed_out,
-                    failure = %failure_detail,
-                    "Lifecycle hook returned non-pass disposition; continuing"
-                );
+    let pty_result = if let Some(lines) = tui_lines {
+        let mut handler = TuiStreamHandler::with_lines(verbosity == Verbosity::Verbose, lines);
+        executor.execute(prompt, &mut handler).await?
+    } else if let Some(stdout_writer) = rpc_stdout {
+        let mut handler = JsonRpcStreamHandler::new(
+            stdout_writer,
+            iteration,
+            Some(hat.to_string()),
+            Some(backend_name.to_string()),
+        );
+        executor.execute(prompt, &mut handler).await?
+    } else {
+        match verbosity {
+            Verbosity::Quiet => {
+                let mut handler = QuietStreamHandler;
+                executor.execute(prompt, &mut handler).await?
             }
-
-            HookDispatchOutcome {
-                phase_event,
-                hook_name: hook_name.to_string(),
-                disposition,
-                suspend_mode,
-                failure,
-                mutation_parse_outcome,
+            Verbosity::Normal => {
+                let mut handler = ConsoleStreamHandler::new(false);
+                executor.execute(prompt, &mut handler).await?
+            }
+            Verbosity::Verbose => {
+                let mut handler = ConsoleStreamHandler::new(true);
+                executor.execute(prompt, &mut handler).await?
             }
         }
-        Err(error) => {
-            let disposition = disposition_from_on_error(on_error);
+    };
 
-            warn!(
-                phase_event = %phase_event_key,
-                hook_name = %hook_name,
-                disposition = ?disposition,
-                error = %error,
-                "Lifecycle hook execution failed; continuing"
-            );
+    let failure = pty_result.failure_class();
+    let output = if pty_result.extracted_text.is_empty() {
+        pty_result.stripped_output
+    } else {
+        pty_result.extracted_text
+    };
 
-            HookDispatchOutcome {
-                phase_event,
-                hook_name: hook_name.to_string(),
-                disposition,
-                suspend_mode,
-                failure: Some(HookDispatchFailure::HookExecutionError {
-                    message: error.to_string(),
-                }),
-                mutation_parse_outcome: HookMutationParseOutcome::Disabled,
-            }
-        }
-    }
+    Ok(ExecutionOutcome {
+        output,
+        success: pty_result.success,
+        termination: None,
+        failure,
+        total_cost_usd: pty_result.total_cost_usd,
+        input_tokens: pty_result.input_tokens,
+        output_tokens: pty_result.output_tokens,
+        cache_read_tokens: pty_result.cache_read_tokens,
+        cache_write_tokens: pty_result.cache_write_tokens,
+    })
 }
 
-fn wait_for_retry_backoff_delay_with_signal_poll(
-    workspace_root: &Path,
-    backoff_delay: Duration,
-) -> RetryBackoffDelayOutcome {
-    if backoff_delay.is_zero() {
-        return RetryBackoffDelayOutcome::Elapsed;
-    }
+async fn execute_pty(
+    executor: Option<&mut PtyExecutor>,
+    backend: &CliBackend,
+    config: &RalphConfig,
+    prompt: &str,
+    interactive: bool,
+    interrupt_rx: tokio::sync::watch::Receiver<bool>,
+    verbosity: Verbosity,
+    tui_lines: Option<Arc<std::sync::Mutex<Vec<ratatui::text::Line<'static>>>>>,
+    rpc_stdout: Option<Arc<std::sync::Mutex<std::io::Stdout>>>,
+    iteration: u32,
+    hat: &str,
+    backend_name: &str,
+) -> Result<ExecutionOutcome> {
+    use crossterm::terminal::{disable_raw_mode, enable_raw_mode};
 
-    let poll_interval = Duration::from_millis(RETRY_BACKOFF_SIGNAL_POLL_INTERVAL_MS);
-    let sleep_started_at = std::time::Instant::now();
+    // Use provided executor or create a new one
+    // If executor is provided, TUI is connected and owns raw mode management
+    let tui_connected = executor.is_some();
+    let mut temp_executor;
+    let exec = if let Some(e) = executor {
+        // Update the executor's backend to use hat-level configuration
+        // This is critical for hat-level backend support - without this update,
+        // the executor would continue using the global backend it was created with
+        e.set_backend(backend.clone());
+        e
+    } else {
+        let idle_timeout_secs = if interactive {
+            config.cli.idle_timeout_secs
+        } else {
+            0
+        };
+        let pty_config = PtyConfig {
+            interactive,
+            idle_timeout_secs,
+            workspace_root: config.core.workspace_root.clone(),
+            ..PtyConfig::from_env()
+        };
+        temp_executor = PtyExecutor::new(backend.clone(), pty_config);
+        &mut temp_executor
+    };
 
-    loop {
-        if is_stop_requested(workspace_root) {
-            return RetryBackoffDelayOutcome::StopRequested;
-        }
+    // Set TUI mode flag when TUI is connected (tui_lines is Some)
+    // This replaces the broken output_rx.is_none() detection in PtyExecutor
+    if tui_lines.is_some() {
+        exec.set_tui_mode(true);
+    }
 
-        if is_restart_requested(workspace_root) {
-            return RetryBackoffDelayOutcome::RestartRequested;
-        }
+    // Enter raw mode for interactive mode to capture keystrokes
+    // Skip if TUI is connected - TUI owns raw mode and will manage it
+    if interactive && !tui_connected {
+        enable_raw_mode().context("Failed to enable raw mode")?;
+    }
 
-        let elapsed = sleep_started_at.elapsed();
-        if elapsed >= backoff_delay {
-            return RetryBackoffDelayOutcome::Elapsed;
+    // Use scopeguard to ensure raw mode is restored on any exit path
+    // Skip if TUI is connected - TUI owns raw mode
+    let _guard = scopeguard::guard((interactive, tui_connected), |(is_interactive, tui)| {
+        if is_interactive && !tui {
+            let _ = disable_raw_mode();
         }
+    });
 
-        let remaining = backoff_delay.saturating_sub(elapsed);
-        std::thread::sleep(std::cmp::min(remaining, poll_interval));
-    }
-}
-
-fn wait_for_suspend_signal_with_poll(
-    suspend_state_store: &SuspendStateStore,
-) -> Result<SuspendWaitOutcome> {
-    let poll_interval = Duration::from_millis(SUSPEND_WAIT_SIGNAL_POLL_INTERVAL_MS);
+    // Run PTY executor with shared interrupt channel
+    let result = if interactive && tui_lines.is_none() && rpc_stdout.is_none() {
+        // Raw interactive mode only when not using TUI or RPC (TUI/RPC handle their own I/O)
+        exec.run_interactive(prompt, interrupt_rx).await
+    } else if let Some(lines) = tui_lines {
+        // TUI mode: use TuiStreamHandler to capture output for TUI display
+        let verbose = verbosity == Verbosity::Verbose;
+        let mut handler = TuiStreamHandler::with_lines(verbose, lines);
+        exec.run_observe_streaming(prompt, interrupt_rx, &mut handler)
+            .await
+    } else if let Some(stdout_writer) = rpc_stdout {
+        // RPC mode: use JsonRpcStreamHandler for JSON-lines output
+        let mut handler = JsonRpcStreamHandler::new(
+            stdout_writer,
+            iteration,
+            Some(hat.to_string()),
+            Some(backend_name.to_string()),
+        );
+        exec.run_observe_streaming(prompt, interrupt_rx, &mut handler)
+            .await
+    } else {
+        // Use streaming handler for non-interactive mode (respects verbosity)
+        // Use PrettyStreamHandler for StreamJson backends (Claude) on TTY for markdown rendering
+        // Use ConsoleStreamHandler for Text format backends (Kiro, Gemini, etc.) for immediate output
+        let use_pretty =
+            backend.output_format == BackendOutputFormat::StreamJson && stdout().is_terminal();
 
-    loop {
-        if is_stop_requested(suspend_state_store.workspace_root()) {
-            return Ok(SuspendWaitOutcome::Stop);
+        match verbosity {
+            Verbosity::Quiet => {
+                let mut handler = QuietStreamHandler;
+                exec.run_observe_streaming(prompt, interrupt_rx, &mut handler)
+                    .await
+            }
+            Verbosity::Normal => {
+                if use_pretty {
+                    let mut handler = PrettyStreamHandler::new(false);
+                    exec.run_observe_streaming(prompt, interrupt_rx, &mut handler)
+                        .await
+                } else {
+                    let mut handler = ConsoleStreamHandler::new(false);
+                    exec.run_observe_streaming(prompt, interrupt_rx, &mut handler)
+                        .await
+                }
+            }
+            Verbosity::Verbose => {
+                if use_pretty {
+                    let mut handler = PrettyStreamHandler::new(true);
+                    exec.run_observe_streaming(prompt, interrupt_rx, &mut handler)
+                        .await
+                } else {
+                    let mut handler = ConsoleStreamHandler::new(true);
+                    exec.run_observe_streaming(prompt, interrupt_rx, &mut handler)
+                        .await
+                }
+            }
         }
+    };
 
-        if is_restart_requested(suspend_state_store.workspace_root()) {
-            return Ok(SuspendWaitOutcome::Restart);
-        }
+    match result {
+        Ok(pty_result) => {
+            // In interactive mode an idle timeout ends the iteration normally.
+            let failure = pty_result
+                .failure_class()
+                .filter(|failure| !(interactive && *failure == FailureClass::Timeout));
+            let termination = convert_termination_type(pty_result.termination, interactive);
 
-        if suspend_state_store
-            .consume_resume_requested()
-            .context("Failed to consume resume signal while suspended")?
-        {
-            return Ok(SuspendWaitOutcome::Resume);
+            // Use extracted_text for event parsing when available (NDJSON backends like Claude),
+            // otherwise fall back to stripped_output (non-JSON backends or interactive mode).
+            // This fixes event parsing for Claude's stream-json output where event tags like
+            // <event topic="..."> are inside JSON string values and not directly visible.
+            let output_for_parsing = if pty_result.extracted_text.is_empty() {
+                pty_result.stripped_output
+            } else {
+                pty_result.extracted_text
+            };
+            Ok(ExecutionOutcome {
+                output: output_for_parsing,
+                success: pty_result.success,
+                termination,
+                failure,
+                total_cost_usd: pty_result.total_cost_usd,
+                input_tokens: pty_result.input_tokens,
+                output_tokens: pty_result.output_tokens,
+                cache_read_tokens: pty_result.cache_read_tokens,
+                cache_write_tokens: pty_result.cache_write_tokens,
+            })
+        }
+        Err(e) => {
+            // PTY allocation may have failed - log and continue with error
+            warn!("PTY execution failed: {}, continuing with error status", e);
+            Err(anyhow::Error::new(e))
         }
-
-        std::thread::sleep(poll_interval);
     }
 }
 
-fn fail_if_blocking_loop_start_outcomes(outcomes: &[HookDispatchOutcome]) -> Result<()> {
-    let Some(blocking_outcome) = outcomes
-        .iter()
-        .find(|outcome| outcome.disposition == HookDisposition::Block)
-    else {
-        return Ok(());
-    };
-
-    let reason = format_blocking_hook_reason(blocking_outcome);
-    error!(
-        phase_event = %blocking_outcome.phase_event,
-        hook_name = %blocking_outcome.hook_name,
-        reason = %reason,
-        "Lifecycle hook blocked loop.start boundary"
-    );
-
-    Err(anyhow::anyhow!(reason))
-}
-
-fn fail_if_blocking_iteration_start_outcomes(outcomes: &[HookDispatchOutcome]) -> Result<()> {
-    let Some(blocking_outcome) = outcomes
-        .iter()
-        .find(|outcome| outcome.disposition == HookDisposition::Block)
-    else {
-        return Ok(());
-    };
-
-    let reason = format_blocking_hook_reason(blocking_outcome);
-    error!(
-        phase_event = %blocking_outcome.phase_event,
-        hook_name = %blocking_outcome.hook_name,
-        reason = %reason,
-        "Lifecycle hook blocked iteration.start boundary"
-    );
-
-    Err(anyhow::anyhow!(reason))
-}
-
-fn fail_if_blocking_plan_created_outcomes(outcomes: &[HookDispatchOutcome]) -> Result<()> {
-    let Some(blocking_outcome) = outcomes
-        .iter()
-        .find(|outcome| outcome.disposition == HookDisposition::Block)
-    else {
-        return Ok(());
-    };
-
-    let reason = format_blocking_hook_reason(blocking_outcome);
-    error!(
-        phase_event = %blocking_outcome.phase_event,
-        hook_name = %blocking_outcome.hook_name,
-        reason = %reason,
-        "Lifecycle hook blocked plan.created boundary"
-    );
-
-    Err(anyhow::anyhow!(reason))
-}
-
-fn fail_if_blocking_human_interact_outcomes(outcomes: &[HookDispatchOutcome]) -> Result<()> {
-    let Some(blocking_outcome) = outcomes
-        .iter()
-        .find(|outcome| outcome.disposition == HookDisposition::Block)
-    else {
-        return Ok(());
-    };
-
-    let reason = format_blocking_hook_reason(blocking_outcome);
-    error!(
-        phase_event = %blocking_outcome.phase_event,
-        hook_name = %blocking_outcome.hook_name,
-        reason = %reason,
-        "Lifecycle hook blocked human.interact boundary"
-    );
-
-    Err(anyhow::anyhow!(reason))
-}
-
-fn fail_if_blocking_loop_termination_outcomes(outcomes: &[HookDispatchOutcome]) -> Result<()> {
-    let Some(blocking_outcome) = outcomes
-        .iter()
-        .find(|outcome| outcome.disposition == HookDisposition::Block)
-    else {
-        return Ok(());
-    };
+/// Logs events parsed from output to the event history file.
+///
+/// When an event has no subscriber (orphan), also logs an `event.orphaned`
+/// system event to help Ralph understand the misconfiguration.
+fn log_events_from_output(
+    logger: &mut EventLogger,
+    iteration: u32,
+    hat_id: &HatId,
+    output: &str,
+    registry: &ralph_core::HatRegistry,
+) {
+    let parser = EventParser::new();
+    let events = parser.parse(output);
 
-    let reason = format_blocking_hook_reason(blocking_outcome);
-    error!(
-        phase_event = %blocking_outcome.phase_event,
-        hook_name = %blocking_outcome.hook_name,
-        reason = %reason,
-        "Lifecycle hook blocked loop termination boundary"
-    );
+    for event in events {
+        // Determine which hat will be triggered by this event
+        let triggered = registry.find_by_trigger(event.topic.as_str());
 
-    Err(anyhow::anyhow!(reason))
-}
+        // Per spec: Log "Published {topic} -> triggers {hat}" at DEBUG level
+        if let Some(triggered_hat) = triggered {
+            debug!("Published {} -> triggers {}", event.topic, triggered_hat);
+        } else {
+            debug!(
+                "Published {} -> no hat triggered (orphan event)",
+                event.topic
+            );
 
-async fn wait_for_resume_if_suspended(
-    outcomes: &[HookDispatchOutcome],
-    loop_id: &str,
-    suspend_state_store: &SuspendStateStore,
-) -> Result<Option<TerminationReason>> {
-    let Some(suspending_outcome) = outcomes
-        .iter()
-        .find(|outcome| outcome.disposition == HookDisposition::Suspend)
-    else {
-        return Ok(None);
-    };
+            // Emit event.orphaned system event so Ralph sees the problem
+            // Collect valid events (all hat subscriptions except wildcards)
+            let valid_events: Vec<String> = registry
+                .all()
+                .flat_map(|hat| hat.subscriptions.iter())
+                .map(|t| t.as_str().to_string())
+                .filter(|t| t != "*")
+                .collect::<std::collections::HashSet<_>>()
+                .into_iter()
+                .collect();
 
-    let reason = format_suspending_hook_reason(suspending_outcome);
-    let suspend_state = SuspendStateRecord::new(
-        loop_id,
-        suspending_outcome.phase_event,
-        &suspending_outcome.hook_name,
-        &reason,
-        suspending_outcome.suspend_mode,
-        chrono::Utc::now(),
-    );
+            warn!(
+                topic = %event.topic,
+                source = %hat_id.as_str(),
+                valid_events = ?valid_events,
+                "Event has no subscriber - logging event.orphaned"
+            );
 
-    suspend_state_store
-        .write_suspend_state(&suspend_state)
-        .with_context(|| {
-            format!(
-                "Failed to persist suspend-state for hook '{}' at '{}'",
-                suspending_outcome.hook_name,
-                suspending_outcome.phase_event.as_str()
+            let orphan_event = Event::new(
+                "event.orphaned",
+                format!(
+                    "Event '{}' has no subscriber hat. Valid events to publish: {:?}",
+                    event.topic, valid_events
+                ),
             )
-        })?;
-
-    warn!(
-        phase_event = %suspending_outcome.phase_event,
-        hook_name = %suspending_outcome.hook_name,
-        suspend_mode = ?suspending_outcome.suspend_mode,
-        reason = %reason,
-        "Lifecycle hook requested suspend; entering wait_for_resume gate"
-    );
-
-    loop {
-        if consume_stop_requested_signal(suspend_state_store.workspace_root())? {
-            clear_suspend_wait_artifacts(suspend_state_store)?;
-            info!(
-                phase_event = %suspending_outcome.phase_event,
-                hook_name = %suspending_outcome.hook_name,
-                "Stop requested while suspended; terminating loop"
-            );
-            return Ok(Some(TerminationReason::Stopped));
-        }
+            .with_source(hat_id.clone());
 
-        if is_restart_requested(suspend_state_store.workspace_root()) {
-            clear_suspend_wait_artifacts(suspend_state_store)?;
-            info!(
-                phase_event = %suspending_outcome.phase_event,
-                hook_name = %suspending_outcome.hook_name,
-                "Restart requested while suspended; terminating loop for restart"
-            );
-            return Ok(Some(TerminationReason::RestartRequested));
+            let orphan_record = EventRecord::new(iteration, "loop", &orphan_event, None::<&HatId>);
+            if let Err(e) = logger.log(&orphan_record) {
+                warn!("Failed to log event.orphaned: {}", e);
+            }
         }
 
-        if suspend_state_store
-            .consume_resume_requested()
-            .context("Failed to consume resume signal while suspended")?
-        {
-            suspend_state_store
-                .clear_suspend_state()
-                .context("Failed to clear suspend-state after resume signal")?;
+        let record = EventRecord::new(iteration, hat_id.to_string(), &event, triggered);
 
-            info!(
-                phase_event = %suspending_outcome.phase_event,
-                hook_name = %suspending_outcome.hook_name,
-                "Resume signal consumed; leaving suspended wait_for_resume state"
-            );
-            return Ok(None);
+        if let Err(e) = logger.log(&record) {
+            warn!("Failed to log event {}: {}", event.topic, e);
         }
-
-        tokio::time::sleep(Duration::from_millis(SUSPEND_WAIT_SIGNAL_POLL_INTERVAL_MS)).await;
     }
 }
 
-fn clear_suspend_wait_artifacts(suspend_state_store: &SuspendStateStore) -> Result<()> {
-    suspend_state_store
-        .clear_suspend_state()
-        .context("Failed to clear suspend-state artifact")?;
-    suspend_state_store
-        .consume_resume_requested()
-        .context("Failed to clear stale resume signal")?;
-    Ok(())
-}
-
-fn is_stop_requested(workspace_root: &Path) -> bool {
-    workspace_root.join(".ralph/stop-requested").exists()
-}
+/// Logs the loop.terminate system event to the event history.
+///
+/// Per spec: loop.terminate is an observer-only event published on loop exit.
+fn log_terminate_event(logger: &mut EventLogger, iteration: u32, event: &Event) {
+    // loop.terminate is published by the orchestrator, not a hat
+    // No hat can trigger on it (it's observer-only)
+    let record = EventRecord::new(iteration, "loop", event, None::<&HatId>);
 
-fn consume_stop_requested_signal(workspace_root: &Path) -> Result<bool> {
-    let stop_path = workspace_root.join(".ralph/stop-requested");
-    match fs::remove_file(&stop_path) {
-        Ok(()) => Ok(true),
-        Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(false),
-        Err(error) => Err(anyhow::Error::new(error)).with_context(|| {
-            format!(
-                "Failed to consume stop signal while suspended: {}",
-                stop_path.display()
-            )
-        }),
+    if let Err(e) = logger.log(&record) {
+        warn!("Failed to log loop.terminate event: {}", e);
     }
 }
 
-fn is_restart_requested(workspace_root: &Path) -> bool {
-    workspace_root.join(".ralph/restart-requested").exists()
-}
-
-fn format_suspending_hook_reason(outcome: &HookDispatchOutcome) -> String {
-    format!(
-        "Lifecycle hook '{}' suspended orchestration at '{}': {}",
-        outcome.hook_name,
-        outcome.phase_event.as_str(),
-        format_hook_failure_detail(outcome.failure.as_ref())
-    )
-}
-
-fn format_blocking_hook_reason(outcome: &HookDispatchOutcome) -> String {
-    format!(
-        "Lifecycle hook '{}' blocked orchestration at '{}': {}",
-        outcome.hook_name,
-        outcome.phase_event.as_str(),
-        format_hook_failure_detail(outcome.failure.as_ref())
-    )
-}
-
-fn format_hook_failure_detail(failure: Option<&HookDispatchFailure>) -> String {
-    match failure {
-        Some(HookDispatchFailure::HookRunFailed {
-            exit_code,
-            timed_out,
-        }) => {
-            if *timed_out {
-                "hook timed out".to_string()
-            } else if let Some(code) = exit_code {
-                format!("hook exited with code {code}")
-            } else {
-                "hook exited unsuccessfully".to_string()
-            }
-        }
-        Some(HookDispatchFailure::HookExecutionError { message }) => {
-            format!("hook execution failed: {message}")
-        }
-        Some(HookDispatchFailure::InvalidMutationOutput { message }) => {
-            format!("invalid mutation output: {message}")
-        }
-        None => "hook failed without failure details".to_string(),
-    }
-}
+/// Gets the last commit info (short SHA and subject) for the summary file.
+fn get_last_commit_info_with_cmd(git_cmd: &OsStr) -> Option<String> {
+    let output = Command::new(git_cmd)
+        .args(["log", "-1", "--format=%h: %s"])
+        .output()
+        .ok()?;
 
-fn classify_hook_disposition(on_error: HookOnError, run_result: &HookRunResult) -> HookDisposition {
-    if !run_result.timed_out && run_result.exit_code == Some(0) {
-        HookDisposition::Pass
+    if output.status.success() {
+        let info = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        if info.is_empty() { None } else { Some(info) }
     } else {
-        disposition_from_on_error(on_error)
+        None
     }
 }
 
-fn disposition_from_on_error(on_error: HookOnError) -> HookDisposition {
-    match on_error {
-        HookOnError::Warn => HookDisposition::Warn,
-        HookOnError::Block => HookDisposition::Block,
-        HookOnError::Suspend => HookDisposition::Suspend,
-    }
+fn get_last_commit_info() -> Option<String> {
+    get_last_commit_info_with_cmd(OsStr::new("git"))
 }
 
-/// Executes a prompt in PTY mode with raw terminal handling.
-/// Converts PTY termination type to loop termination reason.
-///
-/// In interactive mode, idle timeout signals "iteration complete" rather than
-/// "loop stopped", allowing the event loop to process output and continue.
+/// Resolves prompt content with proper precedence.
 ///
-/// # Arguments
-/// * `termination_type` - The PTY executor's termination type
-/// * `interactive` - Whether running in interactive mode
+/// Precedence (highest to lowest):
+/// 1. CLI -p "text" (inline prompt text)
+/// 2. CLI -P path (prompt file path)
+/// 3. Config event_loop.prompt (inline prompt text)
+/// 4. Config event_loop.prompt_file (prompt file path)
+/// 5. Default PROMPT.md
 ///
-/// # Returns
-/// * `None` - Continue processing (iteration complete)
-/// * `Some(TerminationReason)` - Stop the loop
-fn convert_termination_type(
-    termination_type: ralph_adapters::TerminationType,
-    interactive: bool,
-) -> Option<TerminationReason> {
-    match termination_type {
-        ralph_adapters::TerminationType::Natural => None,
-        ralph_adapters::TerminationType::IdleTimeout => {
-            if interactive {
-                // In interactive mode, idle timeout signals iteration complete,
-                // not loop termination. Let output be processed for events.
-                info!("PTY idle timeout in interactive mode, iteration complete");
-                None
-            } else {
-                warn!("PTY idle timeout reached, terminating loop");
-                Some(TerminationReason::Stopped)
-            }
-        }
-        ralph_adapters::TerminationType::UserInterrupt
-        | ralph_adapters::TerminationType::ForceKill => Some(TerminationReason::Interrupted),
-    }
-}
-
-fn detect_solo_output_completion(
-    registry: &HatRegistry,
-    output: &str,
-    completion_promise: &str,
-) -> bool {
-    registry.is_empty() && EventParser::contains_promise(output, completion_promise)
-}
-
-fn normalize_cli_output_for_parsing(
-    output_format: BackendOutputFormat,
-    raw_output: &str,
-) -> String {
-    match output_format {
-        BackendOutputFormat::StreamJson => extract_claude_stream_text(raw_output),
-        BackendOutputFormat::PiStreamJson => extract_pi_stream_text(raw_output),
-        _ => raw_output.to_string(),
-    }
-}
-
-fn extract_claude_stream_text(raw_output: &str) -> String {
-    let mut extracted = String::new();
-
-    for line in raw_output.lines() {
-        let Some(event) = ClaudeStreamParser::parse_line(line) else {
-            continue;
-        };
-
-        if let ClaudeStreamEvent::Assistant { message, .. } = event {
-            for block in message.content {
-                if let ContentBlock::Text { text } = block {
-                    extracted.push_str(&text);
-                    extracted.push('\n');
-                }
-            }
-        }
-    }
-
-    if extracted.is_empty() {
-        raw_output.to_string()
-    } else {
-        extracted
-    }
-}
-
-fn extract_pi_stream_text(raw_output: &str) -> String {
-    let mut extracted = String::new();
-
-    for line in raw_output.lines() {
-        let Some(event) = PiStreamParser::parse_line(line) else {
-            continue;
-        };
-
-        if let PiStreamEvent::MessageUpdate {
-            assistant_message_event,
-        } = event
-            && let PiAssistantEvent::TextDelta { delta } = assistant_message_event
-        {
-            extracted.push_str(&delta);
-        }
-    }
+/// Note: CLI overrides are already applied to config before this function is called.
+fn resolve_prompt_content(event_loop_config: &ralph_core::EventLoopConfig) -> Result<String> {
+    debug!(
+        inline_prompt = ?event_loop_config.prompt.as_ref().map(|s| format!("{}...", &s[..s.len().min(50)])),
+        prompt_file = %event_loop_config.prompt_file,
+        "Resolving prompt content"
+    );
 
-    if extracted.is_empty() {
-        raw_output.to_string()
-    } else {
-        extracted
+    // Check for inline prompt first (CLI -p or config prompt)
+    if let Some(ref inline_text) = event_loop_config.prompt {
+        debug!(len = inline_text.len(), "Using inline prompt text");
+        return Ok(inline_text.clone());
     }
-}
-
-/// Resolves the active timestamped events JSONL file path for this run.
-///
-/// The authoritative source is `.ralph/current-events`, which contains a
-/// relative path like `.ralph/events-YYYYMMDD-HHMMSS.jsonl`.
-///
-/// Falls back to `ctx.events_path()` if the marker is missing/unreadable.
-fn resolve_current_events_path(ctx: &LoopContext) -> PathBuf {
-    fs::read_to_string(ctx.current_events_marker())
-        .ok()
-        .map(|relative| {
-            let relative = relative.trim().to_string();
-            if std::path::Path::new(&relative).is_relative() {
-                ctx.workspace().join(relative)
-            } else {
-                PathBuf::from(relative)
-            }
-        })
-        .unwrap_or_else(|| ctx.events_path())
-}
-
-fn prepare_tui_iteration(
-    tui_state: &Arc<std::sync::Mutex<ralph_tui::TuiState>>,
-    hat_display: String,
-    backend: String,
-    max_iterations: u32,
-) -> Option<Arc<std::sync::Mutex<Vec<ratatui::text::Line<'static>>>>> {
-    let Ok(mut state) = tui_state.lock() else {
-        return None;
-    };
-    // Ensure max_iterations is always available for header display, even if
-    // state was reset by earlier events.
-    state.max_iterations = Some(max_iterations);
-    state.start_new_iteration_with_metadata(Some(hat_display), Some(backend));
-    state.latest_iteration_lines_handle()
-}
 
-/// Execute a prompt via ACP (Agent Client Protocol) for kiro-acp backend.
-async fn execute_acp(
-    backend: &CliBackend,
-    config: &RalphConfig,
-    prompt: &str,
-    verbosity: Verbosity,
-    tui_lines: Option<Arc<std::sync::Mutex<Vec<ratatui::text::Line<'static>>>>>,
-    rpc_stdout: Option<Arc<std::sync::Mutex<std::io::Stdout>>>,
-    iteration: u32,
-    hat: &str,
-    backend_name: &str,
-) -> Result<ExecutionOutcome> {
-    let executor = AcpExecutor::new(backend.clone(), config.core.workspace_root.clone());
-
-    let pty_result = if let Some(lines) = tui_lines {
-        let mut handler = TuiStreamHandler::with_lines(verbosity == Verbosity::Verbose, lines);
-        executor.execute(prompt, &mut handler).await?
-    } else if let Some(stdout_writer) = rpc_stdout {
-        let mut handler = JsonRpcStreamHandler::new(
-            stdout_writer,
-            iteration,
-            Some(hat.to_string()),
-            Some(backend_name.to_string()),
-        );
-        executor.execute(prompt, &mut handler).await?
-    } else {
-        match verbosity {
-            Verbosity::Quiet => {
-                let mut handler = QuietStreamHandler;
-                executor.execute(prompt, &mut handler).await?
-            }
-            Verbosity::Normal => {
-                let mut handler = ConsoleStreamHandler::new(false);
-                executor.execute(prompt, &mut handler).await?
-            }
-            Verbosity::Verbose => {
-                let mut handler = ConsoleStreamHandler::new(true);
-                executor.execute(prompt, &mut handler).await?
-            }
-        }
-    };
-
-    let output = if pty_result.extracted_text.is_empty() {
-        pty_result.stripped_output
-    } else {
-        pty_result.extracted_text
-    };
-
-    Ok(ExecutionOutcome {
-        output,
-        success: pty_result.success,
-        termination: None,
-        total_cost_usd: pty_result.total_cost_usd,
-        input_tokens: pty_result.input_tokens,
-        output_tokens: pty_result.output_tokens,
-        cache_read_tokens: pty_result.cache_read_tokens,
-        cache_write_tokens: pty_result.cache_write_tokens,
-    })
-}
-
-async fn execute_pty(
-    executor: Option<&mut PtyExecutor>,
-    backend: &CliBackend,
-    config: &RalphConfig,
-    prompt: &str,
-    interactive: bool,
-    interrupt_rx: tokio::sync::watch::Receiver<bool>,
-    verbosity: Verbosity,
-    tui_lines: Option<Arc<std::sync::Mutex<Vec<ratatui::text::Line<'static>>>>>,
-    rpc_stdout: Option<Arc<std::sync::Mutex<std::io::Stdout>>>,
-    iteration: u32,
-    hat: &str,
-    backend_name: &str,
-) -> Result<ExecutionOutcome> {
-    use crossterm::terminal::{disable_raw_mode, enable_raw_mode};
-
-    // Use provided executor or create a new one
-    // If executor is provided, TUI is connected and owns raw mode management
-    let tui_connected = executor.is_some();
-    let mut temp_executor;
-    let exec = if let Some(e) = executor {
-        // Update the executor's backend to use hat-level configuration
-        // This is critical for hat-level backend support - without this update,
-        // the executor would continue using the global backend it was created with
-        e.set_backend(backend.clone());
-        e
-    } else {
-        let idle_timeout_secs = if interactive {
-            config.cli.idle_timeout_secs
-        } else {
-            0
-        };
-        let pty_config = PtyConfig {
-            interactive,
-            idle_timeout_secs,
-            workspace_root: config.core.workspace_root.clone(),
-            ..PtyConfig::from_env()
-        };
-        temp_executor = PtyExecutor::new(backend.clone(), pty_config);
-        &mut temp_executor
-    };
-
-    // Set TUI mode flag when TUI is connected (tui_lines is Some)
-    // This replaces the broken output_rx.is_none() detection in PtyExecutor
-    if tui_lines.is_some() {
-        exec.set_tui_mode(true);
-    }
-
-    // Enter raw mode for interactive mode to capture keystrokes
-    // Skip if TUI is connected - TUI owns raw mode and will manage it
-    if interactive && !tui_connected {
-        enable_raw_mode().context("Failed to enable raw mode")?;
-    }
-
-    // Use scopeguard to ensure raw mode is restored on any exit path
-    // Skip if TUI is connected - TUI owns raw mode
-    let _guard = scopeguard::guard((interactive, tui_connected), |(is_interactive, tui)| {
-        if is_interactive && !tui {
-            let _ = disable_raw_mode();
-        }
-    });
-
-    // Run PTY executor with shared interrupt channel
-    let result = if interactive && tui_lines.is_none() && rpc_stdout.is_none() {
-        // Raw interactive mode only when not using TUI or RPC (TUI/RPC handle their own I/O)
-        exec.run_interactive(prompt, interrupt_rx).await
-    } else if let Some(lines) = tui_lines {
-        // TUI mode: use TuiStreamHandler to capture output for TUI display
-        let verbose = verbosity == Verbosity::Verbose;
-        let mut handler = TuiStreamHandler::with_lines(verbose, lines);
-        exec.run_observe_streaming(prompt, interrupt_rx, &mut handler)
-            .await
-    } else if let Some(stdout_writer) = rpc_stdout {
-        // RPC mode: use JsonRpcStreamHandler for JSON-lines output
-        let mut handler = JsonRpcStreamHandler::new(
-            stdout_writer,
-            iteration,
-            Some(hat.to_string()),
-            Some(backend_name.to_string()),
-        );
-        exec.run_observe_streaming(prompt, interrupt_rx, &mut handler)
-            .await
-    } else {
-        // Use streaming handler for non-interactive mode (respects verbosity)
-        // Use PrettyStreamHandler for StreamJson backends (Claude) on TTY for markdown rendering
-        // Use ConsoleStreamHandler for Text format backends (Kiro, Gemini, etc.) for immediate output
-        let use_pretty =
-            backend.output_format == BackendOutputFormat::StreamJson && stdout().is_terminal();
-
-        match verbosity {
-            Verbosity::Quiet => {
-                let mut handler = QuietStreamHandler;
-                exec.run_observe_streaming(prompt, interrupt_rx, &mut handler)
-                    .await
-            }
-            Verbosity::Normal => {
-                if use_pretty {
-                    let mut handler = PrettyStreamHandler::new(false);
-                    exec.run_observe_streaming(prompt, interrupt_rx, &mut handler)
-                        .await
-                } else {
-                    let mut handler = ConsoleStreamHandler::new(false);
-                    exec.run_observe_streaming(prompt, interrupt_rx, &mut handler)
-                        .await
-                }
-            }
-            Verbosity::Verbose => {
-                if use_pretty {
-                    let mut handler = PrettyStreamHandler::new(true);
-                    exec.run_observe_streaming(prompt, interrupt_rx, &mut handler)
-                        .await
-                } else {
-                    let mut handler = ConsoleStreamHandler::new(true);
-                    exec.run_observe_streaming(prompt, interrupt_rx, &mut handler)
-                        .await
-                }
-            }
-        }
-    };
-
-    match result {
-        Ok(pty_result) => {
-            let termination = convert_termination_type(pty_result.termination, interactive);
-
-            // Use extracted_text for event parsing when available (NDJSON backends like Claude),
-            // otherwise fall back to stripped_output (non-JSON backends or interactive mode).
-            // This fixes event parsing for Claude's stream-json output where event tags like
-            // <event topic="..."> are inside JSON string values and not directly visible.
-            let output_for_parsing = if pty_result.extracted_text.is_empty() {
-                pty_result.stripped_output
-            } else {
-                pty_result.extracted_text
-            };
-            Ok(ExecutionOutcome {
-                output: output_for_parsing,
-                success: pty_result.success,
-                termination,
-                total_cost_usd: pty_result.total_cost_usd,
-                input_tokens: pty_result.input_tokens,
-                output_tokens: pty_result.output_tokens,
-                cache_read_tokens: pty_result.cache_read_tokens,
-                cache_write_tokens: pty_result.cache_write_tokens,
-            })
-        }
-        Err(e) => {
-            // PTY allocation may have failed - log and continue with error
-            warn!("PTY execution failed: {}, continuing with error status", e);
-            Err(anyhow::Error::new(e))
-        }
-    }
-}
-
-/// Logs events parsed from output to the event history file.
-///
-/// When an event has no subscriber (orphan), also logs an `event.orphaned`
-/// system event to help Ralph understand the misconfiguration.
-fn log_events_from_output(
-    logger: &mut EventLogger,
-    iteration: u32,
-    hat_id: &HatId,
-    output: &str,
-    registry: &ralph_core::HatRegistry,
-) {
-    let parser = EventParser::new();
-    let events = parser.parse(output);
-
-    for event in events {
-        // Determine which hat will be triggered by this event
-        let triggered = registry.find_by_trigger(event.topic.as_str());
-
-        // Per spec: Log "Published {topic} -> triggers {hat}" at DEBUG level
-        if let Some(triggered_hat) = triggered {
-            debug!("Published {} -> triggers {}", event.topic, triggered_hat);
-        } else {
-            debug!(
-                "Published {} -> no hat triggered (orphan event)",
-                event.topic
-            );
-
-            // Emit event.orphaned system event so Ralph sees the problem
-            // Collect valid events (all hat subscriptions except wildcards)
-            let valid_events: Vec<String> = registry
-                .all()
-                .flat_map(|hat| hat.subscriptions.iter())
-                .map(|t| t.as_str().to_string())
-                .filter(|t| t != "*")
-                .collect::<std::collections::HashSet<_>>()
-                .into_iter()
-                .collect();
-
-            warn!(
-                topic = %event.topic,
-                source = %hat_id.as_str(),
-                valid_events = ?valid_events,
-                "Event has no subscriber - logging event.orphaned"
-            );
-
-            let orphan_event = Event::new(
-                "event.orphaned",
-                format!(
-                    "Event '{}' has no subscriber hat. Valid events to publish: {:?}",
-                    event.topic, valid_events
-                ),
-            )
-            .with_source(hat_id.clone());
-
-            let orphan_record = EventRecord::new(iteration, "loop", &orphan_event, None::<&HatId>);
-            if let Err(e) = logger.log(&orphan_record) {
-                warn!("Failed to log event.orphaned: {}", e);
-            }
-        }
-
-        let record = EventRecord::new(iteration, hat_id.to_string(), &event, triggered);
-
-        if let Err(e) = logger.log(&record) {
-            warn!("Failed to log event {}: {}", event.topic, e);
-        }
-    }
-}
-
-/// Logs the loop.terminate system event to the event history.
-///
-/// Per spec: loop.terminate is an observer-only event published on loop exit.
-fn log_terminate_event(logger: &mut EventLogger, iteration: u32, event: &Event) {
-    // loop.terminate is published by the orchestrator, not a hat
-    // No hat can trigger on it (it's observer-only)
-    let record = EventRecord::new(iteration, "loop", event, None::<&HatId>);
-
-    if let Err(e) = logger.log(&record) {
-        warn!("Failed to log loop.terminate event: {}", e);
-    }
-}
-
-/// Gets the last commit info (short SHA and subject) for the summary file.
-fn get_last_commit_info_with_cmd(git_cmd: &OsStr) -> Option<String> {
-    let output = Command::new(git_cmd)
-        .args(["log", "-1", "--format=%h: %s"])
-        .output()
-        .ok()?;
-
-    if output.status.success() {
-        let info = String::from_utf8_lossy(&output.stdout).trim().to_string();
-        if info.is_empty() { None } else { Some(info) }
-    } else {
-        None
-    }
-}
-
-fn get_last_commit_info() -> Option<String> {
-    get_last_commit_info_with_cmd(OsStr::new("git"))
-}
-
-/// Resolves prompt content with proper precedence.
-///
-/// Precedence (highest to lowest):
-/// 1. CLI -p "text" (inline prompt text)
-/// 2. CLI -P path (prompt file path)
-/// 3. Config event_loop.prompt (inline prompt text)
-/// 4. Config event_loop.prompt_file (prompt file path)
-/// 5. Default PROMPT.md
-///
-/// Note: CLI overrides are already applied to config before this function is called.
-fn resolve_prompt_content(event_loop_config: &ralph_core::EventLoopConfig) -> Result<String> {
-    debug!(
-        inline_prompt = ?event_loop_config.prompt.as_ref().map(|s| format!("{}...", &s[..s.len().min(50)])),
-        prompt_file = %event_loop_config.prompt_file,
-        "Resolving prompt content"
-    );
-
-    // Check for inline prompt first (CLI -p or config prompt)
-    if let Some(ref inline_text) = event_loop_config.prompt {
-        debug!(len = inline_text.len(), "Using inline prompt text");
-        return Ok(inline_text.clone());
-    }
-
-    // Check for prompt file (CLI -P or config prompt_file or default)
-    let prompt_file = &event_loop_config.prompt_file;
-    if !prompt_file.is_empty() {
-        let path = std::path::Path::new(prompt_file);
-        debug!(path = %prompt_file, exists = path.exists(), "Checking prompt file");
-        if path.exists() {
-            let content = std::fs::read_to_string(path)
-                .with_context(|| format!("Failed to read prompt file: {}", prompt_file))?;
-            debug!(path = %prompt_file, len = content.len(), "Read prompt from file");
-            return Ok(content);
+    // Check for prompt file (CLI -P or config prompt_file or default)
+    let prompt_file = &event_loop_config.prompt_file;
+    if !prompt_file.is_empty() {
+        let path = std::path::Path::new(prompt_file);
+        debug!(path = %prompt_file, exists = path.exists(), "Checking prompt file");
+        if path.exists() {
+            let content = std::fs::read_to_string(path)
+                .with_context(|| format!("Failed to read prompt file: {}", prompt_file))?;
+            debug!(path = %prompt_file, len = content.len(), "Read prompt from file");
+            return Ok(content);
         } else {
             // File specified but doesn't exist - error with helpful message
             anyhow::bail!(
-                "Prompt file '{}' not found. Check the path or use -p \"text\" for inline prompt.",
-                prompt_file
-            );
-        }
-    }
-
-    // No valid prompt source found
-    anyhow::bail!(
-        "No prompt specified. Use -p \"text\" for inline prompt, -P path for file, \
-         or create PROMPT.md in the current directory."
-    )
-}
-
-/// Checks for planning session user responses and publishes them as events.
-///
-/// When running in planning mode (RALPH_PLANNING_SESSION_ID is set),
-/// this function reads the conversation file for new user responses and
-/// publishes them as `user.response` events to the event loop.
-fn check_planning_session_responses(event_loop: &mut EventLoop) -> Result<()> {
-    // Get the planning session ID from environment
-    let session_id = match std::env::var("RALPH_PLANNING_SESSION_ID") {
-        Ok(id) => id,
-        Err(_) => return Ok(()), // Not in planning mode
-    };
-    check_planning_session_responses_for_session(event_loop, &session_id)
-}
-
-fn check_planning_session_responses_for_session(
-    event_loop: &mut EventLoop,
-    session_id: &str,
-) -> Result<()> {
-    // Get loop context to find the conversation file path
-    let ctx = match event_loop.loop_context() {
-        Some(ctx) => ctx,
-        None => return Ok(()), // No context, can't find conversation file
-    };
-
-    let conversation_path = ctx.planning_conversation_path(session_id);
-
-    // Read conversation entries and look for new responses
-    // We track which response IDs we've already processed to avoid duplicates
-
-    // Track processed response IDs (static to persist across iterations)
-    static PROCESSED_RESPONSES: std::sync::Mutex<Vec<String>> = std::sync::Mutex::new(Vec::new());
-
-    let conversation_content = match fs::read_to_string(&conversation_path) {
-        Ok(content) => content,
-        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()), // File doesn't exist yet
-        Err(e) => {
-            warn!(
-                session_id = %session_id,
-                error = %e,
-                "Failed to read planning conversation file"
-            );
-            return Ok(());
-        }
-    };
-
-    let mut processed = PROCESSED_RESPONSES.lock().unwrap();
-
-    for line in conversation_content.lines() {
-        let line = line.trim();
-        if line.is_empty() {
-            continue;
-        }
-
-        // Parse the conversation entry
-        let entry: ralph_core::planning_session::ConversationEntry =
-            match serde_json::from_str(line) {
-                Ok(entry) => entry,
-                Err(e) => {
-                    warn!(
-                        session_id = %session_id,
-                        line = %line,
-                        error = %e,
-                        "Failed to parse conversation entry"
-                    );
-                    continue;
-                }
-            };
-
-        // Only process user_response entries
-        if entry.entry_type != ralph_core::planning_session::ConversationType::UserResponse {
-            continue;
-        }
-
-        // Check if we've already processed this response
-        let response_key = format!("{}:{}", entry.id, entry.ts);
-        if processed.contains(&response_key) {
-            continue;
-        }
-
-        // Publish as user.response event
-        let event = Event::new(
-            "user.response",
-            format!("[id: {}] {}", entry.id, entry.text),
-        );
-        event_loop.bus().publish(event.clone());
-
-        info!(
-            session_id = %session_id,
-            response_id = %entry.id,
-            "Published user response from planning session"
-        );
-
-        // Mark as processed
-        processed.push(response_key);
-    }
-
-    Ok(())
-}
-
-/// Processes pending merges from the merge queue.
-///
-/// Called when the primary loop completes successfully. Spawns merge-ralph
-/// processes for each queued loop in FIFO order.
-fn process_pending_merges_with_command(repo_root: &Path, ralph_cmd: &OsStr) {
-    let queue = MergeQueue::new(repo_root);
-
-    // Get all pending merges
-    let pending = match queue.list_by_state(ralph_core::merge_queue::MergeState::Queued) {
-        Ok(entries) => entries,
-        Err(e) => {
-            warn!("Failed to read merge queue: {}", e);
-            return;
-        }
-    };
-
-    if pending.is_empty() {
-        debug!("No pending merges in queue");
-        return;
-    }
-
-    info!(
-        count = pending.len(),
-        "Processing pending merges from queue"
-    );
-
-    // Get the merge-loop preset content
-    let preset = match crate::presets::get_preset("merge-loop") {
-        Some(p) => p,
-        None => {
-            warn!("merge-loop preset not found, pending merges will remain queued");
-            return;
-        }
-    };
-
-    // Write a core-only merge config once (shared by all merge loops).
-    let mut core_value: serde_yaml::Value = match serde_yaml::from_str(preset.content) {
-        Ok(value) => value,
-        Err(e) => {
-            warn!(
-                error = %e,
-                "Failed to parse merge-loop preset, pending merges will remain queued"
-            );
-            return;
-        }
-    };
-
-    if let Some(mapping) = core_value.as_mapping_mut() {
-        let hats_key = serde_yaml::Value::String("hats".to_string());
-        let events_key = serde_yaml::Value::String("events".to_string());
-        mapping.remove(&hats_key);
-        mapping.remove(&events_key);
-    }
-
-    let core_yaml = match serde_yaml::to_string(&core_value) {
-        Ok(yaml) => yaml,
-        Err(e) => {
-            warn!(
-                error = %e,
-                "Failed to serialize core-only merge config, pending merges will remain queued"
-            );
-            return;
-        }
-    };
-
-    let config_path = repo_root.join(".ralph/merge-loop-config.yml");
-    if let Err(e) = fs::write(&config_path, core_yaml) {
-        warn!(
-            error = %e,
-            "Failed to write merge config, pending merges will remain queued"
-        );
-        return;
-    }
-
-    // Process each pending merge
-    for entry in pending {
-        let loop_id = &entry.loop_id;
-
-        info!(loop_id = %loop_id, "Spawning merge-ralph process");
-
-        // Redirect subprocess stdio to a log file to prevent TUI corruption.
-        // If log file creation fails, fall back to Stdio::null rather than
-        // inheriting the parent's terminal (which would corrupt the TUI).
-        let (stdout_stdio, stderr_stdio, log_path) =
-            match create_merge_subprocess_log_file(repo_root, loop_id) {
-                Ok((file, path)) => match file.try_clone() {
-                    Ok(file_clone) => (Stdio::from(file_clone), Stdio::from(file), Some(path)),
-                    Err(e) => {
-                        warn!(
-                            loop_id = %loop_id,
-                            error = %e,
-                            "Failed to clone log file handle, subprocess output will be discarded"
-                        );
-                        (Stdio::null(), Stdio::null(), None)
-                    }
-                },
-                Err(e) => {
-                    warn!(
-                        loop_id = %loop_id,
-                        error = %e,
-                        "Failed to create subprocess log file, output will be discarded"
-                    );
-                    (Stdio::null(), Stdio::null(), None)
-                }
-            };
-
-        match Command::new(ralph_cmd)
-            .current_dir(repo_root)
-            .args([
-                "run",
-                "-c",
-                ".ralph/merge-loop-config.yml",
-                "-H",
-                "builtin:merge-loop",
-                "--exclusive",
-                "--no-tui",
-                "-p",
-                &format!("Merge loop {} from branch ralph/{}", loop_id, loop_id),
-            ])
-            .env("RALPH_MERGE_LOOP_ID", loop_id)
-            .stdout(stdout_stdio)
-            .stderr(stderr_stdio)
-            .spawn()
-        {
-            Ok(child) => {
-                if let Some(path) = log_path {
-                    info!(
-                        loop_id = %loop_id,
-                        pid = child.id(),
-                        log_file = %path.display(),
-                        "merge-ralph spawned successfully"
-                    );
-                } else {
-                    info!(
-                        loop_id = %loop_id,
-                        pid = child.id(),
-                        "merge-ralph spawned successfully"
-                    );
-                }
-            }
-            Err(e) => {
-                warn!(
-                    loop_id = %loop_id,
-                    error = %e,
-                    "Failed to spawn merge-ralph, loop will remain queued for manual retry"
-                );
-            }
-        }
-    }
-}
-
-/// Creates a timestamped log file for a merge subprocess under `.ralph/diagnostics/logs/`.
-///
-/// Uses the loop_id in the filename for easier identification when debugging.
-/// Participates in the existing log rotation scheme.
-fn create_merge_subprocess_log_file(
-    repo_root: &Path,
-    loop_id: &str,
-) -> std::io::Result<(File, PathBuf)> {
-    use chrono::Local;
-
-    let logs_dir = repo_root.join(".ralph").join("diagnostics").join("logs");
-    fs::create_dir_all(&logs_dir)?;
-
-    let _ = ralph_core::diagnostics::rotate_logs(&logs_dir, 10);
-
-    let timestamp = Local::now().format("%Y-%m-%dT%H-%M-%S");
-    let log_path = logs_dir.join(format!("ralph-merge-{}-{}.log", loop_id, timestamp));
-    let file = File::create(&log_path)?;
-
-    Ok((file, log_path))
-}
-
-fn process_pending_merges(repo_root: &Path) {
-    process_pending_merges_with_command(repo_root, OsStr::new("ralph"));
-}
-
-/// Public wrapper for CLI invocation of process_pending_merges.
-///
-/// Called by `ralph loops process` command to process the merge queue.
-pub fn process_pending_merges_cli(repo_root: &Path) {
-    process_pending_merges(repo_root);
-}
-
-/// Start a loop from an external caller (e.g., the bot daemon).
-///
-/// Loads config from `ralph.yml`, applies the given prompt, acquires the
-/// loop lock, and runs the orchestration loop headlessly. The caller is
-/// responsible for Telegram interaction — the spawned loop has `robot.enabled`
-/// disabled to prevent a second Telegram poller from conflicting.
-///
-/// Returns `Ok(TerminationReason)` on completion or `Err` on fatal errors.
-pub async fn start_loop(
-    prompt: String,
-    workspace_root: PathBuf,
-    config_path: Option<PathBuf>,
-) -> Result<TerminationReason> {
-    use crate::{ColorMode, ConfigSource, load_config_with_overrides};
-
-    // Load config from file or defaults
-    let config_source = config_path.unwrap_or_else(|| workspace_root.join("ralph.yml"));
-    let sources = vec![ConfigSource::File(config_source)];
-    let mut config = load_config_with_overrides(&sources)?;
-
-    // Set workspace root to the provided path
-    config.core.workspace_root = workspace_root.clone();
-
-    // Apply the prompt
-    config.event_loop.prompt = Some(prompt);
-    config.event_loop.prompt_file = String::new();
-
-    // Keep robot.enabled as-is from config. When the daemon starts a loop,
-    // the loop's own TelegramService handles all Telegram interaction
-    // (commands, guidance, responses, check-ins). The daemon stops polling
-    // while the loop runs, so there's no conflict.
-
-    // Force autonomous headless mode (no TUI, no interactive)
-    config.cli.default_mode = "autonomous".to_string();
-
-    // Normalize and validate
-    config.normalize();
-    let warnings = config
-        .validate()
-        .context("Configuration validation failed")?;
-    for warning in &warnings {
-        tracing::warn!("{}", warning);
-    }
-
-    // Auto-detect backend if needed
-    if config.cli.backend == "auto" {
-        let priority = config.get_agent_priority();
-        let detected = ralph_adapters::detect_backend(&priority, |backend| {
-            config.adapter_settings(backend).enabled
-        });
-        match detected {
-            Ok(backend) => {
-                info!("Auto-detected backend: {}", backend);
-                config.cli.backend = backend;
-            }
-            Err(e) => return Err(anyhow::Error::new(e)),
-        }
-    }
-
-    // Ensure scratchpad directory exists
-    crate::ensure_scratchpad_directory(&config)?;
-
-    // Acquire the loop lock (primary loop)
-    let prompt_summary = config.event_loop.prompt.as_deref().unwrap_or("[daemon]");
-    let prompt_summary = ralph_core::truncate_with_ellipsis(prompt_summary, 100);
-
-    let _lock_guard = ralph_core::LoopLock::try_acquire(&workspace_root, &prompt_summary)
-        .context("Failed to acquire loop lock — another loop may be running")?;
-
-    let loop_context = ralph_core::LoopContext::primary(workspace_root);
-
-    // Run the loop headlessly
-    run_loop_impl(
-        config,
-        ColorMode::Never,
-        false, // not resume
-        false, // no TUI
-        false, // no RPC
-        Verbosity::Normal,
-        None,               // no session recording
-        Some(loop_context), // loop context
-        Vec::new(),         // no custom args
-        None,               // default auto-merge
-        None,               // no explicit loop ID
-    )
-    .await
-}
-
-/// Creates a robot service (Telegram) for human-in-the-loop communication.
-///
-/// Called by `run_loop_impl` when `robot.enabled` is true and this is the primary loop.
-/// Returns `None` if the service cannot be created or started.
-fn create_robot_service(
-    config: &RalphConfig,
-    context: &LoopContext,
-) -> Option<Box<dyn ralph_proto::RobotService>> {
-    let workspace_root = context.workspace().to_path_buf();
-    let bot_token = config.robot.resolve_bot_token();
-    let api_url = config.robot.resolve_api_url();
-    let timeout_secs = config.robot.timeout_seconds.unwrap_or(300);
-    let loop_id = context
-        .loop_id()
-        .map(String::from)
-        .unwrap_or_else(|| "main".to_string());
-
-    match ralph_telegram::TelegramService::new(
-        workspace_root,
-        bot_token,
-        api_url,
-        timeout_secs,
-        loop_id,
-    ) {
-        Ok(service) => {
-            if let Err(e) = service.start() {
-                warn!(error = %e, "Failed to start robot service");
-                return None;
-            }
-            info!(
-                bot_token = %service.bot_token_masked(),
-                timeout_secs = service.timeout_secs(),
-                "Robot human-in-the-loop service active"
-            );
-            Some(Box::new(service))
-        }
-        Err(e) => {
-            warn!(error = %e, "Failed to create robot service");
-            None
-        }
-    }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::test_support::CwdGuard;
-    use ralph_core::HatRegistry;
-    use ralph_core::planning_session::{ConversationEntry, ConversationType};
-    use ralph_proto::{Hat, Topic};
-    use std::ffi::OsStr;
-    use std::sync::Arc;
-    use std::sync::Mutex;
-
-    #[test]
-    fn test_resolve_loop_id_fresh_generates_new() {
-        let temp = tempfile::TempDir::new().unwrap();
-        let ctx = ralph_core::LoopContext::primary(temp.path().to_path_buf());
-        ctx.ensure_ralph_dir().unwrap();
-
-        let id = resolve_loop_id(&ctx, false, None);
-        assert!(
-            id.starts_with("primary-"),
-            "Fresh run should generate primary-{{timestamp}}, got: {}",
-            id
-        );
-    }
-
-    #[test]
-    fn test_resolve_loop_id_continue_reuses_marker() {
-        let temp = tempfile::TempDir::new().unwrap();
-        let ctx = ralph_core::LoopContext::primary(temp.path().to_path_buf());
-        ctx.ensure_ralph_dir().unwrap();
-
-        // Write a marker from a "previous run"
-        std::fs::write(
-            ctx.ralph_dir().join("current-loop-id"),
-            "primary-20260303-100000",
-        )
-        .unwrap();
-
-        let id = resolve_loop_id(&ctx, true, None);
-        assert_eq!(
-            id, "primary-20260303-100000",
-            "--continue should reuse existing loop ID"
-        );
-    }
-
-    #[test]
-    fn test_resolve_loop_id_continue_explicit_overrides_marker() {
-        let temp = tempfile::TempDir::new().unwrap();
-        let ctx = ralph_core::LoopContext::primary(temp.path().to_path_buf());
-        ctx.ensure_ralph_dir().unwrap();
-
-        std::fs::write(
-            ctx.ralph_dir().join("current-loop-id"),
-            "primary-20260303-100000",
-        )
-        .unwrap();
-
-        let id = resolve_loop_id(&ctx, true, Some("custom-loop-42"));
-        assert_eq!(
-            id, "custom-loop-42",
-            "--loop-id should override the marker file"
-        );
-    }
-
-    #[test]
-    fn test_resolve_loop_id_continue_no_marker_generates_new() {
-        let temp = tempfile::TempDir::new().unwrap();
-        let ctx = ralph_core::LoopContext::primary(temp.path().to_path_buf());
-        ctx.ensure_ralph_dir().unwrap();
-
-        // No marker file exists
-        let id = resolve_loop_id(&ctx, true, None);
-        assert!(
-            id.starts_with("primary-"),
-            "--continue without marker should fall back to generating new ID, got: {}",
-            id
-        );
-    }
-
-    #[test]
-    fn test_pty_only_enabled_for_tui_rpc_or_interactive() {
-        let should_use_pty = |enable_tui: bool, enable_rpc: bool, user_interactive: bool| -> bool {
-            enable_tui || enable_rpc || user_interactive
-        };
-
-        assert!(!should_use_pty(false, false, false));
-        assert!(should_use_pty(true, false, false));
-        assert!(should_use_pty(false, true, false));
-        assert!(should_use_pty(false, false, true));
-    }
-
-    #[test]
-    fn test_user_interactive_mode_determination() {
-        // user_interactive is determined by default_mode setting, not PTY.
-        // PTY handles output streaming; user_interactive handles input forwarding.
-
-        // Autonomous mode: no user input forwarding
-        let autonomous_interactive = false;
-        assert!(
-            !autonomous_interactive,
-            "Autonomous mode should not forward user input"
-        );
-
-        // Interactive mode with TTY: forward user input
-        let interactive_with_tty = true;
-        assert!(
-            interactive_with_tty,
-            "Interactive mode with TTY should forward user input"
-        );
-    }
-
-    #[test]
-    fn test_prepare_tui_iteration_seeds_max_iterations() {
-        let state = Arc::new(Mutex::new(ralph_tui::TuiState::new()));
-
-        let lines = prepare_tui_iteration(&state, "Planner".to_string(), "claude".to_string(), 42);
-
-        assert!(lines.is_some(), "should return a lines handle");
-        let state = state.lock().expect("state lock");
-        assert_eq!(state.max_iterations, Some(42));
-        assert_eq!(state.total_iterations(), 1);
-    }
-
-    #[cfg(unix)]
-    fn write_fake_executable(dir: &Path, name: &str, body: &str) -> std::path::PathBuf {
-        use std::os::unix::fs::PermissionsExt;
-        let path = dir.join(name);
-        let script = format!("#!/bin/sh\n{}\n", body);
-        std::fs::write(&path, script).expect("write script");
-        let mut perms = std::fs::metadata(&path).expect("metadata").permissions();
-        perms.set_mode(0o755);
-        std::fs::set_permissions(&path, perms).expect("chmod");
-        path
-    }
-
-    #[cfg(unix)]
-    fn hook_spec_with_command_and_on_error_and_suspend_mode(
-        name: &str,
-        command: Vec<String>,
-        on_error: HookOnError,
-        suspend_mode: Option<HookSuspendMode>,
-    ) -> ralph_core::HookSpec {
-        ralph_core::HookSpec {
-            name: name.to_string(),
-            command,
-            cwd: None,
-            env: std::collections::HashMap::new(),
-            timeout_seconds: None,
-            max_output_bytes: None,
-            on_error: Some(on_error),
-            suspend_mode,
-            mutate: ralph_core::HookMutationConfig::default(),
-            extra: std::collections::HashMap::new(),
-        }
-    }
-
-    #[cfg(unix)]
-    fn hook_spec_with_command_and_on_error(
-        name: &str,
-        command: Vec<String>,
-        on_error: HookOnError,
-    ) -> ralph_core::HookSpec {
-        hook_spec_with_command_and_on_error_and_suspend_mode(name, command, on_error, None)
-    }
-
-    #[cfg(unix)]
-    fn hook_spec_with_command(name: &str, command: Vec<String>) -> ralph_core::HookSpec {
-        hook_spec_with_command_and_on_error(name, command, HookOnError::Warn)
-    }
-
-    #[cfg(unix)]
-    fn recording_hook(name: &str, log_path: &Path) -> ralph_core::HookSpec {
-        hook_spec_with_command(
-            name,
-            vec![
-                "sh".to_string(),
-                "-c".to_string(),
-                r#"payload="$(cat)"
-phase="$(printf '%s' "$payload" | grep -o '"phase_event":"[^"]*"' | cut -d'"' -f4)"
-printf '%s|%s\n' "$1" "$phase" >> "$2""#
-                    .to_string(),
-                "hook-recorder".to_string(),
-                name.to_string(),
-                log_path.to_string_lossy().into_owned(),
-            ],
-        )
-    }
-
-    #[cfg(unix)]
-    fn payload_recording_hook(name: &str, log_path: &Path) -> ralph_core::HookSpec {
-        hook_spec_with_command(
-            name,
-            vec![
-                "sh".to_string(),
-                "-c".to_string(),
-                r#"payload="$(cat)"
-printf '%s\n' "$payload" >> "$1""#
-                    .to_string(),
-                "hook-payload-recorder".to_string(),
-                log_path.to_string_lossy().into_owned(),
-            ],
-        )
-    }
-
-    #[cfg(unix)]
-    fn hook_engine_with_events(
-        events: std::collections::HashMap<HookPhaseEvent, Vec<ralph_core::HookSpec>>,
-    ) -> HookEngine {
-        let hooks_config = ralph_core::HooksConfig {
-            enabled: true,
-            events,
-            ..ralph_core::HooksConfig::default()
-        };
-        HookEngine::new(&hooks_config)
-    }
-
-    #[cfg(unix)]
-    fn dispatch_test_event_loop(workspace_root: &Path) -> EventLoop {
-        let mut config = RalphConfig::default();
-        config.core.workspace_root = workspace_root.to_path_buf();
-        EventLoop::new(config)
-    }
-
-    #[cfg(unix)]
-    fn dispatch_test_event_loop_with_context(workspace_root: &Path) -> (EventLoop, LoopContext) {
-        let mut config = RalphConfig::default();
-        config.core.workspace_root = workspace_root.to_path_buf();
-        let context = LoopContext::primary(workspace_root.to_path_buf());
-        let event_loop = EventLoop::with_context(config, context.clone());
-        (event_loop, context)
-    }
-
-    fn dispatch_test_event_loop_from_yaml_with_context(
-        workspace_root: &Path,
-        yaml: &str,
-    ) -> (EventLoop, LoopContext) {
-        let mut config: RalphConfig = serde_yaml::from_str(yaml).expect("parse config");
-        config.core.workspace_root = workspace_root.to_path_buf();
-        let context = LoopContext::primary(workspace_root.to_path_buf());
-        let event_loop = EventLoop::with_context(config, context.clone());
-        (event_loop, context)
-    }
-
-    #[cfg(unix)]
-    fn dispatch_test_event_loop_with_diagnostics(workspace_root: &Path) -> EventLoop {
-        let mut config = RalphConfig::default();
-        config.core.workspace_root = workspace_root.to_path_buf();
-        let diagnostics =
-            ralph_core::diagnostics::DiagnosticsCollector::with_enabled(workspace_root, true)
-                .expect("create diagnostics collector");
-        EventLoop::with_diagnostics(config, diagnostics)
-    }
-
-    #[cfg(unix)]
-    fn read_hook_run_telemetry_entries(workspace_root: &Path) -> Vec<HookRunTelemetryEntry> {
-        let diagnostics_root = workspace_root.join(".ralph").join("diagnostics");
-        let mut session_dirs: Vec<_> = std::fs::read_dir(&diagnostics_root)
-            .expect("read diagnostics root")
-            .filter_map(Result::ok)
-            .collect();
-        session_dirs.sort_by_key(|entry| entry.path());
-
-        let latest_session = session_dirs
-            .last()
-            .expect("at least one diagnostics session should exist");
-        let hook_runs_path = latest_session.path().join("hook-runs.jsonl");
-        let content = std::fs::read_to_string(&hook_runs_path).expect("read hook-runs.jsonl");
-
-        content
-            .lines()
-            .map(|line| serde_json::from_str(line).expect("parse hook run telemetry entry"))
-            .collect()
-    }
-
-    #[cfg(unix)]
-    fn read_hook_log(log_path: &Path) -> Vec<String> {
-        std::fs::read_to_string(log_path)
-            .expect("read hook log")
-            .lines()
-            .map(str::to_string)
-            .collect()
-    }
-
-    #[cfg(unix)]
-    fn read_hook_payload_log(log_path: &Path) -> Vec<serde_json::Value> {
-        std::fs::read_to_string(log_path)
-            .expect("read hook payload log")
-            .lines()
-            .filter(|line| !line.trim().is_empty())
-            .map(|line| serde_json::from_str(line).expect("parse hook payload JSON"))
-            .collect()
-    }
-
-    fn suspend_outcome_with_mode(
-        phase_event: HookPhaseEvent,
-        hook_name: &str,
-        suspend_mode: HookSuspendMode,
-    ) -> HookDispatchOutcome {
-        HookDispatchOutcome {
-            phase_event,
-            hook_name: hook_name.to_string(),
-            disposition: HookDisposition::Suspend,
-            suspend_mode,
-            failure: Some(HookDispatchFailure::HookRunFailed {
-                exit_code: Some(41),
-                timed_out: false,
-            }),
-
-            mutation_parse_outcome: HookMutationParseOutcome::Disabled,
-        }
-    }
-
-    fn suspend_outcome(phase_event: HookPhaseEvent, hook_name: &str) -> HookDispatchOutcome {
-        suspend_outcome_with_mode(phase_event, hook_name, HookSuspendMode::WaitForResume)
-    }
-
-    fn block_on_test_future<F>(future: F) -> F::Output
-    where
-        F: std::future::Future,
-    {
-        tokio::runtime::Builder::new_current_thread()
-            .enable_time()
-            .build()
-            .expect("build tokio runtime")
-            .block_on(future)
-    }
-
-    fn empty_hook_metadata() -> serde_json::Map<String, serde_json::Value> {
-        serde_json::Map::new()
-    }
-
-    fn build_loop_start_payload_input(
-        loop_id: &str,
-        ctx: &LoopContext,
-        max_iterations: u32,
-        iteration_current: u32,
-        active_hat: Option<String>,
-    ) -> HookPayloadBuilderInput {
-        super::build_loop_start_payload_input(
-            loop_id,
-            ctx,
-            max_iterations,
-            iteration_current,
-            active_hat,
-            &empty_hook_metadata(),
-        )
-    }
-
-    fn build_iteration_start_payload_input(
-        loop_id: &str,
-        ctx: &LoopContext,
-        max_iterations: u32,
-        iteration_current: u32,
-        active_hat: Option<String>,
-        selected_hat: Option<String>,
-        selected_task: Option<String>,
-    ) -> HookPayloadBuilderInput {
-        super::build_iteration_start_payload_input(
-            loop_id,
-            ctx,
-            max_iterations,
-            iteration_current,
-            active_hat,
-            selected_hat,
-            selected_task,
-            &empty_hook_metadata(),
-        )
-    }
-
-    fn build_plan_created_payload_input(
-        loop_id: &str,
-        ctx: &LoopContext,
-        max_iterations: u32,
-        iteration_current: u32,
-        active_hat: Option<String>,
-        selected_hat: Option<String>,
-        selected_task: Option<String>,
-    ) -> HookPayloadBuilderInput {
-        super::build_plan_created_payload_input(
-            loop_id,
-            ctx,
-            max_iterations,
-            iteration_current,
-            active_hat,
-            selected_hat,
-            selected_task,
-            &empty_hook_metadata(),
-        )
-    }
-
-    fn build_human_interact_payload_input(
-        loop_id: &str,
-        ctx: &LoopContext,
-        max_iterations: u32,
-        iteration_current: u32,
-        active_hat: Option<String>,
-        selected_hat: Option<String>,
-        selected_task: Option<String>,
-        human_interact: Option<serde_json::Value>,
-    ) -> HookPayloadBuilderInput {
-        super::build_human_interact_payload_input(
-            loop_id,
-            ctx,
-            max_iterations,
-            iteration_current,
-            active_hat,
-            selected_hat,
-            selected_task,
-            human_interact,
-            &empty_hook_metadata(),
-        )
-    }
-
-    fn build_loop_termination_payload_input(
-        loop_id: &str,
-        ctx: &LoopContext,
-        max_iterations: u32,
-        iteration_current: u32,
-        active_hat: Option<String>,
-        selected_hat: Option<String>,
-        selected_task: Option<String>,
-        termination_reason: &TerminationReason,
-    ) -> HookPayloadBuilderInput {
-        super::build_loop_termination_payload_input(
-            loop_id,
-            ctx,
-            max_iterations,
-            iteration_current,
-            active_hat,
-            selected_hat,
-            selected_task,
-            termination_reason,
-            &empty_hook_metadata(),
-        )
-    }
-
-    async fn dispatch_pre_loop_termination_hooks(
-        event_loop: &EventLoop,
-        hooks_dispatch_enabled: bool,
-        loop_id: &str,
-        hook_engine: &HookEngine,
-        hook_executor: &HookExecutor,
-        suspend_state_store: &SuspendStateStore,
-        ctx: &LoopContext,
-        max_iterations: u32,
-        reason: TerminationReason,
-    ) -> Result<TerminationReason> {
-        let mut accumulated_hook_metadata = serde_json::Map::new();
-        super::dispatch_pre_loop_termination_hooks(
-            event_loop,
-            hooks_dispatch_enabled,
-            loop_id,
-            hook_engine,
-            hook_executor,
-            suspend_state_store,
-            ctx,
-            max_iterations,
-            &mut accumulated_hook_metadata,
-            reason,
-        )
-        .await
-    }
-
-    async fn dispatch_post_loop_termination_hooks(
-        event_loop: &EventLoop,
-        hooks_dispatch_enabled: bool,
-        loop_id: &str,
-        hook_engine: &HookEngine,
-        hook_executor: &HookExecutor,
-        suspend_state_store: &SuspendStateStore,
-        ctx: &LoopContext,
-        max_iterations: u32,
-        reason: TerminationReason,
-    ) -> Result<TerminationReason> {
-        let mut accumulated_hook_metadata = serde_json::Map::new();
-        super::dispatch_post_loop_termination_hooks(
-            event_loop,
-            hooks_dispatch_enabled,
-            loop_id,
-            hook_engine,
-            hook_executor,
-            suspend_state_store,
-            ctx,
-            max_iterations,
-            &mut accumulated_hook_metadata,
-            reason,
-        )
-        .await
-    }
-
-    #[cfg(unix)]
-    #[test]
-    fn test_dispatch_phase_event_hooks_routes_by_phase_and_preserves_order() {
-        let temp_dir = tempfile::tempdir().expect("temp dir");
-        let log_path = temp_dir.path().join("hook-dispatch.log");
-
-        let mut events = std::collections::HashMap::new();
-        events.insert(
-            HookPhaseEvent::PreIterationStart,
-            vec![
-                recording_hook("pre-iteration-first", &log_path),
-                recording_hook("pre-iteration-second", &log_path),
-            ],
-        );
-        events.insert(
-            HookPhaseEvent::PostLoopStart,
-            vec![recording_hook("post-loop-only", &log_path)],
-        );
-
-        let hook_engine = hook_engine_with_events(events);
-        let hook_executor = HookExecutor::new();
-        let event_loop = dispatch_test_event_loop(temp_dir.path());
-        let loop_ctx = LoopContext::primary(temp_dir.path().to_path_buf());
-
-        dispatch_phase_event_hooks(
-            &event_loop,
-            true,
-            "loop-test",
-            &hook_engine,
-            &hook_executor,
-            HookPhaseEvent::PreIterationStart,
-            build_iteration_start_payload_input(
-                "loop-test",
-                &loop_ctx,
-                5,
-                1,
-                Some("ralph".to_string()),
-                Some("builder".to_string()),
-                Some("task-123".to_string()),
-            ),
-        );
-
-        assert_eq!(
-            read_hook_log(&log_path),
-            vec![
-                "pre-iteration-first|pre.iteration.start".to_string(),
-                "pre-iteration-second|pre.iteration.start".to_string(),
-            ]
-        );
-
-        dispatch_phase_event_hooks(
-            &event_loop,
-            true,
-            "loop-test",
-            &hook_engine,
-            &hook_executor,
-            HookPhaseEvent::PostLoopStart,
-            build_loop_start_payload_input("loop-test", &loop_ctx, 5, 1, Some("ralph".to_string())),
-        );
-
-        assert_eq!(
-            read_hook_log(&log_path),
-            vec![
-                "pre-iteration-first|pre.iteration.start".to_string(),
-                "pre-iteration-second|pre.iteration.start".to_string(),
-                "post-loop-only|post.loop.start".to_string(),
-            ]
-        );
-    }
-
-    #[cfg(unix)]
-    #[test]
-    fn test_ac13_mutation_disabled_json_output_is_inert_for_accumulator_and_downstream_payloads() {
-        let temp_dir = tempfile::tempdir().expect("temp dir");
-        let payload_log_path = temp_dir
-            .path()
-            .join("hook-metadata-disabled-payloads.jsonl");
-
-        let mut disabled_mutation_spec = hook_spec_with_command(
-            "metadata-emitter",
-            vec![
-                "sh".to_string(),
-                "-c".to_string(),
-                "printf '%s' '{\"metadata\":{\"risk_score\":0.72}}'".to_string(),
-            ],
-        );
-        disabled_mutation_spec.mutate = hook_mutation_config(false);
-
-        let mut events = std::collections::HashMap::new();
-        events.insert(HookPhaseEvent::PreLoopStart, vec![disabled_mutation_spec]);
-        events.insert(
-            HookPhaseEvent::PostLoopStart,
-            vec![payload_recording_hook(
-                "payload-recorder",
-                &payload_log_path,
-            )],
-        );
-
-        let hook_engine = hook_engine_with_events(events);
-        let hook_executor = HookExecutor::new();
-        let event_loop = dispatch_test_event_loop(temp_dir.path());
-        let loop_ctx = LoopContext::primary(temp_dir.path().to_path_buf());
-        let mut accumulated_hook_metadata = serde_json::Map::new();
-        accumulated_hook_metadata.insert("upstream".to_string(), serde_json::json!("preserved"));
-
-        let pre_outcomes = dispatch_phase_event_hooks(
-            &event_loop,
-            true,
-            "loop-test",
-            &hook_engine,
-            &hook_executor,
-            HookPhaseEvent::PreLoopStart,
-            super::build_loop_start_payload_input(
-                "loop-test",
-                &loop_ctx,
-                5,
-                0,
-                Some("planner".to_string()),
-                &accumulated_hook_metadata,
-            ),
-        );
-
-        assert_eq!(pre_outcomes.len(), 1);
-        assert_eq!(pre_outcomes[0].disposition, HookDisposition::Pass);
-        assert_eq!(pre_outcomes[0].failure, None);
-        assert_eq!(
-            pre_outcomes[0].mutation_parse_outcome,
-            HookMutationParseOutcome::Disabled
-        );
-
-        let metadata_before_merge = accumulated_hook_metadata.clone();
-        merge_accumulated_hook_metadata_from_outcomes(
-            &mut accumulated_hook_metadata,
-            &pre_outcomes,
-        );
-        assert_eq!(accumulated_hook_metadata, metadata_before_merge);
-
-        let post_outcomes = dispatch_phase_event_hooks(
-            &event_loop,
-            true,
-            "loop-test",
-            &hook_engine,
-            &hook_executor,
-            HookPhaseEvent::PostLoopStart,
-            super::build_loop_start_payload_input(
-                "loop-test",
-                &loop_ctx,
-                5,
-                0,
-                Some("planner".to_string()),
-                &accumulated_hook_metadata,
-            ),
-        );
-        merge_accumulated_hook_metadata_from_outcomes(
-            &mut accumulated_hook_metadata,
-            &post_outcomes,
-        );
-
-        let payloads = read_hook_payload_log(&payload_log_path);
-        assert_eq!(payloads.len(), 1);
-        assert_eq!(
-            payloads[0]["metadata"]["accumulated"],
-            serde_json::json!({"upstream":"preserved"})
-        );
-
-        let payload_accumulated = payloads[0]["metadata"]["accumulated"]
-            .as_object()
-            .expect("metadata.accumulated object");
-        assert!(!payload_accumulated.contains_key("hook_metadata"));
-    }
-
-    #[cfg(unix)]
-    #[test]
-    fn test_ac14_mutation_enabled_updates_only_namespaced_metadata_in_downstream_payloads() {
-        let temp_dir = tempfile::tempdir().expect("temp dir");
-        let payload_log_path = temp_dir.path().join("hook-metadata-enabled-payloads.jsonl");
-
-        let mut mutation_spec = hook_spec_with_command(
-            "metadata-emitter",
-            vec![
-                "sh".to_string(),
-                "-c".to_string(),
-                "printf '%s' '{\"metadata\":{\"risk_score\":0.72,\"gates\":[\"policy_check\"]}}'"
-                    .to_string(),
-            ],
-        );
-        mutation_spec.mutate = hook_mutation_config(true);
-
-        let mut events = std::collections::HashMap::new();
-        events.insert(HookPhaseEvent::PreLoopStart, vec![mutation_spec]);
-        events.insert(
-            HookPhaseEvent::PostLoopStart,
-            vec![payload_recording_hook(
-                "payload-recorder",
-                &payload_log_path,
-            )],
-        );
-
-        let hook_engine = hook_engine_with_events(events);
-        let hook_executor = HookExecutor::new();
-        let event_loop = dispatch_test_event_loop(temp_dir.path());
-        let loop_ctx = LoopContext::primary(temp_dir.path().to_path_buf());
-        let mut accumulated_hook_metadata = serde_json::Map::new();
-        accumulated_hook_metadata.insert("upstream".to_string(), serde_json::json!("preserved"));
-
-        let pre_outcomes = dispatch_phase_event_hooks(
-            &event_loop,
-            true,
-            "loop-test",
-            &hook_engine,
-            &hook_executor,
-            HookPhaseEvent::PreLoopStart,
-            super::build_loop_start_payload_input(
-                "loop-test",
-                &loop_ctx,
-                5,
-                0,
-                Some("planner".to_string()),
-                &accumulated_hook_metadata,
-            ),
-        );
-        assert!(matches!(
-            pre_outcomes[0].mutation_parse_outcome,
-            HookMutationParseOutcome::Parsed { .. }
-        ));
-
-        merge_accumulated_hook_metadata_from_outcomes(
-            &mut accumulated_hook_metadata,
-            &pre_outcomes,
-        );
-        assert_eq!(
-            serde_json::Value::Object(accumulated_hook_metadata.clone()),
-            serde_json::json!({
-                "upstream": "preserved",
-                "hook_metadata": {
-                    "metadata-emitter": {
-                        "risk_score": 0.72,
-                        "gates": ["policy_check"]
-                    }
-                }
-            })
-        );
-
-        let post_outcomes = dispatch_phase_event_hooks(
-            &event_loop,
-            true,
-            "loop-test",
-            &hook_engine,
-            &hook_executor,
-            HookPhaseEvent::PostLoopStart,
-            super::build_loop_start_payload_input(
-                "loop-test",
-                &loop_ctx,
-                5,
-                0,
-                Some("planner".to_string()),
-                &accumulated_hook_metadata,
-            ),
-        );
-        merge_accumulated_hook_metadata_from_outcomes(
-            &mut accumulated_hook_metadata,
-            &post_outcomes,
-        );
-
-        let payloads = read_hook_payload_log(&payload_log_path);
-        assert_eq!(payloads.len(), 1);
-        let payload = &payloads[0];
-
-        assert_eq!(payload["phase_event"], serde_json::json!("post.loop.start"));
-        assert_eq!(
-            payload["context"]["active_hat"],
-            serde_json::json!("planner")
-        );
-        assert_eq!(
-            payload["metadata"]["accumulated"],
-            serde_json::json!({
-                "upstream": "preserved",
-                "hook_metadata": {
-                    "metadata-emitter": {
-                        "risk_score": 0.72,
-                        "gates": ["policy_check"]
-                    }
-                }
-            })
-        );
-
-        let payload_object = payload.as_object().expect("payload object");
-        assert!(!payload_object.contains_key("prompt"));
-        assert!(!payload_object.contains_key("events"));
-        assert!(!payload_object.contains_key("config"));
-
-        let context = payload["context"]
-            .as_object()
-            .expect("payload context object");
-        assert!(!context.contains_key("prompt"));
-        assert!(!context.contains_key("events"));
-        assert!(!context.contains_key("config"));
-
-        let payload_accumulated = payload["metadata"]["accumulated"]
-            .as_object()
-            .expect("metadata.accumulated object");
-        assert!(!payload_accumulated.contains_key("risk_score"));
-        assert!(!payload_accumulated.contains_key("gates"));
-    }
-
-    #[cfg(unix)]
-    #[test]
-    fn test_dispatch_phase_event_hooks_noop_when_disabled_or_unconfigured() {
-        let temp_dir = tempfile::tempdir().expect("temp dir");
-        let log_path = temp_dir.path().join("hook-noop.log");
-
-        let mut events = std::collections::HashMap::new();
-        events.insert(
-            HookPhaseEvent::PreIterationStart,
-            vec![recording_hook("should-not-run", &log_path)],
-        );
-
-        let hook_engine = hook_engine_with_events(events);
-        let empty_engine = hook_engine_with_events(std::collections::HashMap::new());
-        let hook_executor = HookExecutor::new();
-        let event_loop = dispatch_test_event_loop(temp_dir.path());
-        let loop_ctx = LoopContext::primary(temp_dir.path().to_path_buf());
-
-        let disabled_outcomes = dispatch_phase_event_hooks(
-            &event_loop,
-            false,
-            "loop-test",
-            &hook_engine,
-            &hook_executor,
-            HookPhaseEvent::PreIterationStart,
-            build_iteration_start_payload_input(
-                "loop-test",
-                &loop_ctx,
-                5,
-                1,
-                Some("ralph".to_string()),
-                Some("builder".to_string()),
-                Some("task-123".to_string()),
-            ),
-        );
-
-        let empty_outcomes = dispatch_phase_event_hooks(
-            &event_loop,
-            true,
-            "loop-test",
-            &empty_engine,
-            &hook_executor,
-            HookPhaseEvent::PreIterationStart,
-            build_iteration_start_payload_input(
-                "loop-test",
-                &loop_ctx,
-                5,
-                1,
-                Some("ralph".to_string()),
-                Some("builder".to_string()),
-                Some("task-123".to_string()),
-            ),
-        );
-
-        let mismatched_phase_outcomes = dispatch_phase_event_hooks(
-            &event_loop,
-            true,
-            "loop-test",
-            &hook_engine,
-            &hook_executor,
-            HookPhaseEvent::PostLoopStart,
-            build_loop_start_payload_input("loop-test", &loop_ctx, 5, 1, Some("ralph".to_string())),
-        );
-
-        assert!(
-            disabled_outcomes.is_empty(),
-            "disabled hooks must be a no-op"
-        );
-        assert!(
-            empty_outcomes.is_empty(),
-            "empty hooks config must be a no-op"
-        );
-        assert!(
-            mismatched_phase_outcomes.is_empty(),
-            "dispatching a phase without hooks must be a no-op"
-        );
-        assert!(
-            !log_path.exists(),
-            "hook log should not be created on no-op paths"
-        );
-    }
-
-    #[cfg(unix)]
-    #[test]
-    fn test_dispatch_phase_event_hooks_returns_dispositions_and_failure_context() {
-        let temp_dir = tempfile::tempdir().expect("temp dir");
-
-        let mut events = std::collections::HashMap::new();
-        events.insert(
-            HookPhaseEvent::PreLoopStart,
-            vec![
-                hook_spec_with_command(
-                    "hook-pass",
-                    vec!["sh".to_string(), "-c".to_string(), "exit 0".to_string()],
-                ),
-                hook_spec_with_command(
-                    "hook-warn",
-                    vec!["sh".to_string(), "-c".to_string(), "exit 7".to_string()],
-                ),
-                hook_spec_with_command_and_on_error(
-                    "hook-block",
-                    vec!["sh".to_string(), "-c".to_string(), "exit 23".to_string()],
-                    HookOnError::Block,
-                ),
-            ],
-        );
-
-        let hook_engine = hook_engine_with_events(events);
-        let hook_executor = HookExecutor::new();
-        let event_loop = dispatch_test_event_loop(temp_dir.path());
-        let loop_ctx = LoopContext::primary(temp_dir.path().to_path_buf());
-
-        let outcomes = dispatch_phase_event_hooks(
-            &event_loop,
-            true,
-            "loop-test",
-            &hook_engine,
-            &hook_executor,
-            HookPhaseEvent::PreLoopStart,
-            build_loop_start_payload_input("loop-test", &loop_ctx, 5, 1, Some("ralph".to_string())),
-        );
-
-        assert_eq!(outcomes.len(), 3);
-
-        assert_eq!(outcomes[0].hook_name, "hook-pass");
-        assert_eq!(outcomes[0].phase_event, HookPhaseEvent::PreLoopStart);
-        assert_eq!(outcomes[0].disposition, HookDisposition::Pass);
-        assert!(outcomes[0].failure.is_none());
-
-        assert_eq!(outcomes[1].hook_name, "hook-warn");
-        assert_eq!(outcomes[1].disposition, HookDisposition::Warn);
-        assert_eq!(
-            outcomes[1].failure,
-            Some(HookDispatchFailure::HookRunFailed {
-                exit_code: Some(7),
-                timed_out: false,
-            })
-        );
-
-        assert_eq!(outcomes[2].hook_name, "hook-block");
-        assert_eq!(outcomes[2].disposition, HookDisposition::Block);
-        assert_eq!(
-            outcomes[2].failure,
-            Some(HookDispatchFailure::HookRunFailed {
-                exit_code: Some(23),
-                timed_out: false,
-            })
-        );
-    }
-
-    #[cfg(unix)]
-    #[test]
-    fn test_dispatch_phase_event_hooks_maps_executor_failures_to_on_error_disposition() {
-        let temp_dir = tempfile::tempdir().expect("temp dir");
-
-        let mut events = std::collections::HashMap::new();
-        events.insert(
-            HookPhaseEvent::PreLoopStart,
-            vec![
-                hook_spec_with_command(
-                    "warn-exec-error",
-                    vec!["definitely-not-a-real-exec-warn".to_string()],
-                ),
-                hook_spec_with_command_and_on_error(
-                    "block-exec-error",
-                    vec!["definitely-not-a-real-exec-block".to_string()],
-                    HookOnError::Block,
-                ),
-            ],
-        );
-
-        let hook_engine = hook_engine_with_events(events);
-        let hook_executor = HookExecutor::new();
-        let event_loop = dispatch_test_event_loop(temp_dir.path());
-        let loop_ctx = LoopContext::primary(temp_dir.path().to_path_buf());
-
-        let outcomes = dispatch_phase_event_hooks(
-            &event_loop,
-            true,
-            "loop-test",
-            &hook_engine,
-            &hook_executor,
-            HookPhaseEvent::PreLoopStart,
-            build_loop_start_payload_input("loop-test", &loop_ctx, 5, 1, Some("ralph".to_string())),
-        );
-
-        assert_eq!(outcomes.len(), 2);
-        assert_eq!(outcomes[0].hook_name, "warn-exec-error");
-        assert_eq!(outcomes[0].disposition, HookDisposition::Warn);
-        match &outcomes[0].failure {
-            Some(HookDispatchFailure::HookExecutionError { message }) => {
-                assert!(
-                    message.contains("definitely-not-a-real-exec-warn"),
-                    "executor failure context should include missing command"
-                );
-            }
-            other => panic!("expected execution error failure context, got {other:?}"),
-        }
-
-        assert_eq!(outcomes[1].hook_name, "block-exec-error");
-        assert_eq!(outcomes[1].disposition, HookDisposition::Block);
-        match &outcomes[1].failure {
-            Some(HookDispatchFailure::HookExecutionError { message }) => {
-                assert!(
-                    message.contains("definitely-not-a-real-exec-block"),
-                    "executor failure context should include missing command"
-                );
-            }
-            other => panic!("expected execution error failure context, got {other:?}"),
-        }
-    }
-
-    // AC-15: JSON-only mutation format errors must flow through lifecycle on_error dispositions.
-    #[cfg(unix)]
-    #[test]
-    fn test_ac15_dispatch_phase_event_hooks_non_json_mutation_warn_continues_through_block_gate() {
-        let temp_dir = tempfile::tempdir().expect("temp dir");
-
-        let mut warn_hook = hook_spec_with_command_and_on_error(
-            "warn-invalid-mutation",
-            vec![
-                "sh".to_string(),
-                "-c".to_string(),
-                "printf '%s' 'oops'".to_string(),
-            ],
-            HookOnError::Warn,
-        );
-        warn_hook.mutate = hook_mutation_config(true);
-
-        let mut events = std::collections::HashMap::new();
-        events.insert(HookPhaseEvent::PreLoopStart, vec![warn_hook]);
-
-        let hook_engine = hook_engine_with_events(events);
-        let hook_executor = HookExecutor::new();
-        let event_loop = dispatch_test_event_loop(temp_dir.path());
-        let loop_ctx = LoopContext::primary(temp_dir.path().to_path_buf());
-
-        let outcomes = dispatch_phase_event_hooks(
-            &event_loop,
-            true,
-            "loop-test",
-            &hook_engine,
-            &hook_executor,
-            HookPhaseEvent::PreLoopStart,
-            build_loop_start_payload_input("loop-test", &loop_ctx, 5, 1, Some("ralph".to_string())),
-        );
-
-        assert_eq!(outcomes.len(), 1);
-        assert_eq!(outcomes[0].disposition, HookDisposition::Warn);
-        assert!(matches!(
-            outcomes[0].mutation_parse_outcome,
-            HookMutationParseOutcome::Invalid(_)
-        ));
-        assert!(matches!(
-            &outcomes[0].failure,
-            Some(HookDispatchFailure::InvalidMutationOutput { message })
-            if message.contains("not valid JSON")
-        ));
-        assert!(fail_if_blocking_loop_start_outcomes(&outcomes).is_ok());
-    }
-
-    #[cfg(unix)]
-    #[test]
-    fn test_ac15_dispatch_phase_event_hooks_non_json_mutation_block_surfaces_invalid_output_reason()
-    {
-        let temp_dir = tempfile::tempdir().expect("temp dir");
-
-        let mut block_hook = hook_spec_with_command_and_on_error(
-            "block-invalid-mutation",
-            vec![
-                "sh".to_string(),
-                "-c".to_string(),
-                "printf '%s' 'oops'".to_string(),
-            ],
-            HookOnError::Block,
-        );
-        block_hook.mutate = hook_mutation_config(true);
-
-        let mut events = std::collections::HashMap::new();
-        events.insert(HookPhaseEvent::PreLoopStart, vec![block_hook]);
-
-        let hook_engine = hook_engine_with_events(events);
-        let hook_executor = HookExecutor::new();
-        let event_loop = dispatch_test_event_loop(temp_dir.path());
-        let loop_ctx = LoopContext::primary(temp_dir.path().to_path_buf());
-
-        let outcomes = dispatch_phase_event_hooks(
-            &event_loop,
-            true,
-            "loop-test",
-            &hook_engine,
-            &hook_executor,
-            HookPhaseEvent::PreLoopStart,
-            build_loop_start_payload_input("loop-test", &loop_ctx, 5, 1, Some("ralph".to_string())),
-        );
-
-        assert_eq!(outcomes.len(), 1);
-        assert_eq!(outcomes[0].disposition, HookDisposition::Block);
-        assert!(matches!(
-            &outcomes[0].failure,
-            Some(HookDispatchFailure::InvalidMutationOutput { message })
-            if message.contains("not valid JSON")
-        ));
-
-        let block_error = fail_if_blocking_loop_start_outcomes(&outcomes)
-            .expect_err("block disposition should fail loop.start boundary");
-        let block_message = block_error.to_string();
-        assert!(block_message.contains("block-invalid-mutation"));
-        assert!(block_message.contains("pre.loop.start"));
-        assert!(block_message.contains("invalid mutation output"));
-        assert!(block_message.contains("not valid JSON"));
-    }
-
-    #[cfg(unix)]
-    #[test]
-    fn test_dispatch_phase_event_hooks_runtime_failure_takes_precedence_over_mutation_parse_error()
-    {
-        let temp_dir = tempfile::tempdir().expect("temp dir");
-
-        let mut block_hook = hook_spec_with_command_and_on_error(
-            "block-runtime-failure",
-            vec![
-                "sh".to_string(),
-                "-c".to_string(),
-                "printf '%s' 'oops'; exit 23".to_string(),
-            ],
-            HookOnError::Block,
-        );
-        block_hook.mutate = hook_mutation_config(true);
-
-        let mut events = std::collections::HashMap::new();
-        events.insert(HookPhaseEvent::PreLoopStart, vec![block_hook]);
-
-        let hook_engine = hook_engine_with_events(events);
-        let hook_executor = HookExecutor::new();
-        let event_loop = dispatch_test_event_loop(temp_dir.path());
-        let loop_ctx = LoopContext::primary(temp_dir.path().to_path_buf());
-
-        let outcomes = dispatch_phase_event_hooks(
-            &event_loop,
-            true,
-            "loop-test",
-            &hook_engine,
-            &hook_executor,
-            HookPhaseEvent::PreLoopStart,
-            build_loop_start_payload_input("loop-test", &loop_ctx, 5, 1, Some("ralph".to_string())),
-        );
-
-        assert_eq!(outcomes.len(), 1);
-        assert_eq!(outcomes[0].disposition, HookDisposition::Block);
-        assert_eq!(
-            outcomes[0].failure,
-            Some(HookDispatchFailure::HookRunFailed {
-                exit_code: Some(23),
-                timed_out: false,
-            })
-        );
-        assert!(matches!(
-            outcomes[0].mutation_parse_outcome,
-            HookMutationParseOutcome::Invalid(_)
-        ));
-
-        let block_error = fail_if_blocking_loop_start_outcomes(&outcomes)
-            .expect_err("block disposition should fail loop.start boundary");
-        let block_message = block_error.to_string();
-        assert!(block_message.contains("hook exited with code 23"));
-        assert!(!block_message.contains("invalid mutation output"));
-    }
-
-    #[cfg(unix)]
-    #[test]
-    fn test_ac15_dispatch_phase_event_hooks_non_json_mutation_suspend_uses_wait_for_resume_gate() {
-        let temp_dir = tempfile::tempdir().expect("temp dir");
-
-        let mut suspend_hook = hook_spec_with_command_and_on_error(
-            "suspend-invalid-mutation",
-            vec![
-                "sh".to_string(),
-                "-c".to_string(),
-                "printf '%s' 'oops'".to_string(),
-            ],
-            HookOnError::Suspend,
-        );
-        suspend_hook.mutate = hook_mutation_config(true);
-
-        let mut events = std::collections::HashMap::new();
-        events.insert(HookPhaseEvent::PreIterationStart, vec![suspend_hook]);
-
-        let hook_engine = hook_engine_with_events(events);
-        let hook_executor = HookExecutor::new();
-        let event_loop = dispatch_test_event_loop(temp_dir.path());
-        let loop_ctx = LoopContext::primary(temp_dir.path().to_path_buf());
-        let suspend_state_store = SuspendStateStore::new(temp_dir.path());
-
-        let outcomes = dispatch_phase_event_hooks(
-            &event_loop,
-            true,
-            "loop-test",
-            &hook_engine,
-            &hook_executor,
-            HookPhaseEvent::PreIterationStart,
-            build_iteration_start_payload_input(
-                "loop-test",
-                &loop_ctx,
-                5,
-                1,
-                Some("planner".to_string()),
-                None,
-                None,
-            ),
-        );
-
-        assert_eq!(outcomes.len(), 1);
-        assert_eq!(outcomes[0].disposition, HookDisposition::Suspend);
-        assert!(matches!(
-            &outcomes[0].failure,
-            Some(HookDispatchFailure::InvalidMutationOutput { message })
-            if message.contains("not valid JSON")
-        ));
-        assert!(fail_if_blocking_iteration_start_outcomes(&outcomes).is_ok());
-
-        let resume_store = suspend_state_store.clone();
-        let resume_handle = std::thread::spawn(move || {
-            let wait_started_at = std::time::Instant::now();
-            while !resume_store.suspend_state_path().exists() {
-                assert!(
-                    wait_started_at.elapsed() < Duration::from_secs(2),
-                    "suspend-state should be written before resume"
-                );
-                std::thread::sleep(Duration::from_millis(10));
-            }
-
-            let suspend_state = resume_store
-                .read_suspend_state()
-                .expect("read suspend-state")
-                .expect("suspend-state should exist while waiting");
-            assert!(suspend_state.reason.contains("invalid mutation output"));
-            assert!(suspend_state.reason.contains("not valid JSON"));
-
-            resume_store
-                .write_resume_requested()
-                .expect("write resume signal");
-        });
-
-        let wait_result = block_on_test_future(wait_for_resume_if_suspended(
-            &outcomes,
-            "loop-test",
-            &suspend_state_store,
-        ))
-        .expect("wait helper should succeed");
-
-        resume_handle
-            .join()
-            .expect("resume helper thread should not panic");
-
-        assert_eq!(wait_result, None);
-        assert!(
-            suspend_state_store
-                .read_suspend_state()
-                .expect("read suspend-state after resume")
-                .is_none(),
-            "suspend-state should be cleared after resume"
-        );
-        assert!(
-            !suspend_state_store.resume_requested_path().exists(),
-            "resume-requested should be consumed after resume"
-        );
-    }
-
-    #[cfg(unix)]
-    #[test]
-    fn test_loop_start_dispatch_warn_continues_and_block_aborts() {
-        let temp_dir = tempfile::tempdir().expect("temp dir");
-
-        let mut events = std::collections::HashMap::new();
-        events.insert(
-            HookPhaseEvent::PreLoopStart,
-            vec![hook_spec_with_command_and_on_error(
-                "warn-pre-loop-start",
-                vec!["sh".to_string(), "-c".to_string(), "exit 17".to_string()],
-                HookOnError::Warn,
-            )],
-        );
-        events.insert(
-            HookPhaseEvent::PostLoopStart,
-            vec![hook_spec_with_command_and_on_error(
-                "block-post-loop-start",
-                vec!["sh".to_string(), "-c".to_string(), "exit 29".to_string()],
-                HookOnError::Block,
-            )],
-        );
-
-        let hook_engine = hook_engine_with_events(events);
-        let hook_executor = HookExecutor::new();
-        let event_loop = dispatch_test_event_loop(temp_dir.path());
-        let loop_ctx = LoopContext::primary(temp_dir.path().to_path_buf());
-
-        let pre_loop_start_outcomes = dispatch_phase_event_hooks(
-            &event_loop,
-            true,
-            "loop-test",
-            &hook_engine,
-            &hook_executor,
-            HookPhaseEvent::PreLoopStart,
-            build_loop_start_payload_input("loop-test", &loop_ctx, 5, 0, None),
-        );
-
-        assert_eq!(pre_loop_start_outcomes.len(), 1);
-        assert_eq!(
-            pre_loop_start_outcomes[0].disposition,
-            HookDisposition::Warn
-        );
-        assert_eq!(
-            pre_loop_start_outcomes[0].failure,
-            Some(HookDispatchFailure::HookRunFailed {
-                exit_code: Some(17),
-                timed_out: false,
-            })
-        );
-        assert!(
-            fail_if_blocking_loop_start_outcomes(&pre_loop_start_outcomes).is_ok(),
-            "warn disposition should continue across loop.start boundary"
-        );
-
-        let post_loop_start_outcomes = dispatch_phase_event_hooks(
-            &event_loop,
-            true,
-            "loop-test",
-            &hook_engine,
-            &hook_executor,
-            HookPhaseEvent::PostLoopStart,
-            build_loop_start_payload_input(
-                "loop-test",
-                &loop_ctx,
-                5,
-                0,
-                Some("planner".to_string()),
-            ),
-        );
-
-        assert_eq!(post_loop_start_outcomes.len(), 1);
-        assert_eq!(
-            post_loop_start_outcomes[0].disposition,
-            HookDisposition::Block
-        );
-        let post_loop_start_error = fail_if_blocking_loop_start_outcomes(&post_loop_start_outcomes)
-            .expect_err("block disposition should abort loop.start boundary");
-        let post_loop_start_message = post_loop_start_error.to_string();
-        assert!(post_loop_start_message.contains("block-post-loop-start"));
-        assert!(post_loop_start_message.contains("post.loop.start"));
-        assert!(post_loop_start_message.contains("hook exited with code 29"));
-    }
-
-    #[cfg(unix)]
-    #[test]
-    fn test_iteration_start_dispatch_warn_continues_and_block_aborts() {
-        let temp_dir = tempfile::tempdir().expect("temp dir");
-
-        let mut events = std::collections::HashMap::new();
-        events.insert(
-            HookPhaseEvent::PreIterationStart,
-            vec![hook_spec_with_command_and_on_error(
-                "warn-pre-iteration-start",
-                vec!["sh".to_string(), "-c".to_string(), "exit 19".to_string()],
-                HookOnError::Warn,
-            )],
-        );
-        events.insert(
-            HookPhaseEvent::PostIterationStart,
-            vec![hook_spec_with_command_and_on_error(
-                "block-post-iteration-start",
-                vec!["sh".to_string(), "-c".to_string(), "exit 31".to_string()],
-                HookOnError::Block,
-            )],
-        );
-
-        let hook_engine = hook_engine_with_events(events);
-        let hook_executor = HookExecutor::new();
-        let event_loop = dispatch_test_event_loop(temp_dir.path());
-        let loop_ctx = LoopContext::primary(temp_dir.path().to_path_buf());
-
-        let pre_iteration_start_outcomes = dispatch_phase_event_hooks(
-            &event_loop,
-            true,
-            "loop-test",
-            &hook_engine,
-            &hook_executor,
-            HookPhaseEvent::PreIterationStart,
-            build_iteration_start_payload_input(
-                "loop-test",
-                &loop_ctx,
-                5,
-                1,
-                Some("planner".to_string()),
-                None,
-                None,
-            ),
-        );
-
-        assert_eq!(pre_iteration_start_outcomes.len(), 1);
-        assert_eq!(
-            pre_iteration_start_outcomes[0].disposition,
-            HookDisposition::Warn
-        );
-        assert_eq!(
-            pre_iteration_start_outcomes[0].failure,
-            Some(HookDispatchFailure::HookRunFailed {
-                exit_code: Some(19),
-                timed_out: false,
-            })
-        );
-        assert!(
-            fail_if_blocking_iteration_start_outcomes(&pre_iteration_start_outcomes).is_ok(),
-            "warn disposition should continue across iteration.start boundary"
-        );
-
-        let post_iteration_start_outcomes = dispatch_phase_event_hooks(
-            &event_loop,
-            true,
-            "loop-test",
-            &hook_engine,
-            &hook_executor,
-            HookPhaseEvent::PostIterationStart,
-            build_iteration_start_payload_input(
-                "loop-test",
-                &loop_ctx,
-                5,
-                1,
-                Some("planner".to_string()),
-                Some("builder".to_string()),
-                Some("task-123".to_string()),
-            ),
-        );
-
-        assert_eq!(post_iteration_start_outcomes.len(), 1);
-        assert_eq!(
-            post_iteration_start_outcomes[0].disposition,
-            HookDisposition::Block
-        );
-        let post_iteration_start_error =
-            fail_if_blocking_iteration_start_outcomes(&post_iteration_start_outcomes)
-                .expect_err("block disposition should abort iteration.start boundary");
-        let post_iteration_start_message = post_iteration_start_error.to_string();
-        assert!(post_iteration_start_message.contains("block-post-iteration-start"));
-        assert!(post_iteration_start_message.contains("post.iteration.start"));
-        assert!(post_iteration_start_message.contains("hook exited with code 31"));
-    }
-
-    #[cfg(unix)]
-    #[test]
-    fn test_plan_created_lifecycle_hooks_dispatch_only_for_semantic_plan_batches() {
-        use std::io::Write;
-
-        let temp_dir = tempfile::tempdir().expect("temp dir");
-        let (mut event_loop, loop_ctx) = dispatch_test_event_loop_with_context(temp_dir.path());
-        let events_path = loop_ctx.events_path();
-        std::fs::create_dir_all(events_path.parent().expect("events path parent"))
-            .expect("create events directory");
-
-        let mut events_file = std::fs::OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(&events_path)
-            .expect("open events file");
-        writeln!(
-            events_file,
-            r#"{{"topic":"task.start","payload":"noop","ts":"2024-01-01T00:00:00Z"}}"#
-        )
-        .expect("write non-plan event");
-        events_file.flush().expect("flush non-plan event");
-
-        let log_path = temp_dir.path().join("plan-created-hook-payloads.jsonl");
-        let mut events = std::collections::HashMap::new();
-        events.insert(
-            HookPhaseEvent::PrePlanCreated,
-            vec![payload_recording_hook("pre-plan-created", &log_path)],
-        );
-        events.insert(
-            HookPhaseEvent::PostPlanCreated,
-            vec![payload_recording_hook("post-plan-created", &log_path)],
-        );
-        let hook_engine = hook_engine_with_events(events);
-        let hook_executor = HookExecutor::new();
-
-        assert!(
-            !event_loop
-                .has_pending_plan_events_in_jsonl()
-                .expect("peek non-plan events"),
-            "non-plan batches must not trigger pre.plan.created"
-        );
-
-        let processed_non_plan = event_loop
-            .process_events_from_jsonl()
-            .expect("process non-plan batch");
-        assert!(processed_non_plan.had_events);
-        assert!(
-            !processed_non_plan.had_plan_events,
-            "non-plan batches must not trigger post.plan.created"
-        );
-        assert!(
-            !log_path.exists(),
-            "plan.created hooks should not run for non-plan batches"
-        );
-
-        writeln!(
-            events_file,
-            r#"{{"topic":"plan.created","payload":"ready","ts":"2024-01-01T00:00:01Z"}}"#
-        )
-        .expect("write plan event");
-        events_file.flush().expect("flush plan event");
-
-        assert!(
-            event_loop
-                .has_pending_plan_events_in_jsonl()
-                .expect("peek plan events"),
-            "plan.* batches should trigger pre.plan.created"
-        );
-
-        let pre_outcomes = dispatch_phase_event_hooks(
-            &event_loop,
-            true,
-            "loop-test",
-            &hook_engine,
-            &hook_executor,
-            HookPhaseEvent::PrePlanCreated,
-            build_plan_created_payload_input(
-                "loop-test",
-                &loop_ctx,
-                5,
-                event_loop.state().iteration,
-                Some("planner".to_string()),
-                Some("planner".to_string()),
-                None,
-            ),
-        );
-        assert!(fail_if_blocking_plan_created_outcomes(&pre_outcomes).is_ok());
-
-        let processed_plan = event_loop
-            .process_events_from_jsonl()
-            .expect("process plan batch");
-        assert!(processed_plan.had_events);
-        assert!(
-            processed_plan.had_plan_events,
-            "plan.* batches should trigger post.plan.created"
-        );
-
-        let post_outcomes = dispatch_phase_event_hooks(
-            &event_loop,
-            true,
-            "loop-test",
-            &hook_engine,
-            &hook_executor,
-            HookPhaseEvent::PostPlanCreated,
-            build_plan_created_payload_input(
-                "loop-test",
-                &loop_ctx,
-                5,
-                event_loop.state().iteration,
-                Some("planner".to_string()),
-                Some("planner".to_string()),
-                None,
-            ),
-        );
-        assert!(fail_if_blocking_plan_created_outcomes(&post_outcomes).is_ok());
-
-        let payloads = read_hook_payload_log(&log_path);
-        let observed_phases: Vec<&str> = payloads
-            .iter()
-            .map(|payload| {
-                payload["phase_event"]
-                    .as_str()
-                    .expect("phase_event should be present")
-            })
-            .collect();
-
-        assert_eq!(
-            observed_phases,
-            vec!["pre.plan.created", "post.plan.created"],
-            "plan.created hooks should dispatch exactly once around semantic plan batches"
-        );
-    }
-
-    #[cfg(unix)]
-    #[test]
-    fn test_human_interact_lifecycle_hooks_dispatch_with_post_outcome_context() {
-        use std::io::Write;
-
-        let temp_dir = tempfile::tempdir().expect("temp dir");
-        let (mut event_loop, loop_ctx) = dispatch_test_event_loop_with_context(temp_dir.path());
-        let events_path = loop_ctx.events_path();
-        std::fs::create_dir_all(events_path.parent().expect("events path parent"))
-            .expect("create events directory");
-
-        let mut events_file = std::fs::OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(&events_path)
-            .expect("open events file");
-        writeln!(
-            events_file,
-            r#"{{"topic":"human.interact","payload":"Need approval?","ts":"2024-01-01T00:00:00Z"}}"#
-        )
-        .expect("write human.interact event");
-        events_file.flush().expect("flush human.interact event");
-
-        let log_path = temp_dir.path().join("human-interact-hook-payloads.jsonl");
-        let mut events = std::collections::HashMap::new();
-        events.insert(
-            HookPhaseEvent::PreHumanInteract,
-            vec![payload_recording_hook("pre-human-interact", &log_path)],
-        );
-        events.insert(
-            HookPhaseEvent::PostHumanInteract,
-            vec![payload_recording_hook("post-human-interact", &log_path)],
-        );
-        let hook_engine = hook_engine_with_events(events);
-        let hook_executor = HookExecutor::new();
-
-        let pending_context = event_loop
-            .pending_human_interact_context_in_jsonl()
-            .expect("peek pending human.interact context")
-            .expect("pending human.interact context should exist");
-        assert_eq!(
-            pending_context["question"],
-            serde_json::json!("Need approval?")
-        );
-        assert!(
-            pending_context.get("outcome").is_none(),
-            "pre human.interact context should not include an outcome"
-        );
-
-        let pre_outcomes = dispatch_phase_event_hooks(
-            &event_loop,
-            true,
-            "loop-test",
-            &hook_engine,
-            &hook_executor,
-            HookPhaseEvent::PreHumanInteract,
-            build_human_interact_payload_input(
-                "loop-test",
-                &loop_ctx,
-                5,
-                event_loop.state().iteration,
-                Some("planner".to_string()),
-                Some("planner".to_string()),
-                None,
-                Some(pending_context),
-            ),
-        );
-        assert!(fail_if_blocking_human_interact_outcomes(&pre_outcomes).is_ok());
-
-        let processed = event_loop
-            .process_events_from_jsonl()
-            .expect("process human.interact batch");
-        let post_context = processed
-            .human_interact_context
-            .expect("processed context should include human.interact outcome");
-        assert_eq!(
-            post_context["question"],
-            serde_json::json!("Need approval?")
-        );
-        assert_eq!(
-            post_context["outcome"],
-            serde_json::json!("no_robot_service")
-        );
-
-        let post_outcomes = dispatch_phase_event_hooks(
-            &event_loop,
-            true,
-            "loop-test",
-            &hook_engine,
-            &hook_executor,
-            HookPhaseEvent::PostHumanInteract,
-            build_human_interact_payload_input(
-                "loop-test",
-                &loop_ctx,
-                5,
-                event_loop.state().iteration,
-                Some("planner".to_string()),
-                Some("planner".to_string()),
-                None,
-                Some(post_context),
-            ),
-        );
-        assert!(fail_if_blocking_human_interact_outcomes(&post_outcomes).is_ok());
-
-        let payloads = read_hook_payload_log(&log_path);
-        assert_eq!(payloads.len(), 2);
-        assert_eq!(
-            payloads[0]["phase_event"],
-            serde_json::json!("pre.human.interact")
-        );
-        assert_eq!(
-            payloads[0]["context"]["human_interact"]["question"],
-            serde_json::json!("Need approval?")
-        );
-        assert!(
-            payloads[0]["context"]["human_interact"]
-                .get("outcome")
-                .is_none(),
-            "pre.human.interact payload should not include outcome"
-        );
-
-        assert_eq!(
-            payloads[1]["phase_event"],
-            serde_json::json!("post.human.interact")
-        );
-        assert_eq!(
-            payloads[1]["context"]["human_interact"]["question"],
-            serde_json::json!("Need approval?")
-        );
-        assert_eq!(
-            payloads[1]["context"]["human_interact"]["outcome"],
-            serde_json::json!("no_robot_service")
-        );
-    }
-
-    #[cfg(unix)]
-    #[test]
-    fn test_loop_termination_lifecycle_hooks_dispatch_complete_and_error_boundaries() {
-        let temp_dir = tempfile::tempdir().expect("temp dir");
-        let log_path = temp_dir.path().join("loop-termination-hook-payloads.jsonl");
-
-        let mut events = std::collections::HashMap::new();
-        events.insert(
-            HookPhaseEvent::PreLoopComplete,
-            vec![payload_recording_hook("pre-loop-complete", &log_path)],
-        );
-        events.insert(
-            HookPhaseEvent::PostLoopComplete,
-            vec![payload_recording_hook("post-loop-complete", &log_path)],
-        );
-        events.insert(
-            HookPhaseEvent::PreLoopError,
-            vec![payload_recording_hook("pre-loop-error", &log_path)],
-        );
-        events.insert(
-            HookPhaseEvent::PostLoopError,
-            vec![payload_recording_hook("post-loop-error", &log_path)],
-        );
-
-        let hook_engine = hook_engine_with_events(events);
-        let hook_executor = HookExecutor::new();
-        let event_loop = dispatch_test_event_loop(temp_dir.path());
-        let loop_ctx = LoopContext::primary(temp_dir.path().to_path_buf());
-        let suspend_state_store = SuspendStateStore::new(temp_dir.path());
-
-        let completed_reason = block_on_test_future(dispatch_pre_loop_termination_hooks(
-            &event_loop,
-            true,
-            "loop-test",
-            &hook_engine,
-            &hook_executor,
-            &suspend_state_store,
-            &loop_ctx,
-            5,
-            TerminationReason::CompletionPromise,
-        ))
-        .expect("pre.loop.complete dispatch should succeed");
-        let completed_reason = block_on_test_future(dispatch_post_loop_termination_hooks(
-            &event_loop,
-            true,
-            "loop-test",
-            &hook_engine,
-            &hook_executor,
-            &suspend_state_store,
-            &loop_ctx,
-            5,
-            completed_reason,
-        ))
-        .expect("post.loop.complete dispatch should succeed");
-        assert_eq!(completed_reason, TerminationReason::CompletionPromise);
-
-        let error_reason = block_on_test_future(dispatch_pre_loop_termination_hooks(
-            &event_loop,
-            true,
-            "loop-test",
-            &hook_engine,
-            &hook_executor,
-            &suspend_state_store,
-            &loop_ctx,
-            5,
-            TerminationReason::MaxRuntime,
-        ))
-        .expect("pre.loop.error dispatch should succeed");
-        let error_reason = block_on_test_future(dispatch_post_loop_termination_hooks(
-            &event_loop,
-            true,
-            "loop-test",
-            &hook_engine,
-            &hook_executor,
-            &suspend_state_store,
-            &loop_ctx,
-            5,
-            error_reason,
-        ))
-        .expect("post.loop.error dispatch should succeed");
-        assert_eq!(error_reason, TerminationReason::MaxRuntime);
-
-        let payloads = read_hook_payload_log(&log_path);
-        let phases: Vec<&str> = payloads
-            .iter()
-            .map(|payload| {
-                payload["phase_event"]
-                    .as_str()
-                    .expect("phase_event should be present")
-            })
-            .collect();
-        let reasons: Vec<&str> = payloads
-            .iter()
-            .map(|payload| {
-                payload["context"]["termination_reason"]
-                    .as_str()
-                    .expect("termination_reason should be present")
-            })
-            .collect();
-
-        assert_eq!(
-            phases,
-            vec![
-                "pre.loop.complete",
-                "post.loop.complete",
-                "pre.loop.error",
-                "post.loop.error"
-            ]
-        );
-        assert_eq!(
-            reasons,
-            vec!["completed", "completed", "max_runtime", "max_runtime"]
-        );
+                "Prompt file '{}' not found. Check the path or use -p \"text\" for inline prompt.",
+                prompt_file
+            );
+        }
     }
 
-    #[cfg(unix)]
-    #[test]
-    fn test_iteration_start_suspend_waits_for_resume_and_clears_artifacts_before_continuing() {
-        let temp_dir = tempfile::tempdir().expect("temp dir");
+    // No valid prompt source found
+    anyhow::bail!(
+        "No prompt specified. Use -p \"text\" for inline prompt, -P path for file, \
+         or create PROMPT.md in the current directory."
+    )
+}
 
-        let mut events = std::collections::HashMap::new();
-        events.insert(
-            HookPhaseEvent::PreIterationStart,
-            vec![hook_spec_with_command_and_on_error(
-                "suspend-pre-iteration-start",
-                vec!["sh".to_string(), "-c".to_string(), "exit 41".to_string()],
-                HookOnError::Suspend,
-            )],
-        );
+/// Checks for planning session user responses and publishes them as events.
+///
+/// When running in planning mode (RALPH_PLANNING_SESSION_ID is set),
+/// this function reads the conversation file for new user responses and
+/// publishes them as `user.response` events to the event loop.
+fn check_planning_session_responses(event_loop: &mut EventLoop) -> Result<()> {
+    // Get the planning session ID from environment
+    let session_id = match std::env::var("RALPH_PLANNING_SESSION_ID") {
+        Ok(id) => id,
+        Err(_) => return Ok(()), // Not in planning mode
+    };
+    check_planning_session_responses_for_session(event_loop, &session_id)
+}
 
-        let hook_engine = hook_engine_with_events(events);
-        let hook_executor = HookExecutor::new();
-        let event_loop = dispatch_test_event_loop(temp_dir.path());
-        let loop_ctx = LoopContext::primary(temp_dir.path().to_path_buf());
+fn check_planning_session_responses_for_session(
+    event_loop: &mut EventLoop,
+    session_id: &str,
+) -> Result<()> {
+    // Get loop context to find the conversation file path
+    let ctx = match event_loop.loop_context() {
+        Some(ctx) => ctx,
+        None => return Ok(()), // No context, can't find conversation file
+    };
 
-        let pre_iteration_start_outcomes = dispatch_phase_event_hooks(
-            &event_loop,
-            true,
-            "loop-test",
-            &hook_engine,
-            &hook_executor,
-            HookPhaseEvent::PreIterationStart,
-            build_iteration_start_payload_input(
-                "loop-test",
-                &loop_ctx,
-                5,
-                1,
-                Some("planner".to_string()),
-                None,
-                None,
-            ),
-        );
+    let conversation_path = ctx.planning_conversation_path(session_id);
 
-        assert_eq!(pre_iteration_start_outcomes.len(), 1);
-        assert_eq!(
-            pre_iteration_start_outcomes[0].disposition,
-            HookDisposition::Suspend
-        );
-        assert_eq!(
-            pre_iteration_start_outcomes[0].failure,
-            Some(HookDispatchFailure::HookRunFailed {
-                exit_code: Some(41),
-                timed_out: false,
-            })
-        );
-        assert!(
-            fail_if_blocking_iteration_start_outcomes(&pre_iteration_start_outcomes).is_ok(),
-            "suspend disposition should not block iteration.start boundary"
-        );
+    // Read conversation entries and look for new responses
+    // We track which response IDs we've already processed to avoid duplicates
 
-        let suspend_state_store = SuspendStateStore::new(temp_dir.path());
+    // Track processed response IDs (static to persist across iterations)
+    static PROCESSED_RESPONSES: std::sync::Mutex<Vec<String>> = std::sync::Mutex::new(Vec::new());
 
-        let wait_result = block_on_test_future(async {
-            let wait_outcomes = pre_iteration_start_outcomes.clone();
-            let wait_store = suspend_state_store.clone();
-            let wait_handle = tokio::spawn(async move {
-                wait_for_resume_if_suspended(&wait_outcomes, "loop-test", &wait_store).await
-            });
+    let conversation_content = match fs::read_to_string(&conversation_path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()), // File doesn't exist yet
+        Err(e) => {
+            warn!(
+                session_id = %session_id,
+                error = %e,
+                "Failed to read planning conversation file"
+            );
+            return Ok(());
+        }
+    };
 
-            tokio::time::timeout(Duration::from_secs(2), async {
-                loop {
-                    if suspend_state_store.suspend_state_path().exists() {
-                        break;
-                    }
+    let mut processed = PROCESSED_RESPONSES.lock().unwrap();
 
-                    tokio::time::sleep(Duration::from_millis(25)).await;
-                }
-            })
-            .await
-            .expect("suspend-state should be written before resume");
-
-            let suspend_state = suspend_state_store
-                .read_suspend_state()
-                .expect("read suspend-state")
-                .expect("suspend-state should exist while waiting for resume");
-
-            assert_eq!(suspend_state.loop_id, "loop-test");
-            assert_eq!(suspend_state.phase_event, HookPhaseEvent::PreIterationStart);
-            assert_eq!(suspend_state.hook_name, "suspend-pre-iteration-start");
-            assert_eq!(suspend_state.suspend_mode, HookSuspendMode::WaitForResume);
-            assert!(!suspend_state_store.resume_requested_path().exists());
-
-            suspend_state_store
-                .write_resume_requested()
-                .expect("write resume signal");
-
-            tokio::time::timeout(Duration::from_secs(2), wait_handle)
-                .await
-                .expect("wait_for_resume helper should complete after resume signal")
-                .expect("wait_for_resume task should not panic")
-        })
-        .expect("wait helper should succeed");
+    for line in conversation_content.lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
 
-        assert_eq!(wait_result, None);
-        assert!(
-            suspend_state_store
-                .read_suspend_state()
-                .expect("read suspend-state after resume")
-                .is_none(),
-            "suspend-state should be cleared after resume"
-        );
-        assert!(
-            !suspend_state_store.resume_requested_path().exists(),
-            "resume-requested should be consumed after resume"
-        );
-    }
+        // Parse the conversation entry
+        let entry: ralph_core::planning_session::ConversationEntry =
+            match serde_json::from_str(line) {
+                Ok(entry) => entry,
+                Err(e) => {
+                    warn!(
+                        session_id = %session_id,
+                        line = %line,
+                        error = %e,
+                        "Failed to parse conversation entry"
+                    );
+                    continue;
+                }
+            };
 
-    #[cfg(unix)]
-    #[test]
-    fn test_dispatch_phase_event_hooks_retry_backoff_recovers_before_exhaustion() {
-        let temp_dir = tempfile::tempdir().expect("temp dir");
-        let attempts_path = temp_dir.path().join("retry-backoff-attempts.txt");
-
-        let mut events = std::collections::HashMap::new();
-        events.insert(
-            HookPhaseEvent::PreIterationStart,
-            vec![hook_spec_with_command_and_on_error_and_suspend_mode(
-                "retry-backoff-pre-iteration-start",
-                vec![
-                    "sh".to_string(),
-                    "-c".to_string(),
-                    r#"attempts_file="$1"
-attempt=0
-if [ -f "$attempts_file" ]; then
-  attempt="$(cat "$attempts_file")"
-fi
-attempt=$((attempt + 1))
-printf '%s' "$attempt" > "$attempts_file"
-if [ "$attempt" -lt 3 ]; then
-  exit 41
-fi
-exit 0"#
-                        .to_string(),
-                    "retry-backoff-hook".to_string(),
-                    attempts_path.to_string_lossy().into_owned(),
-                ],
-                HookOnError::Suspend,
-                Some(HookSuspendMode::RetryBackoff),
-            )],
-        );
+        // Only process user_response entries
+        if entry.entry_type != ralph_core::planning_session::ConversationType::UserResponse {
+            continue;
+        }
 
-        let hook_engine = hook_engine_with_events(events);
-        let hook_executor = HookExecutor::new();
-        let event_loop = dispatch_test_event_loop_with_diagnostics(temp_dir.path());
-        let loop_ctx = LoopContext::primary(temp_dir.path().to_path_buf());
+        // Check if we've already processed this response
+        let response_key = format!("{}:{}", entry.id, entry.ts);
+        if processed.contains(&response_key) {
+            continue;
+        }
 
-        let outcomes = dispatch_phase_event_hooks(
-            &event_loop,
-            true,
-            "loop-test",
-            &hook_engine,
-            &hook_executor,
-            HookPhaseEvent::PreIterationStart,
-            build_iteration_start_payload_input(
-                "loop-test",
-                &loop_ctx,
-                5,
-                1,
-                Some("planner".to_string()),
-                None,
-                None,
-            ),
+        // Publish as user.response event
+        let event = Event::new(
+            "user.response",
+            format!("[id: {}] {}", entry.id, entry.text),
         );
+        event_loop.bus().publish(event.clone());
 
-        assert_eq!(outcomes.len(), 1);
-        assert_eq!(outcomes[0].disposition, HookDisposition::Pass);
-        assert_eq!(outcomes[0].suspend_mode, HookSuspendMode::RetryBackoff);
-        assert_eq!(outcomes[0].failure, None);
-
-        let attempts = std::fs::read_to_string(&attempts_path).expect("read attempts");
-        assert_eq!(attempts.trim(), "3", "hook should recover on third attempt");
-
-        let telemetry_entries = read_hook_run_telemetry_entries(temp_dir.path());
-        assert_eq!(telemetry_entries.len(), 3);
-        assert_eq!(
-            telemetry_entries
-                .iter()
-                .map(|entry| entry.retry_attempt)
-                .collect::<Vec<_>>(),
-            vec![1, 2, 3]
-        );
-        assert!(
-            telemetry_entries
-                .iter()
-                .all(|entry| entry.retry_max_attempts == 4)
-        );
-        assert!(
-            telemetry_entries
-                .iter()
-                .all(|entry| entry.suspend_mode == HookSuspendMode::RetryBackoff)
-        );
-        assert_eq!(
-            telemetry_entries
-                .iter()
-                .map(|entry| entry.disposition)
-                .collect::<Vec<_>>(),
-            vec![
-                HookDisposition::Suspend,
-                HookDisposition::Suspend,
-                HookDisposition::Pass,
-            ]
+        info!(
+            session_id = %session_id,
+            response_id = %entry.id,
+            "Published user response from planning session"
         );
-    }
 
-    #[cfg(unix)]
-    #[test]
-    fn test_dispatch_phase_event_hooks_retry_backoff_exhausts_to_suspend() {
-        let temp_dir = tempfile::tempdir().expect("temp dir");
-        let attempts_path = temp_dir.path().join("retry-backoff-attempts.txt");
-
-        let mut events = std::collections::HashMap::new();
-        events.insert(
-            HookPhaseEvent::PostIterationStart,
-            vec![hook_spec_with_command_and_on_error_and_suspend_mode(
-                "retry-backoff-post-iteration-start",
-                vec![
-                    "sh".to_string(),
-                    "-c".to_string(),
-                    r#"attempts_file="$1"
-attempt=0
-if [ -f "$attempts_file" ]; then
-  attempt="$(cat "$attempts_file")"
-fi
-attempt=$((attempt + 1))
-printf '%s' "$attempt" > "$attempts_file"
-exit 51"#
-                        .to_string(),
-                    "retry-backoff-hook".to_string(),
-                    attempts_path.to_string_lossy().into_owned(),
-                ],
-                HookOnError::Suspend,
-                Some(HookSuspendMode::RetryBackoff),
-            )],
-        );
+        // Mark as processed
+        processed.push(response_key);
+    }
 
-        let hook_engine = hook_engine_with_events(events);
-        let hook_executor = HookExecutor::new();
-        let event_loop = dispatch_test_event_loop(temp_dir.path());
-        let loop_ctx = LoopContext::primary(temp_dir.path().to_path_buf());
+    Ok(())
+}
 
-        let outcomes = dispatch_phase_event_hooks(
-            &event_loop,
-            true,
-            "loop-test",
-            &hook_engine,
-            &hook_executor,
-            HookPhaseEvent::PostIterationStart,
-            build_iteration_start_payload_input(
-                "loop-test",
-                &loop_ctx,
-                5,
-                1,
-                Some("planner".to_string()),
-                Some("builder".to_string()),
-                Some("task-123".to_string()),
-            ),
-        );
+/// Spawns merge-ralph for every queued loop (`merge.executor: llm`).
+///
+/// Spawns merge-ralph processes for each queued loop in FIFO order.
+fn process_pending_merges_with_command(repo_root: &Path, ralph_cmd: &OsStr) {
+    let queue = MergeQueue::new(repo_root);
 
-        assert_eq!(outcomes.len(), 1);
-        assert_eq!(outcomes[0].disposition, HookDisposition::Suspend);
-        assert_eq!(outcomes[0].suspend_mode, HookSuspendMode::RetryBackoff);
-        assert_eq!(
-            outcomes[0].failure,
-            Some(HookDispatchFailure::HookRunFailed {
-                exit_code: Some(51),
-                timed_out: false,
-            })
-        );
+    // Get all pending merges
+    let pending = match queue.list_by_state(ralph_core::merge_queue::MergeState::Queued) {
+        Ok(entries) => entries,
+        Err(e) => {
+            warn!("Failed to read merge queue: {}", e);
+            return;
+        }
+    };
 
-        let attempts: usize = std::fs::read_to_string(&attempts_path)
-            .expect("read attempts")
-            .trim()
-            .parse()
-            .expect("parse attempts");
-        assert_eq!(
-            attempts,
-            RETRY_BACKOFF_DELAYS_MS.len() + 1,
-            "retry_backoff should cap retries at the configured schedule"
-        );
+    if pending.is_empty() {
+        debug!("No pending merges in queue");
+        return;
     }
 
-    #[cfg(unix)]
-    #[test]
-    fn test_dispatch_phase_event_hooks_retry_backoff_yields_to_stop_signal() {
-        let temp_dir = tempfile::tempdir().expect("temp dir");
-        let attempts_path = temp_dir.path().join("retry-backoff-attempts.txt");
-        std::fs::create_dir_all(temp_dir.path().join(".ralph")).expect("create .ralph");
-        std::fs::write(temp_dir.path().join(".ralph/stop-requested"), "")
-            .expect("write stop signal");
-
-        let mut events = std::collections::HashMap::new();
-        events.insert(
-            HookPhaseEvent::PreLoopStart,
-            vec![hook_spec_with_command_and_on_error_and_suspend_mode(
-                "retry-backoff-pre-loop-start",
-                vec![
-                    "sh".to_string(),
-                    "-c".to_string(),
-                    r#"attempts_file="$1"
-attempt=0
-if [ -f "$attempts_file" ]; then
-  attempt="$(cat "$attempts_file")"
-fi
-attempt=$((attempt + 1))
-printf '%s' "$attempt" > "$attempts_file"
-exit 61"#
-                        .to_string(),
-                    "retry-backoff-hook".to_string(),
-                    attempts_path.to_string_lossy().into_owned(),
-                ],
-                HookOnError::Suspend,
-                Some(HookSuspendMode::RetryBackoff),
-            )],
-        );
+    info!(
+        count = pending.len(),
+        "Processing pending merges from queue"
+    );
 
-        let hook_engine = hook_engine_with_events(events);
-        let hook_executor = HookExecutor::new();
-        let event_loop = dispatch_test_event_loop(temp_dir.path());
-        let loop_ctx = LoopContext::primary(temp_dir.path().to_path_buf());
+    if !write_merge_loop_config(repo_root) {
+        return;
+    }
 
-        let outcomes = dispatch_phase_event_hooks(
-            &event_loop,
-            true,
-            "loop-test",
-            &hook_engine,
-            &hook_executor,
-            HookPhaseEvent::PreLoopStart,
-            build_loop_start_payload_input("loop-test", &loop_ctx, 5, 1, Some("ralph".to_string())),
-        );
+    // Process each pending merge
+    for entry in pending {
+        let base_branch = entry
+            .base_branch
+            .clone()
+            .unwrap_or_else(|| ralph_core::loop_base_branch(repo_root, &entry.loop_id));
+        spawn_merge_ralph_process(repo_root, ralph_cmd, &entry.loop_id, &base_branch);
+    }
+}
 
-        let attempts = std::fs::read_to_string(&attempts_path).expect("read attempts");
-        assert_eq!(
-            attempts.trim(),
-            "1",
-            "stop signal should short-circuit retry_backoff retries"
-        );
+/// Writes the core-only merge-loop config shared by all merge-ralph processes.
+///
+/// Returns false (leaving entries for manual retry) when the preset is unavailable.
+fn write_merge_loop_config(repo_root: &Path) -> bool {
+    // Get the merge-loop preset content
+    let preset = match crate::presets::get_preset("merge-loop") {
+        Some(p) => p,
+        None => {
+            warn!("merge-loop preset not found, pending merges will remain queued");
+            return false;
+        }
+    };
 
-        let suspend_state_store = SuspendStateStore::new(temp_dir.path());
-        let wait_result = block_on_test_future(wait_for_resume_if_suspended(
-            &outcomes,
-            "loop-test",
-            &suspend_state_store,
-        ))
-        .expect("wait helper should succeed");
+    // Write a core-only merge config once (shared by all merge loops).
+    let mut core_value: serde_yaml::Value = match serde_yaml::from_str(preset.content) {
+        Ok(value) => value,
+        Err(e) => {
+            warn!(
+                error = %e,
+                "Failed to parse merge-loop preset, pending merges will remain queued"
+            );
+            return false;
+        }
+    };
 
-        assert_eq!(wait_result, Some(TerminationReason::Stopped));
-        assert!(!temp_dir.path().join(".ralph/stop-requested").exists());
+    if let Some(mapping) = core_value.as_mapping_mut() {
+        let hats_key = serde_yaml::Value::String("hats".to_string());
+        let events_key = serde_yaml::Value::String("events".to_string());
+        mapping.remove(&hats_key);
+        mapping.remove(&events_key);
     }
 
-    #[cfg(unix)]
-    #[test]
-    fn test_dispatch_phase_event_hooks_wait_then_retry_recovers_after_resume() {
-        let temp_dir = tempfile::tempdir().expect("temp dir");
-        let attempts_path = temp_dir.path().join("wait-then-retry-attempts.txt");
-
-        let mut events = std::collections::HashMap::new();
-        events.insert(
-            HookPhaseEvent::PreIterationStart,
-            vec![hook_spec_with_command_and_on_error_and_suspend_mode(
-                "wait-then-retry-pre-iteration-start",
-                vec![
-                    "sh".to_string(),
-                    "-c".to_string(),
-                    r#"attempts_file="$1"
-attempt=0
-if [ -f "$attempts_file" ]; then
-  attempt="$(cat "$attempts_file")"
-fi
-attempt=$((attempt + 1))
-printf '%s' "$attempt" > "$attempts_file"
-if [ "$attempt" -lt 2 ]; then
-  exit 71
-fi
-exit 0"#
-                        .to_string(),
-                    "wait-then-retry-hook".to_string(),
-                    attempts_path.to_string_lossy().into_owned(),
-                ],
-                HookOnError::Suspend,
-                Some(HookSuspendMode::WaitThenRetry),
-            )],
-        );
-
-        let hook_engine = hook_engine_with_events(events);
-        let hook_executor = HookExecutor::new();
-        let event_loop = dispatch_test_event_loop_with_diagnostics(temp_dir.path());
-        let loop_ctx = LoopContext::primary(temp_dir.path().to_path_buf());
-        let suspend_state_store = SuspendStateStore::new(temp_dir.path());
-
-        let resume_store = suspend_state_store.clone();
-        let resume_handle = std::thread::spawn(move || {
-            let wait_started_at = std::time::Instant::now();
-            while !resume_store.suspend_state_path().exists() {
-                assert!(
-                    wait_started_at.elapsed() < Duration::from_secs(2),
-                    "wait_then_retry should persist suspend-state before waiting"
-                );
-                std::thread::sleep(Duration::from_millis(10));
-            }
-
-            resume_store
-                .write_resume_requested()
-                .expect("write resume signal");
-        });
+    let core_yaml = match serde_yaml::to_string(&core_value) {
+        Ok(yaml) => yaml,
+        Err(e) => {
+            warn!(
+                error = %e,
+                "Failed to serialize core-only merge config, pending merges will remain queued"
+            );
+            return false;
+        }
+    };
 
-        let outcomes = dispatch_phase_event_hooks(
-            &event_loop,
-            true,
-            "loop-test",
-            &hook_engine,
-            &hook_executor,
-            HookPhaseEvent::PreIterationStart,
-            build_iteration_start_payload_input(
-                "loop-test",
-                &loop_ctx,
-                5,
-                1,
-                Some("planner".to_string()),
-                None,
-                None,
-            ),
+    let config_path = repo_root.join(".ralph/merge-loop-config.yml");
+    if let Err(e) = fs::write(&config_path, core_yaml) {
+        warn!(
+            error = %e,
+            "Failed to write merge config, pending merges will remain queued"
         );
+        return false;
+    }
 
-        resume_handle
-            .join()
-            .expect("resume helper thread should not panic");
-
-        assert_eq!(outcomes.len(), 1);
-        assert_eq!(outcomes[0].disposition, HookDisposition::Pass);
-        assert_eq!(outcomes[0].suspend_mode, HookSuspendMode::WaitThenRetry);
-        assert_eq!(outcomes[0].failure, None);
-
-        let attempts = std::fs::read_to_string(&attempts_path).expect("read attempts");
-        assert_eq!(
-            attempts.trim(),
-            "2",
-            "wait_then_retry should run exactly one retry after resume"
-        );
-        assert!(
-            suspend_state_store
-                .read_suspend_state()
-                .expect("read suspend-state after wait_then_retry")
-                .is_none(),
-            "suspend-state should be cleared after wait_then_retry resume"
-        );
-        assert!(
-            !suspend_state_store.resume_requested_path().exists(),
-            "resume signal should be consumed under wait_then_retry"
-        );
+    true
+}
 
-        let telemetry_entries = read_hook_run_telemetry_entries(temp_dir.path());
-        assert_eq!(telemetry_entries.len(), 2);
-        assert_eq!(
-            telemetry_entries
-                .iter()
-                .map(|entry| entry.retry_attempt)
-                .collect::<Vec<_>>(),
-            vec![1, 2]
-        );
-        assert!(
-            telemetry_entries
-                .iter()
-                .all(|entry| entry.retry_max_attempts == 2)
-        );
-        assert!(
-            telemetry_entries
-                .iter()
-                .all(|entry| entry.suspend_mode == HookSuspendMode::WaitThenRetry)
-        );
-        assert_eq!(
-            telemetry_entries
-                .iter()
-                .map(|entry| entry.disposition)
-                .collect::<Vec<_>>(),
-            vec![HookDisposition::Suspend, HookDisposition::Pass]
-        );
+/// Redirects a merge subprocess's stdio to a log file to prevent TUI corruption.
+///
+/// If log file creation fails, falls back to `Stdio::null` rather than
+/// inheriting the parent's terminal (which would corrupt the TUI).
+fn merge_subprocess_stdio(repo_root: &Path, log_name: &str) -> (Stdio, Stdio, Option<PathBuf>) {
+    match create_merge_subprocess_log_file(repo_root, log_name) {
+        Ok((file, path)) => match file.try_clone() {
+            Ok(file_clone) => (Stdio::from(file_clone), Stdio::from(file), Some(path)),
+            Err(e) => {
+                warn!(
+                    log_name = %log_name,
+                    error = %e,
+                    "Failed to clone log file handle, subprocess output will be discarded"
+                );
+                (Stdio::null(), Stdio::null(), None)
+            }
+        },
+        Err(e) => {
+            warn!(
+                log_name = %log_name,
+                error = %e,
+                "Failed to create subprocess log file, output will be discarded"
+            );
+            (Stdio::null(), Stdio::null(), None)
+        }
     }
+}
 
-    #[cfg(unix)]
-    #[test]
-    fn test_dispatch_phase_event_hooks_wait_then_retry_retry_failure_remains_suspended() {
-        let temp_dir = tempfile::tempdir().expect("temp dir");
-        let attempts_path = temp_dir.path().join("wait-then-retry-attempts.txt");
-
-        let mut events = std::collections::HashMap::new();
-        events.insert(
-            HookPhaseEvent::PostIterationStart,
-            vec![hook_spec_with_command_and_on_error_and_suspend_mode(
-                "wait-then-retry-post-iteration-start",
-                vec![
-                    "sh".to_string(),
-                    "-c".to_string(),
-                    r#"attempts_file="$1"
-attempt=0
-if [ -f "$attempts_file" ]; then
-  attempt="$(cat "$attempts_file")"
-fi
-attempt=$((attempt + 1))
-printf '%s' "$attempt" > "$attempts_file"
-exit 72"#
-                        .to_string(),
-                    "wait-then-retry-hook".to_string(),
-                    attempts_path.to_string_lossy().into_owned(),
-                ],
-                HookOnError::Suspend,
-                Some(HookSuspendMode::WaitThenRetry),
-            )],
-        );
-
-        let hook_engine = hook_engine_with_events(events);
-        let hook_executor = HookExecutor::new();
-        let event_loop = dispatch_test_event_loop(temp_dir.path());
-        let loop_ctx = LoopContext::primary(temp_dir.path().to_path_buf());
-        let suspend_state_store = SuspendStateStore::new(temp_dir.path());
-
-        let resume_store = suspend_state_store.clone();
-        let resume_handle = std::thread::spawn(move || {
-            let wait_started_at = std::time::Instant::now();
-            while !resume_store.suspend_state_path().exists() {
-                assert!(
-                    wait_started_at.elapsed() < Duration::from_secs(2),
-                    "wait_then_retry should persist suspend-state before waiting"
+/// Spawns a merge-ralph process for one loop. Returns true when it started.
+fn spawn_merge_ralph_process(
+    repo_root: &Path,
+    ralph_cmd: &OsStr,
+    loop_id: &str,
+    base_branch: &str,
+) -> bool {
+    info!(loop_id = %loop_id, base = %base_branch, "Spawning merge-ralph process");
+
+    let (stdout_stdio, stderr_stdio, log_path) = merge_subprocess_stdio(repo_root, loop_id);
+
+    match Command::new(ralph_cmd)
+        .current_dir(repo_root)
+        .args([
+            "run",
+            "-c",
+            ".ralph/merge-loop-config.yml",
+            "-H",
+            "builtin:merge-loop",
+            "--exclusive",
+            "--no-tui",
+            "-p",
+            &format!(
+                "Merge loop {} from branch ralph/{} into {}",
+                loop_id, loop_id, base_branch
+            ),
+        ])
+        .env("RALPH_MERGE_LOOP_ID", loop_id)
+        .env("RALPH_MERGE_BASE_BRANCH", base_branch)
+        .stdout(stdout_stdio)
+        .stderr(stderr_stdio)
+        .spawn()
+    {
+        Ok(child) => {
+            if let Some(path) = log_path {
+                info!(
+                    loop_id = %loop_id,
+                    pid = child.id(),
+                    log_file = %path.display(),
+                    "merge-ralph spawned successfully"
+                );
+            } else {
+                info!(
+                    loop_id = %loop_id,
+                    pid = child.id(),
+                    "merge-ralph spawned successfully"
                 );
-                std::thread::sleep(Duration::from_millis(10));
             }
+            true
+        }
+        Err(e) => {
+            warn!(
+                loop_id = %loop_id,
+                error = %e,
+                "Failed to spawn merge-ralph, loop will remain queued for manual retry"
+            );
+            false
+        }
+    }
+}
 
-            resume_store
-                .write_resume_requested()
-                .expect("write resume signal");
-        });
-
-        let outcomes = dispatch_phase_event_hooks(
-            &event_loop,
-            true,
-            "loop-test",
-            &hook_engine,
-            &hook_executor,
-            HookPhaseEvent::PostIterationStart,
-            build_iteration_start_payload_input(
-                "loop-test",
-                &loop_ctx,
-                5,
-                1,
-                Some("planner".to_string()),
-                Some("builder".to_string()),
-                Some("task-123".to_string()),
-            ),
-        );
+/// Runs the deterministic merge executor in a background `ralph loops process`.
+///
+/// Verification commands can take minutes, so the completing primary loop
+/// hands the queue to a detached process instead of blocking its exit.
+fn spawn_merge_queue_processor(repo_root: &Path, ralph_cmd: &OsStr) {
+    let queue = MergeQueue::new(repo_root);
+    match queue.next_pending() {
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            debug!("No pending merges in queue");
+            return;
+        }
+        Err(e) => {
+            warn!("Failed to read merge queue: {}", e);
+            return;
+        }
+    }
 
-        resume_handle
-            .join()
-            .expect("resume helper thread should not panic");
+    let (stdout_stdio, stderr_stdio, log_path) = merge_subprocess_stdio(repo_root, "queue");
 
-        assert_eq!(outcomes.len(), 1);
-        assert_eq!(outcomes[0].disposition, HookDisposition::Suspend);
-        assert_eq!(outcomes[0].suspend_mode, HookSuspendMode::WaitThenRetry);
-        assert_eq!(
-            outcomes[0].failure,
-            Some(HookDispatchFailure::HookRunFailed {
-                exit_code: Some(72),
-                timed_out: false,
-            })
-        );
+    match Command::new(ralph_cmd)
+        .current_dir(repo_root)
+        .args(["loops", "process"])
+        .stdout(stdout_stdio)
+        .stderr(stderr_stdio)
+        .spawn()
+    {
+        Ok(child) => {
+            info!(
+                pid = child.id(),
+                log_file = ?log_path,
+                "Merge queue executor spawned"
+            );
+        }
+        Err(e) => {
+            warn!(
+                error = %e,
+                "Failed to spawn merge queue executor, run `ralph loops process` manually"
+            );
+        }
+    }
+}
 
-        let attempts = std::fs::read_to_string(&attempts_path).expect("read attempts");
-        assert_eq!(
-            attempts.trim(),
-            "2",
-            "wait_then_retry should run a single retry attempt after resume"
-        );
-        assert!(
-            suspend_state_store
-                .read_suspend_state()
-                .expect("read suspend-state after wait_then_retry")
-                .is_none(),
-            "first wait_then_retry suspend-state should be cleared after resume"
-        );
-        assert!(
-            !suspend_state_store.resume_requested_path().exists(),
-            "resume signal should be consumed after wait_then_retry"
-        );
+/// Hands conflicting merges to merge-ralph. Returns the loop IDs that were handed off.
+fn spawn_merge_fallbacks(
+    repo_root: &Path,
+    ralph_cmd: &OsStr,
+    reports: &[MergeReport],
+) -> Vec<String> {
+    let conflicts: Vec<&MergeReport> = reports.iter().filter(|r| r.outcome.is_conflict()).collect();
+    if conflicts.is_empty() || !write_merge_loop_config(repo_root) {
+        return Vec::new();
     }
 
-    #[cfg(unix)]
-    #[test]
-    fn test_dispatch_phase_event_hooks_wait_then_retry_prioritizes_stop_over_resume() {
-        let temp_dir = tempfile::tempdir().expect("temp dir");
-        let attempts_path = temp_dir.path().join("wait-then-retry-attempts.txt");
-        std::fs::create_dir_all(temp_dir.path().join(".ralph")).expect("create .ralph");
-        std::fs::write(temp_dir.path().join(".ralph/stop-requested"), "")
-            .expect("write stop signal");
-        std::fs::write(temp_dir.path().join(".ralph/resume-requested"), "")
-            .expect("write resume signal");
-
-        let mut events = std::collections::HashMap::new();
-        events.insert(
-            HookPhaseEvent::PreLoopStart,
-            vec![hook_spec_with_command_and_on_error_and_suspend_mode(
-                "wait-then-retry-pre-loop-start",
-                vec![
-                    "sh".to_string(),
-                    "-c".to_string(),
-                    r#"attempts_file="$1"
-attempt=0
-if [ -f "$attempts_file" ]; then
-  attempt="$(cat "$attempts_file")"
-fi
-attempt=$((attempt + 1))
-printf '%s' "$attempt" > "$attempts_file"
-exit 73"#
-                        .to_string(),
-                    "wait-then-retry-hook".to_string(),
-                    attempts_path.to_string_lossy().into_owned(),
-                ],
-                HookOnError::Suspend,
-                Some(HookSuspendMode::WaitThenRetry),
-            )],
-        );
+    conflicts
+        .into_iter()
+        .filter(|report| {
+            spawn_merge_ralph_process(repo_root, ralph_cmd, &report.loop_id, &report.base_branch)
+        })
+        .map(|report| report.loop_id.clone())
+        .collect()
+}
 
-        let hook_engine = hook_engine_with_events(events);
-        let hook_executor = HookExecutor::new();
-        let event_loop = dispatch_test_event_loop(temp_dir.path());
-        let loop_ctx = LoopContext::primary(temp_dir.path().to_path_buf());
-        let suspend_state_store = SuspendStateStore::new(temp_dir.path());
+/// Creates a timestamped log file for a merge subprocess under `.ralph/diagnostics/logs/`.
+///
+/// Uses the loop_id in the filename for easier identification when debugging.
+/// Participates in the existing log rotation scheme.
+fn create_merge_subprocess_log_file(
+    repo_root: &Path,
+    loop_id: &str,
+) -> std::io::Result<(File, PathBuf)> {
+    use chrono::Local;
 
-        let outcomes = dispatch_phase_event_hooks(
-            &event_loop,
-            true,
-            "loop-test",
-            &hook_engine,
-            &hook_executor,
-            HookPhaseEvent::PreLoopStart,
-            build_loop_start_payload_input("loop-test", &loop_ctx, 5, 1, Some("ralph".to_string())),
-        );
+    let logs_dir = repo_root.join(".ralph").join("diagnostics").join("logs");
+    fs::create_dir_all(&logs_dir)?;
 
-        let attempts = std::fs::read_to_string(&attempts_path).expect("read attempts");
-        assert_eq!(
-            attempts.trim(),
-            "1",
-            "stop signal should prevent wait_then_retry from running the retry"
-        );
+    let _ = ralph_core::diagnostics::rotate_logs(&logs_dir, 10);
 
-        let wait_result = block_on_test_future(wait_for_resume_if_suspended(
-            &outcomes,
-            "loop-test",
-            &suspend_state_store,
-        ))
-        .expect("wait helper should succeed");
+    let timestamp = Local::now().format("%Y-%m-%dT%H-%M-%S");
+    let log_path = logs_dir.join(format!("ralph-merge-{}-{}.log", loop_id, timestamp));
+    let file = File::create(&log_path)?;
+
+    Ok((file, log_path))
+}
 
-        assert_eq!(wait_result, Some(TerminationReason::Stopped));
-        assert!(!temp_dir.path().join(".ralph/stop-requested").exists());
-        assert!(!suspend_state_store.resume_requested_path().exists());
+/// Processes pending merges from the merge queue.
+///
+/// Called when the primary loop completes successfully.
+fn process_pending_merges(repo_root: &Path, mode: MergeExecutorMode) {
+    match mode {
+        MergeExecutorMode::Deterministic => {
+            spawn_merge_queue_processor(repo_root, OsStr::new("ralph"));
+        }
+        MergeExecutorMode::Llm => {
+            process_pending_merges_with_command(repo_root, OsStr::new("ralph"))
+        }
     }
+}
 
-    #[test]
-    fn test_run_retry_backoff_policy_replays_configured_schedule_deterministically() {
-        let mut observed_delays_ms = Vec::new();
-        let mut observed_retry_attempts = Vec::new();
-
-        let outcome = run_retry_backoff_policy(
-            "pre.iteration.start",
-            "retry-hook",
-            &[3, 5, 8],
-            |delay, retry_attempt| {
-                observed_delays_ms.push(delay.as_millis() as u64);
-                assert_eq!(retry_attempt, observed_delays_ms.len());
-                RetryBackoffDelayOutcome::Elapsed
-            },
-            |retry_attempt| {
-                observed_retry_attempts.push(retry_attempt);
-                if retry_attempt == 4 {
-                    HookDispatchOutcome {
-                        phase_event: HookPhaseEvent::PreIterationStart,
-                        hook_name: "retry-hook".to_string(),
-                        disposition: HookDisposition::Pass,
-                        suspend_mode: HookSuspendMode::RetryBackoff,
-                        failure: None,
-
-                        mutation_parse_outcome: HookMutationParseOutcome::Disabled,
+/// Starts or cancels loops scheduled with `ralph run --after`.
+///
+/// Called whenever a loop exits, is merged or is discarded, since any of those
+/// can move a parent into the state its dependents wait for.
+pub fn reconcile_scheduled_loops(repo_root: &Path) -> Vec<ScheduleAction> {
+    let schedule = LoopSchedule::new(repo_root);
+    match schedule.reconcile(|entry| spawn_scheduled_loop("ralph", repo_root, entry)) {
+        Ok(actions) => {
+            for action in &actions {
+                match action {
+                    ScheduleAction::Started {
+                        id,
+                        start_point,
+                        pid,
+                    } => {
+                        info!(loop_id = %id, start_point = %start_point, pid, "Started scheduled loop")
+                    }
+                    ScheduleAction::Cancelled { id, reason } => {
+                        info!(loop_id = %id, reason = %reason, "Cancelled scheduled loop");
                     }
-                } else {
-                    suspend_outcome_with_mode(
-                        HookPhaseEvent::PreIterationStart,
-                        "retry-hook",
-                        HookSuspendMode::RetryBackoff,
-                    )
                 }
-            },
-            suspend_outcome_with_mode(
-                HookPhaseEvent::PreIterationStart,
-                "retry-hook",
-                HookSuspendMode::RetryBackoff,
-            ),
-        );
-
-        assert_eq!(observed_delays_ms, vec![3, 5, 8]);
-        assert_eq!(observed_retry_attempts, vec![2, 3, 4]);
-        assert_eq!(outcome.disposition, HookDisposition::Pass);
-        assert_eq!(outcome.failure, None);
+            }
+            actions
+        }
+        Err(e) => {
+            warn!("Failed to reconcile scheduled loops: {}", e);
+            Vec::new()
+        }
     }
+}
 
-    #[test]
-    fn test_run_retry_backoff_policy_exhausts_after_last_configured_delay() {
-        let mut observed_retry_attempts = Vec::new();
-
-        let outcome = run_retry_backoff_policy(
-            "post.iteration.start",
-            "retry-hook",
-            &[11, 13],
-            |_delay, _retry_attempt| RetryBackoffDelayOutcome::Elapsed,
-            |retry_attempt| {
-                observed_retry_attempts.push(retry_attempt);
-                suspend_outcome_with_mode(
-                    HookPhaseEvent::PostIterationStart,
-                    "retry-hook",
-                    HookSuspendMode::RetryBackoff,
-                )
-            },
-            suspend_outcome_with_mode(
-                HookPhaseEvent::PostIterationStart,
-                "retry-hook",
-                HookSuspendMode::RetryBackoff,
-            ),
-        );
+/// Result of `ralph loops process`: executor reports plus loops handed to merge-ralph.
+pub struct MergeQueueRun {
+    pub reports: Vec<MergeReport>,
+    pub handed_off: Vec<String>,
+}
 
-        assert_eq!(observed_retry_attempts, vec![2, 3]);
-        assert_eq!(outcome.disposition, HookDisposition::Suspend);
-        assert_eq!(
-            outcome.failure,
-            Some(HookDispatchFailure::HookRunFailed {
-                exit_code: Some(41),
-                timed_out: false,
+/// Processes the merge queue in the foreground.
+///
+/// Called by `ralph loops process`. The deterministic executor merges and
+/// verifies each queued loop; conflicts go to merge-ralph when
+/// `merge.llm_fallback` is set. In `llm` mode every entry goes to merge-ralph.
+pub fn process_pending_merges_cli(repo_root: &Path, config: &RalphConfig) -> Result<MergeQueueRun> {
+    let ralph_cmd = OsStr::new("ralph");
+    match config.merge.executor {
+        MergeExecutorMode::Llm => {
+            process_pending_merges_with_command(repo_root, ralph_cmd);
+            Ok(MergeQueueRun {
+                reports: Vec::new(),
+                handed_off: Vec::new(),
             })
-        );
-    }
-
-    #[test]
-    fn test_run_retry_backoff_policy_stop_signal_short_circuits_before_retry_attempt() {
-        let initial_outcome = suspend_outcome_with_mode(
-            HookPhaseEvent::PreLoopStart,
-            "retry-hook",
-            HookSuspendMode::RetryBackoff,
-        );
-        let mut retry_attempt_called = false;
-
-        let outcome = run_retry_backoff_policy(
-            "pre.loop.start",
-            "retry-hook",
-            &[21, 34],
-            |_delay, _retry_attempt| RetryBackoffDelayOutcome::StopRequested,
-            |_retry_attempt| {
-                retry_attempt_called = true;
-                initial_outcome.clone()
-            },
-            initial_outcome.clone(),
-        );
-
-        assert!(!retry_attempt_called);
-        assert_eq!(outcome, initial_outcome);
-    }
-
-    #[test]
-    fn test_run_wait_then_retry_policy_resume_retries_once_and_returns_retry_result() {
-        let mut clear_suspend_calls = 0usize;
-        let mut retry_calls = 0usize;
-
-        let outcome = run_wait_then_retry_policy(
-            "pre.iteration.start",
-            "wait-hook",
-            || Ok(SuspendWaitOutcome::Resume),
-            || {
-                clear_suspend_calls += 1;
-                Ok(())
-            },
-            || {
-                retry_calls += 1;
-                HookDispatchOutcome {
-                    phase_event: HookPhaseEvent::PreIterationStart,
-                    hook_name: "wait-hook".to_string(),
-                    disposition: HookDisposition::Pass,
-                    suspend_mode: HookSuspendMode::WaitThenRetry,
-                    failure: None,
-
-                    mutation_parse_outcome: HookMutationParseOutcome::Disabled,
-                }
-            },
-            suspend_outcome_with_mode(
-                HookPhaseEvent::PreIterationStart,
-                "wait-hook",
-                HookSuspendMode::WaitThenRetry,
-            ),
-        );
-
-        assert_eq!(clear_suspend_calls, 1);
-        assert_eq!(retry_calls, 1);
-        assert_eq!(outcome.disposition, HookDisposition::Pass);
-        assert_eq!(outcome.failure, None);
-    }
-
-    #[test]
-    fn test_run_wait_then_retry_policy_retry_failure_returns_suspend() {
-        let mut clear_suspend_calls = 0usize;
-        let mut retry_calls = 0usize;
-
-        let outcome = run_wait_then_retry_policy(
-            "post.iteration.start",
-            "wait-hook",
-            || Ok(SuspendWaitOutcome::Resume),
-            || {
-                clear_suspend_calls += 1;
-                Ok(())
-            },
-            || {
-                retry_calls += 1;
-                suspend_outcome_with_mode(
-                    HookPhaseEvent::PostIterationStart,
-                    "wait-hook",
-                    HookSuspendMode::WaitThenRetry,
-                )
-            },
-            suspend_outcome_with_mode(
-                HookPhaseEvent::PostIterationStart,
-                "wait-hook",
-                HookSuspendMode::WaitThenRetry,
-            ),
-        );
-
-        assert_eq!(clear_suspend_calls, 1);
-        assert_eq!(retry_calls, 1);
-        assert_eq!(outcome.disposition, HookDisposition::Suspend);
-        assert_eq!(
-            outcome.failure,
-            Some(HookDispatchFailure::HookRunFailed {
-                exit_code: Some(41),
-                timed_out: false,
+        }
+        MergeExecutorMode::Deterministic => {
+            let reports = MergeExecutor::from_config(repo_root, config).process_all()?;
+            let handed_off = if config.merge.llm_fallback {
+                spawn_merge_fallbacks(repo_root, ralph_cmd, &reports)
+            } else {
+                Vec::new()
+            };
+            Ok(MergeQueueRun {
+                reports,
+                handed_off,
             })
-        );
+        }
     }
+}
 
-    #[test]
-    fn test_run_wait_then_retry_policy_stop_skips_retry_path() {
-        let initial_outcome = suspend_outcome_with_mode(
-            HookPhaseEvent::PreLoopStart,
-            "wait-hook",
-            HookSuspendMode::WaitThenRetry,
-        );
-        let mut clear_suspend_called = false;
-        let mut retry_called = false;
-
-        let outcome = run_wait_then_retry_policy(
-            "pre.loop.start",
-            "wait-hook",
-            || Ok(SuspendWaitOutcome::Stop),
-            || {
-                clear_suspend_called = true;
-                Ok(())
-            },
-            || {
-                retry_called = true;
-                HookDispatchOutcome {
-                    phase_event: HookPhaseEvent::PreLoopStart,
-                    hook_name: "wait-hook".to_string(),
-                    disposition: HookDisposition::Pass,
-                    suspend_mode: HookSuspendMode::WaitThenRetry,
-                    failure: None,
-
-                    mutation_parse_outcome: HookMutationParseOutcome::Disabled,
-                }
-            },
-            initial_outcome.clone(),
-        );
+/// Start a loop from an external caller (e.g., the bot daemon).
+///
+/// Loads config from `ralph.yml`, applies the given prompt, acquires the
+/// loop lock, and runs the orchestration loop headlessly. The caller is
+/// responsible for Telegram interaction — the spawned loop has `robot.enabled`
+/// disabled to prevent a second Telegram poller from conflicting.
+///
+/// Returns `Ok(TerminationReason)` on completion or `Err` on fatal errors.
+pub async fn start_loop(
+    prompt: String,
+    workspace_root: PathBuf,
+    config_path: Option<PathBuf>,
+) -> Result<TerminationReason> {
+    use crate::{ColorMode, ConfigSource, load_config_with_overrides};
 
-        assert!(!clear_suspend_called);
-        assert!(!retry_called);
-        assert_eq!(outcome, initial_outcome);
-    }
+    // Load config from file or defaults
+    let config_source = config_path.unwrap_or_else(|| workspace_root.join("ralph.yml"));
+    let sources = vec![ConfigSource::File(config_source)];
+    let mut config = load_config_with_overrides(&sources)?;
 
-    #[test]
-    fn test_fail_if_blocking_loop_start_outcomes_allows_non_blocking_dispositions() {
-        let outcomes = vec![
-            HookDispatchOutcome {
-                phase_event: HookPhaseEvent::PreLoopStart,
-                hook_name: "warn-hook".to_string(),
-                disposition: HookDisposition::Warn,
-                suspend_mode: HookSuspendMode::WaitForResume,
-                failure: Some(HookDispatchFailure::HookRunFailed {
-                    exit_code: Some(7),
-                    timed_out: false,
-                }),
-
-                mutation_parse_outcome: HookMutationParseOutcome::Disabled,
-            },
-            HookDispatchOutcome {
-                phase_event: HookPhaseEvent::PostLoopStart,
-                hook_name: "pass-hook".to_string(),
-                disposition: HookDisposition::Pass,
-                suspend_mode: HookSuspendMode::WaitForResume,
-                failure: None,
-
-                mutation_parse_outcome: HookMutationParseOutcome::Disabled,
-            },
-        ];
+    // Set workspace root to the provided path
+    config.core.workspace_root = workspace_root.clone();
 
-        assert!(fail_if_blocking_loop_start_outcomes(&outcomes).is_ok());
-    }
+    // Apply the prompt
+    config.event_loop.prompt = Some(prompt);
+    config.event_loop.prompt_file = String::new();
 
-    #[test]
-    fn test_fail_if_blocking_loop_start_outcomes_surfaces_failure_context() {
-        let blocked_exit_outcomes = vec![HookDispatchOutcome {
-            phase_event: HookPhaseEvent::PostLoopStart,
-            hook_name: "block-hook".to_string(),
-            disposition: HookDisposition::Block,
-            suspend_mode: HookSuspendMode::WaitForResume,
-            failure: Some(HookDispatchFailure::HookRunFailed {
-                exit_code: Some(42),
-                timed_out: false,
-            }),
-
-            mutation_parse_outcome: HookMutationParseOutcome::Disabled,
-        }];
-
-        let blocked_exit_error = fail_if_blocking_loop_start_outcomes(&blocked_exit_outcomes)
-            .expect_err("block disposition should fail loop.start boundary");
-        let blocked_exit_message = blocked_exit_error.to_string();
-        assert!(blocked_exit_message.contains("block-hook"));
-        assert!(blocked_exit_message.contains("post.loop.start"));
-        assert!(blocked_exit_message.contains("hook exited with code 42"));
-
-        let blocked_exec_outcomes = vec![HookDispatchOutcome {
-            phase_event: HookPhaseEvent::PreLoopStart,
-            hook_name: "block-exec-hook".to_string(),
-            disposition: HookDisposition::Block,
-            suspend_mode: HookSuspendMode::WaitForResume,
-            failure: Some(HookDispatchFailure::HookExecutionError {
-                message: "spawn failed".to_string(),
-            }),
-
-            mutation_parse_outcome: HookMutationParseOutcome::Disabled,
-        }];
-
-        let blocked_exec_error = fail_if_blocking_loop_start_outcomes(&blocked_exec_outcomes)
-            .expect_err("block disposition should fail loop.start boundary");
-        let blocked_exec_message = blocked_exec_error.to_string();
-        assert!(blocked_exec_message.contains("block-exec-hook"));
-        assert!(blocked_exec_message.contains("pre.loop.start"));
-        assert!(blocked_exec_message.contains("hook execution failed: spawn failed"));
-    }
+    // Keep robot.enabled as-is from config. When the daemon starts a loop,
+    // the loop's own TelegramService handles all Telegram interaction
+    // (commands, guidance, responses, check-ins). The daemon stops polling
+    // while the loop runs, so there's no conflict.
 
-    #[test]
-    fn test_fail_if_blocking_iteration_start_outcomes_allows_non_blocking_dispositions() {
-        let outcomes = vec![
-            HookDispatchOutcome {
-                phase_event: HookPhaseEvent::PreIterationStart,
-                hook_name: "warn-hook".to_string(),
-                disposition: HookDisposition::Warn,
-                suspend_mode: HookSuspendMode::WaitForResume,
-                failure: Some(HookDispatchFailure::HookRunFailed {
-                    exit_code: Some(9),
-                    timed_out: false,
-                }),
-
-                mutation_parse_outcome: HookMutationParseOutcome::Disabled,
-            },
-            HookDispatchOutcome {
-                phase_event: HookPhaseEvent::PostIterationStart,
-                hook_name: "pass-hook".to_string(),
-                disposition: HookDisposition::Pass,
-                suspend_mode: HookSuspendMode::WaitForResume,
-                failure: None,
-
-                mutation_parse_outcome: HookMutationParseOutcome::Disabled,
-            },
-        ];
+    // Force autonomous headless mode (no TUI, no interactive)
+    config.cli.default_mode = "autonomous".to_string();
 
-        assert!(fail_if_blocking_iteration_start_outcomes(&outcomes).is_ok());
+    // Normalize and validate
+    config.normalize();
+    let warnings = config
+        .validate()
+        .context("Configuration validation failed")?;
+    for warning in &warnings {
+        tracing::warn!("{}", warning);
     }
 
-    #[test]
-    fn test_fail_if_blocking_iteration_start_outcomes_surfaces_failure_context() {
-        let blocked_timeout_outcomes = vec![HookDispatchOutcome {
-            phase_event: HookPhaseEvent::PreIterationStart,
-            hook_name: "block-timeout-hook".to_string(),
-            disposition: HookDisposition::Block,
-            suspend_mode: HookSuspendMode::WaitForResume,
-            failure: Some(HookDispatchFailure::HookRunFailed {
-                exit_code: None,
-                timed_out: true,
-            }),
-
-            mutation_parse_outcome: HookMutationParseOutcome::Disabled,
-        }];
-
-        let blocked_timeout_error =
-            fail_if_blocking_iteration_start_outcomes(&blocked_timeout_outcomes)
-                .expect_err("block disposition should fail iteration.start boundary");
-        let blocked_timeout_message = blocked_timeout_error.to_string();
-        assert!(blocked_timeout_message.contains("block-timeout-hook"));
-        assert!(blocked_timeout_message.contains("pre.iteration.start"));
-        assert!(blocked_timeout_message.contains("hook timed out"));
-
-        let blocked_exec_outcomes = vec![HookDispatchOutcome {
-            phase_event: HookPhaseEvent::PostIterationStart,
-            hook_name: "block-exec-hook".to_string(),
-            disposition: HookDisposition::Block,
-            suspend_mode: HookSuspendMode::WaitForResume,
-            failure: Some(HookDispatchFailure::HookExecutionError {
-                message: "spawn failed".to_string(),
-            }),
-
-            mutation_parse_outcome: HookMutationParseOutcome::Disabled,
-        }];
-
-        let blocked_exec_error = fail_if_blocking_iteration_start_outcomes(&blocked_exec_outcomes)
-            .expect_err("block disposition should fail iteration.start boundary");
-        let blocked_exec_message = blocked_exec_error.to_string();
-        assert!(blocked_exec_message.contains("block-exec-hook"));
-        assert!(blocked_exec_message.contains("post.iteration.start"));
-        assert!(blocked_exec_message.contains("hook execution failed: spawn failed"));
+    // Auto-detect backend if needed
+    if config.cli.backend == "auto" {
+        let priority = config.get_agent_priority();
+        let detected = ralph_adapters::detect_backend(&priority, |backend| {
+            config.adapter_settings(backend).enabled
+        });
+        match detected {
+            Ok(backend) => {
+                info!("Auto-detected backend: {}", backend);
+                config.cli.backend = backend;
+            }
+            Err(e) => return Err(anyhow::Error::new(e)),
+        }
     }
 
-    #[test]
-    fn test_fail_if_blocking_human_interact_outcomes_allows_non_blocking_dispositions() {
-        let outcomes = vec![
-            HookDispatchOutcome {
-                phase_event: HookPhaseEvent::PreHumanInteract,
-                hook_name: "warn-hook".to_string(),
-                disposition: HookDisposition::Warn,
-                suspend_mode: HookSuspendMode::WaitForResume,
-                failure: Some(HookDispatchFailure::HookRunFailed {
-                    exit_code: Some(9),
-                    timed_out: false,
-                }),
-
-                mutation_parse_outcome: HookMutationParseOutcome::Disabled,
-            },
-            HookDispatchOutcome {
-                phase_event: HookPhaseEvent::PostHumanInteract,
-                hook_name: "pass-hook".to_string(),
-                disposition: HookDisposition::Pass,
-                suspend_mode: HookSuspendMode::WaitForResume,
-                failure: None,
-
-                mutation_parse_outcome: HookMutationParseOutcome::Disabled,
-            },
-        ];
+    // Ensure scratchpad directory exists
+    crate::ensure_scratchpad_directory(&config)?;
 
-        assert!(fail_if_blocking_human_interact_outcomes(&outcomes).is_ok());
-    }
+    // Acquire the loop lock (primary loop)
+    let prompt_summary = config.event_loop.prompt.as_deref().unwrap_or("[daemon]");
+    let prompt_summary = ralph_core::truncate_with_ellipsis(prompt_summary, 100);
 
-    #[test]
-    fn test_fail_if_blocking_human_interact_outcomes_surfaces_failure_context() {
-        let blocked_timeout_outcomes = vec![HookDispatchOutcome {
-            phase_event: HookPhaseEvent::PostHumanInteract,
-            hook_name: "block-timeout-hook".to_string(),
-            disposition: HookDisposition::Block,
-            suspend_mode: HookSuspendMode::WaitForResume,
-            failure: Some(HookDispatchFailure::HookRunFailed {
-                exit_code: None,
-                timed_out: true,
-            }),
-
-            mutation_parse_outcome: HookMutationParseOutcome::Disabled,
-        }];
-
-        let blocked_timeout_error =
-            fail_if_blocking_human_interact_outcomes(&blocked_timeout_outcomes)
-                .expect_err("block disposition should fail human.interact boundary");
-        let blocked_timeout_message = blocked_timeout_error.to_string();
-        assert!(blocked_timeout_message.contains("block-timeout-hook"));
-        assert!(blocked_timeout_message.contains("post.human.interact"));
-        assert!(blocked_timeout_message.contains("hook timed out"));
-
-        let blocked_exec_outcomes = vec![HookDispatchOutcome {
-            phase_event: HookPhaseEvent::PreHumanInteract,
-            hook_name: "block-exec-hook".to_string(),
-            disposition: HookDisposition::Block,
-            suspend_mode: HookSuspendMode::WaitForResume,
-            failure: Some(HookDispatchFailure::HookExecutionError {
-                message: "spawn failed".to_string(),
-            }),
-
-            mutation_parse_outcome: HookMutationParseOutcome::Disabled,
-        }];
-
-        let blocked_exec_error = fail_if_blocking_human_interact_outcomes(&blocked_exec_outcomes)
-            .expect_err("block disposition should fail human.interact boundary");
-        let blocked_exec_message = blocked_exec_error.to_string();
-        assert!(blocked_exec_message.contains("block-exec-hook"));
-        assert!(blocked_exec_message.contains("pre.human.interact"));
-        assert!(blocked_exec_message.contains("hook execution failed: spawn failed"));
-    }
+    let _lock_guard = ralph_core::LoopLock::try_acquire(&workspace_root, &prompt_summary)
+        .context("Failed to acquire loop lock — another loop may be running")?;
 
-    #[test]
-    fn test_loop_termination_phase_events_maps_success_and_error_reasons() {
-        assert_eq!(
-            loop_termination_phase_events(&TerminationReason::CompletionPromise),
-            (
-                HookPhaseEvent::PreLoopComplete,
-                HookPhaseEvent::PostLoopComplete
-            )
-        );
-        assert_eq!(
-            loop_termination_phase_events(&TerminationReason::MaxRuntime),
-            (HookPhaseEvent::PreLoopError, HookPhaseEvent::PostLoopError)
-        );
-    }
+    let loop_context = ralph_core::LoopContext::primary(workspace_root);
+
+    // Run the loop headlessly (boxed: the loop future is large)
+    Box::pin(run_loop_impl(
+        config,
+        ColorMode::Never,
+        false, // not resume
+        false, // no TUI
+        false, // no RPC
+        Verbosity::Normal,
+        None,               // no session recording
+        Some(loop_context), // loop context
+        Vec::new(),         // no custom args
+        None,               // default auto-merge
+        None,               // no explicit loop ID
+    ))
+    .await
+}
 
-    #[test]
-    fn test_build_loop_termination_payload_input_sets_termination_reason_context() {
-        let temp_dir = tempfile::tempdir().expect("temp dir");
-        let loop_ctx = LoopContext::primary(temp_dir.path().to_path_buf());
-
-        let payload_input = build_loop_termination_payload_input(
-            "loop-test",
-            &loop_ctx,
-            42,
-            7,
-            Some("planner".to_string()),
-            Some("builder".to_string()),
-            Some("task-123".to_string()),
-            &TerminationReason::RestartRequested,
-        );
+/// Creates a robot service (webhook or Telegram) for human-in-the-loop communication.
+///
+/// Called by `run_loop_impl` when `robot.enabled` is true and this is the primary loop.
+/// `RObot.webhook` takes precedence over Telegram when configured.
+/// Returns `None` if the service cannot be created or started.
+fn create_robot_service(
+    config: &RalphConfig,
+    context: &LoopContext,
+) -> Option<Box<dyn ralph_proto::RobotService>> {
+    let workspace_root = context.workspace().to_path_buf();
+    let timeout_secs = config.robot.timeout_seconds.unwrap_or(300);
+    let loop_id = context
+        .loop_id()
+        .map(String::from)
+        .unwrap_or_else(|| "main".to_string());
 
-        assert_eq!(
-            payload_input.context.termination_reason.as_deref(),
-            Some("restart_requested")
-        );
-        assert_eq!(payload_input.context.active_hat.as_deref(), Some("planner"));
-        assert_eq!(
-            payload_input.context.selected_hat.as_deref(),
-            Some("builder")
-        );
-        assert_eq!(
-            payload_input.context.selected_task.as_deref(),
-            Some("task-123")
+    if let Some(webhook) = &config.robot.webhook {
+        return create_webhook_robot_service(
+            webhook,
+            config.robot.resolve_webhook_secret(),
+            &workspace_root,
+            timeout_secs,
+            loop_id,
         );
     }
 
-    fn hook_mutation_config(enabled: bool) -> HookMutationConfig {
-        HookMutationConfig {
-            enabled,
-            format: Some("json".to_string()),
-            extra: std::collections::HashMap::new(),
+    let bot_token = config.robot.resolve_bot_token();
+    let api_url = config.robot.resolve_api_url();
+
+    match ralph_telegram::TelegramService::new(
+        workspace_root,
+        bot_token,
+        api_url,
+        timeout_secs,
+        loop_id,
+    ) {
+        Ok(service) => {
+            if let Err(e) = service.start() {
+                warn!(error = %e, "Failed to start robot service");
+                return None;
+            }
+            info!(
+                bot_token = %service.bot_token_masked(),
+                timeout_secs = service.timeout_secs(),
+                "Robot human-in-the-loop service active"
+            );
+            Some(Box::new(service))
+        }
+        Err(e) => {
+            warn!(error = %e, "Failed to create robot service");
+            None
         }
     }
+}
 
-    fn json_object(value: serde_json::Value) -> serde_json::Map<String, serde_json::Value> {
-        value.as_object().cloned().expect("json object")
-    }
+fn create_webhook_robot_service(
+    webhook: &ralph_core::WebhookRobotConfig,
+    secret: Option<String>,
+    workspace_root: &Path,
+    timeout_secs: u64,
+    loop_id: String,
+) -> Option<Box<dyn ralph_proto::RobotService>> {
+    let Some(secret) = secret else {
+        warn!("Webhook robot service needs RObot.webhook.secret or RALPH_WEBHOOK_SECRET");
+        return None;
+    };
 
-    #[test]
-    fn test_parse_hook_mutation_stdout_skips_when_disabled() {
-        let outcome =
-            parse_hook_mutation_stdout(&HookMutationConfig::default(), "env-guard", "not-json");
+    let service = match ralph_webhook::WebhookService::new(
+        workspace_root,
+        webhook.urls.clone(),
+        secret,
+        timeout_secs,
+        loop_id,
+    ) {
+        Ok(service) => service
+            .with_max_attempts(webhook.max_attempts)
+            .with_response_url(webhook.response_url.clone()),
+        Err(e) => {
+            warn!(error = %e, "Failed to create webhook robot service");
+            return None;
+        }
+    };
 
-        assert_eq!(outcome, HookMutationParseOutcome::Disabled);
+    if let Err(e) = service.start() {
+        warn!(error = %e, "Failed to flush webhook spool; continuing");
     }
+    info!(
+        urls = webhook.urls.len(),
+        timeout_secs = service.timeout_secs(),
+        "Robot human-in-the-loop service active (webhook)"
+    );
+    Some(Box::new(service))
+}
 
-    #[test]
-    fn test_parse_hook_mutation_stdout_accepts_metadata_only_payload_and_namespaces_by_hook() {
-        let outcome = parse_hook_mutation_stdout(
-            &hook_mutation_config(true),
-            "env-guard",
-            r#"{"metadata":{"risk_score":0.72,"gates":["policy_check"]}}"#,
-        );
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::CwdGuard;
+    use ralph_core::HatRegistry;
+    use ralph_core::planning_session::{ConversationEntry, ConversationType};
+    use ralph_proto::{Hat, Topic};
+    use std::ffi::OsStr;
+    use std::sync::Arc;
+    use std::sync::Mutex;
 
-        let HookMutationParseOutcome::Parsed {
-            namespaced_metadata,
-        } = outcome
-        else {
-            panic!("expected parsed mutation payload");
-        };
+    #[test]
+    fn test_resolve_loop_id_fresh_generates_new() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let ctx = ralph_core::LoopContext::primary(temp.path().to_path_buf());
+        ctx.ensure_ralph_dir().unwrap();
 
-        assert_eq!(
-            serde_json::Value::Object(namespaced_metadata),
-            serde_json::json!({
-                "hook_metadata": {
-                    "env-guard": {
-                        "risk_score": 0.72,
-                        "gates": ["policy_check"]
-                    }
-                }
-            })
+        let id = resolve_loop_id(&ctx, false, None);
+        assert!(
+            id.starts_with("primary-"),
+            "Fresh run should generate primary-{{timestamp}}, got: {}",
+            id
         );
     }
 
     #[test]
-    fn test_parse_hook_mutation_stdout_rejects_non_json_payload_when_enabled() {
-        let outcome = parse_hook_mutation_stdout(&hook_mutation_config(true), "env-guard", "oops");
-
-        let HookMutationParseOutcome::Invalid(HookMutationParseError::InvalidJson { message }) =
-            outcome
-        else {
-            panic!("expected invalid-json mutation parse outcome");
-        };
+    fn test_resolve_loop_id_continue_reuses_marker() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let ctx = ralph_core::LoopContext::primary(temp.path().to_path_buf());
+        ctx.ensure_ralph_dir().unwrap();
 
-        assert!(message.contains("valid JSON"));
-    }
+        // Write a marker from a "previous run"
+        std::fs::write(
+            ctx.ralph_dir().join("current-loop-id"),
+            "primary-20260303-100000",
+        )
+        .unwrap();
 
-    #[test]
-    fn test_parse_hook_mutation_stdout_rejects_non_metadata_payload_shape() {
-        let outcome = parse_hook_mutation_stdout(
-            &hook_mutation_config(true),
-            "env-guard",
-            r#"{"metadata":{"risk_score":0.72},"prompt":"inject"}"#,
+        let id = resolve_loop_id(&ctx, true, None);
+        assert_eq!(
+            id, "primary-20260303-100000",
+            "--continue should reuse existing loop ID"
         );
-
-        let HookMutationParseOutcome::Invalid(HookMutationParseError::InvalidSchema { message }) =
-            outcome
-        else {
-            panic!("expected invalid-schema mutation parse outcome");
-        };
-
-        assert!(message.contains("supports only"));
     }
 
     #[test]
-    fn test_merge_hook_metadata_namespace_merges_multiple_hook_entries() {
-        let mut accumulated_metadata = serde_json::Map::new();
-        accumulated_metadata.insert("upstream".to_string(), serde_json::json!("preserved"));
-
-        merge_hook_metadata_namespace(
-            &mut accumulated_metadata,
-            "env-guard",
-            json_object(serde_json::json!({"risk_score": 0.72})),
-        )
-        .expect("merge env-guard metadata");
+    fn test_resolve_loop_id_continue_explicit_overrides_marker() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let ctx = ralph_core::LoopContext::primary(temp.path().to_path_buf());
+        ctx.ensure_ralph_dir().unwrap();
 
-        merge_hook_metadata_namespace(
-            &mut accumulated_metadata,
-            "policy-gate",
-            json_object(serde_json::json!({"status": "pass"})),
+        std::fs::write(
+            ctx.ralph_dir().join("current-loop-id"),
+            "primary-20260303-100000",
         )
-        .expect("merge policy-gate metadata");
+        .unwrap();
 
+        let id = resolve_loop_id(&ctx, true, Some("custom-loop-42"));
         assert_eq!(
-            accumulated_metadata["upstream"],
-            serde_json::json!("preserved")
-        );
-        assert_eq!(
-            accumulated_metadata["hook_metadata"]["env-guard"]["risk_score"],
-            serde_json::json!(0.72)
-        );
-        assert_eq!(
-            accumulated_metadata["hook_metadata"]["policy-gate"]["status"],
-            serde_json::json!("pass")
+            id, "custom-loop-42",
+            "--loop-id should override the marker file"
         );
     }
 
     #[test]
-    fn test_merge_hook_metadata_namespace_rejects_non_object_namespace_value() {
-        let mut accumulated_metadata = serde_json::Map::new();
-        accumulated_metadata.insert(
-            "hook_metadata".to_string(),
-            serde_json::Value::String("invalid".to_string()),
-        );
+    fn test_resolve_loop_id_continue_no_marker_generates_new() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let ctx = ralph_core::LoopContext::primary(temp.path().to_path_buf());
+        ctx.ensure_ralph_dir().unwrap();
 
-        let merge_result = merge_hook_metadata_namespace(
-            &mut accumulated_metadata,
-            "env-guard",
-            json_object(serde_json::json!({"risk_score": 0.72})),
+        // No marker file exists
+        let id = resolve_loop_id(&ctx, true, None);
+        assert!(
+            id.starts_with("primary-"),
+            "--continue without marker should fall back to generating new ID, got: {}",
+            id
         );
-
-        assert!(matches!(
-            merge_result,
-            Err(HookMutationParseError::InvalidSchema { message })
-            if message.contains("must be a JSON object")
-        ));
     }
 
     #[test]
-    fn test_fail_if_blocking_loop_termination_outcomes_allows_non_blocking_dispositions() {
-        let outcomes = vec![
-            HookDispatchOutcome {
-                phase_event: HookPhaseEvent::PreLoopComplete,
-                hook_name: "warn-hook".to_string(),
-                disposition: HookDisposition::Warn,
-                suspend_mode: HookSuspendMode::WaitForResume,
-                failure: Some(HookDispatchFailure::HookRunFailed {
-                    exit_code: Some(9),
-                    timed_out: false,
-                }),
-
-                mutation_parse_outcome: HookMutationParseOutcome::Disabled,
-            },
-            HookDispatchOutcome {
-                phase_event: HookPhaseEvent::PostLoopError,
-                hook_name: "pass-hook".to_string(),
-                disposition: HookDisposition::Pass,
-                suspend_mode: HookSuspendMode::WaitForResume,
-                failure: None,
-
-                mutation_parse_outcome: HookMutationParseOutcome::Disabled,
-            },
-        ];
+    fn test_pty_only_enabled_for_tui_rpc_or_interactive() {
+        let should_use_pty = |enable_tui: bool, enable_rpc: bool, user_interactive: bool| -> bool {
+            enable_tui || enable_rpc || user_interactive
+        };
 
-        assert!(fail_if_blocking_loop_termination_outcomes(&outcomes).is_ok());
+        assert!(!should_use_pty(false, false, false));
+        assert!(should_use_pty(true, false, false));
+        assert!(should_use_pty(false, true, false));
+        assert!(should_use_pty(false, false, true));
     }
 
     #[test]
-    fn test_fail_if_blocking_loop_termination_outcomes_surfaces_failure_context() {
-        let blocked_timeout_outcomes = vec![HookDispatchOutcome {
-            phase_event: HookPhaseEvent::PostLoopError,
-            hook_name: "block-timeout-hook".to_string(),
-            disposition: HookDisposition::Block,
-            suspend_mode: HookSuspendMode::WaitForResume,
-            failure: Some(HookDispatchFailure::HookRunFailed {
-                exit_code: None,
-                timed_out: true,
-            }),
-
-            mutation_parse_outcome: HookMutationParseOutcome::Disabled,
-        }];
-
-        let blocked_timeout_error =
-            fail_if_blocking_loop_termination_outcomes(&blocked_timeout_outcomes)
-                .expect_err("block disposition should fail loop termination boundary");
-        let blocked_timeout_message = blocked_timeout_error.to_string();
-        assert!(blocked_timeout_message.contains("block-timeout-hook"));
-        assert!(blocked_timeout_message.contains("post.loop.error"));
-        assert!(blocked_timeout_message.contains("hook timed out"));
-
-        let blocked_exec_outcomes = vec![HookDispatchOutcome {
-            phase_event: HookPhaseEvent::PreLoopComplete,
-            hook_name: "block-exec-hook".to_string(),
-            disposition: HookDisposition::Block,
-            suspend_mode: HookSuspendMode::WaitForResume,
-            failure: Some(HookDispatchFailure::HookExecutionError {
-                message: "spawn failed".to_string(),
-            }),
-
-            mutation_parse_outcome: HookMutationParseOutcome::Disabled,
-        }];
-
-        let blocked_exec_error = fail_if_blocking_loop_termination_outcomes(&blocked_exec_outcomes)
-            .expect_err("block disposition should fail loop termination boundary");
-        let blocked_exec_message = blocked_exec_error.to_string();
-        assert!(blocked_exec_message.contains("block-exec-hook"));
-        assert!(blocked_exec_message.contains("pre.loop.complete"));
-        assert!(blocked_exec_message.contains("hook execution failed: spawn failed"));
-    }
+    fn test_user_interactive_mode_determination() {
+        // user_interactive is determined by default_mode setting, not PTY.
+        // PTY handles output streaming; user_interactive handles input forwarding.
 
-    #[test]
-    fn test_wait_for_resume_if_suspended_is_noop_without_suspend_dispositions() {
-        let temp_dir = tempfile::tempdir().expect("temp dir");
-        let suspend_state_store = SuspendStateStore::new(temp_dir.path());
-
-        let outcomes = vec![HookDispatchOutcome {
-            phase_event: HookPhaseEvent::PreLoopStart,
-            hook_name: "warn-hook".to_string(),
-            disposition: HookDisposition::Warn,
-            suspend_mode: HookSuspendMode::WaitForResume,
-            failure: Some(HookDispatchFailure::HookRunFailed {
-                exit_code: Some(7),
-                timed_out: false,
-            }),
-
-            mutation_parse_outcome: HookMutationParseOutcome::Disabled,
-        }];
-
-        let wait_result = block_on_test_future(wait_for_resume_if_suspended(
-            &outcomes,
-            "loop-test",
-            &suspend_state_store,
-        ))
-        .expect("wait helper should succeed");
+        // Autonomous mode: no user input forwarding
+        let autonomous_interactive = false;
+        assert!(
+            !autonomous_interactive,
+            "Autonomous mode should not forward user input"
+        );
 
-        assert_eq!(wait_result, None);
-        assert!(!suspend_state_store.suspend_state_path().exists());
-        assert!(!suspend_state_store.resume_requested_path().exists());
+        // Interactive mode with TTY: forward user input
+        let interactive_with_tty = true;
+        assert!(
+            interactive_with_tty,
+            "Interactive mode with TTY should forward user input"
+        );
     }
 
     #[test]
-    fn test_wait_for_resume_if_suspended_resumes_and_clears_suspend_artifacts() {
-        let temp_dir = tempfile::tempdir().expect("temp dir");
-        let suspend_state_store = SuspendStateStore::new(temp_dir.path());
-        suspend_state_store
-            .write_resume_requested()
-            .expect("write resume signal");
-
-        let outcomes = vec![suspend_outcome(
-            HookPhaseEvent::PreLoopStart,
-            "suspend-hook",
-        )];
-
-        let wait_result = block_on_test_future(wait_for_resume_if_suspended(
-            &outcomes,
-            "loop-test",
-            &suspend_state_store,
-        ))
-        .expect("wait helper should succeed");
+    fn test_prepare_tui_iteration_seeds_max_iterations() {
+        let state = Arc::new(Mutex::new(ralph_tui::TuiState::new()));
 
-        assert_eq!(wait_result, None);
-        assert!(!suspend_state_store.suspend_state_path().exists());
-        assert!(!suspend_state_store.resume_requested_path().exists());
-    }
+        let lines = prepare_tui_iteration(&state, "Planner".to_string(), "claude".to_string(), 42);
 
-    #[test]
-    fn test_wait_for_resume_if_suspended_prioritizes_stop_over_resume() {
-        let temp_dir = tempfile::tempdir().expect("temp dir");
-        let suspend_state_store = SuspendStateStore::new(temp_dir.path());
-
-        std::fs::create_dir_all(temp_dir.path().join(".ralph")).expect("create .ralph");
-        std::fs::write(temp_dir.path().join(".ralph/stop-requested"), "")
-            .expect("write stop signal");
-        suspend_state_store
-            .write_resume_requested()
-            .expect("write resume signal");
-
-        let outcomes = vec![suspend_outcome(
-            HookPhaseEvent::PreIterationStart,
-            "suspend-hook",
-        )];
-
-        let wait_result = block_on_test_future(wait_for_resume_if_suspended(
-            &outcomes,
-            "loop-test",
-            &suspend_state_store,
-        ))
-        .expect("wait helper should succeed");
+        assert!(lines.is_some(), "should return a lines handle");
+        let state = state.lock().expect("state lock");
+        assert_eq!(state.max_iterations, Some(42));
+        assert_eq!(state.total_iterations(), 1);
+    }
 
-        assert_eq!(wait_result, Some(TerminationReason::Stopped));
-        assert!(!temp_dir.path().join(".ralph/stop-requested").exists());
-        assert!(!suspend_state_store.suspend_state_path().exists());
-        assert!(!suspend_state_store.resume_requested_path().exists());
+    #[cfg(unix)]
+    fn write_fake_executable(dir: &Path, name: &str, body: &str) -> std::path::PathBuf {
+        use std::os::unix::fs::PermissionsExt;
+        let path = dir.join(name);
+        let script = format!("#!/bin/sh\n{}\n", body);
+        std::fs::write(&path, script).expect("write script");
+        let mut perms = std::fs::metadata(&path).expect("metadata").permissions();
+        perms.set_mode(0o755);
+        std::fs::set_permissions(&path, perms).expect("chmod");
+        path
     }
 
-    #[test]
-    fn test_wait_for_resume_if_suspended_prioritizes_restart_over_resume() {
-        let temp_dir = tempfile::tempdir().expect("temp dir");
-        let suspend_state_store = SuspendStateStore::new(temp_dir.path());
-
-        std::fs::create_dir_all(temp_dir.path().join(".ralph")).expect("create .ralph");
-        std::fs::write(temp_dir.path().join(".ralph/restart-requested"), "")
-            .expect("write restart signal");
-        suspend_state_store
-            .write_resume_requested()
-            .expect("write resume signal");
-
-        let outcomes = vec![suspend_outcome(
-            HookPhaseEvent::PostIterationStart,
-            "suspend-hook",
-        )];
-
-        let wait_result = block_on_test_future(wait_for_resume_if_suspended(
-            &outcomes,
-            "loop-test",
-            &suspend_state_store,
-        ))
-        .expect("wait helper should succeed");
+    #[cfg(unix)]
+    fn dispatch_test_event_loop_with_context(workspace_root: &Path) -> (EventLoop, LoopContext) {
+        let mut config = RalphConfig::default();
+        config.core.workspace_root = workspace_root.to_path_buf();
+        let context = LoopContext::primary(workspace_root.to_path_buf());
+        let event_loop = EventLoop::with_context(config, context.clone());
+        (event_loop, context)
+    }
 
-        assert_eq!(wait_result, Some(TerminationReason::RestartRequested));
-        assert!(temp_dir.path().join(".ralph/restart-requested").exists());
-        assert!(!suspend_state_store.suspend_state_path().exists());
-        assert!(!suspend_state_store.resume_requested_path().exists());
+    fn dispatch_test_event_loop_from_yaml_with_context(
+        workspace_root: &Path,
+        yaml: &str,
+    ) -> (EventLoop, LoopContext) {
+        let mut config: RalphConfig = serde_yaml::from_str(yaml).expect("parse config");
+        config.core.workspace_root = workspace_root.to_path_buf();
+        let context = LoopContext::primary(workspace_root.to_path_buf());
+        let event_loop = EventLoop::with_context(config, context.clone());
+        (event_loop, context)
     }
 
     #[test]
@@ -8391,7 +4089,7 @@ hats:
         let repo_root = temp_dir.path();
         std::fs::create_dir_all(repo_root.join(".ralph/merge-queue")).expect("queue dir");
 
-        process_pending_merges(repo_root);
+        process_pending_merges(repo_root, MergeExecutorMode::default());
     }
 
     #[cfg(unix)]
@@ -8415,6 +4113,36 @@ hats:
         process_pending_merges_with_command(repo_root, ralph_path.as_os_str());
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn test_deterministic_mode_spawns_loops_process_with_log() {
+        let temp_dir = tempfile::tempdir().expect("temp dir");
+        let repo_root = temp_dir.path();
+        let queue = ralph_core::merge_queue::MergeQueue::new(repo_root);
+        queue.enqueue("loop-4242", "merge prompt").expect("enqueue");
+
+        let bin_dir = repo_root.join("bin");
+        std::fs::create_dir_all(&bin_dir).expect("bin dir");
+        let ralph_path = write_fake_executable(&bin_dir, "ralph", "echo \"args: $*\"");
+
+        spawn_merge_queue_processor(repo_root, ralph_path.as_os_str());
+        std::thread::sleep(std::time::Duration::from_millis(500));
+
+        // The executor subprocess owns the queue; merge-ralph is not involved.
+        assert!(!repo_root.join(".ralph/merge-loop-config.yml").exists());
+        let log = std::fs::read_dir(repo_root.join(".ralph/diagnostics/logs"))
+            .expect("logs dir")
+            .filter_map(|e| e.ok())
+            .find(|e| {
+                e.file_name()
+                    .to_string_lossy()
+                    .starts_with("ralph-merge-queue-")
+            })
+            .expect("queue processor log");
+        let content = std::fs::read_to_string(log.path()).expect("read log");
+        assert!(content.contains("args: loops process"), "got: {content}");
+    }
+
     #[test]
     fn test_process_pending_merges_missing_command_keeps_queue() {
         let temp_dir = tempfile::tempdir().expect("temp dir");
@@ -9016,6 +4744,63 @@ hats:
         assert_eq!(display_hat.as_str(), "fixer");
     }
 
+    #[test]
+    fn test_extract_cli_stream_usage_sums_claude_usage() {
+        let output = concat!(
+            r#"{"type":"assistant","message":{"content":[{"type":"text","text":"Reviewing"}]},"usage":{"input_tokens":1000,"output_tokens":200}}"#,
+            "\n",
+            r#"{"type":"result","duration_ms":1,"total_cost_usd":0.42,"num_turns":1,"is_error":false}"#,
+            "\n"
+        );
+        assert_eq!(
+            extract_cli_stream_usage(BackendOutputFormat::StreamJson, output),
+            (0.42, 1200)
+        );
+        assert_eq!(
+            extract_cli_stream_usage(BackendOutputFormat::Text, output),
+            (0.0, 0)
+        );
+    }
+
+    #[test]
+    fn test_extract_cli_stream_usage_sums_pi_turns() {
+        let output = concat!(
+            r#"{"type":"turn_end","message":{"stopReason":"toolUse","usage":{"input":100,"output":20,"cacheRead":0,"cacheWrite":0,"cost":{"total":0.01}}}}"#,
+            "\n",
+            r#"{"type":"turn_end","message":{"stopReason":"stop","usage":{"input":300,"output":80,"cacheRead":0,"cacheWrite":0,"cost":{"total":0.03}}}}"#,
+            "\n"
+        );
+        let (cost_usd, tokens) =
+            extract_cli_stream_usage(BackendOutputFormat::PiStreamJson, output);
+        assert!((cost_usd - 0.04).abs() < 1e-10);
+        assert_eq!(tokens, 500);
+    }
+
+    #[test]
+    fn test_extract_cli_stream_usage_reads_partial_claude_transcript() {
+        // A branch stopped by its timeout never emits the final result event.
+        let output = concat!(
+            r#"{"type":"assistant","message":{"content":[{"type":"text","text":"Reviewing"}]},"usage":{"input_tokens":700,"output_tokens":50}}"#,
+            "\n",
+            r#"{"type":"assistant","message":{"content":[{"type":"text","text":"Still"#
+        );
+        assert_eq!(
+            extract_cli_stream_usage(BackendOutputFormat::StreamJson, output),
+            (0.0, 750)
+        );
+    }
+
+    #[test]
+    fn test_build_subloop_objective_lists_delegated_events() {
+        let events = vec![Event::new("migrate.start", "Move the store to SQLite")];
+        let objective = build_subloop_objective("  Migrate the storage layer.\n", &events);
+        assert_eq!(
+            objective,
+            "Migrate the storage layer.\n\n## Delegated Events\n\n- `migrate.start`: Move the store to SQLite"
+        );
+        assert_eq!(build_subloop_objective("Do it", &[]), "Do it");
+    }
+
     #[test]
     fn test_output_mentions_ralph_emit_detects_tool_call_output() {
         assert!(output_mentions_ralph_emit(
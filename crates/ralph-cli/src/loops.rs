@@ -3,7 +3,7 @@
 //! Manage parallel Ralph loops running in git worktrees.
 //!
 //! Subcommands:
-//! - `list`: Show all loops (active, merging, merged, needs-review)
+//! - `list`: Show all loops (active, scheduled, merging, merged, needs-review)
 //! - `logs`: View loop output
 //! - `history`: Show event history
 //! - `retry`: Re-run merge for failed loop
@@ -14,6 +14,7 @@
 //! - `attach`: Open shell in worktree
 //! - `diff`: Show changes from merge-base
 
+use std::io::Write;
 use std::path::{Path, PathBuf};
 use std::process::Command;
 use std::time::{Duration, Instant};
@@ -21,10 +22,12 @@ use std::time::{Duration, Instant};
 use anyhow::{Context, Result, bail};
 use clap::{Parser, Subcommand};
 
+use crate::{ConfigSource, load_config_with_overrides};
 use ralph_core::worktree::{list_ralph_worktrees, remove_worktree};
 use ralph_core::{
-    LoopRegistry, MergeButtonState, MergeQueue, MergeState, SuspendStateStore, merge_button_state,
-    truncate_with_ellipsis,
+    LoopHistory, LoopRegistry, LoopSchedule, MergeButtonState, MergeExecutor, MergeExecutorMode,
+    MergeQueue, MergeReport, MergeState, RegistryError, ScheduleAction, ScheduleStatus, SlotState,
+    SuspendStateStore, loop_base_branch, merge_button_state, truncate_with_ellipsis,
 };
 
 /// Manage parallel loops.
@@ -164,6 +167,10 @@ pub struct MergeArgs {
     /// Force merge even if state is 'merging'
     #[arg(long)]
     pub force: bool,
+
+    /// Merge into this branch instead of the loop's recorded base branch
+    #[arg(long, value_name = "BRANCH")]
+    pub onto: Option<String>,
 }
 
 #[derive(Parser, Debug)]
@@ -173,7 +180,7 @@ pub struct MergeButtonStateArgs {
 }
 
 /// Execute a loops command.
-pub fn execute(args: LoopsArgs, use_colors: bool) -> Result<()> {
+pub fn execute(args: LoopsArgs, config_sources: &[ConfigSource], use_colors: bool) -> Result<()> {
     match args.command {
         None => list_loops(
             ListArgs {
@@ -185,29 +192,73 @@ pub fn execute(args: LoopsArgs, use_colors: bool) -> Result<()> {
         Some(LoopsCommands::List(args)) => list_loops(args, use_colors),
         Some(LoopsCommands::Logs(logs_args)) => show_logs(logs_args),
         Some(LoopsCommands::History(history_args)) => show_history(history_args),
-        Some(LoopsCommands::Retry(retry_args)) => retry_merge(retry_args),
+        Some(LoopsCommands::Retry(retry_args)) => retry_merge(retry_args, config_sources),
         Some(LoopsCommands::Discard(discard_args)) => discard_loop(discard_args),
         Some(LoopsCommands::Stop(stop_args)) => stop_loop(stop_args),
         Some(LoopsCommands::Resume(resume_args)) => resume_loop(resume_args),
         Some(LoopsCommands::Prune) => prune_stale(),
         Some(LoopsCommands::Attach(attach_args)) => attach_to_loop(attach_args),
         Some(LoopsCommands::Diff(diff_args)) => show_diff(diff_args),
-        Some(LoopsCommands::Merge(merge_args)) => merge_loop(merge_args),
-        Some(LoopsCommands::Process) => process_queue(),
+        Some(LoopsCommands::Merge(merge_args)) => merge_loop(merge_args, config_sources),
+        Some(LoopsCommands::Process) => process_queue(config_sources),
         Some(LoopsCommands::MergeButtonState(args)) => get_merge_button_state(args),
     }
 }
 
 /// Process pending merge queue entries.
-fn process_queue() -> Result<()> {
+fn process_queue(config_sources: &[ConfigSource]) -> Result<()> {
     let cwd = std::env::current_dir()?;
+    let config = load_config_with_overrides(config_sources)?;
+
+    let run = crate::loop_runner::process_pending_merges_cli(&cwd, &config)?;
+
+    for report in &run.reports {
+        print_merge_report(report, run.handed_off.contains(&report.loop_id));
+    }
 
-    // Delegate to the loop_runner's process_pending_merges function
-    crate::loop_runner::process_pending_merges_cli(&cwd);
+    print_schedule_actions(&crate::loop_runner::reconcile_scheduled_loops(&cwd));
 
     Ok(())
 }
 
+/// Prints the outcome of one executor merge and what to do next.
+fn print_merge_report(report: &MergeReport, handed_off: bool) {
+    println!(
+        "{} → {}: {}",
+        report.loop_id,
+        report.base_branch,
+        report.outcome.summary()
+    );
+    if handed_off {
+        println!(
+            "  Conflicts handed to merge-ralph (ralph loops logs {})",
+            report.loop_id
+        );
+    } else if !report.outcome.is_merged() {
+        println!(
+            "  Needs review: ralph loops diff {} / ralph loops retry {}",
+            report.loop_id, report.loop_id
+        );
+    }
+    if let Some(reason) = &report.halted {
+        println!("  Queue halted: {reason}");
+    }
+}
+
+/// Prints the scheduled loops started or cancelled by a reconcile pass.
+fn print_schedule_actions(actions: &[ScheduleAction]) {
+    for action in actions {
+        match action {
+            ScheduleAction::Started {
+                id, start_point, ..
+            } => println!("Started scheduled loop {id} from {start_point}"),
+            ScheduleAction::Cancelled { id, reason } => {
+                println!("Cancelled scheduled loop {id}: {reason}");
+            }
+        }
+    }
+}
+
 /// Get merge button state for a loop (JSON output for web API).
 fn get_merge_button_state(args: MergeButtonStateArgs) -> Result<()> {
     let cwd = std::env::current_dir()?;
@@ -274,6 +325,9 @@ fn list_loops(args: ListArgs, use_colors: bool) -> Result<()> {
     // Get merge queue entries
     let merge_entries = merge_queue.list().unwrap_or_default();
 
+    // Get loops waiting on other loops
+    let scheduled = LoopSchedule::new(&cwd).list().unwrap_or_default();
+
     // Build combined view
     let mut rows: Vec<LoopRow> = Vec::new();
     let mut has_needs_review = false;
@@ -298,6 +352,7 @@ fn list_loops(args: ListArgs, use_colors: bool) -> Result<()> {
                     prompt: truncate(&metadata.prompt, 40),
                     age: None,   // Primary loop age not easily available
                     merge: None, // Primary loop doesn't have merge state
+                    after: None,
                 });
             }
         }
@@ -306,7 +361,10 @@ fn list_loops(args: ListArgs, use_colors: bool) -> Result<()> {
     // Add running loops from registry
     for entry in &loop_entries {
         let status = if entry.is_alive() {
-            "running"
+            match entry.state {
+                SlotState::Running => "running",
+                SlotState::Queued => "queued",
+            }
         } else if entry.is_pid_alive() {
             // PID alive but is_alive() false → worktree removed externally
             "orphan"
@@ -327,6 +385,7 @@ fn list_loops(args: ListArgs, use_colors: bool) -> Result<()> {
             prompt: truncate(&entry.prompt, 40),
             age: None, // Registry doesn't track start time
             merge: None,
+            after: None,
         });
     }
 
@@ -379,6 +438,7 @@ fn list_loops(args: ListArgs, use_colors: bool) -> Result<()> {
                 prompt: truncate(&entry.prompt, 40),
                 age,
                 merge: merge_status,
+                after: None,
             });
         }
     }
@@ -396,11 +456,39 @@ fn list_loops(args: ListArgs, use_colors: bool) -> Result<()> {
                     prompt: String::new(),
                     age: None,
                     merge: None,
+                    after: None,
                 });
             }
         }
     }
 
+    // Add loops scheduled with `ralph run --after` and link children to parents
+    for entry in &scheduled {
+        if let Some(row) = rows.iter_mut().find(|r| r.id == entry.id) {
+            row.after = Some(entry.after.clone());
+            continue;
+        }
+        let status = match entry.status {
+            ScheduleStatus::Pending => "scheduled",
+            ScheduleStatus::Cancelled if args.all => "cancelled",
+            ScheduleStatus::Cancelled => {
+                hidden_terminal_count += 1;
+                continue;
+            }
+            // Started loops are listed through the registry or merge queue
+            ScheduleStatus::Started => continue,
+        };
+        rows.push(LoopRow {
+            id: entry.id.clone(),
+            status: status.to_string(),
+            location: format!("on {}", entry.start_on.as_str()),
+            prompt: truncate(&entry.prompt, 40),
+            age: Some(format_age(now.signed_duration_since(entry.created))),
+            merge: None,
+            after: Some(entry.after.clone()),
+        });
+    }
+
     if rows.is_empty() {
         if args.json {
             println!("[]");
@@ -426,11 +514,13 @@ fn list_loops(args: ListArgs, use_colors: bool) -> Result<()> {
     // Print summary header
     let summary_parts: Vec<String> = [
         "running",
+        "scheduled",
         "queued",
         "merging",
         "needs-review",
         "merged",
         "discarded",
+        "cancelled",
         "crashed",
         "orphan",
     ]
@@ -454,7 +544,11 @@ fn list_loops(args: ListArgs, use_colors: bool) -> Result<()> {
     );
     println!("{}", "-".repeat(88));
 
-    for row in rows {
+    for (depth, row) in dependency_order(rows) {
+        let id_display = match depth {
+            0 => row.id.clone(),
+            _ => format!("{}└─ {}", "  ".repeat(depth - 1), row.id),
+        };
         let status_display = if use_colors {
             colorize_status(&row.status)
         } else {
@@ -466,7 +560,7 @@ fn list_loops(args: ListArgs, use_colors: bool) -> Result<()> {
 
         println!(
             "{:<20} {:<12} {:<8} {:<8} {:<20} {}",
-            truncate(&row.id, 20),
+            truncate(&id_display, 20),
             status_display,
             merge_display,
             age_display,
@@ -479,7 +573,7 @@ fn list_loops(args: ListArgs, use_colors: bool) -> Result<()> {
     println!();
     if hidden_terminal_count > 0 {
         println!(
-            "({} merged/discarded hidden. Use --all to show.)",
+            "({} merged/discarded/cancelled hidden. Use --all to show.)",
             hidden_terminal_count
         );
     }
@@ -501,6 +595,51 @@ struct LoopRow {
     age: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     merge: Option<String>,
+    /// Parent loop for loops scheduled with `ralph run --after`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    after: Option<String>,
+}
+
+/// Orders rows so each scheduled loop follows its parent, paired with its depth.
+///
+/// Rows whose parent is not listed are shown at the top level.
+fn dependency_order(rows: Vec<LoopRow>) -> Vec<(usize, LoopRow)> {
+    fn visit(
+        index: usize,
+        depth: usize,
+        children: &std::collections::HashMap<&str, Vec<usize>>,
+        ids: &[String],
+        order: &mut Vec<(usize, usize)>,
+    ) {
+        order.push((depth, index));
+        for &child in children.get(ids[index].as_str()).into_iter().flatten() {
+            visit(child, depth + 1, children, ids, order);
+        }
+    }
+
+    let ids: Vec<String> = rows.iter().map(|r| r.id.clone()).collect();
+    let mut children: std::collections::HashMap<&str, Vec<usize>> =
+        std::collections::HashMap::new();
+    let mut roots = Vec::new();
+    for (index, row) in rows.iter().enumerate() {
+        match row.after.as_deref() {
+            Some(parent) if ids.iter().any(|id| id == parent) => {
+                children.entry(parent).or_default().push(index);
+            }
+            _ => roots.push(index),
+        }
+    }
+
+    let mut order = Vec::with_capacity(rows.len());
+    for root in roots {
+        visit(root, 0, &children, &ids, &mut order);
+    }
+
+    let mut slots: Vec<Option<LoopRow>> = rows.into_iter().map(Some).collect();
+    order
+        .into_iter()
+        .filter_map(|(depth, index)| slots[index].take().map(|row| (depth, row)))
+        .collect()
 }
 
 fn colorize_status(status: &str) -> String {
@@ -513,6 +652,8 @@ fn colorize_status(status: &str) -> String {
         "orphan" => format!("\x1b[90m{}\x1b[0m", status),  // gray
         "queued" => format!("\x1b[36m{}\x1b[0m", status),  // cyan
         "discarded" => format!("\x1b[90m{}\x1b[0m", status), // gray
+        "scheduled" => format!("\x1b[35m{}\x1b[0m", status), // magenta
+        "cancelled" => format!("\x1b[90m{}\x1b[0m", status), // gray
         _ => status.to_string(),
     }
 }
@@ -616,8 +757,16 @@ fn show_history(args: HistoryArgs) -> Result<()> {
             }
             if let Ok(event) = serde_json::from_str::<serde_json::Value>(line) {
                 let ts = event.get("ts").and_then(|v| v.as_str()).unwrap_or("-");
-                let event_type = event.get("type").and_then(|v| v.as_str()).unwrap_or("-");
-                let data = event.get("data").map(|v| v.to_string()).unwrap_or_default();
+                // History events are tagged as {"type": {"kind": ..., ...fields}}
+                let event_type = event
+                    .get("type")
+                    .and_then(|v| v.as_str().or_else(|| v.get("kind")?.as_str()))
+                    .unwrap_or("-");
+                let data = event
+                    .get("data")
+                    .or_else(|| event.get("type").filter(|v| v.is_object()))
+                    .map(|v| v.to_string())
+                    .unwrap_or_default();
                 println!(
                     "{:<25} {:<20} {}",
                     truncate(ts, 25),
@@ -626,13 +775,44 @@ fn show_history(args: HistoryArgs) -> Result<()> {
                 );
             }
         }
+
+        write_hat_spend(&mut std::io::stdout(), &LoopHistory::new(&history_path))?;
+    }
+
+    Ok(())
+}
+
+/// Writes per-hat token and cost totals recorded in loop history.
+fn write_hat_spend<W: Write>(writer: &mut W, history: &LoopHistory) -> Result<()> {
+    let Ok(summary) = history.summary() else {
+        return Ok(());
+    };
+    if summary.hat_usage.is_empty() {
+        return Ok(());
     }
 
+    writeln!(writer)?;
+    writeln!(
+        writer,
+        "{:<20} {:>10} {:>12} {:>10}",
+        "HAT", "ITERATIONS", "TOKENS", "COST"
+    )?;
+    writeln!(writer, "{}", "-".repeat(55))?;
+    for (hat, usage) in &summary.hat_usage {
+        writeln!(
+            writer,
+            "{:<20} {:>10} {:>12} {:>10}",
+            truncate(hat, 20),
+            usage.iterations,
+            usage.tokens,
+            format!("${:.4}", usage.cost_usd)
+        )?;
+    }
     Ok(())
 }
 
 /// Retry merge for a failed loop.
-fn retry_merge(args: RetryArgs) -> Result<()> {
+fn retry_merge(args: RetryArgs, config_sources: &[ConfigSource]) -> Result<()> {
     let cwd = std::env::current_dir()?;
     let merge_queue = MergeQueue::new(&cwd);
 
@@ -648,12 +828,23 @@ fn retry_merge(args: RetryArgs) -> Result<()> {
         );
     }
 
-    spawn_merge_ralph(&cwd, &args.loop_id)
+    merge_with_executor(&cwd, config_sources, &args.loop_id)
 }
 
 /// Discard a loop and clean up.
 fn discard_loop(args: DiscardArgs) -> Result<()> {
     let cwd = std::env::current_dir()?;
+
+    // A loop that hasn't started yet only needs its schedule entry cancelled
+    let schedule = LoopSchedule::new(&cwd);
+    if let Ok(Some(entry)) = schedule.get(&args.loop_id)
+        && entry.is_pending()
+    {
+        let actions = schedule.cancel(&entry.id, "User requested discard")?;
+        print_schedule_actions(&actions);
+        return Ok(());
+    }
+
     let (loop_id, worktree_path) = resolve_loop(&cwd, &args.loop_id)?;
 
     // Confirmation unless -y
@@ -697,6 +888,9 @@ fn discard_loop(args: DiscardArgs) -> Result<()> {
     }
 
     println!("Loop '{}' discarded.", loop_id);
+
+    // Loops scheduled after this one will never start
+    print_schedule_actions(&crate::loop_runner::reconcile_scheduled_loops(&cwd));
     Ok(())
 }
 
@@ -869,7 +1063,17 @@ fn resume_loop(args: ResumeArgs) -> Result<()> {
         .map(PathBuf::from)
         .unwrap_or_else(|| cwd.clone());
 
-    let suspend_state_store = SuspendStateStore::new(&target_root);
+    // Merge hooks run from the repo root, so a loop suspended by a
+    // `pre.merge`/`post.merge` hook keeps its suspend-state there.
+    let mut suspend_state_store = SuspendStateStore::new(&target_root);
+    if target_root != cwd && !matches!(suspend_state_store.read_suspend_state(), Ok(Some(_))) {
+        let root_store = SuspendStateStore::new(&cwd);
+        if let Ok(Some(state)) = root_store.read_suspend_state()
+            && state.loop_id == loop_id
+        {
+            suspend_state_store = root_store;
+        }
+    }
     let suspend_state = suspend_state_store
         .read_suspend_state()
         .with_context(|| format!("Failed to read suspend-state for loop '{}'", loop_id))?;
@@ -989,11 +1193,12 @@ fn show_diff(args: DiffArgs) -> Result<()> {
         bail!("Branch '{}' not found", branch);
     }
 
-    let base_branch = default_diff_base_branch(&cwd);
+    let base_branch = loop_base_branch(&cwd, &loop_id);
     if !git_ref_exists(&cwd, &base_branch) {
         bail!(
-            "Base branch '{}' not found in this repository.\n\nTry explicitly passing a base via upstream/main merge history.",
-            base_branch
+            "Base branch '{}' not found in this repository.\n\nRetarget the loop with `ralph loops merge {} --onto <branch>`.",
+            base_branch,
+            loop_id
         );
     }
 
@@ -1019,31 +1224,18 @@ fn show_diff(args: DiffArgs) -> Result<()> {
     Ok(())
 }
 
-fn default_diff_base_branch(cwd: &std::path::Path) -> String {
-    if let Some(output) = git_output(
-        cwd,
-        ["symbolic-ref", "-q", "--short", "refs/remotes/origin/HEAD"],
-    ) {
-        let value = output.trim();
-        if let Some(base) = value.split('/').next_back() {
-            let direct = base.to_string();
-            let with_remote = format!("origin/{}", direct);
-            if git_ref_exists(cwd, &direct) {
-                return direct;
-            }
-            if git_ref_exists(cwd, &with_remote) {
-                return with_remote;
-            }
-        }
-    }
-
-    for candidate in ["origin/main", "main", "origin/master", "master"] {
-        if git_ref_exists(cwd, candidate) {
-            return candidate.to_string();
-        }
-    }
+/// Resolves a reference to its commit SHA.
+fn git_rev_parse(cwd: &std::path::Path, reference: &str) -> Option<String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--verify", "--quiet", reference])
+        .current_dir(cwd)
+        .output()
+        .ok()?;
 
-    "main".to_string()
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
 }
 
 fn git_ref_exists(cwd: &std::path::Path, reference: &str) -> bool {
@@ -1054,20 +1246,6 @@ fn git_ref_exists(cwd: &std::path::Path, reference: &str) -> bool {
         .is_ok_and(|status| status.success())
 }
 
-fn git_output(cwd: &std::path::Path, args: [&str; 4]) -> Option<String> {
-    let output = Command::new("git")
-        .args(args)
-        .current_dir(cwd)
-        .output()
-        .ok()?;
-
-    if output.status.success() {
-        Some(String::from_utf8_lossy(&output.stdout).into_owned())
-    } else {
-        None
-    }
-}
-
 fn wait_for_process_exit(pid: u32, timeout: Duration) -> bool {
     let deadline = Instant::now() + timeout;
     while Instant::now() < deadline {
@@ -1090,7 +1268,7 @@ fn orphan_stop_wait_timeout(force: bool) -> Duration {
 }
 
 /// Merge a completed loop (or force retry).
-fn merge_loop(args: MergeArgs) -> Result<()> {
+fn merge_loop(args: MergeArgs, config_sources: &[ConfigSource]) -> Result<()> {
     let cwd = std::env::current_dir()?;
     let registry = LoopRegistry::new(&cwd);
     let merge_queue = MergeQueue::new(&cwd);
@@ -1158,7 +1336,61 @@ fn merge_loop(args: MergeArgs) -> Result<()> {
         }
     }
 
-    spawn_merge_ralph(&cwd, &loop_id)
+    if let Some(onto) = &args.onto {
+        retarget_loop(&cwd, &loop_id, onto)?;
+    }
+
+    merge_with_executor(&cwd, config_sources, &loop_id)
+}
+
+/// Merges one queued loop with the deterministic executor.
+///
+/// merge-ralph only runs when the executor stops on conflicts (and
+/// `merge.llm_fallback` is set), or for every merge with `merge.executor: llm`.
+fn merge_with_executor(
+    cwd: &std::path::Path,
+    config_sources: &[ConfigSource],
+    loop_id: &str,
+) -> Result<()> {
+    let config = load_config_with_overrides(config_sources)?;
+    if config.merge.executor == MergeExecutorMode::Llm {
+        return spawn_merge_ralph(cwd, loop_id);
+    }
+
+    let Some(report) = MergeExecutor::from_config(cwd, &config).process_loop(loop_id)? else {
+        bail!(
+            "Loop '{}' is being merged by another executor. Try again once it finishes.",
+            loop_id
+        );
+    };
+
+    let hand_off = report.outcome.is_conflict() && config.merge.llm_fallback;
+    print_merge_report(&report, hand_off);
+    print_schedule_actions(&crate::loop_runner::reconcile_scheduled_loops(cwd));
+
+    if hand_off {
+        spawn_merge_ralph(cwd, loop_id)?;
+    }
+    Ok(())
+}
+
+/// Points a loop at a new base branch in the merge queue and registry.
+fn retarget_loop(cwd: &std::path::Path, loop_id: &str, onto: &str) -> Result<()> {
+    let Some(commit) = git_rev_parse(cwd, onto) else {
+        bail!("Branch '{}' not found in this repository.", onto);
+    };
+
+    let merge_queue = MergeQueue::new(cwd);
+    if merge_queue.get_entry(loop_id)?.is_some() {
+        merge_queue.retarget(loop_id, onto, Some(&commit))?;
+    }
+    match LoopRegistry::new(cwd).set_base(loop_id, onto, Some(&commit)) {
+        Ok(()) | Err(RegistryError::NotFound(_)) => {}
+        Err(e) => return Err(e.into()),
+    }
+
+    println!("Retargeted loop '{}' onto '{}'.", loop_id, onto);
+    Ok(())
 }
 
 /// Helper to spawn merge-ralph
@@ -1183,6 +1415,7 @@ fn spawn_merge_ralph(cwd: &std::path::Path, loop_id: &str) -> Result<()> {
     // Spawn merge-ralph
     println!("Spawning merge-ralph for loop '{}'...", loop_id);
 
+    let base_branch = loop_base_branch(cwd, loop_id);
     let status = Command::new("ralph")
         .args([
             "run",
@@ -1192,9 +1425,13 @@ fn spawn_merge_ralph(cwd: &std::path::Path, loop_id: &str) -> Result<()> {
             "builtin:merge-loop",
             "--exclusive",
             "-p",
-            &format!("Merge loop {} from branch ralph/{}", loop_id, loop_id),
+            &format!(
+                "Merge loop {} from branch ralph/{} into {}",
+                loop_id, loop_id, base_branch
+            ),
         ])
         .env("RALPH_MERGE_LOOP_ID", loop_id)
+        .env("RALPH_MERGE_BASE_BRANCH", &base_branch)
         .status()
         .context("Failed to spawn merge-ralph")?;
 
@@ -1579,6 +1816,52 @@ mod tests {
         .expect("show history");
     }
 
+    #[test]
+    fn test_show_history_with_hat_usage() {
+        let temp_dir = tempfile::tempdir().expect("temp dir");
+        let _cwd = CwdGuard::set(temp_dir.path());
+
+        let history = LoopHistory::new(temp_dir.path().join(".ralph/history.jsonl"));
+        history.record_started("test prompt").expect("record start");
+        history
+            .record_hat_usage(1, "builder", 0.12, 3_000)
+            .expect("record usage");
+        history
+            .record_hat_usage(2, "reviewer", 0.9, 12_000)
+            .expect("record usage");
+
+        let registry = LoopRegistry::new(temp_dir.path());
+        let entry = LoopEntry::with_id(
+            "loop-hist-usage",
+            "test prompt",
+            None::<String>,
+            temp_dir.path().display().to_string(),
+        );
+        registry.register(entry).expect("register loop");
+
+        show_history(HistoryArgs {
+            loop_id: "loop-hist-usage".to_string(),
+            json: false,
+        })
+        .expect("show history");
+
+        let mut output = Vec::new();
+        write_hat_spend(&mut output, &history).expect("write hat spend");
+        let output = String::from_utf8(output).expect("utf8 output");
+        let builder = output
+            .lines()
+            .find(|line| line.starts_with("builder"))
+            .expect("builder row");
+        assert!(builder.contains("3000"), "builder row: {builder}");
+        assert!(builder.contains("$0.1200"), "builder row: {builder}");
+        let reviewer = output
+            .lines()
+            .find(|line| line.starts_with("reviewer"))
+            .expect("reviewer row");
+        assert!(reviewer.contains("12000"), "reviewer row: {reviewer}");
+        assert!(reviewer.contains("$0.9000"), "reviewer row: {reviewer}");
+    }
+
     #[test]
     fn test_retry_merge_rejects_non_needs_review_state() {
         let temp_dir = tempfile::tempdir().expect("temp dir");
@@ -1587,9 +1870,12 @@ mod tests {
         let queue = MergeQueue::new(temp_dir.path());
         queue.enqueue("loop-queue-1", "prompt").expect("enqueue");
 
-        let err = retry_merge(RetryArgs {
-            loop_id: "loop-queue-1".to_string(),
-        })
+        let err = retry_merge(
+            RetryArgs {
+                loop_id: "loop-queue-1".to_string(),
+            },
+            &[],
+        )
         .expect_err("retry should fail for non-needs-review");
 
         assert!(err.to_string().contains("can only retry"));
@@ -1697,6 +1983,81 @@ mod tests {
         assert_eq!(entry.state, MergeState::Discarded);
     }
 
+    #[test]
+    fn test_discard_scheduled_loop_cancels_its_dependents() {
+        let temp_dir = tempfile::tempdir().expect("temp dir");
+        let _cwd = CwdGuard::set(temp_dir.path());
+
+        MergeQueue::new(temp_dir.path())
+            .enqueue("loop-parent", "prompt")
+            .expect("enqueue");
+        let schedule = LoopSchedule::new(temp_dir.path());
+        for (id, after) in [
+            ("loop-child", "loop-parent"),
+            ("loop-grandchild", "loop-child"),
+        ] {
+            schedule
+                .add(ralph_core::ScheduledLoop::new(
+                    id,
+                    after,
+                    ralph_core::StartOn::Merged,
+                    "next",
+                    Vec::new(),
+                ))
+                .expect("schedule");
+        }
+
+        discard_loop(DiscardArgs {
+            loop_id: "loop-child".to_string(),
+            yes: true,
+        })
+        .expect("discard scheduled loop");
+
+        let grandchild = schedule.get("loop-grandchild").unwrap().unwrap();
+        assert_eq!(grandchild.status, ScheduleStatus::Cancelled);
+        let parent = MergeQueue::new(temp_dir.path())
+            .get_entry("loop-parent")
+            .unwrap()
+            .unwrap();
+        assert_eq!(parent.state, MergeState::Queued);
+    }
+
+    #[test]
+    fn test_dependency_order_nests_children_under_parents() {
+        let row = |id: &str, after: Option<&str>| LoopRow {
+            id: id.to_string(),
+            status: "scheduled".to_string(),
+            location: "-".to_string(),
+            prompt: String::new(),
+            age: None,
+            merge: None,
+            after: after.map(String::from),
+        };
+        let rows = vec![
+            row("grandchild", Some("child")),
+            row("other", None),
+            row("child", Some("parent")),
+            row("parent", None),
+            row("orphaned", Some("gone")),
+        ];
+
+        let order: Vec<(usize, String)> = dependency_order(rows)
+            .into_iter()
+            .map(|(depth, row)| (depth, row.id))
+            .collect();
+
+        assert_eq!(
+            order,
+            vec![
+                (0, "other".to_string()),
+                (0, "parent".to_string()),
+                (1, "child".to_string()),
+                (2, "grandchild".to_string()),
+                (0, "orphaned".to_string()),
+            ]
+        );
+    }
+
     #[test]
     fn test_discard_loop_without_worktree_path_runs_fallback_cleanup() {
         if Command::new("git").arg("--version").output().is_err() {
@@ -1864,6 +2225,41 @@ mod tests {
         assert!(!temp_dir.path().join(".ralph/resume-requested").exists());
     }
 
+    #[test]
+    fn test_resume_loop_targets_repo_root_for_suspended_merge_hook() {
+        let temp_dir = tempfile::tempdir().expect("temp dir");
+        let _cwd = CwdGuard::set(temp_dir.path());
+
+        let loop_id = "loop-merge-suspend-1";
+        let worktree_path = temp_dir.path().join(".worktrees").join(loop_id);
+        std::fs::create_dir_all(&worktree_path).expect("create worktree dir");
+
+        let registry = LoopRegistry::new(temp_dir.path());
+        let entry = LoopEntry::with_id(
+            loop_id,
+            "resume me",
+            Some(worktree_path.display().to_string()),
+            temp_dir.path().display().to_string(),
+        );
+        registry.register(entry).expect("register loop");
+
+        // The merge executor suspends from the repo root, not the worktree.
+        let store = SuspendStateStore::new(temp_dir.path());
+        write_suspend_state(&store, loop_id);
+
+        resume_loop(ResumeArgs {
+            loop_id: loop_id.to_string(),
+        })
+        .expect("resume loop");
+
+        assert!(store.resume_requested_path().exists());
+        assert!(
+            !SuspendStateStore::new(&worktree_path)
+                .resume_requested_path()
+                .exists()
+        );
+    }
+
     #[test]
     fn test_resume_loop_is_idempotent_when_resume_already_requested() {
         let temp_dir = tempfile::tempdir().expect("temp dir");
@@ -1995,7 +2391,7 @@ mod tests {
     }
 
     #[test]
-    fn test_default_diff_base_branch_prefers_main_branch() {
+    fn test_default_base_branch_prefers_main_branch() {
         let temp_dir = tempfile::tempdir().expect("temp dir");
         let _cwd = CwdGuard::set(temp_dir.path());
 
@@ -2004,11 +2400,11 @@ mod tests {
             .status()
             .expect("git init -b main");
 
-        assert_eq!(default_diff_base_branch(temp_dir.path()), "main");
+        assert_eq!(ralph_core::default_base_branch(temp_dir.path()), "main");
     }
 
     #[test]
-    fn test_default_diff_base_branch_falls_back_to_master() {
+    fn test_default_base_branch_falls_back_to_master() {
         let temp_dir = tempfile::tempdir().expect("temp dir");
         let _cwd = CwdGuard::set(temp_dir.path());
 
@@ -2049,7 +2445,7 @@ mod tests {
             .current_dir(temp_dir.path())
             .status();
 
-        assert_eq!(default_diff_base_branch(temp_dir.path()), "master");
+        assert_eq!(ralph_core::default_base_branch(temp_dir.path()), "master");
     }
 
     #[test]
@@ -2057,7 +2453,7 @@ mod tests {
         let temp_dir = tempfile::tempdir().expect("temp dir");
         let _cwd = CwdGuard::set(temp_dir.path());
 
-        execute(LoopsArgs { command: None }, false).expect("execute default");
+        execute(LoopsArgs { command: None }, &[], false).expect("execute default");
     }
 
     #[test]
@@ -2085,10 +2481,14 @@ mod tests {
             .mark_merged("loop-merged-1", "abc123")
             .expect("mark merged");
 
-        let err = merge_loop(MergeArgs {
-            loop_id: "loop-merged-1".to_string(),
-            force: false,
-        })
+        let err = merge_loop(
+            MergeArgs {
+                loop_id: "loop-merged-1".to_string(),
+                force: false,
+                onto: None,
+            },
+            &[],
+        )
         .expect_err("merge should fail for merged loop");
 
         assert!(err.to_string().contains("already merged"));
@@ -2107,10 +2507,14 @@ mod tests {
             .discard("loop-discarded-1", Some("no longer needed"))
             .expect("discard");
 
-        let err = merge_loop(MergeArgs {
-            loop_id: "loop-discarded-1".to_string(),
-            force: false,
-        })
+        let err = merge_loop(
+            MergeArgs {
+                loop_id: "loop-discarded-1".to_string(),
+                force: false,
+                onto: None,
+            },
+            &[],
+        )
         .expect_err("merge should fail for discarded loop");
 
         assert!(err.to_string().contains("discarded"));
@@ -2127,12 +2531,70 @@ mod tests {
             .mark_merging("loop-merging-1", 4242)
             .expect("mark merging");
 
-        let err = merge_loop(MergeArgs {
-            loop_id: "loop-merging-1".to_string(),
-            force: false,
-        })
+        let err = merge_loop(
+            MergeArgs {
+                loop_id: "loop-merging-1".to_string(),
+                force: false,
+                onto: None,
+            },
+            &[],
+        )
         .expect_err("merge should fail for merging loop without force");
 
         assert!(err.to_string().contains("currently merging"));
     }
+
+    #[test]
+    fn test_merge_loop_fast_forwardable_branch_merges_without_merge_ralph() {
+        if Command::new("git").arg("--version").output().is_err() {
+            return;
+        }
+
+        let temp_dir = tempfile::tempdir().expect("temp dir");
+        let _cwd = CwdGuard::set(temp_dir.path());
+        let git = |args: &[&str]| {
+            let status = Command::new("git").args(args).status().expect("git");
+            assert!(status.success(), "git {args:?} failed");
+        };
+
+        git(&["init", "-q", "-b", "main"]);
+        git(&["config", "user.email", "test@example.com"]);
+        git(&["config", "user.name", "Test User"]);
+        std::fs::write(".gitignore", ".ralph/\n.worktrees/\n").expect("write .gitignore");
+        std::fs::write("README.md", "# Test").expect("write README");
+        git(&["add", "."]);
+        git(&["commit", "-q", "-m", "Initial commit"]);
+        git(&["checkout", "-q", "-b", "ralph/loop-ff-1"]);
+        std::fs::write("feature.txt", "feature").expect("write feature");
+        git(&["add", "feature.txt"]);
+        git(&["commit", "-q", "-m", "feat: loop work"]);
+        git(&["checkout", "-q", "main"]);
+
+        let queue = MergeQueue::new(temp_dir.path());
+        queue
+            .enqueue_with_base("loop-ff-1", "prompt", Some("main"), None)
+            .expect("enqueue");
+
+        merge_loop(
+            MergeArgs {
+                loop_id: "loop-ff-1".to_string(),
+                force: false,
+                onto: None,
+            },
+            &[],
+        )
+        .expect("merge loop");
+
+        let entry = queue.get_entry("loop-ff-1").expect("read queue").unwrap();
+        assert_eq!(entry.state, MergeState::Merged);
+        git(&["merge-base", "--is-ancestor", "ralph/loop-ff-1", "main"]);
+        assert!(temp_dir.path().join("feature.txt").exists());
+        // merge-ralph writes its config before spawning; the executor never got there.
+        assert!(
+            !temp_dir
+                .path()
+                .join(".ralph/merge-loop-config.yml")
+                .exists()
+        );
+    }
 }
@@ -41,9 +41,10 @@ use anyhow::{Context, Result};
 use clap::{ArgAction, CommandFactory, Parser, Subcommand, ValueEnum};
 use ralph_adapters::detect_backend;
 use ralph_core::{
-    CheckStatus, EventHistory, LockError, LoopContext, LoopEntry, LoopLock, LoopRegistry,
-    PreflightReport, PreflightRunner, RalphConfig, TerminationReason, truncate_with_ellipsis,
-    worktree::{WorktreeConfig, create_worktree, ensure_gitignore, remove_worktree},
+    Admission, CheckStatus, EventHistory, LockError, LoopContext, LoopEntry, LoopLock,
+    LoopRegistry, LoopSchedule, ParallelLimits, PreflightReport, PreflightRunner, RalphConfig,
+    SCHEDULED_LOOP_ENV, ScheduledLoop, StartOn, TerminationReason, truncate_with_ellipsis,
+    worktree::{WorktreeConfig, create_worktree_from, ensure_gitignore, remove_worktree},
 };
 use std::fs;
 use std::io::{IsTerminal, Write, stdout};
@@ -722,6 +723,27 @@ struct RunArgs {
     #[arg(long)]
     no_auto_merge: bool,
 
+    /// Schedule this loop to start after another loop instead of running now.
+    /// The loop runs in its own worktree once the parent reaches the
+    /// --start-on state, and is cancelled if the parent is discarded or fails.
+    #[arg(
+        long,
+        value_name = "LOOP_ID",
+        conflicts_with_all = ["continue_mode", "exclusive", "dry_run", "rpc"]
+    )]
+    after: Option<String>,
+
+    /// Parent state that starts a loop scheduled with --after: `queued` starts
+    /// from the parent's branch once it is queued for merge, `merged` (default)
+    /// starts from the base branch after the parent merges.
+    #[arg(long, value_name = "STATE", requires = "after")]
+    start_on: Option<StartOn>,
+
+    /// Queue priority for a parallel loop waiting on features.max_parallel_loops
+    /// or a backend concurrency cap (higher starts first).
+    #[arg(long, default_value_t = 0, allow_hyphen_values = true)]
+    priority: i32,
+
     // ─────────────────────────────────────────────────────────────────────────
     // Preflight Options
     // ─────────────────────────────────────────────────────────────────────────
@@ -1159,7 +1181,9 @@ async fn main() -> Result<()> {
             code_task_command(&config_sources, hats_source.as_ref(), cli.color, args).await
         }
         Some(Commands::Tools(args)) => tools::execute(args, cli.color.should_use_colors()).await,
-        Some(Commands::Loops(args)) => loops::execute(args, cli.color.should_use_colors()),
+        Some(Commands::Loops(args)) => {
+            loops::execute(args, &config_sources, cli.color.should_use_colors())
+        }
         Some(Commands::Hats(args)) => {
             hats::execute(
                 &config_sources,
@@ -1200,6 +1224,9 @@ async fn main() -> Result<()> {
                 idle_timeout: None,
                 exclusive: false,
                 no_auto_merge: false,
+                after: None,
+                start_on: None,
+                priority: 0,
                 skip_preflight: false,
                 verbose: false,
                 quiet: false,
@@ -1382,6 +1409,11 @@ async fn run_command(
 ) -> Result<()> {
     let mut config = preflight::load_config_for_preflight(config_sources, hats_source).await?;
 
+    // --after: register the loop to start later instead of running it now
+    if let Some(parent) = args.after.as_deref() {
+        return schedule_loop_after(config_sources, hats_source, &config, &args, parent);
+    }
+
     // Handle --continue mode: check scratchpad exists before proceeding
     let resume = args.continue_mode;
     if resume {
@@ -1556,7 +1588,31 @@ async fn run_command(
     // This implements the lock detection flow from the multi-loop spec
     // Skip lock acquisition in subprocess TUI mode - let the child acquire it
     let workspace_root = &config.core.workspace_root;
-    let (loop_context, _lock_guard) = if use_subprocess_tui {
+    let scheduled_loop = scheduled_loop_entry(workspace_root)?;
+    let (loop_context, _lock_guard) = if let Some(entry) = scheduled_loop {
+        // Launched by the loop schedule: run in a worktree from the recorded start point
+        let start_point = entry.start_point.as_deref();
+        info!(
+            "Starting scheduled loop {} after {} from {}",
+            entry.id,
+            entry.after,
+            start_point.unwrap_or("HEAD")
+        );
+        let base_branch = entry
+            .base_branch
+            .clone()
+            .unwrap_or_else(|| ralph_core::default_base_branch(workspace_root));
+        let (context, registration) = prepare_worktree_loop(
+            workspace_root,
+            &entry.id,
+            &prompt_summary,
+            start_point,
+            base_branch,
+            None,
+        )?;
+        pending_worktree_registration = Some(registration);
+        (context, None)
+    } else if use_subprocess_tui {
         // In subprocess TUI mode, don't acquire lock here - the child RPC process will do it
         // This avoids the self-lock contention where parent holds lock and child sees it,
         // then incorrectly spawns a worktree thinking there's another concurrent loop
@@ -1607,49 +1663,23 @@ async fn run_command(
                     // This ID will be used consistently for: registry ID, worktree path, and branch name
                     let name_generator =
                         ralph_core::LoopNameGenerator::from_config(&config.features.loop_naming);
+                    let schedule = LoopSchedule::new(workspace_root);
                     let loop_id = name_generator.generate_memorable_unique(|name| {
                         ralph_core::worktree_exists(workspace_root, name, &worktree_config)
+                            || matches!(schedule.get(name), Ok(Some(_)))
                     });
 
-                    // Ensure worktree directory is in .gitignore
-                    ensure_gitignore(workspace_root, ".worktrees")
-                        .context("Failed to update .gitignore for worktrees")?;
-
-                    // Create the worktree
-                    let worktree = create_worktree(workspace_root, &loop_id, &worktree_config)
-                        .context("Failed to create worktree for parallel loop")?;
-
-                    info!(
-                        "Created worktree at {} on branch {}",
-                        worktree.path.display(),
-                        worktree.branch
-                    );
-
-                    // Create loop context for the worktree
-                    let context = LoopContext::worktree(
-                        loop_id.clone(),
-                        worktree.path.clone(),
-                        workspace_root.clone(),
-                    );
-
-                    // Set up all worktree symlinks (memories, specs, code tasks)
-                    context
-                        .setup_worktree_symlinks()
-                        .context("Failed to create symlinks in worktree")?;
-
-                    // Generate context file with worktree metadata
-                    context
-                        .generate_context_file(&worktree.branch, &prompt_summary)
-                        .context("Failed to generate context file in worktree")?;
+                    // Record what the worktree forks from so merges target the same branch
+                    let (base_branch, base_commit) = ralph_core::current_base(workspace_root);
 
-                    // Register this loop after preflight succeeds so failed runs
-                    // don't leave stale registry entries behind.
-                    let entry = LoopEntry::with_id(
+                    let (context, entry) = prepare_worktree_loop(
+                        workspace_root,
                         &loop_id,
                         &prompt_summary,
-                        Some(worktree.path.to_string_lossy().to_string()),
-                        worktree.path.to_string_lossy().to_string(),
-                    );
+                        None,
+                        base_branch,
+                        base_commit,
+                    )?;
                     pending_worktree_registration = Some(entry);
 
                     // Update config to use worktree paths
@@ -1711,9 +1741,21 @@ async fn run_command(
 
     if let Some(entry) = pending_worktree_registration {
         let registry = LoopRegistry::new(loop_context.repo_root());
-        registry
-            .register(entry)
-            .context("Failed to register loop in registry")?;
+        let limits = ParallelLimits::from_features(&config.features);
+        let entry = entry
+            .with_backend(config.cli.backend.clone())
+            .with_priority(args.priority);
+        if limits.is_unlimited() {
+            registry
+                .register(entry)
+                .context("Failed to register loop in registry")?;
+        } else {
+            let loop_id = entry.id.clone();
+            registry
+                .register(entry.queued())
+                .context("Failed to register loop in registry")?;
+            wait_for_parallel_slot(&registry, &loop_id, &limits).await?;
+        }
     }
 
     // Run the orchestration loop and exit with proper exit code
@@ -1743,7 +1785,8 @@ async fn run_command(
     } else {
         // In-process mode: run_loop_impl handles everything
         let enable_tui = wants_tui && use_legacy_tui;
-        loop_runner::run_loop_impl(
+        // Boxed: the loop future is large and would otherwise inflate every caller's future
+        Box::pin(loop_runner::run_loop_impl(
             config,
             color_mode,
             resume,
@@ -1755,7 +1798,7 @@ async fn run_command(
             custom_args,
             auto_merge_override,
             args.loop_id,
-        )
+        ))
         .await?
     };
 
@@ -1797,6 +1840,237 @@ async fn run_command(
     Ok(())
 }
 
+/// Creates the worktree for a parallel loop and prepares its context.
+///
+/// Returns the loop context and the registry entry to record once preflight
+/// succeeds, so failed runs don't leave stale registry entries behind.
+fn prepare_worktree_loop(
+    workspace_root: &Path,
+    loop_id: &str,
+    prompt_summary: &str,
+    start_point: Option<&str>,
+    base_branch: String,
+    base_commit: Option<String>,
+) -> Result<(LoopContext, LoopEntry)> {
+    // Ensure worktree directory is in .gitignore
+    ensure_gitignore(workspace_root, ".worktrees")
+        .context("Failed to update .gitignore for worktrees")?;
+
+    // Create the worktree
+    let worktree = create_worktree_from(
+        workspace_root,
+        loop_id,
+        &WorktreeConfig::default(),
+        start_point,
+    )
+    .context("Failed to create worktree for parallel loop")?;
+
+    info!(
+        "Created worktree at {} on branch {}",
+        worktree.path.display(),
+        worktree.branch
+    );
+
+    // Create loop context for the worktree
+    let context = LoopContext::worktree(
+        loop_id.to_string(),
+        worktree.path.clone(),
+        workspace_root.to_path_buf(),
+    );
+
+    // Set up all worktree symlinks (memories, specs, code tasks)
+    context
+        .setup_worktree_symlinks()
+        .context("Failed to create symlinks in worktree")?;
+
+    // Generate context file with worktree metadata
+    context
+        .generate_context_file(&worktree.branch, prompt_summary)
+        .context("Failed to generate context file in worktree")?;
+
+    let entry = LoopEntry::with_id(
+        loop_id,
+        prompt_summary,
+        Some(worktree.path.to_string_lossy().to_string()),
+        worktree.path.to_string_lossy().to_string(),
+    )
+    .with_base(base_branch, base_commit);
+
+    Ok((context, entry))
+}
+
+/// Blocks until the registry admits a queued worktree loop.
+///
+/// Polls rather than waiting on a notification so that slots freed by crashed
+/// loops (cleaned up as stale registry entries) are picked up too.
+async fn wait_for_parallel_slot(
+    registry: &LoopRegistry,
+    loop_id: &str,
+    limits: &ParallelLimits,
+) -> Result<()> {
+    let mut last_position = None;
+    loop {
+        match registry
+            .try_admit(loop_id, limits)
+            .context("Failed to check parallel loop slots")?
+        {
+            Admission::Admitted => {
+                if last_position.is_some() {
+                    info!("Loop {} admitted, starting", loop_id);
+                }
+                return Ok(());
+            }
+            Admission::Waiting { position, reason } => {
+                if last_position != Some(position) {
+                    info!(
+                        "Loop {} queued at position {} ({})",
+                        loop_id, position, reason
+                    );
+                    last_position = Some(position);
+                }
+            }
+        }
+        tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+    }
+}
+
+/// Returns the schedule entry when this process was launched by the loop schedule.
+fn scheduled_loop_entry(workspace_root: &Path) -> Result<Option<ScheduledLoop>> {
+    let Ok(id) = std::env::var(SCHEDULED_LOOP_ENV) else {
+        return Ok(None);
+    };
+    let entry = LoopSchedule::new(workspace_root)
+        .get(&id)
+        .context("Failed to read loop schedule")?
+        .with_context(|| format!("Scheduled loop '{id}' not found in loop schedule"))?;
+    Ok(Some(entry))
+}
+
+/// Registers a loop that starts once `parent` reaches the `--start-on` state.
+///
+/// The prompt is captured now so later edits to a prompt file don't change
+/// what the scheduled loop runs.
+fn schedule_loop_after(
+    config_sources: &[ConfigSource],
+    hats_source: Option<&HatsSource>,
+    config: &RalphConfig,
+    args: &RunArgs,
+    parent: &str,
+) -> Result<()> {
+    let workspace_root = &config.core.workspace_root;
+    let prompt = scheduled_prompt(args, config)?;
+    let schedule = LoopSchedule::new(workspace_root);
+
+    let worktree_config = WorktreeConfig::default();
+    let name_generator = ralph_core::LoopNameGenerator::from_config(&config.features.loop_naming);
+    let loop_id = name_generator.generate_memorable_unique(|name| {
+        ralph_core::worktree_exists(workspace_root, name, &worktree_config)
+            || matches!(schedule.get(name), Ok(Some(_)))
+    });
+
+    let start_on = args.start_on.unwrap_or_default();
+    let run_args = scheduled_run_args(config_sources, hats_source, args, &prompt);
+    schedule
+        .add(ScheduledLoop::new(
+            &loop_id, parent, start_on, &prompt, run_args,
+        ))
+        .context("Failed to schedule loop")?;
+
+    println!(
+        "Scheduled loop {loop_id} to start when {parent} is {}.",
+        start_on.as_str()
+    );
+    println!("Use `ralph loops list` to follow it, or `ralph loops discard {loop_id}` to cancel.");
+
+    // The parent may already be in the requested state
+    for action in loop_runner::reconcile_scheduled_loops(workspace_root) {
+        match action {
+            ralph_core::ScheduleAction::Started {
+                id, start_point, ..
+            } => {
+                println!("Started scheduled loop {id} from {start_point}");
+            }
+            ralph_core::ScheduleAction::Cancelled { id, reason } => {
+                println!("Cancelled scheduled loop {id}: {reason}");
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Resolves the prompt text for a scheduled loop (CLI -p/-P, then config).
+fn scheduled_prompt(args: &RunArgs, config: &RalphConfig) -> Result<String> {
+    if let Some(text) = &args.prompt_text {
+        return Ok(text.clone());
+    }
+    if let Some(path) = &args.prompt_file {
+        return fs::read_to_string(path)
+            .with_context(|| format!("Failed to read prompt file {}", path.display()));
+    }
+    if let Some(text) = &config.event_loop.prompt {
+        return Ok(text.clone());
+    }
+    let prompt_file = Path::new(&config.event_loop.prompt_file);
+    if !config.event_loop.prompt_file.is_empty() && prompt_file.exists() {
+        return fs::read_to_string(prompt_file)
+            .with_context(|| format!("Failed to read prompt file {}", prompt_file.display()));
+    }
+    anyhow::bail!("A scheduled loop needs a prompt. Pass -p \"...\" or -P <file>.")
+}
+
+/// Builds the `ralph` arguments a scheduled loop is launched with.
+fn scheduled_run_args(
+    config_sources: &[ConfigSource],
+    hats_source: Option<&HatsSource>,
+    args: &RunArgs,
+    prompt: &str,
+) -> Vec<String> {
+    let mut run_args = Vec::new();
+    for source in config_sources {
+        run_args.push("-c".to_string());
+        run_args.push(source.to_cli_string());
+    }
+    if let Some(hats) = hats_source {
+        run_args.push("-H".to_string());
+        run_args.push(hats.label());
+    }
+    run_args.extend(["run", "--no-tui", "-p"].map(String::from));
+    run_args.push(prompt.to_string());
+
+    if let Some(backend) = &args.backend {
+        run_args.push("-b".to_string());
+        run_args.push(backend.clone());
+    }
+    if let Some(max_iterations) = args.max_iterations {
+        run_args.push("--max-iterations".to_string());
+        run_args.push(max_iterations.to_string());
+    }
+    if let Some(promise) = &args.completion_promise {
+        run_args.push("--completion-promise".to_string());
+        run_args.push(promise.clone());
+    }
+    if args.no_auto_merge {
+        run_args.push("--no-auto-merge".to_string());
+    }
+    if args.priority != 0 {
+        run_args.push(format!("--priority={}", args.priority));
+    }
+    if args.skip_preflight {
+        run_args.push("--skip-preflight".to_string());
+    }
+    if args.verbose {
+        run_args.push("-v".to_string());
+    }
+    if args.quiet {
+        run_args.push("-q".to_string());
+    }
+    if !args.custom_args.is_empty() {
+        run_args.push("--".to_string());
+        run_args.extend(args.custom_args.iter().cloned());
+    }
+    run_args
+}
+
 fn required_restart_command(pid: u32) -> String {
     format!("kill {pid} && RALPH_DIAGNOSTICS=1 cargo run --bin ralph -- resume -c ralph.test.yml")
 }
@@ -2146,7 +2420,8 @@ async fn resume_command(
     let enable_tui = !args.no_tui && !args.autonomous && !args.rpc;
     let enable_rpc = args.rpc;
     let verbosity = Verbosity::resolve(verbose || args.verbose, args.quiet);
-    let reason = loop_runner::run_loop_impl(
+    // Boxed: the loop future is large and would otherwise inflate every caller's future
+    let reason = Box::pin(loop_runner::run_loop_impl(
         config,
         color_mode,
         true,
@@ -2158,7 +2433,7 @@ async fn resume_command(
         Vec::new(), // Resume command doesn't support custom args
         None,       // Use config.features.auto_merge (deprecated command)
         None,       // Deprecated resume command doesn't support --loop-id
-    )
+    ))
     .await?;
     let exit_code = reason.exit_code();
 
@@ -2443,10 +2718,14 @@ fn emit_command_with_root(
     });
 
     // Read events path from marker file, fall back to CLI arg if marker doesn't exist
-    // This ensures `ralph emit` writes to the same events file as the active run
-    let events_file = fs::read_to_string(&current_events_marker)
-        .map(|s| resolve_marker_target(&workspace_root, &s))
-        .unwrap_or_else(|_| args.file.clone());
+    // This ensures `ralph emit` writes to the same events file as the active run.
+    // Fan-out branches override it with their own file via RALPH_EVENTS_FILE.
+    let events_file = match std::env::var_os("RALPH_EVENTS_FILE") {
+        Some(path) if !path.is_empty() => PathBuf::from(path),
+        _ => fs::read_to_string(&current_events_marker)
+            .map(|s| resolve_marker_target(&workspace_root, &s))
+            .unwrap_or_else(|_| args.file.clone()),
+    };
 
     // Ensure parent directory exists
     if let Some(parent) = events_file.parent()
@@ -3221,6 +3500,7 @@ mod tests {
                 on_error: Some(HookOnError::Block),
                 suspend_mode: None,
                 mutate: HookMutationConfig::default(),
+                topics: Vec::new(),
                 extra: std::collections::HashMap::new(),
             }],
         );
@@ -3619,6 +3899,9 @@ core:
             idle_timeout: None,
             exclusive: false,
             no_auto_merge: false,
+            after: None,
+            start_on: None,
+            priority: 0,
             skip_preflight: true,
             verbose: false,
             quiet: false,
@@ -3627,6 +3910,57 @@ core:
         }
     }
 
+    #[tokio::test]
+    async fn test_run_command_after_unknown_loop_returns_error() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let _cwd = CwdGuard::set(temp_dir.path());
+
+        let mut args = default_run_args();
+        args.prompt_text = Some("follow-up".to_string());
+        args.after = Some("ghost-loop".to_string());
+
+        let err = run_command(&[], None, false, ColorMode::Never, args)
+            .await
+            .expect_err("unknown parent should fail");
+        assert!(format!("{err:#}").contains("Unknown parent loop: ghost-loop"));
+    }
+
+    #[tokio::test]
+    async fn test_run_command_after_registers_pending_loop() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let _cwd = CwdGuard::set(temp_dir.path());
+        ralph_core::MergeQueue::new(temp_dir.path())
+            .enqueue("parent-loop", "first task")
+            .unwrap();
+
+        let mut args = default_run_args();
+        args.prompt_text = Some("follow-up".to_string());
+        args.after = Some("parent-loop".to_string());
+
+        run_command(&[], None, false, ColorMode::Never, args)
+            .await
+            .expect("schedule loop");
+
+        let scheduled = LoopSchedule::new(temp_dir.path()).list().unwrap();
+        assert_eq!(scheduled.len(), 1);
+        let entry = &scheduled[0];
+        assert!(entry.is_pending(), "parent is queued, not merged");
+        assert_eq!(entry.after, "parent-loop");
+        assert_eq!(entry.start_on, StartOn::Merged);
+        assert_eq!(
+            entry.args,
+            [
+                "run",
+                "--no-tui",
+                "-p",
+                "follow-up",
+                "-b",
+                "claude",
+                "--skip-preflight"
+            ]
+        );
+    }
+
     #[tokio::test]
     async fn test_run_command_continue_missing_scratchpad_returns_error() {
         let temp_dir = tempfile::tempdir().unwrap();
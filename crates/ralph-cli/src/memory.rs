@@ -8,11 +8,15 @@
 //! - `search`: Find memories by query
 //! - `prime`: Output memories for context injection
 //! - `init`: Initialize memories file
+//! - `compact`: Merge near-duplicates and expire stale memories
 
 use crate::resolve_workspace_root;
 use anyhow::{Context, Result};
 use clap::{Parser, Subcommand, ValueEnum};
-use ralph_core::{MarkdownMemoryStore, Memory, MemoryType, truncate_with_ellipsis};
+use ralph_core::{
+    CompactOptions, CompactReport, DEFAULT_SIMILARITY_THRESHOLD, MarkdownMemoryStore, Memory,
+    MemoryType, compact_memories, truncate_with_ellipsis, undo_compaction,
+};
 use std::path::PathBuf;
 
 /// ANSI color codes for terminal output.
@@ -103,6 +107,9 @@ pub enum MemoryCommands {
 
     /// Initialize memories file
     Init(InitArgs),
+
+    /// Merge near-duplicate memories and expire memories about deleted files
+    Compact(CompactArgs),
 }
 
 /// Arguments for the `memory add` command.
@@ -213,6 +220,30 @@ pub struct InitArgs {
     pub force: bool,
 }
 
+/// Arguments for the `memory compact` command.
+#[derive(Parser, Debug)]
+pub struct CompactArgs {
+    /// Content similarity (0-1) at which memories of the same type are merged
+    #[arg(long, default_value_t = DEFAULT_SIMILARITY_THRESHOLD)]
+    pub threshold: f64,
+
+    /// Keep memories whose referenced file paths no longer exist
+    #[arg(long)]
+    pub no_expire: bool,
+
+    /// Show what would change without modifying the memories file
+    #[arg(long, conflicts_with = "undo")]
+    pub dry_run: bool,
+
+    /// Revert a compaction from the journal (default: the latest one)
+    #[arg(long, value_name = "JOURNAL_ID", num_args = 0..=1, default_missing_value = "latest")]
+    pub undo: Option<String>,
+
+    /// Output format
+    #[arg(long, value_enum, default_value_t = OutputFormat::Table)]
+    pub format: OutputFormat,
+}
+
 /// Execute a memory command.
 pub fn execute(args: MemoryArgs, use_colors: bool) -> Result<()> {
     let root = resolve_workspace_root(args.root.as_ref());
@@ -226,6 +257,9 @@ pub fn execute(args: MemoryArgs, use_colors: bool) -> Result<()> {
         MemoryCommands::Search(search_args) => search_command(&store, search_args, use_colors),
         MemoryCommands::Prime(prime_args) => prime_command(&store, prime_args),
         MemoryCommands::Init(init_args) => init_command(&store, init_args, use_colors),
+        MemoryCommands::Compact(compact_args) => {
+            compact_command(&store, &root, &compact_args, use_colors)
+        }
     }
 }
 
@@ -253,11 +287,10 @@ fn add_command(store: &MarkdownMemoryStore, args: AddArgs, use_colors: bool) ->
         }
         OutputFormat::Markdown => {
             println!(
-                "### {}\n> {}\n<!-- tags: {} | created: {} -->",
+                "### {}\n> {}\n{}",
                 memory.id,
                 memory.content.replace('\n', "\n> "),
-                memory.tags.join(", "),
-                memory.created
+                memory.metadata_comment()
             );
         }
         OutputFormat::Table => {
@@ -524,6 +557,96 @@ fn init_command(store: &MarkdownMemoryStore, args: InitArgs, use_colors: bool) -
     Ok(())
 }
 
+fn compact_command(
+    store: &MarkdownMemoryStore,
+    root: &std::path::Path,
+    args: &CompactArgs,
+    use_colors: bool,
+) -> Result<()> {
+    if !store.exists() {
+        anyhow::bail!("No memories file at {}", store.path().display());
+    }
+    if !(0.0..=1.0).contains(&args.threshold) {
+        anyhow::bail!("--threshold must be between 0 and 1");
+    }
+
+    let (report, verb) = if let Some(undo) = &args.undo {
+        let id = (undo != "latest").then_some(undo.as_str());
+        let report = undo_compaction(store, id).context("Failed to undo compaction")?;
+        (report, "Reverted")
+    } else {
+        let options = CompactOptions {
+            threshold: args.threshold,
+            expire_missing_paths: !args.no_expire,
+            dry_run: args.dry_run,
+            ..CompactOptions::new(root)
+        };
+        let report = compact_memories(store, &options).context("Failed to compact memories")?;
+        (
+            report,
+            if args.dry_run {
+                "Would compact"
+            } else {
+                "Compacted"
+            },
+        )
+    };
+
+    match args.format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&report)?),
+        OutputFormat::Quiet => {
+            if let Some(id) = &report.id {
+                println!("{id}");
+            }
+        }
+        OutputFormat::Table | OutputFormat::Markdown => {
+            print_compact_report(&report, verb, use_colors);
+        }
+    }
+    Ok(())
+}
+
+fn print_compact_report(report: &CompactReport, verb: &str, use_colors: bool) {
+    let (bold, dim, green, reset) = if use_colors {
+        (colors::BOLD, colors::DIM, colors::GREEN, colors::RESET)
+    } else {
+        ("", "", "", "")
+    };
+
+    if report.is_empty() {
+        println!("Nothing to compact.");
+        return;
+    }
+
+    for merge in &report.merges {
+        println!(
+            "{bold}merge{reset}  {} ← {} {dim}(reinforced {}){reset}",
+            merge.kept,
+            merge.merged.join(", "),
+            merge.reinforced
+        );
+    }
+    for expired in &report.expired {
+        println!(
+            "{bold}expire{reset} {} {dim}(missing: {}){reset}",
+            expired.id,
+            expired.missing_paths.join(", ")
+        );
+    }
+
+    println!();
+    println!(
+        "{green}✓{reset} {verb}: {} merged, {} expired",
+        report.merged_count(),
+        report.expired.len()
+    );
+    if let Some(id) = report.id.as_ref().filter(|_| verb == "Compacted") {
+        println!(
+            "{dim}Journal entry: {id} (undo with `ralph tools memory compact --undo {id}`){reset}"
+        );
+    }
+}
+
 // ─────────────────────────────────────────────────────────────────────────────
 // Output Helpers
 // ─────────────────────────────────────────────────────────────────────────────
@@ -556,11 +679,10 @@ fn output_memory(memory: &Memory, format: OutputFormat, use_colors: bool) {
         }
         OutputFormat::Markdown => {
             println!(
-                "### {}\n> {}\n<!-- tags: {} | created: {} -->",
+                "### {}\n> {}\n{}",
                 memory.id,
                 memory.content.replace('\n', "\n> "),
-                memory.tags.join(", "),
-                memory.created
+                memory.metadata_comment()
             );
         }
         OutputFormat::Quiet => {
@@ -711,11 +833,10 @@ fn format_memories_as_markdown(memories: &[Memory]) -> String {
 
         for memory in type_memories {
             output.push_str(&format!(
-                "\n### {}\n> {}\n<!-- tags: {} | created: {} -->\n",
+                "\n### {}\n> {}\n{}\n",
                 memory.id,
                 memory.content.replace('\n', "\n> "),
-                memory.tags.join(", "),
-                memory.created
+                memory.metadata_comment()
             ));
         }
     }
@@ -875,6 +996,7 @@ mod tests {
                 content: "alpha".to_string(),
                 tags: vec!["tag1".to_string()],
                 created: "2026-01-31".to_string(),
+                reinforced: 0,
             },
             Memory {
                 id: "mem-2".to_string(),
@@ -882,6 +1004,7 @@ mod tests {
                 content: "beta".to_string(),
                 tags: vec![],
                 created: "2026-01-31".to_string(),
+                reinforced: 0,
             },
         ];
 
@@ -902,6 +1025,7 @@ mod tests {
             content: "beta".to_string(),
             tags: vec!["tag1".to_string()],
             created: "2026-01-31".to_string(),
+            reinforced: 0,
         }];
 
         let output = format_memories_as_text(&memories);
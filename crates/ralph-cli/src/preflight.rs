@@ -229,6 +229,31 @@ pub(crate) async fn load_config_for_preflight(
     Ok(config)
 }
 
+/// Builds the config of a hat's sub-loop: the parent's core settings with the
+/// hats, events and event-loop overlay of `preset` (in `-H` syntax).
+pub(crate) async fn load_subloop_config(parent: &RalphConfig, preset: &str) -> Result<RalphConfig> {
+    let source = HatsSource::parse(preset);
+
+    // The child only sees the preset's topology, never the delegating hat itself.
+    let mut base = parent.clone();
+    base.hats.clear();
+    base.events.clear();
+    base.event_loop.starting_event = None;
+    let core_value =
+        serde_yaml::to_value(&base).context("Failed to serialize parent config for sub-loop")?;
+
+    let hats_value = load_hats_value(&source).await?;
+    validate_hats_config_shape(&hats_value, &source.label())?;
+    let merged = merge_hats_overlay(core_value, hats_value)?;
+
+    let mut config: RalphConfig = serde_yaml::from_value(merged)
+        .with_context(|| format!("Failed to parse sub-loop config from {}", source.label()))?;
+    config.normalize();
+    config.core.workspace_root = parent.core.workspace_root.clone();
+
+    Ok(config)
+}
+
 pub(crate) fn config_source_label(
     config_sources: &[ConfigSource],
     hats_source: Option<&HatsSource>,
@@ -760,6 +785,41 @@ hats:
         assert_eq!(config.event_loop.completion_promise, "REVIEW_COMPLETE");
     }
 
+    #[tokio::test]
+    async fn load_subloop_config_replaces_parent_topology() {
+        let mut parent: RalphConfig = serde_yaml::from_str(
+            r"
+cli:
+  backend: gemini
+event_loop:
+  max_iterations: 40
+  starting_event: migrate.start
+hats:
+  migrator:
+    name: Migrator
+    description: Delegates to a sub-loop
+    triggers: [migrate.start]
+    publishes: [migrate.done]
+",
+        )
+        .unwrap();
+        parent.core.workspace_root = std::path::PathBuf::from("/project");
+
+        let config = load_subloop_config(&parent, "builtin:code-assist")
+            .await
+            .unwrap();
+
+        assert_eq!(config.cli.backend, "gemini");
+        assert_eq!(config.event_loop.max_iterations, 40);
+        assert_eq!(config.core.workspace_root, parent.core.workspace_root);
+        assert!(!config.hats.contains_key("migrator"));
+        assert!(!config.hats.is_empty());
+        assert_ne!(
+            config.event_loop.starting_event.as_deref(),
+            Some("migrate.start")
+        );
+    }
+
     #[tokio::test]
     async fn load_config_for_preflight_hats_source_takes_precedence_over_core_hats() {
         let temp_dir = tempfile::tempdir().unwrap();
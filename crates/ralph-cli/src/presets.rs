@@ -231,6 +231,22 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_presets_have_no_topology_errors() {
+        for preset in PRESETS {
+            let config =
+                RalphConfig::parse_yaml(preset.content).expect("embedded preset YAML should parse");
+            let registry = ralph_core::HatRegistry::from_config(&config);
+            let report = ralph_core::TopologyReport::analyze(&config, &registry);
+            assert!(
+                !report.has_errors(),
+                "Preset '{}' has topology errors: {:?}",
+                preset.name,
+                report.issues
+            );
+        }
+    }
+
     #[test]
     fn test_pdd_to_code_assist_uses_reviewed_increment_loop() {
         let preset = get_preset("pdd-to-code-assist").expect("pdd-to-code-assist should exist");
@@ -659,3 +659,56 @@ fn test_merge_execution_summary_includes_stats() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_merge_execution_summary_uses_recorded_base_branch() -> Result<()> {
+    let temp_dir = setup_workspace()?;
+    let temp_path = temp_dir.path();
+
+    // Given: A `develop` branch one commit ahead of main
+    Command::new("git")
+        .args(["checkout", "-q", "-b", "develop"])
+        .current_dir(temp_path)
+        .output()?;
+    fs::write(temp_path.join("develop.txt"), "develop only")?;
+    Command::new("git")
+        .args(["add", "develop.txt"])
+        .current_dir(temp_path)
+        .output()?;
+    Command::new("git")
+        .args(["commit", "-m", "Develop commit"])
+        .current_dir(temp_path)
+        .output()?;
+
+    // And: A loop worktree forked from develop
+    let (base_branch, base_commit) = ralph_core::current_base(temp_path);
+    assert_eq!(base_branch, "develop");
+    create_worktree_with_commits(temp_path, "test-loop-016", 1)?;
+
+    let queue = ralph_core::MergeQueue::new(temp_path);
+    queue.enqueue_with_base(
+        "test-loop-016",
+        "Work on develop",
+        Some(&base_branch),
+        base_commit.as_deref(),
+    )?;
+
+    // When/Then: Only the loop's own commit is counted against develop
+    let summary = ralph_core::merge_execution_summary(temp_path, "test-loop-016")?;
+    assert!(
+        summary.starts_with("1 commit,"),
+        "Summary should be relative to develop. Got: {}",
+        summary
+    );
+
+    // And: Retargeting onto main counts the develop commit too
+    queue.retarget("test-loop-016", "main", None)?;
+    let summary = ralph_core::merge_execution_summary(temp_path, "test-loop-016")?;
+    assert!(
+        summary.starts_with("2 commits,"),
+        "Summary should be relative to main after retarget. Got: {}",
+        summary
+    );
+
+    Ok(())
+}
@@ -0,0 +1,455 @@
+//! Orchestrator-verified backpressure gates.
+//!
+//! Runs the commands declared under `backpressure.commands` in the workspace
+//! and compares the actual outcomes with the evidence an agent claimed in its
+//! `build.done` / `verify.passed` payload. Command execution reuses the hook
+//! executor so timeouts and output caps behave exactly like lifecycle hooks.
+
+use crate::config::{BackpressureConfig, QualityDimension};
+use crate::hooks::{HookExecutor, HookExecutorContract, HookRunRequest, HookStreamOutput};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tracing::{debug, warn};
+
+/// Phase-event label used for backpressure command runs.
+const BACKPRESSURE_PHASE: &str = "backpressure";
+
+/// Number of trailing output lines included in rejection payloads.
+const FAILURE_OUTPUT_TAIL_LINES: usize = 20;
+
+/// A backpressure dimension that the orchestrator can verify by running a command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BackpressureCheck {
+    Tests,
+    Lint,
+    Typecheck,
+    Audit,
+    Coverage,
+}
+
+impl BackpressureCheck {
+    /// All verifiable dimensions in execution order.
+    pub const ALL: [Self; 5] = [
+        Self::Tests,
+        Self::Lint,
+        Self::Typecheck,
+        Self::Audit,
+        Self::Coverage,
+    ];
+
+    /// Canonical name matching the evidence key (`tests:`, `lint:`, ...).
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Tests => "tests",
+            Self::Lint => "lint",
+            Self::Typecheck => "typecheck",
+            Self::Audit => "audit",
+            Self::Coverage => "coverage",
+        }
+    }
+
+    /// Returns the quality dimension this check verifies.
+    pub fn dimension(self) -> QualityDimension {
+        match self {
+            Self::Tests => QualityDimension::Tests,
+            Self::Lint => QualityDimension::Lint,
+            Self::Typecheck => QualityDimension::Typecheck,
+            Self::Audit => QualityDimension::Audit,
+            Self::Coverage => QualityDimension::Coverage,
+        }
+    }
+
+    /// Returns the configured command for this dimension, if any.
+    pub fn command(self, config: &BackpressureConfig) -> Option<&str> {
+        let commands = &config.commands;
+        match self {
+            Self::Tests => commands.tests.as_deref(),
+            Self::Lint => commands.lint.as_deref(),
+            Self::Typecheck => commands.typecheck.as_deref(),
+            Self::Audit => commands.audit.as_deref(),
+            Self::Coverage => commands.coverage.as_deref(),
+        }
+    }
+}
+
+impl std::fmt::Display for BackpressureCheck {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str((*self).as_str())
+    }
+}
+
+/// Outcome of one orchestrator-run backpressure command.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BackpressureCheckResult {
+    pub check: BackpressureCheck,
+    pub command: String,
+    pub passed: bool,
+    pub exit_code: Option<i32>,
+    pub timed_out: bool,
+    pub duration_ms: u64,
+    pub stdout: HookStreamOutput,
+    pub stderr: HookStreamOutput,
+    /// Set when the command could not be launched at all.
+    pub error: Option<String>,
+}
+
+impl BackpressureCheckResult {
+    /// Short status line, e.g. `tests: fail (exit 101, 2.4s)`.
+    pub fn summary(&self) -> String {
+        let status = if self.passed { "pass" } else { "fail" };
+        let detail = if let Some(error) = &self.error {
+            format!("error: {error}")
+        } else if self.timed_out {
+            "timed out".to_string()
+        } else {
+            match self.exit_code {
+                Some(code) => format!("exit {code}"),
+                None => "terminated".to_string(),
+            }
+        };
+        format!(
+            "{}: {} ({}, {:.1}s)",
+            self.check,
+            status,
+            detail,
+            self.duration_ms as f64 / 1000.0
+        )
+    }
+
+    /// Returns the last `max_lines` lines of combined stdout/stderr.
+    pub fn output_tail(&self, max_lines: usize) -> String {
+        let combined = [self.stdout.content.as_str(), self.stderr.content.as_str()]
+            .iter()
+            .filter(|content| !content.trim().is_empty())
+            .copied()
+            .collect::<Vec<_>>()
+            .join("\n");
+        let lines: Vec<&str> = combined.lines().collect();
+        let start = lines.len().saturating_sub(max_lines);
+        lines[start..].join("\n")
+    }
+}
+
+/// Disagreement between the agent's claimed evidence and the verified result.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackpressureMismatch {
+    pub check: BackpressureCheck,
+    pub claimed: bool,
+    pub actual: bool,
+}
+
+impl std::fmt::Display for BackpressureMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let label = |passed: bool| if passed { "pass" } else { "fail" };
+        write!(
+            f,
+            "{}: claimed {}, verified {}",
+            self.check,
+            label(self.claimed),
+            label(self.actual)
+        )
+    }
+}
+
+/// Verified results attached to parsed evidence.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BackpressureVerification {
+    pub results: Vec<BackpressureCheckResult>,
+    pub mismatches: Vec<BackpressureMismatch>,
+}
+
+impl BackpressureVerification {
+    /// Builds a verification record, flagging results that contradict `claims`.
+    ///
+    /// Checks missing from `claims` were not reported by the agent and are
+    /// never flagged as mismatches.
+    pub fn from_results(
+        results: Vec<BackpressureCheckResult>,
+        claims: &HashMap<BackpressureCheck, bool>,
+    ) -> Self {
+        let mismatches = results
+            .iter()
+            .filter_map(|result| {
+                let claimed = *claims.get(&result.check)?;
+                (claimed != result.passed).then_some(BackpressureMismatch {
+                    check: result.check,
+                    claimed,
+                    actual: result.passed,
+                })
+            })
+            .collect();
+
+        Self {
+            results,
+            mismatches,
+        }
+    }
+
+    /// Returns the verified result for a dimension, if a command ran for it.
+    pub fn result(&self, check: BackpressureCheck) -> Option<&BackpressureCheckResult> {
+        self.results.iter().find(|result| result.check == check)
+    }
+
+    /// Returns true when every verified command passed.
+    pub fn all_passed(&self) -> bool {
+        self.results.iter().all(|result| result.passed)
+    }
+
+    /// Returns the dimensions whose commands failed.
+    pub fn failed_checks(&self) -> Vec<BackpressureCheck> {
+        self.results
+            .iter()
+            .filter(|result| !result.passed)
+            .map(|result| result.check)
+            .collect()
+    }
+
+    /// Human-readable report fed back to the agent when verification rejects work.
+    pub fn failure_report(&self) -> String {
+        let mut report = String::new();
+
+        if !self.mismatches.is_empty() {
+            report.push_str("Evidence mismatches (claimed vs. orchestrator-verified):\n");
+            for mismatch in &self.mismatches {
+                report.push_str(&format!("- {mismatch}\n"));
+            }
+        }
+
+        for result in self.results.iter().filter(|result| !result.passed) {
+            if !report.is_empty() {
+                report.push('\n');
+            }
+            report.push_str(&format!("{}\n$ {}\n", result.summary(), result.command));
+            let tail = result.output_tail(FAILURE_OUTPUT_TAIL_LINES);
+            if !tail.is_empty() {
+                report.push_str(&tail);
+                report.push('\n');
+            }
+        }
+
+        report
+    }
+}
+
+/// Runs configured backpressure commands in a workspace.
+pub struct BackpressureRunner<E = HookExecutor> {
+    config: BackpressureConfig,
+    workspace_root: PathBuf,
+    executor: E,
+}
+
+impl BackpressureRunner<HookExecutor> {
+    /// Creates a runner using the default process executor.
+    pub fn new(config: BackpressureConfig, workspace_root: impl Into<PathBuf>) -> Self {
+        Self::with_executor(config, workspace_root, HookExecutor::new())
+    }
+}
+
+impl<E: HookExecutorContract> BackpressureRunner<E> {
+    /// Creates a runner with an explicit executor (for testing).
+    pub fn with_executor(
+        config: BackpressureConfig,
+        workspace_root: impl Into<PathBuf>,
+        executor: E,
+    ) -> Self {
+        Self {
+            config,
+            workspace_root: workspace_root.into(),
+            executor,
+        }
+    }
+
+    /// Returns the workspace the commands run in.
+    pub fn workspace_root(&self) -> &Path {
+        &self.workspace_root
+    }
+
+    /// Returns true when the runner has commands to execute.
+    pub fn is_active(&self) -> bool {
+        self.config.is_active()
+    }
+
+    /// Runs every configured command sequentially and returns the results.
+    ///
+    /// Returns an empty list when backpressure verification is disabled.
+    pub fn run(&self) -> Vec<BackpressureCheckResult> {
+        if !self.is_active() {
+            return Vec::new();
+        }
+
+        BackpressureCheck::ALL
+            .iter()
+            .filter_map(|&check| {
+                let command = check.command(&self.config)?;
+                Some(self.run_check(check, command))
+            })
+            .collect()
+    }
+
+    fn run_check(&self, check: BackpressureCheck, command: &str) -> BackpressureCheckResult {
+        debug!(
+            check = %check,
+            command,
+            workspace = %self.workspace_root.display(),
+            "Running orchestrator backpressure check"
+        );
+
+        let request = HookRunRequest {
+            phase_event: BACKPRESSURE_PHASE.to_string(),
+            hook_name: check.as_str().to_string(),
+            command: vec!["bash".to_string(), "-c".to_string(), command.to_string()],
+            workspace_root: self.workspace_root.clone(),
+            cwd: None,
+            env: HashMap::new(),
+            timeout_seconds: self.config.timeout_seconds,
+            max_output_bytes: self.config.max_output_bytes,
+            stdin_payload: serde_json::Value::Null,
+        };
+
+        match self.executor.run(request) {
+            Ok(run) => BackpressureCheckResult {
+                check,
+                command: command.to_string(),
+                passed: !run.timed_out && run.exit_code == Some(0),
+                exit_code: run.exit_code,
+                timed_out: run.timed_out,
+                duration_ms: run.duration_ms,
+                stdout: run.stdout,
+                stderr: run.stderr,
+                error: None,
+            },
+            Err(e) => {
+                warn!(check = %check, error = %e, "Backpressure check could not be executed");
+                BackpressureCheckResult {
+                    check,
+                    command: command.to_string(),
+                    passed: false,
+                    exit_code: None,
+                    timed_out: false,
+                    duration_ms: 0,
+                    stdout: HookStreamOutput::default(),
+                    stderr: HookStreamOutput::default(),
+                    error: Some(e.to_string()),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::BackpressureCommands;
+    use tempfile::TempDir;
+
+    fn config(commands: BackpressureCommands) -> BackpressureConfig {
+        BackpressureConfig {
+            enabled: true,
+            timeout_seconds: 5,
+            commands,
+            ..BackpressureConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_runner_disabled_runs_nothing() {
+        let temp = TempDir::new().unwrap();
+        let mut cfg = config(BackpressureCommands {
+            tests: Some("false".to_string()),
+            ..BackpressureCommands::default()
+        });
+        cfg.enabled = false;
+
+        let runner = BackpressureRunner::new(cfg, temp.path());
+        assert!(runner.run().is_empty());
+    }
+
+    #[test]
+    fn test_runner_captures_pass_and_fail_in_workspace() {
+        let temp = TempDir::new().unwrap();
+        std::fs::write(temp.path().join("marker.txt"), "present").unwrap();
+
+        let runner = BackpressureRunner::new(
+            config(BackpressureCommands {
+                tests: Some("cat marker.txt".to_string()),
+                lint: Some("echo 'warning: unused variable' >&2; exit 3".to_string()),
+                ..BackpressureCommands::default()
+            }),
+            temp.path(),
+        );
+
+        let results = runner.run();
+        assert_eq!(results.len(), 2);
+
+        assert_eq!(results[0].check, BackpressureCheck::Tests);
+        assert!(results[0].passed);
+        assert_eq!(results[0].stdout.content, "present");
+
+        assert_eq!(results[1].check, BackpressureCheck::Lint);
+        assert!(!results[1].passed);
+        assert_eq!(results[1].exit_code, Some(3));
+        assert!(results[1].output_tail(5).contains("unused variable"));
+    }
+
+    #[test]
+    fn test_runner_times_out_long_commands() {
+        let temp = TempDir::new().unwrap();
+        let mut cfg = config(BackpressureCommands {
+            tests: Some("sleep 5".to_string()),
+            ..BackpressureCommands::default()
+        });
+        cfg.timeout_seconds = 1;
+
+        let results = BackpressureRunner::new(cfg, temp.path()).run();
+        assert!(results[0].timed_out);
+        assert!(!results[0].passed);
+        assert!(results[0].summary().contains("timed out"));
+    }
+
+    #[test]
+    fn test_verification_flags_claim_mismatches() {
+        let result = |check, passed| BackpressureCheckResult {
+            check,
+            command: "cmd".to_string(),
+            passed,
+            exit_code: Some(i32::from(!passed)),
+            timed_out: false,
+            duration_ms: 10,
+            stdout: HookStreamOutput::default(),
+            stderr: HookStreamOutput::default(),
+            error: None,
+        };
+        let claims = HashMap::from([
+            (BackpressureCheck::Tests, true),
+            (BackpressureCheck::Lint, true),
+        ]);
+
+        let verification = BackpressureVerification::from_results(
+            vec![
+                result(BackpressureCheck::Tests, false),
+                result(BackpressureCheck::Lint, true),
+                result(BackpressureCheck::Audit, false),
+            ],
+            &claims,
+        );
+
+        assert_eq!(
+            verification.mismatches,
+            vec![BackpressureMismatch {
+                check: BackpressureCheck::Tests,
+                claimed: true,
+                actual: false,
+            }]
+        );
+        assert!(!verification.all_passed());
+        assert_eq!(
+            verification.failed_checks(),
+            vec![BackpressureCheck::Tests, BackpressureCheck::Audit]
+        );
+        let report = verification.failure_report();
+        assert!(report.contains("tests: claimed pass, verified fail"));
+        assert!(report.contains("audit: fail (exit 1"));
+    }
+}
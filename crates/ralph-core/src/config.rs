@@ -3,7 +3,7 @@
 //! This module supports both v1.x flat configuration format and v2.0 nested format.
 //! Users can switch from Python v1.x to Rust v2.0 with zero config changes.
 
-use ralph_proto::Topic;
+use ralph_proto::{PayloadCondition, Topic};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
@@ -137,6 +137,18 @@ pub struct RalphConfig {
     /// RObot (Ralph-Orchestrator bot) configuration for Telegram-based interaction.
     #[serde(default, rename = "RObot")]
     pub robot: RobotConfig,
+
+    /// Orchestrator-run backpressure commands that verify `build.done` claims.
+    #[serde(default)]
+    pub backpressure: BackpressureConfig,
+
+    /// Quality gate thresholds and policies for `build.done` and `verify.passed`.
+    #[serde(default)]
+    pub quality: QualityConfig,
+
+    /// Merge queue executor settings for completed worktree loops.
+    #[serde(default)]
+    pub merge: MergeConfig,
 }
 
 fn default_true() -> bool {
@@ -184,6 +196,12 @@ impl Default for RalphConfig {
             features: FeaturesConfig::default(),
             // RObot (Ralph-Orchestrator bot)
             robot: RobotConfig::default(),
+            // Orchestrator-verified backpressure
+            backpressure: BackpressureConfig::default(),
+            // Quality gates (built-in thresholds)
+            quality: QualityConfig::default(),
+            // Merge queue executor
+            merge: MergeConfig::default(),
         }
     }
 }
@@ -456,6 +474,15 @@ impl RalphConfig {
         // Validate hooks config semantics (v1 guardrails)
         self.validate_hooks()?;
 
+        // Validate orchestrator-verified backpressure commands
+        self.validate_backpressure(&mut warnings)?;
+        Self::validate_quality("quality", &self.quality)?;
+        for (hat_id, hat_config) in &self.hats {
+            if let Some(quality) = &hat_config.quality {
+                Self::validate_quality(&format!("hats.{hat_id}.quality"), quality)?;
+            }
+        }
+
         // Check for required description field on all hats
         for (hat_id, hat_config) in &self.hats {
             if hat_config
@@ -469,6 +496,27 @@ impl RalphConfig {
             }
         }
 
+        for (hat_id, hat_config) in &self.hats {
+            if let Some(retry) = &hat_config.retry
+                && let Some(RetryEscalation::Hat { hat: target }) = &retry.escalate_to
+            {
+                if target == hat_id || !self.hats.contains_key(target) {
+                    return Err(ConfigError::HatRetryValidation {
+                        hat: hat_id.clone(),
+                        message: format!(
+                            "escalate_to hat '{target}' must be a different, configured hat"
+                        ),
+                    });
+                }
+                if self.hats[target].triggers.is_empty() {
+                    return Err(ConfigError::HatRetryValidation {
+                        hat: hat_id.clone(),
+                        message: format!("escalate_to hat '{target}' has no triggers"),
+                    });
+                }
+            }
+        }
+
         // Check for reserved triggers: task.start and task.resume are reserved for Ralph
         // Per design: Ralph coordinates first, then delegates to custom hats via events
         const RESERVED_TRIGGERS: &[&str] = &["task.start", "task.resume"];
@@ -483,13 +531,49 @@ impl RalphConfig {
             }
         }
 
+        for (hat_id, hat_config) in &self.hats {
+            if let Some(subloop) = &hat_config.subloop {
+                Self::validate_subloop(hat_id, hat_config, subloop)?;
+            }
+            if let Some(fan_out) = &hat_config.fan_out {
+                self.validate_fan_out(hat_id, hat_config, fan_out)?;
+            }
+        }
+
+        for (hat_id, hat_config) in &self.hats {
+            for (trigger, expression) in &hat_config.trigger_conditions {
+                if !hat_config.triggers.contains(trigger) {
+                    return Err(ConfigError::TriggerCondition {
+                        hat: hat_id.clone(),
+                        trigger: trigger.clone(),
+                        message: "not listed in the hat's triggers".to_string(),
+                    });
+                }
+                if let Err(e) = PayloadCondition::parse(expression) {
+                    return Err(ConfigError::TriggerCondition {
+                        hat: hat_id.clone(),
+                        trigger: trigger.clone(),
+                        message: e.to_string(),
+                    });
+                }
+            }
+        }
+
         // Check for ambiguous routing: each trigger topic must map to exactly one hat
         // Per spec: "Every trigger maps to exactly one hat | No ambiguous routing"
+        // Hats that guard a shared trigger with payload conditions are exempt.
         if !self.hats.is_empty() {
             let mut trigger_to_hat: HashMap<&str, &str> = HashMap::new();
             for (hat_id, hat_config) in &self.hats {
                 for trigger in &hat_config.triggers {
                     if let Some(existing_hat) = trigger_to_hat.get(trigger.as_str()) {
+                        let both_conditional = hat_config.trigger_conditions.contains_key(trigger)
+                            && self.hats[*existing_hat]
+                                .trigger_conditions
+                                .contains_key(trigger);
+                        if both_conditional {
+                            continue;
+                        }
                         return Err(ConfigError::AmbiguousRouting {
                             trigger: trigger.clone(),
                             hat1: (*existing_hat).to_string(),
@@ -504,6 +588,94 @@ impl RalphConfig {
         Ok(warnings)
     }
 
+    fn validate_subloop(
+        hat_id: &str,
+        hat_config: &HatConfig,
+        subloop: &SubloopConfig,
+    ) -> Result<(), ConfigError> {
+        let invalid = |message: String| ConfigError::SubloopValidation {
+            hat: hat_id.to_string(),
+            message,
+        };
+
+        if subloop.preset.trim().is_empty() {
+            return Err(invalid("preset must not be empty".to_string()));
+        }
+        if subloop.max_iterations == Some(0) {
+            return Err(invalid("max_iterations must be greater than 0".to_string()));
+        }
+        if subloop
+            .max_cost_usd
+            .is_some_and(|cost| !cost.is_finite() || cost <= 0.0)
+        {
+            return Err(invalid(
+                "max_cost_usd must be a positive number".to_string(),
+            ));
+        }
+        for topic in [Some(&subloop.on_complete), subloop.on_failure.as_ref()]
+            .into_iter()
+            .flatten()
+        {
+            if !hat_config.publishes.contains(topic) {
+                return Err(invalid(format!(
+                    "'{topic}' must be listed in the hat's publishes"
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_fan_out(
+        &self,
+        hat_id: &str,
+        hat_config: &HatConfig,
+        fan_out: &FanOutConfig,
+    ) -> Result<(), ConfigError> {
+        let invalid = |message: String| ConfigError::FanOutValidation {
+            hat: hat_id.to_string(),
+            message,
+        };
+
+        if hat_config.subloop.is_some() {
+            return Err(invalid(
+                "a hat cannot have both fan_out and subloop".to_string(),
+            ));
+        }
+        if fan_out.hats.is_empty() {
+            return Err(invalid("hats must list at least one branch".to_string()));
+        }
+        for (index, branch) in fan_out.hats.iter().enumerate() {
+            if fan_out.hats[..index].contains(branch) {
+                return Err(invalid(format!("branch '{branch}' is listed twice")));
+            }
+            if branch == hat_id {
+                return Err(invalid("a hat cannot fan out to itself".to_string()));
+            }
+            let Some(branch_config) = self.hats.get(branch) else {
+                return Err(invalid(format!(
+                    "branch '{branch}' is not a configured hat"
+                )));
+            };
+            if branch_config.fan_out.is_some() || branch_config.subloop.is_some() {
+                return Err(invalid(format!(
+                    "branch '{branch}' must not itself use fan_out or subloop"
+                )));
+            }
+        }
+        if fan_out.timeout_seconds == Some(0) {
+            return Err(invalid(
+                "timeout_seconds must be greater than 0".to_string(),
+            ));
+        }
+        if !hat_config.publishes.contains(&fan_out.join) {
+            return Err(invalid(format!(
+                "'{}' must be listed in the hat's publishes",
+                fan_out.join
+            )));
+        }
+        Ok(())
+    }
+
     fn validate_hooks(&self) -> Result<(), ConfigError> {
         Self::validate_non_v1_hook_fields("hooks", &self.hooks.extra)?;
 
@@ -576,6 +748,20 @@ impl RalphConfig {
                     });
                 }
 
+                if !hook.topics.is_empty() && *phase_event != HookPhaseEvent::PreEventPublish {
+                    return Err(ConfigError::HookValidation {
+                        field: format!("{hook_field_base}.topics"),
+                        message: "is only supported on pre.event.publish hooks".to_string(),
+                    });
+                }
+
+                if hook.topics.iter().any(|topic| topic.trim().is_empty()) {
+                    return Err(ConfigError::HookValidation {
+                        field: format!("{hook_field_base}.topics"),
+                        message: "must not contain empty topic patterns".to_string(),
+                    });
+                }
+
                 Self::validate_non_v1_hook_fields(&hook_field_base, &hook.extra)?;
                 Self::validate_mutation_contract(&hook_field_base, &hook.mutate)?;
             }
@@ -584,6 +770,88 @@ impl RalphConfig {
         Ok(())
     }
 
+    fn validate_backpressure(&self, warnings: &mut Vec<ConfigWarning>) -> Result<(), ConfigError> {
+        let backpressure = &self.backpressure;
+        if !backpressure.enabled {
+            return Ok(());
+        }
+
+        if backpressure.timeout_seconds == 0 {
+            return Err(ConfigError::BackpressureValidation {
+                field: "backpressure.timeout_seconds".to_string(),
+                message: "must be greater than 0".to_string(),
+            });
+        }
+
+        if backpressure.max_output_bytes == 0 {
+            return Err(ConfigError::BackpressureValidation {
+                field: "backpressure.max_output_bytes".to_string(),
+                message: "must be greater than 0".to_string(),
+            });
+        }
+
+        let commands = &backpressure.commands;
+        for (name, command) in [
+            ("tests", &commands.tests),
+            ("lint", &commands.lint),
+            ("typecheck", &commands.typecheck),
+            ("audit", &commands.audit),
+            ("coverage", &commands.coverage),
+        ] {
+            if command.as_deref().is_some_and(|c| c.trim().is_empty()) {
+                return Err(ConfigError::BackpressureValidation {
+                    field: format!("backpressure.commands.{name}"),
+                    message: "must be a non-empty shell command when specified".to_string(),
+                });
+            }
+        }
+
+        if commands.is_empty() {
+            warnings.push(ConfigWarning::InvalidValue {
+                field: "backpressure.commands".to_string(),
+                message: "backpressure is enabled but no commands are configured; evidence stays self-reported".to_string(),
+            });
+        }
+
+        Ok(())
+    }
+
+    fn validate_quality(path_prefix: &str, quality: &QualityConfig) -> Result<(), ConfigError> {
+        for dimension in QualityDimension::ALL {
+            let Some(threshold) = quality.gate(dimension).threshold else {
+                continue;
+            };
+            let field = format!("{path_prefix}.{dimension}.threshold");
+
+            let valid = match dimension {
+                QualityDimension::Coverage | QualityDimension::Mutation => {
+                    (0.0..=100.0).contains(&threshold)
+                }
+                QualityDimension::Complexity => threshold.is_finite() && threshold >= 0.0,
+                _ => {
+                    return Err(ConfigError::QualityValidation {
+                        field,
+                        message: format!(
+                            "'{dimension}' is pass/fail and does not accept a threshold; only coverage, mutation and complexity do"
+                        ),
+                    });
+                }
+            };
+
+            if !valid {
+                return Err(ConfigError::QualityValidation {
+                    field,
+                    message: match dimension {
+                        QualityDimension::Complexity => "must be a non-negative number".to_string(),
+                        _ => "must be a percentage between 0 and 100".to_string(),
+                    },
+                });
+            }
+        }
+
+        Ok(())
+    }
+
     fn validate_non_v1_hook_fields(
         path_prefix: &str,
         fields: &HashMap<String, serde_yaml::Value>,
@@ -642,7 +910,7 @@ impl RalphConfig {
             let field = format!("{mutate_field_base}.{key}");
             let reason = match key.as_str() {
                 "prompt" | "prompt_mutation" | "events" | "event" | "config" | "full_context" => {
-                    "v1 allows metadata-only mutation; prompt/event/config mutation is unsupported"
+                    "mutation actions are returned on hook stdout (metadata, reject, rewrite, publish, guidance), not configured under mutate; config mutation is unsupported"
                         .to_string()
                 }
                 "xml" => "v1 mutation payloads are JSON-only".to_string(),
@@ -955,6 +1223,19 @@ pub struct CliConfig {
     /// If None, defaults to "-p" for arg mode.
     #[serde(default)]
     pub prompt_flag: Option<String>,
+
+    /// Backends to fail over to, in order, when an iteration fails because the
+    /// backend is rate limited, unauthenticated, crashed, or timed out.
+    ///
+    /// The same prompt is retried on the next backend. Entries accept the same
+    /// forms as a hat's `backend:` field. Hats can override this with `failover:`.
+    /// ```yaml
+    /// cli:
+    ///   backend: claude
+    ///   failover: [gemini, { type: codex, args: ["--model", "o3"] }]
+    /// ```
+    #[serde(default)]
+    pub failover: Vec<HatBackend>,
 }
 
 fn default_backend() -> String {
@@ -983,6 +1264,7 @@ impl Default for CliConfig {
             idle_timeout_secs: default_idle_timeout(),
             args: Vec::new(),
             prompt_flag: None,
+            failover: Vec::new(),
         }
     }
 }
@@ -1056,6 +1338,13 @@ pub struct MemoriesConfig {
     /// Filter configuration for memory injection.
     #[serde(default)]
     pub filter: MemoriesFilter,
+
+    /// Whether to compact memories when a loop terminates.
+    ///
+    /// Merges near-duplicates and expires memories about deleted files,
+    /// as `ralph tools memory compact` does.
+    #[serde(default)]
+    pub compact_on_exit: bool,
 }
 
 impl Default for MemoriesConfig {
@@ -1065,6 +1354,7 @@ impl Default for MemoriesConfig {
             inject: InjectMode::Auto,
             budget: 0,
             filter: MemoriesFilter::default(),
+            compact_on_exit: false,
         }
     }
 }
@@ -1215,6 +1505,16 @@ pub enum HookPhaseEvent {
     PreLoopError,
     #[serde(rename = "post.loop.error")]
     PostLoopError,
+    #[serde(rename = "pre.hat.activate")]
+    PreHatActivate,
+    #[serde(rename = "post.hat.complete")]
+    PostHatComplete,
+    #[serde(rename = "pre.event.publish")]
+    PreEventPublish,
+    #[serde(rename = "pre.merge")]
+    PreMerge,
+    #[serde(rename = "post.merge")]
+    PostMerge,
 }
 
 impl HookPhaseEvent {
@@ -1233,6 +1533,11 @@ impl HookPhaseEvent {
             Self::PostLoopComplete => "post.loop.complete",
             Self::PreLoopError => "pre.loop.error",
             Self::PostLoopError => "post.loop.error",
+            Self::PreHatActivate => "pre.hat.activate",
+            Self::PostHatComplete => "post.hat.complete",
+            Self::PreEventPublish => "pre.event.publish",
+            Self::PreMerge => "pre.merge",
+            Self::PostMerge => "post.merge",
         }
     }
 
@@ -1251,6 +1556,11 @@ impl HookPhaseEvent {
             "post.loop.complete" => Some(Self::PostLoopComplete),
             "pre.loop.error" => Some(Self::PreLoopError),
             "post.loop.error" => Some(Self::PostLoopError),
+            "pre.hat.activate" => Some(Self::PreHatActivate),
+            "post.hat.complete" => Some(Self::PostHatComplete),
+            "pre.event.publish" => Some(Self::PreEventPublish),
+            "pre.merge" => Some(Self::PreMerge),
+            "post.merge" => Some(Self::PostMerge),
             _ => None,
         }
     }
@@ -1376,6 +1686,13 @@ pub struct HookSpec {
     #[serde(default)]
     pub mutate: HookMutationConfig,
 
+    /// Event topic patterns this hook runs for (`pre.event.publish` only).
+    ///
+    /// Supports the same `*` segment wildcards as hat triggers. Empty runs the
+    /// hook for every published event.
+    #[serde(default)]
+    pub topics: Vec<String>,
+
     /// Unknown keys captured for v1 guardrails.
     #[serde(default, flatten)]
     pub extra: HashMap<String, serde_yaml::Value>,
@@ -1478,6 +1795,9 @@ pub struct PreflightConfig {
 /// ```yaml
 /// features:
 ///   parallel: true  # Enable parallel loops via git worktrees
+///   max_parallel_loops: 4  # Queue worktree loops beyond this (0 = unlimited)
+///   backend_concurrency:
+///     claude: 2  # At most two worktree loops on claude at once
 ///   auto_merge: false  # Auto-merge worktree branches on completion
 ///   preflight:
 ///     enabled: false      # Opt-in: run preflight checks before `ralph run`
@@ -1496,6 +1816,20 @@ pub struct FeaturesConfig {
     #[serde(default = "default_true")]
     pub parallel: bool,
 
+    /// Maximum number of worktree loops running at once (0 = unlimited).
+    ///
+    /// Loops started while the limit is reached wait as `queued` in the loop
+    /// registry and start in priority, then FIFO, order as slots free up.
+    #[serde(default)]
+    pub max_parallel_loops: usize,
+
+    /// Per-backend caps on concurrently running worktree loops.
+    ///
+    /// Keeps parallel loops under provider rate limits. Backends without an
+    /// entry are only bound by `max_parallel_loops`.
+    #[serde(default)]
+    pub backend_concurrency: HashMap<String, usize>,
+
     /// Whether to automatically merge worktree branches on completion.
     ///
     /// When false (default), completed worktree loops queue for manual merge.
@@ -1520,7 +1854,9 @@ pub struct FeaturesConfig {
 impl Default for FeaturesConfig {
     fn default() -> Self {
         Self {
-            parallel: true,    // Parallel loops enabled by default
+            parallel: true,        // Parallel loops enabled by default
+            max_parallel_loops: 0, // No cap on concurrent worktree loops
+            backend_concurrency: HashMap::new(),
             auto_merge: false, // Auto-merge disabled by default for safety
             loop_naming: crate::loop_name::LoopNamingConfig::default(),
             preflight: PreflightConfig::default(),
@@ -1612,8 +1948,395 @@ pub struct EventMetadata {
     pub on_publish: String,
 }
 
-/// Backend configuration for a hat.
+/// Orchestrator-verified backpressure configuration.
+///
+/// When enabled, the orchestrator runs the declared commands in the workspace
+/// before accepting `build.done` (and `verify.passed`), instead of trusting the
+/// evidence the agent wrote into the event payload. Actual results override
+/// the agent's claims, and any disagreement is flagged as a mismatch.
+///
+/// Example configuration:
+/// ```yaml
+/// backpressure:
+///   enabled: true
+///   timeout_seconds: 600
+///   commands:
+///     tests: "cargo test --workspace"
+///     lint: "cargo clippy --workspace -- -D warnings"
+///     typecheck: "cargo check --workspace"
+///     audit: "cargo audit"
+///     coverage: "cargo llvm-cov --fail-under-lines 80"
+/// ```
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackpressureConfig {
+    /// Whether the orchestrator runs the configured commands itself.
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Maximum execution time per command in seconds.
+    #[serde(default = "default_backpressure_timeout_seconds")]
+    pub timeout_seconds: u64,
+
+    /// Maximum stdout/stderr bytes captured per stream.
+    #[serde(default = "default_backpressure_max_output_bytes")]
+    pub max_output_bytes: u64,
+
+    /// Shell commands per backpressure dimension. Unset dimensions stay self-reported.
+    #[serde(default)]
+    pub commands: BackpressureCommands,
+}
+
+fn default_backpressure_timeout_seconds() -> u64 {
+    600 // 10 minutes
+}
+
+fn default_backpressure_max_output_bytes() -> u64 {
+    16384
+}
+
+impl Default for BackpressureConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            timeout_seconds: default_backpressure_timeout_seconds(),
+            max_output_bytes: default_backpressure_max_output_bytes(),
+            commands: BackpressureCommands::default(),
+        }
+    }
+}
+
+impl BackpressureConfig {
+    /// Returns true when enabled and at least one command is declared.
+    pub fn is_active(&self) -> bool {
+        self.enabled && !self.commands.is_empty()
+    }
+}
+
+/// Shell commands (run via `bash -c`) for each verifiable backpressure dimension.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BackpressureCommands {
+    /// Test suite command (maps to `tests:` evidence).
+    #[serde(default)]
+    pub tests: Option<String>,
+
+    /// Lint command (maps to `lint:` evidence).
+    #[serde(default)]
+    pub lint: Option<String>,
+
+    /// Typecheck command (maps to `typecheck:` evidence).
+    #[serde(default)]
+    pub typecheck: Option<String>,
+
+    /// Dependency/security audit command (maps to `audit:` evidence).
+    #[serde(default)]
+    pub audit: Option<String>,
+
+    /// Coverage gate command (maps to `coverage:` evidence).
+    #[serde(default)]
+    pub coverage: Option<String>,
+}
+
+impl BackpressureCommands {
+    /// Returns true when no command is declared.
+    pub fn is_empty(&self) -> bool {
+        self.tests.is_none()
+            && self.lint.is_none()
+            && self.typecheck.is_none()
+            && self.audit.is_none()
+            && self.coverage.is_none()
+    }
+}
+
+/// How queued worktree loops are merged back into their base branch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MergeExecutorMode {
+    /// Merge with git and verification commands; merge-ralph only resolves conflicts.
+    #[default]
+    Deterministic,
+    /// Hand every queued loop to a merge-ralph loop (`builtin:merge-loop`).
+    Llm,
+}
+
+/// How the loop branch is integrated with its base in the scratch worktree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MergeStrategy {
+    /// Create a `--no-ff` merge commit (same history shape as merge-ralph).
+    #[default]
+    Merge,
+    /// Rebase the loop commits onto the base for a linear history.
+    Rebase,
+}
+
+/// Merge queue executor configuration.
+///
+/// Example configuration:
+/// ```yaml
+/// merge:
+///   executor: deterministic
+///   strategy: rebase
+///   verify:
+///     - cargo test
+///   llm_fallback: true
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MergeConfig {
+    /// Executor used by `ralph loops process` and primary-loop auto-merge.
+    #[serde(default)]
+    pub executor: MergeExecutorMode,
+
+    /// Integration strategy for the deterministic executor.
+    #[serde(default)]
+    pub strategy: MergeStrategy,
+
+    /// Verification commands (run via `bash -c`) gating the merge.
+    /// When empty, the declared `backpressure.commands` are used.
+    #[serde(default)]
+    pub verify: Vec<String>,
+
+    /// Maximum execution time per verification command in seconds.
+    #[serde(default = "default_merge_timeout_seconds")]
+    pub timeout_seconds: u64,
+
+    /// Maximum stdout/stderr bytes captured per verification stream.
+    #[serde(default = "default_backpressure_max_output_bytes")]
+    pub max_output_bytes: u64,
+
+    /// Hand conflicting merges to merge-ralph instead of leaving them for review.
+    #[serde(default = "default_true")]
+    pub llm_fallback: bool,
+}
+
+fn default_merge_timeout_seconds() -> u64 {
+    1800 // 30 minutes
+}
+
+impl Default for MergeConfig {
+    fn default() -> Self {
+        Self {
+            executor: MergeExecutorMode::default(),
+            strategy: MergeStrategy::default(),
+            verify: Vec::new(),
+            timeout_seconds: default_merge_timeout_seconds(),
+            max_output_bytes: default_backpressure_max_output_bytes(),
+            llm_fallback: true,
+        }
+    }
+}
+
+impl MergeConfig {
+    /// Returns the verification commands, falling back to `backpressure.commands`.
+    pub fn verification_commands(&self, backpressure: &BackpressureConfig) -> Vec<String> {
+        if !self.verify.is_empty() {
+            return self.verify.clone();
+        }
+        let commands = &backpressure.commands;
+        [
+            &commands.tests,
+            &commands.lint,
+            &commands.typecheck,
+            &commands.audit,
+            &commands.coverage,
+        ]
+        .into_iter()
+        .flatten()
+        .cloned()
+        .collect()
+    }
+}
+
+/// Quality gate configuration for `build.done` evidence and `verify.passed` reports.
+///
+/// Each dimension can set a threshold (coverage, mutation and complexity only)
+/// and a policy. Unset values keep the built-in defaults: every dimension is
+/// required, except mutation evidence on `build.done`, which is warn-only.
+/// Hats can override individual dimensions via `hats.<id>.quality`.
+///
+/// Example configuration:
+/// ```yaml
+/// quality:
+///   coverage:
+///     threshold: 60
+///     policy: warn
+///   mutation:
+///     policy: ignore
+///   complexity:
+///     threshold: 15
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct QualityConfig {
+    #[serde(default)]
+    pub tests: QualityGateConfig,
+    #[serde(default)]
+    pub lint: QualityGateConfig,
+    #[serde(default)]
+    pub typecheck: QualityGateConfig,
+    #[serde(default)]
+    pub audit: QualityGateConfig,
+    /// Minimum coverage percentage (default 80).
+    #[serde(default)]
+    pub coverage: QualityGateConfig,
+    /// Minimum mutation score percentage (default 70).
+    #[serde(default)]
+    pub mutation: QualityGateConfig,
+    /// Maximum complexity score (default 10).
+    #[serde(default)]
+    pub complexity: QualityGateConfig,
+    #[serde(default)]
+    pub duplication: QualityGateConfig,
+    #[serde(default)]
+    pub performance: QualityGateConfig,
+    #[serde(default)]
+    pub specs: QualityGateConfig,
+}
+
+impl QualityConfig {
+    /// Returns the gate configuration for a dimension.
+    pub fn gate(&self, dimension: QualityDimension) -> &QualityGateConfig {
+        match dimension {
+            QualityDimension::Tests => &self.tests,
+            QualityDimension::Lint => &self.lint,
+            QualityDimension::Typecheck => &self.typecheck,
+            QualityDimension::Audit => &self.audit,
+            QualityDimension::Coverage => &self.coverage,
+            QualityDimension::Mutation => &self.mutation,
+            QualityDimension::Complexity => &self.complexity,
+            QualityDimension::Duplication => &self.duplication,
+            QualityDimension::Performance => &self.performance,
+            QualityDimension::Specs => &self.specs,
+        }
+    }
+
+    fn gate_mut(&mut self, dimension: QualityDimension) -> &mut QualityGateConfig {
+        match dimension {
+            QualityDimension::Tests => &mut self.tests,
+            QualityDimension::Lint => &mut self.lint,
+            QualityDimension::Typecheck => &mut self.typecheck,
+            QualityDimension::Audit => &mut self.audit,
+            QualityDimension::Coverage => &mut self.coverage,
+            QualityDimension::Mutation => &mut self.mutation,
+            QualityDimension::Complexity => &mut self.complexity,
+            QualityDimension::Duplication => &mut self.duplication,
+            QualityDimension::Performance => &mut self.performance,
+            QualityDimension::Specs => &mut self.specs,
+        }
+    }
+
+    /// Returns a copy with every value set in `overrides` taking precedence.
+    #[must_use]
+    pub fn merged_with(&self, overrides: &QualityConfig) -> QualityConfig {
+        let mut merged = self.clone();
+        for dimension in QualityDimension::ALL {
+            let override_gate = overrides.gate(dimension);
+            let gate = merged.gate_mut(dimension);
+            if override_gate.policy.is_some() {
+                gate.policy = override_gate.policy;
+            }
+            if override_gate.threshold.is_some() {
+                gate.threshold = override_gate.threshold;
+            }
+        }
+        merged
+    }
+}
+
+/// Threshold and policy for a single quality dimension.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct QualityGateConfig {
+    /// How a failing dimension is handled (built-in default when unset).
+    #[serde(default)]
+    pub policy: Option<QualityPolicy>,
+
+    /// Numeric threshold (coverage/mutation percentage, maximum complexity).
+    #[serde(default)]
+    pub threshold: Option<f64>,
+}
+
+/// How a failing quality dimension affects the gate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QualityPolicy {
+    /// Failure rejects the event.
+    Required,
+    /// Failure is logged and reported but does not reject the event.
+    #[serde(alias = "warn_only")]
+    Warn,
+    /// The dimension is not evaluated.
+    Ignore,
+}
+
+impl QualityPolicy {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Required => "required",
+            Self::Warn => "warn",
+            Self::Ignore => "ignore",
+        }
+    }
+}
+
+impl std::fmt::Display for QualityPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A quality dimension evaluated by backpressure gates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QualityDimension {
+    Tests,
+    Lint,
+    Typecheck,
+    Audit,
+    Coverage,
+    Mutation,
+    Complexity,
+    Duplication,
+    Performance,
+    Specs,
+}
+
+impl QualityDimension {
+    /// All dimensions, in reporting order.
+    pub const ALL: [Self; 10] = [
+        Self::Tests,
+        Self::Lint,
+        Self::Typecheck,
+        Self::Audit,
+        Self::Coverage,
+        Self::Mutation,
+        Self::Complexity,
+        Self::Duplication,
+        Self::Performance,
+        Self::Specs,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Tests => "tests",
+            Self::Lint => "lint",
+            Self::Typecheck => "typecheck",
+            Self::Audit => "audit",
+            Self::Coverage => "coverage",
+            Self::Mutation => "mutation",
+            Self::Complexity => "complexity",
+            Self::Duplication => "duplication",
+            Self::Performance => "performance",
+            Self::Specs => "specs",
+        }
+    }
+}
+
+impl std::fmt::Display for QualityDimension {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Backend configuration for a hat.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum HatBackend {
     // Order matters for serde untagged - most specific first
@@ -1669,6 +2392,22 @@ pub struct HatConfig {
     #[serde(default)]
     pub triggers: Vec<String>,
 
+    /// Payload predicates keyed by trigger pattern.
+    ///
+    /// A trigger with a condition only activates the hat when the event payload
+    /// (JSON or `key: value` lines) satisfies it; otherwise the event falls
+    /// through to the next subscriber or Ralph. Several hats may share a trigger
+    /// as long as each of them guards it with a condition:
+    /// ```yaml
+    /// hats:
+    ///   fixer:
+    ///     triggers: ["review.done"]
+    ///     trigger_conditions:
+    ///       review.done: "$.verdict == reject"
+    /// ```
+    #[serde(default)]
+    pub trigger_conditions: HashMap<String, String>,
+
     /// Topics this hat publishes.
     #[serde(default)]
     pub publishes: Vec<String>,
@@ -1700,6 +2439,10 @@ pub struct HatConfig {
     #[serde(default)]
     pub backend: Option<HatBackend>,
 
+    /// Failover chain for this hat, replacing `cli.failover` (an empty list disables failover).
+    #[serde(default)]
+    pub failover: Option<Vec<HatBackend>>,
+
     /// Custom args to append to the backend CLI when this hat is active.
     ///
     /// Accepts both `backend_args:` and shorthand `args:`.
@@ -1716,6 +2459,19 @@ pub struct HatConfig {
     /// instead of activating the hat again.
     pub max_activations: Option<u32>,
 
+    /// Maximum cost in USD this hat may spend in a single loop run.
+    ///
+    /// When the hat's running total reaches the cap, the orchestrator publishes
+    /// `<hat_id>.budget_exhausted` instead of activating the hat again.
+    #[serde(default)]
+    pub max_cost_usd: Option<f64>,
+
+    /// Maximum input + output tokens this hat may consume in a single loop run.
+    ///
+    /// Enforced like `max_cost_usd`, publishing `<hat_id>.budget_exhausted`.
+    #[serde(default)]
+    pub max_tokens: Option<u64>,
+
     /// Tools the hat is not allowed to use.
     ///
     /// Injected as a TOOL RESTRICTIONS section in the prompt (soft enforcement).
@@ -1723,6 +2479,26 @@ pub struct HatConfig {
     /// `Edit` or `Write` are disallowed (hard enforcement via scope_violation event).
     #[serde(default)]
     pub disallowed_tools: Vec<String>,
+
+    /// Quality gate overrides applied when this hat emits `build.done` or `verify.passed`.
+    ///
+    /// Only the dimensions set here override the top-level `quality:` section.
+    #[serde(default)]
+    pub quality: Option<QualityConfig>,
+
+    /// Retry policy for failed iterations of this hat.
+    ///
+    /// Without one, failures only count toward `max_consecutive_failures`.
+    #[serde(default)]
+    pub retry: Option<HatRetryConfig>,
+
+    /// Runs a nested Ralph loop instead of a single backend invocation.
+    #[serde(default)]
+    pub subloop: Option<SubloopConfig>,
+
+    /// Runs other hats concurrently instead of a single backend invocation.
+    #[serde(default)]
+    pub fan_out: Option<FanOutConfig>,
 }
 
 impl HatConfig {
@@ -1737,12 +2513,189 @@ impl HatConfig {
     }
 }
 
+/// Per-hat retry policy.
+///
+/// A failed iteration re-delivers the hat's triggering events (with a
+/// "previous attempt failed" section in the prompt) up to `max_retries` times.
+/// Once retries are exhausted the orchestrator publishes `<hat_id>.failed`
+/// instead of counting the failure toward `max_consecutive_failures`.
+///
+/// Example configuration:
+/// ```yaml
+/// hats:
+///   builder:
+///     retry:
+///       max_retries: 3
+///       backoff_seconds: 10
+///       escalate_after: 2
+///       escalate_to: { backend: claude }   # or { hat: senior_builder }
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HatRetryConfig {
+    /// Retries after the first failed attempt before the hat is declared failed.
+    #[serde(default)]
+    pub max_retries: u32,
+
+    /// Delay before the first retry, doubled for each further retry.
+    #[serde(default)]
+    pub backoff_seconds: u64,
+
+    /// Upper bound for the exponential backoff delay.
+    #[serde(default)]
+    pub max_backoff_seconds: Option<u64>,
+
+    /// Failed attempts after which `escalate_to` takes over (defaults to 1).
+    #[serde(default)]
+    pub escalate_after: Option<u32>,
+
+    /// Hat or backend that handles the work once `escalate_after` is reached.
+    #[serde(default)]
+    pub escalate_to: Option<RetryEscalation>,
+}
+
+impl HatRetryConfig {
+    /// Returns the delay before retry number `retry` (1-indexed).
+    pub fn backoff(&self, retry: u32) -> std::time::Duration {
+        let factor = 2u64.saturating_pow(retry.saturating_sub(1));
+        let mut seconds = self.backoff_seconds.saturating_mul(factor);
+        if let Some(max) = self.max_backoff_seconds {
+            seconds = seconds.min(max);
+        }
+        std::time::Duration::from_secs(seconds)
+    }
+
+    /// Failed attempts after which the escalation target takes over.
+    pub fn escalate_after(&self) -> u32 {
+        self.escalate_after.unwrap_or(1).max(1)
+    }
+}
+
+/// Nested loop delegated to by a hat.
+///
+/// When the hat activates, the orchestrator runs a child loop with its own hat
+/// collection and limits, then publishes `on_complete` (or `on_failure`) in the
+/// parent with the child's loop ID, termination reason, iterations and cost as
+/// a JSON payload. Child cost counts toward the parent's budget.
+///
+/// Example configuration:
+/// ```yaml
+/// hats:
+///   migrator:
+///     publishes: ["migration.done", "migration.failed"]
+///     subloop:
+///       preset: builtin:code-assist
+///       max_iterations: 30
+///       max_cost_usd: 5.0
+///       on_complete: migration.done
+///       on_failure: migration.failed
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubloopConfig {
+    /// Hat collection for the child loop, in `-H` syntax
+    /// (`builtin:<name>`, a file path, or a URL).
+    pub preset: String,
+
+    /// Iteration limit of the child loop (inherits `event_loop.max_iterations`).
+    #[serde(default)]
+    pub max_iterations: Option<u32>,
+
+    /// Cost cap of the child loop in USD.
+    #[serde(default)]
+    pub max_cost_usd: Option<f64>,
+
+    /// Event published in the parent when the child loop completes.
+    pub on_complete: String,
+
+    /// Event published when the child loop stops for any other reason
+    /// (defaults to `on_complete`).
+    #[serde(default)]
+    pub on_failure: Option<String>,
+}
+
+impl SubloopConfig {
+    /// Returns the parent event topic for a child loop outcome.
+    pub fn result_topic(&self, completed: bool) -> &str {
+        if completed {
+            &self.on_complete
+        } else {
+            self.on_failure.as_deref().unwrap_or(&self.on_complete)
+        }
+    }
+}
+
+/// Concurrent branches run by a hat, joined into one event.
+///
+/// When the hat activates, every branch hat runs at the same time on the
+/// triggering events. Once all branches finish (or `timeout_seconds` expires)
+/// the orchestrator publishes `join` with each branch's events, status and
+/// usage as a JSON payload.
+///
+/// Example configuration:
+/// ```yaml
+/// hats:
+///   review_fan_out:
+///     triggers: ["build.done"]
+///     publishes: ["review.joined"]
+///     fan_out:
+///       hats: [security_reviewer, performance_reviewer, api_reviewer]
+///       join: review.joined
+///       timeout_seconds: 900
+///       isolation: shared
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FanOutConfig {
+    /// Hats run as concurrent branches.
+    pub hats: Vec<String>,
+
+    /// Event published once every branch has finished or timed out.
+    pub join: String,
+
+    /// Wall-clock limit per branch; unfinished branches are reported as timed out.
+    #[serde(default)]
+    pub timeout_seconds: Option<u64>,
+
+    /// Where branches run.
+    #[serde(default)]
+    pub isolation: FanOutIsolation,
+}
+
+impl FanOutConfig {
+    /// Returns the branch timeout, if any.
+    pub fn timeout(&self) -> Option<std::time::Duration> {
+        self.timeout_seconds.map(std::time::Duration::from_secs)
+    }
+}
+
+/// Working tree of fan-out branches.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FanOutIsolation {
+    /// Branches share the workspace and are expected to be read-only.
+    #[default]
+    Shared,
+    /// Each branch runs in a throwaway git worktree.
+    Worktree,
+}
+
+/// Escalation target of a [`HatRetryConfig`].
+///
+/// Written as `{ hat: <hat_id> }` or `{ backend: <backend> }`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum RetryEscalation {
+    /// Hand the failed work to another hat (via a targeted event).
+    Hat { hat: String },
+    /// Run the remaining retries on another backend.
+    Backend { backend: HatBackend },
+}
+
 /// RObot (Ralph-Orchestrator bot) configuration.
 ///
 /// Enables bidirectional communication between AI agents and humans
 /// during orchestration loops. When enabled, agents can emit `human.interact`
 /// events to request clarification (blocking the loop), and humans can
-/// send proactive guidance via Telegram.
+/// send proactive guidance via Telegram, or via signed webhooks when
+/// `webhook` is configured.
 ///
 /// Example configuration:
 /// ```yaml
@@ -1771,6 +2724,10 @@ pub struct RobotConfig {
     /// Telegram bot configuration.
     #[serde(default)]
     pub telegram: Option<TelegramBotConfig>,
+
+    /// Webhook configuration. When set, it is used instead of Telegram.
+    #[serde(default)]
+    pub webhook: Option<WebhookRobotConfig>,
 }
 
 impl RobotConfig {
@@ -1787,6 +2744,10 @@ impl RobotConfig {
             });
         }
 
+        if let Some(webhook) = &self.webhook {
+            return webhook.validate(self.resolve_webhook_secret().is_some());
+        }
+
         // Bot token must be available from config, keychain, or env var
         if self.resolve_bot_token().is_none() {
             return Err(ConfigError::RobotMissingField {
@@ -1817,32 +2778,113 @@ impl RobotConfig {
             return env_token.or(config_token);
         }
 
-        env_token
-            // 2. Config file (explicit override)
-            .or(config_token)
-            // 3. OS keychain (best effort)
-            .or_else(|| {
-                std::panic::catch_unwind(|| {
-                    keyring::Entry::new("ralph", "telegram-bot-token")
-                        .ok()
-                        .and_then(|e| e.get_password().ok())
-                })
-                .ok()
-                .flatten()
-            })
-    }
-
-    /// Resolves the custom Telegram API URL from multiple sources.
-    ///
-    /// Resolution order (highest to lowest priority):
-    /// 1. `RALPH_TELEGRAM_API_URL` environment variable
-    /// 2. `RObot.telegram.api_url` in config file
-    pub fn resolve_api_url(&self) -> Option<String> {
-        std::env::var("RALPH_TELEGRAM_API_URL").ok().or_else(|| {
-            self.telegram
-                .as_ref()
-                .and_then(|telegram| telegram.api_url.clone())
-        })
+        env_token
+            // 2. Config file (explicit override)
+            .or(config_token)
+            // 3. OS keychain (best effort)
+            .or_else(|| {
+                std::panic::catch_unwind(|| {
+                    keyring::Entry::new("ralph", "telegram-bot-token")
+                        .ok()
+                        .and_then(|e| e.get_password().ok())
+                })
+                .ok()
+                .flatten()
+            })
+    }
+
+    /// Resolves the custom Telegram API URL from multiple sources.
+    ///
+    /// Resolution order (highest to lowest priority):
+    /// 1. `RALPH_TELEGRAM_API_URL` environment variable
+    /// 2. `RObot.telegram.api_url` in config file
+    pub fn resolve_api_url(&self) -> Option<String> {
+        std::env::var("RALPH_TELEGRAM_API_URL").ok().or_else(|| {
+            self.telegram
+                .as_ref()
+                .and_then(|telegram| telegram.api_url.clone())
+        })
+    }
+
+    /// Resolves the webhook signing secret.
+    ///
+    /// Resolution order (highest to lowest priority):
+    /// 1. `RALPH_WEBHOOK_SECRET` environment variable
+    /// 2. `RObot.webhook.secret` in config file
+    pub fn resolve_webhook_secret(&self) -> Option<String> {
+        std::env::var("RALPH_WEBHOOK_SECRET")
+            .ok()
+            .or_else(|| {
+                self.webhook
+                    .as_ref()
+                    .and_then(|webhook| webhook.secret.clone())
+            })
+            .filter(|secret| !secret.trim().is_empty())
+    }
+}
+
+/// Webhook robot configuration.
+///
+/// Questions, check-ins, and loop completion/failure notices are POSTed as
+/// JSON to every URL, signed with HMAC-SHA256 over the request body.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookRobotConfig {
+    /// Endpoints that receive every notification.
+    pub urls: Vec<String>,
+
+    /// Shared HMAC secret. Optional if `RALPH_WEBHOOK_SECRET` env var is set.
+    pub secret: Option<String>,
+
+    /// Delivery attempts per message before it is spooled to disk.
+    #[serde(default = "default_webhook_max_attempts")]
+    pub max_attempts: u32,
+
+    /// URL receivers should POST human responses to (typically the
+    /// `ralph-api` `/hooks/human-response` endpoint). Included in question
+    /// payloads as `responseUrl`.
+    pub response_url: Option<String>,
+}
+
+fn default_webhook_max_attempts() -> u32 {
+    3
+}
+
+impl WebhookRobotConfig {
+    fn validate(&self, has_secret: bool) -> Result<(), ConfigError> {
+        if self.urls.is_empty() {
+            return Err(ConfigError::RobotMissingField {
+                field: "RObot.webhook.urls".to_string(),
+                hint: "configure at least one webhook URL".to_string(),
+            });
+        }
+
+        if let Some(url) = self
+            .urls
+            .iter()
+            .find(|url| !(url.starts_with("http://") || url.starts_with("https://")))
+        {
+            return Err(ConfigError::RobotMissingField {
+                field: "RObot.webhook.urls".to_string(),
+                hint: format!("'{url}' must be an http:// or https:// URL"),
+            });
+        }
+
+        if !has_secret {
+            return Err(ConfigError::RobotMissingField {
+                field: "RObot.webhook.secret".to_string(),
+                hint: "Set RALPH_WEBHOOK_SECRET env var or RObot.webhook.secret in config"
+                    .to_string(),
+            });
+        }
+
+        if self.max_attempts == 0 {
+            return Err(ConfigError::RobotMissingField {
+                field: "RObot.webhook.max_attempts".to_string(),
+                hint: "max_attempts must be at least 1".to_string(),
+            });
+        }
+
+        Ok(())
     }
 }
 
@@ -1906,7 +2948,7 @@ pub enum ConfigError {
     RobotMissingField { field: String, hint: String },
 
     #[error(
-        "Invalid hooks phase-event '{phase_event}'. Supported v1 phase-events: pre.loop.start, post.loop.start, pre.iteration.start, post.iteration.start, pre.plan.created, post.plan.created, pre.human.interact, post.human.interact, pre.loop.complete, post.loop.complete, pre.loop.error, post.loop.error.\nFix: use one of the supported keys under hooks.events."
+        "Invalid hooks phase-event '{phase_event}'. Supported v1 phase-events: pre.loop.start, post.loop.start, pre.iteration.start, post.iteration.start, pre.plan.created, post.plan.created, pre.human.interact, post.human.interact, pre.loop.complete, post.loop.complete, pre.loop.error, post.loop.error, pre.hat.activate, post.hat.complete, pre.event.publish, pre.merge, post.merge.\nFix: use one of the supported keys under hooks.events."
     )]
     InvalidHookPhaseEvent { phase_event: String },
 
@@ -1920,6 +2962,38 @@ pub enum ConfigError {
     )]
     UnsupportedHookField { field: String, reason: String },
 
+    #[error(
+        "Backpressure config validation error at '{field}': {message}\nSee: docs/concepts/backpressure.md#orchestrator-verified-gates"
+    )]
+    BackpressureValidation { field: String, message: String },
+
+    #[error(
+        "Quality config validation error at '{field}': {message}\nSee: docs/concepts/backpressure.md#configurable-quality-gates"
+    )]
+    QualityValidation { field: String, message: String },
+
+    #[error("Retry policy of hat '{hat}' is invalid: {message}\nSee: docs/guide/configuration.md")]
+    HatRetryValidation { hat: String, message: String },
+
+    #[error(
+        "Sub-loop of hat '{hat}' is invalid: {message}\nSee: docs/guide/configuration.md#sub-loops"
+    )]
+    SubloopValidation { hat: String, message: String },
+
+    #[error(
+        "Fan-out of hat '{hat}' is invalid: {message}\nSee: docs/guide/configuration.md#fan-out-hats"
+    )]
+    FanOutValidation { hat: String, message: String },
+
+    #[error(
+        "Trigger condition for '{trigger}' on hat '{hat}' is invalid: {message}\nSee: docs/guide/configuration.md#conditional-triggers"
+    )]
+    TriggerCondition {
+        hat: String,
+        trigger: String,
+        message: String,
+    },
+
     #[error(
         "Invalid config key 'project'. Use 'core' instead (e.g. 'core.specs_dir' instead of 'project.specs_dir').\nSee: docs/guide/configuration.md"
     )]
@@ -1930,6 +3004,36 @@ pub enum ConfigError {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_hat_retry_escalation_must_target_configured_hat() {
+        let yaml = r#"
+hats:
+  builder:
+    name: "Builder"
+    description: "Builds"
+    triggers: ["build.task"]
+    publishes: ["build.done"]
+    retry:
+      max_retries: 1
+      escalate_to: { hat: senior }
+"#;
+        let config: RalphConfig = serde_yaml::from_str(yaml).unwrap();
+        let retry = config.hats["builder"].retry.as_ref().unwrap();
+        assert_eq!(
+            retry.escalate_to,
+            Some(RetryEscalation::Hat {
+                hat: "senior".to_string()
+            })
+        );
+        assert_eq!(retry.backoff(3), std::time::Duration::ZERO);
+
+        let err = config.validate().unwrap_err();
+        assert!(
+            matches!(&err, ConfigError::HatRetryValidation { hat, .. } if hat == "builder"),
+            "unexpected error: {err}"
+        );
+    }
+
     #[test]
     fn test_default_config() {
         let config = RalphConfig::default();
@@ -2204,6 +3308,170 @@ hats:
         );
     }
 
+    #[test]
+    fn test_shared_trigger_allowed_when_every_hat_has_condition() {
+        let yaml = r#"
+hats:
+  fixer:
+    name: "Fixer"
+    description: "Fixes rejected work"
+    triggers: ["review.done"]
+    trigger_conditions:
+      review.done: "$.verdict == reject"
+  shipper:
+    name: "Shipper"
+    description: "Ships approved work"
+    triggers: ["review.done"]
+    trigger_conditions:
+      review.done: "$.verdict == approve"
+"#;
+        let config: RalphConfig = serde_yaml::from_str(yaml).unwrap();
+        assert!(config.validate().is_ok());
+
+        let yaml = r#"
+hats:
+  fixer:
+    name: "Fixer"
+    description: "Fixes rejected work"
+    triggers: ["review.done"]
+    trigger_conditions:
+      review.done: "$.verdict == reject"
+  shipper:
+    name: "Shipper"
+    description: "Ships everything"
+    triggers: ["review.done"]
+"#;
+        let config: RalphConfig = serde_yaml::from_str(yaml).unwrap();
+        assert!(matches!(
+            config.validate(),
+            Err(ConfigError::AmbiguousRouting { .. })
+        ));
+    }
+
+    #[test]
+    fn test_invalid_trigger_conditions_rejected() {
+        let yaml = r#"
+hats:
+  fixer:
+    name: "Fixer"
+    description: "Fixes rejected work"
+    triggers: ["review.done"]
+    trigger_conditions:
+      build.done: "$.verdict == reject"
+"#;
+        let config: RalphConfig = serde_yaml::from_str(yaml).unwrap();
+        assert!(matches!(
+            config.validate(),
+            Err(ConfigError::TriggerCondition { trigger, .. }) if trigger == "build.done"
+        ));
+
+        let yaml = r#"
+hats:
+  fixer:
+    name: "Fixer"
+    description: "Fixes rejected work"
+    triggers: ["review.done"]
+    trigger_conditions:
+      review.done: "$.verdict =="
+"#;
+        let config: RalphConfig = serde_yaml::from_str(yaml).unwrap();
+        assert!(matches!(
+            config.validate(),
+            Err(ConfigError::TriggerCondition { trigger, .. }) if trigger == "review.done"
+        ));
+    }
+
+    #[test]
+    fn test_subloop_hat_validation() {
+        let yaml = r#"
+hats:
+  migrator:
+    name: "Migrator"
+    description: "Delegates migrations to a sub-loop"
+    triggers: ["migrate.start"]
+    publishes: ["migrate.done", "migrate.failed"]
+    subloop:
+      preset: builtin:code-assist
+      max_iterations: 20
+      max_cost_usd: 2.5
+      on_complete: migrate.done
+      on_failure: migrate.failed
+"#;
+        let config: RalphConfig = serde_yaml::from_str(yaml).unwrap();
+        assert!(config.validate().is_ok());
+        let subloop = config.hats["migrator"].subloop.as_ref().unwrap();
+        assert_eq!(subloop.result_topic(true), "migrate.done");
+        assert_eq!(subloop.result_topic(false), "migrate.failed");
+
+        let yaml = r#"
+hats:
+  migrator:
+    name: "Migrator"
+    description: "Delegates migrations to a sub-loop"
+    triggers: ["migrate.start"]
+    publishes: ["migrate.done"]
+    subloop:
+      preset: builtin:code-assist
+      on_complete: migrate.done
+      on_failure: migrate.failed
+"#;
+        let config: RalphConfig = serde_yaml::from_str(yaml).unwrap();
+        assert!(matches!(
+            config.validate(),
+            Err(ConfigError::SubloopValidation { message, .. }) if message.contains("migrate.failed")
+        ));
+    }
+
+    #[test]
+    fn test_fan_out_hat_validation() {
+        let yaml = r#"
+hats:
+  review_fan_out:
+    name: "Review Fan-out"
+    description: "Runs reviewers in parallel"
+    triggers: ["build.done"]
+    publishes: ["review.joined"]
+    fan_out:
+      hats: [security, performance]
+      join: review.joined
+      timeout_seconds: 90
+      isolation: worktree
+  security:
+    name: "Security"
+    description: "Reviews security"
+    publishes: ["review.security"]
+  performance:
+    name: "Performance"
+    description: "Reviews performance"
+    publishes: ["review.performance"]
+"#;
+        let config: RalphConfig = serde_yaml::from_str(yaml).unwrap();
+        assert!(config.validate().is_ok());
+        let fan_out = config.hats["review_fan_out"].fan_out.as_ref().unwrap();
+        assert_eq!(fan_out.isolation, FanOutIsolation::Worktree);
+        assert_eq!(fan_out.timeout(), Some(std::time::Duration::from_secs(90)));
+
+        let yaml = r#"
+hats:
+  review_fan_out:
+    name: "Review Fan-out"
+    description: "Runs reviewers in parallel"
+    triggers: ["build.done"]
+    publishes: ["review.joined"]
+    fan_out:
+      hats: [security, compliance]
+      join: review.joined
+  security:
+    name: "Security"
+    description: "Reviews security"
+"#;
+        let config: RalphConfig = serde_yaml::from_str(yaml).unwrap();
+        assert!(matches!(
+            config.validate(),
+            Err(ConfigError::FanOutValidation { message, .. }) if message.contains("compliance")
+        ));
+    }
+
     #[test]
     fn test_unique_triggers_accepted() {
         // Valid config: each trigger maps to exactly one hat
@@ -3053,6 +4321,77 @@ hooks:
         ));
     }
 
+    #[test]
+    fn test_hooks_parse_boundary_phase_events_with_topic_filters() {
+        let yaml = r#"
+hooks:
+  enabled: true
+  events:
+    pre.hat.activate:
+      - name: hat-policy
+        command: ["./scripts/hooks/hat-policy.sh"]
+        on_error: block
+    post.hat.complete:
+      - name: hat-audit
+        command: ["./scripts/hooks/hat-audit.sh"]
+        on_error: warn
+    pre.event.publish:
+      - name: release-gate
+        command: ["./scripts/hooks/release-gate.sh"]
+        on_error: suspend
+        topics: ["release.*", "deploy.requested"]
+    pre.merge:
+      - name: merge-policy
+        command: ["./scripts/hooks/merge-policy.sh"]
+        on_error: block
+    post.merge:
+      - name: merge-notify
+        command: ["./scripts/hooks/merge-notify.sh"]
+        on_error: warn
+"#;
+        let config = RalphConfig::parse_yaml(yaml).unwrap();
+        config.validate().unwrap();
+
+        assert_eq!(config.hooks.events.len(), 5);
+        let publish_hooks = &config.hooks.events[&HookPhaseEvent::PreEventPublish];
+        assert_eq!(
+            publish_hooks[0].topics,
+            vec!["release.*", "deploy.requested"]
+        );
+        assert!(
+            config.hooks.events[&HookPhaseEvent::PreMerge][0]
+                .topics
+                .is_empty()
+        );
+        assert_eq!(
+            HookPhaseEvent::parse("post.hat.complete"),
+            Some(HookPhaseEvent::PostHatComplete)
+        );
+        assert_eq!(HookPhaseEvent::PostMerge.to_string(), "post.merge");
+    }
+
+    #[test]
+    fn test_hooks_validate_rejects_topics_outside_event_publish() {
+        let yaml = r#"
+hooks:
+  enabled: true
+  events:
+    pre.hat.activate:
+      - name: hat-policy
+        command: ["./scripts/hooks/hat-policy.sh"]
+        on_error: block
+        topics: ["build.*"]
+"#;
+        let config = RalphConfig::parse_yaml(yaml).unwrap();
+
+        let err = config.validate().unwrap_err();
+        assert!(matches!(
+            &err,
+            ConfigError::HookValidation { field, .. }
+            if field == "hooks.events.pre.hat.activate[0].topics"
+        ));
+    }
+
     #[test]
     fn test_hooks_validate_rejects_missing_command() {
         let yaml = r"
@@ -3186,6 +4525,149 @@ hooks:
         ));
     }
 
+    // ─────────────────────────────────────────────────────────────────────────
+    // BACKPRESSURE CONFIG TESTS
+    // ─────────────────────────────────────────────────────────────────────────
+
+    #[test]
+    fn test_backpressure_config_defaults_disabled() {
+        let config = RalphConfig::default();
+        assert!(!config.backpressure.enabled);
+        assert!(!config.backpressure.is_active());
+        assert!(config.backpressure.commands.is_empty());
+        assert_eq!(config.backpressure.timeout_seconds, 600);
+    }
+
+    #[test]
+    fn test_backpressure_config_parses_and_validates() {
+        let yaml = r#"
+backpressure:
+  enabled: true
+  timeout_seconds: 120
+  commands:
+    tests: "cargo test"
+    lint: "cargo clippy -- -D warnings"
+"#;
+        let config = RalphConfig::parse_yaml(yaml).unwrap();
+
+        assert!(config.backpressure.is_active());
+        assert_eq!(config.backpressure.timeout_seconds, 120);
+        assert_eq!(
+            config.backpressure.commands.tests.as_deref(),
+            Some("cargo test")
+        );
+        assert!(config.backpressure.commands.typecheck.is_none());
+
+        let warnings = config.validate().unwrap();
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn test_backpressure_validate_rejects_blank_command() {
+        let yaml = r#"
+backpressure:
+  enabled: true
+  commands:
+    tests: "  "
+"#;
+        let config = RalphConfig::parse_yaml(yaml).unwrap();
+
+        let err = config.validate().unwrap_err();
+        assert!(matches!(
+            &err,
+            ConfigError::BackpressureValidation { field, .. }
+            if field == "backpressure.commands.tests"
+        ));
+    }
+
+    #[test]
+    fn test_backpressure_validate_warns_when_enabled_without_commands() {
+        let yaml = r"
+backpressure:
+  enabled: true
+";
+        let config = RalphConfig::parse_yaml(yaml).unwrap();
+
+        let warnings = config.validate().unwrap();
+        assert!(warnings.iter().any(|warning| matches!(
+            warning,
+            ConfigWarning::InvalidValue { field, .. } if field == "backpressure.commands"
+        )));
+    }
+
+    // ─────────────────────────────────────────────────────────────────────────
+    // QUALITY CONFIG TESTS
+    // ─────────────────────────────────────────────────────────────────────────
+
+    #[test]
+    fn test_quality_config_parses_with_hat_override() {
+        let yaml = r#"
+quality:
+  coverage:
+    threshold: 60
+    policy: warn
+  mutation:
+    policy: ignore
+hats:
+  legacy:
+    name: "Legacy Builder"
+    description: "Builds on the legacy service"
+    triggers: ["legacy.build"]
+    quality:
+      coverage:
+        policy: required
+"#;
+        let config = RalphConfig::parse_yaml(yaml).unwrap();
+        assert_eq!(config.quality.coverage.threshold, Some(60.0));
+        assert_eq!(config.quality.coverage.policy, Some(QualityPolicy::Warn));
+        assert_eq!(config.quality.mutation.policy, Some(QualityPolicy::Ignore));
+        assert!(config.validate().is_ok());
+
+        let overrides = config.hats["legacy"].quality.as_ref().unwrap();
+        let merged = config.quality.merged_with(overrides);
+        assert_eq!(merged.coverage.policy, Some(QualityPolicy::Required));
+        assert_eq!(merged.coverage.threshold, Some(60.0));
+        assert_eq!(merged.mutation.policy, Some(QualityPolicy::Ignore));
+    }
+
+    #[test]
+    fn test_quality_validate_rejects_threshold_on_pass_fail_dimension() {
+        let yaml = r"
+quality:
+  tests:
+    threshold: 90
+";
+        let config = RalphConfig::parse_yaml(yaml).unwrap();
+
+        let err = config.validate().unwrap_err();
+        assert!(matches!(
+            &err,
+            ConfigError::QualityValidation { field, .. } if field == "quality.tests.threshold"
+        ));
+    }
+
+    #[test]
+    fn test_quality_validate_rejects_out_of_range_hat_threshold() {
+        let yaml = r#"
+hats:
+  builder:
+    name: "Builder"
+    description: "Builds"
+    triggers: ["build.task"]
+    quality:
+      coverage:
+        threshold: 120
+"#;
+        let config = RalphConfig::parse_yaml(yaml).unwrap();
+
+        let err = config.validate().unwrap_err();
+        assert!(matches!(
+            &err,
+            ConfigError::QualityValidation { field, .. }
+            if field == "hats.builder.quality.coverage.threshold"
+        ));
+    }
+
     // ─────────────────────────────────────────────────────────────────────────
     // ROBOT CONFIG TESTS
     // ─────────────────────────────────────────────────────────────────────────
@@ -3268,6 +4750,7 @@ RObot:
             timeout_seconds: None,
             checkin_interval_seconds: None,
             telegram: None,
+            webhook: None,
         };
         let result = robot.validate();
         assert!(result.is_err());
@@ -3293,6 +4776,7 @@ RObot:
                 bot_token: Some("config-token".to_string()),
                 api_url: None,
             }),
+            webhook: None,
         };
 
         // When RALPH_TELEGRAM_BOT_TOKEN is not set, config token is returned
@@ -3311,6 +4795,7 @@ RObot:
             timeout_seconds: Some(300),
             checkin_interval_seconds: None,
             telegram: None,
+            webhook: None,
         };
 
         // Without env var AND without config token, resolve returns None
@@ -3332,6 +4817,7 @@ RObot:
                 bot_token: Some("test-token".to_string()),
                 api_url: None,
             }),
+            webhook: None,
         };
         assert!(robot.validate().is_ok());
     }
@@ -3349,6 +4835,7 @@ RObot:
             timeout_seconds: Some(300),
             checkin_interval_seconds: None,
             telegram: None,
+            webhook: None,
         };
         let result = robot.validate();
         assert!(result.is_err());
@@ -3377,6 +4864,7 @@ RObot:
                 bot_token: None,
                 api_url: None,
             }),
+            webhook: None,
         };
         let result = robot.validate();
         assert!(result.is_err());
@@ -3389,6 +4877,44 @@ RObot:
         );
     }
 
+    #[test]
+    fn test_robot_config_webhook_replaces_telegram_requirement() {
+        if std::env::var("RALPH_WEBHOOK_SECRET").is_ok() {
+            return;
+        }
+
+        let yaml = r#"
+RObot:
+  enabled: true
+  timeout_seconds: 60
+  webhook:
+    urls: ["https://hooks.example.com/ralph"]
+    secret: "s3cret"
+"#;
+        let config: RalphConfig = serde_yaml::from_str(yaml).unwrap();
+        assert!(config.robot.validate().is_ok());
+        let webhook = config.robot.webhook.as_ref().unwrap();
+        assert_eq!(webhook.max_attempts, 3);
+        assert_eq!(
+            config.robot.resolve_webhook_secret().as_deref(),
+            Some("s3cret")
+        );
+
+        let mut missing_secret = config.robot.clone();
+        missing_secret.webhook.as_mut().unwrap().secret = None;
+        assert!(matches!(
+            missing_secret.validate(),
+            Err(ConfigError::RobotMissingField { field, .. }) if field == "RObot.webhook.secret"
+        ));
+
+        let mut bad_url = config.robot.clone();
+        bad_url.webhook.as_mut().unwrap().urls = vec!["ftp://example.com".to_string()];
+        assert!(matches!(
+            bad_url.validate(),
+            Err(ConfigError::RobotMissingField { field, .. }) if field == "RObot.webhook.urls"
+        ));
+    }
+
     #[test]
     fn test_extra_instructions_merged_during_normalize() {
         let yaml = r#"
@@ -0,0 +1,172 @@
+use crate::backpressure::{BackpressureCheck, BackpressureCheckResult, BackpressureVerification};
+use crate::hooks::HookStreamOutput;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+/// Structured diagnostics record persisted for each orchestrator-run backpressure command.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackpressureRunEntry {
+    pub timestamp: DateTime<Utc>,
+    pub iteration: u32,
+    /// Event topic whose evidence was verified (`build.done`, `verify.passed`).
+    pub topic: String,
+    pub check: BackpressureCheck,
+    pub command: String,
+    pub passed: bool,
+    /// The agent's contradicting claim, when the verified result mismatched it.
+    pub claimed: Option<bool>,
+    pub mismatch: bool,
+    pub exit_code: Option<i32>,
+    pub timed_out: bool,
+    pub duration_ms: u64,
+    pub stdout: HookStreamOutput,
+    pub stderr: HookStreamOutput,
+    pub error: Option<String>,
+}
+
+impl BackpressureRunEntry {
+    /// Builds one entry per verified command from a verification record.
+    #[must_use]
+    pub fn from_verification(
+        iteration: u32,
+        topic: &str,
+        verification: &BackpressureVerification,
+    ) -> Vec<Self> {
+        verification
+            .results
+            .iter()
+            .map(|result| {
+                let mismatch = verification
+                    .mismatches
+                    .iter()
+                    .find(|mismatch| mismatch.check == result.check);
+                Self::from_result(iteration, topic, result, mismatch.map(|m| m.claimed))
+            })
+            .collect()
+    }
+
+    fn from_result(
+        iteration: u32,
+        topic: &str,
+        result: &BackpressureCheckResult,
+        mismatched_claim: Option<bool>,
+    ) -> Self {
+        Self {
+            timestamp: Utc::now(),
+            iteration,
+            topic: topic.to_string(),
+            check: result.check,
+            command: result.command.clone(),
+            passed: result.passed,
+            claimed: mismatched_claim,
+            mismatch: mismatched_claim.is_some(),
+            exit_code: result.exit_code,
+            timed_out: result.timed_out,
+            duration_ms: result.duration_ms,
+            stdout: result.stdout.clone(),
+            stderr: result.stderr.clone(),
+            error: result.error.clone(),
+        }
+    }
+}
+
+/// JSONL writer for backpressure command runs (`backpressure-runs.jsonl`).
+pub struct BackpressureRunLogger {
+    writer: BufWriter<File>,
+}
+
+impl BackpressureRunLogger {
+    pub fn new(session_dir: &Path) -> std::io::Result<Self> {
+        let log_file = session_dir.join("backpressure-runs.jsonl");
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(log_file)?;
+
+        Ok(Self {
+            writer: BufWriter::new(file),
+        })
+    }
+
+    pub fn log(&mut self, entry: &BackpressureRunEntry) -> std::io::Result<()> {
+        serde_json::to_writer(&mut self.writer, entry)?;
+        self.writer.write_all(b"\n")?;
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backpressure::BackpressureMismatch;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn result(check: BackpressureCheck, passed: bool) -> BackpressureCheckResult {
+        BackpressureCheckResult {
+            check,
+            command: format!("run-{check}"),
+            passed,
+            exit_code: Some(i32::from(!passed)),
+            timed_out: false,
+            duration_ms: 42,
+            stdout: HookStreamOutput {
+                content: "out".to_string(),
+                truncated: false,
+            },
+            stderr: HookStreamOutput::default(),
+            error: None,
+        }
+    }
+
+    #[test]
+    fn entries_mark_mismatched_claims() {
+        let verification = BackpressureVerification {
+            results: vec![
+                result(BackpressureCheck::Tests, false),
+                result(BackpressureCheck::Lint, true),
+            ],
+            mismatches: vec![BackpressureMismatch {
+                check: BackpressureCheck::Tests,
+                claimed: true,
+                actual: false,
+            }],
+        };
+
+        let entries = BackpressureRunEntry::from_verification(3, "build.done", &verification);
+
+        assert_eq!(entries.len(), 2);
+        assert!(entries[0].mismatch);
+        assert_eq!(entries[0].claimed, Some(true));
+        assert_eq!(entries[0].command, "run-tests");
+        assert!(!entries[1].mismatch);
+        assert_eq!(entries[1].iteration, 3);
+    }
+
+    #[test]
+    fn logger_persists_jsonl_entries() {
+        let temp_dir = TempDir::new().expect("temp dir");
+        let mut logger = BackpressureRunLogger::new(temp_dir.path()).expect("create logger");
+
+        let verification = BackpressureVerification {
+            results: vec![result(BackpressureCheck::Audit, true)],
+            mismatches: Vec::new(),
+        };
+        for entry in BackpressureRunEntry::from_verification(1, "build.done", &verification) {
+            logger.log(&entry).expect("write entry");
+        }
+        drop(logger);
+
+        let content = fs::read_to_string(temp_dir.path().join("backpressure-runs.jsonl"))
+            .expect("read backpressure-runs.jsonl");
+        let value: serde_json::Value =
+            serde_json::from_str(content.lines().next().expect("one line")).expect("json");
+        assert_eq!(value["check"], "audit");
+        assert_eq!(value["topic"], "build.done");
+        assert_eq!(value["stdout"]["content"], "out");
+    }
+}
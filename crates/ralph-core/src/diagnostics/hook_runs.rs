@@ -15,6 +15,16 @@ pub enum HookDisposition {
     Suspend,
 }
 
+/// Mutation actions a hook returned on stdout, recorded after schema validation.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HookMutationTelemetry {
+    /// Accepted mutation keys (`metadata`, `reject`, `rewrite`, `publish`, `guidance`).
+    pub actions: Vec<String>,
+    /// Validation error when the mutation payload was rejected.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
 /// Structured diagnostics record persisted for each hook invocation.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HookRunTelemetryEntry {
@@ -33,6 +43,9 @@ pub struct HookRunTelemetryEntry {
     pub suspend_mode: HookSuspendMode,
     pub retry_attempt: u32,
     pub retry_max_attempts: u32,
+    /// Parsed mutation actions; absent when mutation is disabled for the hook.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mutation: Option<HookMutationTelemetry>,
 }
 
 impl HookRunTelemetryEntry {
@@ -64,8 +77,16 @@ impl HookRunTelemetryEntry {
             suspend_mode,
             retry_attempt,
             retry_max_attempts,
+            mutation: None,
         }
     }
+
+    /// Attaches the validated mutation actions (or validation error) for this run.
+    #[must_use]
+    pub fn with_mutation(mut self, mutation: Option<HookMutationTelemetry>) -> Self {
+        self.mutation = mutation;
+        self
+    }
 }
 
 /// JSONL writer for hook invocation telemetry (`hook-runs.jsonl`).
@@ -130,6 +151,7 @@ mod tests {
             suspend_mode: HookSuspendMode::RetryBackoff,
             retry_attempt: 2,
             retry_max_attempts: 4,
+            mutation: None,
         }
     }
 
@@ -191,6 +213,35 @@ mod tests {
         assert_eq!(value["suspend_mode"], "retry_backoff");
         assert_eq!(value["retry_attempt"], 2);
         assert_eq!(value["retry_max_attempts"], 4);
+        assert!(value.get("mutation").is_none());
+    }
+
+    #[test]
+    fn telemetry_entry_records_mutation_actions_and_errors() {
+        let entry =
+            sample_entry(HookDisposition::Pass).with_mutation(Some(HookMutationTelemetry {
+                actions: vec!["reject".to_string(), "guidance".to_string()],
+                error: None,
+            }));
+        let value = serde_json::to_value(&entry).expect("serialize telemetry entry");
+        assert_eq!(value["mutation"]["actions"][0], "reject");
+        assert_eq!(value["mutation"]["actions"][1], "guidance");
+        assert!(value["mutation"].get("error").is_none());
+
+        let entry =
+            sample_entry(HookDisposition::Warn).with_mutation(Some(HookMutationTelemetry {
+                actions: Vec::new(),
+                error: Some("mutation payload must be a JSON object".to_string()),
+            }));
+        let line = serde_json::to_string(&entry).expect("serialize telemetry entry");
+        let parsed: HookRunTelemetryEntry =
+            serde_json::from_str(&line).expect("parse telemetry entry");
+        let mutation = parsed.mutation.expect("mutation telemetry");
+        assert!(mutation.actions.is_empty());
+        assert_eq!(
+            mutation.error.as_deref(),
+            Some("mutation payload must be a JSON object")
+        );
     }
 
     #[test]
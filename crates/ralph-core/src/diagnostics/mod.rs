@@ -4,6 +4,7 @@
 //! and errors to structured JSONL files when `RALPH_DIAGNOSTICS=1` is set.
 
 mod agent_output;
+mod backpressure_runs;
 mod errors;
 mod hook_runs;
 mod log_rotation;
@@ -16,8 +17,9 @@ mod trace_layer;
 mod integration_tests;
 
 pub use agent_output::{AgentOutputContent, AgentOutputEntry, AgentOutputLogger};
+pub use backpressure_runs::{BackpressureRunEntry, BackpressureRunLogger};
 pub use errors::{DiagnosticError, ErrorLogger};
-pub use hook_runs::{HookDisposition, HookRunLogger, HookRunTelemetryEntry};
+pub use hook_runs::{HookDisposition, HookMutationTelemetry, HookRunLogger, HookRunTelemetryEntry};
 pub use log_rotation::{create_log_file, rotate_logs};
 pub use orchestration::{OrchestrationEvent, OrchestrationLogger};
 pub use performance::{PerformanceLogger, PerformanceMetric};
@@ -40,6 +42,7 @@ pub struct DiagnosticsCollector {
     performance_logger: Option<Arc<Mutex<performance::PerformanceLogger>>>,
     error_logger: Option<Arc<Mutex<errors::ErrorLogger>>>,
     hook_run_logger: Option<Arc<Mutex<hook_runs::HookRunLogger>>>,
+    backpressure_run_logger: Option<Arc<Mutex<backpressure_runs::BackpressureRunLogger>>>,
 }
 
 impl DiagnosticsCollector {
@@ -56,29 +59,37 @@ impl DiagnosticsCollector {
 
     /// Creates a diagnostics collector with explicit enabled flag (for testing).
     pub fn with_enabled(base_path: &Path, enabled: bool) -> std::io::Result<Self> {
-        let (session_dir, orchestration_logger, performance_logger, error_logger, hook_run_logger) =
-            if enabled {
-                let timestamp = Local::now().format("%Y-%m-%dT%H-%M-%S");
-                let dir = base_path
-                    .join(".ralph")
-                    .join("diagnostics")
-                    .join(timestamp.to_string());
-                fs::create_dir_all(&dir)?;
-
-                let orch_logger = orchestration::OrchestrationLogger::new(&dir)?;
-                let perf_logger = performance::PerformanceLogger::new(&dir)?;
-                let err_logger = errors::ErrorLogger::new(&dir)?;
-                let hook_logger = hook_runs::HookRunLogger::new(&dir)?;
-                (
-                    Some(dir),
-                    Some(Arc::new(Mutex::new(orch_logger))),
-                    Some(Arc::new(Mutex::new(perf_logger))),
-                    Some(Arc::new(Mutex::new(err_logger))),
-                    Some(Arc::new(Mutex::new(hook_logger))),
-                )
-            } else {
-                (None, None, None, None, None)
-            };
+        let (
+            session_dir,
+            orchestration_logger,
+            performance_logger,
+            error_logger,
+            hook_run_logger,
+            backpressure_run_logger,
+        ) = if enabled {
+            let timestamp = Local::now().format("%Y-%m-%dT%H-%M-%S");
+            let dir = base_path
+                .join(".ralph")
+                .join("diagnostics")
+                .join(timestamp.to_string());
+            fs::create_dir_all(&dir)?;
+
+            let orch_logger = orchestration::OrchestrationLogger::new(&dir)?;
+            let perf_logger = performance::PerformanceLogger::new(&dir)?;
+            let err_logger = errors::ErrorLogger::new(&dir)?;
+            let hook_logger = hook_runs::HookRunLogger::new(&dir)?;
+            let backpressure_logger = backpressure_runs::BackpressureRunLogger::new(&dir)?;
+            (
+                Some(dir),
+                Some(Arc::new(Mutex::new(orch_logger))),
+                Some(Arc::new(Mutex::new(perf_logger))),
+                Some(Arc::new(Mutex::new(err_logger))),
+                Some(Arc::new(Mutex::new(hook_logger))),
+                Some(Arc::new(Mutex::new(backpressure_logger))),
+            )
+        } else {
+            (None, None, None, None, None, None)
+        };
 
         Ok(Self {
             enabled,
@@ -87,6 +98,7 @@ impl DiagnosticsCollector {
             performance_logger,
             error_logger,
             hook_run_logger,
+            backpressure_run_logger,
         })
     }
 
@@ -99,6 +111,7 @@ impl DiagnosticsCollector {
             performance_logger: None,
             error_logger: None,
             hook_run_logger: None,
+            backpressure_run_logger: None,
         }
     }
 
@@ -174,6 +187,17 @@ impl DiagnosticsCollector {
         }
     }
 
+    /// Logs an orchestrator-run backpressure command result.
+    ///
+    /// Does nothing if diagnostics are disabled.
+    pub fn log_backpressure_run(&self, entry: &BackpressureRunEntry) {
+        if let Some(logger) = &self.backpressure_run_logger
+            && let Ok(mut logger) = logger.lock()
+        {
+            let _ = logger.log(entry);
+        }
+    }
+
     /// Logs the full prompt for an iteration to `prompt-log.md`.
     ///
     /// Does nothing if diagnostics are disabled.
@@ -15,11 +15,41 @@ pub struct OrchestrationEntry {
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum OrchestrationEvent {
     IterationStarted,
-    HatSelected { hat: String, reason: String },
-    EventPublished { topic: String },
-    BackpressureTriggered { reason: String },
-    LoopTerminated { reason: String },
-    TaskAbandoned { reason: String },
+    HatSelected {
+        hat: String,
+        reason: String,
+    },
+    EventPublished {
+        topic: String,
+    },
+    BackpressureTriggered {
+        reason: String,
+    },
+    BackpressureMismatch {
+        check: String,
+        claimed: bool,
+        actual: bool,
+    },
+    LoopTerminated {
+        reason: String,
+    },
+    TaskAbandoned {
+        reason: String,
+    },
+    /// A memory picked for prompt injection, with its relevance score.
+    MemoryInjected {
+        memory_id: String,
+        score: f64,
+        rank: usize,
+    },
+    /// A fan-out branch finished, with its own usage and output.
+    FanOutBranch {
+        status: String,
+        topics: Vec<String>,
+        cost_usd: f64,
+        tokens: u64,
+        output_tail: String,
+    },
 }
 
 pub struct OrchestrationLogger {
@@ -76,12 +106,24 @@ mod tests {
             OrchestrationEvent::BackpressureTriggered {
                 reason: "tests failed".to_string(),
             },
+            OrchestrationEvent::BackpressureMismatch {
+                check: "tests".to_string(),
+                claimed: true,
+                actual: false,
+            },
             OrchestrationEvent::LoopTerminated {
                 reason: "completion_promise".to_string(),
             },
             OrchestrationEvent::TaskAbandoned {
                 reason: "max_iterations".to_string(),
             },
+            OrchestrationEvent::FanOutBranch {
+                status: "completed".to_string(),
+                topics: vec!["review.security".to_string()],
+                cost_usd: 0.12,
+                tokens: 900,
+                output_tail: "LGTM".to_string(),
+            },
         ];
 
         for event in events {
@@ -4,11 +4,49 @@
 //! state of the orchestration loop including iteration count, failures,
 //! timing, and hat activation tracking.
 
+use crate::failover::FailureClass;
 use ralph_proto::{Event, HatId};
 use std::collections::{HashMap, HashSet};
 use std::hash::{DefaultHasher, Hash, Hasher};
 use std::time::{Duration, Instant};
 
+/// Running token and cost totals for a single hat.
+#[derive(Debug, Clone, Copy, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct HatUsage {
+    /// Cost in USD attributed to the hat.
+    pub cost_usd: f64,
+    /// Input plus output tokens attributed to the hat.
+    pub tokens: u64,
+    /// Iterations the usage was collected over.
+    pub iterations: u32,
+}
+
+impl HatUsage {
+    /// Adds another usage sample to the running totals.
+    pub fn add(&mut self, other: HatUsage) {
+        self.cost_usd += other.cost_usd;
+        self.tokens += other.tokens;
+        self.iterations += other.iterations;
+    }
+}
+
+/// A failed attempt of a hat with a retry policy, described in the next prompt.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryAttempt {
+    /// Hat whose attempt failed.
+    pub hat_id: HatId,
+    /// Failed attempts so far (1-indexed).
+    pub attempt: u32,
+    /// Retries allowed by the hat's policy.
+    pub max_retries: u32,
+    /// Why the attempt failed.
+    pub reason: String,
+    /// Tail of the failed attempt's output.
+    pub output_tail: String,
+    /// Hat or backend the work was escalated to, if any.
+    pub escalated_to: Option<String>,
+}
+
 /// Fingerprint of the last emitted event for stale loop detection.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct EventSignature {
@@ -51,6 +89,30 @@ pub struct LoopState {
     /// Hats for which `<hat_id>.exhausted` has been emitted.
     pub exhausted_hats: HashSet<HatId>,
 
+    /// Per-hat token and cost totals (used for max_cost_usd / max_tokens).
+    pub hat_usage: HashMap<HatId, HatUsage>,
+
+    /// Hats for which `<hat_id>.budget_exhausted` has been emitted.
+    pub budget_exhausted_hats: HashSet<HatId>,
+
+    /// Per-hat number of failover steps taken into the backend failover chain.
+    pub failover_positions: HashMap<HatId, usize>,
+
+    /// Per-hat failed attempts since the hat last succeeded (used for `retry`).
+    pub hat_failure_counts: HashMap<HatId, u32>,
+
+    /// Events that activated each hat in the last iteration (re-delivered on retry).
+    pub last_activation_events: HashMap<HatId, Vec<Event>>,
+
+    /// Failed attempts to describe in the next prompt.
+    pub pending_retries: Vec<RetryAttempt>,
+
+    /// Delay requested by a retry policy before the next iteration.
+    pub retry_backoff: Option<Duration>,
+
+    /// Failure class of the last iteration, as reported by the runner.
+    pub last_failure: Option<FailureClass>,
+
     /// When the last Telegram check-in message was sent.
     /// `None` means no check-in has been sent yet.
     pub last_checkin_at: Option<Instant>,
@@ -89,6 +151,14 @@ impl Default for LoopState {
             completion_requested: false,
             hat_activation_counts: HashMap::new(),
             exhausted_hats: HashSet::new(),
+            hat_usage: HashMap::new(),
+            budget_exhausted_hats: HashSet::new(),
+            failover_positions: HashMap::new(),
+            hat_failure_counts: HashMap::new(),
+            last_activation_events: HashMap::new(),
+            pending_retries: Vec::new(),
+            retry_backoff: None,
+            last_failure: None,
             last_checkin_at: None,
             last_active_hat_ids: Vec::new(),
             seen_topics: HashSet::new(),
@@ -6,19 +6,30 @@ mod loop_state;
 #[cfg(test)]
 mod tests;
 
-pub use loop_state::LoopState;
-
-use crate::config::{HatBackend, InjectMode, RalphConfig};
-use crate::event_parser::{EventParser, MutationEvidence, MutationStatus};
+pub use loop_state::{HatUsage, LoopState, RetryAttempt};
+
+use crate::backpressure::{BackpressureCheckResult, BackpressureRunner, BackpressureVerification};
+use crate::config::{
+    HatBackend, InjectMode, QualityConfig, QualityPolicy, RalphConfig, RetryEscalation,
+};
+use crate::diagnostics::HookDisposition;
+use crate::event_parser::{EventParser, MutationEvidence, MutationStatus, QualityFinding};
 use crate::event_reader::EventReader;
+use crate::failover::FailureClass;
+use crate::fan_out::BranchOutcome;
 use crate::hat_registry::HatRegistry;
 use crate::hatless_ralph::HatlessRalph;
+use crate::hooks::{
+    HookDispatchOutcome, HookOrchestrator, HookPayloadContextInput, HookPhaseEvent,
+    fail_if_blocking_outcomes, loop_termination_phase_events,
+};
 use crate::instructions::InstructionBuilder;
 use crate::loop_context::LoopContext;
-use crate::memory_store::{MarkdownMemoryStore, format_memories_as_markdown, truncate_to_budget};
+use crate::memory_index::select_memories;
+use crate::memory_store::{MarkdownMemoryStore, format_memories_as_markdown};
 use crate::skill_registry::SkillRegistry;
 use crate::text::floor_char_boundary;
-use ralph_proto::{CheckinContext, Event, EventBus, Hat, HatId, RobotService};
+use ralph_proto::{CheckinContext, Event, EventBus, Hat, HatId, LoopOutcome, RobotService};
 use serde_json::{Map, Value};
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -38,6 +49,49 @@ pub struct ProcessedEvents {
     pub human_interact_context: Option<Value>,
     /// Whether any events lacked specific hat subscribers (orphans handled by Ralph).
     pub has_orphans: bool,
+    /// Whether a hook suspended before the batch was read; it stays unread
+    /// until [`EventLoop::await_hook_resume`] clears the boundary.
+    pub suspended: bool,
+}
+
+/// Hook-requested change to an unread JSONL event, applied when the event is read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PendingEventMutation {
+    /// Replace the event with an `event.rejected` event carrying the reason.
+    Reject { reason: String },
+    /// Replace the event payload.
+    Rewrite { payload: String },
+}
+
+/// One boundary in the hook sequence of a lifecycle step.
+#[derive(Debug, Clone)]
+enum HookStep {
+    /// Lifecycle hooks for a phase-event.
+    Phase(HookPhaseEvent, HookPayloadContextInput),
+    /// `pre.event.publish` hooks for each unread JSONL event.
+    EventPublish { active_hat: String },
+}
+
+/// Work a lifecycle step does once its hook sequence has been crossed.
+#[derive(Debug, Clone)]
+enum HookContinuation {
+    /// Nothing beyond the hooks themselves.
+    Done,
+    /// Publish the start event, then run `post.loop.start` hooks.
+    StartLoop { topic: String, prompt: String },
+    /// Hand the routed hat to the next `next_hat` call.
+    ActivateHat(HatId),
+    /// Let the next `process_events_from_jsonl` call read the batch.
+    ReadEvents,
+}
+
+/// A lifecycle step parked by a suspending hook until the loop resumes.
+#[derive(Debug)]
+struct HookSuspend {
+    outcomes: Vec<HookDispatchOutcome>,
+    iteration: u32,
+    remaining: Vec<HookStep>,
+    then: HookContinuation,
 }
 
 /// Reason the event loop terminated.
@@ -69,6 +123,8 @@ pub enum TerminationReason {
     WorkspaceGone,
     /// Loop was cancelled gracefully via loop.cancel event (human rejection, timeout).
     Cancelled,
+    /// A lifecycle hook blocked a boundary, or its suspend gate failed.
+    HookBlocked,
 }
 
 impl TerminationReason {
@@ -87,7 +143,8 @@ impl TerminationReason {
             | TerminationReason::LoopStale
             | TerminationReason::ValidationFailure
             | TerminationReason::Stopped
-            | TerminationReason::WorkspaceGone => 1,
+            | TerminationReason::WorkspaceGone
+            | TerminationReason::HookBlocked => 1,
             TerminationReason::MaxIterations
             | TerminationReason::MaxRuntime
             | TerminationReason::MaxCost => 2,
@@ -118,6 +175,7 @@ impl TerminationReason {
             TerminationReason::RestartRequested => "restart_requested",
             TerminationReason::WorkspaceGone => "workspace_gone",
             TerminationReason::Cancelled => "cancelled",
+            TerminationReason::HookBlocked => "hook_blocked",
         }
     }
 
@@ -137,10 +195,24 @@ pub struct EventLoop {
     ralph: HatlessRalph,
     /// Cached human guidance messages that should persist across iterations.
     robot_guidance: Vec<String>,
+    /// Hook guidance appended to the next prompt only.
+    hook_guidance: Vec<String>,
+    /// Hook mutations keyed by position in the current unread JSONL batch.
+    pending_event_mutations: std::collections::HashMap<usize, PendingEventMutation>,
     /// Event reader for consuming events from JSONL file.
     /// Made pub(crate) to allow tests to override the path.
     pub(crate) event_reader: EventReader,
     diagnostics: crate::diagnostics::DiagnosticsCollector,
+    /// Lifecycle hook dispatch shared by every embedder of this loop.
+    hooks: HookOrchestrator,
+    /// Termination requested at a lifecycle hook boundary; reported by `check_termination`.
+    hook_termination: Option<TerminationReason>,
+    /// Lifecycle step waiting on a suspended hook; see `await_hook_resume`.
+    hook_suspend: Option<HookSuspend>,
+    /// Hat whose activation hooks have run, returned by the next `next_hat` call.
+    activated_hat: Option<HatId>,
+    /// Whether the hooks guarding the unread JSONL batch have already run.
+    event_hooks_cleared: bool,
     /// Loop context for path resolution (None for legacy single-loop mode).
     loop_context: Option<LoopContext>,
     /// Skill registry for the current loop.
@@ -263,6 +335,14 @@ impl EventLoop {
             .unwrap_or_else(|_| context.events_path());
         let event_reader = EventReader::new(&events_path);
 
+        let hooks = HookOrchestrator::new(
+            &config.hooks,
+            context.loop_id().unwrap_or("primary"),
+            context.is_primary(),
+            context.workspace(),
+            context.repo_root(),
+        );
+
         Self {
             config,
             registry,
@@ -271,8 +351,15 @@ impl EventLoop {
             instruction_builder,
             ralph,
             robot_guidance: Vec::new(),
+            hook_guidance: Vec::new(),
+            pending_event_mutations: std::collections::HashMap::new(),
             event_reader,
             diagnostics,
+            hooks,
+            hook_termination: None,
+            hook_suspend: None,
+            activated_hat: None,
+            event_hooks_cleared: false,
             loop_context: Some(context),
             skill_registry,
             robot_service: None,
@@ -353,6 +440,14 @@ impl EventLoop {
             .unwrap_or_else(|_| ".ralph/events.jsonl".to_string());
         let event_reader = EventReader::new(&events_path);
 
+        let hooks = HookOrchestrator::new(
+            &config.hooks,
+            "primary",
+            true,
+            &config.core.workspace_root,
+            &config.core.workspace_root,
+        );
+
         Self {
             config,
             registry,
@@ -361,8 +456,15 @@ impl EventLoop {
             instruction_builder,
             ralph,
             robot_guidance: Vec::new(),
+            hook_guidance: Vec::new(),
+            pending_event_mutations: std::collections::HashMap::new(),
             event_reader,
             diagnostics,
+            hooks,
+            hook_termination: None,
+            hook_suspend: None,
+            activated_hat: None,
+            event_hooks_cleared: false,
             loop_context: None,
             skill_registry,
             robot_service: None,
@@ -460,7 +562,14 @@ impl EventLoop {
     }
 
     /// Checks if any termination condition is met.
+    ///
+    /// Includes stops requested at lifecycle hook boundaries: a blocking hook,
+    /// or a suspended hook that was stopped or restarted.
     pub fn check_termination(&self) -> Option<TerminationReason> {
+        if let Some(reason) = &self.hook_termination {
+            return Some(reason.clone());
+        }
+
         let cfg = &self.config.event_loop;
 
         if self.state.iteration >= cfg.max_iterations {
@@ -645,6 +754,9 @@ impl EventLoop {
     }
 
     /// Initializes the loop by publishing the start event.
+    ///
+    /// Runs `pre.loop.start` hooks before the start event is published and
+    /// `post.loop.start` hooks after it.
     pub fn initialize(&mut self, prompt_content: &str) {
         // Use configured starting_event or default to task.start for backward compatibility
         let topic = self
@@ -667,6 +779,21 @@ impl EventLoop {
 
     /// Common initialization logic with configurable topic.
     fn initialize_with_topic(&mut self, topic: &str, prompt_content: &str) {
+        self.run_hook_sequence(
+            vec![HookStep::Phase(
+                HookPhaseEvent::PreLoopStart,
+                HookPayloadContextInput::default(),
+            )],
+            self.state.iteration,
+            HookContinuation::StartLoop {
+                topic: topic.to_string(),
+                prompt: prompt_content.to_string(),
+            },
+        );
+    }
+
+    /// Publishes the start event once `pre.loop.start` hooks let the loop begin.
+    fn start_loop(&mut self, topic: &str, prompt_content: &str) {
         // Store the objective so it persists across all iterations.
         // After iteration 1, bus.take_pending() consumes the start event,
         // so without this the objective would be invisible to later hats.
@@ -675,6 +802,19 @@ impl EventLoop {
         let start_event = Event::new(topic, prompt_content);
         self.bus.publish(start_event);
         debug!(topic = topic, "Published {} event", topic);
+
+        let active_hat = self.get_active_hat_id().as_str().to_string();
+        self.run_hook_sequence(
+            vec![HookStep::Phase(
+                HookPhaseEvent::PostLoopStart,
+                HookPayloadContextInput {
+                    active_hat: Some(active_hat),
+                    ..HookPayloadContextInput::default()
+                },
+            )],
+            self.state.iteration,
+            HookContinuation::Done,
+        );
     }
 
     /// Gets the next hat to execute (if any have pending events).
@@ -685,7 +825,52 @@ impl EventLoop {
     ///
     /// - Solo mode (no custom hats): Returns "ralph" if Ralph has pending events
     /// - Multi-hat mode (custom hats defined): Always returns "ralph" if ANY hat has pending events
-    pub fn next_hat(&self) -> Option<&HatId> {
+    ///
+    /// Selecting a hat starts the next iteration, so this runs the
+    /// `pre.iteration.start`, `post.iteration.start` and `pre.hat.activate`
+    /// hooks. Returns `None` when a hook stops the loop (`check_termination`
+    /// then reports why) or suspends it (`await_hook_resume`, after which
+    /// this returns the hat without running the hooks again).
+    pub fn next_hat(&mut self) -> Option<&HatId> {
+        let hat_id = match self.activated_hat.take() {
+            Some(hat_id) => hat_id,
+            None => {
+                let hat_id = self.route_next_hat()?.clone();
+                let hook_hat = if hat_id.as_str() == "ralph" {
+                    self.get_active_hat_id()
+                } else {
+                    hat_id.clone()
+                };
+                let hat_context = HookPayloadContextInput {
+                    active_hat: Some(hook_hat.as_str().to_string()),
+                    selected_hat: Some(hook_hat.as_str().to_string()),
+                    ..HookPayloadContextInput::default()
+                };
+
+                self.run_hook_sequence(
+                    vec![
+                        HookStep::Phase(
+                            HookPhaseEvent::PreIterationStart,
+                            HookPayloadContextInput {
+                                selected_hat: None,
+                                ..hat_context.clone()
+                            },
+                        ),
+                        HookStep::Phase(HookPhaseEvent::PostIterationStart, hat_context.clone()),
+                        HookStep::Phase(HookPhaseEvent::PreHatActivate, hat_context),
+                    ],
+                    self.state.iteration + 1,
+                    HookContinuation::ActivateHat(hat_id),
+                );
+                self.activated_hat.take()?
+            }
+        };
+
+        self.bus.hat_ids().find(|id| **id == hat_id)
+    }
+
+    /// Routes pending events to the hat that should run next.
+    fn route_next_hat(&self) -> Option<&HatId> {
         let next = self.bus.next_hat_with_pending();
 
         // If no pending hat events but human interactions are pending, route to Ralph.
@@ -739,8 +924,8 @@ impl EventLoop {
 
     /// Returns whether unread JSONL events include any semantic `plan.*` topics.
     ///
-    /// This allows callers to dispatch `pre.plan.created` hooks before
-    /// event publication handling without consuming unread events.
+    /// `process_events_from_jsonl` uses this to run `pre.plan.created` hooks
+    /// before the batch is read.
     pub fn has_pending_plan_events_in_jsonl(&self) -> std::io::Result<bool> {
         let result = self.event_reader.peek_new_events()?;
         Ok(result
@@ -762,6 +947,337 @@ impl EventLoop {
             }))
     }
 
+    /// Returns unread JSONL events in file order without consuming reader state.
+    ///
+    /// `pre.event.publish` hooks inspect events through this before they
+    /// reach the bus.
+    pub fn pending_events_in_jsonl(&self) -> std::io::Result<Vec<crate::event_reader::Event>> {
+        Ok(self.event_reader.peek_new_events()?.events)
+    }
+
+    /// Queues a hook mutation for the unread JSONL event at `index`, as
+    /// returned by [`Self::pending_events_in_jsonl`].
+    ///
+    /// A rejection takes precedence over any rewrite for the same event.
+    pub fn mutate_pending_event(&mut self, index: usize, mutation: PendingEventMutation) {
+        if matches!(
+            self.pending_event_mutations.get(&index),
+            Some(PendingEventMutation::Reject { .. })
+        ) {
+            return;
+        }
+        self.pending_event_mutations.insert(index, mutation);
+    }
+
+    /// Appends hook guidance to the next prompt build.
+    ///
+    /// Unlike `human.guidance`, hook guidance is neither persisted to the
+    /// scratchpad nor carried past the next prompt.
+    pub fn append_hook_guidance(&mut self, guidance: impl Into<String>) {
+        self.hook_guidance.push(guidance.into());
+    }
+
+    /// Returns the lifecycle hook orchestrator for this loop.
+    pub fn hooks(&self) -> &HookOrchestrator {
+        &self.hooks
+    }
+
+    /// Sets the loop ID stamped into hook payloads and suspend-state.
+    ///
+    /// Primary loops get their ID from the embedder, so call this before the
+    /// first hook dispatch when the loop context has no ID of its own.
+    pub fn set_hook_loop_id(&mut self, loop_id: impl Into<String>) {
+        self.hooks.set_loop_id(loop_id);
+    }
+
+    /// Dispatches lifecycle hooks for `phase_event` and applies their
+    /// `publish`/`guidance` mutation actions.
+    pub(crate) fn dispatch_lifecycle_hooks(
+        &mut self,
+        phase_event: HookPhaseEvent,
+        iteration: u32,
+        context: HookPayloadContextInput,
+    ) -> Vec<HookDispatchOutcome> {
+        let payload_input =
+            self.hooks
+                .payload_input(iteration, self.config.event_loop.max_iterations, context);
+        let outcomes = self
+            .hooks
+            .dispatch(phase_event, payload_input, &self.diagnostics);
+        crate::hooks::apply_hook_mutation_actions(self, &outcomes);
+        outcomes
+    }
+
+    /// Returns whether a lifecycle step is parked on a suspended hook.
+    pub fn is_hook_suspended(&self) -> bool {
+        self.hook_suspend.is_some()
+    }
+
+    /// Waits out a suspended lifecycle hook, then finishes the step it parked.
+    ///
+    /// Does nothing unless a hook suspended. The wait goes through
+    /// [`HookOrchestrator::resolve`], so it yields to the runtime instead of
+    /// blocking it. On resume the parked step runs its remaining hooks (which
+    /// may suspend again); a stop, restart or failure is recorded for
+    /// `check_termination`.
+    pub async fn await_hook_resume(&mut self) {
+        while let Some(suspend) = self.hook_suspend.take() {
+            match self.hooks.resolve(&suspend.outcomes).await {
+                Ok(None) => {
+                    self.run_hook_sequence(suspend.remaining, suspend.iteration, suspend.then);
+                }
+                Ok(Some(reason)) => self.hook_termination = Some(reason),
+                Err(error) => {
+                    warn!(error = %error, "Lifecycle hook boundary stopped the loop");
+                    self.hook_termination = Some(TerminationReason::HookBlocked);
+                }
+            }
+        }
+    }
+
+    /// Runs the hooks for each step in order, then the step's `then` work.
+    ///
+    /// Stops at the first step whose hooks block, recording the reason for
+    /// `check_termination`, or suspend, parking the rest of the sequence until
+    /// `await_hook_resume`. Once the loop is stopping or parked, no further
+    /// hooks run.
+    fn run_hook_sequence(&mut self, steps: Vec<HookStep>, iteration: u32, then: HookContinuation) {
+        if self.hook_termination.is_some() || self.hook_suspend.is_some() {
+            return;
+        }
+
+        let mut steps = steps.into_iter();
+        while let Some(step) = steps.next() {
+            let outcomes = match step {
+                HookStep::Phase(phase_event, context) => {
+                    self.dispatch_lifecycle_hooks(phase_event, iteration, context)
+                }
+                HookStep::EventPublish { active_hat } => {
+                    self.dispatch_event_publish_hooks(iteration, &active_hat)
+                }
+            };
+
+            if let Err(error) = fail_if_blocking_outcomes(&outcomes) {
+                warn!(error = %error, "Lifecycle hook boundary stopped the loop");
+                self.hook_termination = Some(TerminationReason::HookBlocked);
+                return;
+            }
+            if outcomes
+                .iter()
+                .any(|outcome| outcome.disposition == HookDisposition::Suspend)
+            {
+                self.hook_suspend = Some(HookSuspend {
+                    outcomes,
+                    iteration,
+                    remaining: steps.collect(),
+                    then,
+                });
+                return;
+            }
+        }
+
+        match then {
+            HookContinuation::Done => {}
+            HookContinuation::StartLoop { topic, prompt } => self.start_loop(&topic, &prompt),
+            HookContinuation::ActivateHat(hat_id) => self.activated_hat = Some(hat_id),
+            HookContinuation::ReadEvents => self.event_hooks_cleared = true,
+        }
+    }
+
+    /// Returns the hat hook payloads report for work routed to `hat_id`.
+    ///
+    /// Ralph coordinates every iteration in multi-hat mode, so hooks see the
+    /// hat Ralph is wearing instead.
+    fn hook_hat(&self, hat_id: &HatId) -> HatId {
+        if hat_id.as_str() != "ralph" {
+            return hat_id.clone();
+        }
+
+        self.state
+            .last_active_hat_ids
+            .first()
+            .cloned()
+            .unwrap_or_else(|| self.get_active_hat_id())
+    }
+
+    /// Dispatches `pre.event.publish` hooks for each unread JSONL event, in
+    /// file order, running only hooks whose topic filters match the event.
+    ///
+    /// `reject`/`rewrite` actions are queued against the event and applied when
+    /// it is read. Stops at the first event whose hooks block or suspend.
+    pub(crate) fn dispatch_event_publish_hooks(
+        &mut self,
+        iteration: u32,
+        active_hat: &str,
+    ) -> Vec<HookDispatchOutcome> {
+        if !self.hooks.is_enabled() {
+            return Vec::new();
+        }
+
+        let pending_events = match self.pending_events_in_jsonl() {
+            Ok(events) => events,
+            Err(error) => {
+                warn!(
+                    error = %error,
+                    "Failed to inspect unread JSONL events for event.publish boundary"
+                );
+                return Vec::new();
+            }
+        };
+
+        let mut outcomes = Vec::new();
+        for (index, event) in pending_events.iter().enumerate() {
+            let payload_input = self.hooks.payload_input(
+                iteration,
+                self.config.event_loop.max_iterations,
+                HookPayloadContextInput {
+                    active_hat: Some(active_hat.to_string()),
+                    selected_hat: Some(active_hat.to_string()),
+                    event: Some(serde_json::json!({
+                        "topic": event.topic,
+                        "payload": event.payload,
+                        "ts": event.ts,
+                    })),
+                    ..HookPayloadContextInput::default()
+                },
+            );
+            let event_outcomes = self.hooks.dispatch_for_topic(
+                HookPhaseEvent::PreEventPublish,
+                &event.topic,
+                payload_input,
+                &self.diagnostics,
+            );
+            crate::hooks::queue_pending_event_mutations(self, index, &event.topic, &event_outcomes);
+
+            let stop = event_outcomes.iter().any(|outcome| {
+                matches!(
+                    outcome.disposition,
+                    crate::diagnostics::HookDisposition::Block
+                        | crate::diagnostics::HookDisposition::Suspend
+                )
+            });
+            outcomes.extend(event_outcomes);
+            if stop {
+                break;
+            }
+        }
+
+        crate::hooks::apply_hook_mutation_actions(self, &outcomes);
+        outcomes
+    }
+
+    /// Hook steps guarding unread JSONL events before they are read:
+    /// `pre.plan.created` for `plan.*` batches, `pre.human.interact` for a
+    /// pending question, then `pre.event.publish` per event.
+    fn pre_event_hook_steps(&self, active_hat: &str) -> Vec<HookStep> {
+        let hat_context = HookPayloadContextInput {
+            active_hat: Some(active_hat.to_string()),
+            selected_hat: Some(active_hat.to_string()),
+            ..HookPayloadContextInput::default()
+        };
+        let mut steps = Vec::new();
+
+        let has_plan_events = self
+            .has_pending_plan_events_in_jsonl()
+            .inspect_err(|e| {
+                warn!(
+                    error = %e,
+                    "Failed to inspect unread JSONL events for semantic plan.* topics"
+                )
+            })
+            .unwrap_or(false);
+        if has_plan_events {
+            steps.push(HookStep::Phase(
+                HookPhaseEvent::PrePlanCreated,
+                hat_context.clone(),
+            ));
+        }
+
+        let human_interact_context = self
+            .pending_human_interact_context_in_jsonl()
+            .inspect_err(|e| {
+                warn!(
+                    error = %e,
+                    "Failed to inspect unread JSONL events for human.interact boundary"
+                )
+            })
+            .ok()
+            .flatten();
+        if let Some(human_interact) = human_interact_context {
+            steps.push(HookStep::Phase(
+                HookPhaseEvent::PreHumanInteract,
+                HookPayloadContextInput {
+                    human_interact: Some(human_interact),
+                    ..hat_context
+                },
+            ));
+        }
+
+        steps.push(HookStep::EventPublish {
+            active_hat: active_hat.to_string(),
+        });
+        steps
+    }
+
+    /// Hook steps for a batch read from JSONL: `post.human.interact`, then
+    /// `post.plan.created`.
+    fn post_event_hook_steps(active_hat: &str, processed: &ProcessedEvents) -> Vec<HookStep> {
+        let hat_context = HookPayloadContextInput {
+            active_hat: Some(active_hat.to_string()),
+            selected_hat: Some(active_hat.to_string()),
+            ..HookPayloadContextInput::default()
+        };
+        let mut steps = Vec::new();
+
+        if let Some(human_interact) = processed.human_interact_context.clone() {
+            steps.push(HookStep::Phase(
+                HookPhaseEvent::PostHumanInteract,
+                HookPayloadContextInput {
+                    human_interact: Some(human_interact),
+                    ..hat_context.clone()
+                },
+            ));
+        }
+        if processed.had_plan_events {
+            steps.push(HookStep::Phase(
+                HookPhaseEvent::PostPlanCreated,
+                hat_context,
+            ));
+        }
+        steps
+    }
+
+    /// Runs the `pre`/`post` loop-termination hooks at `phase_event` for `reason`.
+    ///
+    /// Returns the reason the loop should terminate with: a suspended hook that
+    /// is stopped or restarted overrides `reason`, and a blocking hook fails
+    /// the loop with [`TerminationReason::HookBlocked`].
+    async fn run_loop_termination_hooks(
+        &mut self,
+        phase_event: HookPhaseEvent,
+        reason: TerminationReason,
+    ) -> TerminationReason {
+        let active_hat = self.get_active_hat_id().as_str().to_string();
+        let outcomes = self.dispatch_lifecycle_hooks(
+            phase_event,
+            self.state.iteration,
+            HookPayloadContextInput {
+                active_hat: Some(active_hat.clone()),
+                selected_hat: Some(active_hat),
+                termination_reason: Some(reason.as_str().to_string()),
+                ..HookPayloadContextInput::default()
+            },
+        );
+
+        match self.hooks.resolve(&outcomes).await {
+            Ok(resolved) => resolved.unwrap_or(reason),
+            Err(error) => {
+                warn!(error = %error, "Loop termination hook boundary failed the loop");
+                TerminationReason::HookBlocked
+            }
+        }
+    }
+
     /// Gets the topics a hat is allowed to publish.
     ///
     /// Used to build retry prompts when the LLM forgets to publish an event.
@@ -861,9 +1377,10 @@ impl EventLoop {
                 self.apply_robot_guidance();
 
                 // Build base prompt and prepend memories + scratchpad + ready tasks
+                let memory_query = self.memory_query(&events_context);
                 let base_prompt = self.ralph.build_prompt(&events_context, &[]);
                 self.ralph.clear_robot_guidance();
-                let with_skills = self.prepend_auto_inject_skills(base_prompt);
+                let with_skills = self.prepend_auto_inject_skills(base_prompt, &memory_query);
                 let with_scratchpad = self.prepend_scratchpad(with_skills);
                 let final_prompt = self.prepend_ready_tasks(with_scratchpad);
 
@@ -877,6 +1394,7 @@ impl EventLoop {
 
                 let mut all_events = Vec::new();
                 let mut system_events = Vec::new();
+                self.state.last_activation_events.clear();
 
                 for id in &all_hat_ids {
                     let pending = self.bus.take_pending(id);
@@ -884,7 +1402,11 @@ impl EventLoop {
                         continue;
                     }
 
-                    let (drop_pending, exhausted_event) = self.check_hat_exhaustion(id, &pending);
+                    let (mut drop_pending, mut exhausted_event) =
+                        self.check_hat_exhaustion(id, &pending);
+                    if !drop_pending {
+                        (drop_pending, exhausted_event) = self.check_hat_budget(id, &pending);
+                    }
                     if drop_pending {
                         // Drop the pending events that would have activated the hat.
                         if let Some(exhausted_event) = exhausted_event {
@@ -894,6 +1416,9 @@ impl EventLoop {
                         continue;
                     }
 
+                    self.state
+                        .last_activation_events
+                        .insert(id.clone(), pending.clone());
                     all_events.extend(pending);
                 }
 
@@ -946,7 +1471,9 @@ impl EventLoop {
 
                 // Clear guidance after active_hats references are no longer needed
                 self.ralph.clear_robot_guidance();
-                let with_skills = self.prepend_auto_inject_skills(base_prompt);
+                let base_prompt = self.prepend_retry_context(base_prompt);
+                let memory_query = self.memory_query(&events_context);
+                let with_skills = self.prepend_auto_inject_skills(base_prompt, &memory_query);
                 let with_scratchpad = self.prepend_scratchpad(with_skills);
                 let final_prompt = self.prepend_ready_tasks(with_scratchpad);
 
@@ -958,6 +1485,10 @@ impl EventLoop {
         // next_hat() always returns "ralph" when custom hats are defined.
         // But we keep this code path for backward compatibility and tests.
         let events = self.bus.take_pending(&hat_id.clone());
+        self.state.last_activation_events.clear();
+        self.state
+            .last_activation_events
+            .insert(hat_id.clone(), events.clone());
         let events_context = events
             .iter()
             .map(|e| Self::format_event(e))
@@ -978,10 +1509,44 @@ impl EventLoop {
             "build_prompt: routing to build_custom_hat() for '{}'",
             hat_id.as_str()
         );
-        Some(
-            self.instruction_builder
-                .build_custom_hat(hat, &events_context),
-        )
+        let prompt = self
+            .instruction_builder
+            .build_custom_hat(hat, &events_context);
+        Some(self.prepend_retry_context(prompt))
+    }
+
+    /// Prepends a `## PREVIOUS ATTEMPT FAILED` section for scheduled retries.
+    fn prepend_retry_context(&mut self, prompt: String) -> String {
+        let retries = std::mem::take(&mut self.state.pending_retries);
+        if retries.is_empty() {
+            return prompt;
+        }
+
+        let mut section = String::from("## PREVIOUS ATTEMPT FAILED\n\n");
+        for retry in &retries {
+            section.push_str(&format!(
+                "Hat `{}` failed attempt {} of {} because: {}\n",
+                retry.hat_id,
+                retry.attempt,
+                retry.max_retries + 1,
+                retry.reason
+            ));
+            if let Some(target) = &retry.escalated_to {
+                section.push_str(&format!("The work has been escalated to {target}.\n"));
+            }
+            if !retry.output_tail.is_empty() {
+                section.push_str(&format!(
+                    "\nLast output of the failed attempt:\n```\n{}\n```\n",
+                    retry.output_tail
+                ));
+            }
+            section.push('\n');
+        }
+        section.push_str(
+            "Address the cause of the failure before retrying; do not repeat the same approach unchanged.\n\n",
+        );
+        section.push_str(&prompt);
+        section
     }
 
     /// Stores guidance payloads, persists them to scratchpad, and prepares them for prompt injection.
@@ -1053,13 +1618,15 @@ impl EventLoop {
         );
     }
 
-    /// Injects cached guidance into the next prompt build.
+    /// Injects cached guidance, plus any one-shot hook guidance, into the next prompt build.
     fn apply_robot_guidance(&mut self) {
-        if self.robot_guidance.is_empty() {
+        if self.robot_guidance.is_empty() && self.hook_guidance.is_empty() {
             return;
         }
 
-        self.ralph.set_robot_guidance(self.robot_guidance.clone());
+        let mut guidance = self.robot_guidance.clone();
+        guidance.append(&mut self.hook_guidance);
+        self.ralph.set_robot_guidance(guidance);
     }
 
     /// Prepends auto-injected skill content to the prompt.
@@ -1068,14 +1635,15 @@ impl EventLoop {
     /// pipeline that handles memories, tools, and any other auto-inject skills.
     ///
     /// Injection order:
-    /// 1. Memory data + ralph-tools skill (special case: loads memory data from store, applies budget)
+    /// 1. Memory data + ralph-tools skill (special case: loads memory data from store,
+    ///    ranks it against `memory_query` and applies the budget)
     /// 2. RObot interaction skill (gated by `robot.enabled`)
     /// 3. Other auto-inject skills from the registry (wrapped in XML tags)
-    fn prepend_auto_inject_skills(&self, prompt: String) -> String {
+    fn prepend_auto_inject_skills(&self, prompt: String, memory_query: &str) -> String {
         let mut prefix = String::new();
 
         // 1. Memory data + ralph-tools skill — special case with data loading
-        self.inject_memories_and_tools_skill(&mut prefix);
+        self.inject_memories_and_tools_skill(&mut prefix, memory_query);
 
         // 2. RObot interaction skill — gated by robot.enabled
         self.inject_robot_skill(&mut prefix);
@@ -1092,14 +1660,29 @@ impl EventLoop {
         prefix
     }
 
+    /// Text that injected memories are ranked against: the objective, the
+    /// active hats' instructions and the triggering events.
+    fn memory_query(&self, events_context: &str) -> String {
+        let mut query = self.ralph.objective().unwrap_or_default().to_string();
+        for hat_id in &self.state.last_active_hat_ids {
+            if let Some(config) = self.registry.get_config(hat_id) {
+                query.push('\n');
+                query.push_str(&config.instructions);
+            }
+        }
+        query.push('\n');
+        query.push_str(events_context);
+        query
+    }
+
     /// Injects memory data and the ralph-tools skill into the prefix.
     ///
-    /// Special case: loads memory entries from the store, applies budget
-    /// truncation, then appends the ralph-tools skill content (which covers
-    /// both tasks and memories CLI usage).
+    /// Special case: loads memory entries from the store, picks the ones most
+    /// relevant to `memory_query` (BM25) within the budget, then appends the
+    /// ralph-tools skill content (which covers both tasks and memories CLI usage).
     /// Memory data is gated by `memories.enabled && memories.inject == Auto`.
     /// The ralph-tools skill is injected when either memories or tasks are enabled.
-    fn inject_memories_and_tools_skill(&self, prefix: &mut String) {
+    fn inject_memories_and_tools_skill(&self, prefix: &mut String, memory_query: &str) {
         let memories_config = &self.config.memories;
 
         // Inject memory DATA if memories are enabled with auto-inject
@@ -1136,22 +1719,39 @@ impl EventLoop {
             if memories.is_empty() {
                 info!("Memory store is empty - no memories to inject");
             } else {
-                let mut memories_content = format_memories_as_markdown(&memories);
+                let selection = select_memories(&memories, memory_query, memories_config.budget);
+                for (rank, scored) in selection.memories.iter().enumerate() {
+                    self.diagnostics.log_orchestration(
+                        self.state.iteration + 1,
+                        "loop",
+                        crate::diagnostics::OrchestrationEvent::MemoryInjected {
+                            memory_id: scored.memory.id.clone(),
+                            score: scored.score,
+                            rank: rank + 1,
+                        },
+                    );
+                }
 
-                if memories_config.budget > 0 {
-                    let original_len = memories_content.len();
-                    memories_content =
-                        truncate_to_budget(&memories_content, memories_config.budget);
+                let selected: Vec<_> = selection
+                    .memories
+                    .iter()
+                    .map(|scored| scored.memory.clone())
+                    .collect();
+                let mut memories_content = format_memories_as_markdown(&selected);
+                if selection.omitted > 0 {
                     debug!(
-                        "Applied budget: {} chars -> {} chars (budget: {})",
-                        original_len,
-                        memories_content.len(),
-                        memories_config.budget
+                        "Applied budget: omitted {} less relevant memories (budget: {})",
+                        selection.omitted, memories_config.budget
                     );
+                    memories_content.push_str(&format!(
+                        "\n\n<!-- {} less relevant memories omitted: budget {} tokens -->",
+                        selection.omitted, memories_config.budget
+                    ));
                 }
 
                 info!(
-                    "Injecting {} memories ({} chars) into prompt",
+                    "Injecting {} of {} memories ({} chars) into prompt",
+                    selected.len(),
                     memories.len(),
                     memories_content.len()
                 );
@@ -1455,9 +2055,7 @@ impl EventLoop {
             return Some(target.clone());
         }
 
-        self.registry
-            .get_for_topic(event.topic.as_str())
-            .map(|hat| hat.id.clone())
+        self.registry.get_for_event(event).map(|hat| hat.id.clone())
     }
 
     fn is_kickoff_or_recovery_event(topic: &str) -> bool {
@@ -1540,6 +2138,71 @@ impl EventLoop {
         )
     }
 
+    fn check_hat_budget(&mut self, hat_id: &HatId, dropped: &[Event]) -> (bool, Option<Event>) {
+        let Some(config) = self.registry.get_config(hat_id) else {
+            return (false, None);
+        };
+        let usage = self
+            .state
+            .hat_usage
+            .get(hat_id)
+            .copied()
+            .unwrap_or_default();
+
+        let mut exceeded = Vec::new();
+        if let Some(max_cost) = config.max_cost_usd
+            && usage.cost_usd >= max_cost
+        {
+            exceeded.push(format!(
+                "- max_cost_usd: {max_cost:.4}\n- cost_usd: {:.4}",
+                usage.cost_usd
+            ));
+        }
+        if let Some(max_tokens) = config.max_tokens
+            && usage.tokens >= max_tokens
+        {
+            exceeded.push(format!(
+                "- max_tokens: {max_tokens}\n- tokens: {}",
+                usage.tokens
+            ));
+        }
+        if exceeded.is_empty() {
+            return (false, None);
+        }
+
+        // Emit only once per hat per run (avoid flooding).
+        if !self.state.budget_exhausted_hats.insert(hat_id.clone()) {
+            return (true, None);
+        }
+
+        let mut dropped_topics: Vec<String> = dropped.iter().map(|e| e.topic.to_string()).collect();
+        dropped_topics.sort();
+
+        let payload = format!(
+            "Hat '{hat}' budget exhausted.\n{limits}\n- dropped_topics:\n  - {topics}",
+            hat = hat_id.as_str(),
+            limits = exceeded.join("\n"),
+            topics = dropped_topics.join("\n  - ")
+        );
+
+        warn!(
+            hat = %hat_id.as_str(),
+            cost_usd = usage.cost_usd,
+            tokens = usage.tokens,
+            max_cost_usd = config.max_cost_usd,
+            max_tokens = config.max_tokens,
+            "Hat budget exhausted (max_cost_usd/max_tokens reached)"
+        );
+
+        (
+            true,
+            Some(Event::new(
+                format!("{}.budget_exhausted", hat_id.as_str()),
+                payload,
+            )),
+        )
+    }
+
     fn record_hat_activations(&mut self, active_hat_ids: &[HatId]) {
         for hat_id in active_hat_ids {
             *self
@@ -1614,7 +2277,8 @@ impl EventLoop {
 
     /// Processes output from a hat execution.
     ///
-    /// Returns the termination reason if the loop should stop.
+    /// Runs `post.hat.complete` hooks first. Returns the termination reason if
+    /// the loop should stop.
     pub fn process_output(
         &mut self,
         hat_id: &HatId,
@@ -1624,6 +2288,20 @@ impl EventLoop {
         self.state.iteration += 1;
         self.state.last_hat = Some(hat_id.clone());
 
+        let hook_hat = self.hook_hat(hat_id).as_str().to_string();
+        self.run_hook_sequence(
+            vec![HookStep::Phase(
+                HookPhaseEvent::PostHatComplete,
+                HookPayloadContextInput {
+                    active_hat: Some(hook_hat.clone()),
+                    selected_hat: Some(hook_hat),
+                    ..HookPayloadContextInput::default()
+                },
+            )],
+            self.state.iteration,
+            HookContinuation::Done,
+        );
+
         // Periodic robot check-in
         if let Some(interval_secs) = self.config.robot.checkin_interval_seconds
             && let Some(ref robot_service) = self.robot_service
@@ -1667,14 +2345,14 @@ impl EventLoop {
             },
         );
 
-        // Track failures
+        // Track failures. Hats with a retry policy absorb their failures.
         if success {
             self.state.consecutive_failures = 0;
-        } else {
+            self.reset_hat_failures(hat_id);
+        } else if !self.apply_retry_policies(hat_id, output) {
             self.state.consecutive_failures += 1;
         }
-
-        let _ = output;
+        self.state.last_failure = None;
 
         // File-modification audit: detect when a hat with disallowed Edit/Write tools
         // modified files. This is hard enforcement — emits a scope_violation event.
@@ -1757,6 +2435,332 @@ impl EventLoop {
         self.state.cumulative_cost += cost;
     }
 
+    /// Records one iteration's usage against the loop total and the hats that ran it.
+    ///
+    /// Usage is split evenly across the hats active in the last iteration
+    /// (Ralph when coordinating). Returns the per-hat share so callers can
+    /// persist it, e.g. to loop history.
+    pub fn add_hat_usage(&mut self, cost_usd: f64, tokens: u64) -> Vec<(HatId, HatUsage)> {
+        self.add_cost(cost_usd);
+
+        let hat_ids = if self.state.last_active_hat_ids.is_empty() {
+            vec![HatId::new("ralph")]
+        } else {
+            self.state.last_active_hat_ids.clone()
+        };
+        let share_count = hat_ids.len() as u64;
+        #[allow(clippy::cast_precision_loss)]
+        let cost_share = cost_usd / share_count as f64;
+
+        hat_ids
+            .into_iter()
+            .enumerate()
+            .map(|(index, hat_id)| {
+                // First hat absorbs the token remainder so totals stay exact.
+                let remainder = if index == 0 { tokens % share_count } else { 0 };
+                let share = HatUsage {
+                    cost_usd: cost_share,
+                    tokens: tokens / share_count + remainder,
+                    iterations: 1,
+                };
+                self.state
+                    .hat_usage
+                    .entry(hat_id.clone())
+                    .or_default()
+                    .add(share);
+                (hat_id, share)
+            })
+            .collect()
+    }
+
+    /// Records the usage of fan-out branches against the loop total and each branch hat.
+    ///
+    /// Unlike [`EventLoop::add_hat_usage`], every branch keeps its own usage,
+    /// which is also logged to diagnostics. Returns the per-hat usage so
+    /// callers can persist it.
+    pub fn add_branch_usage(&mut self, branches: &[BranchOutcome]) -> Vec<(HatId, HatUsage)> {
+        branches
+            .iter()
+            .map(|branch| {
+                let hat_id = HatId::new(&branch.hat);
+                self.add_cost(branch.usage.cost_usd);
+                self.state
+                    .hat_usage
+                    .entry(hat_id.clone())
+                    .or_default()
+                    .add(branch.usage);
+                self.diagnostics.log_orchestration(
+                    self.state.iteration,
+                    &branch.hat,
+                    crate::diagnostics::OrchestrationEvent::FanOutBranch {
+                        status: branch.status.as_str().to_string(),
+                        topics: branch.events.iter().map(|e| e.topic.clone()).collect(),
+                        cost_usd: branch.usage.cost_usd,
+                        tokens: branch.usage.tokens,
+                        output_tail: branch.output_tail.clone(),
+                    },
+                );
+                (hat_id, branch.usage)
+            })
+            .collect()
+    }
+
+    /// Builds the prompt of a fan-out branch: `hat_id` running alone on `events`.
+    ///
+    /// Returns `None` if the hat is not registered.
+    pub fn build_branch_prompt(&self, hat_id: &HatId, events: &[Event]) -> Option<String> {
+        let hat = self.registry.get(hat_id)?;
+        let events_context = events
+            .iter()
+            .map(Self::format_event)
+            .collect::<Vec<_>>()
+            .join("\n");
+        let base_prompt = self.ralph.build_prompt(&events_context, &[hat]);
+        Some(self.prepend_scratchpad(base_prompt))
+    }
+
+    /// Returns the backend failover chain for a hat.
+    ///
+    /// A hat's own `failover:` list replaces `cli.failover`; hats without a
+    /// config (Ralph) use the global chain.
+    pub fn failover_chain(&self, hat_id: &HatId) -> &[HatBackend] {
+        self.registry
+            .get_config(hat_id)
+            .and_then(|config| config.failover.as_deref())
+            .unwrap_or(&self.config.cli.failover)
+    }
+
+    /// Returns the backend a hat has failed over to, if any.
+    ///
+    /// Failover is sticky for the rest of the run: once a hat moves down the
+    /// chain it does not return to its primary backend.
+    pub fn active_failover_backend(&self, hat_id: &HatId) -> Option<&HatBackend> {
+        let position = *self.state.failover_positions.get(hat_id)?;
+        self.failover_chain(hat_id).get(position.checked_sub(1)?)
+    }
+
+    /// Moves a hat to the next backend in its failover chain after a failure.
+    ///
+    /// Returns the backend to retry the same prompt on, or `None` when the
+    /// chain is exhausted (the failure then counts as a regular iteration failure).
+    pub fn advance_failover(
+        &mut self,
+        hat_id: &HatId,
+        failure: FailureClass,
+    ) -> Option<HatBackend> {
+        let position = self
+            .state
+            .failover_positions
+            .get(hat_id)
+            .copied()
+            .unwrap_or(0);
+        let next = self.failover_chain(hat_id).get(position).cloned()?;
+
+        self.state
+            .failover_positions
+            .insert(hat_id.clone(), position + 1);
+        warn!(
+            hat = %hat_id,
+            failure = %failure,
+            backend = %next.to_cli_backend(),
+            "Iteration failed; failing over to next backend"
+        );
+        Some(next)
+    }
+
+    /// Records the failure class of the iteration about to be processed.
+    ///
+    /// Used to explain the failure in retry prompts and `<hat>.failed` events.
+    pub fn record_failure_class(&mut self, failure: Option<FailureClass>) {
+        self.state.last_failure = failure;
+    }
+
+    /// Returns the backend a hat's retries escalated to, if its policy says so.
+    pub fn escalation_backend(&self, hat_id: &HatId) -> Option<&HatBackend> {
+        let policy = self.registry.get_config(hat_id)?.retry.as_ref()?;
+        let failures = *self.state.hat_failure_counts.get(hat_id)?;
+        match &policy.escalate_to {
+            Some(RetryEscalation::Backend { backend }) if failures >= policy.escalate_after() => {
+                Some(backend)
+            }
+            _ => None,
+        }
+    }
+
+    /// Takes the backoff delay requested by a scheduled retry.
+    pub fn take_retry_backoff(&mut self) -> Option<Duration> {
+        self.state.retry_backoff.take()
+    }
+
+    fn reset_hat_failures(&mut self, hat_id: &HatId) {
+        if self.registry.get_config(hat_id).is_some() {
+            self.state.hat_failure_counts.remove(hat_id);
+        } else {
+            for id in &self.state.last_active_hat_ids {
+                self.state.hat_failure_counts.remove(id);
+            }
+        }
+    }
+
+    /// Applies hat retry policies to a failed iteration.
+    ///
+    /// Returns `true` when a policy absorbed the failure: the hat's triggering
+    /// events were re-delivered, handed to its escalation hat, or replaced by
+    /// `<hat>.failed` once retries ran out.
+    fn apply_retry_policies(&mut self, hat_id: &HatId, output: &str) -> bool {
+        let hat_ids = if self.registry.get_config(hat_id).is_some() {
+            vec![hat_id.clone()]
+        } else {
+            self.state.last_active_hat_ids.clone()
+        };
+        let reason = match self.state.last_failure {
+            Some(failure) => format!("the backend failed ({failure})"),
+            None => "the iteration exited unsuccessfully".to_string(),
+        };
+        let output_tail = failure_output_tail(output);
+
+        let mut handled = false;
+        for id in hat_ids {
+            let Some(policy) = self
+                .registry
+                .get_config(&id)
+                .and_then(|config| config.retry.clone())
+            else {
+                continue;
+            };
+            handled = true;
+
+            let failures = {
+                let count = self.state.hat_failure_counts.entry(id.clone()).or_insert(0);
+                *count += 1;
+                *count
+            };
+            let events = self
+                .state
+                .last_activation_events
+                .remove(&id)
+                .unwrap_or_default();
+
+            if let Some(RetryEscalation::Hat { hat: target }) = &policy.escalate_to
+                && failures >= policy.escalate_after()
+            {
+                self.state.hat_failure_counts.remove(&id);
+                self.escalate_to_hat(&id, target, failures, &reason, &events);
+                self.state.pending_retries.push(RetryAttempt {
+                    hat_id: id,
+                    attempt: failures,
+                    max_retries: policy.max_retries,
+                    reason: reason.clone(),
+                    output_tail: output_tail.clone(),
+                    escalated_to: Some(format!("hat `{target}`")),
+                });
+                continue;
+            }
+
+            if failures > policy.max_retries {
+                self.state.hat_failure_counts.remove(&id);
+                self.publish_hat_failed(&id, failures, &reason, &events);
+                continue;
+            }
+
+            warn!(
+                hat = %id,
+                attempt = failures,
+                max_retries = policy.max_retries,
+                "Hat attempt failed; scheduling retry"
+            );
+            let backoff = policy.backoff(failures);
+            self.state.retry_backoff = Some(
+                self.state
+                    .retry_backoff
+                    .map_or(backoff, |current| current.max(backoff)),
+            );
+            let escalated_to = self
+                .escalation_backend(&id)
+                .map(|backend| format!("backend `{}`", backend.to_cli_backend()));
+            for event in events {
+                self.bus.publish(event.with_target(id.clone()));
+            }
+            self.state.pending_retries.push(RetryAttempt {
+                hat_id: id,
+                attempt: failures,
+                max_retries: policy.max_retries,
+                reason: reason.clone(),
+                output_tail: output_tail.clone(),
+                escalated_to,
+            });
+        }
+        handled
+    }
+
+    fn escalate_to_hat(
+        &mut self,
+        hat_id: &HatId,
+        target: &str,
+        failures: u32,
+        reason: &str,
+        events: &[Event],
+    ) {
+        let target_id = HatId::new(target);
+        let Some(topic) = self
+            .registry
+            .get_config(&target_id)
+            .and_then(|config| config.triggers.first().cloned())
+        else {
+            warn!(hat = %hat_id, target, "Escalation hat has no trigger; dropping escalation");
+            return;
+        };
+
+        let mut payload = format!(
+            "Hat '{hat}' failed {failures} time(s) and escalated its work.\n- reason: {reason}",
+            hat = hat_id.as_str()
+        );
+        for event in events {
+            payload.push_str(&format!("\n\n{}", Self::format_event(event)));
+        }
+
+        warn!(hat = %hat_id, target, attempts = failures, "Escalating failed hat to another hat");
+        self.bus.publish(
+            Event::new(topic, payload)
+                .with_source(hat_id.clone())
+                .with_target(target_id),
+        );
+    }
+
+    fn publish_hat_failed(
+        &mut self,
+        hat_id: &HatId,
+        failures: u32,
+        reason: &str,
+        events: &[Event],
+    ) {
+        let mut dropped_topics: Vec<String> = events.iter().map(|e| e.topic.to_string()).collect();
+        dropped_topics.sort();
+
+        let payload = format!(
+            "Hat '{hat}' failed.\n- attempts: {failures}\n- reason: {reason}\n- dropped_topics:\n  - {topics}",
+            hat = hat_id.as_str(),
+            topics = dropped_topics.join("\n  - ")
+        );
+
+        warn!(
+            hat = %hat_id.as_str(),
+            attempts = failures,
+            "Hat failed (retries exhausted)"
+        );
+        self.bus
+            .publish(Event::new(format!("{}.failed", hat_id.as_str()), payload));
+    }
+
+    /// Returns the running usage totals for a hat.
+    pub fn hat_usage(&self, hat_id: &HatId) -> HatUsage {
+        self.state
+            .hat_usage
+            .get(hat_id)
+            .copied()
+            .unwrap_or_default()
+    }
+
     /// Verifies all tasks in scratchpad are complete or cancelled.
     ///
     /// Returns:
@@ -1881,6 +2885,118 @@ impl EventLoop {
         vec![]
     }
 
+    /// Runs the orchestrator-verified backpressure commands, if configured.
+    ///
+    /// Returns `None` when `backpressure` is disabled or declares no commands,
+    /// in which case evidence stays self-reported.
+    fn run_backpressure_checks(&self) -> Option<Vec<BackpressureCheckResult>> {
+        if !self.config.backpressure.is_active() {
+            return None;
+        }
+
+        let runner = BackpressureRunner::new(
+            self.config.backpressure.clone(),
+            &self.config.core.workspace_root,
+        );
+        info!(
+            workspace = %runner.workspace_root().display(),
+            "Running orchestrator-verified backpressure checks"
+        );
+        Some(runner.run())
+    }
+
+    /// Records verified command output and flags claim mismatches in diagnostics.
+    fn record_backpressure_verification(
+        &self,
+        topic: &str,
+        verification: &BackpressureVerification,
+    ) {
+        for entry in crate::diagnostics::BackpressureRunEntry::from_verification(
+            self.state.iteration,
+            topic,
+            verification,
+        ) {
+            self.diagnostics.log_backpressure_run(&entry);
+        }
+
+        for mismatch in &verification.mismatches {
+            warn!(
+                topic,
+                check = %mismatch.check,
+                claimed = mismatch.claimed,
+                actual = mismatch.actual,
+                "Backpressure evidence mismatch: agent claim contradicts verified result"
+            );
+            self.diagnostics.log_orchestration(
+                self.state.iteration,
+                "jsonl",
+                crate::diagnostics::OrchestrationEvent::BackpressureMismatch {
+                    check: mismatch.check.to_string(),
+                    claimed: mismatch.claimed,
+                    actual: mismatch.actual,
+                },
+            );
+        }
+    }
+
+    /// Resolves the quality policy for the hat that emitted the current events.
+    ///
+    /// The top-level `quality:` section is overlaid with the active hat's
+    /// `quality:` overrides, if any.
+    fn active_quality_config(&self) -> QualityConfig {
+        self.state
+            .last_active_hat_ids
+            .first()
+            .and_then(|hat_id| self.config.hats.get(hat_id.as_str()))
+            .and_then(|hat| hat.quality.as_ref())
+            .map_or_else(
+                || self.config.quality.clone(),
+                |overrides| self.config.quality.merged_with(overrides),
+            )
+    }
+
+    fn warn_on_quality_findings(topic: &str, findings: &[QualityFinding]) {
+        for finding in findings {
+            warn!(
+                topic,
+                dimension = %finding.dimension,
+                policy = %finding.policy,
+                detail = %finding.detail,
+                "Quality gate warning (not blocking under configured policy)"
+            );
+        }
+    }
+
+    fn format_quality_findings(findings: &[QualityFinding]) -> String {
+        findings
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Appends the failed gates, with the policy applied to each, to a rejection payload.
+    fn append_quality_findings(payload: &mut String, findings: &[QualityFinding]) {
+        if findings.is_empty() {
+            return;
+        }
+        payload.push_str("\n\nFailed quality gates (configured policy in brackets):");
+        for finding in findings {
+            payload.push_str("\n- ");
+            payload.push_str(&finding.to_string());
+        }
+    }
+
+    /// Appends the verified failure report to a rejection payload.
+    fn append_verification_report(payload: &mut String, verification: &BackpressureVerification) {
+        let report = verification.failure_report();
+        if report.is_empty() {
+            return;
+        }
+        payload.push_str("\n\nOrchestrator-verified results:\n");
+        payload.push_str(report.trim_end());
+    }
+
     fn warn_on_mutation_evidence(&self, evidence: &crate::event_parser::BackpressureEvidence) {
         let threshold = self.config.event_loop.mutation_score_warn_threshold;
 
@@ -2006,6 +3122,38 @@ impl EventLoop {
         );
     }
 
+    /// Applies queued hook mutations to a freshly read JSONL batch.
+    ///
+    /// Rejected events become `event.rejected` events so the reason reaches
+    /// the agent on the next iteration.
+    fn apply_pending_event_mutations(&mut self, events: &mut [crate::event_reader::Event]) {
+        let mutations = std::mem::take(&mut self.pending_event_mutations);
+        for (index, mutation) in mutations {
+            let Some(event) = events.get_mut(index) else {
+                warn!(
+                    index,
+                    "Hook mutation targets an event that is no longer unread; ignoring"
+                );
+                continue;
+            };
+
+            match mutation {
+                PendingEventMutation::Reject { reason } => {
+                    info!(topic = %event.topic, reason = %reason, "Hook rejected event");
+                    event.payload = Some(format!(
+                        "Event '{}' was rejected by a lifecycle hook: {reason}",
+                        event.topic
+                    ));
+                    event.topic = "event.rejected".to_string();
+                }
+                PendingEventMutation::Rewrite { payload } => {
+                    info!(topic = %event.topic, "Hook rewrote event payload");
+                    event.payload = Some(payload);
+                }
+            }
+        }
+    }
+
     /// Processes events from JSONL and routes orphaned events to Ralph.
     ///
     /// Also handles backpressure for malformed JSONL lines by:
@@ -2017,8 +3165,58 @@ impl EventLoop {
     /// semantic `plan.*` topics were published, structured `human.interact`
     /// context/outcome metadata, and whether any were orphans that Ralph should
     /// handle.
+    ///
+    /// Runs the `plan.created`, `human.interact` and `pre.event.publish` hooks
+    /// around the batch. When a hook stops the loop the batch is left unread;
+    /// when one suspends it the result is marked `suspended` and the batch is
+    /// read by the first call after `await_hook_resume`.
     pub fn process_events_from_jsonl(&mut self) -> std::io::Result<ProcessedEvents> {
-        let result = self.event_reader.read_new_events()?;
+        if !self.hooks.is_enabled() {
+            return self.read_events_from_jsonl();
+        }
+
+        let hook_hat = match self.state.last_hat.clone() {
+            Some(hat_id) => self.hook_hat(&hat_id),
+            None => self.get_active_hat_id(),
+        };
+        let hook_hat = hook_hat.as_str().to_string();
+
+        if !std::mem::take(&mut self.event_hooks_cleared) {
+            let steps = self.pre_event_hook_steps(&hook_hat);
+            self.run_hook_sequence(steps, self.state.iteration, HookContinuation::ReadEvents);
+            if !std::mem::take(&mut self.event_hooks_cleared) {
+                return Ok(ProcessedEvents {
+                    had_events: false,
+                    had_plan_events: false,
+                    human_interact_context: None,
+                    has_orphans: false,
+                    suspended: self.hook_suspend.is_some(),
+                });
+            }
+        }
+
+        let processed = self.read_events_from_jsonl()?;
+        let steps = Self::post_event_hook_steps(&hook_hat, &processed);
+        self.run_hook_sequence(steps, self.state.iteration, HookContinuation::Done);
+        Ok(processed)
+    }
+
+    /// Async counterpart of [`process_events_from_jsonl`](Self::process_events_from_jsonl)
+    /// that waits out hooks suspended around the batch instead of leaving it unread.
+    pub async fn await_events_from_jsonl(&mut self) -> std::io::Result<ProcessedEvents> {
+        loop {
+            let processed = self.process_events_from_jsonl()?;
+            self.await_hook_resume().await;
+            if !processed.suspended {
+                return Ok(processed);
+            }
+        }
+    }
+
+    /// Reads unread JSONL events and publishes them to the bus.
+    fn read_events_from_jsonl(&mut self) -> std::io::Result<ProcessedEvents> {
+        let mut result = self.event_reader.read_new_events()?;
+        self.apply_pending_event_mutations(&mut result.events);
 
         // Handle malformed lines with backpressure
         for malformed in &result.malformed {
@@ -2047,6 +3245,7 @@ impl EventLoop {
                 had_plan_events: false,
                 human_interact_context: None,
                 has_orphans: false,
+                suspended: false,
             });
         }
 
@@ -2135,9 +3334,23 @@ impl EventLoop {
 
             if event.topic == "build.done" {
                 // Validate build.done events have backpressure evidence
-                if let Some(evidence) = EventParser::parse_backpressure_evidence(&payload) {
-                    if evidence.all_passed() {
-                        self.warn_on_mutation_evidence(&evidence);
+                if let Some(mut evidence) = EventParser::parse_backpressure_evidence(&payload) {
+                    // Orchestrator-verified gates override the agent's self-reported claims
+                    if let Some(results) = self.run_backpressure_checks() {
+                        evidence.apply_verification(results);
+                    }
+                    if let Some(verification) = &evidence.verification {
+                        self.record_backpressure_verification("build.done", verification);
+                    }
+
+                    let quality = self.active_quality_config();
+                    let findings = evidence.failed_dimensions(&quality);
+
+                    if findings.iter().all(|finding| !finding.is_blocking()) {
+                        Self::warn_on_quality_findings("build.done", &findings);
+                        if quality.mutation.policy != Some(QualityPolicy::Ignore) {
+                            self.warn_on_mutation_evidence(&evidence);
+                        }
                         validated_events.push(Event::new(event.topic.as_str(), &payload));
                     } else {
                         // Evidence present but checks failed - synthesize build.blocked
@@ -2151,6 +3364,10 @@ impl EventLoop {
                             duplication = evidence.duplication_passed,
                             performance = evidence.performance_regression,
                             specs = evidence.specs_verified,
+                            mismatches = evidence
+                                .verification
+                                .as_ref()
+                                .map_or(0, |verification| verification.mismatches.len()),
                             "build.done rejected: backpressure checks failed"
                         );
 
@@ -2174,7 +3391,7 @@ impl EventLoop {
                             "jsonl",
                             crate::diagnostics::OrchestrationEvent::BackpressureTriggered {
                                 reason: format!(
-                                    "backpressure checks failed: tests={}, lint={}, typecheck={}, audit={}, coverage={}, complexity={}, duplication={}, performance={}, specs={}",
+                                    "backpressure checks failed: tests={}, lint={}, typecheck={}, audit={}, coverage={}, complexity={}, duplication={}, performance={}, specs={}; policy: {}",
                                     evidence.tests_passed,
                                     evidence.lint_passed,
                                     evidence.typecheck_passed,
@@ -2183,15 +3400,19 @@ impl EventLoop {
                                     complexity,
                                     evidence.duplication_passed,
                                     performance,
-                                    specs
+                                    specs,
+                                    Self::format_quality_findings(&findings)
                                 ),
                             },
                         );
 
-                        validated_events.push(Event::new(
-                            "build.blocked",
-                            "Backpressure checks failed. Fix tests/lint/typecheck/audit/coverage/complexity/duplication/specs before emitting build.done.",
-                        ));
+                        let mut blocked_payload = "Backpressure checks failed. Fix tests/lint/typecheck/audit/coverage/complexity/duplication/specs before emitting build.done.".to_string();
+                        Self::append_quality_findings(&mut blocked_payload, &findings);
+                        if let Some(verification) = &evidence.verification {
+                            Self::append_verification_report(&mut blocked_payload, verification);
+                        }
+
+                        validated_events.push(Event::new("build.blocked", blocked_payload));
                     }
                 } else {
                     // No evidence found - synthesize build.blocked
@@ -2257,19 +3478,28 @@ impl EventLoop {
                     ));
                 }
             } else if event.topic == "verify.passed" {
-                if let Some(report) = EventParser::parse_quality_report(&payload) {
-                    if report.meets_thresholds() {
+                if let Some(mut report) = EventParser::parse_quality_report(&payload) {
+                    if let Some(results) = self.run_backpressure_checks() {
+                        report.apply_verification(results);
+                    }
+                    if let Some(verification) = &report.verification {
+                        self.record_backpressure_verification("verify.passed", verification);
+                    }
+
+                    let quality = self.active_quality_config();
+                    let findings = report.failed_dimensions(&quality);
+
+                    if findings.iter().all(|finding| !finding.is_blocking()) {
+                        Self::warn_on_quality_findings("verify.passed", &findings);
                         validated_events.push(Event::new(event.topic.as_str(), &payload));
                     } else {
-                        let failed = report.failed_dimensions();
-                        let reason = if failed.is_empty() {
-                            "quality thresholds failed".to_string()
-                        } else {
-                            format!("quality thresholds failed: {}", failed.join(", "))
-                        };
+                        let reason = format!(
+                            "quality thresholds failed: {}",
+                            Self::format_quality_findings(&findings)
+                        );
 
                         warn!(
-                            failed_dimensions = ?failed,
+                            failed_dimensions = %Self::format_quality_findings(&findings),
                             "verify.passed rejected: quality thresholds failed"
                         );
 
@@ -2281,10 +3511,13 @@ impl EventLoop {
                             },
                         );
 
-                        validated_events.push(Event::new(
-                            "verify.failed",
-                            "Quality thresholds failed. Include quality.tests, quality.coverage, quality.lint, quality.audit, quality.mutation, quality.complexity with thresholds in verify.passed payload.",
-                        ));
+                        let mut failed_payload = "Quality thresholds failed. Include quality.tests, quality.coverage, quality.lint, quality.audit, quality.mutation, quality.complexity with thresholds in verify.passed payload.".to_string();
+                        Self::append_quality_findings(&mut failed_payload, &findings);
+                        if let Some(verification) = &report.verification {
+                            Self::append_verification_report(&mut failed_payload, verification);
+                        }
+
+                        validated_events.push(Event::new("verify.failed", failed_payload));
                     }
                 } else {
                     // No quality report found - synthesize verify.failed
@@ -2555,7 +3788,7 @@ impl EventLoop {
                 },
             );
 
-            if !self.registry.has_subscriber(event.topic.as_str()) {
+            if !self.registry.has_subscriber_for(&event) {
                 has_orphans = true;
             }
 
@@ -2581,6 +3814,7 @@ impl EventLoop {
             had_plan_events,
             human_interact_context,
             has_orphans,
+            suspended: false,
         })
     }
 
@@ -2599,12 +3833,53 @@ impl EventLoop {
     /// Per spec: "Published by the orchestrator (not agents) when the loop exits."
     /// This is an observer-only event—hats cannot trigger on it.
     ///
-    /// Returns the event for logging purposes.
-    pub fn publish_terminate_event(&mut self, reason: &TerminationReason) -> Event {
+    /// Waits out any suspended hook first, then runs the
+    /// `pre.loop.complete`/`pre.loop.error` hooks before publishing and the
+    /// matching `post` hooks after. A hook may change the outcome, so this
+    /// returns the reason the loop terminates with alongside the event for
+    /// logging purposes.
+    pub async fn publish_terminate_event(
+        &mut self,
+        reason: TerminationReason,
+    ) -> (TerminationReason, Event) {
+        self.await_hook_resume().await;
+
+        let (pre_phase_event, _) = loop_termination_phase_events(&reason);
+        let reason = self
+            .run_loop_termination_hooks(pre_phase_event, reason)
+            .await;
+
+        let event = self.publish_loop_terminate(&reason);
+
+        let (_, post_phase_event) = loop_termination_phase_events(&reason);
+        let reason = self
+            .run_loop_termination_hooks(post_phase_event, reason)
+            .await;
+        (reason, event)
+    }
+
+    fn publish_loop_terminate(&mut self, reason: &TerminationReason) -> Event {
+        let elapsed = self.state.elapsed();
+
+        if let Some(ref robot_service) = self.robot_service {
+            let outcome = LoopOutcome {
+                reason: reason.as_str().to_string(),
+                success: reason.is_success(),
+                exit_code: reason.exit_code(),
+                iterations: self.state.iteration,
+                elapsed,
+                cumulative_cost: self.state.cumulative_cost,
+            };
+            if let Err(e) = robot_service.send_outcome(&outcome) {
+                warn!(error = %e, "Failed to send robot loop outcome");
+            }
+        }
+
         // Stop the robot service if it was running
         self.stop_robot_service();
 
-        let elapsed = self.state.elapsed();
+        self.compact_memories_on_exit();
+
         let duration_str = format_duration(elapsed);
 
         let payload = format!(
@@ -2634,6 +3909,34 @@ impl EventLoop {
         event
     }
 
+    /// Runs the end-of-loop memory compaction pass when configured.
+    fn compact_memories_on_exit(&self) {
+        let memories_config = &self.config.memories;
+        if !memories_config.enabled || !memories_config.compact_on_exit {
+            return;
+        }
+
+        let workspace_root = &self.config.core.workspace_root;
+        let store = MarkdownMemoryStore::with_default_path(workspace_root);
+        if !store.exists() {
+            return;
+        }
+
+        match crate::memory_compact::compact_memories(
+            &store,
+            &crate::memory_compact::CompactOptions::new(workspace_root),
+        ) {
+            Ok(report) if !report.is_empty() => info!(
+                merged = report.merged_count(),
+                expired = report.expired.len(),
+                journal_id = report.id.as_deref().unwrap_or_default(),
+                "Compacted memories"
+            ),
+            Ok(_) => {}
+            Err(e) => warn!(error = %e, "Failed to compact memories"),
+        }
+    }
+
     /// Returns the robot service's shutdown flag, if active.
     ///
     /// Signal handlers can set this flag to interrupt `wait_for_response()`
@@ -2707,6 +4010,23 @@ pub struct UserPrompt {
     pub text: String,
 }
 
+/// Returns the last few non-empty lines of a failed attempt's output.
+fn failure_output_tail(output: &str) -> String {
+    const TAIL_LINES: usize = 20;
+    const TAIL_CHARS: usize = 2000;
+
+    let lines: Vec<&str> = output
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .collect();
+    let tail = lines[lines.len().saturating_sub(TAIL_LINES)..].join("\n");
+    if tail.len() <= TAIL_CHARS {
+        return tail;
+    }
+    let start = floor_char_boundary(&tail, tail.len() - TAIL_CHARS);
+    tail[start..].to_string()
+}
+
 /// Formats a duration as human-readable string.
 fn format_duration(d: Duration) -> String {
     let total_secs = d.as_secs();
@@ -2743,5 +4063,6 @@ fn termination_status_text(reason: &TerminationReason) -> &'static str {
         TerminationReason::RestartRequested => "Restarting by human request.",
         TerminationReason::WorkspaceGone => "Workspace directory removed externally.",
         TerminationReason::Cancelled => "Cancelled gracefully (human rejection or timeout).",
+        TerminationReason::HookBlocked => "Stopped by a blocking lifecycle hook.",
     }
 }
@@ -281,6 +281,349 @@ hats:
     );
 }
 
+#[test]
+fn test_failover_chain_is_sticky_and_overridable_per_hat() {
+    let yaml = r#"
+cli:
+  backend: claude
+  failover: [gemini, codex]
+hats:
+  builder:
+    name: "Builder"
+    triggers: ["build.task"]
+    publishes: ["build.done"]
+  reviewer:
+    name: "Reviewer"
+    triggers: ["build.done"]
+    publishes: ["review.done"]
+    failover: [kiro]
+  pinned:
+    name: "Pinned"
+    triggers: ["review.done"]
+    publishes: []
+    failover: []
+"#;
+    let config: RalphConfig = serde_yaml::from_str(yaml).unwrap();
+    let mut event_loop = EventLoop::new(config);
+    let builder = HatId::new("builder");
+    let reviewer = HatId::new("reviewer");
+    let pinned = HatId::new("pinned");
+
+    assert_eq!(event_loop.failover_chain(&builder).len(), 2);
+    assert_eq!(event_loop.failover_chain(&HatId::new("ralph")).len(), 2);
+    assert!(event_loop.active_failover_backend(&builder).is_none());
+
+    let next = event_loop.advance_failover(&builder, FailureClass::RateLimit);
+    assert_eq!(next, Some(HatBackend::Named("gemini".to_string())));
+    assert_eq!(
+        event_loop.active_failover_backend(&builder),
+        Some(&HatBackend::Named("gemini".to_string()))
+    );
+
+    let next = event_loop.advance_failover(&builder, FailureClass::Crash);
+    assert_eq!(next, Some(HatBackend::Named("codex".to_string())));
+    assert!(
+        event_loop
+            .advance_failover(&builder, FailureClass::Timeout)
+            .is_none()
+    );
+    // Exhausting the chain keeps the hat on its last backend.
+    assert_eq!(
+        event_loop.active_failover_backend(&builder),
+        Some(&HatBackend::Named("codex".to_string()))
+    );
+
+    // Per-hat chains replace the global one, and an empty list disables failover.
+    assert_eq!(
+        event_loop.advance_failover(&reviewer, FailureClass::Auth),
+        Some(HatBackend::Named("kiro".to_string()))
+    );
+    assert!(
+        event_loop
+            .advance_failover(&pinned, FailureClass::RateLimit)
+            .is_none()
+    );
+}
+
+#[test]
+fn test_fan_out_branches_keep_their_own_usage() {
+    let yaml = r#"
+hats:
+  review_fan_out:
+    name: "Review Fan-out"
+    description: "Runs reviewers in parallel"
+    triggers: ["build.done"]
+    publishes: ["review.joined"]
+    fan_out:
+      hats: [security, performance]
+      join: review.joined
+  security:
+    name: "Security"
+    description: "Reviews security"
+    publishes: ["review.security"]
+    instructions: "Look for injection risks."
+  performance:
+    name: "Performance"
+    description: "Reviews performance"
+    publishes: ["review.performance"]
+"#;
+    let config: RalphConfig = serde_yaml::from_str(yaml).unwrap();
+    let mut event_loop = EventLoop::new(config);
+
+    let prompt = event_loop
+        .build_branch_prompt(
+            &HatId::new("security"),
+            &[Event::new("build.done", "Added the upload endpoint")],
+        )
+        .unwrap();
+    assert!(prompt.contains("Look for injection risks."));
+    assert!(prompt.contains("Event: build.done - Added the upload endpoint"));
+    assert!(
+        event_loop
+            .build_branch_prompt(&HatId::new("missing"), &[])
+            .is_none()
+    );
+
+    let branch = |hat: &str, cost_usd: f64, tokens: u64| BranchOutcome {
+        hat: hat.to_string(),
+        status: crate::fan_out::BranchStatus::Completed,
+        events: Vec::new(),
+        usage: HatUsage {
+            cost_usd,
+            tokens,
+            iterations: 1,
+        },
+        output_tail: String::new(),
+    };
+    let shares = event_loop.add_branch_usage(&[
+        branch("security", 0.5, 4_000),
+        branch("performance", 0.25, 1_000),
+    ]);
+    assert_eq!(shares.len(), 2);
+    assert_eq!(event_loop.hat_usage(&HatId::new("security")).tokens, 4_000);
+    assert!(
+        (event_loop.hat_usage(&HatId::new("performance")).cost_usd - 0.25).abs() < f64::EPSILON
+    );
+    assert!((event_loop.state().cumulative_cost - 0.75).abs() < f64::EPSILON);
+}
+
+#[test]
+fn test_hat_budget_emits_budget_exhausted_event() {
+    let yaml = r#"
+hats:
+  executor:
+    name: "Executor"
+    description: "Implements requested changes"
+    triggers: ["work.start", "review.changes_requested"]
+    publishes: ["implementation.done"]
+  code_reviewer:
+    name: "Code Reviewer"
+    description: "Reviews changes on a pricier model"
+    triggers: ["implementation.done"]
+    publishes: ["review.changes_requested"]
+    max_cost_usd: 1.0
+    max_tokens: 100000
+  escalator:
+    name: "Escalator"
+    description: "Handles over-budget hats"
+    triggers: ["code_reviewer.budget_exhausted"]
+    publishes: []
+"#;
+    let config: RalphConfig = serde_yaml::from_str(yaml).unwrap();
+    let mut event_loop = EventLoop::new(config);
+    let ralph = HatId::new("ralph");
+    let executor_id = HatId::new("executor");
+    let reviewer_id = HatId::new("code_reviewer");
+
+    event_loop
+        .bus
+        .publish(Event::new("work.start", "begin").with_source(ralph.clone()));
+
+    // Executor iteration: usage is attributed to the executor only.
+    let _ = event_loop.build_prompt(&ralph).unwrap();
+    let shares = event_loop.add_hat_usage(0.25, 5_000);
+    assert_eq!(shares.len(), 1);
+    assert_eq!(shares[0].0, executor_id);
+    event_loop
+        .bus
+        .publish(Event::new("implementation.done", "done"));
+
+    // Reviewer iteration burns through its cost cap.
+    let prompt = event_loop.build_prompt(&ralph).unwrap();
+    assert!(!prompt.contains("code_reviewer.budget_exhausted"));
+    event_loop.add_hat_usage(1.5, 20_000);
+    event_loop
+        .bus
+        .publish(Event::new("review.changes_requested", "fix"));
+
+    assert!((event_loop.hat_usage(&reviewer_id).cost_usd - 1.5).abs() < f64::EPSILON);
+    assert_eq!(event_loop.hat_usage(&executor_id).tokens, 5_000);
+    assert!((event_loop.state().cumulative_cost - 1.75).abs() < f64::EPSILON);
+
+    // Executor is still within budget; the next reviewer activation is replaced.
+    let _ = event_loop.build_prompt(&ralph).unwrap();
+    event_loop.add_hat_usage(0.1, 1_000);
+    event_loop
+        .bus
+        .publish(Event::new("implementation.done", "done"));
+
+    let prompt = event_loop.build_prompt(&ralph).unwrap();
+    assert!(
+        prompt.contains("Event: code_reviewer.budget_exhausted"),
+        "Expected code_reviewer.budget_exhausted when max_cost_usd is reached"
+    );
+    assert!(prompt.contains("max_cost_usd: 1.0000"));
+    assert!(
+        event_loop
+            .bus
+            .peek_pending(&HatId::new("escalator"))
+            .is_some_and(|events| events
+                .iter()
+                .any(|e| e.topic.as_str() == "code_reviewer.budget_exhausted")),
+        "Expected code_reviewer.budget_exhausted to be published for escalator"
+    );
+    assert_eq!(
+        *event_loop
+            .state
+            .hat_activation_counts
+            .get(&reviewer_id)
+            .unwrap_or(&0),
+        1,
+        "Over-budget reviewer should not be activated again"
+    );
+}
+
+#[test]
+fn test_hat_retry_policy_retries_with_context_then_emits_failed() {
+    let yaml = r#"
+event_loop:
+  max_consecutive_failures: 2
+hats:
+  builder:
+    name: "Builder"
+    description: "Builds the change"
+    triggers: ["build.task"]
+    publishes: ["build.done"]
+    retry:
+      max_retries: 2
+      backoff_seconds: 5
+      escalate_after: 2
+      escalate_to: { backend: claude }
+  fixer:
+    name: "Fixer"
+    description: "Routes around failed builds"
+    triggers: ["builder.failed"]
+    publishes: []
+"#;
+    let config: RalphConfig = serde_yaml::from_str(yaml).unwrap();
+    let mut event_loop = EventLoop::new(config);
+    let ralph = HatId::new("ralph");
+    let builder = HatId::new("builder");
+
+    event_loop
+        .bus
+        .publish(Event::new("build.task", "implement auth"));
+    let prompt = event_loop.build_prompt(&ralph).unwrap();
+    assert!(!prompt.contains("## PREVIOUS ATTEMPT FAILED"));
+
+    // First failure: the trigger is re-delivered and the failure does not count.
+    event_loop.record_failure_class(Some(FailureClass::Timeout));
+    assert!(
+        event_loop
+            .process_output(&ralph, "compiling...\nerror: timed out", false)
+            .is_none()
+    );
+    assert_eq!(event_loop.state().consecutive_failures, 0);
+    assert_eq!(
+        event_loop.take_retry_backoff(),
+        Some(Duration::from_secs(5))
+    );
+    assert!(event_loop.escalation_backend(&builder).is_none());
+
+    let prompt = event_loop.build_prompt(&ralph).unwrap();
+    assert!(prompt.contains("## PREVIOUS ATTEMPT FAILED"));
+    assert!(
+        prompt
+            .contains("Hat `builder` failed attempt 1 of 3 because: the backend failed (timeout)")
+    );
+    assert!(prompt.contains("error: timed out"));
+    assert!(prompt.contains("implement auth"));
+
+    // Second failure: escalate_after moves the retry to the escalation backend.
+    assert!(event_loop.process_output(&ralph, "crash", false).is_none());
+    assert_eq!(
+        event_loop.take_retry_backoff(),
+        Some(Duration::from_secs(10))
+    );
+    assert_eq!(
+        event_loop.escalation_backend(&builder),
+        Some(&HatBackend::Named("claude".to_string()))
+    );
+    let prompt = event_loop.build_prompt(&ralph).unwrap();
+    assert!(prompt.contains("attempt 2 of 3"));
+    assert!(prompt.contains("escalated to backend `claude`"));
+
+    // Third failure exhausts the retries and routes to `builder.failed`.
+    assert!(event_loop.process_output(&ralph, "crash", false).is_none());
+    assert_eq!(event_loop.state().consecutive_failures, 0);
+    assert!(event_loop.escalation_backend(&builder).is_none());
+    let pending = event_loop.bus.peek_pending(&HatId::new("fixer")).unwrap();
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].topic.as_str(), "builder.failed");
+    assert!(pending[0].payload.contains("attempts: 3"));
+    assert!(pending[0].payload.contains("build.task"));
+    assert!(
+        event_loop
+            .bus
+            .peek_pending(&builder)
+            .is_none_or(|events| events.is_empty())
+    );
+}
+
+#[test]
+fn test_hat_retry_policy_escalates_to_hat() {
+    let yaml = r#"
+hats:
+  builder:
+    name: "Builder"
+    description: "Builds the change"
+    triggers: ["build.task"]
+    publishes: ["build.done"]
+    retry:
+      escalate_to: { hat: senior }
+  senior:
+    name: "Senior"
+    description: "Takes over failed builds"
+    triggers: ["build.escalated"]
+    publishes: ["build.done"]
+"#;
+    let config: RalphConfig = serde_yaml::from_str(yaml).unwrap();
+    let mut event_loop = EventLoop::new(config);
+    let ralph = HatId::new("ralph");
+
+    event_loop
+        .bus
+        .publish(Event::new("build.task", "fix login"));
+    let _ = event_loop.build_prompt(&ralph).unwrap();
+    assert!(event_loop.process_output(&ralph, "boom", false).is_none());
+
+    let pending = event_loop.bus.peek_pending(&HatId::new("senior")).unwrap();
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].topic.as_str(), "build.escalated");
+    assert!(pending[0].payload.contains("fix login"));
+
+    let prompt = event_loop.build_prompt(&ralph).unwrap();
+    assert!(prompt.contains("escalated to hat `senior`"));
+    assert_eq!(
+        event_loop.state().last_active_hat_ids,
+        vec![HatId::new("senior")]
+    );
+
+    // Hats without a policy still count toward max_consecutive_failures.
+    assert!(event_loop.process_output(&ralph, "boom", false).is_none());
+    assert_eq!(event_loop.state().consecutive_failures, 1);
+}
+
 #[test]
 fn test_termination_max_iterations() {
     let yaml = r"
@@ -1041,14 +1384,22 @@ fn test_default_publishes_injects_when_no_events() {
             name: "test-hat".to_string(),
             description: Some("Test hat for default publishes".to_string()),
             triggers: vec!["task.start".to_string()],
+            trigger_conditions: HashMap::new(),
             publishes: vec!["task.done".to_string()],
             instructions: "Test hat".to_string(),
             extra_instructions: vec![],
+            failover: None,
             backend_args: None,
             backend: None,
             default_publishes: Some("task.done".to_string()),
             max_activations: None,
+            max_cost_usd: None,
+            max_tokens: None,
             disallowed_tools: vec![],
+            quality: None,
+            retry: None,
+            subloop: None,
+            fan_out: None,
         },
     );
     config.hats = hats;
@@ -1095,14 +1446,22 @@ fn test_default_publishes_not_injected_when_events_written() {
             name: "test-hat".to_string(),
             description: Some("Test hat for default publishes".to_string()),
             triggers: vec!["task.start".to_string()],
+            trigger_conditions: HashMap::new(),
             publishes: vec!["task.done".to_string()],
             instructions: "Test hat".to_string(),
             extra_instructions: vec![],
+            failover: None,
             backend_args: None,
             backend: None,
             default_publishes: Some("task.done".to_string()),
             max_activations: None,
+            max_cost_usd: None,
+            max_tokens: None,
             disallowed_tools: vec![],
+            quality: None,
+            retry: None,
+            subloop: None,
+            fan_out: None,
         },
     );
     config.hats = hats;
@@ -1235,6 +1594,115 @@ fn test_pending_human_interact_context_in_jsonl_peeks_without_consuming() {
     );
 }
 
+#[test]
+fn test_pending_events_in_jsonl_peeks_without_consuming() {
+    use std::io::Write;
+    use tempfile::tempdir;
+
+    let temp_dir = tempdir().unwrap();
+    let events_path = temp_dir.path().join("events.jsonl");
+
+    let mut event_loop = EventLoop::new(RalphConfig::default());
+    event_loop.event_reader = crate::event_reader::EventReader::new(&events_path);
+
+    let mut file = std::fs::File::create(&events_path).unwrap();
+    writeln!(
+        file,
+        r#"{{"topic":"build.done","payload":"ok","ts":"2024-01-01T00:00:00Z"}}"#
+    )
+    .unwrap();
+    writeln!(
+        file,
+        r#"{{"topic":"release.approved","payload":"v1","ts":"2024-01-01T00:00:01Z"}}"#
+    )
+    .unwrap();
+    file.flush().unwrap();
+
+    let pending = event_loop.pending_events_in_jsonl().unwrap();
+    let topics: Vec<_> = pending.iter().map(|event| event.topic.as_str()).collect();
+    assert_eq!(topics, vec!["build.done", "release.approved"]);
+
+    let processed = event_loop.process_events_from_jsonl().unwrap();
+    assert!(processed.had_events);
+    assert!(event_loop.pending_events_in_jsonl().unwrap().is_empty());
+}
+
+#[test]
+fn test_pending_event_mutations_reject_and_rewrite_unread_events() {
+    use std::io::Write;
+    use tempfile::tempdir;
+
+    let temp_dir = tempdir().unwrap();
+    let events_path = temp_dir.path().join("events.jsonl");
+
+    let mut event_loop = EventLoop::new(RalphConfig::default());
+    event_loop.event_reader = crate::event_reader::EventReader::new(&events_path);
+
+    let mut file = std::fs::File::create(&events_path).unwrap();
+    writeln!(
+        file,
+        r#"{{"topic":"release.approved","payload":"ship it","ts":"2024-01-01T00:00:00Z"}}"#
+    )
+    .unwrap();
+    writeln!(
+        file,
+        r#"{{"topic":"build.task","payload":"original","ts":"2024-01-01T00:00:01Z"}}"#
+    )
+    .unwrap();
+    file.flush().unwrap();
+
+    event_loop.mutate_pending_event(
+        0,
+        PendingEventMutation::Reject {
+            reason: "release window is closed".to_string(),
+        },
+    );
+    event_loop.mutate_pending_event(
+        0,
+        PendingEventMutation::Rewrite {
+            payload: "ignored".to_string(),
+        },
+    );
+    event_loop.mutate_pending_event(
+        1,
+        PendingEventMutation::Rewrite {
+            payload: "rewritten".to_string(),
+        },
+    );
+
+    let processed = event_loop.process_events_from_jsonl().unwrap();
+    assert!(processed.had_events);
+
+    let prompt = event_loop.build_prompt(&HatId::new("ralph")).unwrap();
+    assert!(prompt.contains("event.rejected"), "{prompt}");
+    assert!(
+        prompt.contains(
+            "Event 'release.approved' was rejected by a lifecycle hook: release window is closed"
+        ),
+        "{prompt}"
+    );
+    assert!(prompt.contains("rewritten"), "{prompt}");
+    assert!(!prompt.contains("original"), "{prompt}");
+    assert!(!prompt.contains("ignored"), "{prompt}");
+}
+
+#[test]
+fn test_hook_guidance_applies_to_next_prompt_only() {
+    let mut event_loop = EventLoop::new(RalphConfig::default());
+    let ralph_id = HatId::new("ralph");
+
+    event_loop.append_hook_guidance("Run the migration check first");
+    let prompt = event_loop.build_prompt(&ralph_id).unwrap();
+    assert!(prompt.contains("## ROBOT GUIDANCE"), "{prompt}");
+    assert!(prompt.contains("Run the migration check first"), "{prompt}");
+
+    let prompt_again = event_loop.build_prompt(&ralph_id).unwrap();
+    assert!(
+        !prompt_again.contains("Run the migration check first"),
+        "hook guidance should not persist past the next prompt"
+    );
+}
+
 #[test]
 fn test_process_events_from_jsonl_reports_when_plan_topics_absent() {
     use std::io::Write;
@@ -1291,14 +1759,22 @@ fn test_default_publishes_skipped_when_non_orphan_event_written() {
             name: "hat-a".to_string(),
             description: Some("Hat triggered by task.start".to_string()),
             triggers: vec!["task.start".to_string()],
+            trigger_conditions: HashMap::new(),
             publishes: vec!["task.done".to_string()],
             instructions: "Do the task".to_string(),
             extra_instructions: vec![],
+            failover: None,
             backend_args: None,
             backend: None,
             default_publishes: Some("task.done".to_string()),
             max_activations: None,
+            max_cost_usd: None,
+            max_tokens: None,
             disallowed_tools: vec![],
+            quality: None,
+            retry: None,
+            subloop: None,
+            fan_out: None,
         },
     );
     config.hats = hats;
@@ -1358,14 +1834,22 @@ fn test_default_publishes_not_injected_when_not_configured() {
             name: "test-hat".to_string(),
             description: Some("Test hat for default publishes".to_string()),
             triggers: vec!["task.start".to_string()],
+            trigger_conditions: HashMap::new(),
             publishes: vec!["task.done".to_string()],
             instructions: "Test hat".to_string(),
             extra_instructions: vec![],
+            failover: None,
             backend_args: None,
             backend: None,
             default_publishes: None, // No default configured
             max_activations: None,
+            max_cost_usd: None,
+            max_tokens: None,
             disallowed_tools: vec![],
+            quality: None,
+            retry: None,
+            subloop: None,
+            fan_out: None,
         },
     );
     config.hats = hats;
@@ -2409,6 +2893,63 @@ fn test_scratchpad_injection_empty_file() {
     );
 }
 
+#[test]
+fn test_memory_injection_ranks_by_relevance_within_budget() {
+    use crate::diagnostics::DiagnosticsCollector;
+    use crate::memory::{Memory, MemoryType};
+    use crate::memory_store::MarkdownMemoryStore;
+    use tempfile::TempDir;
+
+    let temp_dir = TempDir::new().unwrap();
+    let store = MarkdownMemoryStore::with_default_path(temp_dir.path());
+    for (memory_type, content, tags) in [
+        (
+            MemoryType::Decision,
+            "Chose JWT tokens over sessions for the auth service",
+            vec!["auth".to_string()],
+        ),
+        (
+            MemoryType::Pattern,
+            "Render admin dashboards with zebra-striped tables",
+            vec!["typescript".to_string()],
+        ),
+        (
+            MemoryType::Fix,
+            "Postgres migrations must run before the integration tests",
+            vec!["database".to_string()],
+        ),
+    ] {
+        store
+            .append(&Memory::new(memory_type, content.to_string(), tags))
+            .unwrap();
+    }
+
+    let mut config = RalphConfig::default();
+    config.core.workspace_root = temp_dir.path().to_path_buf();
+    config.memories.budget = 40;
+    let diagnostics = DiagnosticsCollector::with_enabled(temp_dir.path(), true).unwrap();
+    let session_dir = diagnostics.session_dir().unwrap().to_path_buf();
+
+    let mut event_loop = EventLoop::with_diagnostics(config, diagnostics);
+    event_loop.initialize("Fix the JWT refresh bug in the auth service");
+    let prompt = event_loop.build_prompt(&HatId::new("ralph")).unwrap();
+
+    assert!(prompt.contains("Chose JWT tokens over sessions"));
+    assert!(!prompt.contains("zebra-striped tables"));
+    assert!(!prompt.contains("Postgres migrations"));
+    assert!(prompt.contains("2 less relevant memories omitted: budget 40 tokens"));
+
+    let log = std::fs::read_to_string(session_dir.join("orchestration.jsonl")).unwrap();
+    let injected: Vec<serde_json::Value> = log
+        .lines()
+        .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+        .filter(|entry| entry["event"]["type"] == "memory_injected")
+        .collect();
+    assert_eq!(injected.len(), 1);
+    assert_eq!(injected[0]["event"]["rank"], 1);
+    assert!(injected[0]["event"]["score"].as_f64().unwrap() > 0.0);
+}
+
 #[test]
 fn test_scratchpad_injection_ordering() {
     use tempfile::TempDir;
@@ -2628,6 +3169,96 @@ fn test_build_done_backpressure_rejects_duplication() {
     );
 }
 
+#[test]
+fn test_build_done_verified_backpressure_rejects_false_claim() {
+    use tempfile::tempdir;
+
+    let temp_dir = tempdir().unwrap();
+    let events_path = temp_dir.path().join("events.jsonl");
+
+    let mut config = RalphConfig::default();
+    config.core.workspace_root = temp_dir.path().to_path_buf();
+    config.backpressure.enabled = true;
+    config.backpressure.commands.tests = Some("echo 'test result: FAILED'; exit 1".to_string());
+    config.backpressure.commands.lint = Some("true".to_string());
+    let mut event_loop = EventLoop::new(config);
+    event_loop.event_reader = crate::event_reader::EventReader::new(&events_path);
+
+    let payload = "tests: pass\nlint: pass\ntypecheck: pass\naudit: pass\ncoverage: pass\ncomplexity: 7\nduplication: pass";
+    write_event_to_jsonl(&events_path, "build.done", payload);
+    let _ = event_loop.process_events_from_jsonl();
+
+    let empty = Vec::new();
+    let pending: Vec<Event> = event_loop
+        .bus
+        .hat_ids()
+        .flat_map(|id| event_loop.bus.peek_pending(id).unwrap_or(&empty).clone())
+        .collect();
+
+    let blocked = pending
+        .iter()
+        .find(|e| e.topic.as_str() == "build.blocked")
+        .expect("claimed pass with failing verified tests should be blocked");
+    assert!(
+        blocked
+            .payload
+            .contains("tests: claimed pass, verified fail"),
+        "blocked payload should flag the mismatch. Got: {}",
+        blocked.payload
+    );
+    assert!(
+        blocked.payload.contains("test result: FAILED"),
+        "blocked payload should include verified output. Got: {}",
+        blocked.payload
+    );
+    assert!(
+        !pending.iter().any(|e| e.topic.as_str() == "build.done"),
+        "build.done should not pass through when verified tests fail"
+    );
+}
+
+#[test]
+fn test_build_done_verified_backpressure_accepts_passing_commands() {
+    use tempfile::tempdir;
+
+    let temp_dir = tempdir().unwrap();
+    let events_path = temp_dir.path().join("events.jsonl");
+
+    let mut config = RalphConfig::default();
+    config.core.workspace_root = temp_dir.path().to_path_buf();
+    config.backpressure.enabled = true;
+    config.backpressure.commands.tests = Some("true".to_string());
+    config.backpressure.commands.lint = Some("true".to_string());
+    let mut event_loop = EventLoop::new(config);
+    event_loop.event_reader = crate::event_reader::EventReader::new(&events_path);
+
+    let payload = "tests: pass\nlint: pass\ntypecheck: pass\naudit: pass\ncoverage: pass\ncomplexity: 7\nduplication: pass";
+    write_event_to_jsonl(&events_path, "build.done", payload);
+    let _ = event_loop.process_events_from_jsonl();
+
+    let empty = Vec::new();
+    let pending_topics: Vec<String> = event_loop
+        .bus
+        .hat_ids()
+        .flat_map(|id| {
+            event_loop
+                .bus
+                .peek_pending(id)
+                .unwrap_or(&empty)
+                .iter()
+                .map(|e| e.topic.to_string())
+                .collect::<Vec<_>>()
+        })
+        .collect();
+
+    assert!(
+        pending_topics.contains(&"build.done".to_string()),
+        "build.done with verified passing commands should pass through. Got: {:?}",
+        pending_topics
+    );
+    assert!(!pending_topics.contains(&"build.blocked".to_string()));
+}
+
 #[test]
 fn test_build_done_backpressure_rejects_performance_regression() {
     use tempfile::tempdir;
@@ -2909,6 +3540,106 @@ fn test_verify_passed_backpressure_rejects_failed_thresholds() {
     );
 }
 
+#[test]
+fn test_verify_passed_rejection_reports_applied_quality_policy() {
+    use tempfile::tempdir;
+
+    let temp_dir = tempdir().unwrap();
+    let events_path = temp_dir.path().join("events.jsonl");
+
+    let mut config = RalphConfig::default();
+    config.quality.mutation.policy = Some(crate::config::QualityPolicy::Warn);
+    let mut event_loop = EventLoop::new(config);
+    event_loop.event_reader = crate::event_reader::EventReader::new(&events_path);
+
+    let payload = "quality.tests: pass\nquality.coverage: 60%\nquality.lint: pass\nquality.audit: pass\nquality.mutation: 50%\nquality.complexity: 7";
+    write_event_to_jsonl(&events_path, "verify.passed", payload);
+    let _ = event_loop.process_events_from_jsonl();
+
+    let empty = Vec::new();
+    let failed = event_loop
+        .bus
+        .hat_ids()
+        .flat_map(|id| event_loop.bus.peek_pending(id).unwrap_or(&empty).clone())
+        .find(|e| e.topic.as_str() == "verify.failed")
+        .expect("coverage below threshold should fail verify.passed");
+
+    assert!(
+        failed.payload.contains("coverage: 60.0% < 80% [required]"),
+        "payload should report the required coverage gate. Got: {}",
+        failed.payload
+    );
+    assert!(
+        failed.payload.contains("mutation: 50.0% < 70% [warn]"),
+        "payload should report the warn-only mutation gate. Got: {}",
+        failed.payload
+    );
+}
+
+#[test]
+fn test_verify_passed_uses_hat_quality_overrides() {
+    use tempfile::tempdir;
+
+    let yaml = r#"
+quality:
+  complexity:
+    threshold: 15
+hats:
+  verifier:
+    name: "Verifier"
+    description: "Verifies legacy code"
+    triggers: ["verify.request"]
+    publishes: ["verify.passed", "verify.failed"]
+    quality:
+      coverage:
+        threshold: 50
+      mutation:
+        policy: ignore
+"#;
+    let config: RalphConfig = serde_yaml::from_str(yaml).unwrap();
+    let payload = "quality.tests: pass\nquality.coverage: 60%\nquality.lint: pass\nquality.audit: pass\nquality.complexity: 12";
+
+    let pending_topics = |active_hats: Vec<HatId>| {
+        let temp_dir = tempdir().unwrap();
+        let events_path = temp_dir.path().join("events.jsonl");
+        let mut event_loop = EventLoop::new(config.clone());
+        event_loop.event_reader = crate::event_reader::EventReader::new(&events_path);
+        event_loop.state.last_active_hat_ids = active_hats;
+
+        write_event_to_jsonl(&events_path, "verify.passed", payload);
+        let _ = event_loop.process_events_from_jsonl();
+
+        let empty = Vec::new();
+        event_loop
+            .bus
+            .hat_ids()
+            .flat_map(|id| {
+                event_loop
+                    .bus
+                    .peek_pending(id)
+                    .unwrap_or(&empty)
+                    .iter()
+                    .map(|e| e.topic.to_string())
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let with_override = pending_topics(vec![HatId::new("verifier")]);
+    assert!(
+        !with_override.contains(&"verify.failed".to_string()),
+        "verifier overrides should accept the report. Got: {:?}",
+        with_override
+    );
+
+    let without_override = pending_topics(Vec::new());
+    assert!(
+        without_override.contains(&"verify.failed".to_string()),
+        "global policy should reject the report. Got: {:?}",
+        without_override
+    );
+}
+
 // === RObot Interaction Skill Injection Tests ===
 
 #[test]
@@ -3891,14 +4622,22 @@ fn test_default_publishes_satisfies_required_events_for_completion() {
             name: "planner".to_string(),
             description: Some("Plans work".to_string()),
             triggers: vec!["research.complete".to_string()],
+            trigger_conditions: HashMap::new(),
             publishes: vec!["plan.draft".to_string()],
             instructions: "Plan".to_string(),
             extra_instructions: vec![],
             backend: None,
+            failover: None,
             backend_args: None,
             default_publishes: Some("plan.draft".to_string()),
             max_activations: None,
+            max_cost_usd: None,
+            max_tokens: None,
             disallowed_tools: vec![],
+            quality: None,
+            retry: None,
+            subloop: None,
+            fan_out: None,
         },
     );
     config.hats = hats;
@@ -3945,14 +4684,22 @@ fn test_default_publishes_completion_promise_triggers_termination() {
             name: "FinalCommitter".to_string(),
             description: Some("Verifies all work is complete".to_string()),
             triggers: vec!["all.built".to_string()],
+            trigger_conditions: HashMap::new(),
             publishes: vec!["LOOP_COMPLETE".to_string()],
             instructions: "Verify and complete".to_string(),
             extra_instructions: vec![],
             backend: None,
+            failover: None,
             backend_args: None,
             default_publishes: Some("LOOP_COMPLETE".to_string()),
             max_activations: None,
+            max_cost_usd: None,
+            max_tokens: None,
             disallowed_tools: vec![],
+            quality: None,
+            retry: None,
+            subloop: None,
+            fan_out: None,
         },
     );
     config.hats = hats;
@@ -6,7 +6,11 @@
 //! <event topic="handoff" target="reviewer">payload</event>
 //! ```
 
+use crate::backpressure::{BackpressureCheck, BackpressureCheckResult, BackpressureVerification};
+use crate::config::{QualityConfig, QualityDimension, QualityPolicy};
 use ralph_proto::{Event, HatId};
+use std::collections::HashMap;
+use std::fmt;
 
 /// Strips ANSI escape sequences from a string.
 ///
@@ -83,26 +87,203 @@ pub struct BackpressureEvidence {
     /// `Some(true)` means all spec criteria are satisfied.
     /// `Some(false)` means some spec criteria are unsatisfied — blocks build.done.
     pub specs_verified: Option<bool>,
+    /// Orchestrator-run command results, when `backpressure.enabled` is set.
+    ///
+    /// Verified results replace the agent's claims for the same dimensions.
+    pub verification: Option<BackpressureVerification>,
 }
 
 impl BackpressureEvidence {
-    /// Returns true if all required checks passed.
+    /// Replaces self-reported results with orchestrator-verified ones.
+    ///
+    /// Only positive claims are compared for mismatches: a payload that omits
+    /// a dimension is indistinguishable from one that reports it failing.
+    pub fn apply_verification(&mut self, results: Vec<BackpressureCheckResult>) {
+        let claims: HashMap<BackpressureCheck, bool> = [
+            (BackpressureCheck::Tests, self.tests_passed),
+            (BackpressureCheck::Lint, self.lint_passed),
+            (BackpressureCheck::Typecheck, self.typecheck_passed),
+            (BackpressureCheck::Audit, self.audit_passed),
+            (BackpressureCheck::Coverage, self.coverage_passed),
+        ]
+        .into_iter()
+        .filter(|(_, claimed)| *claimed)
+        .collect();
+
+        let verification = BackpressureVerification::from_results(results, &claims);
+        for result in &verification.results {
+            let slot = match result.check {
+                BackpressureCheck::Tests => &mut self.tests_passed,
+                BackpressureCheck::Lint => &mut self.lint_passed,
+                BackpressureCheck::Typecheck => &mut self.typecheck_passed,
+                BackpressureCheck::Audit => &mut self.audit_passed,
+                BackpressureCheck::Coverage => &mut self.coverage_passed,
+            };
+            *slot = result.passed;
+        }
+        self.verification = Some(verification);
+    }
+
+    /// Returns true if all required checks passed under the built-in quality policy.
     ///
     /// Mutation testing evidence is warning-only and does not affect this result.
     /// Spec verification blocks when explicitly reported as failed (`Some(false)`),
     /// but is optional — omitting it (`None`) does not block.
     pub fn all_passed(&self) -> bool {
-        self.tests_passed
-            && self.lint_passed
-            && self.typecheck_passed
-            && self.audit_passed
-            && self.coverage_passed
-            && self
-                .complexity_score
-                .is_some_and(|value| value <= QualityReport::COMPLEXITY_THRESHOLD)
-            && self.duplication_passed
-            && !matches!(self.performance_regression, Some(true))
-            && !matches!(self.specs_verified, Some(false))
+        self.all_passed_with(&QualityConfig::default())
+    }
+
+    /// Returns true if no dimension with a `required` policy failed.
+    pub fn all_passed_with(&self, quality: &QualityConfig) -> bool {
+        self.failed_dimensions(quality)
+            .iter()
+            .all(|finding| !finding.is_blocking())
+    }
+
+    /// Returns every dimension that missed its gate, tagged with the policy applied.
+    ///
+    /// Ignored dimensions are never reported. Mutation evidence is only
+    /// evaluated here when its policy resolves to `required`; the default
+    /// warn-only handling is reported by the event loop.
+    pub fn failed_dimensions(&self, quality: &QualityConfig) -> Vec<QualityFinding> {
+        let mut gates = QualityGates::new(quality);
+
+        for (dimension, passed) in [
+            (QualityDimension::Tests, self.tests_passed),
+            (QualityDimension::Lint, self.lint_passed),
+            (QualityDimension::Typecheck, self.typecheck_passed),
+            (QualityDimension::Audit, self.audit_passed),
+            (QualityDimension::Coverage, self.coverage_passed),
+            (QualityDimension::Duplication, self.duplication_passed),
+        ] {
+            gates.check(dimension, QualityPolicy::Required, |_| {
+                (!passed).then(|| "not reported as pass".to_string())
+            });
+        }
+
+        gates.check(
+            QualityDimension::Complexity,
+            QualityPolicy::Required,
+            |max| match self.complexity_score {
+                None => Some("missing".to_string()),
+                Some(score) if score > max => Some(format!("{score:.2} > {max}")),
+                Some(_) => None,
+            },
+        );
+        gates.check(
+            QualityDimension::Performance,
+            QualityPolicy::Required,
+            |_| {
+                matches!(self.performance_regression, Some(true))
+                    .then(|| "regression detected".to_string())
+            },
+        );
+        gates.check(QualityDimension::Specs, QualityPolicy::Required, |_| {
+            matches!(self.specs_verified, Some(false)).then(|| "unsatisfied".to_string())
+        });
+
+        if gates.policy(QualityDimension::Mutation, QualityPolicy::Warn) == QualityPolicy::Required
+        {
+            gates.check(
+                QualityDimension::Mutation,
+                QualityPolicy::Warn,
+                |min| match &self.mutants {
+                    None => Some("missing".to_string()),
+                    Some(mutants) => match (mutants.status, mutants.score_percent) {
+                        (MutationStatus::Fail, _) => Some("failed".to_string()),
+                        (MutationStatus::Unknown, _) => Some("status unknown".to_string()),
+                        (_, Some(score)) if score < min => Some(format!("{score:.1}% < {min}%")),
+                        (MutationStatus::Warn, _) => Some("reported as warn".to_string()),
+                        _ => None,
+                    },
+                },
+            );
+        }
+
+        gates.into_findings()
+    }
+}
+
+/// A quality dimension that missed its gate, with the policy that was applied.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QualityFinding {
+    pub dimension: QualityDimension,
+    pub policy: QualityPolicy,
+    /// What failed, e.g. `"62.0% < 80%"` or `"missing"`.
+    pub detail: String,
+}
+
+impl QualityFinding {
+    /// Returns true when the finding rejects the event (`required` policy).
+    pub fn is_blocking(&self) -> bool {
+        self.policy == QualityPolicy::Required
+    }
+}
+
+impl fmt::Display for QualityFinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} [{}]", self.dimension, self.detail, self.policy)
+    }
+}
+
+/// Collects findings while resolving policies and thresholds against defaults.
+struct QualityGates<'a> {
+    quality: &'a QualityConfig,
+    findings: Vec<QualityFinding>,
+}
+
+impl<'a> QualityGates<'a> {
+    fn new(quality: &'a QualityConfig) -> Self {
+        Self {
+            quality,
+            findings: Vec::new(),
+        }
+    }
+
+    fn policy(&self, dimension: QualityDimension, default: QualityPolicy) -> QualityPolicy {
+        self.quality.gate(dimension).policy.unwrap_or(default)
+    }
+
+    fn threshold(&self, dimension: QualityDimension) -> f64 {
+        self.quality
+            .gate(dimension)
+            .threshold
+            .unwrap_or(match dimension {
+                QualityDimension::Coverage => QualityReport::COVERAGE_THRESHOLD,
+                QualityDimension::Mutation => QualityReport::MUTATION_THRESHOLD,
+                QualityDimension::Complexity => QualityReport::COMPLEXITY_THRESHOLD,
+                _ => 0.0,
+            })
+    }
+
+    /// Records a finding when `failure` returns a detail for the resolved threshold.
+    fn check(
+        &mut self,
+        dimension: QualityDimension,
+        default_policy: QualityPolicy,
+        failure: impl FnOnce(f64) -> Option<String>,
+    ) {
+        let policy = self.policy(dimension, default_policy);
+        if policy == QualityPolicy::Ignore || self.has(dimension) {
+            return;
+        }
+        if let Some(detail) = failure(self.threshold(dimension)) {
+            self.findings.push(QualityFinding {
+                dimension,
+                policy,
+                detail,
+            });
+        }
+    }
+
+    fn has(&self, dimension: QualityDimension) -> bool {
+        self.findings
+            .iter()
+            .any(|finding| finding.dimension == dimension)
+    }
+
+    fn into_findings(self) -> Vec<QualityFinding> {
+        self.findings
     }
 }
 
@@ -155,64 +336,117 @@ pub struct QualityReport {
     /// `None` means not reported (optional — does not fail thresholds).
     /// `Some(false)` means spec criteria are unsatisfied — fails thresholds.
     pub specs_verified: Option<bool>,
+    /// Orchestrator-run command results, when `backpressure.enabled` is set.
+    ///
+    /// Verified pass/fail results replace the reported tests/lint/audit values;
+    /// any failing verified command fails the thresholds.
+    pub verification: Option<BackpressureVerification>,
 }
 
 impl QualityReport {
+    /// Default minimum coverage percentage when `quality.coverage.threshold` is unset.
     pub const COVERAGE_THRESHOLD: f64 = 80.0;
+    /// Default minimum mutation score when `quality.mutation.threshold` is unset.
     pub const MUTATION_THRESHOLD: f64 = 70.0;
+    /// Default maximum complexity when `quality.complexity.threshold` is unset.
     pub const COMPLEXITY_THRESHOLD: f64 = 10.0;
 
+    /// Returns true if the report meets the built-in quality policy.
     pub fn meets_thresholds(&self) -> bool {
-        self.tests_passed == Some(true)
-            && self.lint_passed == Some(true)
-            && self.audit_passed == Some(true)
-            && self
-                .coverage_percent
-                .is_some_and(|value| value >= Self::COVERAGE_THRESHOLD)
-            && self
-                .mutation_percent
-                .is_some_and(|value| value >= Self::MUTATION_THRESHOLD)
-            && self
-                .complexity_score
-                .is_some_and(|value| value <= Self::COMPLEXITY_THRESHOLD)
-            && !matches!(self.specs_verified, Some(false))
-    }
-
-    pub fn failed_dimensions(&self) -> Vec<&'static str> {
-        let mut failed = Vec::new();
-
-        if self.tests_passed != Some(true) {
-            failed.push("tests");
-        }
-        if self.lint_passed != Some(true) {
-            failed.push("lint");
-        }
-        if self.audit_passed != Some(true) {
-            failed.push("audit");
-        }
-        if self
-            .coverage_percent
-            .is_none_or(|value| value < Self::COVERAGE_THRESHOLD)
-        {
-            failed.push("coverage");
+        self.meets_thresholds_with(&QualityConfig::default())
+    }
+
+    /// Returns true if no dimension with a `required` policy failed.
+    pub fn meets_thresholds_with(&self, quality: &QualityConfig) -> bool {
+        self.failed_dimensions(quality)
+            .iter()
+            .all(|finding| !finding.is_blocking())
+    }
+
+    /// Replaces reported results with orchestrator-verified ones.
+    ///
+    /// Coverage is compared against the reported percentage meeting the
+    /// threshold; typecheck has no quality field and is only gated.
+    pub fn apply_verification(&mut self, results: Vec<BackpressureCheckResult>) {
+        let claims: HashMap<BackpressureCheck, bool> = [
+            (BackpressureCheck::Tests, self.tests_passed),
+            (BackpressureCheck::Lint, self.lint_passed),
+            (BackpressureCheck::Audit, self.audit_passed),
+            (
+                BackpressureCheck::Coverage,
+                self.coverage_percent
+                    .map(|value| value >= Self::COVERAGE_THRESHOLD),
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(check, claimed)| claimed.map(|claimed| (check, claimed)))
+        .collect();
+
+        let verification = BackpressureVerification::from_results(results, &claims);
+        for result in &verification.results {
+            match result.check {
+                BackpressureCheck::Tests => self.tests_passed = Some(result.passed),
+                BackpressureCheck::Lint => self.lint_passed = Some(result.passed),
+                BackpressureCheck::Audit => self.audit_passed = Some(result.passed),
+                BackpressureCheck::Typecheck | BackpressureCheck::Coverage => {}
+            }
         }
-        if self
-            .mutation_percent
-            .is_none_or(|value| value < Self::MUTATION_THRESHOLD)
-        {
-            failed.push("mutation");
+        self.verification = Some(verification);
+    }
+
+    /// Returns every dimension that missed its gate, tagged with the policy applied.
+    ///
+    /// Ignored dimensions are never reported; `warn` findings do not fail
+    /// [`meets_thresholds_with`](Self::meets_thresholds_with).
+    pub fn failed_dimensions(&self, quality: &QualityConfig) -> Vec<QualityFinding> {
+        let mut gates = QualityGates::new(quality);
+
+        for (dimension, passed) in [
+            (QualityDimension::Tests, self.tests_passed),
+            (QualityDimension::Lint, self.lint_passed),
+            (QualityDimension::Audit, self.audit_passed),
+        ] {
+            gates.check(dimension, QualityPolicy::Required, |_| match passed {
+                Some(true) => None,
+                Some(false) => Some("failed".to_string()),
+                None => Some("missing".to_string()),
+            });
         }
-        if self
-            .complexity_score
-            .is_none_or(|value| value > Self::COMPLEXITY_THRESHOLD)
-        {
-            failed.push("complexity");
+
+        for dimension in [QualityDimension::Coverage, QualityDimension::Mutation] {
+            let percent = match dimension {
+                QualityDimension::Coverage => self.coverage_percent,
+                _ => self.mutation_percent,
+            };
+            gates.check(dimension, QualityPolicy::Required, |min| match percent {
+                None => Some("missing".to_string()),
+                Some(value) if value < min => Some(format!("{value:.1}% < {min}%")),
+                Some(_) => None,
+            });
         }
-        if matches!(self.specs_verified, Some(false)) {
-            failed.push("specs");
+
+        gates.check(
+            QualityDimension::Complexity,
+            QualityPolicy::Required,
+            |max| match self.complexity_score {
+                None => Some("missing".to_string()),
+                Some(score) if score > max => Some(format!("{score:.2} > {max}")),
+                Some(_) => None,
+            },
+        );
+        gates.check(QualityDimension::Specs, QualityPolicy::Required, |_| {
+            matches!(self.specs_verified, Some(false)).then(|| "unsatisfied".to_string())
+        });
+
+        if let Some(verification) = &self.verification {
+            for check in verification.failed_checks() {
+                gates.check(check.dimension(), QualityPolicy::Required, |_| {
+                    Some("verified command failed".to_string())
+                });
+            }
         }
 
-        failed
+        gates.into_findings()
     }
 }
 
@@ -358,6 +592,7 @@ impl EventParser {
                 performance_regression,
                 mutants,
                 specs_verified,
+                verification: None,
             })
         } else {
             None
@@ -536,6 +771,14 @@ impl EventParser {
         }
     }
 
+    /// Parses a payload into the structured value that hat trigger conditions
+    /// are evaluated against: JSON as-is, otherwise `key: value` lines.
+    ///
+    /// Note: ANSI escape codes are stripped before parsing.
+    pub fn parse_structured_payload(payload: &str) -> Option<serde_json::Value> {
+        ralph_proto::structured_payload(&strip_ansi(payload))
+    }
+
     /// Parses quality report evidence from verify.* event payloads.
     ///
     /// Expected format:
@@ -560,6 +803,7 @@ impl EventParser {
             mutation_percent: None,
             complexity_score: None,
             specs_verified: None,
+            verification: None,
         };
         let mut seen = false;
 
@@ -977,6 +1221,20 @@ Still working..."#;
         assert!(!evidence.all_passed());
     }
 
+    #[test]
+    fn test_parse_structured_payload() {
+        let value =
+            EventParser::parse_structured_payload("\x1b[32mverdict: reject\x1b[0m\nscore: 40")
+                .unwrap();
+        assert_eq!(value["verdict"], "reject");
+        assert_eq!(value["score"], "40");
+
+        let value = EventParser::parse_structured_payload(r#"{"verdict": "approve"}"#).unwrap();
+        assert_eq!(value["verdict"], "approve");
+
+        assert!(EventParser::parse_structured_payload("All done").is_none());
+    }
+
     #[test]
     fn test_parse_review_evidence_all_pass() {
         let payload = "tests: pass\nbuild: pass";
@@ -1126,7 +1384,12 @@ Still working..."#;
             !report.meets_thresholds(),
             "specs: fail should fail quality thresholds"
         );
-        assert!(report.failed_dimensions().contains(&"specs"));
+        let failed = report.failed_dimensions(&QualityConfig::default());
+        assert!(
+            failed
+                .iter()
+                .any(|f| f.dimension == QualityDimension::Specs)
+        );
     }
 
     #[test]
@@ -1138,7 +1401,68 @@ Still working..."#;
             report.meets_thresholds(),
             "missing specs should not fail quality thresholds"
         );
-        assert!(!report.failed_dimensions().contains(&"specs"));
+        let failed = report.failed_dimensions(&QualityConfig::default());
+        assert!(
+            !failed
+                .iter()
+                .any(|f| f.dimension == QualityDimension::Specs)
+        );
+    }
+
+    #[test]
+    fn test_quality_report_uses_configured_thresholds() {
+        let payload = "quality.tests: pass\nquality.coverage: 65%\nquality.lint: pass\nquality.audit: pass\nquality.mutation: 71%\nquality.complexity: 12";
+        let report = EventParser::parse_quality_report(payload).unwrap();
+        assert!(!report.meets_thresholds());
+
+        let mut quality = QualityConfig::default();
+        quality.coverage.threshold = Some(60.0);
+        quality.complexity.threshold = Some(15.0);
+        assert!(report.meets_thresholds_with(&quality));
+        assert!(report.failed_dimensions(&quality).is_empty());
+    }
+
+    #[test]
+    fn test_quality_report_reports_applied_policy() {
+        let payload = "quality.tests: pass\nquality.coverage: 62%\nquality.lint: pass\nquality.audit: pass\nquality.complexity: 7";
+        let report = EventParser::parse_quality_report(payload).unwrap();
+
+        let mut quality = QualityConfig::default();
+        quality.coverage.policy = Some(QualityPolicy::Warn);
+        quality.mutation.policy = Some(QualityPolicy::Ignore);
+
+        let failed = report.failed_dimensions(&quality);
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].dimension, QualityDimension::Coverage);
+        assert_eq!(failed[0].to_string(), "coverage: 62.0% < 80% [warn]");
+        assert!(!failed[0].is_blocking());
+        assert!(report.meets_thresholds_with(&quality));
+
+        let strict = report.failed_dimensions(&QualityConfig::default());
+        assert!(
+            strict
+                .iter()
+                .any(|f| f.to_string() == "mutation: missing [required]")
+        );
+    }
+
+    #[test]
+    fn test_backpressure_evidence_respects_quality_policies() {
+        let payload = "tests: pass\nlint: pass\ntypecheck: pass\naudit: pass\ncoverage: fail\ncomplexity: 7\nduplication: pass";
+        let evidence = EventParser::parse_backpressure_evidence(payload).unwrap();
+        assert!(!evidence.all_passed());
+
+        let mut quality = QualityConfig::default();
+        quality.coverage.policy = Some(QualityPolicy::Ignore);
+        assert!(evidence.all_passed_with(&quality));
+        assert!(evidence.failed_dimensions(&quality).is_empty());
+
+        // Mutation is warn-only on build.done unless configured as required
+        quality.mutation.policy = Some(QualityPolicy::Required);
+        let failed = evidence.failed_dimensions(&quality);
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].to_string(), "mutation: missing [required]");
+        assert!(!evidence.all_passed_with(&quality));
     }
 
     #[test]
@@ -0,0 +1,200 @@
+//! Backend failover for failed iterations.
+//!
+//! When an iteration fails because the backend is rate limited, rejects
+//! credentials, crashes, or times out, the loop can retry the same prompt on
+//! the next backend in the failover chain (`cli.failover`, or a hat's own
+//! `failover` list) instead of burning `max_consecutive_failures`.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::LazyLock;
+
+/// Only the tail of the output is inspected, so error strings quoted in the
+/// agent's own work (source code, logs it read) don't trigger a failover.
+const CLASSIFY_TAIL_BYTES: usize = 4096;
+
+const RATE_LIMIT_MARKERS: &[&str] = &[
+    "rate limit",
+    "rate_limit",
+    "ratelimit",
+    "too many requests",
+    "overloaded",
+    "quota exceeded",
+    "resource_exhausted",
+    "usage limit",
+];
+
+const AUTH_MARKERS: &[&str] = &[
+    "unauthorized",
+    "authentication_error",
+    "authentication failed",
+    "invalid api key",
+    "invalid x-api-key",
+    "not logged in",
+    "please run /login",
+    "api key not found",
+];
+
+/// HTTP status codes only count when introduced as a status, so numbers that
+/// happen to appear in the output ("401 passed", line numbers, hashes) don't.
+static RATE_LIMIT_STATUS_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(&status_code_pattern("429|529")).unwrap());
+
+static AUTH_STATUS_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(&status_code_pattern("401")).unwrap());
+
+fn status_code_pattern(codes: &str) -> String {
+    format!(r"\b(?:status(?: code)?|http(?:/[\d.]+)?|api error|error|code)[\s:=]+(?:{codes})\b")
+}
+
+/// Why an iteration failed, as far as backend failover is concerned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FailureClass {
+    /// The provider rejected the request due to rate limits or overload.
+    RateLimit,
+    /// The backend CLI is not authenticated or the credentials were rejected.
+    Auth,
+    /// The backend exceeded its timeout (or idle timeout).
+    Timeout,
+    /// The backend exited unsuccessfully for any other reason.
+    Crash,
+}
+
+impl FailureClass {
+    /// Classifies an iteration result.
+    ///
+    /// Returns `None` for successful iterations. Timeouts take precedence;
+    /// otherwise the output tail is scanned for rate-limit and auth markers,
+    /// falling back to [`FailureClass::Crash`].
+    pub fn classify(success: bool, timed_out: bool, output: &str) -> Option<Self> {
+        if timed_out {
+            return Some(Self::Timeout);
+        }
+        if success {
+            return None;
+        }
+
+        let mut start = output.len().saturating_sub(CLASSIFY_TAIL_BYTES);
+        while !output.is_char_boundary(start) {
+            start += 1;
+        }
+        let tail = output[start..].to_lowercase();
+
+        if RATE_LIMIT_MARKERS
+            .iter()
+            .any(|marker| tail.contains(marker))
+            || RATE_LIMIT_STATUS_RE.is_match(&tail)
+        {
+            Some(Self::RateLimit)
+        } else if AUTH_MARKERS.iter().any(|marker| tail.contains(marker))
+            || AUTH_STATUS_RE.is_match(&tail)
+        {
+            Some(Self::Auth)
+        } else {
+            Some(Self::Crash)
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::RateLimit => "rate_limit",
+            Self::Auth => "auth",
+            Self::Timeout => "timeout",
+            Self::Crash => "crash",
+        }
+    }
+}
+
+impl fmt::Display for FailureClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classify_success_is_none() {
+        assert_eq!(FailureClass::classify(true, false, "all good"), None);
+    }
+
+    #[test]
+    fn classify_timeout_wins() {
+        assert_eq!(
+            FailureClass::classify(false, true, "429 Too Many Requests"),
+            Some(FailureClass::Timeout)
+        );
+    }
+
+    #[test]
+    fn classify_rate_limit_and_overload() {
+        assert_eq!(
+            FailureClass::classify(false, false, "API Error: 429 rate_limit_error"),
+            Some(FailureClass::RateLimit)
+        );
+        assert_eq!(
+            FailureClass::classify(false, false, "{\"type\":\"overloaded_error\"}"),
+            Some(FailureClass::RateLimit)
+        );
+    }
+
+    #[test]
+    fn classify_auth() {
+        assert_eq!(
+            FailureClass::classify(false, false, "Invalid API key · Please run /login"),
+            Some(FailureClass::Auth)
+        );
+    }
+
+    #[test]
+    fn classify_status_codes_need_status_context() {
+        assert_eq!(
+            FailureClass::classify(false, false, "Error: HTTP 429"),
+            Some(FailureClass::RateLimit)
+        );
+        assert_eq!(
+            FailureClass::classify(false, false, "request failed with status code 529"),
+            Some(FailureClass::RateLimit)
+        );
+        assert_eq!(
+            FailureClass::classify(false, false, "HTTP/1.1 401"),
+            Some(FailureClass::Auth)
+        );
+    }
+
+    #[test]
+    fn classify_ignores_bare_status_numbers() {
+        for output in [
+            "test result: FAILED. 401 passed; 1 failed",
+            "error at src/lib.rs:429:5",
+            "commit 5290c1a4293 failed verification",
+        ] {
+            assert_eq!(
+                FailureClass::classify(false, false, output),
+                Some(FailureClass::Crash),
+                "{output}"
+            );
+        }
+    }
+
+    #[test]
+    fn classify_other_failures_as_crash() {
+        assert_eq!(
+            FailureClass::classify(false, false, "panicked at src/main.rs"),
+            Some(FailureClass::Crash)
+        );
+    }
+
+    #[test]
+    fn classify_ignores_markers_outside_the_tail() {
+        let output = format!("rate limit{}", "x".repeat(CLASSIFY_TAIL_BYTES + 10));
+        assert_eq!(
+            FailureClass::classify(false, false, &output),
+            Some(FailureClass::Crash)
+        );
+    }
+}
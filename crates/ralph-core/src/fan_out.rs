@@ -0,0 +1,194 @@
+//! Fan-out/fan-in of hats within a single iteration.
+//!
+//! A hat with a `fan_out` block runs its branch hats concurrently instead of
+//! invoking a backend itself. Each branch publishes into its own events file;
+//! once every branch has finished or timed out, the branches are joined into a
+//! single event whose JSON payload carries each branch's events and usage.
+
+use crate::event_loop::HatUsage;
+use crate::event_reader::{Event, EventReader};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Output kept per branch in the join payload and diagnostics.
+const OUTPUT_TAIL_CHARS: usize = 2000;
+
+/// How a fan-out branch ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BranchStatus {
+    /// The branch's backend exited successfully.
+    Completed,
+    /// The branch's backend failed or could not be started.
+    Failed,
+    /// The branch was still running when the fan-out timeout expired.
+    TimedOut,
+}
+
+impl BranchStatus {
+    /// Returns the status name used in join payloads.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            BranchStatus::Completed => "completed",
+            BranchStatus::Failed => "failed",
+            BranchStatus::TimedOut => "timed_out",
+        }
+    }
+}
+
+/// Result of one fan-out branch.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BranchOutcome {
+    /// Hat that ran the branch.
+    pub hat: String,
+    /// How the branch ended.
+    pub status: BranchStatus,
+    /// Events the branch published.
+    pub events: Vec<Event>,
+    /// Token and cost usage of the branch.
+    pub usage: HatUsage,
+    /// Tail of the branch's output.
+    pub output_tail: String,
+}
+
+impl BranchOutcome {
+    /// Builds a branch outcome, reading the events it published from `events_path`.
+    pub fn collect(
+        hat: impl Into<String>,
+        status: BranchStatus,
+        events_path: &Path,
+        usage: HatUsage,
+        output: &str,
+    ) -> Self {
+        let events = EventReader::new(events_path)
+            .read_new_events()
+            .map(|result| result.events)
+            .unwrap_or_default();
+        Self {
+            hat: hat.into(),
+            status,
+            events,
+            usage,
+            output_tail: output_tail(output),
+        }
+    }
+}
+
+/// Builds the payload of the join event from the branch outcomes.
+///
+/// Branch payloads that are JSON are embedded as JSON; anything else is kept
+/// as a string.
+pub fn join_payload(branches: &[BranchOutcome]) -> serde_json::Value {
+    let entries: Vec<serde_json::Value> = branches
+        .iter()
+        .map(|branch| {
+            let events: Vec<serde_json::Value> = branch
+                .events
+                .iter()
+                .map(|event| {
+                    let payload = event
+                        .payload
+                        .as_deref()
+                        .map_or(serde_json::Value::Null, |p| {
+                            serde_json::from_str(p)
+                                .unwrap_or_else(|_| serde_json::Value::String(p.to_string()))
+                        });
+                    serde_json::json!({ "topic": event.topic, "payload": payload })
+                })
+                .collect();
+            serde_json::json!({
+                "hat": branch.hat,
+                "status": branch.status.as_str(),
+                "events": events,
+                "cost_usd": branch.usage.cost_usd,
+                "tokens": branch.usage.tokens,
+            })
+        })
+        .collect();
+
+    let all_completed = branches
+        .iter()
+        .all(|branch| branch.status == BranchStatus::Completed);
+    serde_json::json!({
+        "complete": all_completed,
+        "branches": entries,
+    })
+}
+
+fn output_tail(output: &str) -> String {
+    let char_count = output.chars().count();
+    if char_count <= OUTPUT_TAIL_CHARS {
+        return output.to_string();
+    }
+    output
+        .chars()
+        .skip(char_count - OUTPUT_TAIL_CHARS)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_collect_reads_branch_events() {
+        let dir = TempDir::new().unwrap();
+        let events_path = dir.path().join("events.jsonl");
+        std::fs::write(
+            &events_path,
+            concat!(
+                r#"{"topic":"review.security","payload":{"verdict":"approve"},"ts":"2026-01-01T00:00:00Z"}"#,
+                "\n",
+                r#"{"topic":"review.note","payload":"check input sizes","ts":"2026-01-01T00:00:01Z"}"#,
+                "\n"
+            ),
+        )
+        .unwrap();
+
+        let usage = HatUsage {
+            cost_usd: 0.25,
+            tokens: 1200,
+            iterations: 1,
+        };
+        let branch = BranchOutcome::collect(
+            "security",
+            BranchStatus::Completed,
+            &events_path,
+            usage,
+            "done",
+        );
+        assert_eq!(branch.events.len(), 2);
+
+        let missing = BranchOutcome::collect(
+            "performance",
+            BranchStatus::TimedOut,
+            &dir.path().join("missing.jsonl"),
+            HatUsage::default(),
+            "",
+        );
+        assert!(missing.events.is_empty());
+
+        let payload = join_payload(&[branch, missing]);
+        assert_eq!(payload["complete"], false);
+        assert_eq!(payload["branches"][0]["hat"], "security");
+        assert_eq!(
+            payload["branches"][0]["events"][0]["payload"]["verdict"],
+            "approve"
+        );
+        assert_eq!(
+            payload["branches"][0]["events"][1]["payload"],
+            "check input sizes"
+        );
+        assert_eq!(payload["branches"][0]["cost_usd"], 0.25);
+        assert_eq!(payload["branches"][1]["status"], "timed_out");
+    }
+
+    #[test]
+    fn test_output_tail_keeps_end_of_output() {
+        let output = "x".repeat(OUTPUT_TAIL_CHARS) + "end";
+        let tail = output_tail(&output);
+        assert_eq!(tail.chars().count(), OUTPUT_TAIL_CHARS);
+        assert!(tail.ends_with("end"));
+    }
+}
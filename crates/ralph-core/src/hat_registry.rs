@@ -1,7 +1,7 @@
 //! Hat registry for managing agent personas.
 
 use crate::config::{HatConfig, RalphConfig};
-use ralph_proto::{Hat, HatId, Topic};
+use ralph_proto::{Event, Hat, HatId, PayloadCondition, Topic};
 use std::collections::{BTreeMap, HashSet};
 
 /// Registry for managing and creating hats from configuration.
@@ -40,6 +40,16 @@ impl HatRegistry {
         let mut hat = Hat::new(id, &config.name);
         hat.description = config.description.clone().unwrap_or_default();
         hat.subscriptions = config.trigger_topics();
+        // Expressions are checked by config validation; unparsable ones are dropped here.
+        hat.conditions = config
+            .trigger_conditions
+            .iter()
+            .filter_map(|(trigger, expression)| {
+                PayloadCondition::parse(expression)
+                    .ok()
+                    .map(|condition| (trigger.clone(), condition))
+            })
+            .collect();
         hat.publishes = config.publish_topics();
         hat.instructions = config.instructions.clone();
         hat
@@ -163,6 +173,22 @@ impl HatRegistry {
         // Fall back to full linear scan (BTreeMap is already sorted by key)
         self.hats.values().find(|hat| hat.is_subscribed_str(topic))
     }
+
+    /// Returns the first hat triggered by the event, honouring payload conditions.
+    ///
+    /// Like `EventBus::publish`, a specific subscription wins over a global wildcard.
+    pub fn get_for_event(&self, event: &Event) -> Option<&Hat> {
+        self.get_for_topic(event.topic.as_str())?;
+        self.hats
+            .values()
+            .find(|hat| hat.is_specifically_triggered_by(event))
+            .or_else(|| self.hats.values().find(|hat| hat.is_triggered_by(event)))
+    }
+
+    /// Returns true if any hat would be triggered by the event.
+    pub fn has_subscriber_for(&self, event: &Event) -> bool {
+        self.get_for_event(event).is_some()
+    }
 }
 
 #[cfg(test)]
@@ -238,6 +264,41 @@ hats:
         assert!(no_hat.is_none());
     }
 
+    #[test]
+    fn test_get_for_event_honours_trigger_conditions() {
+        let yaml = r#"
+hats:
+  fixer:
+    name: "Fixer"
+    triggers: ["review.done"]
+    trigger_conditions:
+      review.done: "$.verdict == reject"
+  shipper:
+    name: "Shipper"
+    triggers: ["review.done"]
+    trigger_conditions:
+      review.done: "$.verdict == approve"
+"#;
+        let config: RalphConfig = serde_yaml::from_str(yaml).unwrap();
+        let registry = HatRegistry::from_config(&config);
+
+        let reject = Event::new("review.done", "verdict: reject\nnotes: missing tests");
+        assert_eq!(
+            registry.get_for_event(&reject).map(|hat| hat.id.as_str()),
+            Some("fixer")
+        );
+
+        let approve = Event::new("review.done", r#"{"verdict": "approve"}"#);
+        assert_eq!(
+            registry.get_for_event(&approve).map(|hat| hat.id.as_str()),
+            Some("shipper")
+        );
+
+        let unknown = Event::new("review.done", "looks fine");
+        assert!(!registry.has_subscriber_for(&unknown));
+        assert!(registry.has_subscriber("review.done"));
+    }
+
     #[test]
     fn test_empty_registry_has_no_subscribers() {
         let config = RalphConfig::default();
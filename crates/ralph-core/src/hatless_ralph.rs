@@ -4,7 +4,7 @@
 
 use crate::config::CoreConfig;
 use crate::hat_registry::HatRegistry;
-use ralph_proto::Topic;
+use ralph_proto::{Hat, Topic};
 use std::collections::HashMap;
 use std::path::Path;
 
@@ -39,6 +39,8 @@ pub struct HatTopology {
 pub struct EventReceiver {
     pub name: String,
     pub description: String,
+    /// Payload condition the event must satisfy to reach this hat.
+    pub condition: Option<String>,
 }
 
 /// Information about a hat for prompt generation.
@@ -46,6 +48,8 @@ pub struct HatInfo {
     pub name: String,
     pub description: String,
     pub subscribes_to: Vec<String>,
+    /// Payload conditions keyed by trigger pattern.
+    pub trigger_conditions: HashMap<String, String>,
     pub publishes: Vec<String>,
     pub instructions: String,
     /// Maps each published event to the hats that receive it.
@@ -55,6 +59,14 @@ pub struct HatInfo {
 }
 
 impl HatInfo {
+    /// Formats a trigger for display, appending its payload condition if any.
+    pub fn trigger_label(&self, trigger: &str) -> String {
+        match self.trigger_conditions.get(trigger) {
+            Some(condition) => format!("{trigger} [when {condition}]"),
+            None => trigger.to_string(),
+        }
+    }
+
     /// Generates an Event Publishing Guide section showing what happens when this hat publishes events.
     ///
     /// Returns `None` if the hat doesn't publish any events.
@@ -75,17 +87,28 @@ impl HatInfo {
         for pub_event in &self.publishes {
             let receivers = self.event_receivers.get(pub_event);
             let receiver_text = match receivers {
-                Some(r) if !r.is_empty() => r
-                    .iter()
-                    .map(|recv| {
-                        if recv.description.is_empty() {
-                            recv.name.clone()
-                        } else {
-                            format!("{} ({})", recv.name, recv.description)
-                        }
-                    })
-                    .collect::<Vec<_>>()
-                    .join(", "),
+                Some(r) if !r.is_empty() => {
+                    let mut text = r
+                        .iter()
+                        .map(|recv| {
+                            let mut label = if recv.description.is_empty() {
+                                recv.name.clone()
+                            } else {
+                                format!("{} ({})", recv.name, recv.description)
+                            };
+                            if let Some(condition) = &recv.condition {
+                                label.push_str(&format!(" when `{condition}`"));
+                            }
+                            label
+                        })
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    // A payload that satisfies no condition falls back to Ralph
+                    if r.iter().all(|recv| recv.condition.is_some()) {
+                        text.push_str(", otherwise Ralph");
+                    }
+                    text
+                }
                 _ => "Ralph (coordinates next steps)".to_string(),
             };
             guide.push_str(&format!(
@@ -116,6 +139,7 @@ impl HatTopology {
                             .map(|h| EventReceiver {
                                 name: h.name.clone(),
                                 description: h.description.clone(),
+                                condition: receiving_condition(h, pub_topic),
                             })
                             .collect();
                         (pub_topic.as_str().to_string(), receivers)
@@ -135,6 +159,11 @@ impl HatTopology {
                         .iter()
                         .map(|t| t.as_str().to_string())
                         .collect(),
+                    trigger_conditions: hat
+                        .conditions
+                        .iter()
+                        .map(|(trigger, condition)| (trigger.clone(), condition.to_string()))
+                        .collect(),
                     publishes: hat
                         .publishes
                         .iter()
@@ -151,6 +180,29 @@ impl HatTopology {
     }
 }
 
+/// Quotes a Mermaid edge label when it contains characters Mermaid treats as syntax.
+fn mermaid_label(label: &str) -> String {
+    if label.contains(['|', '[', ']', '"', '(', ')']) {
+        format!("\"{}\"", label.replace('"', "#quot;"))
+    } else {
+        label.to_string()
+    }
+}
+
+/// Returns the condition guarding delivery of `topic` to `hat`.
+///
+/// `None` when any matching subscription is unconditional.
+fn receiving_condition(hat: &Hat, topic: &Topic) -> Option<String> {
+    let mut condition = None;
+    for sub in hat.subscriptions.iter().filter(|sub| sub.matches(topic)) {
+        match hat.condition_for(sub) {
+            Some(c) => condition = condition.or_else(|| Some(c.to_string())),
+            None => return None,
+        }
+    }
+    condition
+}
+
 impl HatlessRalph {
     /// Creates a new HatlessRalph.
     ///
@@ -210,6 +262,11 @@ impl HatlessRalph {
         self.objective = Some(objective);
     }
 
+    /// Returns the user's original objective, if set.
+    pub fn objective(&self) -> Option<&str> {
+        self.objective.as_deref()
+    }
+
     /// Sets robot guidance messages collected from `human.guidance` events.
     ///
     /// Called by `EventLoop::build_prompt()` before `HatlessRalph::build_prompt()`.
@@ -669,7 +726,12 @@ You MUST continue until all tasks are `[x]` or `[~]`.
 
             // Add all other hats
             for hat in &topology.hats {
-                let subscribes = hat.subscribes_to.join(", ");
+                let subscribes = hat
+                    .subscribes_to
+                    .iter()
+                    .map(|t| hat.trigger_label(t))
+                    .collect::<Vec<_>>()
+                    .join(", ");
                 let publishes = hat.publishes.join(", ");
                 section.push_str(&format!(
                     "| {} | {} | {} | {} |\n",
@@ -763,12 +825,13 @@ You MUST continue until all tasks are `[x]` or `[~]`.
             let node_id = &node_ids[hat.name.as_str()];
             for trigger in &hat.subscribes_to {
                 if ralph_publishes.contains(&trigger.as_str()) {
+                    let label = mermaid_label(&hat.trigger_label(trigger));
                     if node_id == &hat.name {
-                        diagram.push_str(&format!("    Ralph -->|{}| {}\n", trigger, hat.name));
+                        diagram.push_str(&format!("    Ralph -->|{}| {}\n", label, hat.name));
                     } else {
                         diagram.push_str(&format!(
                             "    Ralph -->|{}| {}[{}]\n",
-                            trigger, node_id, hat.name
+                            label, node_id, hat.name
                         ));
                     }
                 }
@@ -794,7 +857,9 @@ You MUST continue until all tasks are `[x]` or `[~]`.
                         let target_id = &node_ids[target_hat.name.as_str()];
                         diagram.push_str(&format!(
                             "    {} -->|{}| {}\n",
-                            source_id, pub_event, target_id
+                            source_id,
+                            mermaid_label(&target_hat.trigger_label(pub_event)),
+                            target_id
                         ));
                     }
                 }
@@ -2317,6 +2382,41 @@ hats:
         );
     }
 
+    #[test]
+    fn test_topology_shows_trigger_conditions() {
+        let yaml = r#"
+hats:
+  reviewer:
+    name: "Reviewer"
+    description: "Reviews changes"
+    triggers: ["review.start"]
+    publishes: ["review.done"]
+  fixer:
+    name: "Fixer"
+    description: "Fixes rejected work"
+    triggers: ["review.done"]
+    trigger_conditions:
+      review.done: "$.verdict == reject || $.score < 50"
+"#;
+        let config: RalphConfig = serde_yaml::from_str(yaml).unwrap();
+        let registry = HatRegistry::from_config(&config);
+        let ralph = HatlessRalph::new("LOOP_COMPLETE", config.core.clone(), &registry, None);
+
+        let coordinating = ralph.build_prompt("", &[]);
+        assert!(coordinating.contains(
+            "| Fixer | review.done [when $.verdict == reject || $.score < 50] |  | Fixes rejected work |"
+        ));
+        assert!(coordinating.contains(
+            "Reviewer -->|\"review.done [when $.verdict == reject || $.score < 50]\"| Fixer"
+        ));
+
+        let reviewer = registry.get(&ralph_proto::HatId::new("reviewer")).unwrap();
+        let prompt = ralph.build_prompt("[review.start] Go", &[reviewer]);
+        assert!(prompt.contains(
+            "`review.done` → Received by: Fixer (Fixes rejected work) when `$.verdict == reject || $.score < 50`, otherwise Ralph"
+        ));
+    }
+
     // === Event Publishing Constraint Tests ===
 
     #[test]
@@ -3,6 +3,7 @@ use crate::config::{
     HooksConfig,
 };
 use chrono::{DateTime, Utc};
+use ralph_proto::Topic;
 use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
 use std::collections::HashMap;
@@ -50,6 +51,21 @@ impl HookEngine {
             .unwrap_or_default()
     }
 
+    /// Resolves hooks for a phase-event whose topic filters match `topic`.
+    ///
+    /// Hooks without topic filters match every topic.
+    #[must_use]
+    pub fn resolve_phase_event_for_topic(
+        &self,
+        phase_event: HookPhaseEvent,
+        topic: &str,
+    ) -> Vec<ResolvedHookSpec> {
+        self.resolve_phase_event(phase_event)
+            .into_iter()
+            .filter(|hook| hook.matches_topic(topic))
+            .collect()
+    }
+
     /// Resolves hooks by phase-event string key.
     ///
     /// Unknown phase-event keys return an empty list.
@@ -96,6 +112,8 @@ impl HookEngine {
             selected_task,
             termination_reason,
             human_interact,
+            event: event_context,
+            merge,
             metadata,
         } = context;
 
@@ -122,6 +140,8 @@ impl HookEngine {
                 selected_task,
                 termination_reason,
                 human_interact,
+                event: event_context,
+                merge,
             },
             metadata: HookPayloadMetadata {
                 accumulated: metadata,
@@ -150,9 +170,20 @@ pub struct ResolvedHookSpec {
     pub on_error: HookOnError,
     pub suspend_mode: HookSuspendMode,
     pub mutate: HookMutationConfig,
+    pub topics: Vec<String>,
 }
 
 impl ResolvedHookSpec {
+    /// Returns whether the hook's topic filters (if any) match `topic`.
+    #[must_use]
+    pub fn matches_topic(&self, topic: &str) -> bool {
+        self.topics.is_empty()
+            || self
+                .topics
+                .iter()
+                .any(|pattern| Topic::new(pattern).matches_str(topic))
+    }
+
     fn from_spec(
         phase_event: HookPhaseEvent,
         declaration_order: usize,
@@ -171,6 +202,7 @@ impl ResolvedHookSpec {
             on_error: spec.on_error.unwrap_or(HookOnError::Warn),
             suspend_mode: spec.suspend_mode.unwrap_or(defaults.suspend_mode),
             mutate: spec.mutate.clone(),
+            topics: spec.topics.clone(),
         }
     }
 }
@@ -196,6 +228,8 @@ pub struct HookPayloadContextInput {
     pub selected_task: Option<String>,
     pub termination_reason: Option<String>,
     pub human_interact: Option<Value>,
+    pub event: Option<Value>,
+    pub merge: Option<Value>,
     pub metadata: Map<String, Value>,
 }
 
@@ -239,6 +273,10 @@ pub struct HookPayloadContext {
     pub selected_task: Option<String>,
     pub termination_reason: Option<String>,
     pub human_interact: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub event: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub merge: Option<Value>,
 }
 
 /// Mutable metadata payload block.
@@ -265,6 +303,7 @@ mod tests {
             on_error: Some(HookOnError::Warn),
             suspend_mode: None,
             mutate: HookMutationConfig::default(),
+            topics: Vec::new(),
             extra: HashMap::new(),
         }
     }
@@ -390,6 +429,7 @@ mod tests {
             termination_reason: None,
             human_interact: Some(json!({"question": "Proceed?"})),
             metadata,
+            ..HookPayloadContextInput::default()
         };
 
         let payload = engine.build_payload_with_timestamp(
@@ -456,4 +496,57 @@ mod tests {
                 .is_empty()
         );
     }
+
+    #[test]
+    fn resolve_phase_event_for_topic_applies_topic_filters() {
+        let mut release_gate = hook_spec("release-gate");
+        release_gate.topics = vec!["release.*".to_string(), "deploy.requested".to_string()];
+
+        let mut events = HashMap::new();
+        events.insert(
+            HookPhaseEvent::PreEventPublish,
+            vec![hook_spec("audit-all"), release_gate],
+        );
+
+        let engine = HookEngine::new(&hooks_config(events));
+
+        let names = |topic: &str| {
+            engine
+                .resolve_phase_event_for_topic(HookPhaseEvent::PreEventPublish, topic)
+                .into_iter()
+                .map(|hook| hook.name)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(names("release.approved"), vec!["audit-all", "release-gate"]);
+        assert_eq!(names("deploy.requested"), vec!["audit-all", "release-gate"]);
+        assert_eq!(names("build.done"), vec!["audit-all"]);
+    }
+
+    #[test]
+    fn build_payload_includes_event_and_merge_context_only_when_present() {
+        let engine = HookEngine::new(&hooks_config(HashMap::new()));
+        let mut input = payload_input();
+        input.context.event = Some(json!({"topic": "release.approved", "payload": "v1.2.0"}));
+
+        let payload = engine.build_payload_with_timestamp(
+            HookPhaseEvent::PreEventPublish,
+            input,
+            fixed_time(21, 49, 0),
+        );
+        assert_eq!(payload.phase, "pre");
+        assert_eq!(payload.event, "event.publish");
+
+        let value = serde_json::to_value(&payload).expect("serialize payload");
+        assert_eq!(value["context"]["event"]["topic"], "release.approved");
+        assert!(value["context"].get("merge").is_none());
+
+        let payload = engine.build_payload_with_timestamp(
+            HookPhaseEvent::PreMerge,
+            payload_input(),
+            fixed_time(21, 50, 0),
+        );
+        assert_eq!(payload.phase, "pre");
+        assert_eq!(payload.event, "merge");
+    }
 }
@@ -42,7 +42,7 @@ pub struct HookRunRequest {
 }
 
 /// Captured hook stream output with truncation metadata.
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct HookStreamOutput {
     /// Captured UTF-8 output text.
     pub content: String,
@@ -2,6 +2,7 @@
 
 mod engine;
 mod executor;
+mod orchestrator;
 mod suspend_state;
 
 pub use crate::config::{
@@ -17,6 +18,13 @@ pub use executor::{
     HookExecutor, HookExecutorContract, HookExecutorError, HookRunRequest, HookRunResult,
     HookStreamOutput,
 };
+pub use orchestrator::{
+    HookBoundaryError, HookDispatchFailure, HookDispatchOutcome, HookOrchestrator,
+    loop_termination_phase_events,
+};
+pub(crate) use orchestrator::{
+    apply_hook_mutation_actions, fail_if_blocking_outcomes, queue_pending_event_mutations,
+};
 pub use suspend_state::{
     SUSPEND_STATE_SCHEMA_VERSION, SuspendLifecycleState, SuspendStateRecord, SuspendStateStore,
     SuspendStateStoreError,
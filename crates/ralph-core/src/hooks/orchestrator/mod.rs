@@ -0,0 +1,1556 @@
+//! Lifecycle hook orchestration shared by every loop embedder.
+//!
+//! [`HookOrchestrator`] resolves the hooks for a phase-event, runs them through
+//! the executor, applies `on_error`/`suspend_mode` policies, parses mutation
+//! output and records hook-run telemetry. `EventLoop` owns one orchestrator and
+//! runs each phase-event from its own lifecycle methods (`initialize`,
+//! `next_hat`, `process_output`, `process_events_from_jsonl` and
+//! `publish_terminate_event`), so the CLI, daemon-started loops and the bench
+//! harness cannot diverge on hook semantics. A suspending hook parks the step
+//! inside `EventLoop`; the async embedder awaits
+//! `EventLoop::await_hook_resume` so the wait never blocks the runtime.
+
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use tracing::{debug, error, info, warn};
+
+use super::{
+    HookEngine, HookExecutor, HookExecutorContract, HookMutationConfig, HookOnError,
+    HookPayloadBuilderInput, HookPayloadContextInput, HookPhaseEvent, HookRunRequest,
+    HookRunResult, HookSuspendMode, HooksConfig, ResolvedHookSpec, SuspendStateRecord,
+    SuspendStateStore, SuspendStateStoreError,
+};
+use crate::diagnostics::{
+    DiagnosticsCollector, HookDisposition, HookMutationTelemetry, HookRunTelemetryEntry,
+};
+use crate::event_loop::{EventLoop, PendingEventMutation, TerminationReason};
+
+/// Result of running one hook at a phase-event boundary.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HookDispatchOutcome {
+    /// Phase-event the hook ran for.
+    pub phase_event: HookPhaseEvent,
+    /// Hook name from config.
+    pub hook_name: String,
+    /// Disposition after applying `on_error` and any suspend retries.
+    pub disposition: HookDisposition,
+    /// Suspend policy the hook was configured with.
+    pub suspend_mode: HookSuspendMode,
+    /// Why the hook did not pass, if it did not.
+    pub failure: Option<HookDispatchFailure>,
+    pub(crate) mutation_parse_outcome: HookMutationParseOutcome,
+}
+
+/// Why a hook run did not pass.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HookDispatchFailure {
+    /// The hook exited non-zero or timed out.
+    HookRunFailed {
+        exit_code: Option<i32>,
+        timed_out: bool,
+    },
+    /// The hook could not be launched.
+    HookExecutionError { message: String },
+    /// The hook passed but its mutation stdout was rejected.
+    InvalidMutationOutput { message: String },
+}
+
+/// Errors that stop a loop at a lifecycle hook boundary.
+#[derive(Debug, thiserror::Error)]
+pub enum HookBoundaryError {
+    /// A hook with `on_error: block` failed.
+    #[error("{reason}")]
+    Blocked { reason: String },
+
+    /// The suspend-state record could not be written before waiting.
+    #[error("failed to persist suspend-state for hook '{hook_name}' at '{phase_event}': {source}")]
+    PersistSuspendState {
+        hook_name: String,
+        phase_event: HookPhaseEvent,
+        #[source]
+        source: SuspendStateStoreError,
+    },
+
+    /// Resume signals or suspend-state could not be read or cleared while suspended.
+    #[error("failed to handle suspend signals: {0}")]
+    SuspendSignal(#[source] SuspendStateStoreError),
+
+    /// The stop signal could not be consumed while suspended.
+    #[error("I/O error while consuming stop signal at {path}: {source}")]
+    StopSignal {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+}
+
+/// Runs lifecycle hooks for one loop.
+///
+/// Holds the state hooks share across phase-events: the loop identity stamped
+/// into payloads, the metadata accumulated from mutation output and the
+/// suspend-state store used by `suspend` dispositions.
+#[derive(Debug, Clone)]
+pub struct HookOrchestrator {
+    enabled: bool,
+    loop_id: String,
+    is_primary: bool,
+    workspace: PathBuf,
+    repo_root: PathBuf,
+    engine: HookEngine,
+    executor: HookExecutor,
+    suspend_state_store: SuspendStateStore,
+    accumulated_metadata: serde_json::Map<String, serde_json::Value>,
+}
+
+impl HookOrchestrator {
+    /// Creates an orchestrator for a loop running in `workspace`.
+    ///
+    /// Dispatch is a no-op unless hooks are enabled and at least one
+    /// phase-event has hooks configured.
+    pub fn new(
+        config: &HooksConfig,
+        loop_id: impl Into<String>,
+        is_primary: bool,
+        workspace: impl Into<PathBuf>,
+        repo_root: impl Into<PathBuf>,
+    ) -> Self {
+        let workspace = workspace.into();
+        Self {
+            enabled: config.enabled && !config.events.is_empty(),
+            loop_id: loop_id.into(),
+            is_primary,
+            suspend_state_store: SuspendStateStore::new(&workspace),
+            workspace,
+            repo_root: repo_root.into(),
+            engine: HookEngine::new(config),
+            executor: HookExecutor::new(),
+            accumulated_metadata: serde_json::Map::new(),
+        }
+    }
+
+    /// Returns true when dispatch will run hooks.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Returns the loop ID stamped into hook payloads and suspend-state.
+    pub fn loop_id(&self) -> &str {
+        &self.loop_id
+    }
+
+    /// Sets the loop ID stamped into hook payloads and suspend-state.
+    pub fn set_loop_id(&mut self, loop_id: impl Into<String>) {
+        self.loop_id = loop_id.into();
+    }
+
+    /// Returns the metadata accumulated from hook mutation output so far.
+    pub fn accumulated_metadata(&self) -> &serde_json::Map<String, serde_json::Value> {
+        &self.accumulated_metadata
+    }
+
+    /// Returns the suspend-state store for this loop's workspace.
+    pub fn suspend_state_store(&self) -> &SuspendStateStore {
+        &self.suspend_state_store
+    }
+
+    /// Builds a payload input stamped with the loop identity and accumulated
+    /// metadata. Any metadata already set on `context` is replaced.
+    pub fn payload_input(
+        &self,
+        iteration_current: u32,
+        iteration_max: u32,
+        context: HookPayloadContextInput,
+    ) -> HookPayloadBuilderInput {
+        HookPayloadBuilderInput {
+            loop_id: self.loop_id.clone(),
+            is_primary: self.is_primary,
+            workspace: self.workspace.clone(),
+            repo_root: self.repo_root.clone(),
+            pid: std::process::id(),
+            iteration_current,
+            iteration_max,
+            context: HookPayloadContextInput {
+                metadata: self.accumulated_metadata.clone(),
+                ..context
+            },
+        }
+    }
+
+    /// Runs the hooks configured for `phase_event` in declaration order and
+    /// merges their metadata mutations into the accumulator.
+    pub fn dispatch(
+        &mut self,
+        phase_event: HookPhaseEvent,
+        payload_input: HookPayloadBuilderInput,
+        telemetry: &DiagnosticsCollector,
+    ) -> Vec<HookDispatchOutcome> {
+        let outcomes = dispatch_phase_event_hooks(
+            telemetry,
+            self.enabled,
+            &self.loop_id,
+            &self.engine,
+            &self.executor,
+            phase_event,
+            payload_input,
+        );
+        merge_accumulated_hook_metadata_from_outcomes(&mut self.accumulated_metadata, &outcomes);
+        outcomes
+    }
+
+    /// Like [`dispatch`](Self::dispatch), but runs only hooks whose topic
+    /// filters match `topic`.
+    pub fn dispatch_for_topic(
+        &mut self,
+        phase_event: HookPhaseEvent,
+        topic: &str,
+        payload_input: HookPayloadBuilderInput,
+        telemetry: &DiagnosticsCollector,
+    ) -> Vec<HookDispatchOutcome> {
+        if !self.enabled {
+            return Vec::new();
+        }
+
+        let resolved_hooks = self
+            .engine
+            .resolve_phase_event_for_topic(phase_event, topic);
+        let outcomes = dispatch_resolved_hooks(
+            telemetry,
+            &self.loop_id,
+            &self.engine,
+            &self.executor,
+            phase_event,
+            resolved_hooks,
+            payload_input,
+        );
+        merge_accumulated_hook_metadata_from_outcomes(&mut self.accumulated_metadata, &outcomes);
+        outcomes
+    }
+
+    /// Decides whether the loop may cross the boundary the outcomes came from.
+    ///
+    /// Fails when a hook blocked. When a hook suspended, persists suspend-state
+    /// and waits for a resume, stop or restart signal; stop and restart resolve
+    /// to the termination reason the loop should exit with. The returned future
+    /// owns its inputs, so callers may keep using the loop while awaiting it.
+    pub fn resolve(
+        &self,
+        outcomes: &[HookDispatchOutcome],
+    ) -> impl Future<Output = Result<Option<TerminationReason>, HookBoundaryError>> + Send + use<>
+    {
+        let blocked = fail_if_blocking_outcomes(outcomes);
+        let outcomes = outcomes.to_vec();
+        let loop_id = self.loop_id.clone();
+        let suspend_state_store = self.suspend_state_store.clone();
+
+        async move {
+            blocked?;
+            wait_for_resume_if_suspended(&outcomes, &loop_id, &suspend_state_store).await
+        }
+    }
+
+    /// Blocking variant of [`resolve`](Self::resolve) for callers that run
+    /// outside an async runtime, such as the merge executor.
+    pub fn resolve_blocking(
+        &self,
+        outcomes: &[HookDispatchOutcome],
+    ) -> Result<Option<TerminationReason>, HookBoundaryError> {
+        fail_if_blocking_outcomes(outcomes)?;
+        block_for_resume_if_suspended(outcomes, &self.loop_id, &self.suspend_state_store)
+    }
+}
+
+/// Maps a termination reason to its `(pre, post)` lifecycle phase-events.
+pub fn loop_termination_phase_events(
+    reason: &TerminationReason,
+) -> (HookPhaseEvent, HookPhaseEvent) {
+    if reason.is_success() {
+        (
+            HookPhaseEvent::PreLoopComplete,
+            HookPhaseEvent::PostLoopComplete,
+        )
+    } else {
+        (HookPhaseEvent::PreLoopError, HookPhaseEvent::PostLoopError)
+    }
+}
+
+const RETRY_BACKOFF_DELAYS_MS: [u64; 3] = [100, 200, 400];
+const RETRY_BACKOFF_SIGNAL_POLL_INTERVAL_MS: u64 = 100;
+const SUSPEND_WAIT_SIGNAL_POLL_INTERVAL_MS: u64 = 250;
+const HOOK_MUTATION_PAYLOAD_METADATA_KEY: &str = "metadata";
+const HOOK_MUTATION_PAYLOAD_REJECT_KEY: &str = "reject";
+const HOOK_MUTATION_PAYLOAD_REWRITE_KEY: &str = "rewrite";
+const HOOK_MUTATION_PAYLOAD_PUBLISH_KEY: &str = "publish";
+const HOOK_MUTATION_PAYLOAD_GUIDANCE_KEY: &str = "guidance";
+const HOOK_MUTATION_PAYLOAD_KEYS: [&str; 5] = [
+    HOOK_MUTATION_PAYLOAD_METADATA_KEY,
+    HOOK_MUTATION_PAYLOAD_REJECT_KEY,
+    HOOK_MUTATION_PAYLOAD_REWRITE_KEY,
+    HOOK_MUTATION_PAYLOAD_PUBLISH_KEY,
+    HOOK_MUTATION_PAYLOAD_GUIDANCE_KEY,
+];
+const HOOK_MUTATION_METADATA_NAMESPACE_KEY: &str = "hook_metadata";
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum HookMutationParseOutcome {
+    Disabled,
+    Parsed {
+        namespaced_metadata: serde_json::Map<String, serde_json::Value>,
+        actions: HookMutationActions,
+    },
+    Invalid(HookMutationParseError),
+}
+
+/// Structured actions a hook may return next to (or instead of) metadata.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct HookMutationActions {
+    /// Reject the event under review; the reason is fed back to the agent.
+    reject: Option<String>,
+    /// Replacement payload for the event under review.
+    rewrite: Option<String>,
+    /// Additional events to publish on the bus.
+    publish: Vec<HookMutationEvent>,
+    /// Guidance appended to the next prompt.
+    guidance: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct HookMutationEvent {
+    topic: String,
+    payload: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum HookMutationParseError {
+    InvalidJson { message: String },
+    InvalidSchema { message: String },
+}
+
+fn format_hook_mutation_parse_error(error: &HookMutationParseError) -> String {
+    match error {
+        HookMutationParseError::InvalidJson { message }
+        | HookMutationParseError::InvalidSchema { message } => message.clone(),
+    }
+}
+
+fn parse_hook_mutation_stdout(
+    mutate: &HookMutationConfig,
+    phase_event: HookPhaseEvent,
+    hook_name: &str,
+    stdout: &str,
+) -> HookMutationParseOutcome {
+    if !mutate.enabled {
+        return HookMutationParseOutcome::Disabled;
+    }
+
+    let parsed = match serde_json::from_str::<serde_json::Value>(stdout.trim()) {
+        Ok(parsed) => parsed,
+        Err(error) => {
+            return HookMutationParseOutcome::Invalid(HookMutationParseError::InvalidJson {
+                message: format!("mutation stdout is not valid JSON: {error}"),
+            });
+        }
+    };
+
+    let Some(payload_object) = parsed.as_object() else {
+        return HookMutationParseOutcome::Invalid(HookMutationParseError::InvalidSchema {
+            message: "mutation payload must be a JSON object".to_string(),
+        });
+    };
+
+    let unsupported_keys = payload_object
+        .keys()
+        .filter(|key| !HOOK_MUTATION_PAYLOAD_KEYS.contains(&key.as_str()))
+        .cloned()
+        .collect::<Vec<_>>();
+    if payload_object.is_empty() || !unsupported_keys.is_empty() {
+        let keys = payload_object.keys().cloned().collect::<Vec<_>>();
+        return HookMutationParseOutcome::Invalid(HookMutationParseError::InvalidSchema {
+            message: format!(
+                "mutation payload supports only keys {HOOK_MUTATION_PAYLOAD_KEYS:?}; found keys: {keys:?}"
+            ),
+        });
+    }
+
+    let mut namespaced_metadata = serde_json::Map::new();
+    if let Some(metadata) = payload_object.get(HOOK_MUTATION_PAYLOAD_METADATA_KEY) {
+        let Some(metadata) = metadata.as_object().cloned() else {
+            return HookMutationParseOutcome::Invalid(HookMutationParseError::InvalidSchema {
+                message: "mutation payload key 'metadata' must contain a JSON object".to_string(),
+            });
+        };
+
+        if let Err(error) =
+            merge_hook_metadata_namespace(&mut namespaced_metadata, hook_name, metadata)
+        {
+            return HookMutationParseOutcome::Invalid(error);
+        }
+    }
+
+    match parse_hook_mutation_actions(phase_event, payload_object) {
+        Ok(actions) => HookMutationParseOutcome::Parsed {
+            namespaced_metadata,
+            actions,
+        },
+        Err(message) => {
+            HookMutationParseOutcome::Invalid(HookMutationParseError::InvalidSchema { message })
+        }
+    }
+}
+
+/// Validates the action keys of a mutation payload against the phase they were
+/// returned from.
+///
+/// `reject` and `rewrite` target the event under review, so they are only valid
+/// on `pre.event.publish`. `publish` and `guidance` feed the next iteration and
+/// are refused on termination and merge phases, where no iteration follows.
+fn parse_hook_mutation_actions(
+    phase_event: HookPhaseEvent,
+    payload_object: &serde_json::Map<String, serde_json::Value>,
+) -> Result<HookMutationActions, String> {
+    let phase = phase_event.as_str();
+    let mut actions = HookMutationActions::default();
+
+    if let Some(reject) = payload_object.get(HOOK_MUTATION_PAYLOAD_REJECT_KEY) {
+        if phase_event != HookPhaseEvent::PreEventPublish {
+            return Err(format!(
+                "mutation action 'reject' is only supported on 'pre.event.publish' (got '{phase}')"
+            ));
+        }
+        let reason = reject
+            .get("reason")
+            .and_then(serde_json::Value::as_str)
+            .map(str::trim)
+            .filter(|reason| !reason.is_empty())
+            .ok_or_else(|| {
+                "mutation action 'reject' must be an object with a non-empty string 'reason'"
+                    .to_string()
+            })?;
+        actions.reject = Some(reason.to_string());
+    }
+
+    if let Some(rewrite) = payload_object.get(HOOK_MUTATION_PAYLOAD_REWRITE_KEY) {
+        if phase_event != HookPhaseEvent::PreEventPublish {
+            return Err(format!(
+                "mutation action 'rewrite' is only supported on 'pre.event.publish' (got '{phase}')"
+            ));
+        }
+        if actions.reject.is_some() {
+            return Err(
+                "mutation actions 'reject' and 'rewrite' are mutually exclusive".to_string(),
+            );
+        }
+        let payload = rewrite
+            .get("payload")
+            .and_then(serde_json::Value::as_str)
+            .ok_or_else(|| {
+                "mutation action 'rewrite' must be an object with a string 'payload'".to_string()
+            })?;
+        actions.rewrite = Some(payload.to_string());
+    }
+
+    let feeds_next_iteration = !matches!(
+        phase_event,
+        HookPhaseEvent::PreLoopComplete
+            | HookPhaseEvent::PostLoopComplete
+            | HookPhaseEvent::PreLoopError
+            | HookPhaseEvent::PostLoopError
+            | HookPhaseEvent::PreMerge
+            | HookPhaseEvent::PostMerge
+    );
+
+    if let Some(publish) = payload_object.get(HOOK_MUTATION_PAYLOAD_PUBLISH_KEY) {
+        if !feeds_next_iteration {
+            return Err(format!(
+                "mutation action 'publish' is not supported on '{phase}'; no iteration follows"
+            ));
+        }
+        let entries = publish.as_array().ok_or_else(|| {
+            "mutation action 'publish' must be an array of {\"topic\", \"payload\"} objects"
+                .to_string()
+        })?;
+        for (index, entry) in entries.iter().enumerate() {
+            let topic = entry
+                .get("topic")
+                .and_then(serde_json::Value::as_str)
+                .map(str::trim)
+                .filter(|topic| !topic.is_empty())
+                .ok_or_else(|| {
+                    format!(
+                        "mutation action 'publish[{index}]' requires a non-empty string 'topic'"
+                    )
+                })?;
+            let payload = match entry.get("payload") {
+                None | Some(serde_json::Value::Null) => "",
+                Some(serde_json::Value::String(payload)) => payload.as_str(),
+                Some(_) => {
+                    return Err(format!(
+                        "mutation action 'publish[{index}].payload' must be a string"
+                    ));
+                }
+            };
+            actions.publish.push(HookMutationEvent {
+                topic: topic.to_string(),
+                payload: payload.to_string(),
+            });
+        }
+    }
+
+    if let Some(guidance) = payload_object.get(HOOK_MUTATION_PAYLOAD_GUIDANCE_KEY) {
+        if !feeds_next_iteration {
+            return Err(format!(
+                "mutation action 'guidance' is not supported on '{phase}'; no iteration follows"
+            ));
+        }
+        let guidance = guidance
+            .as_str()
+            .map(str::trim)
+            .filter(|guidance| !guidance.is_empty())
+            .ok_or_else(|| "mutation action 'guidance' must be a non-empty string".to_string())?;
+        actions.guidance = Some(guidance.to_string());
+    }
+
+    Ok(actions)
+}
+
+/// Summarizes a mutation parse outcome for hook-run telemetry.
+fn hook_mutation_telemetry(
+    mutation_parse_outcome: &HookMutationParseOutcome,
+) -> Option<HookMutationTelemetry> {
+    match mutation_parse_outcome {
+        HookMutationParseOutcome::Disabled => None,
+        HookMutationParseOutcome::Parsed {
+            namespaced_metadata,
+            actions,
+        } => {
+            let applied = [
+                !namespaced_metadata.is_empty(),
+                actions.reject.is_some(),
+                actions.rewrite.is_some(),
+                !actions.publish.is_empty(),
+                actions.guidance.is_some(),
+            ];
+            Some(HookMutationTelemetry {
+                actions: HOOK_MUTATION_PAYLOAD_KEYS
+                    .iter()
+                    .zip(applied)
+                    .filter(|(_, applied)| *applied)
+                    .map(|(key, _)| (*key).to_string())
+                    .collect(),
+                error: None,
+            })
+        }
+        HookMutationParseOutcome::Invalid(error) => Some(HookMutationTelemetry {
+            actions: Vec::new(),
+            error: Some(format_hook_mutation_parse_error(error)),
+        }),
+    }
+}
+
+fn merge_hook_metadata_namespace(
+    accumulated_metadata: &mut serde_json::Map<String, serde_json::Value>,
+    hook_name: &str,
+    metadata: serde_json::Map<String, serde_json::Value>,
+) -> Result<(), HookMutationParseError> {
+    if hook_name.trim().is_empty() {
+        return Err(HookMutationParseError::InvalidSchema {
+            message: "hook metadata namespace requires non-empty hook name".to_string(),
+        });
+    }
+
+    let namespace = accumulated_metadata
+        .entry(HOOK_MUTATION_METADATA_NAMESPACE_KEY.to_string())
+        .or_insert_with(|| serde_json::Value::Object(serde_json::Map::new()));
+
+    let Some(namespace_object) = namespace.as_object_mut() else {
+        return Err(HookMutationParseError::InvalidSchema {
+            message: format!(
+                "metadata namespace '{HOOK_MUTATION_METADATA_NAMESPACE_KEY}' must be a JSON object"
+            ),
+        });
+    };
+
+    namespace_object.insert(hook_name.to_string(), serde_json::Value::Object(metadata));
+    Ok(())
+}
+
+fn merge_namespaced_hook_metadata(
+    accumulated_metadata: &mut serde_json::Map<String, serde_json::Value>,
+    namespaced_metadata: &serde_json::Map<String, serde_json::Value>,
+) -> Result<(), HookMutationParseError> {
+    let Some(namespace_object) = namespaced_metadata
+        .get(HOOK_MUTATION_METADATA_NAMESPACE_KEY)
+        .and_then(serde_json::Value::as_object)
+    else {
+        return Err(HookMutationParseError::InvalidSchema {
+            message: format!(
+                "parsed mutation metadata must contain object key '{HOOK_MUTATION_METADATA_NAMESPACE_KEY}'"
+            ),
+        });
+    };
+
+    for (hook_name, metadata_value) in namespace_object {
+        let Some(metadata_object) = metadata_value.as_object().cloned() else {
+            return Err(HookMutationParseError::InvalidSchema {
+                message: format!(
+                    "parsed metadata entry for hook '{hook_name}' must be a JSON object"
+                ),
+            });
+        };
+
+        merge_hook_metadata_namespace(accumulated_metadata, hook_name, metadata_object)?;
+    }
+
+    Ok(())
+}
+
+fn merge_accumulated_hook_metadata_from_outcomes(
+    accumulated_hook_metadata: &mut serde_json::Map<String, serde_json::Value>,
+    outcomes: &[HookDispatchOutcome],
+) {
+    for outcome in outcomes {
+        let HookMutationParseOutcome::Parsed {
+            namespaced_metadata,
+            ..
+        } = &outcome.mutation_parse_outcome
+        else {
+            continue;
+        };
+        if namespaced_metadata.is_empty() {
+            continue;
+        }
+
+        if let Err(error) =
+            merge_namespaced_hook_metadata(accumulated_hook_metadata, namespaced_metadata)
+        {
+            warn!(
+                phase_event = %outcome.phase_event,
+                hook_name = %outcome.hook_name,
+                error = ?error,
+                "Failed to merge parsed hook mutation metadata; ignoring mutation output"
+            );
+        }
+    }
+}
+
+fn mutation_parse_failure(
+    mutation_parse_outcome: &HookMutationParseOutcome,
+) -> Option<HookDispatchFailure> {
+    let HookMutationParseOutcome::Invalid(error) = mutation_parse_outcome else {
+        return None;
+    };
+
+    Some(HookDispatchFailure::InvalidMutationOutput {
+        message: format_hook_mutation_parse_error(error),
+    })
+}
+
+fn max_retry_attempts_for_suspend_mode(suspend_mode: HookSuspendMode) -> u32 {
+    match suspend_mode {
+        HookSuspendMode::WaitForResume => 1,
+        HookSuspendMode::RetryBackoff => RETRY_BACKOFF_DELAYS_MS.len() as u32 + 1,
+        HookSuspendMode::WaitThenRetry => 2,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SuspendWaitOutcome {
+    Resume,
+    Stop,
+    Restart,
+}
+
+impl SuspendWaitOutcome {
+    /// Termination reason the loop should exit with, if any.
+    fn termination_reason(self) -> Option<TerminationReason> {
+        match self {
+            Self::Resume => None,
+            Self::Stop => Some(TerminationReason::Stopped),
+            Self::Restart => Some(TerminationReason::RestartRequested),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RetryBackoffDelayOutcome {
+    Elapsed,
+    StopRequested,
+    RestartRequested,
+}
+
+fn dispatch_phase_event_hooks(
+    telemetry: &DiagnosticsCollector,
+    hooks_enabled: bool,
+    loop_id: &str,
+    hook_engine: &HookEngine,
+    hook_executor: &HookExecutor,
+    phase_event: HookPhaseEvent,
+    payload_input: HookPayloadBuilderInput,
+) -> Vec<HookDispatchOutcome> {
+    if !hooks_enabled {
+        return Vec::new();
+    }
+
+    let resolved_hooks = hook_engine.resolve_phase_event(phase_event);
+    dispatch_resolved_hooks(
+        telemetry,
+        loop_id,
+        hook_engine,
+        hook_executor,
+        phase_event,
+        resolved_hooks,
+        payload_input,
+    )
+}
+
+/// Queues `reject`/`rewrite` actions from `pre.event.publish` outcomes against
+/// the unread event at `index`.
+pub(crate) fn queue_pending_event_mutations(
+    event_loop: &mut EventLoop,
+    index: usize,
+    topic: &str,
+    outcomes: &[HookDispatchOutcome],
+) {
+    for outcome in outcomes {
+        let HookMutationParseOutcome::Parsed { actions, .. } = &outcome.mutation_parse_outcome
+        else {
+            continue;
+        };
+
+        if let Some(reason) = &actions.reject {
+            info!(
+                hook_name = %outcome.hook_name,
+                topic = %topic,
+                reason = %reason,
+                "Lifecycle hook rejected event"
+            );
+            event_loop.mutate_pending_event(
+                index,
+                PendingEventMutation::Reject {
+                    reason: format!("{reason} (hook '{}')", outcome.hook_name),
+                },
+            );
+        } else if let Some(payload) = &actions.rewrite {
+            info!(
+                hook_name = %outcome.hook_name,
+                topic = %topic,
+                "Lifecycle hook rewrote event payload"
+            );
+            event_loop.mutate_pending_event(
+                index,
+                PendingEventMutation::Rewrite {
+                    payload: payload.clone(),
+                },
+            );
+        }
+    }
+}
+
+/// Applies `publish` and `guidance` mutation actions from hook outcomes.
+///
+/// Published events land on the bus for the next iteration; guidance is
+/// appended to the next prompt only.
+pub(crate) fn apply_hook_mutation_actions(
+    event_loop: &mut EventLoop,
+    outcomes: &[HookDispatchOutcome],
+) {
+    for outcome in outcomes {
+        let HookMutationParseOutcome::Parsed { actions, .. } = &outcome.mutation_parse_outcome
+        else {
+            continue;
+        };
+
+        for event in &actions.publish {
+            debug!(
+                hook_name = %outcome.hook_name,
+                topic = %event.topic,
+                "Publishing event returned by lifecycle hook"
+            );
+            event_loop.bus().publish(ralph_proto::Event::new(
+                event.topic.as_str(),
+                event.payload.as_str(),
+            ));
+        }
+
+        if let Some(guidance) = &actions.guidance {
+            event_loop.append_hook_guidance(guidance.clone());
+        }
+    }
+}
+
+fn dispatch_resolved_hooks(
+    telemetry: &DiagnosticsCollector,
+    loop_id: &str,
+    hook_engine: &HookEngine,
+    hook_executor: &HookExecutor,
+    phase_event: HookPhaseEvent,
+    resolved_hooks: Vec<ResolvedHookSpec>,
+    payload_input: HookPayloadBuilderInput,
+) -> Vec<HookDispatchOutcome> {
+    if resolved_hooks.is_empty() {
+        return Vec::new();
+    }
+
+    let workspace_root = payload_input.workspace.clone();
+    let payload = hook_engine.build_payload(phase_event, payload_input);
+    let stdin_payload = match serde_json::to_value(&payload) {
+        Ok(value) => value,
+        Err(error) => {
+            warn!(
+                phase_event = %phase_event,
+                error = %error,
+                "Failed to serialize lifecycle hook payload; skipping phase-event dispatch"
+            );
+            return Vec::new();
+        }
+    };
+
+    let mut outcomes = Vec::with_capacity(resolved_hooks.len());
+
+    for hook in resolved_hooks {
+        let hook_name = hook.name.clone();
+        let phase_event_key = hook.phase_event.as_str().to_string();
+
+        let request = HookRunRequest {
+            phase_event: phase_event_key.clone(),
+            hook_name: hook_name.clone(),
+            command: hook.command.clone(),
+            workspace_root: workspace_root.clone(),
+            cwd: hook.cwd.clone(),
+            env: hook.env.clone(),
+            timeout_seconds: hook.timeout_seconds,
+            max_output_bytes: hook.max_output_bytes,
+            stdin_payload: stdin_payload.clone(),
+        };
+
+        let outcome = dispatch_hook_with_suspend_policy(
+            telemetry,
+            hook_executor,
+            loop_id,
+            &phase_event_key,
+            hook.phase_event,
+            &hook_name,
+            hook.on_error,
+            hook.suspend_mode,
+            &hook.mutate,
+            &request,
+        );
+        outcomes.push(outcome);
+    }
+
+    outcomes
+}
+
+#[allow(clippy::too_many_arguments)]
+fn dispatch_hook_with_suspend_policy(
+    telemetry: &DiagnosticsCollector,
+    hook_executor: &HookExecutor,
+    loop_id: &str,
+    phase_event_key: &str,
+    phase_event: HookPhaseEvent,
+    hook_name: &str,
+    on_error: HookOnError,
+    suspend_mode: HookSuspendMode,
+    mutate: &HookMutationConfig,
+    request: &HookRunRequest,
+) -> HookDispatchOutcome {
+    let retry_max_attempts = max_retry_attempts_for_suspend_mode(suspend_mode);
+    let outcome = execute_hook_attempt(
+        telemetry,
+        hook_executor,
+        loop_id,
+        phase_event_key,
+        phase_event,
+        hook_name,
+        on_error,
+        suspend_mode,
+        mutate,
+        1,
+        retry_max_attempts,
+        request,
+    );
+
+    if outcome.disposition != HookDisposition::Suspend {
+        return outcome;
+    }
+
+    match suspend_mode {
+        HookSuspendMode::WaitForResume => outcome,
+        HookSuspendMode::RetryBackoff => dispatch_retry_backoff_suspend_policy(
+            telemetry,
+            hook_executor,
+            loop_id,
+            phase_event_key,
+            phase_event,
+            hook_name,
+            on_error,
+            suspend_mode,
+            mutate,
+            retry_max_attempts,
+            request,
+            outcome,
+        ),
+        HookSuspendMode::WaitThenRetry => dispatch_wait_then_retry_suspend_policy(
+            telemetry,
+            hook_executor,
+            loop_id,
+            phase_event_key,
+            phase_event,
+            hook_name,
+            on_error,
+            suspend_mode,
+            mutate,
+            retry_max_attempts,
+            request,
+            outcome,
+        ),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn dispatch_retry_backoff_suspend_policy(
+    telemetry: &DiagnosticsCollector,
+    hook_executor: &HookExecutor,
+    loop_id: &str,
+    phase_event_key: &str,
+    phase_event: HookPhaseEvent,
+    hook_name: &str,
+    on_error: HookOnError,
+    suspend_mode: HookSuspendMode,
+    mutate: &HookMutationConfig,
+    retry_max_attempts: u32,
+    request: &HookRunRequest,
+    outcome: HookDispatchOutcome,
+) -> HookDispatchOutcome {
+    run_retry_backoff_policy(
+        phase_event_key,
+        hook_name,
+        &RETRY_BACKOFF_DELAYS_MS,
+        |backoff_delay, _retry_attempt| {
+            wait_for_retry_backoff_delay_with_signal_poll(
+                request.workspace_root.as_path(),
+                backoff_delay,
+            )
+        },
+        |retry_attempt| {
+            execute_hook_attempt(
+                telemetry,
+                hook_executor,
+                loop_id,
+                phase_event_key,
+                phase_event,
+                hook_name,
+                on_error,
+                suspend_mode,
+                mutate,
+                retry_attempt,
+                retry_max_attempts,
+                request,
+            )
+        },
+        outcome,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn dispatch_wait_then_retry_suspend_policy(
+    telemetry: &DiagnosticsCollector,
+    hook_executor: &HookExecutor,
+    loop_id: &str,
+    phase_event_key: &str,
+    phase_event: HookPhaseEvent,
+    hook_name: &str,
+    on_error: HookOnError,
+    suspend_mode: HookSuspendMode,
+    mutate: &HookMutationConfig,
+    retry_max_attempts: u32,
+    request: &HookRunRequest,
+    outcome: HookDispatchOutcome,
+) -> HookDispatchOutcome {
+    let suspend_state_store = SuspendStateStore::new(&request.workspace_root);
+    let reason = format_suspending_hook_reason(&outcome);
+    let suspend_state = SuspendStateRecord::new(
+        loop_id,
+        phase_event,
+        hook_name,
+        reason,
+        suspend_mode,
+        chrono::Utc::now(),
+    );
+
+    if let Err(error) = suspend_state_store.write_suspend_state(&suspend_state) {
+        warn!(
+            phase_event = %phase_event_key,
+            hook_name = %hook_name,
+            error = %error,
+            "Failed to persist suspend-state for wait_then_retry; deferring to standard suspend handling"
+        );
+        return outcome;
+    }
+
+    warn!(
+        phase_event = %phase_event_key,
+        hook_name = %hook_name,
+        "Lifecycle hook requested suspend(wait_then_retry); entering wait-for-resume gate before single retry"
+    );
+
+    run_wait_then_retry_policy(
+        phase_event_key,
+        hook_name,
+        || wait_for_suspend_signal_with_poll(&suspend_state_store),
+        || suspend_state_store.clear_suspend_state().map(|_| ()),
+        || {
+            execute_hook_attempt(
+                telemetry,
+                hook_executor,
+                loop_id,
+                phase_event_key,
+                phase_event,
+                hook_name,
+                on_error,
+                suspend_mode,
+                mutate,
+                2,
+                retry_max_attempts,
+                request,
+            )
+        },
+        outcome,
+    )
+}
+
+fn run_retry_backoff_policy<FWaitForDelay, FRunRetryAttempt>(
+    phase_event_key: &str,
+    hook_name: &str,
+    backoff_delays_ms: &[u64],
+    mut wait_for_delay: FWaitForDelay,
+    mut run_retry_attempt: FRunRetryAttempt,
+    mut outcome: HookDispatchOutcome,
+) -> HookDispatchOutcome
+where
+    FWaitForDelay: FnMut(Duration, usize) -> RetryBackoffDelayOutcome,
+    FRunRetryAttempt: FnMut(u32) -> HookDispatchOutcome,
+{
+    for (retry_attempt, backoff_delay_ms) in backoff_delays_ms.iter().copied().enumerate() {
+        match wait_for_delay(Duration::from_millis(backoff_delay_ms), retry_attempt + 1) {
+            RetryBackoffDelayOutcome::Elapsed => {}
+            RetryBackoffDelayOutcome::StopRequested => {
+                info!(
+                    phase_event = %phase_event_key,
+                    hook_name = %hook_name,
+                    retry_attempt = retry_attempt + 1,
+                    "Stop requested while waiting for retry_backoff retry; deferring to suspend termination handling"
+                );
+                break;
+            }
+            RetryBackoffDelayOutcome::RestartRequested => {
+                info!(
+                    phase_event = %phase_event_key,
+                    hook_name = %hook_name,
+                    retry_attempt = retry_attempt + 1,
+                    "Restart requested while waiting for retry_backoff retry; deferring to suspend termination handling"
+                );
+                break;
+            }
+        }
+
+        outcome = run_retry_attempt(retry_attempt as u32 + 2);
+
+        if outcome.disposition == HookDisposition::Pass {
+            info!(
+                phase_event = %phase_event_key,
+                hook_name = %hook_name,
+                retry_attempt = retry_attempt + 1,
+                "Lifecycle hook recovered under retry_backoff"
+            );
+            return outcome;
+        }
+
+        if outcome.disposition != HookDisposition::Suspend {
+            return outcome;
+        }
+    }
+
+    warn!(
+        phase_event = %phase_event_key,
+        hook_name = %hook_name,
+        retry_attempts = backoff_delays_ms.len(),
+        "Lifecycle hook retry_backoff policy exhausted; entering suspended wait_for_resume fallback"
+    );
+
+    outcome
+}
+
+fn run_wait_then_retry_policy<FWaitForSignal, FClearSuspendState, FRunRetryAttempt>(
+    phase_event_key: &str,
+    hook_name: &str,
+    mut wait_for_signal: FWaitForSignal,
+    mut clear_suspend_state: FClearSuspendState,
+    mut run_retry_attempt: FRunRetryAttempt,
+    outcome: HookDispatchOutcome,
+) -> HookDispatchOutcome
+where
+    FWaitForSignal: FnMut() -> Result<SuspendWaitOutcome, SuspendStateStoreError>,
+    FClearSuspendState: FnMut() -> Result<(), SuspendStateStoreError>,
+    FRunRetryAttempt: FnMut() -> HookDispatchOutcome,
+{
+    let wait_outcome = match wait_for_signal() {
+        Ok(wait_outcome) => wait_outcome,
+        Err(error) => {
+            warn!(
+                phase_event = %phase_event_key,
+                hook_name = %hook_name,
+                error = %error,
+                "wait_then_retry gate failed while polling suspend signals; deferring to standard suspend handling"
+            );
+            return outcome;
+        }
+    };
+
+    match wait_outcome {
+        SuspendWaitOutcome::Stop => {
+            info!(
+                phase_event = %phase_event_key,
+                hook_name = %hook_name,
+                "Stop requested while waiting under wait_then_retry; deferring to suspend termination handling"
+            );
+            outcome
+        }
+        SuspendWaitOutcome::Restart => {
+            info!(
+                phase_event = %phase_event_key,
+                hook_name = %hook_name,
+                "Restart requested while waiting under wait_then_retry; deferring to suspend termination handling"
+            );
+            outcome
+        }
+        SuspendWaitOutcome::Resume => {
+            if let Err(error) = clear_suspend_state() {
+                warn!(
+                    phase_event = %phase_event_key,
+                    hook_name = %hook_name,
+                    error = %error,
+                    "Failed to clear wait_then_retry suspend-state after resume; deferring to standard suspend handling"
+                );
+                return outcome;
+            }
+
+            let retry_outcome = run_retry_attempt();
+
+            if retry_outcome.disposition == HookDisposition::Pass {
+                info!(
+                    phase_event = %phase_event_key,
+                    hook_name = %hook_name,
+                    "Lifecycle hook recovered under wait_then_retry"
+                );
+            }
+
+            retry_outcome
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn execute_hook_attempt(
+    telemetry: &DiagnosticsCollector,
+    hook_executor: &HookExecutor,
+    loop_id: &str,
+    phase_event_key: &str,
+    phase_event: HookPhaseEvent,
+    hook_name: &str,
+    on_error: HookOnError,
+    suspend_mode: HookSuspendMode,
+    mutate: &HookMutationConfig,
+    retry_attempt: u32,
+    retry_max_attempts: u32,
+    request: &HookRunRequest,
+) -> HookDispatchOutcome {
+    match hook_executor.run(request.clone()) {
+        Ok(run_result) => {
+            let run_disposition = classify_hook_disposition(on_error, &run_result);
+            let mutation_parse_outcome = parse_hook_mutation_stdout(
+                mutate,
+                phase_event,
+                hook_name,
+                &run_result.stdout.content,
+            );
+            let mutation_failure = if run_disposition == HookDisposition::Pass {
+                mutation_parse_failure(&mutation_parse_outcome)
+            } else {
+                None
+            };
+
+            let disposition = if mutation_failure.is_some() {
+                disposition_from_on_error(on_error)
+            } else {
+                run_disposition
+            };
+
+            let failure = if let Some(mutation_failure) = mutation_failure {
+                Some(mutation_failure)
+            } else if run_disposition == HookDisposition::Pass {
+                None
+            } else {
+                Some(HookDispatchFailure::HookRunFailed {
+                    exit_code: run_result.exit_code,
+                    timed_out: run_result.timed_out,
+                })
+            };
+
+            telemetry.log_hook_run(
+                HookRunTelemetryEntry::from_run_result(
+                    loop_id,
+                    phase_event_key,
+                    hook_name,
+                    disposition,
+                    suspend_mode,
+                    retry_attempt,
+                    retry_max_attempts,
+                    &run_result,
+                )
+                .with_mutation(hook_mutation_telemetry(&mutation_parse_outcome)),
+            );
+
+            if disposition == HookDisposition::Pass {
+                debug!(
+                    phase_event = %phase_event_key,
+                    hook_name = %hook_name,
+                    duration_ms = run_result.duration_ms,
+                    "Lifecycle hook executed successfully"
+                );
+            } else {
+                let failure_detail = format_hook_failure_detail(failure.as_ref());
+                warn!(
+                    phase_event = %phase_event_key,
+                    hook_name = %hook_name,
+                    disposition = ?disposition,
+                    exit_code = ?run_result.exit_code,
+                    timed_out = run_result.timed_out,
+                    failure = %failure_detail,
+                    "Lifecycle hook returned non-pass disposition; continuing"
+                );
+            }
+
+            HookDispatchOutcome {
+                phase_event,
+                hook_name: hook_name.to_string(),
+                disposition,
+                suspend_mode,
+                failure,
+                mutation_parse_outcome,
+            }
+        }
+        Err(error) => {
+            let disposition = disposition_from_on_error(on_error);
+
+            warn!(
+                phase_event = %phase_event_key,
+                hook_name = %hook_name,
+                disposition = ?disposition,
+                error = %error,
+                "Lifecycle hook execution failed; continuing"
+            );
+
+            HookDispatchOutcome {
+                phase_event,
+                hook_name: hook_name.to_string(),
+                disposition,
+                suspend_mode,
+                failure: Some(HookDispatchFailure::HookExecutionError {
+                    message: error.to_string(),
+                }),
+                mutation_parse_outcome: HookMutationParseOutcome::Disabled,
+            }
+        }
+    }
+}
+
+fn wait_for_retry_backoff_delay_with_signal_poll(
+    workspace_root: &Path,
+    backoff_delay: Duration,
+) -> RetryBackoffDelayOutcome {
+    if backoff_delay.is_zero() {
+        return RetryBackoffDelayOutcome::Elapsed;
+    }
+
+    let poll_interval = Duration::from_millis(RETRY_BACKOFF_SIGNAL_POLL_INTERVAL_MS);
+    let sleep_started_at = std::time::Instant::now();
+
+    loop {
+        if is_stop_requested(workspace_root) {
+            return RetryBackoffDelayOutcome::StopRequested;
+        }
+
+        if is_restart_requested(workspace_root) {
+            return RetryBackoffDelayOutcome::RestartRequested;
+        }
+
+        let elapsed = sleep_started_at.elapsed();
+        if elapsed >= backoff_delay {
+            return RetryBackoffDelayOutcome::Elapsed;
+        }
+
+        let remaining = backoff_delay.saturating_sub(elapsed);
+        std::thread::sleep(std::cmp::min(remaining, poll_interval));
+    }
+}
+
+fn wait_for_suspend_signal_with_poll(
+    suspend_state_store: &SuspendStateStore,
+) -> Result<SuspendWaitOutcome, SuspendStateStoreError> {
+    let poll_interval = Duration::from_millis(SUSPEND_WAIT_SIGNAL_POLL_INTERVAL_MS);
+
+    loop {
+        if is_stop_requested(suspend_state_store.workspace_root()) {
+            return Ok(SuspendWaitOutcome::Stop);
+        }
+
+        if is_restart_requested(suspend_state_store.workspace_root()) {
+            return Ok(SuspendWaitOutcome::Restart);
+        }
+
+        if suspend_state_store.consume_resume_requested()? {
+            return Ok(SuspendWaitOutcome::Resume);
+        }
+
+        std::thread::sleep(poll_interval);
+    }
+}
+
+pub(crate) fn fail_if_blocking_outcomes(
+    outcomes: &[HookDispatchOutcome],
+) -> Result<(), HookBoundaryError> {
+    let Some(blocking_outcome) = outcomes
+        .iter()
+        .find(|outcome| outcome.disposition == HookDisposition::Block)
+    else {
+        return Ok(());
+    };
+
+    let reason = format_blocking_hook_reason(blocking_outcome);
+    error!(
+        phase_event = %blocking_outcome.phase_event,
+        hook_name = %blocking_outcome.hook_name,
+        reason = %reason,
+        "Lifecycle hook blocked orchestration boundary"
+    );
+
+    Err(HookBoundaryError::Blocked { reason })
+}
+
+async fn wait_for_resume_if_suspended(
+    outcomes: &[HookDispatchOutcome],
+    loop_id: &str,
+    suspend_state_store: &SuspendStateStore,
+) -> Result<Option<TerminationReason>, HookBoundaryError> {
+    let Some(suspending_outcome) = enter_suspend(outcomes, loop_id, suspend_state_store)? else {
+        return Ok(None);
+    };
+
+    loop {
+        if let Some(wait_outcome) = poll_suspend_signals(suspending_outcome, suspend_state_store)? {
+            return Ok(wait_outcome.termination_reason());
+        }
+        tokio::time::sleep(Duration::from_millis(SUSPEND_WAIT_SIGNAL_POLL_INTERVAL_MS)).await;
+    }
+}
+
+/// Blocking counterpart of [`wait_for_resume_if_suspended`].
+fn block_for_resume_if_suspended(
+    outcomes: &[HookDispatchOutcome],
+    loop_id: &str,
+    suspend_state_store: &SuspendStateStore,
+) -> Result<Option<TerminationReason>, HookBoundaryError> {
+    let Some(suspending_outcome) = enter_suspend(outcomes, loop_id, suspend_state_store)? else {
+        return Ok(None);
+    };
+
+    loop {
+        if let Some(wait_outcome) = poll_suspend_signals(suspending_outcome, suspend_state_store)? {
+            return Ok(wait_outcome.termination_reason());
+        }
+        std::thread::sleep(Duration::from_millis(SUSPEND_WAIT_SIGNAL_POLL_INTERVAL_MS));
+    }
+}
+
+/// Persists suspend-state for the first suspending outcome, if any.
+fn enter_suspend<'a>(
+    outcomes: &'a [HookDispatchOutcome],
+    loop_id: &str,
+    suspend_state_store: &SuspendStateStore,
+) -> Result<Option<&'a HookDispatchOutcome>, HookBoundaryError> {
+    let Some(suspending_outcome) = outcomes
+        .iter()
+        .find(|outcome| outcome.disposition == HookDisposition::Suspend)
+    else {
+        return Ok(None);
+    };
+
+    let reason = format_suspending_hook_reason(suspending_outcome);
+    let suspend_state = SuspendStateRecord::new(
+        loop_id,
+        suspending_outcome.phase_event,
+        &suspending_outcome.hook_name,
+        &reason,
+        suspending_outcome.suspend_mode,
+        chrono::Utc::now(),
+    );
+
+    suspend_state_store
+        .write_suspend_state(&suspend_state)
+        .map_err(|source| HookBoundaryError::PersistSuspendState {
+            hook_name: suspending_outcome.hook_name.clone(),
+            phase_event: suspending_outcome.phase_event,
+            source,
+        })?;
+
+    warn!(
+        phase_event = %suspending_outcome.phase_event,
+        hook_name = %suspending_outcome.hook_name,
+        suspend_mode = ?suspending_outcome.suspend_mode,
+        reason = %reason,
+        "Lifecycle hook requested suspend; entering wait_for_resume gate"
+    );
+
+    Ok(Some(suspending_outcome))
+}
+
+/// Checks the stop, restart and resume signals once while suspended.
+///
+/// Returns `None` while still suspended, otherwise the signal that ended it.
+fn poll_suspend_signals(
+    suspending_outcome: &HookDispatchOutcome,
+    suspend_state_store: &SuspendStateStore,
+) -> Result<Option<SuspendWaitOutcome>, HookBoundaryError> {
+    if consume_stop_requested_signal(suspend_state_store.workspace_root())? {
+        clear_suspend_wait_artifacts(suspend_state_store)?;
+        info!(
+            phase_event = %suspending_outcome.phase_event,
+            hook_name = %suspending_outcome.hook_name,
+            "Stop requested while suspended; terminating loop"
+        );
+        return Ok(Some(SuspendWaitOutcome::Stop));
+    }
+
+    if is_restart_requested(suspend_state_store.workspace_root()) {
+        clear_suspend_wait_artifacts(suspend_state_store)?;
+        info!(
+            phase_event = %suspending_outcome.phase_event,
+            hook_name = %suspending_outcome.hook_name,
+            "Restart requested while suspended; terminating loop for restart"
+        );
+        return Ok(Some(SuspendWaitOutcome::Restart));
+    }
+
+    if suspend_state_store
+        .consume_resume_requested()
+        .map_err(HookBoundaryError::SuspendSignal)?
+    {
+        suspend_state_store
+            .clear_suspend_state()
+            .map_err(HookBoundaryError::SuspendSignal)?;
+
+        info!(
+            phase_event = %suspending_outcome.phase_event,
+            hook_name = %suspending_outcome.hook_name,
+            "Resume signal consumed; leaving suspended wait_for_resume state"
+        );
+        return Ok(Some(SuspendWaitOutcome::Resume));
+    }
+
+    Ok(None)
+}
+
+fn clear_suspend_wait_artifacts(
+    suspend_state_store: &SuspendStateStore,
+) -> Result<(), HookBoundaryError> {
+    suspend_state_store
+        .clear_suspend_state()
+        .map_err(HookBoundaryError::SuspendSignal)?;
+    suspend_state_store
+        .consume_resume_requested()
+        .map_err(HookBoundaryError::SuspendSignal)?;
+    Ok(())
+}
+
+fn is_stop_requested(workspace_root: &Path) -> bool {
+    workspace_root.join(".ralph/stop-requested").exists()
+}
+
+fn consume_stop_requested_signal(workspace_root: &Path) -> Result<bool, HookBoundaryError> {
+    let stop_path = workspace_root.join(".ralph/stop-requested");
+    match std::fs::remove_file(&stop_path) {
+        Ok(()) => Ok(true),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(false),
+        Err(source) => Err(HookBoundaryError::StopSignal {
+            path: stop_path,
+            source,
+        }),
+    }
+}
+
+fn is_restart_requested(workspace_root: &Path) -> bool {
+    workspace_root.join(".ralph/restart-requested").exists()
+}
+
+fn format_suspending_hook_reason(outcome: &HookDispatchOutcome) -> String {
+    format!(
+        "Lifecycle hook '{}' suspended orchestration at '{}': {}",
+        outcome.hook_name,
+        outcome.phase_event.as_str(),
+        format_hook_failure_detail(outcome.failure.as_ref())
+    )
+}
+
+fn format_blocking_hook_reason(outcome: &HookDispatchOutcome) -> String {
+    format!(
+        "Lifecycle hook '{}' blocked orchestration at '{}': {}",
+        outcome.hook_name,
+        outcome.phase_event.as_str(),
+        format_hook_failure_detail(outcome.failure.as_ref())
+    )
+}
+
+fn format_hook_failure_detail(failure: Option<&HookDispatchFailure>) -> String {
+    match failure {
+        Some(HookDispatchFailure::HookRunFailed {
+            exit_code,
+            timed_out,
+        }) => {
+            if *timed_out {
+                "hook timed out".to_string()
+            } else if let Some(code) = exit_code {
+                format!("hook exited with code {code}")
+            } else {
+                "hook exited unsuccessfully".to_string()
+            }
+        }
+        Some(HookDispatchFailure::HookExecutionError { message }) => {
+            format!("hook execution failed: {message}")
+        }
+        Some(HookDispatchFailure::InvalidMutationOutput { message }) => {
+            format!("invalid mutation output: {message}")
+        }
+        None => "hook failed without failure details".to_string(),
+    }
+}
+
+fn classify_hook_disposition(on_error: HookOnError, run_result: &HookRunResult) -> HookDisposition {
+    if !run_result.timed_out && run_result.exit_code == Some(0) {
+        HookDisposition::Pass
+    } else {
+        disposition_from_on_error(on_error)
+    }
+}
+
+fn disposition_from_on_error(on_error: HookOnError) -> HookDisposition {
+    match on_error {
+        HookOnError::Warn => HookDisposition::Warn,
+        HookOnError::Block => HookDisposition::Block,
+        HookOnError::Suspend => HookDisposition::Suspend,
+    }
+}
+
+#[cfg(test)]
+mod tests;
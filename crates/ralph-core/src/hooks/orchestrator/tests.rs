@@ -0,0 +1,3559 @@
+use super::*;
+use crate::config::RalphConfig;
+use crate::loop_context::LoopContext;
+use ralph_proto::HatId;
+
+#[cfg(unix)]
+fn test_event_loop_with_context(workspace_root: &Path) -> (EventLoop, LoopContext) {
+    let mut config = RalphConfig::default();
+    config.core.workspace_root = workspace_root.to_path_buf();
+    let context = LoopContext::primary(workspace_root.to_path_buf());
+    let event_loop = EventLoop::with_context(config, context.clone());
+    (event_loop, context)
+}
+
+/// Builds an event loop whose config enables `events`, recording hook-run
+/// telemetry under `workspace_root`.
+#[cfg(unix)]
+fn hooked_event_loop(
+    workspace_root: &Path,
+    events: std::collections::HashMap<HookPhaseEvent, Vec<crate::HookSpec>>,
+) -> (EventLoop, LoopContext) {
+    let mut config = RalphConfig::default();
+    config.core.workspace_root = workspace_root.to_path_buf();
+    config.hooks = HooksConfig {
+        enabled: true,
+        events,
+        ..HooksConfig::default()
+    };
+    let context = LoopContext::primary(workspace_root.to_path_buf());
+    let mut event_loop = EventLoop::with_context_and_diagnostics(
+        config,
+        context.clone(),
+        telemetry_collector(workspace_root),
+    );
+    event_loop.set_hook_loop_id("loop-test");
+    (event_loop, context)
+}
+
+#[cfg(unix)]
+fn telemetry_collector(workspace_root: &Path) -> DiagnosticsCollector {
+    DiagnosticsCollector::with_enabled(workspace_root, true).expect("create diagnostics collector")
+}
+
+#[cfg(unix)]
+fn hook_spec_with_command_and_on_error_and_suspend_mode(
+    name: &str,
+    command: Vec<String>,
+    on_error: HookOnError,
+    suspend_mode: Option<HookSuspendMode>,
+) -> crate::HookSpec {
+    crate::HookSpec {
+        name: name.to_string(),
+        command,
+        cwd: None,
+        env: std::collections::HashMap::new(),
+        timeout_seconds: None,
+        max_output_bytes: None,
+        on_error: Some(on_error),
+        suspend_mode,
+        mutate: crate::HookMutationConfig::default(),
+        topics: Vec::new(),
+        extra: std::collections::HashMap::new(),
+    }
+}
+
+#[cfg(unix)]
+fn hook_spec_with_command_and_on_error(
+    name: &str,
+    command: Vec<String>,
+    on_error: HookOnError,
+) -> crate::HookSpec {
+    hook_spec_with_command_and_on_error_and_suspend_mode(name, command, on_error, None)
+}
+
+#[cfg(unix)]
+fn hook_spec_with_command(name: &str, command: Vec<String>) -> crate::HookSpec {
+    hook_spec_with_command_and_on_error(name, command, HookOnError::Warn)
+}
+
+#[cfg(unix)]
+fn recording_hook(name: &str, log_path: &Path) -> crate::HookSpec {
+    hook_spec_with_command(
+        name,
+        vec![
+            "sh".to_string(),
+            "-c".to_string(),
+            r#"payload="$(cat)"
+phase="$(printf '%s' "$payload" | grep -o '"phase_event":"[^"]*"' | cut -d'"' -f4)"
+printf '%s|%s\n' "$1" "$phase" >> "$2""#
+                .to_string(),
+            "hook-recorder".to_string(),
+            name.to_string(),
+            log_path.to_string_lossy().into_owned(),
+        ],
+    )
+}
+
+#[cfg(unix)]
+fn payload_recording_hook(name: &str, log_path: &Path) -> crate::HookSpec {
+    hook_spec_with_command(
+        name,
+        vec![
+            "sh".to_string(),
+            "-c".to_string(),
+            r#"payload="$(cat)"
+printf '%s\n' "$payload" >> "$1""#
+                .to_string(),
+            "hook-payload-recorder".to_string(),
+            log_path.to_string_lossy().into_owned(),
+        ],
+    )
+}
+
+#[cfg(unix)]
+fn hook_engine_with_events(
+    events: std::collections::HashMap<HookPhaseEvent, Vec<crate::HookSpec>>,
+) -> HookEngine {
+    let hooks_config = crate::HooksConfig {
+        enabled: true,
+        events,
+        ..crate::HooksConfig::default()
+    };
+    HookEngine::new(&hooks_config)
+}
+
+#[cfg(unix)]
+fn read_hook_run_telemetry_entries(workspace_root: &Path) -> Vec<HookRunTelemetryEntry> {
+    let diagnostics_root = workspace_root.join(".ralph").join("diagnostics");
+    let mut session_dirs: Vec<_> = std::fs::read_dir(&diagnostics_root)
+        .expect("read diagnostics root")
+        .filter_map(Result::ok)
+        .collect();
+    session_dirs.sort_by_key(|entry| entry.path());
+
+    let latest_session = session_dirs
+        .last()
+        .expect("at least one diagnostics session should exist");
+    let hook_runs_path = latest_session.path().join("hook-runs.jsonl");
+    let content = std::fs::read_to_string(&hook_runs_path).expect("read hook-runs.jsonl");
+
+    content
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("parse hook run telemetry entry"))
+        .collect()
+}
+
+#[cfg(unix)]
+fn read_hook_log(log_path: &Path) -> Vec<String> {
+    std::fs::read_to_string(log_path)
+        .expect("read hook log")
+        .lines()
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(unix)]
+fn read_hook_payload_log(log_path: &Path) -> Vec<serde_json::Value> {
+    std::fs::read_to_string(log_path)
+        .expect("read hook payload log")
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| serde_json::from_str(line).expect("parse hook payload JSON"))
+        .collect()
+}
+
+fn suspend_outcome_with_mode(
+    phase_event: HookPhaseEvent,
+    hook_name: &str,
+    suspend_mode: HookSuspendMode,
+) -> HookDispatchOutcome {
+    HookDispatchOutcome {
+        phase_event,
+        hook_name: hook_name.to_string(),
+        disposition: HookDisposition::Suspend,
+        suspend_mode,
+        failure: Some(HookDispatchFailure::HookRunFailed {
+            exit_code: Some(41),
+            timed_out: false,
+        }),
+
+        mutation_parse_outcome: HookMutationParseOutcome::Disabled,
+    }
+}
+
+fn suspend_outcome(phase_event: HookPhaseEvent, hook_name: &str) -> HookDispatchOutcome {
+    suspend_outcome_with_mode(phase_event, hook_name, HookSuspendMode::WaitForResume)
+}
+
+fn block_on_test_future<F>(future: F) -> F::Output
+where
+    F: std::future::Future,
+{
+    tokio::runtime::Builder::new_current_thread()
+        .enable_time()
+        .build()
+        .expect("build tokio runtime")
+        .block_on(future)
+}
+
+fn test_payload_input(
+    loop_id: &str,
+    ctx: &LoopContext,
+    max_iterations: u32,
+    iteration_current: u32,
+    context: HookPayloadContextInput,
+) -> HookPayloadBuilderInput {
+    HookPayloadBuilderInput {
+        loop_id: loop_id.to_string(),
+        is_primary: ctx.is_primary(),
+        workspace: ctx.workspace().to_path_buf(),
+        repo_root: ctx.repo_root().to_path_buf(),
+        pid: std::process::id(),
+        iteration_current,
+        iteration_max: max_iterations,
+        context,
+    }
+}
+
+fn build_loop_start_payload_input(
+    loop_id: &str,
+    ctx: &LoopContext,
+    max_iterations: u32,
+    iteration_current: u32,
+    active_hat: Option<String>,
+) -> HookPayloadBuilderInput {
+    build_loop_start_payload_input_with_metadata(
+        loop_id,
+        ctx,
+        max_iterations,
+        iteration_current,
+        active_hat,
+        &serde_json::Map::new(),
+    )
+}
+
+fn build_loop_start_payload_input_with_metadata(
+    loop_id: &str,
+    ctx: &LoopContext,
+    max_iterations: u32,
+    iteration_current: u32,
+    active_hat: Option<String>,
+    accumulated_metadata: &serde_json::Map<String, serde_json::Value>,
+) -> HookPayloadBuilderInput {
+    test_payload_input(
+        loop_id,
+        ctx,
+        max_iterations,
+        iteration_current,
+        HookPayloadContextInput {
+            active_hat,
+            metadata: accumulated_metadata.clone(),
+            ..HookPayloadContextInput::default()
+        },
+    )
+}
+
+fn build_iteration_start_payload_input(
+    loop_id: &str,
+    ctx: &LoopContext,
+    max_iterations: u32,
+    iteration_current: u32,
+    active_hat: Option<String>,
+    selected_hat: Option<String>,
+    selected_task: Option<String>,
+) -> HookPayloadBuilderInput {
+    test_payload_input(
+        loop_id,
+        ctx,
+        max_iterations,
+        iteration_current,
+        HookPayloadContextInput {
+            active_hat,
+            selected_hat,
+            selected_task,
+            ..HookPayloadContextInput::default()
+        },
+    )
+}
+
+fn build_plan_created_payload_input(
+    loop_id: &str,
+    ctx: &LoopContext,
+    max_iterations: u32,
+    iteration_current: u32,
+    active_hat: Option<String>,
+    selected_hat: Option<String>,
+    selected_task: Option<String>,
+) -> HookPayloadBuilderInput {
+    test_payload_input(
+        loop_id,
+        ctx,
+        max_iterations,
+        iteration_current,
+        HookPayloadContextInput {
+            active_hat,
+            selected_hat,
+            selected_task,
+            ..HookPayloadContextInput::default()
+        },
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn build_human_interact_payload_input(
+    loop_id: &str,
+    ctx: &LoopContext,
+    max_iterations: u32,
+    iteration_current: u32,
+    active_hat: Option<String>,
+    selected_hat: Option<String>,
+    selected_task: Option<String>,
+    human_interact: Option<serde_json::Value>,
+) -> HookPayloadBuilderInput {
+    test_payload_input(
+        loop_id,
+        ctx,
+        max_iterations,
+        iteration_current,
+        HookPayloadContextInput {
+            active_hat,
+            selected_hat,
+            selected_task,
+            human_interact,
+            ..HookPayloadContextInput::default()
+        },
+    )
+}
+
+#[cfg(unix)]
+#[test]
+fn test_dispatch_phase_event_hooks_routes_by_phase_and_preserves_order() {
+    let temp_dir = tempfile::tempdir().expect("temp dir");
+    let log_path = temp_dir.path().join("hook-dispatch.log");
+
+    let mut events = std::collections::HashMap::new();
+    events.insert(
+        HookPhaseEvent::PreIterationStart,
+        vec![
+            recording_hook("pre-iteration-first", &log_path),
+            recording_hook("pre-iteration-second", &log_path),
+        ],
+    );
+    events.insert(
+        HookPhaseEvent::PostLoopStart,
+        vec![recording_hook("post-loop-only", &log_path)],
+    );
+
+    let hook_engine = hook_engine_with_events(events);
+    let hook_executor = HookExecutor::new();
+    let diagnostics = DiagnosticsCollector::disabled();
+    let loop_ctx = LoopContext::primary(temp_dir.path().to_path_buf());
+
+    dispatch_phase_event_hooks(
+        &diagnostics,
+        true,
+        "loop-test",
+        &hook_engine,
+        &hook_executor,
+        HookPhaseEvent::PreIterationStart,
+        build_iteration_start_payload_input(
+            "loop-test",
+            &loop_ctx,
+            5,
+            1,
+            Some("ralph".to_string()),
+            Some("builder".to_string()),
+            Some("task-123".to_string()),
+        ),
+    );
+
+    assert_eq!(
+        read_hook_log(&log_path),
+        vec![
+            "pre-iteration-first|pre.iteration.start".to_string(),
+            "pre-iteration-second|pre.iteration.start".to_string(),
+        ]
+    );
+
+    dispatch_phase_event_hooks(
+        &diagnostics,
+        true,
+        "loop-test",
+        &hook_engine,
+        &hook_executor,
+        HookPhaseEvent::PostLoopStart,
+        build_loop_start_payload_input("loop-test", &loop_ctx, 5, 1, Some("ralph".to_string())),
+    );
+
+    assert_eq!(
+        read_hook_log(&log_path),
+        vec![
+            "pre-iteration-first|pre.iteration.start".to_string(),
+            "pre-iteration-second|pre.iteration.start".to_string(),
+            "post-loop-only|post.loop.start".to_string(),
+        ]
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_ac13_mutation_disabled_json_output_is_inert_for_accumulator_and_downstream_payloads() {
+    let temp_dir = tempfile::tempdir().expect("temp dir");
+    let payload_log_path = temp_dir
+        .path()
+        .join("hook-metadata-disabled-payloads.jsonl");
+
+    let mut disabled_mutation_spec = hook_spec_with_command(
+        "metadata-emitter",
+        vec![
+            "sh".to_string(),
+            "-c".to_string(),
+            "printf '%s' '{\"metadata\":{\"risk_score\":0.72}}'".to_string(),
+        ],
+    );
+    disabled_mutation_spec.mutate = hook_mutation_config(false);
+
+    let mut events = std::collections::HashMap::new();
+    events.insert(HookPhaseEvent::PreLoopStart, vec![disabled_mutation_spec]);
+    events.insert(
+        HookPhaseEvent::PostLoopStart,
+        vec![payload_recording_hook(
+            "payload-recorder",
+            &payload_log_path,
+        )],
+    );
+
+    let hook_engine = hook_engine_with_events(events);
+    let hook_executor = HookExecutor::new();
+    let diagnostics = DiagnosticsCollector::disabled();
+    let loop_ctx = LoopContext::primary(temp_dir.path().to_path_buf());
+    let mut accumulated_hook_metadata = serde_json::Map::new();
+    accumulated_hook_metadata.insert("upstream".to_string(), serde_json::json!("preserved"));
+
+    let pre_outcomes = dispatch_phase_event_hooks(
+        &diagnostics,
+        true,
+        "loop-test",
+        &hook_engine,
+        &hook_executor,
+        HookPhaseEvent::PreLoopStart,
+        build_loop_start_payload_input_with_metadata(
+            "loop-test",
+            &loop_ctx,
+            5,
+            0,
+            Some("planner".to_string()),
+            &accumulated_hook_metadata,
+        ),
+    );
+
+    assert_eq!(pre_outcomes.len(), 1);
+    assert_eq!(pre_outcomes[0].disposition, HookDisposition::Pass);
+    assert_eq!(pre_outcomes[0].failure, None);
+    assert_eq!(
+        pre_outcomes[0].mutation_parse_outcome,
+        HookMutationParseOutcome::Disabled
+    );
+
+    let metadata_before_merge = accumulated_hook_metadata.clone();
+    merge_accumulated_hook_metadata_from_outcomes(&mut accumulated_hook_metadata, &pre_outcomes);
+    assert_eq!(accumulated_hook_metadata, metadata_before_merge);
+
+    let post_outcomes = dispatch_phase_event_hooks(
+        &diagnostics,
+        true,
+        "loop-test",
+        &hook_engine,
+        &hook_executor,
+        HookPhaseEvent::PostLoopStart,
+        build_loop_start_payload_input_with_metadata(
+            "loop-test",
+            &loop_ctx,
+            5,
+            0,
+            Some("planner".to_string()),
+            &accumulated_hook_metadata,
+        ),
+    );
+    merge_accumulated_hook_metadata_from_outcomes(&mut accumulated_hook_metadata, &post_outcomes);
+
+    let payloads = read_hook_payload_log(&payload_log_path);
+    assert_eq!(payloads.len(), 1);
+    assert_eq!(
+        payloads[0]["metadata"]["accumulated"],
+        serde_json::json!({"upstream":"preserved"})
+    );
+
+    let payload_accumulated = payloads[0]["metadata"]["accumulated"]
+        .as_object()
+        .expect("metadata.accumulated object");
+    assert!(!payload_accumulated.contains_key("hook_metadata"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_ac14_mutation_enabled_updates_only_namespaced_metadata_in_downstream_payloads() {
+    let temp_dir = tempfile::tempdir().expect("temp dir");
+    let payload_log_path = temp_dir.path().join("hook-metadata-enabled-payloads.jsonl");
+
+    let mut mutation_spec = hook_spec_with_command(
+        "metadata-emitter",
+        vec![
+            "sh".to_string(),
+            "-c".to_string(),
+            "printf '%s' '{\"metadata\":{\"risk_score\":0.72,\"gates\":[\"policy_check\"]}}'"
+                .to_string(),
+        ],
+    );
+    mutation_spec.mutate = hook_mutation_config(true);
+
+    let mut events = std::collections::HashMap::new();
+    events.insert(HookPhaseEvent::PreLoopStart, vec![mutation_spec]);
+    events.insert(
+        HookPhaseEvent::PostLoopStart,
+        vec![payload_recording_hook(
+            "payload-recorder",
+            &payload_log_path,
+        )],
+    );
+
+    let hook_engine = hook_engine_with_events(events);
+    let hook_executor = HookExecutor::new();
+    let diagnostics = DiagnosticsCollector::disabled();
+    let loop_ctx = LoopContext::primary(temp_dir.path().to_path_buf());
+    let mut accumulated_hook_metadata = serde_json::Map::new();
+    accumulated_hook_metadata.insert("upstream".to_string(), serde_json::json!("preserved"));
+
+    let pre_outcomes = dispatch_phase_event_hooks(
+        &diagnostics,
+        true,
+        "loop-test",
+        &hook_engine,
+        &hook_executor,
+        HookPhaseEvent::PreLoopStart,
+        build_loop_start_payload_input_with_metadata(
+            "loop-test",
+            &loop_ctx,
+            5,
+            0,
+            Some("planner".to_string()),
+            &accumulated_hook_metadata,
+        ),
+    );
+    assert!(matches!(
+        pre_outcomes[0].mutation_parse_outcome,
+        HookMutationParseOutcome::Parsed { .. }
+    ));
+
+    merge_accumulated_hook_metadata_from_outcomes(&mut accumulated_hook_metadata, &pre_outcomes);
+    assert_eq!(
+        serde_json::Value::Object(accumulated_hook_metadata.clone()),
+        serde_json::json!({
+            "upstream": "preserved",
+            "hook_metadata": {
+                "metadata-emitter": {
+                    "risk_score": 0.72,
+                    "gates": ["policy_check"]
+                }
+            }
+        })
+    );
+
+    let post_outcomes = dispatch_phase_event_hooks(
+        &diagnostics,
+        true,
+        "loop-test",
+        &hook_engine,
+        &hook_executor,
+        HookPhaseEvent::PostLoopStart,
+        build_loop_start_payload_input_with_metadata(
+            "loop-test",
+            &loop_ctx,
+            5,
+            0,
+            Some("planner".to_string()),
+            &accumulated_hook_metadata,
+        ),
+    );
+    merge_accumulated_hook_metadata_from_outcomes(&mut accumulated_hook_metadata, &post_outcomes);
+
+    let payloads = read_hook_payload_log(&payload_log_path);
+    assert_eq!(payloads.len(), 1);
+    let payload = &payloads[0];
+
+    assert_eq!(payload["phase_event"], serde_json::json!("post.loop.start"));
+    assert_eq!(
+        payload["context"]["active_hat"],
+        serde_json::json!("planner")
+    );
+    assert_eq!(
+        payload["metadata"]["accumulated"],
+        serde_json::json!({
+            "upstream": "preserved",
+            "hook_metadata": {
+                "metadata-emitter": {
+                    "risk_score": 0.72,
+                    "gates": ["policy_check"]
+                }
+            }
+        })
+    );
+
+    let payload_object = payload.as_object().expect("payload object");
+    assert!(!payload_object.contains_key("prompt"));
+    assert!(!payload_object.contains_key("events"));
+    assert!(!payload_object.contains_key("config"));
+
+    let context = payload["context"]
+        .as_object()
+        .expect("payload context object");
+    assert!(!context.contains_key("prompt"));
+    assert!(!context.contains_key("events"));
+    assert!(!context.contains_key("config"));
+
+    let payload_accumulated = payload["metadata"]["accumulated"]
+        .as_object()
+        .expect("metadata.accumulated object");
+    assert!(!payload_accumulated.contains_key("risk_score"));
+    assert!(!payload_accumulated.contains_key("gates"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_dispatch_phase_event_hooks_noop_when_disabled_or_unconfigured() {
+    let temp_dir = tempfile::tempdir().expect("temp dir");
+    let log_path = temp_dir.path().join("hook-noop.log");
+
+    let mut events = std::collections::HashMap::new();
+    events.insert(
+        HookPhaseEvent::PreIterationStart,
+        vec![recording_hook("should-not-run", &log_path)],
+    );
+
+    let hook_engine = hook_engine_with_events(events);
+    let empty_engine = hook_engine_with_events(std::collections::HashMap::new());
+    let hook_executor = HookExecutor::new();
+    let diagnostics = DiagnosticsCollector::disabled();
+    let loop_ctx = LoopContext::primary(temp_dir.path().to_path_buf());
+
+    let disabled_outcomes = dispatch_phase_event_hooks(
+        &diagnostics,
+        false,
+        "loop-test",
+        &hook_engine,
+        &hook_executor,
+        HookPhaseEvent::PreIterationStart,
+        build_iteration_start_payload_input(
+            "loop-test",
+            &loop_ctx,
+            5,
+            1,
+            Some("ralph".to_string()),
+            Some("builder".to_string()),
+            Some("task-123".to_string()),
+        ),
+    );
+
+    let empty_outcomes = dispatch_phase_event_hooks(
+        &diagnostics,
+        true,
+        "loop-test",
+        &empty_engine,
+        &hook_executor,
+        HookPhaseEvent::PreIterationStart,
+        build_iteration_start_payload_input(
+            "loop-test",
+            &loop_ctx,
+            5,
+            1,
+            Some("ralph".to_string()),
+            Some("builder".to_string()),
+            Some("task-123".to_string()),
+        ),
+    );
+
+    let mismatched_phase_outcomes = dispatch_phase_event_hooks(
+        &diagnostics,
+        true,
+        "loop-test",
+        &hook_engine,
+        &hook_executor,
+        HookPhaseEvent::PostLoopStart,
+        build_loop_start_payload_input("loop-test", &loop_ctx, 5, 1, Some("ralph".to_string())),
+    );
+
+    assert!(
+        disabled_outcomes.is_empty(),
+        "disabled hooks must be a no-op"
+    );
+    assert!(
+        empty_outcomes.is_empty(),
+        "empty hooks config must be a no-op"
+    );
+    assert!(
+        mismatched_phase_outcomes.is_empty(),
+        "dispatching a phase without hooks must be a no-op"
+    );
+    assert!(
+        !log_path.exists(),
+        "hook log should not be created on no-op paths"
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_dispatch_phase_event_hooks_returns_dispositions_and_failure_context() {
+    let temp_dir = tempfile::tempdir().expect("temp dir");
+
+    let mut events = std::collections::HashMap::new();
+    events.insert(
+        HookPhaseEvent::PreLoopStart,
+        vec![
+            hook_spec_with_command(
+                "hook-pass",
+                vec!["sh".to_string(), "-c".to_string(), "exit 0".to_string()],
+            ),
+            hook_spec_with_command(
+                "hook-warn",
+                vec!["sh".to_string(), "-c".to_string(), "exit 7".to_string()],
+            ),
+            hook_spec_with_command_and_on_error(
+                "hook-block",
+                vec!["sh".to_string(), "-c".to_string(), "exit 23".to_string()],
+                HookOnError::Block,
+            ),
+        ],
+    );
+
+    let hook_engine = hook_engine_with_events(events);
+    let hook_executor = HookExecutor::new();
+    let diagnostics = DiagnosticsCollector::disabled();
+    let loop_ctx = LoopContext::primary(temp_dir.path().to_path_buf());
+
+    let outcomes = dispatch_phase_event_hooks(
+        &diagnostics,
+        true,
+        "loop-test",
+        &hook_engine,
+        &hook_executor,
+        HookPhaseEvent::PreLoopStart,
+        build_loop_start_payload_input("loop-test", &loop_ctx, 5, 1, Some("ralph".to_string())),
+    );
+
+    assert_eq!(outcomes.len(), 3);
+
+    assert_eq!(outcomes[0].hook_name, "hook-pass");
+    assert_eq!(outcomes[0].phase_event, HookPhaseEvent::PreLoopStart);
+    assert_eq!(outcomes[0].disposition, HookDisposition::Pass);
+    assert!(outcomes[0].failure.is_none());
+
+    assert_eq!(outcomes[1].hook_name, "hook-warn");
+    assert_eq!(outcomes[1].disposition, HookDisposition::Warn);
+    assert_eq!(
+        outcomes[1].failure,
+        Some(HookDispatchFailure::HookRunFailed {
+            exit_code: Some(7),
+            timed_out: false,
+        })
+    );
+
+    assert_eq!(outcomes[2].hook_name, "hook-block");
+    assert_eq!(outcomes[2].disposition, HookDisposition::Block);
+    assert_eq!(
+        outcomes[2].failure,
+        Some(HookDispatchFailure::HookRunFailed {
+            exit_code: Some(23),
+            timed_out: false,
+        })
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_dispatch_phase_event_hooks_maps_executor_failures_to_on_error_disposition() {
+    let temp_dir = tempfile::tempdir().expect("temp dir");
+
+    let mut events = std::collections::HashMap::new();
+    events.insert(
+        HookPhaseEvent::PreLoopStart,
+        vec![
+            hook_spec_with_command(
+                "warn-exec-error",
+                vec!["definitely-not-a-real-exec-warn".to_string()],
+            ),
+            hook_spec_with_command_and_on_error(
+                "block-exec-error",
+                vec!["definitely-not-a-real-exec-block".to_string()],
+                HookOnError::Block,
+            ),
+        ],
+    );
+
+    let hook_engine = hook_engine_with_events(events);
+    let hook_executor = HookExecutor::new();
+    let diagnostics = DiagnosticsCollector::disabled();
+    let loop_ctx = LoopContext::primary(temp_dir.path().to_path_buf());
+
+    let outcomes = dispatch_phase_event_hooks(
+        &diagnostics,
+        true,
+        "loop-test",
+        &hook_engine,
+        &hook_executor,
+        HookPhaseEvent::PreLoopStart,
+        build_loop_start_payload_input("loop-test", &loop_ctx, 5, 1, Some("ralph".to_string())),
+    );
+
+    assert_eq!(outcomes.len(), 2);
+    assert_eq!(outcomes[0].hook_name, "warn-exec-error");
+    assert_eq!(outcomes[0].disposition, HookDisposition::Warn);
+    match &outcomes[0].failure {
+        Some(HookDispatchFailure::HookExecutionError { message }) => {
+            assert!(
+                message.contains("definitely-not-a-real-exec-warn"),
+                "executor failure context should include missing command"
+            );
+        }
+        other => panic!("expected execution error failure context, got {other:?}"),
+    }
+
+    assert_eq!(outcomes[1].hook_name, "block-exec-error");
+    assert_eq!(outcomes[1].disposition, HookDisposition::Block);
+    match &outcomes[1].failure {
+        Some(HookDispatchFailure::HookExecutionError { message }) => {
+            assert!(
+                message.contains("definitely-not-a-real-exec-block"),
+                "executor failure context should include missing command"
+            );
+        }
+        other => panic!("expected execution error failure context, got {other:?}"),
+    }
+}
+
+// AC-15: JSON-only mutation format errors must flow through lifecycle on_error dispositions.
+#[cfg(unix)]
+#[test]
+fn test_ac15_dispatch_phase_event_hooks_non_json_mutation_warn_continues_through_block_gate() {
+    let temp_dir = tempfile::tempdir().expect("temp dir");
+
+    let mut warn_hook = hook_spec_with_command_and_on_error(
+        "warn-invalid-mutation",
+        vec![
+            "sh".to_string(),
+            "-c".to_string(),
+            "printf '%s' 'oops'".to_string(),
+        ],
+        HookOnError::Warn,
+    );
+    warn_hook.mutate = hook_mutation_config(true);
+
+    let mut events = std::collections::HashMap::new();
+    events.insert(HookPhaseEvent::PreLoopStart, vec![warn_hook]);
+
+    let hook_engine = hook_engine_with_events(events);
+    let hook_executor = HookExecutor::new();
+    let diagnostics = DiagnosticsCollector::disabled();
+    let loop_ctx = LoopContext::primary(temp_dir.path().to_path_buf());
+
+    let outcomes = dispatch_phase_event_hooks(
+        &diagnostics,
+        true,
+        "loop-test",
+        &hook_engine,
+        &hook_executor,
+        HookPhaseEvent::PreLoopStart,
+        build_loop_start_payload_input("loop-test", &loop_ctx, 5, 1, Some("ralph".to_string())),
+    );
+
+    assert_eq!(outcomes.len(), 1);
+    assert_eq!(outcomes[0].disposition, HookDisposition::Warn);
+    assert!(matches!(
+        outcomes[0].mutation_parse_outcome,
+        HookMutationParseOutcome::Invalid(_)
+    ));
+    assert!(matches!(
+        &outcomes[0].failure,
+        Some(HookDispatchFailure::InvalidMutationOutput { message })
+        if message.contains("not valid JSON")
+    ));
+    assert!(fail_if_blocking_outcomes(&outcomes).is_ok());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_ac15_dispatch_phase_event_hooks_non_json_mutation_block_surfaces_invalid_output_reason() {
+    let temp_dir = tempfile::tempdir().expect("temp dir");
+
+    let mut block_hook = hook_spec_with_command_and_on_error(
+        "block-invalid-mutation",
+        vec![
+            "sh".to_string(),
+            "-c".to_string(),
+            "printf '%s' 'oops'".to_string(),
+        ],
+        HookOnError::Block,
+    );
+    block_hook.mutate = hook_mutation_config(true);
+
+    let mut events = std::collections::HashMap::new();
+    events.insert(HookPhaseEvent::PreLoopStart, vec![block_hook]);
+
+    let hook_engine = hook_engine_with_events(events);
+    let hook_executor = HookExecutor::new();
+    let diagnostics = DiagnosticsCollector::disabled();
+    let loop_ctx = LoopContext::primary(temp_dir.path().to_path_buf());
+
+    let outcomes = dispatch_phase_event_hooks(
+        &diagnostics,
+        true,
+        "loop-test",
+        &hook_engine,
+        &hook_executor,
+        HookPhaseEvent::PreLoopStart,
+        build_loop_start_payload_input("loop-test", &loop_ctx, 5, 1, Some("ralph".to_string())),
+    );
+
+    assert_eq!(outcomes.len(), 1);
+    assert_eq!(outcomes[0].disposition, HookDisposition::Block);
+    assert!(matches!(
+        &outcomes[0].failure,
+        Some(HookDispatchFailure::InvalidMutationOutput { message })
+        if message.contains("not valid JSON")
+    ));
+
+    let block_error = fail_if_blocking_outcomes(&outcomes)
+        .expect_err("block disposition should fail loop.start boundary");
+    let block_message = block_error.to_string();
+    assert!(block_message.contains("block-invalid-mutation"));
+    assert!(block_message.contains("pre.loop.start"));
+    assert!(block_message.contains("invalid mutation output"));
+    assert!(block_message.contains("not valid JSON"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_dispatch_phase_event_hooks_runtime_failure_takes_precedence_over_mutation_parse_error() {
+    let temp_dir = tempfile::tempdir().expect("temp dir");
+
+    let mut block_hook = hook_spec_with_command_and_on_error(
+        "block-runtime-failure",
+        vec![
+            "sh".to_string(),
+            "-c".to_string(),
+            "printf '%s' 'oops'; exit 23".to_string(),
+        ],
+        HookOnError::Block,
+    );
+    block_hook.mutate = hook_mutation_config(true);
+
+    let mut events = std::collections::HashMap::new();
+    events.insert(HookPhaseEvent::PreLoopStart, vec![block_hook]);
+
+    let hook_engine = hook_engine_with_events(events);
+    let hook_executor = HookExecutor::new();
+    let diagnostics = DiagnosticsCollector::disabled();
+    let loop_ctx = LoopContext::primary(temp_dir.path().to_path_buf());
+
+    let outcomes = dispatch_phase_event_hooks(
+        &diagnostics,
+        true,
+        "loop-test",
+        &hook_engine,
+        &hook_executor,
+        HookPhaseEvent::PreLoopStart,
+        build_loop_start_payload_input("loop-test", &loop_ctx, 5, 1, Some("ralph".to_string())),
+    );
+
+    assert_eq!(outcomes.len(), 1);
+    assert_eq!(outcomes[0].disposition, HookDisposition::Block);
+    assert_eq!(
+        outcomes[0].failure,
+        Some(HookDispatchFailure::HookRunFailed {
+            exit_code: Some(23),
+            timed_out: false,
+        })
+    );
+    assert!(matches!(
+        outcomes[0].mutation_parse_outcome,
+        HookMutationParseOutcome::Invalid(_)
+    ));
+
+    let block_error = fail_if_blocking_outcomes(&outcomes)
+        .expect_err("block disposition should fail loop.start boundary");
+    let block_message = block_error.to_string();
+    assert!(block_message.contains("hook exited with code 23"));
+    assert!(!block_message.contains("invalid mutation output"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_ac15_dispatch_phase_event_hooks_non_json_mutation_suspend_uses_wait_for_resume_gate() {
+    let temp_dir = tempfile::tempdir().expect("temp dir");
+
+    let mut suspend_hook = hook_spec_with_command_and_on_error(
+        "suspend-invalid-mutation",
+        vec![
+            "sh".to_string(),
+            "-c".to_string(),
+            "printf '%s' 'oops'".to_string(),
+        ],
+        HookOnError::Suspend,
+    );
+    suspend_hook.mutate = hook_mutation_config(true);
+
+    let mut events = std::collections::HashMap::new();
+    events.insert(HookPhaseEvent::PreIterationStart, vec![suspend_hook]);
+
+    let hook_engine = hook_engine_with_events(events);
+    let hook_executor = HookExecutor::new();
+    let diagnostics = DiagnosticsCollector::disabled();
+    let loop_ctx = LoopContext::primary(temp_dir.path().to_path_buf());
+    let suspend_state_store = SuspendStateStore::new(temp_dir.path());
+
+    let outcomes = dispatch_phase_event_hooks(
+        &diagnostics,
+        true,
+        "loop-test",
+        &hook_engine,
+        &hook_executor,
+        HookPhaseEvent::PreIterationStart,
+        build_iteration_start_payload_input(
+            "loop-test",
+            &loop_ctx,
+            5,
+            1,
+            Some("planner".to_string()),
+            None,
+            None,
+        ),
+    );
+
+    assert_eq!(outcomes.len(), 1);
+    assert_eq!(outcomes[0].disposition, HookDisposition::Suspend);
+    assert!(matches!(
+        &outcomes[0].failure,
+        Some(HookDispatchFailure::InvalidMutationOutput { message })
+        if message.contains("not valid JSON")
+    ));
+    assert!(fail_if_blocking_outcomes(&outcomes).is_ok());
+
+    let resume_store = suspend_state_store.clone();
+    let resume_handle = std::thread::spawn(move || {
+        let wait_started_at = std::time::Instant::now();
+        while !resume_store.suspend_state_path().exists() {
+            assert!(
+                wait_started_at.elapsed() < Duration::from_secs(2),
+                "suspend-state should be written before resume"
+            );
+            std::thread::sleep(Duration::from_millis(10));
+        }
+
+        let suspend_state = resume_store
+            .read_suspend_state()
+            .expect("read suspend-state")
+            .expect("suspend-state should exist while waiting");
+        assert!(suspend_state.reason.contains("invalid mutation output"));
+        assert!(suspend_state.reason.contains("not valid JSON"));
+
+        resume_store
+            .write_resume_requested()
+            .expect("write resume signal");
+    });
+
+    let wait_result = block_on_test_future(wait_for_resume_if_suspended(
+        &outcomes,
+        "loop-test",
+        &suspend_state_store,
+    ))
+    .expect("wait helper should succeed");
+
+    resume_handle
+        .join()
+        .expect("resume helper thread should not panic");
+
+    assert_eq!(wait_result, None);
+    assert!(
+        suspend_state_store
+            .read_suspend_state()
+            .expect("read suspend-state after resume")
+            .is_none(),
+        "suspend-state should be cleared after resume"
+    );
+    assert!(
+        !suspend_state_store.resume_requested_path().exists(),
+        "resume-requested should be consumed after resume"
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_loop_start_dispatch_warn_continues_and_block_aborts() {
+    let temp_dir = tempfile::tempdir().expect("temp dir");
+
+    let mut events = std::collections::HashMap::new();
+    events.insert(
+        HookPhaseEvent::PreLoopStart,
+        vec![hook_spec_with_command_and_on_error(
+            "warn-pre-loop-start",
+            vec!["sh".to_string(), "-c".to_string(), "exit 17".to_string()],
+            HookOnError::Warn,
+        )],
+    );
+    events.insert(
+        HookPhaseEvent::PostLoopStart,
+        vec![hook_spec_with_command_and_on_error(
+            "block-post-loop-start",
+            vec!["sh".to_string(), "-c".to_string(), "exit 29".to_string()],
+            HookOnError::Block,
+        )],
+    );
+
+    let hook_engine = hook_engine_with_events(events);
+    let hook_executor = HookExecutor::new();
+    let diagnostics = DiagnosticsCollector::disabled();
+    let loop_ctx = LoopContext::primary(temp_dir.path().to_path_buf());
+
+    let pre_loop_start_outcomes = dispatch_phase_event_hooks(
+        &diagnostics,
+        true,
+        "loop-test",
+        &hook_engine,
+        &hook_executor,
+        HookPhaseEvent::PreLoopStart,
+        build_loop_start_payload_input("loop-test", &loop_ctx, 5, 0, None),
+    );
+
+    assert_eq!(pre_loop_start_outcomes.len(), 1);
+    assert_eq!(
+        pre_loop_start_outcomes[0].disposition,
+        HookDisposition::Warn
+    );
+    assert_eq!(
+        pre_loop_start_outcomes[0].failure,
+        Some(HookDispatchFailure::HookRunFailed {
+            exit_code: Some(17),
+            timed_out: false,
+        })
+    );
+    assert!(
+        fail_if_blocking_outcomes(&pre_loop_start_outcomes).is_ok(),
+        "warn disposition should continue across loop.start boundary"
+    );
+
+    let post_loop_start_outcomes = dispatch_phase_event_hooks(
+        &diagnostics,
+        true,
+        "loop-test",
+        &hook_engine,
+        &hook_executor,
+        HookPhaseEvent::PostLoopStart,
+        build_loop_start_payload_input("loop-test", &loop_ctx, 5, 0, Some("planner".to_string())),
+    );
+
+    assert_eq!(post_loop_start_outcomes.len(), 1);
+    assert_eq!(
+        post_loop_start_outcomes[0].disposition,
+        HookDisposition::Block
+    );
+    let post_loop_start_error = fail_if_blocking_outcomes(&post_loop_start_outcomes)
+        .expect_err("block disposition should abort loop.start boundary");
+    let post_loop_start_message = post_loop_start_error.to_string();
+    assert!(post_loop_start_message.contains("block-post-loop-start"));
+    assert!(post_loop_start_message.contains("post.loop.start"));
+    assert!(post_loop_start_message.contains("hook exited with code 29"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_iteration_start_dispatch_warn_continues_and_block_aborts() {
+    let temp_dir = tempfile::tempdir().expect("temp dir");
+
+    let mut events = std::collections::HashMap::new();
+    events.insert(
+        HookPhaseEvent::PreIterationStart,
+        vec![hook_spec_with_command_and_on_error(
+            "warn-pre-iteration-start",
+            vec!["sh".to_string(), "-c".to_string(), "exit 19".to_string()],
+            HookOnError::Warn,
+        )],
+    );
+    events.insert(
+        HookPhaseEvent::PostIterationStart,
+        vec![hook_spec_with_command_and_on_error(
+            "block-post-iteration-start",
+            vec!["sh".to_string(), "-c".to_string(), "exit 31".to_string()],
+            HookOnError::Block,
+        )],
+    );
+
+    let hook_engine = hook_engine_with_events(events);
+    let hook_executor = HookExecutor::new();
+    let diagnostics = DiagnosticsCollector::disabled();
+    let loop_ctx = LoopContext::primary(temp_dir.path().to_path_buf());
+
+    let pre_iteration_start_outcomes = dispatch_phase_event_hooks(
+        &diagnostics,
+        true,
+        "loop-test",
+        &hook_engine,
+        &hook_executor,
+        HookPhaseEvent::PreIterationStart,
+        build_iteration_start_payload_input(
+            "loop-test",
+            &loop_ctx,
+            5,
+            1,
+            Some("planner".to_string()),
+            None,
+            None,
+        ),
+    );
+
+    assert_eq!(pre_iteration_start_outcomes.len(), 1);
+    assert_eq!(
+        pre_iteration_start_outcomes[0].disposition,
+        HookDisposition::Warn
+    );
+    assert_eq!(
+        pre_iteration_start_outcomes[0].failure,
+        Some(HookDispatchFailure::HookRunFailed {
+            exit_code: Some(19),
+            timed_out: false,
+        })
+    );
+    assert!(
+        fail_if_blocking_outcomes(&pre_iteration_start_outcomes).is_ok(),
+        "warn disposition should continue across iteration.start boundary"
+    );
+
+    let post_iteration_start_outcomes = dispatch_phase_event_hooks(
+        &diagnostics,
+        true,
+        "loop-test",
+        &hook_engine,
+        &hook_executor,
+        HookPhaseEvent::PostIterationStart,
+        build_iteration_start_payload_input(
+            "loop-test",
+            &loop_ctx,
+            5,
+            1,
+            Some("planner".to_string()),
+            Some("builder".to_string()),
+            Some("task-123".to_string()),
+        ),
+    );
+
+    assert_eq!(post_iteration_start_outcomes.len(), 1);
+    assert_eq!(
+        post_iteration_start_outcomes[0].disposition,
+        HookDisposition::Block
+    );
+    let post_iteration_start_error = fail_if_blocking_outcomes(&post_iteration_start_outcomes)
+        .expect_err("block disposition should abort iteration.start boundary");
+    let post_iteration_start_message = post_iteration_start_error.to_string();
+    assert!(post_iteration_start_message.contains("block-post-iteration-start"));
+    assert!(post_iteration_start_message.contains("post.iteration.start"));
+    assert!(post_iteration_start_message.contains("hook exited with code 31"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_plan_created_lifecycle_hooks_dispatch_only_for_semantic_plan_batches() {
+    use std::io::Write;
+
+    let temp_dir = tempfile::tempdir().expect("temp dir");
+    let (mut event_loop, loop_ctx) = test_event_loop_with_context(temp_dir.path());
+    let diagnostics = DiagnosticsCollector::disabled();
+    let events_path = loop_ctx.events_path();
+    std::fs::create_dir_all(events_path.parent().expect("events path parent"))
+        .expect("create events directory");
+
+    let mut events_file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&events_path)
+        .expect("open events file");
+    writeln!(
+        events_file,
+        r#"{{"topic":"task.start","payload":"noop","ts":"2024-01-01T00:00:00Z"}}"#
+    )
+    .expect("write non-plan event");
+    events_file.flush().expect("flush non-plan event");
+
+    let log_path = temp_dir.path().join("plan-created-hook-payloads.jsonl");
+    let mut events = std::collections::HashMap::new();
+    events.insert(
+        HookPhaseEvent::PrePlanCreated,
+        vec![payload_recording_hook("pre-plan-created", &log_path)],
+    );
+    events.insert(
+        HookPhaseEvent::PostPlanCreated,
+        vec![payload_recording_hook("post-plan-created", &log_path)],
+    );
+    let hook_engine = hook_engine_with_events(events);
+    let hook_executor = HookExecutor::new();
+
+    assert!(
+        !event_loop
+            .has_pending_plan_events_in_jsonl()
+            .expect("peek non-plan events"),
+        "non-plan batches must not trigger pre.plan.created"
+    );
+
+    let processed_non_plan = event_loop
+        .process_events_from_jsonl()
+        .expect("process non-plan batch");
+    assert!(processed_non_plan.had_events);
+    assert!(
+        !processed_non_plan.had_plan_events,
+        "non-plan batches must not trigger post.plan.created"
+    );
+    assert!(
+        !log_path.exists(),
+        "plan.created hooks should not run for non-plan batches"
+    );
+
+    writeln!(
+        events_file,
+        r#"{{"topic":"plan.created","payload":"ready","ts":"2024-01-01T00:00:01Z"}}"#
+    )
+    .expect("write plan event");
+    events_file.flush().expect("flush plan event");
+
+    assert!(
+        event_loop
+            .has_pending_plan_events_in_jsonl()
+            .expect("peek plan events"),
+        "plan.* batches should trigger pre.plan.created"
+    );
+
+    let pre_outcomes = dispatch_phase_event_hooks(
+        &diagnostics,
+        true,
+        "loop-test",
+        &hook_engine,
+        &hook_executor,
+        HookPhaseEvent::PrePlanCreated,
+        build_plan_created_payload_input(
+            "loop-test",
+            &loop_ctx,
+            5,
+            event_loop.state().iteration,
+            Some("planner".to_string()),
+            Some("planner".to_string()),
+            None,
+        ),
+    );
+    assert!(fail_if_blocking_outcomes(&pre_outcomes).is_ok());
+
+    let processed_plan = event_loop
+        .process_events_from_jsonl()
+        .expect("process plan batch");
+    assert!(processed_plan.had_events);
+    assert!(
+        processed_plan.had_plan_events,
+        "plan.* batches should trigger post.plan.created"
+    );
+
+    let post_outcomes = dispatch_phase_event_hooks(
+        &diagnostics,
+        true,
+        "loop-test",
+        &hook_engine,
+        &hook_executor,
+        HookPhaseEvent::PostPlanCreated,
+        build_plan_created_payload_input(
+            "loop-test",
+            &loop_ctx,
+            5,
+            event_loop.state().iteration,
+            Some("planner".to_string()),
+            Some("planner".to_string()),
+            None,
+        ),
+    );
+    assert!(fail_if_blocking_outcomes(&post_outcomes).is_ok());
+
+    let payloads = read_hook_payload_log(&log_path);
+    let observed_phases: Vec<&str> = payloads
+        .iter()
+        .map(|payload| {
+            payload["phase_event"]
+                .as_str()
+                .expect("phase_event should be present")
+        })
+        .collect();
+
+    assert_eq!(
+        observed_phases,
+        vec!["pre.plan.created", "post.plan.created"],
+        "plan.created hooks should dispatch exactly once around semantic plan batches"
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_pre_event_publish_hooks_dispatch_per_matching_topic_before_consuming() {
+    use std::io::Write;
+
+    let temp_dir = tempfile::tempdir().expect("temp dir");
+    let log_path = temp_dir.path().join("event-publish-hook-payloads.jsonl");
+    let mut release_audit = payload_recording_hook("release-audit", &log_path);
+    release_audit.topics = vec!["release.*".to_string()];
+    let mut events = std::collections::HashMap::new();
+    events.insert(
+        HookPhaseEvent::PreEventPublish,
+        vec![
+            payload_recording_hook("audit-all", &log_path),
+            release_audit,
+        ],
+    );
+    let (mut event_loop, loop_ctx) = hooked_event_loop(temp_dir.path(), events);
+
+    let events_path = loop_ctx.events_path();
+    std::fs::create_dir_all(events_path.parent().expect("events path parent"))
+        .expect("create events directory");
+    let mut events_file = std::fs::File::create(&events_path).expect("create events file");
+    for (topic, ts) in [
+        ("build.done", "2024-01-01T00:00:00Z"),
+        ("release.approved", "2024-01-01T00:00:01Z"),
+        ("release.rejected", "2024-01-01T00:00:02Z"),
+    ] {
+        writeln!(
+            events_file,
+            r#"{{"topic":"{topic}","payload":"from {topic}","ts":"{ts}"}}"#
+        )
+        .expect("write event");
+    }
+    events_file.flush().expect("flush events");
+
+    let outcomes = event_loop.dispatch_event_publish_hooks(1, "builder");
+    assert_eq!(outcomes.len(), 5);
+    assert!(fail_if_blocking_outcomes(&outcomes).is_ok());
+
+    let payloads = read_hook_payload_log(&log_path);
+    let observed: Vec<&str> = payloads
+        .iter()
+        .map(|payload| {
+            payload["context"]["event"]["topic"]
+                .as_str()
+                .expect("event topic should be present")
+        })
+        .collect();
+    assert_eq!(
+        observed,
+        vec![
+            "build.done",
+            "release.approved",
+            "release.approved",
+            "release.rejected",
+            "release.rejected",
+        ]
+    );
+    assert_eq!(payloads[0]["phase_event"], "pre.event.publish");
+    assert_eq!(
+        payloads[0]["context"]["event"]["payload"],
+        "from build.done"
+    );
+    assert_eq!(payloads[0]["context"]["active_hat"], "builder");
+
+    let mut events = std::collections::HashMap::new();
+    let mut release_gate = hook_spec_with_command_and_on_error(
+        "release-gate",
+        vec!["sh".to_string(), "-c".to_string(), "exit 2".to_string()],
+        HookOnError::Block,
+    );
+    release_gate.topics = vec!["release.*".to_string()];
+    events.insert(HookPhaseEvent::PreEventPublish, vec![release_gate]);
+    let (mut blocking_event_loop, _) = hooked_event_loop(temp_dir.path(), events);
+
+    let outcomes = blocking_event_loop.dispatch_event_publish_hooks(1, "builder");
+    assert_eq!(outcomes.len(), 1, "dispatch should stop at the first block");
+    let error = blocking_event_loop
+        .hooks()
+        .resolve_blocking(&outcomes)
+        .expect_err("blocking hook should fail the boundary");
+    assert!(matches!(error, HookBoundaryError::Blocked { .. }));
+    let message = error.to_string();
+    assert!(message.contains("release-gate"), "{message}");
+    assert!(message.contains("pre.event.publish"), "{message}");
+
+    assert_eq!(
+        blocking_event_loop
+            .pending_events_in_jsonl()
+            .expect("peek events")
+            .len(),
+        3,
+        "pre.event.publish hooks must not consume unread events"
+    );
+}
+
+#[cfg(unix)]
+fn mutation_hook(name: &str, stdout: &str, topics: &[&str]) -> crate::HookSpec {
+    let mut spec = hook_spec_with_command(
+        name,
+        vec![
+            "sh".to_string(),
+            "-c".to_string(),
+            "cat >/dev/null; printf '%s' \"$1\"".to_string(),
+            "hook-mutation".to_string(),
+            stdout.to_string(),
+        ],
+    );
+    spec.mutate = hook_mutation_config(true);
+    spec.topics = topics.iter().map(|topic| (*topic).to_string()).collect();
+    spec
+}
+
+#[cfg(unix)]
+#[test]
+fn test_ac19_pre_event_publish_mutation_actions_reject_rewrite_publish_and_guide() {
+    use std::io::Write;
+
+    let temp_dir = tempfile::tempdir().expect("temp dir");
+    let mut events = std::collections::HashMap::new();
+    events.insert(
+        HookPhaseEvent::PreEventPublish,
+        vec![
+            mutation_hook(
+                "release-veto",
+                r#"{"reject":{"reason":"release window is closed"}}"#,
+                &["release.*"],
+            ),
+            mutation_hook(
+                "secret-scrubber",
+                r#"{"rewrite":{"payload":"token=[redacted]"}}"#,
+                &["build.task"],
+            ),
+            mutation_hook(
+                "audit-trail",
+                r#"{"publish":[{"topic":"audit.requested","payload":"review scrubbed build task"}],"guidance":"Never echo credentials into events"}"#,
+                &["build.task"],
+            ),
+        ],
+    );
+    let (mut event_loop, loop_ctx) = hooked_event_loop(temp_dir.path(), events);
+
+    let events_path = loop_ctx.events_path();
+    std::fs::create_dir_all(events_path.parent().expect("events path parent"))
+        .expect("create events directory");
+    let mut events_file = std::fs::File::create(&events_path).expect("create events file");
+    for (topic, payload, ts) in [
+        ("release.approved", "ship v2", "2024-01-01T00:00:00Z"),
+        ("build.task", "token=hunter2", "2024-01-01T00:00:01Z"),
+    ] {
+        writeln!(
+            events_file,
+            r#"{{"topic":"{topic}","payload":"{payload}","ts":"{ts}"}}"#
+        )
+        .expect("write event");
+    }
+    events_file.flush().expect("flush events");
+
+    let outcomes = event_loop.dispatch_event_publish_hooks(1, "builder");
+    assert_eq!(outcomes.len(), 3);
+    assert!(
+        outcomes
+            .iter()
+            .all(|outcome| outcome.disposition == HookDisposition::Pass)
+    );
+
+    let processed = event_loop
+        .process_events_from_jsonl()
+        .expect("process events");
+    assert!(processed.had_events);
+
+    let prompt = event_loop
+        .build_prompt(&HatId::new("ralph"))
+        .expect("ralph prompt");
+    assert!(
+        prompt.contains(
+            "Event 'release.approved' was rejected by a lifecycle hook: release window is closed (hook 'release-veto')"
+        ),
+        "{prompt}"
+    );
+    assert!(!prompt.contains("ship v2"), "{prompt}");
+    assert!(prompt.contains("token=[redacted]"), "{prompt}");
+    assert!(!prompt.contains("hunter2"), "{prompt}");
+    assert!(prompt.contains("audit.requested"), "{prompt}");
+    assert!(
+        prompt.contains("Never echo credentials into events"),
+        "{prompt}"
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_ac19_hook_mutation_actions_are_recorded_in_hook_run_telemetry() {
+    let temp_dir = tempfile::tempdir().expect("temp dir");
+
+    let mut events = std::collections::HashMap::new();
+    events.insert(
+        HookPhaseEvent::PreIterationStart,
+        vec![
+            mutation_hook(
+                "coach",
+                r#"{"metadata":{"focus":"tests"},"guidance":"Write the failing test first"}"#,
+                &[],
+            ),
+            mutation_hook(
+                "misplaced-veto",
+                r#"{"reject":{"reason":"nothing to reject here"}}"#,
+                &[],
+            ),
+        ],
+    );
+    let (mut event_loop, _) = hooked_event_loop(temp_dir.path(), events);
+
+    let outcomes = event_loop.dispatch_lifecycle_hooks(
+        HookPhaseEvent::PreIterationStart,
+        1,
+        HookPayloadContextInput::default(),
+    );
+    assert_eq!(outcomes[0].disposition, HookDisposition::Pass);
+    assert_eq!(outcomes[1].disposition, HookDisposition::Warn);
+    assert!(matches!(
+        outcomes[1].failure,
+        Some(HookDispatchFailure::InvalidMutationOutput { .. })
+    ));
+    assert_eq!(
+        serde_json::Value::Object(event_loop.hooks().accumulated_metadata().clone()),
+        serde_json::json!({"hook_metadata": {"coach": {"focus": "tests"}}})
+    );
+
+    let telemetry_entries = read_hook_run_telemetry_entries(temp_dir.path());
+    assert_eq!(telemetry_entries.len(), 2);
+    assert_eq!(
+        telemetry_entries[0].mutation,
+        Some(HookMutationTelemetry {
+            actions: vec!["metadata".to_string(), "guidance".to_string()],
+            error: None,
+        })
+    );
+    let rejected = telemetry_entries[1]
+        .mutation
+        .as_ref()
+        .expect("invalid mutation should be recorded");
+    assert!(rejected.actions.is_empty());
+    assert!(
+        rejected
+            .error
+            .as_deref()
+            .is_some_and(|error| error.contains("only supported on 'pre.event.publish'"))
+    );
+
+    let prompt = event_loop
+        .build_prompt(&HatId::new("ralph"))
+        .expect("ralph prompt");
+    assert!(prompt.contains("Write the failing test first"), "{prompt}");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_hat_lifecycle_hooks_receive_selected_hat() {
+    let temp_dir = tempfile::tempdir().expect("temp dir");
+
+    let log_path = temp_dir.path().join("hat-hook-payloads.jsonl");
+    let mut events = std::collections::HashMap::new();
+    events.insert(
+        HookPhaseEvent::PreHatActivate,
+        vec![payload_recording_hook("pre-hat", &log_path)],
+    );
+    events.insert(
+        HookPhaseEvent::PostHatComplete,
+        vec![payload_recording_hook("post-hat", &log_path)],
+    );
+    let (mut event_loop, _) = hooked_event_loop(temp_dir.path(), events);
+
+    for phase_event in [
+        HookPhaseEvent::PreHatActivate,
+        HookPhaseEvent::PostHatComplete,
+    ] {
+        let outcomes = event_loop.dispatch_lifecycle_hooks(
+            phase_event,
+            2,
+            HookPayloadContextInput {
+                active_hat: Some("reviewer".to_string()),
+                selected_hat: Some("reviewer".to_string()),
+                ..HookPayloadContextInput::default()
+            },
+        );
+        let resolution = event_loop
+            .hooks()
+            .resolve_blocking(&outcomes)
+            .expect("hat boundary should not block");
+        assert_eq!(resolution, None);
+    }
+
+    let payloads = read_hook_payload_log(&log_path);
+    assert_eq!(payloads.len(), 2);
+    assert_eq!(payloads[0]["phase_event"], "pre.hat.activate");
+    assert_eq!(payloads[1]["phase_event"], "post.hat.complete");
+    for payload in &payloads {
+        assert_eq!(payload["loop"]["id"], "loop-test");
+        assert_eq!(payload["context"]["selected_hat"], "reviewer");
+        assert_eq!(payload["iteration"]["current"], 2);
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn test_human_interact_lifecycle_hooks_dispatch_with_post_outcome_context() {
+    use std::io::Write;
+
+    let temp_dir = tempfile::tempdir().expect("temp dir");
+    let (mut event_loop, loop_ctx) = test_event_loop_with_context(temp_dir.path());
+    let diagnostics = DiagnosticsCollector::disabled();
+    let events_path = loop_ctx.events_path();
+    std::fs::create_dir_all(events_path.parent().expect("events path parent"))
+        .expect("create events directory");
+
+    let mut events_file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&events_path)
+        .expect("open events file");
+    writeln!(
+        events_file,
+        r#"{{"topic":"human.interact","payload":"Need approval?","ts":"2024-01-01T00:00:00Z"}}"#
+    )
+    .expect("write human.interact event");
+    events_file.flush().expect("flush human.interact event");
+
+    let log_path = temp_dir.path().join("human-interact-hook-payloads.jsonl");
+    let mut events = std::collections::HashMap::new();
+    events.insert(
+        HookPhaseEvent::PreHumanInteract,
+        vec![payload_recording_hook("pre-human-interact", &log_path)],
+    );
+    events.insert(
+        HookPhaseEvent::PostHumanInteract,
+        vec![payload_recording_hook("post-human-interact", &log_path)],
+    );
+    let hook_engine = hook_engine_with_events(events);
+    let hook_executor = HookExecutor::new();
+
+    let pending_context = event_loop
+        .pending_human_interact_context_in_jsonl()
+        .expect("peek pending human.interact context")
+        .expect("pending human.interact context should exist");
+    assert_eq!(
+        pending_context["question"],
+        serde_json::json!("Need approval?")
+    );
+    assert!(
+        pending_context.get("outcome").is_none(),
+        "pre human.interact context should not include an outcome"
+    );
+
+    let pre_outcomes = dispatch_phase_event_hooks(
+        &diagnostics,
+        true,
+        "loop-test",
+        &hook_engine,
+        &hook_executor,
+        HookPhaseEvent::PreHumanInteract,
+        build_human_interact_payload_input(
+            "loop-test",
+            &loop_ctx,
+            5,
+            event_loop.state().iteration,
+            Some("planner".to_string()),
+            Some("planner".to_string()),
+            None,
+            Some(pending_context),
+        ),
+    );
+    assert!(fail_if_blocking_outcomes(&pre_outcomes).is_ok());
+
+    let processed = event_loop
+        .process_events_from_jsonl()
+        .expect("process human.interact batch");
+    let post_context = processed
+        .human_interact_context
+        .expect("processed context should include human.interact outcome");
+    assert_eq!(
+        post_context["question"],
+        serde_json::json!("Need approval?")
+    );
+    assert_eq!(
+        post_context["outcome"],
+        serde_json::json!("no_robot_service")
+    );
+
+    let post_outcomes = dispatch_phase_event_hooks(
+        &diagnostics,
+        true,
+        "loop-test",
+        &hook_engine,
+        &hook_executor,
+        HookPhaseEvent::PostHumanInteract,
+        build_human_interact_payload_input(
+            "loop-test",
+            &loop_ctx,
+            5,
+            event_loop.state().iteration,
+            Some("planner".to_string()),
+            Some("planner".to_string()),
+            None,
+            Some(post_context),
+        ),
+    );
+    assert!(fail_if_blocking_outcomes(&post_outcomes).is_ok());
+
+    let payloads = read_hook_payload_log(&log_path);
+    assert_eq!(payloads.len(), 2);
+    assert_eq!(
+        payloads[0]["phase_event"],
+        serde_json::json!("pre.human.interact")
+    );
+    assert_eq!(
+        payloads[0]["context"]["human_interact"]["question"],
+        serde_json::json!("Need approval?")
+    );
+    assert!(
+        payloads[0]["context"]["human_interact"]
+            .get("outcome")
+            .is_none(),
+        "pre.human.interact payload should not include outcome"
+    );
+
+    assert_eq!(
+        payloads[1]["phase_event"],
+        serde_json::json!("post.human.interact")
+    );
+    assert_eq!(
+        payloads[1]["context"]["human_interact"]["question"],
+        serde_json::json!("Need approval?")
+    );
+    assert_eq!(
+        payloads[1]["context"]["human_interact"]["outcome"],
+        serde_json::json!("no_robot_service")
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_loop_termination_lifecycle_hooks_dispatch_complete_and_error_boundaries() {
+    let temp_dir = tempfile::tempdir().expect("temp dir");
+    let log_path = temp_dir.path().join("loop-termination-hook-payloads.jsonl");
+
+    let mut events = std::collections::HashMap::new();
+    events.insert(
+        HookPhaseEvent::PreLoopComplete,
+        vec![payload_recording_hook("pre-loop-complete", &log_path)],
+    );
+    events.insert(
+        HookPhaseEvent::PostLoopComplete,
+        vec![payload_recording_hook("post-loop-complete", &log_path)],
+    );
+    events.insert(
+        HookPhaseEvent::PreLoopError,
+        vec![payload_recording_hook("pre-loop-error", &log_path)],
+    );
+    events.insert(
+        HookPhaseEvent::PostLoopError,
+        vec![payload_recording_hook("post-loop-error", &log_path)],
+    );
+    let (mut event_loop, _) = hooked_event_loop(temp_dir.path(), events);
+
+    let (completed_reason, terminate_event) = block_on_test_future(
+        event_loop.publish_terminate_event(TerminationReason::CompletionPromise),
+    );
+    assert_eq!(completed_reason, TerminationReason::CompletionPromise);
+    assert_eq!(terminate_event.topic.as_str(), "loop.terminate");
+
+    let (error_reason, _) =
+        block_on_test_future(event_loop.publish_terminate_event(TerminationReason::MaxRuntime));
+    assert_eq!(error_reason, TerminationReason::MaxRuntime);
+
+    let payloads = read_hook_payload_log(&log_path);
+    let phases: Vec<&str> = payloads
+        .iter()
+        .map(|payload| {
+            payload["phase_event"]
+                .as_str()
+                .expect("phase_event should be present")
+        })
+        .collect();
+    let reasons: Vec<&str> = payloads
+        .iter()
+        .map(|payload| {
+            payload["context"]["termination_reason"]
+                .as_str()
+                .expect("termination_reason should be present")
+        })
+        .collect();
+
+    assert_eq!(
+        phases,
+        vec![
+            "pre.loop.complete",
+            "post.loop.complete",
+            "pre.loop.error",
+            "post.loop.error"
+        ]
+    );
+    assert_eq!(
+        reasons,
+        vec!["completed", "completed", "max_runtime", "max_runtime"]
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_event_loop_runs_lifecycle_phases_from_its_own_methods() {
+    use std::io::Write;
+
+    let temp_dir = tempfile::tempdir().expect("temp dir");
+    let log_path = temp_dir.path().join("lifecycle-hooks.log");
+    let events = [
+        HookPhaseEvent::PreLoopStart,
+        HookPhaseEvent::PostLoopStart,
+        HookPhaseEvent::PreIterationStart,
+        HookPhaseEvent::PostIterationStart,
+        HookPhaseEvent::PreHatActivate,
+        HookPhaseEvent::PostHatComplete,
+        HookPhaseEvent::PrePlanCreated,
+        HookPhaseEvent::PostPlanCreated,
+        HookPhaseEvent::PreHumanInteract,
+        HookPhaseEvent::PostHumanInteract,
+        HookPhaseEvent::PreEventPublish,
+        HookPhaseEvent::PreLoopComplete,
+        HookPhaseEvent::PostLoopComplete,
+    ]
+    .into_iter()
+    .map(|phase_event| {
+        let hook = recording_hook(&phase_event.to_string(), &log_path);
+        (phase_event, vec![hook])
+    })
+    .collect();
+    let (mut event_loop, loop_ctx) = hooked_event_loop(temp_dir.path(), events);
+
+    event_loop.initialize("Ship the release");
+    let hat_id = event_loop
+        .next_hat()
+        .cloned()
+        .expect("start event routes to a hat");
+    assert_eq!(event_loop.process_output(&hat_id, "done", true), None);
+
+    let events_path = loop_ctx.events_path();
+    std::fs::create_dir_all(events_path.parent().expect("events path parent"))
+        .expect("create events directory");
+    let mut events_file = std::fs::File::create(&events_path).expect("create events file");
+    for (topic, ts) in [
+        ("plan.created", "2024-01-01T00:00:00Z"),
+        ("human.interact", "2024-01-01T00:00:01Z"),
+    ] {
+        writeln!(
+            events_file,
+            r#"{{"topic":"{topic}","payload":"from {topic}","ts":"{ts}"}}"#
+        )
+        .expect("write event");
+    }
+    events_file.flush().expect("flush events");
+
+    let processed = event_loop
+        .process_events_from_jsonl()
+        .expect("process events");
+    assert!(processed.had_plan_events);
+
+    let (reason, _) = block_on_test_future(
+        event_loop.publish_terminate_event(TerminationReason::CompletionPromise),
+    );
+    assert_eq!(reason, TerminationReason::CompletionPromise);
+
+    let phases: Vec<String> = read_hook_log(&log_path)
+        .iter()
+        .map(|line| line.split('|').nth(1).expect("phase column").to_string())
+        .collect();
+    assert_eq!(
+        phases,
+        vec![
+            "pre.loop.start",
+            "post.loop.start",
+            "pre.iteration.start",
+            "post.iteration.start",
+            "pre.hat.activate",
+            "post.hat.complete",
+            "pre.plan.created",
+            "pre.human.interact",
+            "pre.event.publish",
+            "pre.event.publish",
+            "post.human.interact",
+            "post.plan.created",
+            "pre.loop.complete",
+            "post.loop.complete",
+        ]
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_event_loop_blocking_hook_stops_the_loop_through_check_termination() {
+    let temp_dir = tempfile::tempdir().expect("temp dir");
+    let log_path = temp_dir.path().join("lifecycle-hooks.log");
+
+    let mut events = std::collections::HashMap::new();
+    events.insert(
+        HookPhaseEvent::PreIterationStart,
+        vec![hook_spec_with_command_and_on_error(
+            "block-pre-iteration-start",
+            vec!["sh".to_string(), "-c".to_string(), "exit 23".to_string()],
+            HookOnError::Block,
+        )],
+    );
+    events.insert(
+        HookPhaseEvent::PreHatActivate,
+        vec![recording_hook("pre-hat", &log_path)],
+    );
+    events.insert(
+        HookPhaseEvent::PreLoopError,
+        vec![recording_hook("pre-loop-error", &log_path)],
+    );
+    let (mut event_loop, _) = hooked_event_loop(temp_dir.path(), events);
+
+    event_loop.initialize("Ship the release");
+    assert_eq!(
+        event_loop.next_hat(),
+        None,
+        "a blocked iteration selects no hat"
+    );
+    assert!(event_loop.has_pending_events());
+    assert_eq!(
+        event_loop.check_termination(),
+        Some(TerminationReason::HookBlocked)
+    );
+    assert_eq!(
+        event_loop.next_hat(),
+        None,
+        "no further boundaries run once the loop is stopping"
+    );
+
+    let (reason, _) =
+        block_on_test_future(event_loop.publish_terminate_event(TerminationReason::HookBlocked));
+    assert_eq!(reason, TerminationReason::HookBlocked);
+    assert_eq!(reason.exit_code(), 1);
+    assert_eq!(
+        read_hook_log(&log_path),
+        vec!["pre-loop-error|pre.loop.error"]
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_event_loop_suspended_loop_start_stops_when_stop_is_requested() {
+    let temp_dir = tempfile::tempdir().expect("temp dir");
+
+    let mut events = std::collections::HashMap::new();
+    events.insert(
+        HookPhaseEvent::PreLoopStart,
+        vec![hook_spec_with_command_and_on_error(
+            "suspend-pre-loop-start",
+            vec!["sh".to_string(), "-c".to_string(), "exit 41".to_string()],
+            HookOnError::Suspend,
+        )],
+    );
+    let (mut event_loop, _) = hooked_event_loop(temp_dir.path(), events);
+
+    event_loop.initialize("Ship the release");
+    assert!(event_loop.is_hook_suspended());
+    assert_eq!(event_loop.check_termination(), None);
+
+    std::fs::write(temp_dir.path().join(".ralph").join("stop-requested"), "")
+        .expect("write stop signal");
+    block_on_test_future(event_loop.await_hook_resume());
+
+    assert!(!event_loop.is_hook_suspended());
+    assert!(
+        !event_loop.has_pending_events(),
+        "the start event must not be published when pre.loop.start stops the loop"
+    );
+    assert_eq!(
+        event_loop.check_termination(),
+        Some(TerminationReason::Stopped)
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_event_loop_suspended_iteration_start_does_not_block_the_runtime() {
+    let temp_dir = tempfile::tempdir().expect("temp dir");
+    let log_path = temp_dir.path().join("lifecycle-hooks.log");
+
+    let mut events = std::collections::HashMap::new();
+    events.insert(
+        HookPhaseEvent::PreIterationStart,
+        vec![hook_spec_with_command_and_on_error(
+            "suspend-pre-iteration-start",
+            vec!["sh".to_string(), "-c".to_string(), "exit 41".to_string()],
+            HookOnError::Suspend,
+        )],
+    );
+    events.insert(
+        HookPhaseEvent::PreHatActivate,
+        vec![recording_hook("pre-hat", &log_path)],
+    );
+    let (mut event_loop, _) = hooked_event_loop(temp_dir.path(), events);
+
+    event_loop.initialize("Ship the release");
+    assert_eq!(
+        event_loop.next_hat(),
+        None,
+        "a suspended iteration selects no hat yet"
+    );
+    assert!(event_loop.is_hook_suspended());
+    assert!(!log_path.exists(), "pre.hat.activate waits for the resume");
+
+    let suspend_state_store = SuspendStateStore::new(temp_dir.path());
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_time()
+        .build()
+        .expect("build tokio runtime");
+    let ticks = runtime.block_on(async {
+        let ticker = tokio::spawn(async move {
+            while !suspend_state_store.suspend_state_path().exists() {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+            // Keep making progress while the loop waits on the suspend.
+            let mut ticks_while_suspended = 0_u32;
+            for _ in 0..5 {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                if suspend_state_store.suspend_state_path().exists() {
+                    ticks_while_suspended += 1;
+                }
+            }
+            suspend_state_store
+                .write_resume_requested()
+                .expect("write resume signal");
+            ticks_while_suspended
+        });
+
+        event_loop.await_hook_resume().await;
+        ticker.await.expect("ticker task")
+    });
+
+    assert_eq!(
+        ticks, 5,
+        "a task sharing the current-thread runtime must make progress while the loop is suspended"
+    );
+    assert!(!event_loop.is_hook_suspended());
+    assert_eq!(event_loop.check_termination(), None);
+    assert!(
+        event_loop.next_hat().is_some(),
+        "the resumed iteration hands out the routed hat"
+    );
+    assert_eq!(read_hook_log(&log_path), vec!["pre-hat|pre.hat.activate"]);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_iteration_start_suspend_waits_for_resume_and_clears_artifacts_before_continuing() {
+    let temp_dir = tempfile::tempdir().expect("temp dir");
+
+    let mut events = std::collections::HashMap::new();
+    events.insert(
+        HookPhaseEvent::PreIterationStart,
+        vec![hook_spec_with_command_and_on_error(
+            "suspend-pre-iteration-start",
+            vec!["sh".to_string(), "-c".to_string(), "exit 41".to_string()],
+            HookOnError::Suspend,
+        )],
+    );
+
+    let hook_engine = hook_engine_with_events(events);
+    let hook_executor = HookExecutor::new();
+    let diagnostics = DiagnosticsCollector::disabled();
+    let loop_ctx = LoopContext::primary(temp_dir.path().to_path_buf());
+
+    let pre_iteration_start_outcomes = dispatch_phase_event_hooks(
+        &diagnostics,
+        true,
+        "loop-test",
+        &hook_engine,
+        &hook_executor,
+        HookPhaseEvent::PreIterationStart,
+        build_iteration_start_payload_input(
+            "loop-test",
+            &loop_ctx,
+            5,
+            1,
+            Some("planner".to_string()),
+            None,
+            None,
+        ),
+    );
+
+    assert_eq!(pre_iteration_start_outcomes.len(), 1);
+    assert_eq!(
+        pre_iteration_start_outcomes[0].disposition,
+        HookDisposition::Suspend
+    );
+    assert_eq!(
+        pre_iteration_start_outcomes[0].failure,
+        Some(HookDispatchFailure::HookRunFailed {
+            exit_code: Some(41),
+            timed_out: false,
+        })
+    );
+    assert!(
+        fail_if_blocking_outcomes(&pre_iteration_start_outcomes).is_ok(),
+        "suspend disposition should not block iteration.start boundary"
+    );
+
+    let suspend_state_store = SuspendStateStore::new(temp_dir.path());
+
+    let wait_result = block_on_test_future(async {
+        let wait_outcomes = pre_iteration_start_outcomes.clone();
+        let wait_store = suspend_state_store.clone();
+        let wait_handle = tokio::spawn(async move {
+            wait_for_resume_if_suspended(&wait_outcomes, "loop-test", &wait_store).await
+        });
+
+        tokio::time::timeout(Duration::from_secs(2), async {
+            loop {
+                if suspend_state_store.suspend_state_path().exists() {
+                    break;
+                }
+
+                tokio::time::sleep(Duration::from_millis(25)).await;
+            }
+        })
+        .await
+        .expect("suspend-state should be written before resume");
+
+        let suspend_state = suspend_state_store
+            .read_suspend_state()
+            .expect("read suspend-state")
+            .expect("suspend-state should exist while waiting for resume");
+
+        assert_eq!(suspend_state.loop_id, "loop-test");
+        assert_eq!(suspend_state.phase_event, HookPhaseEvent::PreIterationStart);
+        assert_eq!(suspend_state.hook_name, "suspend-pre-iteration-start");
+        assert_eq!(suspend_state.suspend_mode, HookSuspendMode::WaitForResume);
+        assert!(!suspend_state_store.resume_requested_path().exists());
+
+        suspend_state_store
+            .write_resume_requested()
+            .expect("write resume signal");
+
+        tokio::time::timeout(Duration::from_secs(2), wait_handle)
+            .await
+            .expect("wait_for_resume helper should complete after resume signal")
+            .expect("wait_for_resume task should not panic")
+    })
+    .expect("wait helper should succeed");
+
+    assert_eq!(wait_result, None);
+    assert!(
+        suspend_state_store
+            .read_suspend_state()
+            .expect("read suspend-state after resume")
+            .is_none(),
+        "suspend-state should be cleared after resume"
+    );
+    assert!(
+        !suspend_state_store.resume_requested_path().exists(),
+        "resume-requested should be consumed after resume"
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_dispatch_phase_event_hooks_retry_backoff_recovers_before_exhaustion() {
+    let temp_dir = tempfile::tempdir().expect("temp dir");
+    let attempts_path = temp_dir.path().join("retry-backoff-attempts.txt");
+
+    let mut events = std::collections::HashMap::new();
+    events.insert(
+        HookPhaseEvent::PreIterationStart,
+        vec![hook_spec_with_command_and_on_error_and_suspend_mode(
+            "retry-backoff-pre-iteration-start",
+            vec![
+                "sh".to_string(),
+                "-c".to_string(),
+                r#"attempts_file="$1"
+attempt=0
+if [ -f "$attempts_file" ]; then
+  attempt="$(cat "$attempts_file")"
+fi
+attempt=$((attempt + 1))
+printf '%s' "$attempt" > "$attempts_file"
+if [ "$attempt" -lt 3 ]; then
+  exit 41
+fi
+exit 0"#
+                    .to_string(),
+                "retry-backoff-hook".to_string(),
+                attempts_path.to_string_lossy().into_owned(),
+            ],
+            HookOnError::Suspend,
+            Some(HookSuspendMode::RetryBackoff),
+        )],
+    );
+
+    let hook_engine = hook_engine_with_events(events);
+    let hook_executor = HookExecutor::new();
+    let diagnostics = telemetry_collector(temp_dir.path());
+    let loop_ctx = LoopContext::primary(temp_dir.path().to_path_buf());
+
+    let outcomes = dispatch_phase_event_hooks(
+        &diagnostics,
+        true,
+        "loop-test",
+        &hook_engine,
+        &hook_executor,
+        HookPhaseEvent::PreIterationStart,
+        build_iteration_start_payload_input(
+            "loop-test",
+            &loop_ctx,
+            5,
+            1,
+            Some("planner".to_string()),
+            None,
+            None,
+        ),
+    );
+
+    assert_eq!(outcomes.len(), 1);
+    assert_eq!(outcomes[0].disposition, HookDisposition::Pass);
+    assert_eq!(outcomes[0].suspend_mode, HookSuspendMode::RetryBackoff);
+    assert_eq!(outcomes[0].failure, None);
+
+    let attempts = std::fs::read_to_string(&attempts_path).expect("read attempts");
+    assert_eq!(attempts.trim(), "3", "hook should recover on third attempt");
+
+    let telemetry_entries = read_hook_run_telemetry_entries(temp_dir.path());
+    assert_eq!(telemetry_entries.len(), 3);
+    assert_eq!(
+        telemetry_entries
+            .iter()
+            .map(|entry| entry.retry_attempt)
+            .collect::<Vec<_>>(),
+        vec![1, 2, 3]
+    );
+    assert!(
+        telemetry_entries
+            .iter()
+            .all(|entry| entry.retry_max_attempts == 4)
+    );
+    assert!(
+        telemetry_entries
+            .iter()
+            .all(|entry| entry.suspend_mode == HookSuspendMode::RetryBackoff)
+    );
+    assert_eq!(
+        telemetry_entries
+            .iter()
+            .map(|entry| entry.disposition)
+            .collect::<Vec<_>>(),
+        vec![
+            HookDisposition::Suspend,
+            HookDisposition::Suspend,
+            HookDisposition::Pass,
+        ]
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_dispatch_phase_event_hooks_retry_backoff_exhausts_to_suspend() {
+    let temp_dir = tempfile::tempdir().expect("temp dir");
+    let attempts_path = temp_dir.path().join("retry-backoff-attempts.txt");
+
+    let mut events = std::collections::HashMap::new();
+    events.insert(
+        HookPhaseEvent::PostIterationStart,
+        vec![hook_spec_with_command_and_on_error_and_suspend_mode(
+            "retry-backoff-post-iteration-start",
+            vec![
+                "sh".to_string(),
+                "-c".to_string(),
+                r#"attempts_file="$1"
+attempt=0
+if [ -f "$attempts_file" ]; then
+  attempt="$(cat "$attempts_file")"
+fi
+attempt=$((attempt + 1))
+printf '%s' "$attempt" > "$attempts_file"
+exit 51"#
+                    .to_string(),
+                "retry-backoff-hook".to_string(),
+                attempts_path.to_string_lossy().into_owned(),
+            ],
+            HookOnError::Suspend,
+            Some(HookSuspendMode::RetryBackoff),
+        )],
+    );
+
+    let hook_engine = hook_engine_with_events(events);
+    let hook_executor = HookExecutor::new();
+    let diagnostics = DiagnosticsCollector::disabled();
+    let loop_ctx = LoopContext::primary(temp_dir.path().to_path_buf());
+
+    let outcomes = dispatch_phase_event_hooks(
+        &diagnostics,
+        true,
+        "loop-test",
+        &hook_engine,
+        &hook_executor,
+        HookPhaseEvent::PostIterationStart,
+        build_iteration_start_payload_input(
+            "loop-test",
+            &loop_ctx,
+            5,
+            1,
+            Some("planner".to_string()),
+            Some("builder".to_string()),
+            Some("task-123".to_string()),
+        ),
+    );
+
+    assert_eq!(outcomes.len(), 1);
+    assert_eq!(outcomes[0].disposition, HookDisposition::Suspend);
+    assert_eq!(outcomes[0].suspend_mode, HookSuspendMode::RetryBackoff);
+    assert_eq!(
+        outcomes[0].failure,
+        Some(HookDispatchFailure::HookRunFailed {
+            exit_code: Some(51),
+            timed_out: false,
+        })
+    );
+
+    let attempts: usize = std::fs::read_to_string(&attempts_path)
+        .expect("read attempts")
+        .trim()
+        .parse()
+        .expect("parse attempts");
+    assert_eq!(
+        attempts,
+        RETRY_BACKOFF_DELAYS_MS.len() + 1,
+        "retry_backoff should cap retries at the configured schedule"
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_dispatch_phase_event_hooks_retry_backoff_yields_to_stop_signal() {
+    let temp_dir = tempfile::tempdir().expect("temp dir");
+    let attempts_path = temp_dir.path().join("retry-backoff-attempts.txt");
+    std::fs::create_dir_all(temp_dir.path().join(".ralph")).expect("create .ralph");
+    std::fs::write(temp_dir.path().join(".ralph/stop-requested"), "").expect("write stop signal");
+
+    let mut events = std::collections::HashMap::new();
+    events.insert(
+        HookPhaseEvent::PreLoopStart,
+        vec![hook_spec_with_command_and_on_error_and_suspend_mode(
+            "retry-backoff-pre-loop-start",
+            vec![
+                "sh".to_string(),
+                "-c".to_string(),
+                r#"attempts_file="$1"
+attempt=0
+if [ -f "$attempts_file" ]; then
+  attempt="$(cat "$attempts_file")"
+fi
+attempt=$((attempt + 1))
+printf '%s' "$attempt" > "$attempts_file"
+exit 61"#
+                    .to_string(),
+                "retry-backoff-hook".to_string(),
+                attempts_path.to_string_lossy().into_owned(),
+            ],
+            HookOnError::Suspend,
+            Some(HookSuspendMode::RetryBackoff),
+        )],
+    );
+
+    let hook_engine = hook_engine_with_events(events);
+    let hook_executor = HookExecutor::new();
+    let diagnostics = DiagnosticsCollector::disabled();
+    let loop_ctx = LoopContext::primary(temp_dir.path().to_path_buf());
+
+    let outcomes = dispatch_phase_event_hooks(
+        &diagnostics,
+        true,
+        "loop-test",
+        &hook_engine,
+        &hook_executor,
+        HookPhaseEvent::PreLoopStart,
+        build_loop_start_payload_input("loop-test", &loop_ctx, 5, 1, Some("ralph".to_string())),
+    );
+
+    let attempts = std::fs::read_to_string(&attempts_path).expect("read attempts");
+    assert_eq!(
+        attempts.trim(),
+        "1",
+        "stop signal should short-circuit retry_backoff retries"
+    );
+
+    let suspend_state_store = SuspendStateStore::new(temp_dir.path());
+    let wait_result = block_on_test_future(wait_for_resume_if_suspended(
+        &outcomes,
+        "loop-test",
+        &suspend_state_store,
+    ))
+    .expect("wait helper should succeed");
+
+    assert_eq!(wait_result, Some(TerminationReason::Stopped));
+    assert!(!temp_dir.path().join(".ralph/stop-requested").exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_dispatch_phase_event_hooks_wait_then_retry_recovers_after_resume() {
+    let temp_dir = tempfile::tempdir().expect("temp dir");
+    let attempts_path = temp_dir.path().join("wait-then-retry-attempts.txt");
+
+    let mut events = std::collections::HashMap::new();
+    events.insert(
+        HookPhaseEvent::PreIterationStart,
+        vec![hook_spec_with_command_and_on_error_and_suspend_mode(
+            "wait-then-retry-pre-iteration-start",
+            vec![
+                "sh".to_string(),
+                "-c".to_string(),
+                r#"attempts_file="$1"
+attempt=0
+if [ -f "$attempts_file" ]; then
+  attempt="$(cat "$attempts_file")"
+fi
+attempt=$((attempt + 1))
+printf '%s' "$attempt" > "$attempts_file"
+if [ "$attempt" -lt 2 ]; then
+  exit 71
+fi
+exit 0"#
+                    .to_string(),
+                "wait-then-retry-hook".to_string(),
+                attempts_path.to_string_lossy().into_owned(),
+            ],
+            HookOnError::Suspend,
+            Some(HookSuspendMode::WaitThenRetry),
+        )],
+    );
+
+    let hook_engine = hook_engine_with_events(events);
+    let hook_executor = HookExecutor::new();
+    let diagnostics = telemetry_collector(temp_dir.path());
+    let loop_ctx = LoopContext::primary(temp_dir.path().to_path_buf());
+    let suspend_state_store = SuspendStateStore::new(temp_dir.path());
+
+    let resume_store = suspend_state_store.clone();
+    let resume_handle = std::thread::spawn(move || {
+        let wait_started_at = std::time::Instant::now();
+        while !resume_store.suspend_state_path().exists() {
+            assert!(
+                wait_started_at.elapsed() < Duration::from_secs(2),
+                "wait_then_retry should persist suspend-state before waiting"
+            );
+            std::thread::sleep(Duration::from_millis(10));
+        }
+
+        resume_store
+            .write_resume_requested()
+            .expect("write resume signal");
+    });
+
+    let outcomes = dispatch_phase_event_hooks(
+        &diagnostics,
+        true,
+        "loop-test",
+        &hook_engine,
+        &hook_executor,
+        HookPhaseEvent::PreIterationStart,
+        build_iteration_start_payload_input(
+            "loop-test",
+            &loop_ctx,
+            5,
+            1,
+            Some("planner".to_string()),
+            None,
+            None,
+        ),
+    );
+
+    resume_handle
+        .join()
+        .expect("resume helper thread should not panic");
+
+    assert_eq!(outcomes.len(), 1);
+    assert_eq!(outcomes[0].disposition, HookDisposition::Pass);
+    assert_eq!(outcomes[0].suspend_mode, HookSuspendMode::WaitThenRetry);
+    assert_eq!(outcomes[0].failure, None);
+
+    let attempts = std::fs::read_to_string(&attempts_path).expect("read attempts");
+    assert_eq!(
+        attempts.trim(),
+        "2",
+        "wait_then_retry should run exactly one retry after resume"
+    );
+    assert!(
+        suspend_state_store
+            .read_suspend_state()
+            .expect("read suspend-state after wait_then_retry")
+            .is_none(),
+        "suspend-state should be cleared after wait_then_retry resume"
+    );
+    assert!(
+        !suspend_state_store.resume_requested_path().exists(),
+        "resume signal should be consumed under wait_then_retry"
+    );
+
+    let telemetry_entries = read_hook_run_telemetry_entries(temp_dir.path());
+    assert_eq!(telemetry_entries.len(), 2);
+    assert_eq!(
+        telemetry_entries
+            .iter()
+            .map(|entry| entry.retry_attempt)
+            .collect::<Vec<_>>(),
+        vec![1, 2]
+    );
+    assert!(
+        telemetry_entries
+            .iter()
+            .all(|entry| entry.retry_max_attempts == 2)
+    );
+    assert!(
+        telemetry_entries
+            .iter()
+            .all(|entry| entry.suspend_mode == HookSuspendMode::WaitThenRetry)
+    );
+    assert_eq!(
+        telemetry_entries
+            .iter()
+            .map(|entry| entry.disposition)
+            .collect::<Vec<_>>(),
+        vec![HookDisposition::Suspend, HookDisposition::Pass]
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_dispatch_phase_event_hooks_wait_then_retry_retry_failure_remains_suspended() {
+    let temp_dir = tempfile::tempdir().expect("temp dir");
+    let attempts_path = temp_dir.path().join("wait-then-retry-attempts.txt");
+
+    let mut events = std::collections::HashMap::new();
+    events.insert(
+        HookPhaseEvent::PostIterationStart,
+        vec![hook_spec_with_command_and_on_error_and_suspend_mode(
+            "wait-then-retry-post-iteration-start",
+            vec![
+                "sh".to_string(),
+                "-c".to_string(),
+                r#"attempts_file="$1"
+attempt=0
+if [ -f "$attempts_file" ]; then
+  attempt="$(cat "$attempts_file")"
+fi
+attempt=$((attempt + 1))
+printf '%s' "$attempt" > "$attempts_file"
+exit 72"#
+                    .to_string(),
+                "wait-then-retry-hook".to_string(),
+                attempts_path.to_string_lossy().into_owned(),
+            ],
+            HookOnError::Suspend,
+            Some(HookSuspendMode::WaitThenRetry),
+        )],
+    );
+
+    let hook_engine = hook_engine_with_events(events);
+    let hook_executor = HookExecutor::new();
+    let diagnostics = DiagnosticsCollector::disabled();
+    let loop_ctx = LoopContext::primary(temp_dir.path().to_path_buf());
+    let suspend_state_store = SuspendStateStore::new(temp_dir.path());
+
+    let resume_store = suspend_state_store.clone();
+    let resume_handle = std::thread::spawn(move || {
+        let wait_started_at = std::time::Instant::now();
+        while !resume_store.suspend_state_path().exists() {
+            assert!(
+                wait_started_at.elapsed() < Duration::from_secs(2),
+                "wait_then_retry should persist suspend-state before waiting"
+            );
+            std::thread::sleep(Duration::from_millis(10));
+        }
+
+        resume_store
+            .write_resume_requested()
+            .expect("write resume signal");
+    });
+
+    let outcomes = dispatch_phase_event_hooks(
+        &diagnostics,
+        true,
+        "loop-test",
+        &hook_engine,
+        &hook_executor,
+        HookPhaseEvent::PostIterationStart,
+        build_iteration_start_payload_input(
+            "loop-test",
+            &loop_ctx,
+            5,
+            1,
+            Some("planner".to_string()),
+            Some("builder".to_string()),
+            Some("task-123".to_string()),
+        ),
+    );
+
+    resume_handle
+        .join()
+        .expect("resume helper thread should not panic");
+
+    assert_eq!(outcomes.len(), 1);
+    assert_eq!(outcomes[0].disposition, HookDisposition::Suspend);
+    assert_eq!(outcomes[0].suspend_mode, HookSuspendMode::WaitThenRetry);
+    assert_eq!(
+        outcomes[0].failure,
+        Some(HookDispatchFailure::HookRunFailed {
+            exit_code: Some(72),
+            timed_out: false,
+        })
+    );
+
+    let attempts = std::fs::read_to_string(&attempts_path).expect("read attempts");
+    assert_eq!(
+        attempts.trim(),
+        "2",
+        "wait_then_retry should run a single retry attempt after resume"
+    );
+    assert!(
+        suspend_state_store
+            .read_suspend_state()
+            .expect("read suspend-state after wait_then_retry")
+            .is_none(),
+        "first wait_then_retry suspend-state should be cleared after resume"
+    );
+    assert!(
+        !suspend_state_store.resume_requested_path().exists(),
+        "resume signal should be consumed after wait_then_retry"
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_dispatch_phase_event_hooks_wait_then_retry_prioritizes_stop_over_resume() {
+    let temp_dir = tempfile::tempdir().expect("temp dir");
+    let attempts_path = temp_dir.path().join("wait-then-retry-attempts.txt");
+    std::fs::create_dir_all(temp_dir.path().join(".ralph")).expect("create .ralph");
+    std::fs::write(temp_dir.path().join(".ralph/stop-requested"), "").expect("write stop signal");
+    std::fs::write(temp_dir.path().join(".ralph/resume-requested"), "")
+        .expect("write resume signal");
+
+    let mut events = std::collections::HashMap::new();
+    events.insert(
+        HookPhaseEvent::PreLoopStart,
+        vec![hook_spec_with_command_and_on_error_and_suspend_mode(
+            "wait-then-retry-pre-loop-start",
+            vec![
+                "sh".to_string(),
+                "-c".to_string(),
+                r#"attempts_file="$1"
+attempt=0
+if [ -f "$attempts_file" ]; then
+  attempt="$(cat "$attempts_file")"
+fi
+attempt=$((attempt + 1))
+printf '%s' "$attempt" > "$attempts_file"
+exit 73"#
+                    .to_string(),
+                "wait-then-retry-hook".to_string(),
+                attempts_path.to_string_lossy().into_owned(),
+            ],
+            HookOnError::Suspend,
+            Some(HookSuspendMode::WaitThenRetry),
+        )],
+    );
+
+    let hook_engine = hook_engine_with_events(events);
+    let hook_executor = HookExecutor::new();
+    let diagnostics = DiagnosticsCollector::disabled();
+    let loop_ctx = LoopContext::primary(temp_dir.path().to_path_buf());
+    let suspend_state_store = SuspendStateStore::new(temp_dir.path());
+
+    let outcomes = dispatch_phase_event_hooks(
+        &diagnostics,
+        true,
+        "loop-test",
+        &hook_engine,
+        &hook_executor,
+        HookPhaseEvent::PreLoopStart,
+        build_loop_start_payload_input("loop-test", &loop_ctx, 5, 1, Some("ralph".to_string())),
+    );
+
+    let attempts = std::fs::read_to_string(&attempts_path).expect("read attempts");
+    assert_eq!(
+        attempts.trim(),
+        "1",
+        "stop signal should prevent wait_then_retry from running the retry"
+    );
+
+    let wait_result = block_on_test_future(wait_for_resume_if_suspended(
+        &outcomes,
+        "loop-test",
+        &suspend_state_store,
+    ))
+    .expect("wait helper should succeed");
+
+    assert_eq!(wait_result, Some(TerminationReason::Stopped));
+    assert!(!temp_dir.path().join(".ralph/stop-requested").exists());
+    assert!(!suspend_state_store.resume_requested_path().exists());
+}
+
+#[test]
+fn test_run_retry_backoff_policy_replays_configured_schedule_deterministically() {
+    let mut observed_delays_ms = Vec::new();
+    let mut observed_retry_attempts = Vec::new();
+
+    let outcome = run_retry_backoff_policy(
+        "pre.iteration.start",
+        "retry-hook",
+        &[3, 5, 8],
+        |delay, retry_attempt| {
+            observed_delays_ms.push(delay.as_millis() as u64);
+            assert_eq!(retry_attempt, observed_delays_ms.len());
+            RetryBackoffDelayOutcome::Elapsed
+        },
+        |retry_attempt| {
+            observed_retry_attempts.push(retry_attempt);
+            if retry_attempt == 4 {
+                HookDispatchOutcome {
+                    phase_event: HookPhaseEvent::PreIterationStart,
+                    hook_name: "retry-hook".to_string(),
+                    disposition: HookDisposition::Pass,
+                    suspend_mode: HookSuspendMode::RetryBackoff,
+                    failure: None,
+
+                    mutation_parse_outcome: HookMutationParseOutcome::Disabled,
+                }
+            } else {
+                suspend_outcome_with_mode(
+                    HookPhaseEvent::PreIterationStart,
+                    "retry-hook",
+                    HookSuspendMode::RetryBackoff,
+                )
+            }
+        },
+        suspend_outcome_with_mode(
+            HookPhaseEvent::PreIterationStart,
+            "retry-hook",
+            HookSuspendMode::RetryBackoff,
+        ),
+    );
+
+    assert_eq!(observed_delays_ms, vec![3, 5, 8]);
+    assert_eq!(observed_retry_attempts, vec![2, 3, 4]);
+    assert_eq!(outcome.disposition, HookDisposition::Pass);
+    assert_eq!(outcome.failure, None);
+}
+
+#[test]
+fn test_run_retry_backoff_policy_exhausts_after_last_configured_delay() {
+    let mut observed_retry_attempts = Vec::new();
+
+    let outcome = run_retry_backoff_policy(
+        "post.iteration.start",
+        "retry-hook",
+        &[11, 13],
+        |_delay, _retry_attempt| RetryBackoffDelayOutcome::Elapsed,
+        |retry_attempt| {
+            observed_retry_attempts.push(retry_attempt);
+            suspend_outcome_with_mode(
+                HookPhaseEvent::PostIterationStart,
+                "retry-hook",
+                HookSuspendMode::RetryBackoff,
+            )
+        },
+        suspend_outcome_with_mode(
+            HookPhaseEvent::PostIterationStart,
+            "retry-hook",
+            HookSuspendMode::RetryBackoff,
+        ),
+    );
+
+    assert_eq!(observed_retry_attempts, vec![2, 3]);
+    assert_eq!(outcome.disposition, HookDisposition::Suspend);
+    assert_eq!(
+        outcome.failure,
+        Some(HookDispatchFailure::HookRunFailed {
+            exit_code: Some(41),
+            timed_out: false,
+        })
+    );
+}
+
+#[test]
+fn test_run_retry_backoff_policy_stop_signal_short_circuits_before_retry_attempt() {
+    let initial_outcome = suspend_outcome_with_mode(
+        HookPhaseEvent::PreLoopStart,
+        "retry-hook",
+        HookSuspendMode::RetryBackoff,
+    );
+    let mut retry_attempt_called = false;
+
+    let outcome = run_retry_backoff_policy(
+        "pre.loop.start",
+        "retry-hook",
+        &[21, 34],
+        |_delay, _retry_attempt| RetryBackoffDelayOutcome::StopRequested,
+        |_retry_attempt| {
+            retry_attempt_called = true;
+            initial_outcome.clone()
+        },
+        initial_outcome.clone(),
+    );
+
+    assert!(!retry_attempt_called);
+    assert_eq!(outcome, initial_outcome);
+}
+
+#[test]
+fn test_run_wait_then_retry_policy_resume_retries_once_and_returns_retry_result() {
+    let mut clear_suspend_calls = 0usize;
+    let mut retry_calls = 0usize;
+
+    let outcome = run_wait_then_retry_policy(
+        "pre.iteration.start",
+        "wait-hook",
+        || Ok(SuspendWaitOutcome::Resume),
+        || {
+            clear_suspend_calls += 1;
+            Ok(())
+        },
+        || {
+            retry_calls += 1;
+            HookDispatchOutcome {
+                phase_event: HookPhaseEvent::PreIterationStart,
+                hook_name: "wait-hook".to_string(),
+                disposition: HookDisposition::Pass,
+                suspend_mode: HookSuspendMode::WaitThenRetry,
+                failure: None,
+
+                mutation_parse_outcome: HookMutationParseOutcome::Disabled,
+            }
+        },
+        suspend_outcome_with_mode(
+            HookPhaseEvent::PreIterationStart,
+            "wait-hook",
+            HookSuspendMode::WaitThenRetry,
+        ),
+    );
+
+    assert_eq!(clear_suspend_calls, 1);
+    assert_eq!(retry_calls, 1);
+    assert_eq!(outcome.disposition, HookDisposition::Pass);
+    assert_eq!(outcome.failure, None);
+}
+
+#[test]
+fn test_run_wait_then_retry_policy_retry_failure_returns_suspend() {
+    let mut clear_suspend_calls = 0usize;
+    let mut retry_calls = 0usize;
+
+    let outcome = run_wait_then_retry_policy(
+        "post.iteration.start",
+        "wait-hook",
+        || Ok(SuspendWaitOutcome::Resume),
+        || {
+            clear_suspend_calls += 1;
+            Ok(())
+        },
+        || {
+            retry_calls += 1;
+            suspend_outcome_with_mode(
+                HookPhaseEvent::PostIterationStart,
+                "wait-hook",
+                HookSuspendMode::WaitThenRetry,
+            )
+        },
+        suspend_outcome_with_mode(
+            HookPhaseEvent::PostIterationStart,
+            "wait-hook",
+            HookSuspendMode::WaitThenRetry,
+        ),
+    );
+
+    assert_eq!(clear_suspend_calls, 1);
+    assert_eq!(retry_calls, 1);
+    assert_eq!(outcome.disposition, HookDisposition::Suspend);
+    assert_eq!(
+        outcome.failure,
+        Some(HookDispatchFailure::HookRunFailed {
+            exit_code: Some(41),
+            timed_out: false,
+        })
+    );
+}
+
+#[test]
+fn test_run_wait_then_retry_policy_stop_skips_retry_path() {
+    let initial_outcome = suspend_outcome_with_mode(
+        HookPhaseEvent::PreLoopStart,
+        "wait-hook",
+        HookSuspendMode::WaitThenRetry,
+    );
+    let mut clear_suspend_called = false;
+    let mut retry_called = false;
+
+    let outcome = run_wait_then_retry_policy(
+        "pre.loop.start",
+        "wait-hook",
+        || Ok(SuspendWaitOutcome::Stop),
+        || {
+            clear_suspend_called = true;
+            Ok(())
+        },
+        || {
+            retry_called = true;
+            HookDispatchOutcome {
+                phase_event: HookPhaseEvent::PreLoopStart,
+                hook_name: "wait-hook".to_string(),
+                disposition: HookDisposition::Pass,
+                suspend_mode: HookSuspendMode::WaitThenRetry,
+                failure: None,
+
+                mutation_parse_outcome: HookMutationParseOutcome::Disabled,
+            }
+        },
+        initial_outcome.clone(),
+    );
+
+    assert!(!clear_suspend_called);
+    assert!(!retry_called);
+    assert_eq!(outcome, initial_outcome);
+}
+
+#[test]
+fn test_fail_if_blocking_outcomes_allows_non_blocking_dispositions() {
+    let outcomes = vec![
+        HookDispatchOutcome {
+            phase_event: HookPhaseEvent::PreLoopStart,
+            hook_name: "warn-hook".to_string(),
+            disposition: HookDisposition::Warn,
+            suspend_mode: HookSuspendMode::WaitForResume,
+            failure: Some(HookDispatchFailure::HookRunFailed {
+                exit_code: Some(7),
+                timed_out: false,
+            }),
+
+            mutation_parse_outcome: HookMutationParseOutcome::Disabled,
+        },
+        HookDispatchOutcome {
+            phase_event: HookPhaseEvent::PostLoopStart,
+            hook_name: "pass-hook".to_string(),
+            disposition: HookDisposition::Pass,
+            suspend_mode: HookSuspendMode::WaitForResume,
+            failure: None,
+
+            mutation_parse_outcome: HookMutationParseOutcome::Disabled,
+        },
+    ];
+
+    assert!(fail_if_blocking_outcomes(&outcomes).is_ok());
+}
+
+#[test]
+fn test_fail_if_blocking_outcomes_surfaces_failure_context() {
+    let blocked_exit_outcomes = vec![HookDispatchOutcome {
+        phase_event: HookPhaseEvent::PostLoopStart,
+        hook_name: "block-hook".to_string(),
+        disposition: HookDisposition::Block,
+        suspend_mode: HookSuspendMode::WaitForResume,
+        failure: Some(HookDispatchFailure::HookRunFailed {
+            exit_code: Some(42),
+            timed_out: false,
+        }),
+
+        mutation_parse_outcome: HookMutationParseOutcome::Disabled,
+    }];
+
+    let blocked_exit_error = fail_if_blocking_outcomes(&blocked_exit_outcomes)
+        .expect_err("block disposition should fail loop.start boundary");
+    let blocked_exit_message = blocked_exit_error.to_string();
+    assert!(blocked_exit_message.contains("block-hook"));
+    assert!(blocked_exit_message.contains("post.loop.start"));
+    assert!(blocked_exit_message.contains("hook exited with code 42"));
+
+    let blocked_exec_outcomes = vec![HookDispatchOutcome {
+        phase_event: HookPhaseEvent::PreLoopStart,
+        hook_name: "block-exec-hook".to_string(),
+        disposition: HookDisposition::Block,
+        suspend_mode: HookSuspendMode::WaitForResume,
+        failure: Some(HookDispatchFailure::HookExecutionError {
+            message: "spawn failed".to_string(),
+        }),
+
+        mutation_parse_outcome: HookMutationParseOutcome::Disabled,
+    }];
+
+    let blocked_exec_error = fail_if_blocking_outcomes(&blocked_exec_outcomes)
+        .expect_err("block disposition should fail loop.start boundary");
+    let blocked_exec_message = blocked_exec_error.to_string();
+    assert!(blocked_exec_message.contains("block-exec-hook"));
+    assert!(blocked_exec_message.contains("pre.loop.start"));
+    assert!(blocked_exec_message.contains("hook execution failed: spawn failed"));
+
+    let blocked_timeout_outcomes = vec![HookDispatchOutcome {
+        phase_event: HookPhaseEvent::PostLoopError,
+        hook_name: "block-timeout-hook".to_string(),
+        disposition: HookDisposition::Block,
+        suspend_mode: HookSuspendMode::WaitForResume,
+        failure: Some(HookDispatchFailure::HookRunFailed {
+            exit_code: None,
+            timed_out: true,
+        }),
+
+        mutation_parse_outcome: HookMutationParseOutcome::Disabled,
+    }];
+
+    let blocked_timeout_error = fail_if_blocking_outcomes(&blocked_timeout_outcomes)
+        .expect_err("block disposition should fail loop termination boundary");
+    assert!(matches!(
+        blocked_timeout_error,
+        HookBoundaryError::Blocked { .. }
+    ));
+    let blocked_timeout_message = blocked_timeout_error.to_string();
+    assert!(blocked_timeout_message.contains("block-timeout-hook"));
+    assert!(blocked_timeout_message.contains("post.loop.error"));
+    assert!(blocked_timeout_message.contains("hook timed out"));
+}
+
+#[test]
+fn test_loop_termination_phase_events_maps_success_and_error_reasons() {
+    assert_eq!(
+        loop_termination_phase_events(&TerminationReason::CompletionPromise),
+        (
+            HookPhaseEvent::PreLoopComplete,
+            HookPhaseEvent::PostLoopComplete
+        )
+    );
+    assert_eq!(
+        loop_termination_phase_events(&TerminationReason::MaxRuntime),
+        (HookPhaseEvent::PreLoopError, HookPhaseEvent::PostLoopError)
+    );
+}
+
+#[test]
+fn test_hook_orchestrator_payload_input_stamps_loop_identity_and_context() {
+    let temp_dir = tempfile::tempdir().expect("temp dir");
+    let hooks_config = HooksConfig::default();
+    let mut orchestrator = HookOrchestrator::new(
+        &hooks_config,
+        "primary",
+        true,
+        temp_dir.path(),
+        temp_dir.path().join("repo"),
+    );
+    orchestrator.set_loop_id("loop-test");
+
+    let payload_input = orchestrator.payload_input(
+        7,
+        42,
+        HookPayloadContextInput {
+            active_hat: Some("planner".to_string()),
+            selected_hat: Some("builder".to_string()),
+            selected_task: Some("task-123".to_string()),
+            termination_reason: Some(TerminationReason::RestartRequested.as_str().to_string()),
+            metadata: json_object(serde_json::json!({"stale": true})),
+            ..HookPayloadContextInput::default()
+        },
+    );
+
+    assert!(!orchestrator.is_enabled());
+    assert_eq!(payload_input.loop_id, "loop-test");
+    assert!(payload_input.is_primary);
+    assert_eq!(payload_input.workspace, temp_dir.path());
+    assert_eq!(payload_input.repo_root, temp_dir.path().join("repo"));
+    assert_eq!(payload_input.iteration_current, 7);
+    assert_eq!(payload_input.iteration_max, 42);
+    assert_eq!(
+        payload_input.context.termination_reason.as_deref(),
+        Some("restart_requested")
+    );
+    assert_eq!(payload_input.context.active_hat.as_deref(), Some("planner"));
+    assert_eq!(
+        payload_input.context.selected_hat.as_deref(),
+        Some("builder")
+    );
+    assert_eq!(
+        payload_input.context.selected_task.as_deref(),
+        Some("task-123")
+    );
+    assert!(
+        payload_input.context.metadata.is_empty(),
+        "payload metadata should come from the orchestrator accumulator"
+    );
+}
+
+fn hook_mutation_config(enabled: bool) -> HookMutationConfig {
+    HookMutationConfig {
+        enabled,
+        format: Some("json".to_string()),
+        extra: std::collections::HashMap::new(),
+    }
+}
+
+fn json_object(value: serde_json::Value) -> serde_json::Map<String, serde_json::Value> {
+    value.as_object().cloned().expect("json object")
+}
+
+#[test]
+fn test_parse_hook_mutation_stdout_skips_when_disabled() {
+    let outcome = parse_hook_mutation_stdout(
+        &HookMutationConfig::default(),
+        HookPhaseEvent::PreLoopStart,
+        "env-guard",
+        "not-json",
+    );
+
+    assert_eq!(outcome, HookMutationParseOutcome::Disabled);
+}
+
+#[test]
+fn test_parse_hook_mutation_stdout_accepts_metadata_only_payload_and_namespaces_by_hook() {
+    let outcome = parse_hook_mutation_stdout(
+        &hook_mutation_config(true),
+        HookPhaseEvent::PreLoopStart,
+        "env-guard",
+        r#"{"metadata":{"risk_score":0.72,"gates":["policy_check"]}}"#,
+    );
+
+    let HookMutationParseOutcome::Parsed {
+        namespaced_metadata,
+        actions,
+    } = outcome
+    else {
+        panic!("expected parsed mutation payload");
+    };
+
+    assert_eq!(
+        serde_json::Value::Object(namespaced_metadata),
+        serde_json::json!({
+            "hook_metadata": {
+                "env-guard": {
+                    "risk_score": 0.72,
+                    "gates": ["policy_check"]
+                }
+            }
+        })
+    );
+    assert_eq!(actions, HookMutationActions::default());
+}
+
+#[test]
+fn test_parse_hook_mutation_stdout_accepts_structured_actions_on_event_publish() {
+    let outcome = parse_hook_mutation_stdout(
+        &hook_mutation_config(true),
+        HookPhaseEvent::PreEventPublish,
+        "release-gate",
+        r#"{
+            "rewrite": {"payload": "redacted"},
+            "publish": [{"topic": "audit.requested", "payload": "check release"}, {"topic": "audit.noted"}],
+            "guidance": "Cite the audit log in the next summary"
+        }"#,
+    );
+
+    let HookMutationParseOutcome::Parsed {
+        namespaced_metadata,
+        actions,
+    } = &outcome
+    else {
+        panic!("expected parsed mutation payload, got {outcome:?}");
+    };
+
+    assert!(namespaced_metadata.is_empty());
+    assert_eq!(
+        *actions,
+        HookMutationActions {
+            reject: None,
+            rewrite: Some("redacted".to_string()),
+            publish: vec![
+                HookMutationEvent {
+                    topic: "audit.requested".to_string(),
+                    payload: "check release".to_string(),
+                },
+                HookMutationEvent {
+                    topic: "audit.noted".to_string(),
+                    payload: String::new(),
+                },
+            ],
+            guidance: Some("Cite the audit log in the next summary".to_string()),
+        }
+    );
+    assert_eq!(
+        hook_mutation_telemetry(&outcome),
+        Some(HookMutationTelemetry {
+            actions: vec![
+                "rewrite".to_string(),
+                "publish".to_string(),
+                "guidance".to_string(),
+            ],
+            error: None,
+        })
+    );
+}
+
+#[test]
+fn test_parse_hook_mutation_stdout_rejects_invalid_action_shapes_and_phases() {
+    let cases = [
+        (
+            HookPhaseEvent::PreIterationStart,
+            r#"{"reject":{"reason":"no"}}"#,
+            "only supported on 'pre.event.publish'",
+        ),
+        (
+            HookPhaseEvent::PreEventPublish,
+            r#"{"reject":{"reason":"  "}}"#,
+            "non-empty string 'reason'",
+        ),
+        (
+            HookPhaseEvent::PreEventPublish,
+            r#"{"reject":{"reason":"no"},"rewrite":{"payload":"x"}}"#,
+            "mutually exclusive",
+        ),
+        (
+            HookPhaseEvent::PreEventPublish,
+            r#"{"rewrite":{"payload":{"nested":true}}}"#,
+            "string 'payload'",
+        ),
+        (
+            HookPhaseEvent::PostHatComplete,
+            r#"{"publish":[{"payload":"missing topic"}]}"#,
+            "publish[0]' requires a non-empty string 'topic'",
+        ),
+        (
+            HookPhaseEvent::PostLoopComplete,
+            r#"{"guidance":"too late"}"#,
+            "no iteration follows",
+        ),
+        (
+            HookPhaseEvent::PreIterationStart,
+            "{}",
+            "supports only keys",
+        ),
+    ];
+
+    for (phase_event, stdout, expected) in cases {
+        let outcome = parse_hook_mutation_stdout(
+            &hook_mutation_config(true),
+            phase_event,
+            "policy-gate",
+            stdout,
+        );
+        let HookMutationParseOutcome::Invalid(HookMutationParseError::InvalidSchema { message }) =
+            outcome
+        else {
+            panic!("expected invalid-schema outcome for {stdout}, got {outcome:?}");
+        };
+        assert!(message.contains(expected), "{stdout}: {message}");
+    }
+}
+
+#[test]
+fn test_parse_hook_mutation_stdout_rejects_non_json_payload_when_enabled() {
+    let outcome = parse_hook_mutation_stdout(
+        &hook_mutation_config(true),
+        HookPhaseEvent::PreLoopStart,
+        "env-guard",
+        "oops",
+    );
+
+    let HookMutationParseOutcome::Invalid(HookMutationParseError::InvalidJson { message }) =
+        outcome
+    else {
+        panic!("expected invalid-json mutation parse outcome");
+    };
+
+    assert!(message.contains("valid JSON"));
+}
+
+#[test]
+fn test_parse_hook_mutation_stdout_rejects_unsupported_payload_keys() {
+    let outcome = parse_hook_mutation_stdout(
+        &hook_mutation_config(true),
+        HookPhaseEvent::PreLoopStart,
+        "env-guard",
+        r#"{"metadata":{"risk_score":0.72},"prompt":"inject"}"#,
+    );
+
+    let HookMutationParseOutcome::Invalid(HookMutationParseError::InvalidSchema { message }) =
+        outcome
+    else {
+        panic!("expected invalid-schema mutation parse outcome");
+    };
+
+    assert!(message.contains("supports only"));
+}
+
+#[test]
+fn test_merge_hook_metadata_namespace_merges_multiple_hook_entries() {
+    let mut accumulated_metadata = serde_json::Map::new();
+    accumulated_metadata.insert("upstream".to_string(), serde_json::json!("preserved"));
+
+    merge_hook_metadata_namespace(
+        &mut accumulated_metadata,
+        "env-guard",
+        json_object(serde_json::json!({"risk_score": 0.72})),
+    )
+    .expect("merge env-guard metadata");
+
+    merge_hook_metadata_namespace(
+        &mut accumulated_metadata,
+        "policy-gate",
+        json_object(serde_json::json!({"status": "pass"})),
+    )
+    .expect("merge policy-gate metadata");
+
+    assert_eq!(
+        accumulated_metadata["upstream"],
+        serde_json::json!("preserved")
+    );
+    assert_eq!(
+        accumulated_metadata["hook_metadata"]["env-guard"]["risk_score"],
+        serde_json::json!(0.72)
+    );
+    assert_eq!(
+        accumulated_metadata["hook_metadata"]["policy-gate"]["status"],
+        serde_json::json!("pass")
+    );
+}
+
+#[test]
+fn test_merge_hook_metadata_namespace_rejects_non_object_namespace_value() {
+    let mut accumulated_metadata = serde_json::Map::new();
+    accumulated_metadata.insert(
+        "hook_metadata".to_string(),
+        serde_json::Value::String("invalid".to_string()),
+    );
+
+    let merge_result = merge_hook_metadata_namespace(
+        &mut accumulated_metadata,
+        "env-guard",
+        json_object(serde_json::json!({"risk_score": 0.72})),
+    );
+
+    assert!(matches!(
+        merge_result,
+        Err(HookMutationParseError::InvalidSchema { message })
+        if message.contains("must be a JSON object")
+    ));
+}
+
+#[test]
+fn test_wait_for_resume_if_suspended_is_noop_without_suspend_dispositions() {
+    let temp_dir = tempfile::tempdir().expect("temp dir");
+    let suspend_state_store = SuspendStateStore::new(temp_dir.path());
+
+    let outcomes = vec![HookDispatchOutcome {
+        phase_event: HookPhaseEvent::PreLoopStart,
+        hook_name: "warn-hook".to_string(),
+        disposition: HookDisposition::Warn,
+        suspend_mode: HookSuspendMode::WaitForResume,
+        failure: Some(HookDispatchFailure::HookRunFailed {
+            exit_code: Some(7),
+            timed_out: false,
+        }),
+
+        mutation_parse_outcome: HookMutationParseOutcome::Disabled,
+    }];
+
+    let wait_result = block_on_test_future(wait_for_resume_if_suspended(
+        &outcomes,
+        "loop-test",
+        &suspend_state_store,
+    ))
+    .expect("wait helper should succeed");
+
+    assert_eq!(wait_result, None);
+    assert!(!suspend_state_store.suspend_state_path().exists());
+    assert!(!suspend_state_store.resume_requested_path().exists());
+}
+
+#[test]
+fn test_wait_for_resume_if_suspended_resumes_and_clears_suspend_artifacts() {
+    let temp_dir = tempfile::tempdir().expect("temp dir");
+    let suspend_state_store = SuspendStateStore::new(temp_dir.path());
+    suspend_state_store
+        .write_resume_requested()
+        .expect("write resume signal");
+
+    let outcomes = vec![suspend_outcome(
+        HookPhaseEvent::PreLoopStart,
+        "suspend-hook",
+    )];
+
+    let wait_result = block_on_test_future(wait_for_resume_if_suspended(
+        &outcomes,
+        "loop-test",
+        &suspend_state_store,
+    ))
+    .expect("wait helper should succeed");
+
+    assert_eq!(wait_result, None);
+    assert!(!suspend_state_store.suspend_state_path().exists());
+    assert!(!suspend_state_store.resume_requested_path().exists());
+}
+
+#[test]
+fn test_wait_for_resume_if_suspended_prioritizes_stop_over_resume() {
+    let temp_dir = tempfile::tempdir().expect("temp dir");
+    let suspend_state_store = SuspendStateStore::new(temp_dir.path());
+
+    std::fs::create_dir_all(temp_dir.path().join(".ralph")).expect("create .ralph");
+    std::fs::write(temp_dir.path().join(".ralph/stop-requested"), "").expect("write stop signal");
+    suspend_state_store
+        .write_resume_requested()
+        .expect("write resume signal");
+
+    let outcomes = vec![suspend_outcome(
+        HookPhaseEvent::PreIterationStart,
+        "suspend-hook",
+    )];
+
+    let wait_result = block_on_test_future(wait_for_resume_if_suspended(
+        &outcomes,
+        "loop-test",
+        &suspend_state_store,
+    ))
+    .expect("wait helper should succeed");
+
+    assert_eq!(wait_result, Some(TerminationReason::Stopped));
+    assert!(!temp_dir.path().join(".ralph/stop-requested").exists());
+    assert!(!suspend_state_store.suspend_state_path().exists());
+    assert!(!suspend_state_store.resume_requested_path().exists());
+}
+
+#[test]
+fn test_wait_for_resume_if_suspended_prioritizes_restart_over_resume() {
+    let temp_dir = tempfile::tempdir().expect("temp dir");
+    let suspend_state_store = SuspendStateStore::new(temp_dir.path());
+
+    std::fs::create_dir_all(temp_dir.path().join(".ralph")).expect("create .ralph");
+    std::fs::write(temp_dir.path().join(".ralph/restart-requested"), "")
+        .expect("write restart signal");
+    suspend_state_store
+        .write_resume_requested()
+        .expect("write resume signal");
+
+    let outcomes = vec![suspend_outcome(
+        HookPhaseEvent::PostIterationStart,
+        "suspend-hook",
+    )];
+
+    let wait_result = block_on_test_future(wait_for_resume_if_suspended(
+        &outcomes,
+        "loop-test",
+        &suspend_state_store,
+    ))
+    .expect("wait helper should succeed");
+
+    assert_eq!(wait_result, Some(TerminationReason::RestartRequested));
+    assert!(temp_dir.path().join(".ralph/restart-requested").exists());
+    assert!(!suspend_state_store.suspend_state_path().exists());
+    assert!(!suspend_state_store.resume_requested_path().exists());
+}
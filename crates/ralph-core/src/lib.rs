@@ -10,6 +10,7 @@
 //! - Terminal capture for session recording
 //! - Benchmark task definitions and workspace isolation
 
+pub mod backpressure;
 #[cfg(feature = "recording")]
 mod cli_capture;
 mod config;
@@ -18,6 +19,8 @@ mod event_logger;
 mod event_loop;
 mod event_parser;
 mod event_reader;
+mod failover;
+mod fan_out;
 pub mod file_lock;
 mod git_ops;
 mod handoff;
@@ -32,9 +35,13 @@ pub mod loop_history;
 pub mod loop_lock;
 mod loop_name;
 pub mod loop_registry;
+pub mod loop_schedule;
 mod memory;
+mod memory_compact;
+mod memory_index;
 pub mod memory_parser;
 mod memory_store;
+pub mod merge_executor;
 pub mod merge_queue;
 pub mod planning_session;
 pub mod preflight;
@@ -50,23 +57,35 @@ pub mod task_definition;
 pub mod task_store;
 pub mod testing;
 mod text;
+mod topology_analysis;
 pub mod utils;
 pub mod workspace;
 pub mod worktree;
 
+pub use backpressure::{
+    BackpressureCheck, BackpressureCheckResult, BackpressureMismatch, BackpressureRunner,
+    BackpressureVerification,
+};
 #[cfg(feature = "recording")]
 pub use cli_capture::{CliCapture, CliCapturePair};
 pub use config::{
-    CliConfig, ConfigError, CoreConfig, EventLoopConfig, EventMetadata, FeaturesConfig, HatBackend,
-    HatConfig, InjectMode, MemoriesConfig, MemoriesFilter, RalphConfig, SkillOverride,
-    SkillsConfig,
+    BackpressureCommands, BackpressureConfig, CliConfig, ConfigError, CoreConfig, EventLoopConfig,
+    EventMetadata, FanOutConfig, FanOutIsolation, FeaturesConfig, HatBackend, HatConfig,
+    HatRetryConfig, InjectMode, MemoriesConfig, MemoriesFilter, MergeConfig, MergeExecutorMode,
+    MergeStrategy, QualityConfig, QualityDimension, QualityGateConfig, QualityPolicy, RalphConfig,
+    RetryEscalation, SkillOverride, SkillsConfig, SubloopConfig, WebhookRobotConfig,
 };
 // Re-export loop_name types (also available via FeaturesConfig.loop_naming)
 pub use diagnostics::DiagnosticsCollector;
 pub use event_logger::{EventHistory, EventLogger, EventRecord};
-pub use event_loop::{EventLoop, LoopState, ProcessedEvents, TerminationReason, UserPrompt};
+pub use event_loop::{
+    EventLoop, HatUsage, LoopState, PendingEventMutation, ProcessedEvents, RetryAttempt,
+    TerminationReason, UserPrompt,
+};
 pub use event_parser::EventParser;
 pub use event_reader::{Event, EventReader, MalformedLine, ParseResult};
+pub use failover::FailureClass;
+pub use fan_out::{BranchOutcome, BranchStatus, join_payload};
 pub use file_lock::{FileLock, LockGuard as FileLockGuard, LockedFile};
 pub use git_ops::{
     AutoCommitResult, GitOpsError, auto_commit_changes, clean_stashes, get_commit_summary,
@@ -77,12 +96,13 @@ pub use handoff::{HandoffError, HandoffResult, HandoffWriter};
 pub use hat_registry::HatRegistry;
 pub use hatless_ralph::{HatInfo, HatTopology, HatlessRalph};
 pub use hooks::{
-    HookDefaults, HookEngine, HookExecutor, HookExecutorContract, HookExecutorError,
-    HookInvocationPayload, HookMutationConfig, HookOnError, HookPayloadBuilderInput,
-    HookPayloadContext, HookPayloadContextInput, HookPayloadIteration, HookPayloadLoop,
-    HookPayloadMetadata, HookPhaseEvent, HookRunRequest, HookRunResult, HookSpec, HookStreamOutput,
-    HookSuspendMode, HooksConfig, ResolvedHookSpec, SUSPEND_STATE_SCHEMA_VERSION,
-    SuspendLifecycleState, SuspendStateRecord, SuspendStateStore, SuspendStateStoreError,
+    HookBoundaryError, HookDefaults, HookDispatchFailure, HookDispatchOutcome, HookEngine,
+    HookExecutor, HookExecutorContract, HookExecutorError, HookInvocationPayload,
+    HookMutationConfig, HookOnError, HookOrchestrator, HookPayloadBuilderInput, HookPayloadContext,
+    HookPayloadContextInput, HookPayloadIteration, HookPayloadLoop, HookPayloadMetadata,
+    HookPhaseEvent, HookRunRequest, HookRunResult, HookSpec, HookStreamOutput, HookSuspendMode,
+    HooksConfig, ResolvedHookSpec, SUSPEND_STATE_SCHEMA_VERSION, SuspendLifecycleState,
+    SuspendStateRecord, SuspendStateStore, SuspendStateStoreError, loop_termination_phase_events,
 };
 pub use instructions::InstructionBuilder;
 pub use landing::{LandingConfig, LandingError, LandingHandler, LandingResult};
@@ -91,15 +111,27 @@ pub use loop_context::LoopContext;
 pub use loop_history::{HistoryError, HistoryEvent, HistoryEventType, HistorySummary, LoopHistory};
 pub use loop_lock::{LockError, LockGuard, LockMetadata, LoopLock};
 pub use loop_name::{LoopNameGenerator, LoopNamingConfig};
-pub use loop_registry::{LoopEntry, LoopRegistry, RegistryError};
+pub use loop_registry::{
+    Admission, LoopEntry, LoopRegistry, ParallelLimits, RegistryError, SlotState,
+};
+pub use loop_schedule::{
+    LoopSchedule, ParentState, SCHEDULED_LOOP_ENV, ScheduleAction, ScheduleError, ScheduleStatus,
+    ScheduledLoop, StartOn, spawn_scheduled_loop,
+};
 pub use memory::{Memory, MemoryType};
+pub use memory_compact::{
+    CompactOptions, CompactReport, DEFAULT_SIMILARITY_THRESHOLD, ExpiredMemory, MemoryMerge,
+    compact_memories, journal_path, undo_compaction,
+};
+pub use memory_index::{MemoryIndex, MemorySelection, ScoredMemory, select_memories};
 pub use memory_store::{
     DEFAULT_MEMORIES_PATH, MarkdownMemoryStore, format_memories_as_markdown, truncate_to_budget,
 };
+pub use merge_executor::{MergeExecutor, MergeExecutorError, MergeOutcome, MergeReport};
 pub use merge_queue::{
     MergeButtonState, MergeEntry, MergeEvent, MergeEventType, MergeOption, MergeQueue,
-    MergeQueueError, MergeState, SteeringDecision, merge_button_state, merge_execution_summary,
-    merge_needs_steering, smart_merge_summary,
+    MergeQueueError, MergeState, SteeringDecision, loop_base_branch, merge_button_state,
+    merge_execution_summary, merge_needs_steering, smart_merge_summary,
 };
 pub use planning_session::{
     ConversationEntry, ConversationType, PlanningSession, PlanningSessionError, SessionMetadata,
@@ -122,12 +154,13 @@ pub use task_definition::{
 };
 pub use task_store::TaskStore;
 pub use text::{floor_char_boundary, truncate_with_ellipsis};
+pub use topology_analysis::{IssueKind, IssueSeverity, TopologyIssue, TopologyReport};
 pub use workspace::{
     CleanupPolicy, TaskWorkspace, VerificationResult, WorkspaceError, WorkspaceInfo,
     WorkspaceManager,
 };
 pub use worktree::{
-    SyncStats, Worktree, WorktreeConfig, WorktreeError, create_worktree, ensure_gitignore,
-    list_ralph_worktrees, list_worktrees, remove_worktree, sync_working_directory_to_worktree,
-    worktree_exists,
+    SyncStats, Worktree, WorktreeConfig, WorktreeError, create_worktree, create_worktree_from,
+    current_base, default_base_branch, ensure_gitignore, list_ralph_worktrees, list_worktrees,
+    remove_worktree, sync_working_directory_to_worktree, worktree_exists,
 };
@@ -36,6 +36,7 @@
 use crate::git_ops::auto_commit_changes;
 use crate::landing::{LandingHandler, LandingResult};
 use crate::loop_context::LoopContext;
+use crate::loop_registry::LoopRegistry;
 use crate::merge_queue::{MergeQueue, MergeQueueError};
 use tracing::{debug, info, warn};
 
@@ -198,8 +199,18 @@ impl LoopCompletionHandler {
             }
 
             // Enqueue to merge queue for automatic merge-ralph processing
+            // Carry over the base recorded when the worktree was created.
+            let registered = LoopRegistry::new(context.repo_root())
+                .get(&loop_id)
+                .ok()
+                .flatten();
             let queue = MergeQueue::new(context.repo_root());
-            queue.enqueue(&loop_id, prompt)?;
+            queue.enqueue_with_base(
+                &loop_id,
+                prompt,
+                registered.as_ref().and_then(|e| e.base_branch.as_deref()),
+                registered.as_ref().and_then(|e| e.base_commit.as_deref()),
+            )?;
 
             info!(
                 loop_id = %loop_id,
@@ -8,6 +8,7 @@
 //!
 //! - **Primary loop**: Runs in the main workspace, paths resolve to standard locations
 //! - **Worktree loop**: Runs in a git worktree, paths resolve to worktree-local locations
+//! - **Sub-loop**: Runs inside a parent loop's workspace with private state under `.ralph/subloops/`
 //! - **Shared memories**: Memories are symlinked in worktrees, pointing to main workspace
 //! - **Shared specs/tasks**: Specs and code tasks are symlinked in worktrees
 //!
@@ -29,6 +30,7 @@
 //! ├── events.jsonl
 //! ├── current-events
 //! ├── history.jsonl
+//! ├── subloops/<loop_id>/       # Sub-loop history, scratchpad, summary, handoff
 //! ├── diagnostics/
 //! └── planning-sessions/
 //! ```
@@ -77,6 +79,9 @@ pub struct LoopContext {
 
     /// Whether this is the primary loop (holds loop.lock).
     is_primary: bool,
+
+    /// Private state directory of a sub-loop (None for top-level loops).
+    subloop_dir: Option<PathBuf>,
 }
 
 impl LoopContext {
@@ -90,6 +95,7 @@ impl LoopContext {
             repo_root: workspace.clone(),
             workspace,
             is_primary: true,
+            subloop_dir: None,
         }
     }
 
@@ -113,6 +119,24 @@ impl LoopContext {
             workspace: worktree_path,
             repo_root,
             is_primary: false,
+            subloop_dir: None,
+        }
+    }
+
+    /// Creates context for a sub-loop run by one of `parent`'s hats.
+    ///
+    /// Sub-loops work in the parent's workspace and share its `.ralph/`
+    /// markers (the parent waits while its child runs), but keep their
+    /// history, scratchpad, summary and handoff under
+    /// `.ralph/subloops/<loop_id>/`.
+    pub fn subloop(parent: &LoopContext, loop_id: impl Into<String>) -> Self {
+        let loop_id = loop_id.into();
+        Self {
+            subloop_dir: Some(parent.ralph_dir().join("subloops").join(&loop_id)),
+            loop_id: Some(loop_id),
+            workspace: parent.workspace.clone(),
+            repo_root: parent.repo_root.clone(),
+            is_primary: false,
         }
     }
 
@@ -128,6 +152,16 @@ impl LoopContext {
         self.is_primary
     }
 
+    /// Returns true if this loop was started by a parent loop's hat.
+    pub fn is_subloop(&self) -> bool {
+        self.subloop_dir.is_some()
+    }
+
+    /// Returns the private state directory of a sub-loop.
+    pub fn subloop_dir(&self) -> Option<&Path> {
+        self.subloop_dir.as_deref()
+    }
+
     /// Returns the workspace root for this loop.
     ///
     /// This is the directory where the loop executes:
@@ -186,7 +220,7 @@ impl LoopContext {
     ///
     /// Each loop has its own isolated scratchpad.
     pub fn scratchpad_path(&self) -> PathBuf {
-        self.agent_dir().join("scratchpad.md")
+        self.own_path("scratchpad.md", self.agent_dir().join("scratchpad.md"))
     }
 
     /// Path to the memories markdown file.
@@ -250,7 +284,7 @@ impl LoopContext {
     ///
     /// Each loop has its own isolated summary.
     pub fn summary_path(&self) -> PathBuf {
-        self.agent_dir().join("summary.md")
+        self.own_path("summary.md", self.agent_dir().join("summary.md"))
     }
 
     /// Path to the handoff markdown file.
@@ -258,7 +292,7 @@ impl LoopContext {
     /// Generated on loop completion to provide context for the next session.
     /// Contains completed tasks, remaining work, and a ready-to-paste prompt.
     pub fn handoff_path(&self) -> PathBuf {
-        self.agent_dir().join("handoff.md")
+        self.own_path("handoff.md", self.agent_dir().join("handoff.md"))
     }
 
     /// Path to the diagnostics directory.
@@ -272,7 +306,15 @@ impl LoopContext {
     ///
     /// Event-sourced history for crash recovery and debugging.
     pub fn history_path(&self) -> PathBuf {
-        self.ralph_dir().join("history.jsonl")
+        self.own_path("history.jsonl", self.ralph_dir().join("history.jsonl"))
+    }
+
+    /// Resolves loop-private state into the sub-loop directory, if any.
+    fn own_path(&self, file_name: &str, default: PathBuf) -> PathBuf {
+        match &self.subloop_dir {
+            Some(dir) => dir.join(file_name),
+            None => default,
+        }
     }
 
     /// Path to the loop lock file (only meaningful for primary loop detection).
@@ -606,6 +648,31 @@ mod tests {
         assert_eq!(ctx.repo_root(), Path::new("/project"));
     }
 
+    #[test]
+    fn test_subloop_context() {
+        let parent = LoopContext::primary(PathBuf::from("/project"));
+        let ctx = LoopContext::subloop(&parent, "loop-1-sub-migrator-3");
+
+        assert!(!ctx.is_primary());
+        assert!(ctx.is_subloop());
+        assert_eq!(ctx.loop_id(), Some("loop-1-sub-migrator-3"));
+        assert_eq!(ctx.workspace(), Path::new("/project"));
+        assert_eq!(ctx.events_path(), parent.events_path());
+        assert_eq!(ctx.tasks_path(), parent.tasks_path());
+        assert_eq!(
+            ctx.history_path(),
+            PathBuf::from("/project/.ralph/subloops/loop-1-sub-migrator-3/history.jsonl")
+        );
+        assert_eq!(
+            ctx.scratchpad_path(),
+            PathBuf::from("/project/.ralph/subloops/loop-1-sub-migrator-3/scratchpad.md")
+        );
+        assert_eq!(
+            ctx.summary_path(),
+            PathBuf::from("/project/.ralph/subloops/loop-1-sub-migrator-3/summary.md")
+        );
+    }
+
     #[test]
     fn test_primary_path_resolution() {
         let ctx = LoopContext::primary(PathBuf::from("/project"));
@@ -7,6 +7,7 @@
 //! - **Auditing**: Complete trace of what happened and when
 //! - **Source of truth**: Registry state can be derived from history
 
+use std::collections::BTreeMap;
 use std::fs::{File, OpenOptions};
 use std::io::{BufRead, BufReader, Write};
 use std::path::{Path, PathBuf};
@@ -15,6 +16,8 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+use crate::event_loop::HatUsage;
+use crate::failover::FailureClass;
 use crate::file_lock::FileLock;
 
 /// Errors that can occur during history operations.
@@ -64,7 +67,7 @@ impl HistoryEvent {
 }
 
 /// Types of events that can be recorded in loop history.
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(tag = "kind", rename_all = "snake_case")]
 pub enum HistoryEventType {
     /// Loop started with given prompt.
@@ -79,6 +82,40 @@ pub enum HistoryEventType {
     /// Iteration completed.
     IterationCompleted { iteration: u32, success: bool },
 
+    /// Token and cost usage attributed to a hat for one iteration.
+    HatUsage {
+        iteration: u32,
+        hat: String,
+        cost_usd: f64,
+        tokens: u64,
+    },
+
+    /// A hat's iteration was retried on the next backend in its failover chain.
+    BackendFailover {
+        iteration: u32,
+        hat: String,
+        from: String,
+        to: String,
+        reason: FailureClass,
+    },
+
+    /// A hat started a nested loop with its own history.
+    SubloopStarted {
+        iteration: u32,
+        hat: String,
+        loop_id: String,
+    },
+
+    /// A nested loop stopped; its usage is included in the hat's usage.
+    SubloopCompleted {
+        iteration: u32,
+        hat: String,
+        loop_id: String,
+        reason: String,
+        iterations: u32,
+        cost_usd: f64,
+    },
+
     /// Loop completed successfully.
     LoopCompleted { reason: String },
 
@@ -252,6 +289,22 @@ impl LoopHistory {
                 HistoryEventType::EventPublished { .. } => {
                     summary.events_published += 1;
                 }
+                HistoryEventType::HatUsage {
+                    hat,
+                    cost_usd,
+                    tokens,
+                    ..
+                } => {
+                    summary
+                        .hat_usage
+                        .entry(hat.clone())
+                        .or_default()
+                        .add(HatUsage {
+                            cost_usd: *cost_usd,
+                            tokens: *tokens,
+                            iterations: 1,
+                        });
+                }
                 HistoryEventType::LoopCompleted { reason } => {
                     summary.completed = true;
                     summary.completion_reason = Some(reason.clone());
@@ -310,6 +363,74 @@ impl LoopHistory {
         }))
     }
 
+    /// Record a hat's share of an iteration's token and cost usage.
+    pub fn record_hat_usage(
+        &self,
+        iteration: u32,
+        hat: &str,
+        cost_usd: f64,
+        tokens: u64,
+    ) -> Result<(), HistoryError> {
+        self.append(HistoryEvent::new(HistoryEventType::HatUsage {
+            iteration,
+            hat: hat.to_string(),
+            cost_usd,
+            tokens,
+        }))
+    }
+
+    /// Record a backend failover for a hat's iteration.
+    pub fn record_backend_failover(
+        &self,
+        iteration: u32,
+        hat: &str,
+        from: &str,
+        to: &str,
+        reason: FailureClass,
+    ) -> Result<(), HistoryError> {
+        self.append(HistoryEvent::new(HistoryEventType::BackendFailover {
+            iteration,
+            hat: hat.to_string(),
+            from: from.to_string(),
+            to: to.to_string(),
+            reason,
+        }))
+    }
+
+    /// Record the start of a hat's nested loop.
+    pub fn record_subloop_started(
+        &self,
+        iteration: u32,
+        hat: &str,
+        loop_id: &str,
+    ) -> Result<(), HistoryError> {
+        self.append(HistoryEvent::new(HistoryEventType::SubloopStarted {
+            iteration,
+            hat: hat.to_string(),
+            loop_id: loop_id.to_string(),
+        }))
+    }
+
+    /// Record the outcome of a hat's nested loop.
+    pub fn record_subloop_completed(
+        &self,
+        iteration: u32,
+        hat: &str,
+        loop_id: &str,
+        reason: &str,
+        iterations: u32,
+        cost_usd: f64,
+    ) -> Result<(), HistoryError> {
+        self.append(HistoryEvent::new(HistoryEventType::SubloopCompleted {
+            iteration,
+            hat: hat.to_string(),
+            loop_id: loop_id.to_string(),
+            reason: reason.to_string(),
+            iterations,
+            cost_usd,
+        }))
+    }
+
     /// Record loop completed event.
     pub fn record_completed(&self, reason: &str) -> Result<(), HistoryError> {
         self.append(HistoryEvent::new(HistoryEventType::LoopCompleted {
@@ -404,6 +525,9 @@ pub struct HistorySummary {
 
     /// Merge failure reason (if failed).
     pub merge_failure_reason: Option<String>,
+
+    /// Per-hat token and cost totals, keyed by hat ID.
+    pub hat_usage: BTreeMap<String, HatUsage>,
 }
 
 #[cfg(test)]
@@ -533,6 +657,94 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_summary_totals_hat_usage() {
+        let (_dir, history) = temp_history();
+
+        history.record_hat_usage(1, "builder", 0.25, 4_000).unwrap();
+        history
+            .record_hat_usage(2, "reviewer", 1.5, 20_000)
+            .unwrap();
+        history.record_hat_usage(3, "builder", 0.5, 6_000).unwrap();
+
+        let summary = history.summary().unwrap();
+        assert_eq!(summary.hat_usage.len(), 2);
+
+        let builder = summary.hat_usage["builder"];
+        assert!((builder.cost_usd - 0.75).abs() < f64::EPSILON);
+        assert_eq!(builder.tokens, 10_000);
+        assert_eq!(builder.iterations, 2);
+        assert_eq!(summary.hat_usage["reviewer"].iterations, 1);
+    }
+
+    #[test]
+    fn test_backend_failover_event() {
+        let (_dir, history) = temp_history();
+
+        history
+            .record_backend_failover(4, "builder", "claude", "gemini", FailureClass::RateLimit)
+            .unwrap();
+
+        let events = history.read_all().unwrap();
+        assert_eq!(events.len(), 1);
+        match &events[0].event_type {
+            HistoryEventType::BackendFailover {
+                iteration,
+                hat,
+                from,
+                to,
+                reason,
+            } => {
+                assert_eq!(*iteration, 4);
+                assert_eq!(hat, "builder");
+                assert_eq!(from, "claude");
+                assert_eq!(to, "gemini");
+                assert_eq!(*reason, FailureClass::RateLimit);
+            }
+            other => panic!("unexpected event: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_subloop_events() {
+        let (_dir, history) = temp_history();
+
+        history
+            .record_subloop_started(3, "migrator", "loop-1-sub-migrator-3")
+            .unwrap();
+        history
+            .record_subloop_completed(
+                3,
+                "migrator",
+                "loop-1-sub-migrator-3",
+                "completion_promise",
+                7,
+                1.25,
+            )
+            .unwrap();
+
+        let events = history.read_all().unwrap();
+        assert_eq!(
+            events[0].event_type,
+            HistoryEventType::SubloopStarted {
+                iteration: 3,
+                hat: "migrator".to_string(),
+                loop_id: "loop-1-sub-migrator-3".to_string(),
+            }
+        );
+        assert_eq!(
+            events[1].event_type,
+            HistoryEventType::SubloopCompleted {
+                iteration: 3,
+                hat: "migrator".to_string(),
+                loop_id: "loop-1-sub-migrator-3".to_string(),
+                reason: "completion_promise".to_string(),
+                iterations: 7,
+                cost_usd: 1.25,
+            }
+        );
+    }
+
     #[test]
     fn test_empty_file() {
         let (_dir, history) = temp_history();
@@ -8,6 +8,9 @@
 //! - **JSON persistence**: Single JSON file at `.ralph/loops.json`
 //! - **File locking**: Uses `flock()` for concurrent access safety
 //! - **PID-based stale detection**: Automatically cleans up entries for dead processes
+//! - **Admission control**: Loops beyond `features.max_parallel_loops` (or a
+//!   per-backend cap) register as [`SlotState::Queued`] and wait for
+//!   [`LoopRegistry::try_admit`] to hand them a slot
 //!
 //! # Example
 //!
@@ -34,11 +37,32 @@
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs::{self, File, OpenOptions};
 use std::io::{self, Read, Seek, SeekFrom, Write};
 use std::path::{Path, PathBuf};
 use std::process;
 
+use crate::config::FeaturesConfig;
+
+/// Whether a registered loop is running or waiting for a parallel-loop slot.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SlotState {
+    /// The loop holds a slot (or no limits are configured).
+    #[default]
+    Running,
+    /// The loop is waiting for a slot under the configured limits.
+    Queued,
+}
+
+impl SlotState {
+    #[allow(clippy::trivially_copy_pass_by_ref)] // serde's skip_serializing_if passes a reference
+    fn is_running(&self) -> bool {
+        *self == Self::Running
+    }
+}
+
 /// Metadata for a registered loop.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct LoopEntry {
@@ -60,6 +84,31 @@ pub struct LoopEntry {
 
     /// The workspace root where the loop is running.
     pub workspace: String,
+
+    /// Branch the worktree was created from and merges back into.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_branch: Option<String>,
+
+    /// Commit of `base_branch` when the worktree was created.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_commit: Option<String>,
+
+    /// Whether the loop is running or waiting for a slot.
+    #[serde(default, skip_serializing_if = "SlotState::is_running")]
+    pub state: SlotState,
+
+    /// Backend the loop runs on, for per-backend concurrency caps.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backend: Option<String>,
+
+    /// Queue priority; higher values are admitted first.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub priority: i32,
+}
+
+#[allow(clippy::trivially_copy_pass_by_ref)] // serde's skip_serializing_if passes a reference
+fn is_zero(value: &i32) -> bool {
+    *value == 0
 }
 
 impl LoopEntry {
@@ -74,6 +123,11 @@ impl LoopEntry {
             workspace: std::env::current_dir()
                 .map(|p| p.display().to_string())
                 .unwrap_or_default(),
+            base_branch: None,
+            base_commit: None,
+            state: SlotState::Running,
+            backend: None,
+            priority: 0,
         }
     }
 
@@ -90,6 +144,11 @@ impl LoopEntry {
             prompt: prompt.into(),
             worktree_path: worktree_path.map(Into::into),
             workspace: workspace.into(),
+            base_branch: None,
+            base_commit: None,
+            state: SlotState::Running,
+            backend: None,
+            priority: 0,
         }
     }
 
@@ -110,9 +169,43 @@ impl LoopEntry {
             prompt: prompt.into(),
             worktree_path: worktree_path.map(Into::into),
             workspace: workspace.into(),
+            base_branch: None,
+            base_commit: None,
+            state: SlotState::Running,
+            backend: None,
+            priority: 0,
         }
     }
 
+    /// Marks the entry as waiting for a parallel-loop slot.
+    #[must_use]
+    pub fn queued(mut self) -> Self {
+        self.state = SlotState::Queued;
+        self
+    }
+
+    /// Records the backend the loop runs on.
+    #[must_use]
+    pub fn with_backend(mut self, backend: impl Into<String>) -> Self {
+        self.backend = Some(backend.into());
+        self
+    }
+
+    /// Sets the queue priority (higher is admitted first).
+    #[must_use]
+    pub fn with_priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Records the branch and commit the loop's worktree was created from.
+    #[must_use]
+    pub fn with_base(mut self, branch: impl Into<String>, commit: Option<String>) -> Self {
+        self.base_branch = Some(branch.into());
+        self.base_commit = commit;
+        self
+    }
+
     /// Generates a unique loop ID: loop-{timestamp}-{hex_suffix}
     fn generate_id() -> String {
         use std::time::{SystemTime, UNIX_EPOCH};
@@ -180,6 +273,75 @@ impl LoopEntry {
     }
 }
 
+/// Concurrency limits for worktree loops.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ParallelLimits {
+    /// Maximum running worktree loops (0 = unlimited).
+    pub max_loops: usize,
+    /// Maximum running worktree loops per backend.
+    pub per_backend: HashMap<String, usize>,
+}
+
+impl ParallelLimits {
+    /// Builds limits from `features.max_parallel_loops` and `features.backend_concurrency`.
+    pub fn from_features(features: &FeaturesConfig) -> Self {
+        Self {
+            max_loops: features.max_parallel_loops,
+            per_backend: features.backend_concurrency.clone(),
+        }
+    }
+
+    /// Returns true when no limit applies, so loops can start without queueing.
+    pub fn is_unlimited(&self) -> bool {
+        self.max_loops == 0 && self.per_backend.values().all(|cap| *cap == 0)
+    }
+
+    /// Returns why `entry` cannot start alongside `running`, or None if it can.
+    fn blocked_by(&self, entry: &LoopEntry, running: &[&LoopEntry]) -> Option<String> {
+        if self.max_loops > 0 && running.len() >= self.max_loops {
+            return Some(format!(
+                "{}/{} parallel loops running",
+                running.len(),
+                self.max_loops
+            ));
+        }
+        let backend = entry.backend.as_deref()?;
+        let cap = self.per_backend.get(backend).copied().unwrap_or(0);
+        if cap == 0 {
+            return None;
+        }
+        let on_backend = running
+            .iter()
+            .filter(|e| e.backend.as_deref() == Some(backend))
+            .count();
+        (on_backend >= cap).then(|| format!("{on_backend}/{cap} {backend} loops running"))
+    }
+}
+
+/// Result of asking the registry for a parallel-loop slot.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Admission {
+    /// The loop now holds a slot and is marked running.
+    Admitted,
+    /// The loop keeps waiting.
+    Waiting {
+        /// 1-based position among queued loops.
+        position: usize,
+        /// Why the loop can't start yet.
+        reason: String,
+    },
+}
+
+/// Returns queued entries ordered by priority, then registration time.
+fn queued_order(loops: &[LoopEntry]) -> Vec<&LoopEntry> {
+    let mut queue: Vec<&LoopEntry> = loops
+        .iter()
+        .filter(|e| e.state == SlotState::Queued)
+        .collect();
+    queue.sort_by(|a, b| b.priority.cmp(&a.priority).then(a.started.cmp(&b.started)));
+    queue
+}
+
 /// The persisted registry data.
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 struct RegistryData {
@@ -261,6 +423,83 @@ impl LoopRegistry {
         Ok(result)
     }
 
+    /// Updates the recorded base branch and commit of a loop.
+    pub fn set_base(
+        &self,
+        id: &str,
+        branch: &str,
+        commit: Option<&str>,
+    ) -> Result<(), RegistryError> {
+        let mut found = false;
+        self.with_lock(|data| {
+            if let Some(entry) = data.loops.iter_mut().find(|e| e.id == id) {
+                entry.base_branch = Some(branch.to_string());
+                entry.base_commit = commit.map(String::from);
+                found = true;
+            }
+        })?;
+        if !found {
+            return Err(RegistryError::NotFound(id.to_string()));
+        }
+        Ok(())
+    }
+
+    /// Tries to move a queued loop into a running slot.
+    ///
+    /// Queued loops are ordered by priority (highest first), then by when they
+    /// were registered. A loop is admitted when the limits allow it and no
+    /// loop ahead of it could start now; a loop held back only by its
+    /// backend's cap doesn't block loops on other backends.
+    pub fn try_admit(&self, id: &str, limits: &ParallelLimits) -> Result<Admission, RegistryError> {
+        let mut result = Err(RegistryError::NotFound(id.to_string()));
+        self.with_lock(|data| {
+            let queue = queued_order(&data.loops);
+            let Some(position) = queue.iter().position(|e| e.id == id) else {
+                if data.loops.iter().any(|e| e.id == id) {
+                    result = Ok(Admission::Admitted);
+                }
+                return;
+            };
+            let running: Vec<&LoopEntry> = data
+                .loops
+                .iter()
+                .filter(|e| e.state == SlotState::Running)
+                .collect();
+            let entry = queue[position];
+            if let Some(reason) = limits.blocked_by(entry, &running) {
+                result = Ok(Admission::Waiting {
+                    position: position + 1,
+                    reason,
+                });
+                return;
+            }
+            if let Some(ahead) = queue[..position]
+                .iter()
+                .find(|e| limits.blocked_by(e, &running).is_none())
+            {
+                result = Ok(Admission::Waiting {
+                    position: position + 1,
+                    reason: format!("{} is ahead in the queue", ahead.id),
+                });
+                return;
+            }
+            if let Some(entry) = data.loops.iter_mut().find(|e| e.id == id) {
+                entry.state = SlotState::Running;
+            }
+            result = Ok(Admission::Admitted);
+        })?;
+        result
+    }
+
+    /// Lists queued loops in admission order.
+    pub fn queued(&self) -> Result<Vec<LoopEntry>, RegistryError> {
+        let mut result = Vec::new();
+        self.with_lock(|data| {
+            result = queued_order(&data.loops).into_iter().cloned().collect();
+        })?;
+        Ok(result)
+    }
+
     /// Lists all active loops (after cleaning stale entries).
     pub fn list(&self) -> Result<Vec<LoopEntry>, RegistryError> {
         let mut result = Vec::new();
@@ -498,6 +737,26 @@ mod tests {
         assert_eq!(retrieved.unwrap().prompt, "test prompt");
     }
 
+    #[test]
+    fn test_registry_set_base() {
+        let temp_dir = TempDir::new().unwrap();
+        let registry = LoopRegistry::new(temp_dir.path());
+
+        let entry = LoopEntry::new("test prompt", Some("/worktree/path"))
+            .with_base("develop", Some("abc123".to_string()));
+        let id = registry.register(entry).unwrap();
+        assert_eq!(
+            registry.get(&id).unwrap().unwrap().base_branch.as_deref(),
+            Some("develop")
+        );
+
+        registry.set_base(&id, "release/1.2", None).unwrap();
+        let retrieved = registry.get(&id).unwrap().unwrap();
+        assert_eq!(retrieved.base_branch.as_deref(), Some("release/1.2"));
+        assert!(retrieved.base_commit.is_none());
+        assert!(registry.set_base("missing", "main", None).is_err());
+    }
+
     #[test]
     fn test_registry_get_nonexistent() {
         let temp_dir = TempDir::new().unwrap();
@@ -750,4 +1009,125 @@ mod tests {
         assert_eq!(removed, 1);
         assert!(registry.get(&id).unwrap().is_none());
     }
+
+    /// A live process whose PID lets several entries coexist in one registry.
+    struct Sleeper(std::process::Child);
+
+    impl Sleeper {
+        fn spawn() -> Self {
+            Self(
+                std::process::Command::new("sleep")
+                    .arg("30")
+                    .spawn()
+                    .unwrap(),
+            )
+        }
+
+        fn entry(&self, id: &str, backend: &str) -> LoopEntry {
+            let mut entry =
+                LoopEntry::with_id(id, "prompt", None::<String>, ".").with_backend(backend);
+            entry.pid = self.0.id();
+            entry
+        }
+    }
+
+    impl Drop for Sleeper {
+        fn drop(&mut self) {
+            let _ = self.0.kill();
+            let _ = self.0.wait();
+        }
+    }
+
+    #[test]
+    fn test_try_admit_waits_for_max_parallel_loops() {
+        let temp_dir = TempDir::new().unwrap();
+        let registry = LoopRegistry::new(temp_dir.path());
+        let limits = ParallelLimits {
+            max_loops: 1,
+            ..ParallelLimits::default()
+        };
+        let (a, b) = (Sleeper::spawn(), Sleeper::spawn());
+        registry.register(a.entry("loop-a", "claude")).unwrap();
+        registry
+            .register(b.entry("loop-b", "claude").queued())
+            .unwrap();
+
+        assert_eq!(
+            registry.try_admit("loop-b", &limits).unwrap(),
+            Admission::Waiting {
+                position: 1,
+                reason: "1/1 parallel loops running".to_string(),
+            }
+        );
+
+        registry.deregister("loop-a").unwrap();
+        assert_eq!(
+            registry.try_admit("loop-b", &limits).unwrap(),
+            Admission::Admitted
+        );
+        assert_eq!(
+            registry.get("loop-b").unwrap().unwrap().state,
+            SlotState::Running
+        );
+        assert!(registry.queued().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_try_admit_backend_cap_does_not_block_other_backends() {
+        let temp_dir = TempDir::new().unwrap();
+        let registry = LoopRegistry::new(temp_dir.path());
+        let limits = ParallelLimits {
+            max_loops: 3,
+            per_backend: HashMap::from([("claude".to_string(), 1)]),
+        };
+        let (a, b, c) = (Sleeper::spawn(), Sleeper::spawn(), Sleeper::spawn());
+        registry.register(a.entry("loop-a", "claude")).unwrap();
+        registry
+            .register(b.entry("loop-b", "claude").queued())
+            .unwrap();
+        registry
+            .register(c.entry("loop-c", "gemini").queued())
+            .unwrap();
+
+        assert_eq!(
+            registry.try_admit("loop-c", &limits).unwrap(),
+            Admission::Admitted
+        );
+        assert_eq!(
+            registry.try_admit("loop-b", &limits).unwrap(),
+            Admission::Waiting {
+                position: 1,
+                reason: "1/1 claude loops running".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_try_admit_prefers_higher_priority() {
+        let temp_dir = TempDir::new().unwrap();
+        let registry = LoopRegistry::new(temp_dir.path());
+        let limits = ParallelLimits {
+            max_loops: 1,
+            ..ParallelLimits::default()
+        };
+        let (low, high) = (Sleeper::spawn(), Sleeper::spawn());
+        registry
+            .register(low.entry("loop-low", "claude").queued())
+            .unwrap();
+        registry
+            .register(high.entry("loop-high", "claude").queued().with_priority(5))
+            .unwrap();
+
+        assert_eq!(
+            registry.try_admit("loop-low", &limits).unwrap(),
+            Admission::Waiting {
+                position: 2,
+                reason: "loop-high is ahead in the queue".to_string(),
+            }
+        );
+        assert_eq!(
+            registry.try_admit("loop-high", &limits).unwrap(),
+            Admission::Admitted
+        );
+    }
 }
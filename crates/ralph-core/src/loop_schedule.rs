@@ -0,0 +1,772 @@
+//! Scheduling of loops that depend on another loop.
+//!
+//! `ralph run --after <loop-id>` (and the `loop.schedule` RPC method) register a
+//! pending loop instead of starting it. The pending loop starts once its parent
+//! reaches the chosen merge-queue state:
+//!
+//! - [`StartOn::Queued`]: as soon as the parent is queued for merge, branching
+//!   from the parent's `ralph/<id>` branch.
+//! - [`StartOn::Merged`]: once the parent has merged, branching from the base
+//!   branch the parent merged into.
+//!
+//! A pending loop is cancelled when its parent is discarded, is itself
+//! cancelled, or exits without completing. Cancellation cascades to the whole
+//! dependency subtree.
+//!
+//! # Design
+//!
+//! - **JSON persistence**: Single JSON file at `.ralph/loop-schedule.json`
+//! - **File locking**: Read-modify-write under an exclusive `flock()`
+//! - **Reconciliation**: [`LoopSchedule::reconcile`] is called whenever a loop
+//!   terminates, is processed by the merge queue or is discarded
+
+use chrono::{DateTime, Local, Utc};
+use serde::{Deserialize, Serialize};
+use std::ffi::OsStr;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::str::FromStr;
+
+use crate::file_lock::LockedFile;
+use crate::loop_history::{HistoryEventType, LoopHistory};
+use crate::loop_registry::LoopRegistry;
+use crate::merge_queue::{MergeQueue, MergeState, loop_base_branch};
+use crate::worktree::WorktreeConfig;
+
+/// Environment variable carrying the ID of the scheduled loop being launched.
+///
+/// `ralph run` reads this to create the loop's worktree from the recorded
+/// start point instead of acquiring the primary loop lock.
+pub const SCHEDULED_LOOP_ENV: &str = "RALPH_SCHEDULED_LOOP_ID";
+
+/// The merge-queue state of the parent that starts a scheduled loop.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StartOn {
+    /// Start from the parent's branch once the parent is queued for merge.
+    Queued,
+    /// Start from the base branch once the parent has merged.
+    #[default]
+    Merged,
+}
+
+impl StartOn {
+    /// Returns the lowercase name used on the command line and in JSON.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Queued => "queued",
+            Self::Merged => "merged",
+        }
+    }
+}
+
+impl FromStr for StartOn {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "queued" => Ok(Self::Queued),
+            "merged" => Ok(Self::Merged),
+            other => Err(format!(
+                "invalid start state '{other}' (expected 'queued' or 'merged')"
+            )),
+        }
+    }
+}
+
+/// Lifecycle of a scheduled loop entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScheduleStatus {
+    /// Waiting for the parent loop.
+    Pending,
+    /// Launched; the loop is tracked by the registry and merge queue from here.
+    Started,
+    /// Will never start (parent discarded, failed or cancelled).
+    Cancelled,
+}
+
+/// A loop registered to start after another loop.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ScheduledLoop {
+    /// Loop ID the scheduled loop will run under.
+    pub id: String,
+
+    /// ID of the parent loop.
+    pub after: String,
+
+    /// Parent state that starts this loop.
+    #[serde(default)]
+    pub start_on: StartOn,
+
+    /// The prompt the loop will execute.
+    pub prompt: String,
+
+    /// Arguments passed to `ralph` when the loop is launched.
+    pub args: Vec<String>,
+
+    /// When the loop was scheduled.
+    pub created: DateTime<Utc>,
+
+    /// Current status.
+    pub status: ScheduleStatus,
+
+    /// PID of the launched `ralph` process.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pid: Option<u32>,
+
+    /// When the loop was launched.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub started: Option<DateTime<Utc>>,
+
+    /// Ref the loop's worktree branches from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_point: Option<String>,
+
+    /// Branch the loop merges back into.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_branch: Option<String>,
+
+    /// Why the loop was cancelled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+impl ScheduledLoop {
+    /// Creates a pending entry.
+    pub fn new(
+        id: impl Into<String>,
+        after: impl Into<String>,
+        start_on: StartOn,
+        prompt: impl Into<String>,
+        args: Vec<String>,
+    ) -> Self {
+        Self {
+            id: id.into(),
+            after: after.into(),
+            start_on,
+            prompt: prompt.into(),
+            args,
+            created: Utc::now(),
+            status: ScheduleStatus::Pending,
+            pid: None,
+            started: None,
+            start_point: None,
+            base_branch: None,
+            reason: None,
+        }
+    }
+
+    /// Returns true while the loop is waiting for its parent.
+    pub fn is_pending(&self) -> bool {
+        self.status == ScheduleStatus::Pending
+    }
+}
+
+/// Observed state of a parent loop.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParentState {
+    /// Still running, launching, or waiting on its own parent.
+    Running,
+    /// Completed but not queued for merge (e.g. auto-merge disabled).
+    Completed,
+    /// In the merge queue but not merged yet.
+    Queued { base_branch: String },
+    /// Merged into `base_branch`.
+    Merged { base_branch: String },
+    /// Discarded from the merge queue.
+    Discarded,
+    /// A scheduled parent that was cancelled.
+    Cancelled,
+    /// Exited without completing.
+    Exited,
+}
+
+/// A change applied by [`LoopSchedule::reconcile`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScheduleAction {
+    /// The loop was launched from `start_point`.
+    Started {
+        id: String,
+        start_point: String,
+        pid: u32,
+    },
+    /// The loop was cancelled.
+    Cancelled { id: String, reason: String },
+}
+
+/// The persisted schedule data.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+struct ScheduleData {
+    loops: Vec<ScheduledLoop>,
+}
+
+/// Errors that can occur during schedule operations.
+#[derive(Debug, thiserror::Error)]
+pub enum ScheduleError {
+    /// IO error during schedule operations.
+    #[error("IO error: {0}")]
+    Io(#[from] io::Error),
+
+    /// Failed to parse schedule data.
+    #[error("Failed to parse loop schedule: {0}")]
+    ParseError(String),
+
+    /// Scheduled loop not found.
+    #[error("Scheduled loop not found: {0}")]
+    NotFound(String),
+
+    /// A loop with this ID is already scheduled.
+    #[error("Loop already scheduled: {0}")]
+    Duplicate(String),
+
+    /// The parent loop is not known to the registry, merge queue or schedule.
+    #[error("Unknown parent loop: {0}")]
+    UnknownParent(String),
+
+    /// The scheduled loop has already started or been cancelled.
+    #[error("Scheduled loop {0} is no longer pending")]
+    NotPending(String),
+}
+
+/// Store of loops waiting on other loops.
+pub struct LoopSchedule {
+    workspace_root: PathBuf,
+    schedule_path: PathBuf,
+}
+
+impl LoopSchedule {
+    /// The relative path to the schedule file within the workspace.
+    pub const SCHEDULE_FILE: &'static str = ".ralph/loop-schedule.json";
+
+    /// Creates a schedule instance for the given workspace.
+    pub fn new(workspace_root: impl AsRef<Path>) -> Self {
+        let workspace_root = workspace_root.as_ref().to_path_buf();
+        Self {
+            schedule_path: workspace_root.join(Self::SCHEDULE_FILE),
+            workspace_root,
+        }
+    }
+
+    /// Registers a pending loop.
+    ///
+    /// Fails if the ID is already scheduled or the parent loop is unknown.
+    pub fn add(&self, entry: ScheduledLoop) -> Result<(), ScheduleError> {
+        if !self.is_known_loop(&entry.after)? {
+            return Err(ScheduleError::UnknownParent(entry.after));
+        }
+        self.with_lock(|data| {
+            if data.loops.iter().any(|e| e.id == entry.id) {
+                return Err(ScheduleError::Duplicate(entry.id.clone()));
+            }
+            data.loops.push(entry);
+            Ok(())
+        })
+    }
+
+    /// Gets a scheduled loop by ID.
+    pub fn get(&self, id: &str) -> Result<Option<ScheduledLoop>, ScheduleError> {
+        Ok(self.read()?.loops.into_iter().find(|e| e.id == id))
+    }
+
+    /// Lists all scheduled loops in creation order.
+    pub fn list(&self) -> Result<Vec<ScheduledLoop>, ScheduleError> {
+        Ok(self.read()?.loops)
+    }
+
+    /// Cancels a pending loop and every pending loop that depends on it.
+    pub fn cancel(&self, id: &str, reason: &str) -> Result<Vec<ScheduleAction>, ScheduleError> {
+        self.with_lock(|data| {
+            let entry = data
+                .loops
+                .iter_mut()
+                .find(|e| e.id == id)
+                .ok_or_else(|| ScheduleError::NotFound(id.to_string()))?;
+            if !entry.is_pending() {
+                return Err(ScheduleError::NotPending(id.to_string()));
+            }
+            entry.status = ScheduleStatus::Cancelled;
+            entry.reason = Some(reason.to_string());
+            let mut actions = vec![ScheduleAction::Cancelled {
+                id: id.to_string(),
+                reason: reason.to_string(),
+            }];
+            actions.extend(self.settle(data, false).1);
+            Ok(actions)
+        })
+    }
+
+    /// Returns true if `id` is a loop the schedule can wait on.
+    pub fn is_known_loop(&self, id: &str) -> Result<bool, ScheduleError> {
+        let queued = MergeQueue::new(&self.workspace_root)
+            .get_entry(id)
+            .map_err(|e| ScheduleError::ParseError(e.to_string()))?
+            .is_some();
+        let registered = LoopRegistry::new(&self.workspace_root)
+            .get(id)
+            .map_err(|e| ScheduleError::ParseError(e.to_string()))?
+            .is_some();
+        let scheduled = self.get(id)?.is_some();
+        let worktree = self.parent_worktree(id).is_dir();
+        Ok(queued || registered || scheduled || worktree)
+    }
+
+    /// Resolves the current state of a parent loop.
+    pub fn parent_state(&self, parent: &str) -> ParentState {
+        let scheduled = self.read().map(|data| data.loops).unwrap_or_default();
+        self.resolve_parent(parent, &scheduled)
+    }
+
+    /// Starts or cancels pending loops whose parents have moved on.
+    ///
+    /// `launch` is called for each loop that should start and returns the PID
+    /// of the launched process. Entries are persisted as started before the
+    /// launcher runs, so the launched process can read its start point. A
+    /// launch failure cancels the entry and its dependents.
+    pub fn reconcile<F>(&self, mut launch: F) -> Result<Vec<ScheduleAction>, ScheduleError>
+    where
+        F: FnMut(&ScheduledLoop) -> io::Result<u32>,
+    {
+        let (to_launch, mut actions) = self.with_lock(|data| Ok(self.settle(data, true)))?;
+
+        for entry in to_launch {
+            let start_point = entry.start_point.clone().unwrap_or_default();
+            match launch(&entry) {
+                Ok(pid) => {
+                    self.with_lock(|data| {
+                        if let Some(e) = data.loops.iter_mut().find(|e| e.id == entry.id) {
+                            e.pid = Some(pid);
+                        }
+                        Ok(())
+                    })?;
+                    actions.push(ScheduleAction::Started {
+                        id: entry.id,
+                        start_point,
+                        pid,
+                    });
+                }
+                Err(e) => {
+                    let reason = format!("failed to launch: {e}");
+                    let cancelled = self.with_lock(|data| {
+                        if let Some(e) = data.loops.iter_mut().find(|e| e.id == entry.id) {
+                            e.status = ScheduleStatus::Cancelled;
+                            e.reason = Some(reason.clone());
+                        }
+                        Ok(self.settle(data, false).1)
+                    })?;
+                    actions.push(ScheduleAction::Cancelled {
+                        id: entry.id,
+                        reason,
+                    });
+                    actions.extend(cancelled);
+                }
+            }
+        }
+
+        Ok(actions)
+    }
+
+    /// Applies parent-state decisions to pending entries until nothing changes.
+    ///
+    /// Returns the entries marked as started (only when `start` is set) and
+    /// the cancellations applied. Cancellations cascade through the tree,
+    /// hence the fixpoint.
+    fn settle(
+        &self,
+        data: &mut ScheduleData,
+        start: bool,
+    ) -> (Vec<ScheduledLoop>, Vec<ScheduleAction>) {
+        let mut started = Vec::new();
+        let mut actions = Vec::new();
+        loop {
+            let mut changed = false;
+            for index in 0..data.loops.len() {
+                if !data.loops[index].is_pending() {
+                    continue;
+                }
+                let state = self.resolve_parent(&data.loops[index].after, &data.loops);
+                let entry = &mut data.loops[index];
+                match decide(entry, &state) {
+                    Decision::Wait => {}
+                    Decision::Start {
+                        start_point,
+                        base_branch,
+                    } => {
+                        if !start {
+                            continue;
+                        }
+                        entry.status = ScheduleStatus::Started;
+                        entry.started = Some(Utc::now());
+                        entry.start_point = Some(start_point);
+                        entry.base_branch = Some(base_branch);
+                        started.push(entry.clone());
+                        changed = true;
+                    }
+                    Decision::Cancel(reason) => {
+                        entry.status = ScheduleStatus::Cancelled;
+                        entry.reason = Some(reason.clone());
+                        actions.push(ScheduleAction::Cancelled {
+                            id: entry.id.clone(),
+                            reason,
+                        });
+                        changed = true;
+                    }
+                }
+            }
+            if !changed {
+                return (started, actions);
+            }
+        }
+    }
+
+    fn resolve_parent(&self, parent: &str, scheduled: &[ScheduledLoop]) -> ParentState {
+        let queue = MergeQueue::new(&self.workspace_root);
+        if let Ok(Some(entry)) = queue.get_entry(parent) {
+            let base_branch = entry
+                .base_branch
+                .unwrap_or_else(|| loop_base_branch(&self.workspace_root, parent));
+            return match entry.state {
+                MergeState::Queued | MergeState::Merging | MergeState::NeedsReview => {
+                    ParentState::Queued { base_branch }
+                }
+                MergeState::Merged => ParentState::Merged { base_branch },
+                MergeState::Discarded => ParentState::Discarded,
+            };
+        }
+
+        if let Ok(Some(_)) = LoopRegistry::new(&self.workspace_root).get(parent) {
+            return ParentState::Running;
+        }
+
+        if let Some(entry) = scheduled.iter().find(|e| e.id == parent) {
+            match entry.status {
+                ScheduleStatus::Pending => return ParentState::Running,
+                ScheduleStatus::Cancelled => return ParentState::Cancelled,
+                ScheduleStatus::Started => {
+                    // The launched process counts as running until it registers,
+                    // except when it is the caller reconciling on its own exit.
+                    let launching = entry
+                        .pid
+                        .is_none_or(|pid| pid != std::process::id() && is_pid_alive(pid));
+                    if launching {
+                        return ParentState::Running;
+                    }
+                }
+            }
+        }
+
+        let history = LoopHistory::new(self.parent_worktree(parent).join(".ralph/history.jsonl"));
+        let completed = history.read_all().ok().and_then(|events| {
+            events
+                .into_iter()
+                .rev()
+                .find_map(|event| match event.event_type {
+                    HistoryEventType::LoopCompleted { reason } => Some(reason),
+                    _ => None,
+                })
+        });
+        match completed.as_deref() {
+            Some("completion_promise") => ParentState::Completed,
+            _ => ParentState::Exited,
+        }
+    }
+
+    fn parent_worktree(&self, id: &str) -> PathBuf {
+        WorktreeConfig::default()
+            .worktree_path(&self.workspace_root)
+            .join(id)
+    }
+
+    fn read(&self) -> Result<ScheduleData, ScheduleError> {
+        let file = LockedFile::new(&self.schedule_path)?;
+        let content = file.read(&self.schedule_path)?;
+        parse(&content)
+    }
+
+    fn with_lock<T, F>(&self, f: F) -> Result<T, ScheduleError>
+    where
+        F: FnOnce(&mut ScheduleData) -> Result<T, ScheduleError>,
+    {
+        let file = LockedFile::new(&self.schedule_path)?;
+        file.with_exclusive_lock(|| {
+            let content = if self.schedule_path.exists() {
+                fs::read_to_string(&self.schedule_path)?
+            } else {
+                String::new()
+            };
+            let outcome = parse(&content).and_then(|mut data| {
+                let value = f(&mut data)?;
+                Ok((data, value))
+            });
+            Ok(match outcome {
+                Ok((data, value)) => {
+                    let json = serde_json::to_string_pretty(&data).map_err(io::Error::other)?;
+                    fs::write(&self.schedule_path, json)?;
+                    Ok(value)
+                }
+                Err(e) => Err(e),
+            })
+        })?
+    }
+}
+
+fn parse(content: &str) -> Result<ScheduleData, ScheduleError> {
+    if content.trim().is_empty() {
+        return Ok(ScheduleData::default());
+    }
+    serde_json::from_str(content).map_err(|e| ScheduleError::ParseError(e.to_string()))
+}
+
+enum Decision {
+    Wait,
+    Start {
+        start_point: String,
+        base_branch: String,
+    },
+    Cancel(String),
+}
+
+fn decide(entry: &ScheduledLoop, parent: &ParentState) -> Decision {
+    let after = &entry.after;
+    match parent {
+        ParentState::Running | ParentState::Completed => Decision::Wait,
+        ParentState::Queued { base_branch } => match entry.start_on {
+            StartOn::Queued => Decision::Start {
+                start_point: format!("ralph/{after}"),
+                base_branch: base_branch.clone(),
+            },
+            StartOn::Merged => Decision::Wait,
+        },
+        ParentState::Merged { base_branch } => Decision::Start {
+            start_point: base_branch.clone(),
+            base_branch: base_branch.clone(),
+        },
+        ParentState::Discarded => Decision::Cancel(format!("parent loop {after} was discarded")),
+        ParentState::Cancelled => Decision::Cancel(format!("parent loop {after} was cancelled")),
+        ParentState::Exited => {
+            Decision::Cancel(format!("parent loop {after} exited without completing"))
+        }
+    }
+}
+
+#[cfg(unix)]
+fn is_pid_alive(pid: u32) -> bool {
+    use nix::sys::signal::kill;
+    use nix::unistd::Pid;
+
+    kill(Pid::from_raw(pid as i32), None).is_ok()
+}
+
+#[cfg(not(unix))]
+fn is_pid_alive(_pid: u32) -> bool {
+    true
+}
+
+/// Launches a scheduled loop as a detached `ralph` process.
+///
+/// Output goes to `.ralph/diagnostics/logs/ralph-scheduled-<id>-<timestamp>.log`.
+/// Returns the PID of the spawned process.
+pub fn spawn_scheduled_loop(
+    ralph_cmd: impl AsRef<OsStr>,
+    workspace_root: &Path,
+    entry: &ScheduledLoop,
+) -> io::Result<u32> {
+    let logs_dir = workspace_root.join(".ralph/diagnostics/logs");
+    fs::create_dir_all(&logs_dir)?;
+    let _ = crate::diagnostics::rotate_logs(&logs_dir, 10);
+
+    let timestamp = Local::now().format("%Y-%m-%dT%H-%M-%S");
+    let log_path = logs_dir.join(format!("ralph-scheduled-{}-{timestamp}.log", entry.id));
+    let log = fs::File::create(&log_path)?;
+
+    let child = Command::new(ralph_cmd)
+        .args(&entry.args)
+        .env(SCHEDULED_LOOP_ENV, &entry.id)
+        .current_dir(workspace_root)
+        .stdin(Stdio::null())
+        .stdout(log.try_clone()?)
+        .stderr(log)
+        .spawn()?;
+
+    tracing::info!(
+        "Started scheduled loop {} from {} (log: {})",
+        entry.id,
+        entry.start_point.as_deref().unwrap_or("HEAD"),
+        log_path.display()
+    );
+
+    Ok(child.id())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::loop_registry::LoopEntry;
+    use tempfile::TempDir;
+
+    fn pending(id: &str, after: &str, start_on: StartOn) -> ScheduledLoop {
+        ScheduledLoop::new(id, after, start_on, "next task", vec!["run".into()])
+    }
+
+    fn no_launch(_: &ScheduledLoop) -> io::Result<u32> {
+        panic!("nothing should launch")
+    }
+
+    #[test]
+    fn test_add_rejects_unknown_parent_and_duplicates() {
+        let temp = TempDir::new().unwrap();
+        let schedule = LoopSchedule::new(temp.path());
+
+        let err = schedule
+            .add(pending("child", "ghost", StartOn::Merged))
+            .unwrap_err();
+        assert!(matches!(err, ScheduleError::UnknownParent(id) if id == "ghost"));
+
+        MergeQueue::new(temp.path())
+            .enqueue_with_base("parent", "first", Some("main"), None)
+            .unwrap();
+        schedule
+            .add(pending("child", "parent", StartOn::Merged))
+            .unwrap();
+        let err = schedule
+            .add(pending("child", "parent", StartOn::Merged))
+            .unwrap_err();
+        assert!(matches!(err, ScheduleError::Duplicate(_)));
+    }
+
+    #[test]
+    fn test_waits_while_parent_is_running() {
+        let temp = TempDir::new().unwrap();
+        LoopRegistry::new(temp.path())
+            .register(LoopEntry::with_id("parent", "first", None::<String>, "."))
+            .unwrap();
+        let schedule = LoopSchedule::new(temp.path());
+        schedule
+            .add(pending("child", "parent", StartOn::Queued))
+            .unwrap();
+
+        assert!(schedule.reconcile(no_launch).unwrap().is_empty());
+        assert!(schedule.get("child").unwrap().unwrap().is_pending());
+    }
+
+    #[test]
+    fn test_start_on_queued_branches_from_parent() {
+        let temp = TempDir::new().unwrap();
+        let queue = MergeQueue::new(temp.path());
+        queue
+            .enqueue_with_base("parent", "first", Some("develop"), None)
+            .unwrap();
+        let schedule = LoopSchedule::new(temp.path());
+        schedule
+            .add(pending("eager", "parent", StartOn::Queued))
+            .unwrap();
+        schedule
+            .add(pending("patient", "parent", StartOn::Merged))
+            .unwrap();
+
+        let mut launched = Vec::new();
+        let actions = schedule
+            .reconcile(|entry| {
+                launched.push(entry.clone());
+                Ok(42)
+            })
+            .unwrap();
+
+        assert_eq!(
+            actions,
+            vec![ScheduleAction::Started {
+                id: "eager".into(),
+                start_point: "ralph/parent".into(),
+                pid: 42,
+            }]
+        );
+        assert_eq!(launched[0].base_branch.as_deref(), Some("develop"));
+        let eager = schedule.get("eager").unwrap().unwrap();
+        assert_eq!(eager.status, ScheduleStatus::Started);
+        assert_eq!(eager.pid, Some(42));
+        assert!(schedule.get("patient").unwrap().unwrap().is_pending());
+
+        queue.mark_merging("parent", 1).unwrap();
+        queue.mark_merged("parent", "abc123").unwrap();
+        let actions = schedule.reconcile(|_| Ok(43)).unwrap();
+        assert_eq!(
+            actions,
+            vec![ScheduleAction::Started {
+                id: "patient".into(),
+                start_point: "develop".into(),
+                pid: 43,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_discarded_parent_cancels_subtree() {
+        let temp = TempDir::new().unwrap();
+        let queue = MergeQueue::new(temp.path());
+        queue.enqueue("parent", "first").unwrap();
+        let schedule = LoopSchedule::new(temp.path());
+        schedule
+            .add(pending("child", "parent", StartOn::Merged))
+            .unwrap();
+        schedule
+            .add(pending("grandchild", "child", StartOn::Merged))
+            .unwrap();
+
+        queue.discard("parent", Some("not needed")).unwrap();
+        let actions = schedule.reconcile(no_launch).unwrap();
+
+        assert_eq!(actions.len(), 2);
+        let grandchild = schedule.get("grandchild").unwrap().unwrap();
+        assert_eq!(grandchild.status, ScheduleStatus::Cancelled);
+        assert_eq!(
+            grandchild.reason.as_deref(),
+            Some("parent loop child was cancelled")
+        );
+    }
+
+    #[test]
+    fn test_parent_exit_without_completion_cancels_child() {
+        let temp = TempDir::new().unwrap();
+        let history_dir = temp.path().join(".worktrees/parent/.ralph");
+        fs::create_dir_all(&history_dir).unwrap();
+        let history = LoopHistory::new(history_dir.join("history.jsonl"));
+        history.record_started("first").unwrap();
+
+        let schedule = LoopSchedule::new(temp.path());
+        schedule
+            .add(pending("child", "parent", StartOn::Merged))
+            .unwrap();
+        assert_eq!(schedule.parent_state("parent"), ParentState::Exited);
+        schedule.reconcile(no_launch).unwrap();
+        assert_eq!(
+            schedule.get("child").unwrap().unwrap().reason.as_deref(),
+            Some("parent loop parent exited without completing")
+        );
+
+        // A parent that completed but was not queued keeps its children waiting.
+        history.record_completed("completion_promise").unwrap();
+        assert_eq!(schedule.parent_state("parent"), ParentState::Completed);
+    }
+
+    #[test]
+    fn test_launch_failure_cancels_entry() {
+        let temp = TempDir::new().unwrap();
+        let queue = MergeQueue::new(temp.path());
+        queue.enqueue("parent", "first").unwrap();
+        let schedule = LoopSchedule::new(temp.path());
+        schedule
+            .add(pending("child", "parent", StartOn::Queued))
+            .unwrap();
+
+        let actions = schedule
+            .reconcile(|_| Err(io::Error::other("no ralph binary")))
+            .unwrap();
+
+        assert!(matches!(
+            &actions[0],
+            ScheduleAction::Cancelled { reason, .. } if reason.contains("no ralph binary")
+        ));
+    }
+}
@@ -13,7 +13,7 @@ use serde::{Deserialize, Serialize};
 ///
 /// Memories are grouped by type in the markdown storage file,
 /// each with its own section header.
-#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum MemoryType {
     /// How this codebase does things (section: "## Patterns")
@@ -128,6 +128,10 @@ pub struct Memory {
 
     /// Creation date (format: YYYY-MM-DD)
     pub created: String,
+
+    /// How many near-duplicates were merged into this memory by compaction.
+    #[serde(default)]
+    pub reinforced: u32,
 }
 
 impl Memory {
@@ -142,6 +146,28 @@ impl Memory {
             content,
             tags,
             created: chrono::Utc::now().format("%Y-%m-%d").to_string(),
+            reinforced: 0,
+        }
+    }
+
+    /// Formats the `<!-- tags: ... | created: ... -->` metadata comment.
+    ///
+    /// The reinforcement count is only written once a memory has absorbed duplicates.
+    #[must_use]
+    pub fn metadata_comment(&self) -> String {
+        if self.reinforced > 0 {
+            format!(
+                "<!-- tags: {} | created: {} | reinforced: {} -->",
+                self.tags.join(", "),
+                self.created,
+                self.reinforced
+            )
+        } else {
+            format!(
+                "<!-- tags: {} | created: {} -->",
+                self.tags.join(", "),
+                self.created
+            )
         }
     }
 
@@ -293,6 +319,7 @@ mod tests {
             content: "Uses barrel exports for modules".to_string(),
             tags: vec!["imports".to_string(), "structure".to_string()],
             created: "2025-01-20".to_string(),
+            reinforced: 0,
         };
 
         // Match in content
@@ -315,6 +342,7 @@ mod tests {
             content: "Docker fix".to_string(),
             tags: vec!["docker".to_string(), "debugging".to_string()],
             created: "2025-01-20".to_string(),
+            reinforced: 0,
         };
 
         assert!(memory.has_any_tag(&["docker".to_string()]));
@@ -346,6 +374,7 @@ mod tests {
             content: "Chose Postgres".to_string(),
             tags: vec!["database".to_string()],
             created: "2025-01-20".to_string(),
+            reinforced: 0,
         };
 
         let json = serde_json::to_string(&memory).unwrap();
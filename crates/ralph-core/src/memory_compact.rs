@@ -0,0 +1,547 @@
+//! Memory consolidation: near-duplicate merging and expiry.
+//!
+//! `compact_memories` merges memories of the same type whose content is
+//! nearly identical (Jaccard similarity over word shingles), unions their
+//! tags and counts how often the surviving memory was reinforced. It also
+//! expires memories whose referenced file paths no longer exist.
+//!
+//! Every compaction is recorded in `.ralph/agent/memories-journal.jsonl`
+//! with the original versions of all memories it changed or removed, so
+//! `undo_compaction` can restore them.
+
+use std::collections::HashSet;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::memory::Memory;
+use crate::memory_store::MarkdownMemoryStore;
+
+/// Default Jaccard similarity at which two memories count as duplicates.
+pub const DEFAULT_SIMILARITY_THRESHOLD: f64 = 0.8;
+
+/// Words per shingle.
+const SHINGLE_SIZE: usize = 3;
+
+/// File name of the compaction journal, next to the memories file.
+const JOURNAL_FILE: &str = "memories-journal.jsonl";
+
+/// Backticked spans, e.g. `src/main.rs`.
+static BACKTICK_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"`([^`\s]+)`").unwrap());
+
+/// Bare relative paths with a directory and a file extension, e.g. src/lib.rs.
+static BARE_PATH_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?:^|[\s("'\[])((?:\.{1,2}/)?[\w.-]+(?:/[\w.-]+)*/[\w-]+\.[A-Za-z]{1,6})\b"#)
+        .unwrap()
+});
+
+/// Options for [`compact_memories`].
+#[derive(Debug, Clone)]
+pub struct CompactOptions {
+    /// Workspace root that referenced paths are resolved against.
+    pub workspace_root: PathBuf,
+    /// Jaccard similarity (0-1) at which memories are merged.
+    pub threshold: f64,
+    /// Whether to expire memories whose referenced paths are all missing.
+    pub expire_missing_paths: bool,
+    /// Report what would change without writing anything.
+    pub dry_run: bool,
+}
+
+impl CompactOptions {
+    /// Default options for a workspace.
+    pub fn new(workspace_root: impl Into<PathBuf>) -> Self {
+        Self {
+            workspace_root: workspace_root.into(),
+            threshold: DEFAULT_SIMILARITY_THRESHOLD,
+            expire_missing_paths: true,
+            dry_run: false,
+        }
+    }
+}
+
+/// Near-duplicates merged into a surviving memory.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MemoryMerge {
+    /// The memory that was kept.
+    pub kept: String,
+    /// Memories merged into it (and removed).
+    pub merged: Vec<String>,
+    /// Tags the kept memory gained from the merged ones.
+    pub tags_added: Vec<String>,
+    /// Reinforcement count of the kept memory after the merge.
+    pub reinforced: u32,
+}
+
+/// A memory removed because the paths it references no longer exist.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExpiredMemory {
+    pub id: String,
+    pub missing_paths: Vec<String>,
+}
+
+/// A compaction, as recorded in the journal.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CompactReport {
+    /// Journal entry ID (`None` for dry runs and no-op compactions).
+    pub id: Option<String>,
+    pub timestamp: String,
+    pub merges: Vec<MemoryMerge>,
+    pub expired: Vec<ExpiredMemory>,
+    /// Original versions of every memory the compaction changed or removed.
+    pub before: Vec<Memory>,
+}
+
+impl CompactReport {
+    /// Returns true if the compaction changed nothing.
+    pub fn is_empty(&self) -> bool {
+        self.merges.is_empty() && self.expired.is_empty()
+    }
+
+    /// Number of memories removed by merging.
+    pub fn merged_count(&self) -> usize {
+        self.merges.iter().map(|merge| merge.merged.len()).sum()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum JournalRecord {
+    Compact(CompactReport),
+    Undo { id: String, timestamp: String },
+}
+
+/// Returns the journal path for a memory store.
+pub fn journal_path(store: &MarkdownMemoryStore) -> PathBuf {
+    store.path().with_file_name(JOURNAL_FILE)
+}
+
+/// Merges near-duplicate memories and expires memories about missing paths.
+///
+/// Memories are compared within the same type. The oldest memory of a group
+/// of duplicates is kept; it gains the others' tags and its `reinforced`
+/// count grows by one per merged memory (plus their own counts).
+pub fn compact_memories(
+    store: &MarkdownMemoryStore,
+    options: &CompactOptions,
+) -> io::Result<CompactReport> {
+    let journal = journal_path(store);
+    store.rewrite(|memories| {
+        let (compacted, mut report) = plan_compaction(memories, options);
+        if report.is_empty() || options.dry_run {
+            return Ok((None, report));
+        }
+
+        report.id = Some(generate_journal_id());
+        append_journal(&journal, &JournalRecord::Compact(report.clone()))?;
+        Ok((Some(compacted), report))
+    })
+}
+
+/// Reverts a compaction recorded in the journal.
+///
+/// Reverts `id`, or the most recent compaction that has not been undone yet.
+/// Returns the reverted compaction.
+pub fn undo_compaction(store: &MarkdownMemoryStore, id: Option<&str>) -> io::Result<CompactReport> {
+    let journal = journal_path(store);
+    store.rewrite(|memories| {
+        let records = read_journal(&journal)?;
+        let undone: HashSet<&str> = records
+            .iter()
+            .filter_map(|record| match record {
+                JournalRecord::Undo { id, .. } => Some(id.as_str()),
+                JournalRecord::Compact(_) => None,
+            })
+            .collect();
+
+        let target = records
+            .iter()
+            .rev()
+            .filter_map(|record| match record {
+                JournalRecord::Compact(report) => Some(report),
+                JournalRecord::Undo { .. } => None,
+            })
+            .find(|report| {
+                let report_id = report.id.as_deref().unwrap_or_default();
+                !undone.contains(report_id) && id.is_none_or(|id| id == report_id)
+            })
+            .cloned()
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::NotFound,
+                    match id {
+                        Some(id) => format!("No compaction to undo with ID {id}"),
+                        None => "No compaction to undo".to_string(),
+                    },
+                )
+            })?;
+
+        let restored = restore_memories(memories, &target);
+        append_journal(
+            &journal,
+            &JournalRecord::Undo {
+                id: target.id.clone().unwrap_or_default(),
+                timestamp: chrono::Utc::now().to_rfc3339(),
+            },
+        )?;
+        Ok((Some(restored), target))
+    })
+}
+
+/// Computes the compacted memory list and the report, without side effects.
+fn plan_compaction(
+    memories: Vec<Memory>,
+    options: &CompactOptions,
+) -> (Vec<Memory>, CompactReport) {
+    let mut report = CompactReport {
+        timestamp: chrono::Utc::now().to_rfc3339(),
+        ..CompactReport::default()
+    };
+
+    // Expiry first, so expired memories never absorb duplicates.
+    let mut kept = Vec::with_capacity(memories.len());
+    for memory in memories {
+        let missing = if options.expire_missing_paths {
+            missing_paths(&memory.content, &options.workspace_root)
+        } else {
+            None
+        };
+        match missing {
+            Some(missing_paths) => {
+                report.expired.push(ExpiredMemory {
+                    id: memory.id.clone(),
+                    missing_paths,
+                });
+                report.before.push(memory);
+            }
+            None => kept.push(memory),
+        }
+    }
+
+    // Oldest first, so the earliest memory of a duplicate group survives.
+    let mut order: Vec<usize> = (0..kept.len()).collect();
+    order.sort_by_key(|&index| memory_timestamp(&kept[index].id));
+
+    let shingles: Vec<HashSet<String>> = kept.iter().map(|m| shingle(&m.content)).collect();
+    let mut merged_into: Vec<Option<usize>> = vec![None; kept.len()];
+    let mut canonicals: Vec<usize> = Vec::new();
+
+    for &index in &order {
+        let duplicate_of = canonicals.iter().copied().find(|&canonical| {
+            kept[canonical].memory_type == kept[index].memory_type
+                && jaccard(&shingles[canonical], &shingles[index]) >= options.threshold
+        });
+        match duplicate_of {
+            Some(canonical) => merged_into[index] = Some(canonical),
+            None => canonicals.push(index),
+        }
+    }
+
+    for &canonical in &canonicals {
+        let duplicates: Vec<usize> = order
+            .iter()
+            .copied()
+            .filter(|&index| merged_into[index] == Some(canonical))
+            .collect();
+        if duplicates.is_empty() {
+            continue;
+        }
+
+        report.before.push(kept[canonical].clone());
+        let mut merge = MemoryMerge {
+            kept: kept[canonical].id.clone(),
+            merged: Vec::new(),
+            tags_added: Vec::new(),
+            reinforced: 0,
+        };
+        for index in duplicates {
+            let duplicate = kept[index].clone();
+            for tag in &duplicate.tags {
+                let target = &mut kept[canonical];
+                if !target.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+                    target.tags.push(tag.clone());
+                    merge.tags_added.push(tag.clone());
+                }
+            }
+            kept[canonical].reinforced += 1 + duplicate.reinforced;
+            merge.merged.push(duplicate.id.clone());
+            report.before.push(duplicate);
+        }
+        merge.reinforced = kept[canonical].reinforced;
+        report.merges.push(merge);
+    }
+
+    let compacted = kept
+        .into_iter()
+        .zip(merged_into)
+        .filter_map(|(memory, merged)| merged.is_none().then_some(memory))
+        .collect();
+    (compacted, report)
+}
+
+/// Puts the original versions recorded by a compaction back in place.
+fn restore_memories(memories: Vec<Memory>, report: &CompactReport) -> Vec<Memory> {
+    let originals: HashSet<&str> = report.before.iter().map(|m| m.id.as_str()).collect();
+    let mut restored: Vec<Memory> = memories
+        .into_iter()
+        .filter(|memory| !originals.contains(memory.id.as_str()))
+        .collect();
+    restored.extend(report.before.iter().cloned());
+    restored.sort_by_key(|memory| std::cmp::Reverse(memory_timestamp(&memory.id)));
+    restored
+}
+
+/// Returns the referenced paths if the content references paths and none exist.
+fn missing_paths(content: &str, workspace_root: &Path) -> Option<Vec<String>> {
+    let paths = referenced_paths(content);
+    if paths.is_empty() || paths.iter().any(|path| workspace_root.join(path).exists()) {
+        return None;
+    }
+    Some(paths)
+}
+
+/// Extracts workspace-relative file paths mentioned in memory content.
+///
+/// Only backticked spans that look like paths and bare `dir/file.ext` tokens
+/// count, so prose such as "and/or" is never mistaken for a path.
+fn referenced_paths(content: &str) -> Vec<String> {
+    let mut paths = Vec::new();
+    let backticked = BACKTICK_RE
+        .captures_iter(content)
+        .map(|caps| caps[1].to_string())
+        .filter(|span| span.contains('/') || looks_like_file(span));
+    let bare = BARE_PATH_RE
+        .captures_iter(content)
+        .map(|caps| caps[1].to_string());
+
+    for candidate in backticked.chain(bare) {
+        let candidate = candidate
+            .trim_end_matches([':', ',', '.', ';', ')'])
+            .trim_start_matches("./")
+            .to_string();
+        let skip = candidate.is_empty()
+            || candidate.starts_with('/')
+            || candidate.starts_with('~')
+            || candidate.contains("://")
+            || candidate.contains(['*', '<', '>', '{', '$']);
+        if !skip && !paths.contains(&candidate) {
+            paths.push(candidate);
+        }
+    }
+    paths
+}
+
+fn looks_like_file(span: &str) -> bool {
+    span.rsplit_once('.').is_some_and(|(stem, extension)| {
+        !stem.is_empty()
+            && (1..=6).contains(&extension.len())
+            && extension.chars().all(|c| c.is_ascii_alphabetic())
+    })
+}
+
+/// Word shingles of the normalized content (single words for short content).
+fn shingle(content: &str) -> HashSet<String> {
+    let words: Vec<String> = content
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+    if words.len() < SHINGLE_SIZE {
+        return words.into_iter().collect();
+    }
+    words
+        .windows(SHINGLE_SIZE)
+        .map(|window| window.join(" "))
+        .collect()
+}
+
+fn jaccard(a: &HashSet<String>, b: &HashSet<String>) -> f64 {
+    if a.is_empty() && b.is_empty() {
+        return 1.0;
+    }
+    let intersection = a.intersection(b).count();
+    let union = a.len() + b.len() - intersection;
+    intersection as f64 / union as f64
+}
+
+/// Creation timestamp encoded in a `mem-{timestamp}-{hex}` ID.
+fn memory_timestamp(id: &str) -> u64 {
+    id.split('-')
+        .nth(1)
+        .and_then(|timestamp| timestamp.parse().ok())
+        .unwrap_or(0)
+}
+
+fn generate_journal_id() -> String {
+    let duration = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default();
+    format!(
+        "compact-{}-{:04x}",
+        duration.as_secs(),
+        duration.subsec_micros() % 0x10000
+    )
+}
+
+fn append_journal(path: &Path, record: &JournalRecord) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut line = serde_json::to_vec(record).map_err(io::Error::other)?;
+    line.push(b'\n');
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?
+        .write_all(&line)
+}
+
+fn read_journal(path: &Path) -> io::Result<Vec<JournalRecord>> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(error) => return Err(error),
+    };
+    Ok(content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::MemoryType;
+    use tempfile::TempDir;
+
+    fn memory(id: &str, memory_type: MemoryType, content: &str, tags: &[&str]) -> Memory {
+        Memory {
+            id: id.to_string(),
+            memory_type,
+            content: content.to_string(),
+            tags: tags.iter().map(ToString::to_string).collect(),
+            created: "2025-01-20".to_string(),
+            reinforced: 0,
+        }
+    }
+
+    fn seeded_store(temp_dir: &TempDir) -> MarkdownMemoryStore {
+        let store = MarkdownMemoryStore::with_default_path(temp_dir.path());
+        for memory in [
+            memory(
+                "mem-1000-aaaa",
+                MemoryType::Fix,
+                "Run cargo fmt before committing or CI fails the lint step",
+                &["ci"],
+            ),
+            memory(
+                "mem-2000-bbbb",
+                MemoryType::Fix,
+                "Run cargo fmt before committing, or CI fails the lint step!",
+                &["formatting", "ci"],
+            ),
+            memory(
+                "mem-3000-cccc",
+                MemoryType::Pattern,
+                "Run cargo fmt before committing or CI fails the lint step",
+                &[],
+            ),
+            memory(
+                "mem-4000-dddd",
+                MemoryType::Context,
+                "Auth lives in `src/legacy/auth.rs`",
+                &["auth"],
+            ),
+            memory(
+                "mem-5000-eeee",
+                MemoryType::Context,
+                "Config parsing is in src/config.rs and/or nearby",
+                &[],
+            ),
+        ] {
+            store.append(&memory).unwrap();
+        }
+        std::fs::create_dir_all(temp_dir.path().join("src")).unwrap();
+        std::fs::write(temp_dir.path().join("src/config.rs"), "").unwrap();
+        store
+    }
+
+    #[test]
+    fn merges_duplicates_and_expires_missing_paths() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = seeded_store(&temp_dir);
+
+        let report = compact_memories(&store, &CompactOptions::new(temp_dir.path())).unwrap();
+        assert!(report.id.is_some());
+        assert_eq!(
+            report.merges,
+            vec![MemoryMerge {
+                kept: "mem-1000-aaaa".to_string(),
+                merged: vec!["mem-2000-bbbb".to_string()],
+                tags_added: vec!["formatting".to_string()],
+                reinforced: 1,
+            }]
+        );
+        assert_eq!(
+            report.expired,
+            vec![ExpiredMemory {
+                id: "mem-4000-dddd".to_string(),
+                missing_paths: vec!["src/legacy/auth.rs".to_string()],
+            }]
+        );
+
+        let memories = store.load().unwrap();
+        let ids: Vec<&str> = memories.iter().map(|m| m.id.as_str()).collect();
+        assert_eq!(ids.len(), 3);
+        assert!(!ids.contains(&"mem-2000-bbbb"));
+        assert!(!ids.contains(&"mem-4000-dddd"));
+        // Same content under another type is not a duplicate.
+        assert!(ids.contains(&"mem-3000-cccc"));
+
+        let kept = store.get("mem-1000-aaaa").unwrap().unwrap();
+        assert_eq!(kept.reinforced, 1);
+        assert_eq!(kept.tags, vec!["ci", "formatting"]);
+    }
+
+    #[test]
+    fn undo_restores_the_original_memories() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = seeded_store(&temp_dir);
+        let original = std::fs::read_to_string(store.path()).unwrap();
+
+        let dry_run = compact_memories(
+            &store,
+            &CompactOptions {
+                dry_run: true,
+                ..CompactOptions::new(temp_dir.path())
+            },
+        )
+        .unwrap();
+        assert!(dry_run.id.is_none());
+        assert_eq!(dry_run.merged_count(), 1);
+        assert_eq!(std::fs::read_to_string(store.path()).unwrap(), original);
+
+        let report = compact_memories(&store, &CompactOptions::new(temp_dir.path())).unwrap();
+        let undone = undo_compaction(&store, None).unwrap();
+        assert_eq!(undone.id, report.id);
+        assert_eq!(std::fs::read_to_string(store.path()).unwrap(), original);
+
+        // Nothing left to undo.
+        assert!(undo_compaction(&store, None).is_err());
+    }
+
+    #[test]
+    fn only_path_like_references_count() {
+        assert_eq!(
+            referenced_paths("See `crates/core/lib.rs`, docs/guide.md and read/write access"),
+            vec!["crates/core/lib.rs", "docs/guide.md"]
+        );
+        assert!(referenced_paths("Use https://example.com/a.html or `/etc/hosts`").is_empty());
+        assert!(jaccard(&shingle("a b c d"), &shingle("a b c d")) > 0.99);
+    }
+}
@@ -0,0 +1,251 @@
+//! Local relevance ranking for memories.
+//!
+//! Provides `MemoryIndex`, a BM25 index over memory content and tags, and
+//! `select_memories`, which picks the memories most relevant to a query
+//! while staying within the `memories.budget` token budget.
+//!
+//! Everything runs in-process; no embeddings or network calls are involved.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::memory::{Memory, MemoryType};
+use crate::memory_store::{format_memory_block, format_memory_section_header};
+
+/// BM25 term-frequency saturation.
+const K1: f64 = 1.2;
+/// BM25 document-length normalization.
+const B: f64 = 0.75;
+/// Tags are short and deliberate, so they count more than content terms.
+const TAG_WEIGHT: usize = 2;
+
+/// Words too common to carry relevance signal.
+const STOPWORDS: &[&str] = &[
+    "a", "an", "and", "are", "as", "at", "be", "by", "for", "from", "has", "have", "in", "is",
+    "it", "its", "of", "on", "or", "that", "the", "this", "to", "was", "were", "will", "with",
+    "you", "your",
+];
+
+/// A BM25 index over a set of memories.
+#[derive(Debug)]
+pub struct MemoryIndex {
+    documents: Vec<HashMap<String, usize>>,
+    lengths: Vec<usize>,
+    average_length: f64,
+    document_frequency: HashMap<String, usize>,
+}
+
+impl MemoryIndex {
+    /// Builds an index over the memories' content and tags.
+    #[must_use]
+    pub fn new(memories: &[Memory]) -> Self {
+        let mut documents = Vec::with_capacity(memories.len());
+        let mut lengths = Vec::with_capacity(memories.len());
+        let mut document_frequency: HashMap<String, usize> = HashMap::new();
+
+        for memory in memories {
+            let mut terms: HashMap<String, usize> = HashMap::new();
+            for token in tokenize(&memory.content) {
+                *terms.entry(token).or_insert(0) += 1;
+            }
+            for tag in &memory.tags {
+                for token in tokenize(tag) {
+                    *terms.entry(token).or_insert(0) += TAG_WEIGHT;
+                }
+            }
+
+            for term in terms.keys() {
+                *document_frequency.entry(term.clone()).or_insert(0) += 1;
+            }
+            lengths.push(terms.values().sum());
+            documents.push(terms);
+        }
+
+        let average_length = if lengths.is_empty() {
+            0.0
+        } else {
+            lengths.iter().sum::<usize>() as f64 / lengths.len() as f64
+        };
+
+        Self {
+            documents,
+            lengths,
+            average_length,
+            document_frequency,
+        }
+    }
+
+    /// Scores every memory against the query, in index order.
+    ///
+    /// Repeated query terms count once, so long queries (whole prompts) are
+    /// not dominated by their most frequent words.
+    #[must_use]
+    pub fn scores(&self, query: &str) -> Vec<f64> {
+        let query_terms: HashSet<String> = tokenize(query).collect();
+        let count = self.documents.len() as f64;
+
+        self.documents
+            .iter()
+            .zip(&self.lengths)
+            .map(|(terms, &length)| {
+                let normalization =
+                    K1 * (1.0 - B + B * length as f64 / self.average_length.max(1.0));
+                query_terms
+                    .iter()
+                    .filter_map(|term| {
+                        let frequency = *terms.get(term)? as f64;
+                        let df = *self.document_frequency.get(term)? as f64;
+                        let idf = ((count - df + 0.5) / (df + 0.5) + 1.0).ln();
+                        Some(idf * frequency * (K1 + 1.0) / (frequency + normalization))
+                    })
+                    .sum()
+            })
+            .collect()
+    }
+
+    /// Returns `(index, score)` pairs ordered by descending score.
+    ///
+    /// Ties keep the original memory order.
+    #[must_use]
+    pub fn rank(&self, query: &str) -> Vec<(usize, f64)> {
+        let mut ranked: Vec<(usize, f64)> = self.scores(query).into_iter().enumerate().collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+        ranked
+    }
+}
+
+/// A memory picked for injection, with its relevance score.
+#[derive(Debug, Clone, Copy)]
+pub struct ScoredMemory<'a> {
+    pub memory: &'a Memory,
+    pub score: f64,
+}
+
+/// Memories picked for injection by `select_memories`.
+#[derive(Debug)]
+pub struct MemorySelection<'a> {
+    /// Picked memories, most relevant first.
+    pub memories: Vec<ScoredMemory<'a>>,
+    /// Memories left out to respect the budget.
+    pub omitted: usize,
+}
+
+/// Picks memories by relevance to `query` within a token budget.
+///
+/// Memories are considered in descending BM25 score and added while the
+/// formatted output fits `budget` tokens (~4 chars per token; 0 = unlimited).
+/// A memory that does not fit is skipped so smaller, lower-ranked ones can
+/// still use the remaining space.
+#[must_use]
+pub fn select_memories<'a>(
+    memories: &'a [Memory],
+    query: &str,
+    budget: usize,
+) -> MemorySelection<'a> {
+    let ranked = MemoryIndex::new(memories).rank(query);
+    let char_budget = if budget == 0 { usize::MAX } else { budget * 4 };
+
+    let mut used = "# Memories\n".len();
+    let mut sections: HashSet<MemoryType> = HashSet::new();
+    let mut picked = Vec::new();
+    let mut omitted = 0;
+
+    for (index, score) in ranked {
+        let memory = &memories[index];
+        let mut size = format_memory_block(memory).len();
+        if !sections.contains(&memory.memory_type) {
+            size += format_memory_section_header(memory.memory_type).len();
+        }
+
+        if used.saturating_add(size) > char_budget {
+            omitted += 1;
+            continue;
+        }
+
+        used += size;
+        sections.insert(memory.memory_type);
+        picked.push(ScoredMemory { memory, score });
+    }
+
+    MemorySelection {
+        memories: picked,
+        omitted,
+    }
+}
+
+/// Splits text into lowercase alphanumeric terms, dropping stopwords.
+fn tokenize(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|token| token.len() > 1)
+        .map(str::to_lowercase)
+        .filter(|token| !STOPWORDS.contains(&token.as_str()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn memory(id: &str, memory_type: MemoryType, content: &str, tags: &[&str]) -> Memory {
+        Memory {
+            id: id.to_string(),
+            memory_type,
+            content: content.to_string(),
+            tags: tags.iter().map(ToString::to_string).collect(),
+            created: "2025-01-20".to_string(),
+            reinforced: 0,
+        }
+    }
+
+    fn sample() -> Vec<Memory> {
+        vec![
+            memory(
+                "mem-1",
+                MemoryType::Pattern,
+                "Use barrel exports for every module",
+                &["typescript"],
+            ),
+            memory(
+                "mem-2",
+                MemoryType::Fix,
+                "Postgres migrations must run before the integration tests",
+                &["database", "testing"],
+            ),
+            memory(
+                "mem-3",
+                MemoryType::Decision,
+                "Chose JWT tokens over sessions for the auth service",
+                &["auth"],
+            ),
+        ]
+    }
+
+    #[test]
+    fn ranks_by_content_and_tags() {
+        let memories = sample();
+        let index = MemoryIndex::new(&memories);
+
+        let ranked = index.rank("fix the failing auth token refresh");
+        assert_eq!(memories[ranked[0].0].id, "mem-3");
+        assert!(ranked[0].1 > 0.0);
+
+        let ranked = index.rank("database testing");
+        assert_eq!(memories[ranked[0].0].id, "mem-2");
+        assert!(ranked.iter().skip(1).all(|(_, score)| *score == 0.0));
+    }
+
+    #[test]
+    fn selection_keeps_relevant_memories_within_budget() {
+        let memories = sample();
+
+        let all = select_memories(&memories, "auth", 0);
+        assert_eq!(all.memories.len(), 3);
+        assert_eq!(all.omitted, 0);
+        assert_eq!(all.memories[0].memory.id, "mem-3");
+
+        // Room for roughly one memory: the relevant one wins even though it
+        // is not first in the file.
+        let tight = select_memories(&memories, "auth jwt", 40);
+        assert_eq!(tight.memories.len(), 1);
+        assert_eq!(tight.memories[0].memory.id, "mem-3");
+        assert_eq!(tight.omitted, 2);
+    }
+}
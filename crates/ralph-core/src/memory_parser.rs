@@ -23,9 +23,11 @@ static MEMORY_ID_RE: LazyLock<Regex> =
 /// Regex to match blockquote content lines like `> content`
 static CONTENT_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^> (.+)$").unwrap());
 
-/// Regex to match metadata HTML comments like `<!-- tags: a, b | created: 2025-01-20 -->`
+/// Regex to match metadata HTML comments like `<!-- tags: a, b | created: 2025-01-20 -->`,
+/// optionally followed by `| reinforced: N`.
 static METADATA_RE: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(r"<!-- tags: ([^|]*) \| created: (\d{4}-\d{2}-\d{2}) -->").unwrap()
+    Regex::new(r"<!-- tags: ([^|]*) \| created: (\d{4}-\d{2}-\d{2})(?: \| reinforced: (\d+))? -->")
+        .unwrap()
 });
 
 /// Parse a memories markdown file into a vector of Memory structs.
@@ -53,6 +55,7 @@ pub fn parse_memories(markdown: &str) -> Vec<Memory> {
     let mut current_content: Vec<String> = Vec::new();
     let mut current_tags: Vec<String> = Vec::new();
     let mut current_created: Option<String> = None;
+    let mut current_reinforced: u32 = 0;
 
     for line in markdown.lines() {
         if let Some(caps) = SECTION_RE.captures(line) {
@@ -64,6 +67,7 @@ pub fn parse_memories(markdown: &str) -> Vec<Memory> {
                 &mut current_content,
                 &mut current_tags,
                 &mut current_created,
+                &mut current_reinforced,
             );
             current_type = MemoryType::from_section(&caps[1]).unwrap_or(MemoryType::Pattern);
         } else if let Some(caps) = MEMORY_ID_RE.captures(line) {
@@ -75,6 +79,7 @@ pub fn parse_memories(markdown: &str) -> Vec<Memory> {
                 &mut current_content,
                 &mut current_tags,
                 &mut current_created,
+                &mut current_reinforced,
             );
             current_id = Some(caps[1].to_string());
         } else if let Some(caps) = CONTENT_RE.captures(line) {
@@ -86,6 +91,10 @@ pub fn parse_memories(markdown: &str) -> Vec<Memory> {
                 .filter(|s| !s.is_empty())
                 .collect();
             current_created = Some(caps[2].to_string());
+            current_reinforced = caps
+                .get(3)
+                .and_then(|m| m.as_str().parse().ok())
+                .unwrap_or(0);
         }
     }
 
@@ -97,6 +106,7 @@ pub fn parse_memories(markdown: &str) -> Vec<Memory> {
         &mut current_content,
         &mut current_tags,
         &mut current_created,
+        &mut current_reinforced,
     );
 
     memories
@@ -110,6 +120,7 @@ fn flush_memory(
     current_content: &mut Vec<String>,
     current_tags: &mut Vec<String>,
     current_created: &mut Option<String>,
+    current_reinforced: &mut u32,
 ) {
     if let Some(id) = current_id.take()
         && !current_content.is_empty()
@@ -122,9 +133,11 @@ fn flush_memory(
             created: current_created
                 .take()
                 .unwrap_or_else(|| chrono::Utc::now().format("%Y-%m-%d").to_string()),
+            reinforced: std::mem::take(current_reinforced),
         });
     }
     current_content.clear();
+    *current_reinforced = 0;
 }
 
 #[cfg(test)]
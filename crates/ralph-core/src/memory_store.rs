@@ -170,6 +170,30 @@ impl MarkdownMemoryStore {
         Ok(true)
     }
 
+    /// Loads, transforms and rewrites all memories under one exclusive lock.
+    ///
+    /// `update` receives the current memories and returns the memories to
+    /// write back (`None` leaves the file untouched) plus a value for the caller.
+    pub fn rewrite<T>(
+        &self,
+        update: impl FnOnce(Vec<Memory>) -> io::Result<(Option<Vec<Memory>>, T)>,
+    ) -> io::Result<T> {
+        let lock = FileLock::new(&self.path)?;
+        let _guard = lock.exclusive()?;
+
+        let memories = if self.exists() {
+            parse_memories(&fs::read_to_string(&self.path)?)
+        } else {
+            Vec::new()
+        };
+
+        let (updated, result) = update(memories)?;
+        if let Some(updated) = updated {
+            self.write_all_internal(&updated)?;
+        }
+        Ok(result)
+    }
+
     /// Returns the memory with the given ID, if it exists.
     pub fn get(&self, id: &str) -> io::Result<Option<Memory>> {
         let memories = self.load()?;
@@ -244,11 +268,10 @@ impl MarkdownMemoryStore {
             .collect();
 
         format!(
-            "\n### {}\n{}\n<!-- tags: {} | created: {} -->\n",
+            "\n### {}\n{}\n{}\n",
             memory.id,
             content_lines.join("\n"),
-            memory.tags.join(", "),
-            memory.created,
+            memory.metadata_comment(),
         )
     }
 
@@ -303,22 +326,31 @@ pub fn format_memories_as_markdown(memories: &[Memory]) -> String {
             continue;
         }
 
-        output.push_str(&format!("\n## {}\n", memory_type.section_name()));
+        output.push_str(&format_memory_section_header(*memory_type));
 
         for memory in type_memories {
-            output.push_str(&format!(
-                "\n### {}\n> {}\n<!-- tags: {} | created: {} -->\n",
-                memory.id,
-                memory.content.replace('\n', "\n> "),
-                memory.tags.join(", "),
-                memory.created
-            ));
+            output.push_str(&format_memory_block(memory));
         }
     }
 
     output
 }
 
+/// Formats the `## <Section>` header that groups memories of one type.
+pub(crate) fn format_memory_section_header(memory_type: MemoryType) -> String {
+    format!("\n## {}\n", memory_type.section_name())
+}
+
+/// Formats a single memory block as produced by `format_memories_as_markdown`.
+pub(crate) fn format_memory_block(memory: &Memory) -> String {
+    format!(
+        "\n### {}\n> {}\n{}\n",
+        memory.id,
+        memory.content.replace('\n', "\n> "),
+        memory.metadata_comment()
+    )
+}
+
 /// Truncates memory content to approximately fit within a token budget.
 ///
 /// Uses a simple heuristic of ~4 characters per token. Tries to end
@@ -691,6 +723,7 @@ mod tests {
             content: "Use barrel exports".to_string(),
             tags: vec!["imports".to_string()],
             created: "2025-01-20".to_string(),
+            reinforced: 0,
         };
 
         let output = format_memories_as_markdown(&[memory]);
@@ -710,6 +743,7 @@ mod tests {
             content: "A pattern".to_string(),
             tags: vec![],
             created: "2025-01-20".to_string(),
+            reinforced: 0,
         };
         let decision = Memory {
             id: "mem-2-d".to_string(),
@@ -717,6 +751,7 @@ mod tests {
             content: "A decision".to_string(),
             tags: vec![],
             created: "2025-01-20".to_string(),
+            reinforced: 0,
         };
 
         let output = format_memories_as_markdown(&[pattern, decision]);
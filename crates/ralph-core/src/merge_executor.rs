@@ -0,0 +1,1043 @@
+//! Deterministic merge queue executor.
+//!
+//! Processes [`MergeQueue`] entries without an LLM: each queued loop branch
+//! (`ralph/<id>`) is merged or rebased onto its recorded base branch in a
+//! scratch worktree, the configured verification commands run there, and the
+//! base branch is fast-forwarded only when everything passes.
+//!
+//! # Outcomes
+//!
+//! - **Merged**: base fast-forwarded, entry marked merged, loop worktree removed
+//! - **Conflict**: entry marked `needs_review` with the conflicted files; callers
+//!   may hand it to merge-ralph (`merge.llm_fallback`)
+//! - **Verification failed / failed**: entry marked `needs_review` with the output
+//!
+//! # Hooks
+//!
+//! When `hooks.enabled` is set, `pre.merge` hooks run before each entry is
+//! integrated and `post.merge` hooks run once its outcome is recorded. They
+//! run through a [`HookOrchestrator`] per entry, so hook runs are recorded in
+//! diagnostics and `metadata` returned by `pre.merge` reaches `post.merge`.
+//! A hook that suspends writes suspend-state under the repo root and waits
+//! for `ralph loops resume <loop-id>`. A `pre.merge` hook that blocks (or is
+//! stopped while suspended) parks the entry in `needs_review` (retry with
+//! `ralph loops retry`); a `post.merge` hook that does so stops the executor,
+//! leaving later entries queued.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use ralph_core::merge_executor::MergeExecutor;
+//! use ralph_core::RalphConfig;
+//!
+//! fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let config = RalphConfig::default();
+//!     let executor = MergeExecutor::from_config(".", &config);
+//!     for report in executor.process_all()? {
+//!         println!("{}: {}", report.loop_id, report.outcome.summary());
+//!     }
+//!     Ok(())
+//! }
+//! ```
+
+use crate::config::{HooksConfig, MergeConfig, MergeStrategy, RalphConfig};
+use crate::diagnostics::DiagnosticsCollector;
+use crate::file_lock::FileLock;
+use crate::hooks::{
+    HookExecutor, HookExecutorContract, HookOrchestrator, HookPayloadContextInput, HookPhaseEvent,
+    HookRunRequest,
+};
+use crate::merge_queue::{MergeEntry, MergeQueue, MergeQueueError, MergeState, loop_base_branch};
+use crate::worktree::{WorktreeConfig, list_worktrees, remove_worktree};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tracing::{debug, error, info, warn};
+
+/// Phase-event label used for verification command runs.
+const MERGE_VERIFY_PHASE: &str = "merge.verify";
+
+/// Number of trailing output lines recorded in `needs_review` reasons.
+const FAILURE_OUTPUT_TAIL_LINES: usize = 20;
+
+/// Lock file serializing executors across processes.
+const EXECUTOR_LOCK_FILE: &str = ".ralph/merge-executor";
+
+/// Errors that stop the executor before it can process entries.
+#[derive(Debug, thiserror::Error)]
+pub enum MergeExecutorError {
+    /// Merge queue could not be read or updated.
+    #[error(transparent)]
+    Queue(#[from] MergeQueueError),
+
+    /// IO error while preparing the executor.
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// The workspace is not a git repository.
+    #[error("Not a git repository: {0}")]
+    NotARepo(String),
+}
+
+/// Result of processing a single queue entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MergeOutcome {
+    /// Base branch fast-forwarded to `commit`.
+    Merged { commit: String },
+
+    /// Integration stopped on conflicts in `files`.
+    Conflict { files: Vec<String>, output: String },
+
+    /// A verification command failed on the integrated tree.
+    VerificationFailed { command: String, output: String },
+
+    /// Any other failure (missing branch, base moved, git error).
+    Failed { reason: String },
+}
+
+impl MergeOutcome {
+    /// Returns true when the base branch now contains the loop.
+    pub fn is_merged(&self) -> bool {
+        matches!(self, Self::Merged { .. })
+    }
+
+    /// Returns true when the outcome may be resolved by merge-ralph.
+    pub fn is_conflict(&self) -> bool {
+        matches!(self, Self::Conflict { .. })
+    }
+
+    /// One-line status, e.g. `merged (abc1234)` or `conflict in 2 file(s)`.
+    pub fn summary(&self) -> String {
+        match self {
+            Self::Merged { commit } => format!("merged ({})", short_sha(commit)),
+            Self::Conflict { files, .. } => format!("conflict in {} file(s)", files.len()),
+            Self::VerificationFailed { command, .. } => {
+                format!("verification failed: {command}")
+            }
+            Self::Failed { reason } => format!("failed: {}", first_line(reason)),
+        }
+    }
+
+    /// Reason recorded on the queue entry when the merge needs review.
+    pub fn review_reason(&self) -> Option<String> {
+        match self {
+            Self::Merged { .. } => None,
+            Self::Conflict { files, output } => {
+                let mut reason = format!("Merge conflict in: {}", files.join(", "));
+                if !output.trim().is_empty() {
+                    reason.push('\n');
+                    reason.push_str(output.trim_end());
+                }
+                Some(reason)
+            }
+            Self::VerificationFailed { command, output } => {
+                let mut reason = format!("Verification failed: {command}");
+                if !output.trim().is_empty() {
+                    reason.push('\n');
+                    reason.push_str(output.trim_end());
+                }
+                Some(reason)
+            }
+            Self::Failed { reason } => Some(reason.clone()),
+        }
+    }
+}
+
+/// Report for one processed queue entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeReport {
+    /// Loop whose branch was processed.
+    pub loop_id: String,
+
+    /// Branch the loop was merged (or attempted to merge) into.
+    pub base_branch: String,
+
+    /// What happened.
+    pub outcome: MergeOutcome,
+
+    /// Set when a `post.merge` hook stopped the executor after this entry.
+    pub halted: Option<String>,
+}
+
+/// Merges queued loops with git and verification commands, sequentially.
+pub struct MergeExecutor<E = HookExecutor> {
+    repo_root: PathBuf,
+    config: MergeConfig,
+    verify_commands: Vec<String>,
+    worktree_config: WorktreeConfig,
+    executor: E,
+    hooks: Option<HooksConfig>,
+    diagnostics: DiagnosticsCollector,
+}
+
+impl MergeExecutor<HookExecutor> {
+    /// Creates an executor using `merge` settings, the resolved verification
+    /// commands and, when enabled, the project's merge hooks.
+    pub fn from_config(repo_root: impl Into<PathBuf>, config: &RalphConfig) -> Self {
+        let verify_commands = config.merge.verification_commands(&config.backpressure);
+        let executor = Self::new(repo_root, config.merge.clone(), verify_commands);
+        if config.hooks.enabled {
+            executor.with_hooks(config.hooks.clone())
+        } else {
+            executor
+        }
+    }
+
+    /// Creates an executor with explicit verification commands.
+    pub fn new(
+        repo_root: impl Into<PathBuf>,
+        config: MergeConfig,
+        verify_commands: Vec<String>,
+    ) -> Self {
+        Self::with_executor(repo_root, config, verify_commands, HookExecutor::new())
+    }
+}
+
+impl<E: HookExecutorContract> MergeExecutor<E> {
+    /// Creates an executor with an explicit command executor (for testing).
+    pub fn with_executor(
+        repo_root: impl Into<PathBuf>,
+        config: MergeConfig,
+        verify_commands: Vec<String>,
+        executor: E,
+    ) -> Self {
+        Self {
+            repo_root: repo_root.into(),
+            config,
+            verify_commands,
+            worktree_config: WorktreeConfig::default(),
+            executor,
+            hooks: None,
+            diagnostics: DiagnosticsCollector::disabled(),
+        }
+    }
+
+    /// Runs `pre.merge` / `post.merge` hooks from `hooks` around each entry.
+    #[must_use]
+    pub fn with_hooks(mut self, hooks: HooksConfig) -> Self {
+        self.diagnostics = DiagnosticsCollector::new(&self.repo_root).unwrap_or_else(|e| {
+            debug!(
+                "Failed to initialize diagnostics: {}, using disabled collector",
+                e
+            );
+            DiagnosticsCollector::disabled()
+        });
+        self.hooks = Some(hooks);
+        self
+    }
+
+    /// Returns the verification commands gating each merge.
+    pub fn verify_commands(&self) -> &[String] {
+        &self.verify_commands
+    }
+
+    /// Processes queued entries in FIFO order until the queue has no pending loops.
+    ///
+    /// Returns an empty list without touching the queue when another executor
+    /// already holds the lock.
+    pub fn process_all(&self) -> Result<Vec<MergeReport>, MergeExecutorError> {
+        self.ensure_repo()?;
+
+        let lock = FileLock::new(self.repo_root.join(EXECUTOR_LOCK_FILE))?;
+        let Some(_guard) = lock.try_exclusive()? else {
+            info!("Another merge executor is running, leaving queue untouched");
+            return Ok(Vec::new());
+        };
+
+        let queue = MergeQueue::new(&self.repo_root);
+        let mut seen = HashSet::new();
+        let mut reports = Vec::new();
+
+        while let Some(entry) = queue.next_pending()? {
+            // Guard against entries that cannot leave `Queued` (e.g. lost races).
+            if !seen.insert(entry.loop_id.clone()) {
+                break;
+            }
+            if let Some(report) = self.process_entry(&queue, &entry)? {
+                let halted = report.halted.is_some();
+                reports.push(report);
+                if halted {
+                    break;
+                }
+            }
+        }
+
+        Ok(reports)
+    }
+
+    /// Processes the oldest queued entry, if any.
+    pub fn process_next(&self) -> Result<Option<MergeReport>, MergeExecutorError> {
+        self.ensure_repo()?;
+
+        let queue = MergeQueue::new(&self.repo_root);
+        match queue.next_pending()? {
+            Some(entry) => self.process_entry(&queue, &entry),
+            None => Ok(None),
+        }
+    }
+
+    /// Processes one entry regardless of its position in the queue.
+    ///
+    /// Used by `ralph loops merge` / `ralph loops retry`. Entries in `Queued`
+    /// or `NeedsReview` are claimed first; an entry already in `Merging` is
+    /// taken over (`--force`). Returns `None` when another executor holds the
+    /// lock or the entry can no longer be claimed.
+    pub fn process_loop(&self, loop_id: &str) -> Result<Option<MergeReport>, MergeExecutorError> {
+        self.ensure_repo()?;
+
+        let lock = FileLock::new(self.repo_root.join(EXECUTOR_LOCK_FILE))?;
+        let Some(_guard) = lock.try_exclusive()? else {
+            info!(loop_id = %loop_id, "Another merge executor is running, leaving entry untouched");
+            return Ok(None);
+        };
+
+        let queue = MergeQueue::new(&self.repo_root);
+        let entry = queue
+            .get_entry(loop_id)?
+            .ok_or_else(|| MergeQueueError::NotFound(loop_id.to_string()))?;
+        self.process_entry(&queue, &entry)
+    }
+
+    fn ensure_repo(&self) -> Result<(), MergeExecutorError> {
+        if git(&self.repo_root, &["rev-parse", "--git-dir"]).is_ok() {
+            Ok(())
+        } else {
+            Err(MergeExecutorError::NotARepo(
+                self.repo_root.to_string_lossy().to_string(),
+            ))
+        }
+    }
+
+    fn process_entry(
+        &self,
+        queue: &MergeQueue,
+        entry: &MergeEntry,
+    ) -> Result<Option<MergeReport>, MergeExecutorError> {
+        let loop_id = &entry.loop_id;
+        if entry.state != MergeState::Merging {
+            match queue.mark_merging(loop_id, std::process::id()) {
+                Ok(()) => {}
+                Err(MergeQueueError::InvalidTransition(_, from, _)) => {
+                    debug!(loop_id = %loop_id, state = ?from, "Queue entry claimed elsewhere, skipping");
+                    return Ok(None);
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        let base_branch = entry
+            .base_branch
+            .clone()
+            .unwrap_or_else(|| loop_base_branch(&self.repo_root, loop_id));
+
+        info!(loop_id = %loop_id, base = %base_branch, strategy = ?self.config.strategy, "Merging queued loop");
+
+        let mut hooks = self.hooks.as_ref().map(|config| {
+            HookOrchestrator::new(
+                config,
+                loop_id.clone(),
+                false,
+                self.repo_root.clone(),
+                self.repo_root.clone(),
+            )
+        });
+        let outcome = match self.run_merge_hooks(
+            hooks.as_mut(),
+            HookPhaseEvent::PreMerge,
+            &base_branch,
+            None,
+        ) {
+            Some(reason) => MergeOutcome::Failed { reason },
+            None => self.merge_loop(loop_id, &base_branch),
+        };
+
+        match outcome.review_reason() {
+            None => {
+                if let MergeOutcome::Merged { commit } = &outcome {
+                    queue.mark_merged(loop_id, commit)?;
+                }
+                self.remove_loop_worktree(loop_id);
+                info!(loop_id = %loop_id, "{}", outcome.summary());
+            }
+            Some(reason) => {
+                queue.mark_needs_review(loop_id, &reason)?;
+                warn!(loop_id = %loop_id, "{}", outcome.summary());
+            }
+        }
+
+        let halted = self.run_merge_hooks(
+            hooks.as_mut(),
+            HookPhaseEvent::PostMerge,
+            &base_branch,
+            Some(&outcome),
+        );
+        if let Some(reason) = &halted {
+            error!(loop_id = %loop_id, reason = %reason, "Merge hook stopped the executor");
+        }
+
+        Ok(Some(MergeReport {
+            loop_id: loop_id.clone(),
+            base_branch,
+            outcome,
+            halted,
+        }))
+    }
+
+    /// Runs the hooks for a merge phase-event through the entry's orchestrator.
+    ///
+    /// Returns the reason when a hook blocks, or when the executor was stopped
+    /// while a hook had it suspended; `warn` failures are logged and the
+    /// remaining hooks still run.
+    fn run_merge_hooks(
+        &self,
+        hooks: Option<&mut HookOrchestrator>,
+        phase_event: HookPhaseEvent,
+        base_branch: &str,
+        outcome: Option<&MergeOutcome>,
+    ) -> Option<String> {
+        let hooks = hooks?;
+        let loop_id = hooks.loop_id().to_string();
+
+        let mut merge = serde_json::json!({
+            "loop_id": loop_id,
+            "branch": format!("ralph/{loop_id}"),
+            "base_branch": base_branch,
+            "strategy": self.config.strategy,
+        });
+        if let Some(outcome) = outcome {
+            merge["merged"] = outcome.is_merged().into();
+            merge["outcome"] = outcome.summary().into();
+            if let MergeOutcome::Merged { commit } = outcome {
+                merge["commit"] = commit.clone().into();
+            }
+        }
+
+        let payload_input = hooks.payload_input(
+            0,
+            0,
+            HookPayloadContextInput {
+                merge: Some(merge),
+                ..HookPayloadContextInput::default()
+            },
+        );
+        let outcomes = hooks.dispatch(phase_event, payload_input, &self.diagnostics);
+
+        match hooks.resolve_blocking(&outcomes) {
+            Ok(None) => None,
+            Ok(Some(reason)) => Some(format!(
+                "Merge executor {} while suspended at '{phase_event}'",
+                reason.as_str()
+            )),
+            Err(e) => Some(e.to_string()),
+        }
+    }
+
+    fn merge_loop(&self, loop_id: &str, base_branch: &str) -> MergeOutcome {
+        let branch = format!("ralph/{loop_id}");
+
+        let Ok(loop_tip) = rev_parse(&self.repo_root, &format!("refs/heads/{branch}")) else {
+            return MergeOutcome::Failed {
+                reason: format!("Loop branch {branch} not found"),
+            };
+        };
+        let Ok(base_tip) = rev_parse(&self.repo_root, &format!("refs/heads/{base_branch}")) else {
+            return MergeOutcome::Failed {
+                reason: format!("Base branch {base_branch} is not a local branch"),
+            };
+        };
+
+        // Nothing to integrate: the base already contains every loop commit.
+        if is_ancestor(&self.repo_root, &loop_tip, &base_tip) {
+            return MergeOutcome::Merged { commit: base_tip };
+        }
+
+        let scratch = self
+            .worktree_config
+            .worktree_path(&self.repo_root)
+            .join(format!("merge-{loop_id}"));
+        let outcome = self.merge_in_scratch(&scratch, loop_id, base_branch, &base_tip, &loop_tip);
+        self.remove_scratch(&scratch);
+        outcome
+    }
+
+    fn merge_in_scratch(
+        &self,
+        scratch: &Path,
+        loop_id: &str,
+        base_branch: &str,
+        base_tip: &str,
+        loop_tip: &str,
+    ) -> MergeOutcome {
+        self.remove_scratch(scratch);
+        if let Some(parent) = scratch.parent()
+            && let Err(e) = fs::create_dir_all(parent)
+        {
+            return MergeOutcome::Failed {
+                reason: format!("Failed to create scratch worktree directory: {e}"),
+            };
+        }
+
+        let start = match self.config.strategy {
+            MergeStrategy::Merge => base_tip,
+            MergeStrategy::Rebase => loop_tip,
+        };
+        let scratch_arg = scratch.to_string_lossy();
+        if let Err(e) = git(
+            &self.repo_root,
+            &["worktree", "add", "--detach", &scratch_arg, start],
+        ) {
+            return MergeOutcome::Failed {
+                reason: format!("Failed to create scratch worktree: {e}"),
+            };
+        }
+
+        let integrated = match self.config.strategy {
+            MergeStrategy::Merge => {
+                let summary = crate::merge_queue::smart_merge_summary(&self.repo_root, loop_id)
+                    .unwrap_or_else(|_| format!("merge {loop_id}"));
+                let message = format!("merge(ralph): {summary} (loop {loop_id})");
+                git(
+                    scratch,
+                    &[
+                        "merge",
+                        "--no-ff",
+                        "-m",
+                        &message,
+                        &format!("ralph/{loop_id}"),
+                    ],
+                )
+                .map_err(|output| (output, ["merge", "--abort"]))
+            }
+            MergeStrategy::Rebase => git(scratch, &["rebase", base_tip])
+                .map_err(|output| (output, ["rebase", "--abort"])),
+        };
+
+        if let Err((output, abort)) = integrated {
+            let files = conflicted_files(scratch);
+            let _ = git(scratch, &abort);
+            if files.is_empty() {
+                return MergeOutcome::Failed {
+                    reason: format!("git {} failed:\n{}", abort[0], tail(&output)),
+                };
+            }
+            return MergeOutcome::Conflict {
+                files,
+                output: tail(&output),
+            };
+        }
+
+        if let Some(failure) = self.run_verification(scratch) {
+            return failure;
+        }
+
+        let Ok(new_tip) = rev_parse(scratch, "HEAD") else {
+            return MergeOutcome::Failed {
+                reason: "Failed to resolve merged HEAD".to_string(),
+            };
+        };
+
+        match self.fast_forward_base(base_branch, base_tip, &new_tip) {
+            Ok(()) => MergeOutcome::Merged { commit: new_tip },
+            Err(reason) => MergeOutcome::Failed { reason },
+        }
+    }
+
+    /// Runs each verification command in the scratch worktree, stopping at the first failure.
+    fn run_verification(&self, scratch: &Path) -> Option<MergeOutcome> {
+        for (index, command) in self.verify_commands.iter().enumerate() {
+            debug!(command, scratch = %scratch.display(), "Running merge verification");
+
+            let request = HookRunRequest {
+                phase_event: MERGE_VERIFY_PHASE.to_string(),
+                hook_name: format!("verify-{}", index + 1),
+                command: vec!["bash".to_string(), "-c".to_string(), command.clone()],
+                workspace_root: scratch.to_path_buf(),
+                cwd: None,
+                env: HashMap::new(),
+                timeout_seconds: self.config.timeout_seconds,
+                max_output_bytes: self.config.max_output_bytes,
+                stdin_payload: serde_json::Value::Null,
+            };
+
+            let output = match self.executor.run(request) {
+                Ok(run) if !run.timed_out && run.exit_code == Some(0) => continue,
+                Ok(run) => {
+                    let combined = format!("{}\n{}", run.stdout.content, run.stderr.content);
+                    let status = if run.timed_out {
+                        format!("timed out after {}s", self.config.timeout_seconds)
+                    } else {
+                        match run.exit_code {
+                            Some(code) => format!("exit {code}"),
+                            None => "terminated".to_string(),
+                        }
+                    };
+                    format!("({status})\n{}", tail(&combined))
+                }
+                Err(e) => format!("(could not execute: {e})"),
+            };
+
+            return Some(MergeOutcome::VerificationFailed {
+                command: command.clone(),
+                output,
+            });
+        }
+        None
+    }
+
+    /// Moves `base_branch` from `base_tip` to `new_tip` without rewriting history.
+    ///
+    /// When the base is checked out in a worktree, the fast-forward happens
+    /// there so the working tree follows the branch.
+    fn fast_forward_base(
+        &self,
+        base_branch: &str,
+        base_tip: &str,
+        new_tip: &str,
+    ) -> Result<(), String> {
+        let checkout = list_worktrees(&self.repo_root)
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .find(|wt| wt.branch == base_branch);
+
+        let result = match checkout {
+            Some(wt) => git(&wt.path, &["merge", "--ff-only", new_tip]),
+            None => git(
+                &self.repo_root,
+                &[
+                    "update-ref",
+                    &format!("refs/heads/{base_branch}"),
+                    new_tip,
+                    base_tip,
+                ],
+            ),
+        };
+
+        result.map(|_| ()).map_err(|output| {
+            format!(
+                "Failed to fast-forward {base_branch} (did it move during verification?):\n{}",
+                tail(&output)
+            )
+        })
+    }
+
+    fn remove_scratch(&self, scratch: &Path) {
+        if scratch.exists() {
+            let _ = git(
+                &self.repo_root,
+                &["worktree", "remove", "--force", &scratch.to_string_lossy()],
+            );
+        }
+        let _ = git(&self.repo_root, &["worktree", "prune"]);
+    }
+
+    fn remove_loop_worktree(&self, loop_id: &str) {
+        let path = self
+            .worktree_config
+            .worktree_path(&self.repo_root)
+            .join(loop_id);
+        if path.exists()
+            && let Err(e) = remove_worktree(&self.repo_root, &path)
+        {
+            warn!(loop_id = %loop_id, error = %e, "Failed to remove merged loop worktree");
+        }
+    }
+}
+
+/// Runs git in `dir`, returning stdout on success and combined output on failure.
+fn git(dir: &Path, args: &[&str]) -> Result<String, String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .map_err(|e| e.to_string())?;
+
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    } else {
+        Err(format!(
+            "{}{}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        ))
+    }
+}
+
+fn rev_parse(dir: &Path, reference: &str) -> Result<String, String> {
+    git(
+        dir,
+        &["rev-parse", "--verify", &format!("{reference}^{{commit}}")],
+    )
+}
+
+fn is_ancestor(dir: &Path, ancestor: &str, descendant: &str) -> bool {
+    git(dir, &["merge-base", "--is-ancestor", ancestor, descendant]).is_ok()
+}
+
+fn conflicted_files(dir: &Path) -> Vec<String> {
+    git(dir, &["diff", "--name-only", "--diff-filter=U"])
+        .map(|out| out.lines().map(str::to_string).collect())
+        .unwrap_or_default()
+}
+
+fn tail(output: &str) -> String {
+    let lines: Vec<&str> = output.trim_end().lines().collect();
+    let start = lines.len().saturating_sub(FAILURE_OUTPUT_TAIL_LINES);
+    lines[start..].join("\n")
+}
+
+fn short_sha(commit: &str) -> &str {
+    &commit[..commit.len().min(7)]
+}
+
+fn first_line(text: &str) -> &str {
+    text.lines().next().unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn run_git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .expect("git");
+        assert!(status.status.success(), "git {args:?} failed: {status:?}");
+    }
+
+    fn commit_file(dir: &Path, name: &str, content: &str, message: &str) {
+        fs::write(dir.join(name), content).unwrap();
+        run_git(dir, &["add", name]);
+        run_git(dir, &["commit", "-q", "-m", message]);
+    }
+
+    /// Repo on `main` with a `ralph/<id>` branch one commit ahead, queued for merge.
+    fn setup_repo(loop_id: &str, loop_file: (&str, &str)) -> TempDir {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        run_git(root, &["init", "-q", "-b", "main"]);
+        run_git(root, &["config", "user.email", "test@test.local"]);
+        run_git(root, &["config", "user.name", "Test"]);
+        fs::write(root.join(".gitignore"), ".ralph/\n.worktrees/\n").unwrap();
+        commit_file(root, "README.md", "# Test\n", "Initial commit");
+
+        run_git(root, &["branch", &format!("ralph/{loop_id}")]);
+        run_git(root, &["checkout", "-q", &format!("ralph/{loop_id}")]);
+        commit_file(root, loop_file.0, loop_file.1, "feat: loop work");
+        run_git(root, &["checkout", "-q", "main"]);
+
+        MergeQueue::new(root)
+            .enqueue_with_base(loop_id, "prompt", Some("main"), None)
+            .unwrap();
+        temp
+    }
+
+    fn executor(root: &Path, strategy: MergeStrategy, verify: &[&str]) -> MergeExecutor {
+        let config = MergeConfig {
+            strategy,
+            ..MergeConfig::default()
+        };
+        MergeExecutor::new(
+            root,
+            config,
+            verify.iter().map(|c| (*c).to_string()).collect(),
+        )
+    }
+
+    #[test]
+    fn test_merges_and_fast_forwards_base() {
+        let temp = setup_repo("loop-a", ("feature.txt", "feature\n"));
+        let root = temp.path();
+
+        let reports = executor(root, MergeStrategy::Merge, &["test -f feature.txt"])
+            .process_all()
+            .unwrap();
+
+        assert_eq!(reports.len(), 1);
+        let MergeOutcome::Merged { commit } = &reports[0].outcome else {
+            panic!("expected merge, got {:?}", reports[0].outcome);
+        };
+        assert_eq!(&rev_parse(root, "refs/heads/main").unwrap(), commit);
+        // main is checked out, so the working tree follows the fast-forward.
+        assert!(root.join("feature.txt").exists());
+        let subject = git(root, &["log", "-1", "--format=%s"]).unwrap();
+        assert_eq!(subject, "merge(ralph): feat: loop work (loop loop-a)");
+
+        let entry = MergeQueue::new(root).get_entry("loop-a").unwrap().unwrap();
+        assert_eq!(entry.state, MergeState::Merged);
+        assert_eq!(entry.merge_commit.as_deref(), Some(commit.as_str()));
+        assert!(!root.join(".worktrees/merge-loop-a").exists());
+    }
+
+    #[test]
+    fn test_rebase_strategy_keeps_history_linear() {
+        let temp = setup_repo("loop-b", ("feature.txt", "feature\n"));
+        let root = temp.path();
+        commit_file(root, "other.txt", "other\n", "chore: move main");
+        run_git(root, &["checkout", "-q", "--detach"]);
+
+        let reports = executor(root, MergeStrategy::Rebase, &[])
+            .process_all()
+            .unwrap();
+
+        assert!(reports[0].outcome.is_merged(), "{:?}", reports[0].outcome);
+        let parents = git(root, &["log", "-1", "--format=%P", "main"]).unwrap();
+        assert_eq!(parents.split_whitespace().count(), 1);
+        let subjects = git(root, &["log", "--format=%s", "main"]).unwrap();
+        assert_eq!(
+            subjects.lines().collect::<Vec<_>>(),
+            ["feat: loop work", "chore: move main", "Initial commit"]
+        );
+    }
+
+    #[test]
+    fn test_failed_verification_marks_needs_review_and_keeps_base() {
+        let temp = setup_repo("loop-c", ("feature.txt", "feature\n"));
+        let root = temp.path();
+        let before = rev_parse(root, "refs/heads/main").unwrap();
+
+        let reports = executor(
+            root,
+            MergeStrategy::Merge,
+            &["echo 'boom: 1 failed'; exit 3"],
+        )
+        .process_all()
+        .unwrap();
+
+        assert!(matches!(
+            reports[0].outcome,
+            MergeOutcome::VerificationFailed { .. }
+        ));
+        assert_eq!(rev_parse(root, "refs/heads/main").unwrap(), before);
+
+        let entry = MergeQueue::new(root).get_entry("loop-c").unwrap().unwrap();
+        assert_eq!(entry.state, MergeState::NeedsReview);
+        let reason = entry.failure_reason.unwrap();
+        assert!(reason.contains("exit 3"), "{reason}");
+        assert!(reason.contains("boom: 1 failed"), "{reason}");
+        assert!(!root.join(".worktrees/merge-loop-c").exists());
+    }
+
+    #[test]
+    fn test_conflict_marks_needs_review_with_files() {
+        let temp = setup_repo("loop-d", ("README.md", "# Loop\n"));
+        let root = temp.path();
+        commit_file(root, "README.md", "# Main\n", "docs: main readme");
+
+        let reports = executor(root, MergeStrategy::Merge, &[])
+            .process_all()
+            .unwrap();
+
+        let MergeOutcome::Conflict { files, .. } = &reports[0].outcome else {
+            panic!("expected conflict, got {:?}", reports[0].outcome);
+        };
+        assert_eq!(files, &["README.md".to_string()]);
+        assert!(reports[0].outcome.is_conflict());
+
+        let entry = MergeQueue::new(root).get_entry("loop-d").unwrap().unwrap();
+        assert_eq!(entry.state, MergeState::NeedsReview);
+        assert!(entry.failure_reason.unwrap().contains("README.md"));
+        assert_eq!(
+            fs::read_to_string(root.join("README.md")).unwrap(),
+            "# Main\n"
+        );
+    }
+
+    #[test]
+    fn test_process_loop_retries_needs_review_entry_only() {
+        let temp = setup_repo("loop-r", ("feature.txt", "feature\n"));
+        let root = temp.path();
+        let queue = MergeQueue::new(root);
+        queue.mark_merging("loop-r", 1).unwrap();
+        queue.mark_needs_review("loop-r", "flaky check").unwrap();
+        queue.enqueue("loop-other", "prompt").unwrap();
+
+        let report = executor(root, MergeStrategy::Merge, &[])
+            .process_loop("loop-r")
+            .unwrap()
+            .expect("entry processed");
+
+        assert!(report.outcome.is_merged(), "{:?}", report.outcome);
+        assert!(root.join("feature.txt").exists());
+        let entry = queue.get_entry("loop-r").unwrap().unwrap();
+        assert_eq!(entry.state, MergeState::Merged);
+        let other = queue.get_entry("loop-other").unwrap().unwrap();
+        assert_eq!(other.state, MergeState::Queued);
+    }
+
+    #[test]
+    fn test_not_a_repo_leaves_queue_untouched() {
+        let temp = TempDir::new().unwrap();
+        let queue = MergeQueue::new(temp.path());
+        queue.enqueue("loop-e", "prompt").unwrap();
+
+        let result = executor(temp.path(), MergeStrategy::Merge, &[]).process_all();
+
+        assert!(matches!(result, Err(MergeExecutorError::NotARepo(_))));
+        let entry = queue.get_entry("loop-e").unwrap().unwrap();
+        assert_eq!(entry.state, MergeState::Queued);
+    }
+
+    fn merge_hooks(events: &str) -> HooksConfig {
+        let yaml = format!("hooks:\n  enabled: true\n  events:\n{events}");
+        let config = RalphConfig::parse_yaml(&yaml).unwrap();
+        config.validate().unwrap();
+        config.hooks
+    }
+
+    #[test]
+    fn test_blocking_pre_merge_hook_parks_entry_for_review() {
+        let temp = setup_repo("loop-f", ("feature.txt", "feature\n"));
+        let root = temp.path();
+        let before = rev_parse(root, "refs/heads/main").unwrap();
+
+        let hooks = merge_hooks(
+            r#"    pre.merge:
+      - name: freeze-window
+        command: ["sh", "-c", "exit 4"]
+        on_error: block
+"#,
+        );
+        let reports = executor(root, MergeStrategy::Merge, &[])
+            .with_hooks(hooks)
+            .process_all()
+            .unwrap();
+
+        let MergeOutcome::Failed { reason } = &reports[0].outcome else {
+            panic!("expected failure, got {:?}", reports[0].outcome);
+        };
+        assert!(reason.contains("'freeze-window' blocked"), "{reason}");
+        assert!(reason.contains("pre.merge"), "{reason}");
+        assert_eq!(rev_parse(root, "refs/heads/main").unwrap(), before);
+
+        let entry = MergeQueue::new(root).get_entry("loop-f").unwrap().unwrap();
+        assert_eq!(entry.state, MergeState::NeedsReview);
+    }
+
+    #[test]
+    fn test_blocking_post_merge_hook_halts_queue_after_merge() {
+        let temp = setup_repo("loop-g", ("feature.txt", "feature\n"));
+        let root = temp.path();
+        run_git(root, &["branch", "ralph/loop-h"]);
+        run_git(root, &["checkout", "-q", "ralph/loop-h"]);
+        commit_file(root, "other.txt", "other\n", "feat: other loop");
+        run_git(root, &["checkout", "-q", "main"]);
+        let queue = MergeQueue::new(root);
+        queue
+            .enqueue_with_base("loop-h", "prompt", Some("main"), None)
+            .unwrap();
+
+        let hooks = merge_hooks(
+            r#"    post.merge:
+      - name: audit
+        command: ["sh", "-c", "cat > .ralph/post-merge.json; exit 1"]
+        on_error: block
+"#,
+        );
+        let reports = executor(root, MergeStrategy::Merge, &[])
+            .with_hooks(hooks)
+            .process_all()
+            .unwrap();
+
+        assert_eq!(reports.len(), 1);
+        assert!(reports[0].outcome.is_merged(), "{:?}", reports[0].outcome);
+        let halted = reports[0].halted.as_deref().expect("queue should halt");
+        assert!(halted.contains("post.merge"), "{halted}");
+        assert_eq!(
+            queue.get_entry("loop-g").unwrap().unwrap().state,
+            MergeState::Merged
+        );
+        assert_eq!(
+            queue.get_entry("loop-h").unwrap().unwrap().state,
+            MergeState::Queued
+        );
+
+        let payload: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(root.join(".ralph/post-merge.json")).unwrap())
+                .unwrap();
+        assert_eq!(payload["phase_event"], "post.merge");
+        assert_eq!(payload["context"]["merge"]["loop_id"], "loop-g");
+        assert_eq!(payload["context"]["merge"]["base_branch"], "main");
+        assert_eq!(payload["context"]["merge"]["merged"], true);
+        assert_eq!(payload["context"]["merge"]["strategy"], "merge");
+    }
+
+    #[test]
+    fn test_pre_merge_metadata_reaches_post_merge_payload() {
+        let temp = setup_repo("loop-i", ("feature.txt", "feature\n"));
+        let root = temp.path();
+
+        let hooks = merge_hooks(
+            r#"    pre.merge:
+      - name: window
+        command: ["sh", "-c", "echo '{\"metadata\":{\"slot\":\"night\"}}'"]
+        on_error: block
+        mutate:
+          enabled: true
+    post.merge:
+      - name: audit
+        command: ["sh", "-c", "cat > .ralph/post-merge.json"]
+        on_error: warn
+"#,
+        );
+        let reports = executor(root, MergeStrategy::Merge, &[])
+            .with_hooks(hooks)
+            .process_all()
+            .unwrap();
+        assert!(reports[0].outcome.is_merged(), "{:?}", reports[0].outcome);
+
+        let payload: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(root.join(".ralph/post-merge.json")).unwrap())
+                .unwrap();
+        assert_eq!(
+            payload["metadata"]["accumulated"]["hook_metadata"]["window"]["slot"],
+            "night"
+        );
+    }
+
+    #[test]
+    fn test_suspending_pre_merge_hook_waits_for_resume() {
+        let temp = setup_repo("loop-j", ("feature.txt", "feature\n"));
+        let root = temp.path().to_path_buf();
+
+        let hooks = merge_hooks(
+            r#"    pre.merge:
+      - name: approval
+        command: ["sh", "-c", "exit 2"]
+        on_error: suspend
+"#,
+        );
+
+        let store = crate::hooks::SuspendStateStore::new(&root);
+        let resumer = {
+            let store = store.clone();
+            std::thread::spawn(move || {
+                let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+                while std::time::Instant::now() < deadline {
+                    if let Ok(Some(state)) = store.read_suspend_state() {
+                        store.write_resume_requested().unwrap();
+                        return Some(state);
+                    }
+                    std::thread::sleep(std::time::Duration::from_millis(20));
+                }
+                store.write_resume_requested().unwrap();
+                None
+            })
+        };
+
+        let reports = executor(&root, MergeStrategy::Merge, &[])
+            .with_hooks(hooks)
+            .process_all()
+            .unwrap();
+
+        let state = resumer.join().unwrap().expect("suspend-state written");
+        assert_eq!(state.loop_id, "loop-j");
+        assert_eq!(state.phase_event, HookPhaseEvent::PreMerge);
+        assert_eq!(state.hook_name, "approval");
+        assert!(reports[0].outcome.is_merged(), "{:?}", reports[0].outcome);
+        assert!(store.read_suspend_state().unwrap().is_none());
+    }
+}
@@ -37,7 +37,9 @@
 //! ```
 
 use crate::loop_lock::LoopLock;
+use crate::loop_registry::LoopRegistry;
 use crate::text::truncate_with_ellipsis;
+use crate::worktree::default_base_branch;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::fs::{self, File, OpenOptions};
@@ -66,6 +68,24 @@ pub enum MergeEventType {
     Queued {
         /// The prompt that was executed in this loop.
         prompt: String,
+
+        /// Branch the loop merges into.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        base_branch: Option<String>,
+
+        /// Commit of the base branch the loop's worktree was created from.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        base_commit: Option<String>,
+    },
+
+    /// Loop was retargeted to merge into another branch.
+    Retargeted {
+        /// The new base branch.
+        base_branch: String,
+
+        /// Commit of the new base branch at retarget time.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        base_commit: Option<String>,
     },
 
     /// Merge operation has started.
@@ -171,6 +191,12 @@ pub struct MergeEntry {
 
     /// Discard reason if discarded.
     pub discard_reason: Option<String>,
+
+    /// Branch the loop merges into (None for entries queued before this was recorded).
+    pub base_branch: Option<String>,
+
+    /// Commit of the base branch the loop's worktree was created from.
+    pub base_commit: Option<String>,
 }
 
 /// Errors that can occur during merge queue operations.
@@ -224,11 +250,69 @@ impl MergeQueue {
     /// * `loop_id` - The loop identifier
     /// * `prompt` - The prompt that was executed
     pub fn enqueue(&self, loop_id: &str, prompt: &str) -> Result<(), MergeQueueError> {
+        self.enqueue_with_base(loop_id, prompt, None, None)
+    }
+
+    /// Enqueues a completed loop for merging into a recorded base branch.
+    ///
+    /// # Arguments
+    ///
+    /// * `loop_id` - The loop identifier
+    /// * `prompt` - The prompt that was executed
+    /// * `base_branch` - Branch the loop merges into
+    /// * `base_commit` - Commit of the base branch the worktree was created from
+    pub fn enqueue_with_base(
+        &self,
+        loop_id: &str,
+        prompt: &str,
+        base_branch: Option<&str>,
+        base_commit: Option<&str>,
+    ) -> Result<(), MergeQueueError> {
         let event = MergeEvent {
             ts: Utc::now(),
             loop_id: loop_id.to_string(),
             event: MergeEventType::Queued {
                 prompt: prompt.to_string(),
+                base_branch: base_branch.map(String::from),
+                base_commit: base_commit.map(String::from),
+            },
+        };
+        self.append_event(&event)
+    }
+
+    /// Retargets a loop to merge into another branch.
+    ///
+    /// # Arguments
+    ///
+    /// * `loop_id` - The loop identifier
+    /// * `base_branch` - The new base branch
+    /// * `base_commit` - Commit of the new base branch
+    pub fn retarget(
+        &self,
+        loop_id: &str,
+        base_branch: &str,
+        base_commit: Option<&str>,
+    ) -> Result<(), MergeQueueError> {
+        // Can retarget until the loop is merged or discarded
+        let entry = self.get_entry(loop_id)?;
+        match entry {
+            Some(e) if !e.state.is_terminal() => {}
+            Some(e) => {
+                return Err(MergeQueueError::InvalidTransition(
+                    loop_id.to_string(),
+                    e.state,
+                    e.state,
+                ));
+            }
+            None => return Err(MergeQueueError::NotFound(loop_id.to_string())),
+        }
+
+        let event = MergeEvent {
+            ts: Utc::now(),
+            loop_id: loop_id.to_string(),
+            event: MergeEventType::Retargeted {
+                base_branch: base_branch.to_string(),
+                base_commit: base_commit.map(String::from),
             },
         };
         self.append_event(&event)
@@ -429,13 +513,28 @@ impl MergeQueue {
                     merge_commit: None,
                     failure_reason: None,
                     discard_reason: None,
+                    base_branch: None,
+                    base_commit: None,
                 });
 
             match &event.event {
-                MergeEventType::Queued { prompt } => {
+                MergeEventType::Queued {
+                    prompt,
+                    base_branch,
+                    base_commit,
+                } => {
                     entry.prompt = prompt.clone();
                     entry.state = MergeState::Queued;
                     entry.queued_at = event.ts;
+                    entry.base_branch = base_branch.clone();
+                    entry.base_commit = base_commit.clone();
+                }
+                MergeEventType::Retargeted {
+                    base_branch,
+                    base_commit,
+                } => {
+                    entry.base_branch = Some(base_branch.clone());
+                    entry.base_commit = base_commit.clone();
                 }
                 MergeEventType::Merging { pid } => {
                     entry.state = MergeState::Merging;
@@ -612,6 +711,30 @@ fn is_pid_alive(pid: u32) -> bool {
     }
 }
 
+/// Returns the branch a loop merges into.
+///
+/// Uses the base recorded in the merge queue, then the one recorded in the
+/// loop registry, and falls back to the repository's default branch for
+/// loops created before bases were recorded.
+pub fn loop_base_branch(workspace: &Path, loop_id: &str) -> String {
+    let queued = MergeQueue::new(workspace)
+        .get_entry(loop_id)
+        .ok()
+        .flatten()
+        .and_then(|entry| entry.base_branch);
+    let registered = || {
+        LoopRegistry::new(workspace)
+            .get(loop_id)
+            .ok()
+            .flatten()
+            .and_then(|entry| entry.base_branch)
+    };
+
+    queued
+        .or_else(registered)
+        .unwrap_or_else(|| default_base_branch(workspace))
+}
+
 /// Generate a smart merge summary from worktree commits.
 ///
 /// Reads the commit history and generates a concise summary suitable for
@@ -619,6 +742,7 @@ fn is_pid_alive(pid: u32) -> bool {
 /// with the loop ID prefix).
 pub fn smart_merge_summary(workspace: &Path, loop_id: &str) -> Result<String, MergeQueueError> {
     let branch_name = format!("ralph/{}", loop_id);
+    let base_branch = loop_base_branch(workspace, loop_id);
 
     // Get commit messages from the branch
     let output = Command::new("git")
@@ -626,7 +750,7 @@ pub fn smart_merge_summary(workspace: &Path, loop_id: &str) -> Result<String, Me
             "log",
             "--oneline",
             "--no-walk=unsorted",
-            &format!("main..{}", branch_name),
+            &format!("{}..{}", base_branch, branch_name),
         ])
         .current_dir(workspace)
         .output()?;
@@ -676,10 +800,11 @@ pub fn merge_needs_steering(
     loop_id: &str,
 ) -> Result<SteeringDecision, MergeQueueError> {
     let branch_name = format!("ralph/{}", loop_id);
+    let base_branch = loop_base_branch(workspace, loop_id);
 
     // Check for potential conflicts by doing a dry-run merge
     let output = Command::new("git")
-        .args(["merge-tree", "--write-tree", "main", &branch_name])
+        .args(["merge-tree", "--write-tree", &base_branch, &branch_name])
         .current_dir(workspace)
         .output()?;
 
@@ -690,7 +815,7 @@ pub fn merge_needs_steering(
     if has_conflicts {
         // Also get list of conflicting files
         let diff_output = Command::new("git")
-            .args(["diff", "--name-only", "main", &branch_name])
+            .args(["diff", "--name-only", &base_branch, &branch_name])
             .current_dir(workspace)
             .output()?;
 
@@ -708,7 +833,7 @@ pub fn merge_needs_steering(
             reason,
             options: vec![
                 MergeOption {
-                    label: "Use ours (main)".to_string(),
+                    label: format!("Use ours ({base_branch})"),
                 },
                 MergeOption {
                     label: "Use theirs (branch)".to_string(),
@@ -732,10 +857,15 @@ pub fn merge_needs_steering(
 /// Describes what was merged including commit count and key changes.
 pub fn merge_execution_summary(workspace: &Path, loop_id: &str) -> Result<String, MergeQueueError> {
     let branch_name = format!("ralph/{}", loop_id);
+    let base_branch = loop_base_branch(workspace, loop_id);
 
     // Get commit count
     let count_output = Command::new("git")
-        .args(["rev-list", "--count", &format!("main..{}", branch_name)])
+        .args([
+            "rev-list",
+            "--count",
+            &format!("{}..{}", base_branch, branch_name),
+        ])
         .current_dir(workspace)
         .output()?;
 
@@ -746,7 +876,7 @@ pub fn merge_execution_summary(workspace: &Path, loop_id: &str) -> Result<String
 
     // Get file count
     let files_output = Command::new("git")
-        .args(["diff", "--name-only", "main", &branch_name])
+        .args(["diff", "--name-only", &base_branch, &branch_name])
         .current_dir(workspace)
         .output()?;
 
@@ -795,6 +925,38 @@ mod tests {
         assert_eq!(entries[0].state, MergeState::Queued);
     }
 
+    #[test]
+    fn test_enqueue_with_base_and_retarget() {
+        let temp_dir = TempDir::new().unwrap();
+        let queue = MergeQueue::new(temp_dir.path());
+
+        queue
+            .enqueue_with_base(
+                "loop-123",
+                "implement auth",
+                Some("develop"),
+                Some("abc123"),
+            )
+            .unwrap();
+        let entry = queue.get_entry("loop-123").unwrap().unwrap();
+        assert_eq!(entry.base_branch.as_deref(), Some("develop"));
+        assert_eq!(entry.base_commit.as_deref(), Some("abc123"));
+        assert_eq!(loop_base_branch(temp_dir.path(), "loop-123"), "develop");
+
+        queue.retarget("loop-123", "release/2.0", None).unwrap();
+        let entry = queue.get_entry("loop-123").unwrap().unwrap();
+        assert_eq!(entry.state, MergeState::Queued);
+        assert_eq!(entry.base_branch.as_deref(), Some("release/2.0"));
+        assert!(entry.base_commit.is_none());
+
+        queue.mark_merging("loop-123", 12345).unwrap();
+        queue.mark_merged("loop-123", "def456").unwrap();
+        assert!(matches!(
+            queue.retarget("loop-123", "main", None),
+            Err(MergeQueueError::InvalidTransition(..))
+        ));
+    }
+
     #[test]
     fn test_full_merge_lifecycle() {
         let temp_dir = TempDir::new().unwrap();
@@ -1017,15 +1179,19 @@ mod tests {
             loop_id: "loop-test".to_string(),
             event: MergeEventType::Queued {
                 prompt: "test prompt".to_string(),
+                base_branch: None,
+                base_commit: None,
             },
         };
 
         let json = serde_json::to_string(&event).unwrap();
+        // Entries without a recorded base keep the original format
+        assert!(!json.contains("base_branch"));
         let parsed: MergeEvent = serde_json::from_str(&json).unwrap();
 
         assert_eq!(parsed.loop_id, event.loop_id);
         match parsed.event {
-            MergeEventType::Queued { prompt } => assert_eq!(prompt, "test prompt"),
+            MergeEventType::Queued { prompt, .. } => assert_eq!(prompt, "test prompt"),
             _ => panic!("Wrong event type"),
         }
     }
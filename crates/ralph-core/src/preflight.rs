@@ -1,7 +1,7 @@
 //! Preflight checks for validating environment and configuration before running.
 
 use crate::config::ConfigWarning;
-use crate::{RalphConfig, git_ops};
+use crate::{HatRegistry, RalphConfig, TopologyReport, git_ops};
 use async_trait::async_trait;
 use serde::Serialize;
 use std::collections::HashMap;
@@ -113,6 +113,7 @@ impl PreflightRunner {
                 Box::new(PathsExistCheck),
                 Box::new(ToolsInPathCheck::default()),
                 Box::new(SpecCompletenessCheck),
+                Box::new(HatTopologyCheck),
             ],
         }
     }
@@ -579,6 +580,50 @@ impl PreflightCheck for ToolsInPathCheck {
     }
 }
 
+struct HatTopologyCheck;
+
+#[async_trait]
+impl PreflightCheck for HatTopologyCheck {
+    fn name(&self) -> &'static str {
+        "topology"
+    }
+
+    async fn run(&self, config: &RalphConfig) -> CheckResult {
+        let registry = HatRegistry::from_config(config);
+        if registry.is_empty() {
+            return CheckResult::pass(self.name(), "No hats configured (skipping)");
+        }
+
+        let report = TopologyReport::analyze(config, &registry);
+        if report.issues.is_empty() {
+            return CheckResult::pass(
+                self.name(),
+                format!("Hat topology valid ({} hat(s))", report.hats),
+            );
+        }
+
+        let details = report
+            .issues
+            .iter()
+            .map(|issue| issue.message.as_str())
+            .collect::<Vec<_>>()
+            .join("\n");
+        if report.has_errors() {
+            CheckResult::fail(
+                self.name(),
+                format!("Hat topology invalid ({} error(s))", report.errors),
+                details,
+            )
+        } else {
+            CheckResult::warn(
+                self.name(),
+                format!("Hat topology valid ({} warning(s))", report.warnings),
+                details,
+            )
+        }
+    }
+}
+
 struct SpecCompletenessCheck;
 
 #[async_trait]
@@ -1110,6 +1155,7 @@ mod tests {
             on_error: Some(HookOnError::Block),
             suspend_mode: None,
             mutate: HookMutationConfig::default(),
+            topics: Vec::new(),
             extra: HashMap::new(),
         }
     }
@@ -1149,6 +1195,37 @@ mod tests {
         assert!(check_names.contains(&"hooks"));
     }
 
+    #[tokio::test]
+    async fn topology_check_fails_on_closed_cycle() {
+        let config: RalphConfig = serde_yaml::from_str(
+            r#"
+event_loop:
+  starting_event: "review.ready"
+hats:
+  builder:
+    name: "Builder"
+    triggers: ["review.rejected"]
+    publishes: ["review.ready"]
+  critic:
+    name: "Critic"
+    triggers: ["review.ready"]
+    publishes: ["review.rejected"]
+"#,
+        )
+        .unwrap();
+
+        let result = HatTopologyCheck.run(&config).await;
+
+        assert_eq!(result.status, CheckStatus::Fail);
+        assert_eq!(result.name, "topology");
+        assert!(
+            result
+                .message
+                .unwrap()
+                .contains("'Builder' -> 'Critic' hand off only to each other")
+        );
+    }
+
     #[tokio::test]
     async fn hooks_check_skips_when_hooks_are_disabled() {
         let config = RalphConfig::default();
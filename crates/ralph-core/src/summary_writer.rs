@@ -223,6 +223,7 @@ impl SummaryWriter {
             TerminationReason::RestartRequested => "Restarting by human request",
             TerminationReason::WorkspaceGone => "Failed: workspace directory removed",
             TerminationReason::Cancelled => "Cancelled gracefully (human rejection or timeout)",
+            TerminationReason::HookBlocked => "Failed: blocked by a lifecycle hook",
         }
     }
 
@@ -321,6 +322,14 @@ mod tests {
             completion_requested: false,
             hat_activation_counts: std::collections::HashMap::new(),
             exhausted_hats: std::collections::HashSet::new(),
+            hat_usage: std::collections::HashMap::new(),
+            budget_exhausted_hats: std::collections::HashSet::new(),
+            failover_positions: std::collections::HashMap::new(),
+            hat_failure_counts: std::collections::HashMap::new(),
+            last_activation_events: std::collections::HashMap::new(),
+            pending_retries: Vec::new(),
+            retry_backoff: None,
+            last_failure: None,
             last_checkin_at: None,
             last_active_hat_ids: Vec::new(),
             seen_topics: std::collections::HashSet::new(),
@@ -70,6 +70,30 @@ pub struct Task {
     /// Completion timestamp (ISO 8601), if closed
     #[serde(skip_serializing_if = "Option::is_none")]
     pub closed: Option<String>,
+
+    /// Last modification timestamp (ISO 8601), maintained by the API.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated: Option<String>,
+
+    /// Archive timestamp (ISO 8601); archived tasks are hidden from API listings.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub archived: Option<String>,
+
+    /// Where the task was created when not by an agent, e.g. `api` for the web API.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub origin: Option<String>,
+
+    /// Queue ticket assigned when the task was queued for background execution.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub queued: Option<String>,
+
+    /// Reason the last background execution failed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+
+    /// Cost in USD of the last background execution.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cost_usd: Option<f64>,
 }
 
 impl Task {
@@ -87,6 +111,12 @@ impl Task {
             created: chrono::Utc::now().to_rfc3339(),
             started: None,
             closed: None,
+            updated: None,
+            archived: None,
+            origin: None,
+            queued: None,
+            error: None,
+            cost_usd: None,
         }
     }
 
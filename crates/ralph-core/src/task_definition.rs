@@ -20,6 +20,7 @@
 //! assert!(task.verification.command.contains("Hello, World!"));
 //! ```
 
+use crate::config::HooksConfig;
 use serde::{Deserialize, Serialize};
 use std::path::Path;
 
@@ -166,6 +167,12 @@ pub struct TaskDefinition {
     /// Tags for filtering and categorization.
     #[serde(default)]
     pub tags: Vec<String>,
+
+    /// Lifecycle hooks to run for this task's loop.
+    ///
+    /// Uses the same schema as the `hooks` section of `ralph.yml`.
+    #[serde(default)]
+    pub hooks: HooksConfig,
 }
 
 fn default_complexity() -> String {
@@ -260,6 +267,7 @@ pub struct TaskDefinitionBuilder {
     timeout_seconds: u64,
     setup: TaskSetup,
     tags: Vec<String>,
+    hooks: HooksConfig,
 }
 
 impl TaskDefinitionBuilder {
@@ -281,6 +289,7 @@ impl TaskDefinitionBuilder {
             timeout_seconds: default_timeout_seconds(),
             setup: TaskSetup::default(),
             tags: Vec::new(),
+            hooks: HooksConfig::default(),
         }
     }
 
@@ -362,6 +371,12 @@ impl TaskDefinitionBuilder {
         self
     }
 
+    /// Sets the lifecycle hooks configuration.
+    pub fn hooks(mut self, hooks: HooksConfig) -> Self {
+        self.hooks = hooks;
+        self
+    }
+
     /// Builds the task definition.
     pub fn build(self) -> TaskDefinition {
         TaskDefinition {
@@ -376,6 +391,7 @@ impl TaskDefinitionBuilder {
             timeout_seconds: self.timeout_seconds,
             setup: self.setup,
             tags: self.tags,
+            hooks: self.hooks,
         }
     }
 }
@@ -493,6 +509,33 @@ mod tests {
         assert!(task.tags.is_empty());
     }
 
+    #[test]
+    fn test_task_definition_parses_hooks() {
+        let json = r#"{
+            "name": "hooked",
+            "prompt_file": "PROMPT.md",
+            "completion_promise": "DONE",
+            "verification": { "command": "true" },
+            "hooks": {
+                "enabled": true,
+                "events": {
+                    "pre.loop.start": [
+                        { "name": "env-guard", "command": ["./guard.sh"], "on_error": "block" }
+                    ]
+                }
+            }
+        }"#;
+
+        let task: TaskDefinition = serde_json::from_str(json).unwrap();
+        assert!(task.hooks.enabled);
+        let hooks = &task.hooks.events[&crate::hooks::HookPhaseEvent::PreLoopStart];
+        assert_eq!(hooks[0].name, "env-guard");
+
+        let default_task = TaskDefinition::builder("plain", "PROMPT.md", "DONE").build();
+        assert!(!default_task.hooks.enabled);
+        assert!(default_task.hooks.events.is_empty());
+    }
+
     #[test]
     fn test_task_validation_valid() {
         let task = TaskDefinition::builder("valid-task", "prompt.md", "DONE")
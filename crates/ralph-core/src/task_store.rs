@@ -255,6 +255,17 @@ impl TaskStore {
         None
     }
 
+    /// Removes a task by ID and returns it.
+    pub fn remove(&mut self, id: &str) -> Option<Task> {
+        let index = self.tasks.iter().position(|t| t.id == id)?;
+        Some(self.tasks.remove(index))
+    }
+
+    /// Removes every task.
+    pub fn clear(&mut self) {
+        self.tasks.clear();
+    }
+
     /// Ensures a task exists for a stable key, returning the existing or created task.
     ///
     /// If a task with the same key already exists, its non-lifecycle metadata is refreshed and
@@ -544,6 +555,23 @@ mod tests {
         assert_eq!(store1.all().len(), 2);
     }
 
+    #[test]
+    fn test_remove_and_clear() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("tasks.jsonl");
+
+        let mut store = TaskStore::load(&path).unwrap();
+        let id = store.add(Task::new("Task 1".to_string(), 1)).id.clone();
+        store.add(Task::new("Task 2".to_string(), 1));
+
+        assert_eq!(store.remove(&id).map(|t| t.id), Some(id.clone()));
+        assert!(store.remove(&id).is_none());
+        assert_eq!(store.all().len(), 1);
+
+        store.clear();
+        assert!(store.all().is_empty());
+    }
+
     #[test]
     fn test_with_exclusive_lock() {
         let tmp = TempDir::new().unwrap();
@@ -0,0 +1,750 @@
+//! Static analysis of hat topologies.
+//!
+//! Models a hat workflow as a graph: each hat consumes the topics matching its
+//! `triggers` and produces its `publishes`, its `default_publishes`, and the
+//! escalations the orchestrator publishes on its behalf (`<hat>.exhausted`,
+//! `<hat>.budget_exhausted`, `<hat>.failed`). Topics no hat subscribes to fall
+//! back to Ralph, who can always emit the completion promise.
+//!
+//! Ralph starts the workflow at `starting_event` when one is configured.
+//! Without one, Ralph coordinates directly and may delegate to any hat trigger.
+//!
+//! From that model [`TopologyReport::analyze`] reports:
+//! - hats that can never be reached from the entry event
+//! - published topics no hat subscribes to
+//! - cycles of hats that never hand control back to Ralph or complete
+//! - `required_events` that no hat can publish
+//! - paths from the entry event after which completion is unreachable
+
+use crate::config::RalphConfig;
+use crate::hat_registry::HatRegistry;
+use ralph_proto::Topic;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+
+/// How serious a topology issue is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IssueSeverity {
+    Warning,
+    Error,
+}
+
+/// Kind of topology issue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueKind {
+    /// No reachable topic matches any of the hat's triggers.
+    UnreachableHat,
+    /// A published topic has no hat subscribers and falls back to Ralph.
+    UnconsumedTopic,
+    /// Hats that only hand off to each other and never complete.
+    ClosedCycle,
+    /// A `required_events` topic that neither Ralph nor any hat publishes.
+    UnpublishableRequiredEvent,
+    /// A path from the entry event after which completion can't be reached.
+    CompletionUnreachable,
+}
+
+impl IssueKind {
+    /// Returns the severity reported for this kind of issue.
+    pub fn severity(self) -> IssueSeverity {
+        match self {
+            Self::UnreachableHat | Self::UnconsumedTopic => IssueSeverity::Warning,
+            Self::ClosedCycle | Self::UnpublishableRequiredEvent | Self::CompletionUnreachable => {
+                IssueSeverity::Error
+            }
+        }
+    }
+}
+
+/// A single finding from [`TopologyReport::analyze`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TopologyIssue {
+    pub kind: IssueKind,
+    pub severity: IssueSeverity,
+    pub message: String,
+    /// Hats involved, in path or cycle order where that matters.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub hats: Vec<String>,
+    /// Topic the issue is about, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub topic: Option<String>,
+}
+
+impl TopologyIssue {
+    fn new(kind: IssueKind, message: String, hats: Vec<String>, topic: Option<String>) -> Self {
+        Self {
+            kind,
+            severity: kind.severity(),
+            message,
+            hats,
+            topic,
+        }
+    }
+}
+
+/// Result of analyzing a hat topology.
+#[derive(Debug, Clone, Serialize)]
+pub struct TopologyReport {
+    /// Event Ralph publishes to start the hat workflow.
+    pub entry: String,
+    /// Number of hats analyzed.
+    pub hats: usize,
+    pub errors: usize,
+    pub warnings: usize,
+    pub issues: Vec<TopologyIssue>,
+}
+
+impl TopologyReport {
+    /// Analyzes the hats in `registry` against the event loop settings in `config`.
+    pub fn analyze(config: &RalphConfig, registry: &HatRegistry) -> Self {
+        let graph = TopologyGraph::new(config, registry);
+        let mut issues = Vec::new();
+
+        let reach = graph.reach();
+        for (index, hat) in graph.hats.iter().enumerate() {
+            if reach.via.contains_key(&index) || hat.fan_out_branch {
+                continue;
+            }
+            let triggers: Vec<&str> = hat.triggers.iter().map(Topic::as_str).collect();
+            issues.push(TopologyIssue::new(
+                IssueKind::UnreachableHat,
+                format!(
+                    "Hat '{}' is unreachable: nothing publishes its triggers ({})",
+                    hat.name,
+                    triggers.join(", ")
+                ),
+                vec![hat.name.clone()],
+                None,
+            ));
+        }
+
+        for (topic, publishers) in graph.unconsumed_topics() {
+            issues.push(TopologyIssue::new(
+                IssueKind::UnconsumedTopic,
+                format!(
+                    "Event '{}' published by '{}' has no hat subscribers",
+                    topic,
+                    publishers.join("', '")
+                ),
+                publishers,
+                Some(topic),
+            ));
+        }
+
+        for topic in &config.event_loop.required_events {
+            if !graph.is_publishable(topic) {
+                issues.push(TopologyIssue::new(
+                    IssueKind::UnpublishableRequiredEvent,
+                    format!("Required event '{topic}' is never published by any hat"),
+                    Vec::new(),
+                    Some(topic.clone()),
+                ));
+            }
+        }
+
+        let can_finish = graph.can_finish();
+        let completion = graph.completion;
+        for cycle in graph.closed_cycles(&reach, &can_finish) {
+            let names: Vec<String> = cycle.iter().map(|&i| graph.hats[i].name.clone()).collect();
+            issues.push(TopologyIssue::new(
+                IssueKind::ClosedCycle,
+                format!(
+                    "Hats {} hand off only to each other and never exit",
+                    names
+                        .iter()
+                        .map(|name| format!("'{name}'"))
+                        .collect::<Vec<_>>()
+                        .join(" -> ")
+                ),
+                names,
+                None,
+            ));
+
+            let first = cycle
+                .iter()
+                .copied()
+                .min_by_key(|i| reach.order[i])
+                .expect("cycles are non-empty");
+            let (steps, hats) = reach.path_to(&graph, first);
+            issues.push(TopologyIssue::new(
+                IssueKind::CompletionUnreachable,
+                format!(
+                    "'{}' is unreachable after {}",
+                    completion,
+                    steps.join(" -> ")
+                ),
+                hats,
+                Some(completion.to_string()),
+            ));
+        }
+
+        let errors = issues
+            .iter()
+            .filter(|issue| issue.severity == IssueSeverity::Error)
+            .count();
+
+        Self {
+            entry: graph.entry.clone(),
+            hats: graph.hats.len(),
+            errors,
+            warnings: issues.len() - errors,
+            issues,
+        }
+    }
+
+    /// Returns true when the analysis found at least one error.
+    pub fn has_errors(&self) -> bool {
+        self.errors > 0
+    }
+}
+
+/// A hat as seen by the analyzer.
+struct HatNode {
+    name: String,
+    triggers: Vec<Topic>,
+    /// Trigger patterns guarded by a payload condition.
+    conditional: Vec<String>,
+    /// Topics the hat may publish (`publishes` plus `default_publishes`).
+    publishes: Vec<String>,
+    /// Topics the orchestrator may publish in place of the hat.
+    escalations: Vec<String>,
+    /// Runs as a fan-out branch; its events are folded into the join event.
+    fan_out_branch: bool,
+}
+
+impl HatNode {
+    fn outputs(&self) -> impl Iterator<Item = &str> {
+        self.publishes
+            .iter()
+            .chain(&self.escalations)
+            .map(String::as_str)
+    }
+
+    fn consumes(&self, topic: &str) -> bool {
+        self.triggers
+            .iter()
+            .any(|trigger| trigger.matches_str(topic))
+    }
+
+    /// Returns true when the hat receives the topic whatever its payload.
+    fn always_consumes(&self, topic: &str) -> bool {
+        self.triggers.iter().any(|trigger| {
+            trigger.matches_str(topic) && !self.conditional.iter().any(|c| c == trigger.as_str())
+        })
+    }
+}
+
+struct TopologyGraph<'a> {
+    hats: Vec<HatNode>,
+    entry: String,
+    /// Topics Ralph publishes when it coordinates.
+    ralph_publishes: Vec<String>,
+    completion: &'a str,
+    cancellation: &'a str,
+}
+
+impl<'a> TopologyGraph<'a> {
+    fn new(config: &'a RalphConfig, registry: &HatRegistry) -> Self {
+        let fan_out_branches: HashSet<&str> = config
+            .hats
+            .values()
+            .filter_map(|hat| hat.fan_out.as_ref())
+            .flat_map(|fan_out| fan_out.hats.iter().map(String::as_str))
+            .collect();
+        let mut hats: Vec<HatNode> = registry
+            .all()
+            .map(|hat| {
+                let hat_config = registry.get_config(&hat.id);
+                let mut publishes: Vec<String> = hat
+                    .publishes
+                    .iter()
+                    .map(|t| t.as_str().to_string())
+                    .collect();
+                if let Some(default) = hat_config.and_then(|c| c.default_publishes.as_ref())
+                    && !publishes.contains(default)
+                {
+                    publishes.push(default.clone());
+                }
+
+                let mut escalations = Vec::new();
+                if let Some(hat_config) = hat_config {
+                    let id = hat.id.as_str();
+                    if hat_config.max_activations.is_some() {
+                        escalations.push(format!("{id}.exhausted"));
+                    }
+                    if hat_config.max_cost_usd.is_some() || hat_config.max_tokens.is_some() {
+                        escalations.push(format!("{id}.budget_exhausted"));
+                    }
+                    if hat_config.retry.is_some() {
+                        escalations.push(format!("{id}.failed"));
+                    }
+                }
+
+                HatNode {
+                    name: hat.name.clone(),
+                    triggers: hat.subscriptions.clone(),
+                    conditional: hat.conditions.keys().cloned().collect(),
+                    publishes,
+                    escalations,
+                    fan_out_branch: fan_out_branches.contains(hat.id.as_str()),
+                }
+            })
+            .collect();
+        hats.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let (entry, ralph_publishes) = match &config.event_loop.starting_event {
+            Some(start) => (start.clone(), vec![start.clone()]),
+            None => {
+                let mut topics = vec!["task.start".to_string()];
+                for hat in &hats {
+                    for trigger in &hat.triggers {
+                        if !topics.iter().any(|t| t == trigger.as_str()) {
+                            topics.push(trigger.as_str().to_string());
+                        }
+                    }
+                }
+                ("task.start".to_string(), topics)
+            }
+        };
+
+        Self {
+            hats,
+            entry,
+            ralph_publishes,
+            completion: &config.event_loop.completion_promise,
+            cancellation: &config.event_loop.cancellation_promise,
+        }
+    }
+
+    /// Returns true for topics that end the loop rather than route to a hat.
+    fn is_terminal(&self, topic: &str) -> bool {
+        topic == self.completion || (!self.cancellation.is_empty() && topic == self.cancellation)
+    }
+
+    fn subscribers(&self, topic: &str) -> impl Iterator<Item = usize> + '_ {
+        let topic = topic.to_string();
+        self.hats
+            .iter()
+            .enumerate()
+            .filter(move |(_, hat)| hat.consumes(&topic))
+            .map(|(index, _)| index)
+    }
+
+    /// Returns true when the hat can end the loop or hand control back to Ralph.
+    ///
+    /// A topic only guarded by payload conditions falls back to Ralph when none hold.
+    fn exits(&self, hat: &HatNode) -> bool {
+        let mut outputs = hat.outputs().peekable();
+        outputs.peek().is_none()
+            || outputs.any(|topic| {
+                self.is_terminal(topic) || !self.hats.iter().any(|h| h.always_consumes(topic))
+            })
+    }
+
+    fn successors(&self, index: usize) -> Vec<usize> {
+        let mut next: Vec<usize> = self.hats[index]
+            .outputs()
+            .filter(|topic| !self.is_terminal(topic))
+            .flat_map(|topic| self.subscribers(topic))
+            .collect();
+        next.sort_unstable();
+        next.dedup();
+        next
+    }
+
+    /// Breadth-first search from the topics Ralph publishes.
+    fn reach(&self) -> Reach {
+        let mut reach = Reach::default();
+        let mut queue: VecDeque<(String, Option<usize>)> = self
+            .ralph_publishes
+            .iter()
+            .map(|topic| (topic.clone(), None))
+            .collect();
+        let mut seen_topics: HashSet<String> = HashSet::new();
+
+        while let Some((topic, from)) = queue.pop_front() {
+            if !seen_topics.insert(topic.clone()) {
+                continue;
+            }
+            for index in self.subscribers(&topic) {
+                if reach.via.contains_key(&index) {
+                    continue;
+                }
+                reach.order.insert(index, reach.via.len());
+                reach.via.insert(index, (topic.clone(), from));
+                for output in self.hats[index].outputs() {
+                    if !self.is_terminal(output) {
+                        queue.push_back((output.to_string(), Some(index)));
+                    }
+                }
+            }
+        }
+        reach
+    }
+
+    /// Returns, per hat, whether some path from it ends the loop or reaches Ralph.
+    fn can_finish(&self) -> Vec<bool> {
+        let mut finish: Vec<bool> = self.hats.iter().map(|hat| self.exits(hat)).collect();
+        let successors: Vec<Vec<usize>> =
+            (0..self.hats.len()).map(|i| self.successors(i)).collect();
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for index in 0..self.hats.len() {
+                if !finish[index] && successors[index].iter().any(|&next| finish[next]) {
+                    finish[index] = true;
+                    changed = true;
+                }
+            }
+        }
+        finish
+    }
+
+    /// Groups reachable hats that can't finish into strongly connected cycles.
+    fn closed_cycles(&self, reach: &Reach, can_finish: &[bool]) -> Vec<Vec<usize>> {
+        let trapped: Vec<usize> = (0..self.hats.len())
+            .filter(|i| reach.via.contains_key(i) && !can_finish[*i])
+            .collect();
+        let descendants: HashMap<usize, HashSet<usize>> = trapped
+            .iter()
+            .map(|&start| {
+                let mut seen = HashSet::new();
+                let mut stack = self.successors(start);
+                while let Some(next) = stack.pop() {
+                    if seen.insert(next) {
+                        stack.extend(self.successors(next));
+                    }
+                }
+                (start, seen)
+            })
+            .collect();
+
+        let mut cycles: Vec<Vec<usize>> = Vec::new();
+        let mut assigned = HashSet::new();
+        for &start in &trapped {
+            if assigned.contains(&start) || !descendants[&start].contains(&start) {
+                continue;
+            }
+            let cycle: Vec<usize> = trapped
+                .iter()
+                .copied()
+                .filter(|other| {
+                    descendants[&start].contains(other) && descendants[other].contains(&start)
+                })
+                .collect();
+            assigned.extend(cycle.iter().copied());
+            cycles.push(cycle);
+        }
+        cycles
+    }
+
+    /// Published topics without subscribers, mapped to the hats that publish them.
+    fn unconsumed_topics(&self) -> BTreeMap<String, Vec<String>> {
+        let mut topics: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for hat in self.hats.iter().filter(|hat| !hat.fan_out_branch) {
+            for topic in &hat.publishes {
+                if self.is_terminal(topic) || self.subscribers(topic).next().is_some() {
+                    continue;
+                }
+                let publishers = topics.entry(topic.clone()).or_default();
+                if !publishers.contains(&hat.name) {
+                    publishers.push(hat.name.clone());
+                }
+            }
+        }
+        topics
+    }
+
+    fn is_publishable(&self, topic: &str) -> bool {
+        topic == self.entry
+            || self
+                .hats
+                .iter()
+                .any(|hat| hat.outputs().any(|t| t == topic))
+    }
+}
+
+/// How each reachable hat was first reached.
+#[derive(Default)]
+struct Reach {
+    /// Hat index -> (topic that triggered it, hat that published that topic).
+    via: HashMap<usize, (String, Option<usize>)>,
+    /// Hat index -> discovery order.
+    order: HashMap<usize, usize>,
+}
+
+impl Reach {
+    /// Returns the alternating topic/hat path from Ralph to `target`, and the hats on it.
+    fn path_to(&self, graph: &TopologyGraph<'_>, target: usize) -> (Vec<String>, Vec<String>) {
+        let mut steps = Vec::new();
+        let mut hats = Vec::new();
+        let mut current = Some(target);
+        while let Some(index) = current {
+            let (topic, from) = &self.via[&index];
+            hats.push(graph.hats[index].name.clone());
+            steps.push(graph.hats[index].name.clone());
+            steps.push(topic.clone());
+            current = *from;
+        }
+        steps.reverse();
+        hats.reverse();
+        (steps, hats)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(yaml: &str) -> RalphConfig {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    fn analyze(yaml: &str) -> TopologyReport {
+        let config = config(yaml);
+        let registry = HatRegistry::from_config(&config);
+        TopologyReport::analyze(&config, &registry)
+    }
+
+    fn kinds(report: &TopologyReport) -> Vec<IssueKind> {
+        report.issues.iter().map(|issue| issue.kind).collect()
+    }
+
+    #[test]
+    fn test_linear_workflow_has_no_issues() {
+        let report = analyze(
+            r#"
+event_loop:
+  starting_event: "build.start"
+  required_events: ["review.passed"]
+hats:
+  builder:
+    name: "Builder"
+    triggers: ["build.start", "review.rejected"]
+    publishes: ["review.ready"]
+  critic:
+    name: "Critic"
+    triggers: ["review.ready"]
+    publishes: ["review.passed", "review.rejected"]
+  finalizer:
+    name: "Finalizer"
+    triggers: ["review.passed"]
+    publishes: ["LOOP_COMPLETE"]
+"#,
+        );
+
+        assert!(report.issues.is_empty(), "{:?}", report.issues);
+        assert_eq!(report.entry, "build.start");
+        assert_eq!(report.hats, 3);
+    }
+
+    #[test]
+    fn test_reports_unreachable_hat_and_unconsumed_topic() {
+        let report = analyze(
+            r#"
+event_loop:
+  starting_event: "build.start"
+hats:
+  builder:
+    name: "Builder"
+    triggers: ["build.start"]
+    publishes: ["build.blocked", "LOOP_COMPLETE"]
+  docs:
+    name: "Docs"
+    triggers: ["docs.start"]
+    publishes: ["LOOP_COMPLETE"]
+"#,
+        );
+
+        assert_eq!(
+            kinds(&report),
+            vec![IssueKind::UnreachableHat, IssueKind::UnconsumedTopic]
+        );
+        assert_eq!(report.issues[0].hats, vec!["Docs"]);
+        assert_eq!(report.issues[1].topic.as_deref(), Some("build.blocked"));
+        assert_eq!(report.warnings, 2);
+        assert!(!report.has_errors());
+    }
+
+    #[test]
+    fn test_without_starting_event_ralph_can_delegate_to_any_trigger() {
+        let report = analyze(
+            r#"
+hats:
+  docs:
+    name: "Docs"
+    triggers: ["docs.start"]
+    publishes: ["LOOP_COMPLETE"]
+"#,
+        );
+
+        assert!(report.issues.is_empty(), "{:?}", report.issues);
+    }
+
+    #[test]
+    fn test_reports_closed_cycle_and_unreachable_completion() {
+        let report = analyze(
+            r#"
+event_loop:
+  starting_event: "build.start"
+hats:
+  planner:
+    name: "Planner"
+    triggers: ["build.start"]
+    publishes: ["build.task"]
+  builder:
+    name: "Builder"
+    triggers: ["build.task", "review.rejected"]
+    publishes: ["review.ready"]
+  critic:
+    name: "Critic"
+    triggers: ["review.ready"]
+    publishes: ["review.rejected"]
+"#,
+        );
+
+        assert_eq!(
+            kinds(&report),
+            vec![IssueKind::ClosedCycle, IssueKind::CompletionUnreachable]
+        );
+        assert_eq!(report.issues[0].hats, vec!["Builder", "Critic"]);
+        assert_eq!(
+            report.issues[1].message,
+            "'LOOP_COMPLETE' is unreachable after build.start -> Planner -> build.task -> Builder"
+        );
+        assert_eq!(report.errors, 2);
+    }
+
+    #[test]
+    fn test_max_activations_gives_a_cycle_an_exit() {
+        let report = analyze(
+            r#"
+event_loop:
+  starting_event: "review.ready"
+hats:
+  builder:
+    name: "Builder"
+    triggers: ["review.rejected"]
+    publishes: ["review.ready"]
+  critic:
+    name: "Critic"
+    triggers: ["review.ready"]
+    publishes: ["review.rejected"]
+    max_activations: 3
+"#,
+        );
+
+        assert!(!report.has_errors(), "{:?}", report.issues);
+    }
+
+    #[test]
+    fn test_conditional_trigger_gives_a_cycle_an_exit() {
+        let report = analyze(
+            r#"
+event_loop:
+  starting_event: "review.ready"
+hats:
+  builder:
+    name: "Builder"
+    triggers: ["review.done"]
+    trigger_conditions:
+      review.done: "$.verdict == reject"
+    publishes: ["review.ready"]
+  critic:
+    name: "Critic"
+    triggers: ["review.ready"]
+    publishes: ["review.done"]
+"#,
+        );
+
+        assert!(!report.has_errors(), "{:?}", report.issues);
+    }
+
+    #[test]
+    fn test_fan_out_branches_are_not_reported() {
+        let report = analyze(
+            r#"
+event_loop:
+  starting_event: "build.done"
+hats:
+  review_fan_out:
+    name: "Review Fan-out"
+    triggers: ["build.done"]
+    publishes: ["review.joined"]
+    fan_out:
+      hats: [security]
+      join: review.joined
+  security:
+    name: "Security"
+    publishes: ["review.security"]
+  summarizer:
+    name: "Summarizer"
+    triggers: ["review.joined"]
+    publishes: ["LOOP_COMPLETE"]
+"#,
+        );
+
+        assert!(report.issues.is_empty(), "{:?}", report.issues);
+    }
+
+    #[test]
+    fn test_reports_required_event_no_hat_publishes() {
+        let report = analyze(
+            r#"
+event_loop:
+  starting_event: "build.start"
+  required_events: ["build.start", "review.passed"]
+hats:
+  builder:
+    name: "Builder"
+    triggers: ["build.start"]
+    publishes: ["LOOP_COMPLETE"]
+"#,
+        );
+
+        assert_eq!(kinds(&report), vec![IssueKind::UnpublishableRequiredEvent]);
+        assert_eq!(report.issues[0].topic.as_deref(), Some("review.passed"));
+    }
+
+    #[test]
+    fn test_default_publishes_counts_as_published() {
+        let report = analyze(
+            r#"
+event_loop:
+  starting_event: "build.start"
+  required_events: ["build.done"]
+hats:
+  builder:
+    name: "Builder"
+    triggers: ["build.start"]
+    publishes: ["LOOP_COMPLETE"]
+    default_publishes: "build.done"
+"#,
+        );
+
+        assert_eq!(kinds(&report), vec![IssueKind::UnconsumedTopic]);
+    }
+
+    #[test]
+    fn test_report_serializes_kinds_in_snake_case() {
+        let report = analyze(
+            r#"
+event_loop:
+  starting_event: "build.start"
+  required_events: ["review.passed"]
+hats:
+  builder:
+    name: "Builder"
+    triggers: ["build.start"]
+    publishes: ["LOOP_COMPLETE"]
+"#,
+        );
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["issues"][0]["kind"], "unpublishable_required_event");
+        assert_eq!(json["issues"][0]["severity"], "error");
+        assert_eq!(json["errors"], 1);
+    }
+}
@@ -141,6 +141,21 @@ pub fn create_worktree(
     repo_root: impl AsRef<Path>,
     loop_id: &str,
     config: &WorktreeConfig,
+) -> Result<Worktree, WorktreeError> {
+    create_worktree_from(repo_root, loop_id, config, None)
+}
+
+/// Create a new worktree for a parallel Ralph loop at a given start point.
+///
+/// With `start_point` set, the branch is created from that ref (e.g. another
+/// loop's branch) and the primary workspace's uncommitted changes are not
+/// synced, since they belong to a different base. Without it this behaves
+/// like [`create_worktree`].
+pub fn create_worktree_from(
+    repo_root: impl AsRef<Path>,
+    loop_id: &str,
+    config: &WorktreeConfig,
+    start_point: Option<&str>,
 ) -> Result<Worktree, WorktreeError> {
     let repo_root = repo_root.as_ref();
 
@@ -166,10 +181,11 @@ pub fn create_worktree(
     fs::create_dir_all(&worktree_base)?;
 
     // Create worktree with new branch
-    // git worktree add -b <branch> <path>
+    // git worktree add -b <branch> <path> [<start-point>]
     let output = Command::new("git")
         .args(["worktree", "add", "-b", &branch_name])
         .arg(&worktree_path)
+        .args(start_point)
         .current_dir(repo_root)
         .output()?;
 
@@ -190,7 +206,11 @@ pub fn create_worktree(
     }
 
     // Sync untracked files and unstaged changes
-    let sync_stats = sync_working_directory_to_worktree(repo_root, &worktree_path, config)?;
+    let sync_stats = if start_point.is_none() {
+        sync_working_directory_to_worktree(repo_root, &worktree_path, config)?
+    } else {
+        SyncStats::default()
+    };
 
     if sync_stats.errors > 0 {
         tracing::warn!(
@@ -460,6 +480,60 @@ fn get_head_commit(worktree_path: &Path) -> Result<String, WorktreeError> {
     }
 }
 
+/// Returns the branch and commit a new worktree loop forks from.
+///
+/// The branch is the one checked out in `repo_root` (falling back to
+/// [`default_base_branch`] on a detached HEAD); the commit is its HEAD.
+pub fn current_base(repo_root: impl AsRef<Path>) -> (String, Option<String>) {
+    let repo_root = repo_root.as_ref();
+    let branch = get_worktree_branch(repo_root).unwrap_or_else(|| default_base_branch(repo_root));
+    (branch, get_head_commit(repo_root).ok())
+}
+
+/// Guesses the repository's default branch.
+///
+/// Used for loops that have no recorded base branch. Prefers the branch
+/// `origin/HEAD` points to, then `main`, then `master`.
+pub fn default_base_branch(repo_root: impl AsRef<Path>) -> String {
+    let repo_root = repo_root.as_ref();
+    let origin_head = Command::new("git")
+        .args(["symbolic-ref", "-q", "--short", "refs/remotes/origin/HEAD"])
+        .current_dir(repo_root)
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string());
+
+    if let Some(origin_head) = origin_head
+        && let Some(base) = origin_head.split('/').next_back()
+    {
+        let with_remote = format!("origin/{base}");
+        if git_ref_exists(repo_root, base) {
+            return base.to_string();
+        }
+        if git_ref_exists(repo_root, &with_remote) {
+            return with_remote;
+        }
+    }
+
+    for candidate in ["origin/main", "main", "origin/master", "master"] {
+        if git_ref_exists(repo_root, candidate) {
+            return candidate.to_string();
+        }
+    }
+
+    "main".to_string()
+}
+
+/// Returns true if `reference` resolves in the repository.
+fn git_ref_exists(repo_root: &Path, reference: &str) -> bool {
+    Command::new("git")
+        .args(["rev-parse", "--verify", "--quiet", reference])
+        .current_dir(repo_root)
+        .output()
+        .is_ok_and(|output| output.status.success())
+}
+
 /// Get the list of Ralph-specific worktrees (those with `ralph/` branches).
 pub fn list_ralph_worktrees(repo_root: impl AsRef<Path>) -> Result<Vec<Worktree>, WorktreeError> {
     let all = list_worktrees(repo_root)?;
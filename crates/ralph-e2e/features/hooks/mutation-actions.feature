@@ -0,0 +1,10 @@
+@hooks @mutation-actions
+Feature: Hook mutation actions
+  # Structured actions returned by mutation-enabled hooks
+  # Extends the AC-13..AC-15 mutation contract beyond metadata
+
+  @AC-19
+  Scenario: AC-19 Structured mutation actions
+    Given mutation is enabled for a pre.event.publish hook
+    When the hook returns reject, rewrite, publish, or guidance actions
+    Then actions are schema-validated, applied to the event or next prompt, and recorded in hook-run telemetry
@@ -651,7 +651,7 @@ fn dispatch_ac_evaluator(
         "AC-04" => evaluate_ac_04,
         "AC-05" => evaluate_ac_05,
         "AC-06" => evaluate_ac_06,
-        // AC-07..AC-18: Safeguards, dispositions, suspend/resume, mutation, telemetry
+        // AC-07..AC-19: Safeguards, dispositions, suspend/resume, mutation, telemetry
         "AC-07" => evaluate_ac_07,
         "AC-08" => evaluate_ac_08,
         "AC-09" => evaluate_ac_09,
@@ -664,6 +664,7 @@ fn dispatch_ac_evaluator(
         "AC-16" => evaluate_ac_16,
         "AC-17" => evaluate_ac_17,
         "AC-18" => evaluate_ac_18,
+        "AC-19" => evaluate_ac_19,
         _ => evaluate_unmapped_acceptance,
     }
 }
@@ -746,11 +747,11 @@ fn runtime_test_cases_for_ac(ac_id: &str) -> Vec<RuntimeTestCase> {
             },
         ],
         "AC-02" => vec![RuntimeTestCase {
-            package: "ralph-cli",
+            package: "ralph-core",
             filter: "test_dispatch_phase_event_hooks_routes_by_phase_and_preserves_order",
         }],
         "AC-03" => vec![RuntimeTestCase {
-            package: "ralph-cli",
+            package: "ralph-core",
             filter: "test_dispatch_phase_event_hooks_routes_by_phase_and_preserves_order",
         }],
         "AC-04" => vec![
@@ -759,7 +760,7 @@ fn runtime_test_cases_for_ac(ac_id: &str) -> Vec<RuntimeTestCase> {
                 filter: "resolve_phase_event_preserves_declaration_order",
             },
             RuntimeTestCase {
-                package: "ralph-cli",
+                package: "ralph-core",
                 filter: "test_dispatch_phase_event_hooks_routes_by_phase_and_preserves_order",
             },
         ],
@@ -776,56 +777,56 @@ fn runtime_test_cases_for_ac(ac_id: &str) -> Vec<RuntimeTestCase> {
             filter: "run_truncates_stdout_and_stderr_at_max_output_bytes",
         }],
         "AC-08" => vec![RuntimeTestCase {
-            package: "ralph-cli",
+            package: "ralph-core",
             filter: "test_loop_start_dispatch_warn_continues_and_block_aborts",
         }],
         "AC-09" => vec![RuntimeTestCase {
-            package: "ralph-cli",
+            package: "ralph-core",
             filter: "test_loop_start_dispatch_warn_continues_and_block_aborts",
         }],
         "AC-10" => vec![RuntimeTestCase {
-            package: "ralph-cli",
+            package: "ralph-core",
             filter: "test_iteration_start_suspend_waits_for_resume_and_clears_artifacts_before_continuing",
         }],
         "AC-11" => vec![RuntimeTestCase {
-            package: "ralph-cli",
+            package: "ralph-core",
             filter: "test_wait_for_resume_if_suspended_resumes_and_clears_suspend_artifacts",
         }],
         "AC-12" => vec![RuntimeTestCase {
-            package: "ralph-cli",
+            package: "ralph-core",
             filter: "test_wait_for_resume_if_suspended_is_noop_without_suspend_dispositions",
         }],
         "AC-13" => vec![RuntimeTestCase {
-            package: "ralph-cli",
+            package: "ralph-core",
             filter: "test_ac13_mutation_disabled_json_output_is_inert_for_accumulator_and_downstream_payloads",
         }],
         "AC-14" => vec![
             RuntimeTestCase {
-                package: "ralph-cli",
+                package: "ralph-core",
                 filter: "test_ac14_mutation_enabled_updates_only_namespaced_metadata_in_downstream_payloads",
             },
             RuntimeTestCase {
-                package: "ralph-cli",
+                package: "ralph-core",
                 filter: "test_parse_hook_mutation_stdout_accepts_metadata_only_payload_and_namespaces_by_hook",
             },
         ],
         "AC-15" => vec![
             RuntimeTestCase {
-                package: "ralph-cli",
+                package: "ralph-core",
                 filter: "test_ac15_dispatch_phase_event_hooks_non_json_mutation_warn_continues_through_block_gate",
             },
             RuntimeTestCase {
-                package: "ralph-cli",
+                package: "ralph-core",
                 filter: "test_ac15_dispatch_phase_event_hooks_non_json_mutation_block_surfaces_invalid_output_reason",
             },
             RuntimeTestCase {
-                package: "ralph-cli",
+                package: "ralph-core",
                 filter: "test_ac15_dispatch_phase_event_hooks_non_json_mutation_suspend_uses_wait_for_resume_gate",
             },
         ],
         "AC-16" => vec![
             RuntimeTestCase {
-                package: "ralph-cli",
+                package: "ralph-core",
                 filter: "test_dispatch_phase_event_hooks_retry_backoff_recovers_before_exhaustion",
             },
             RuntimeTestCase {
@@ -847,6 +848,24 @@ fn runtime_test_cases_for_ac(ac_id: &str) -> Vec<RuntimeTestCase> {
                 filter: "default_checks_include_hooks_check_name",
             },
         ],
+        "AC-19" => vec![
+            RuntimeTestCase {
+                package: "ralph-core",
+                filter: "test_parse_hook_mutation_stdout_rejects_invalid_action_shapes_and_phases",
+            },
+            RuntimeTestCase {
+                package: "ralph-core",
+                filter: "test_ac19_pre_event_publish_mutation_actions_reject_rewrite_publish_and_guide",
+            },
+            RuntimeTestCase {
+                package: "ralph-core",
+                filter: "test_ac19_hook_mutation_actions_are_recorded_in_hook_run_telemetry",
+            },
+            RuntimeTestCase {
+                package: "ralph-core",
+                filter: "test_pending_event_mutations_reject_and_rewrite_unread_events",
+            },
+        ],
         _ => Vec::new(),
     }
 }
@@ -1536,7 +1555,7 @@ fn evaluate_ac_08(
             )?;
 
             assert_workspace_source_contains(
-                "crates/ralph-cli/src/loop_runner.rs",
+                "crates/ralph-core/src/hooks/orchestrator/mod.rs",
                 &[
                     (
                         "warn policy maps to warn disposition",
@@ -1548,15 +1567,21 @@ fn evaluate_ac_08(
                     ),
                     (
                         "hook dispatch logs telemetry entries with computed disposition",
-                        "event_loop.log_hook_run_telemetry(HookRunTelemetryEntry::from_run_result(",
+                        "telemetry.log_hook_run(\n                HookRunTelemetryEntry::from_run_result(",
                     ),
+                ],
+            )?;
+
+            assert_workspace_source_contains(
+                "crates/ralph-core/src/hooks/orchestrator/tests.rs",
+                &[
                     (
                         "lifecycle integration test asserts warn continues across boundary",
                         "warn disposition should continue across loop.start boundary",
                     ),
                     (
                         "blocking gate helper allows non-blocking dispositions",
-                        "fn test_fail_if_blocking_loop_start_outcomes_allows_non_blocking_dispositions() {",
+                        "fn test_fail_if_blocking_outcomes_allows_non_blocking_dispositions() {",
                     ),
                 ],
             )?;
@@ -1597,7 +1622,7 @@ fn evaluate_ac_09(
             )?;
 
             assert_workspace_source_contains(
-                "crates/ralph-cli/src/loop_runner.rs",
+                "crates/ralph-core/src/hooks/orchestrator/mod.rs",
                 &[
                     (
                         "block policy maps to block disposition",
@@ -1609,12 +1634,18 @@ fn evaluate_ac_09(
                     ),
                     (
                         "blocking gate fails lifecycle boundary when block is present",
-                        "Err(anyhow::anyhow!(reason))",
+                        "Err(HookBoundaryError::Blocked { reason })",
                     ),
                     (
                         "blocking failure reason includes hook, phase-event, and failure detail",
                         "\"Lifecycle hook '{}' blocked orchestration at '{}': {}\"",
                     ),
+                ],
+            )?;
+
+            assert_workspace_source_contains(
+                "crates/ralph-core/src/hooks/orchestrator/tests.rs",
+                &[
                     (
                         "loop-start integration test asserts block disposition aborts boundary",
                         "expect_err(\"block disposition should abort loop.start boundary\")",
@@ -1690,7 +1721,7 @@ fn evaluate_ac_11(
                     ),
                     (
                         "resume command resolves suspend-state store at loop workspace root",
-                        "let suspend_state_store = SuspendStateStore::new(&target_root);",
+                        "let mut suspend_state_store = SuspendStateStore::new(&target_root);",
                     ),
                     (
                         "resume command reads persisted suspend-state before resuming",
@@ -1804,7 +1835,7 @@ fn evaluate_ac_13(
         validate_acceptance_context,
         |_harness| {
             assert_workspace_source_contains(
-                "crates/ralph-cli/src/loop_runner.rs",
+                "crates/ralph-core/src/hooks/orchestrator/mod.rs",
                 &[
                     (
                         "mutation parser short-circuits when mutate.enabled is false",
@@ -1822,6 +1853,12 @@ fn evaluate_ac_13(
                         "non-parsed mutation outcomes are skipped during metadata merge",
                         "else {\n            continue;\n        };",
                     ),
+                ],
+            )?;
+
+            assert_workspace_source_contains(
+                "crates/ralph-core/src/hooks/orchestrator/tests.rs",
+                &[
                     (
                         "AC-13 integration test verifies disabled mutations stay inert",
                         "fn test_ac13_mutation_disabled_json_output_is_inert_for_accumulator_and_downstream_payloads() {",
@@ -1858,15 +1895,15 @@ fn evaluate_ac_14(
         validate_acceptance_context,
         |_harness| {
             assert_workspace_source_contains(
-                "crates/ralph-cli/src/loop_runner.rs",
+                "crates/ralph-core/src/hooks/orchestrator/mod.rs",
                 &[
                     (
-                        "mutation payload parser enforces metadata-only top-level schema",
-                        "if payload_object.len() != 1 || !payload_object.contains_key(HOOK_MUTATION_PAYLOAD_METADATA_KEY)",
+                        "mutation payload parser rejects keys outside the mutation schema",
+                        "if payload_object.is_empty() || !unsupported_keys.is_empty() {",
                     ),
                     (
-                        "schema error message documents metadata-only mutation contract",
-                        "mutation payload supports only '{{\\\"{HOOK_MUTATION_PAYLOAD_METADATA_KEY}\\\": {{...}}}}'; found keys: {keys:?}",
+                        "schema error message documents the supported mutation keys",
+                        "mutation payload supports only keys {HOOK_MUTATION_PAYLOAD_KEYS:?}; found keys: {keys:?}",
                     ),
                     (
                         "metadata payload value must be a JSON object",
@@ -1876,6 +1913,12 @@ fn evaluate_ac_14(
                         "parsed metadata is namespaced under hook_metadata by emitting hook",
                         "namespace_object.insert(hook_name.to_string(), serde_json::Value::Object(metadata));",
                     ),
+                ],
+            )?;
+
+            assert_workspace_source_contains(
+                "crates/ralph-core/src/hooks/orchestrator/tests.rs",
+                &[
                     (
                         "AC-14 integration test validates metadata-only downstream mutation behavior",
                         "fn test_ac14_mutation_enabled_updates_only_namespaced_metadata_in_downstream_payloads() {",
@@ -1889,8 +1932,8 @@ fn evaluate_ac_14(
                         "assert!(!payload_object.contains_key(\"events\"));",
                     ),
                     (
-                        "unit test rejects payloads that include non-metadata keys",
-                        "fn test_parse_hook_mutation_stdout_rejects_non_metadata_payload_shape() {",
+                        "unit test rejects payloads that include unsupported keys",
+                        "fn test_parse_hook_mutation_stdout_rejects_unsupported_payload_keys() {",
                     ),
                 ],
             )?;
@@ -1916,7 +1959,7 @@ fn evaluate_ac_15(
         validate_acceptance_context,
         |_harness| {
             assert_workspace_source_contains(
-                "crates/ralph-cli/src/loop_runner.rs",
+                "crates/ralph-core/src/hooks/orchestrator/mod.rs",
                 &[
                     (
                         "mutation parser attempts JSON decode of hook stdout",
@@ -1942,6 +1985,12 @@ fn evaluate_ac_15(
                         "mutation parse failure branch maps through disposition_from_on_error",
                         "disposition_from_on_error(on_error)",
                     ),
+                ],
+            )?;
+
+            assert_workspace_source_contains(
+                "crates/ralph-core/src/hooks/orchestrator/tests.rs",
+                &[
                     (
                         "unit test verifies parser rejects non-JSON mutation stdout",
                         "fn test_parse_hook_mutation_stdout_rejects_non_json_payload_when_enabled() {",
@@ -2057,11 +2106,11 @@ fn evaluate_ac_16(
             )?;
 
             assert_workspace_source_contains(
-                "crates/ralph-cli/src/loop_runner.rs",
+                "crates/ralph-core/src/hooks/orchestrator/mod.rs",
                 &[
                     (
                         "loop runner emits hook-run telemetry after each attempt",
-                        "event_loop.log_hook_run_telemetry(HookRunTelemetryEntry::from_run_result(",
+                        "telemetry.log_hook_run(\n                HookRunTelemetryEntry::from_run_result(",
                     ),
                     (
                         "telemetry emission includes canonical phase-event key",
@@ -2085,6 +2134,12 @@ fn evaluate_ac_16(
                         "telemetry emission includes executor run result",
                         "&run_result,",
                     ),
+                ],
+            )?;
+
+            assert_workspace_source_contains(
+                "crates/ralph-core/src/hooks/orchestrator/tests.rs",
+                &[
                     (
                         "retry-backoff integration test asserts telemetry row count",
                         "assert_eq!(telemetry_entries.len(), 3);",
@@ -2281,6 +2336,118 @@ fn evaluate_ac_18(
     )
 }
 
+// =============================================================================
+// AC-19: Structured mutation actions
+// =============================================================================
+
+fn evaluate_ac_19(
+    scenario: &HooksBddScenario,
+    harness: &mut HooksBddIntegrationHarness,
+    ci_safe_mode: bool,
+) -> HooksBddScenarioResult {
+    evaluate_green_acceptance(
+        scenario,
+        harness,
+        ci_safe_mode,
+        validate_acceptance_context,
+        |_harness| {
+            assert_workspace_source_contains(
+                "crates/ralph-core/src/hooks/orchestrator/mod.rs",
+                &[
+                    (
+                        "mutation schema lists every supported action key",
+                        "const HOOK_MUTATION_PAYLOAD_KEYS: [&str; 5] = [",
+                    ),
+                    (
+                        "action parser validates actions against the returning phase",
+                        "fn parse_hook_mutation_actions(",
+                    ),
+                    (
+                        "event verdicts are limited to pre.event.publish",
+                        "mutation action 'reject' is only supported on 'pre.event.publish' (got '{phase}')",
+                    ),
+                    (
+                        "reject and rewrite cannot be combined",
+                        "mutation actions 'reject' and 'rewrite' are mutually exclusive",
+                    ),
+                    (
+                        "guidance actions are appended to the next prompt",
+                        "event_loop.append_hook_guidance(guidance.clone());",
+                    ),
+                    (
+                        "hook-run telemetry records parsed mutation actions",
+                        ".with_mutation(hook_mutation_telemetry(&mutation_parse_outcome))",
+                    ),
+                ],
+            )?;
+
+            assert_workspace_source_contains(
+                "crates/ralph-core/src/event_loop/mod.rs",
+                &[
+                    (
+                        "reject/rewrite actions are queued against the unread event",
+                        "crate::hooks::queue_pending_event_mutations(self, index, &event.topic, &event_outcomes);",
+                    ),
+                    (
+                        "publish/guidance actions are applied after pre.event.publish dispatch",
+                        "crate::hooks::apply_hook_mutation_actions(self, &outcomes);",
+                    ),
+                ],
+            )?;
+
+            assert_workspace_source_contains(
+                "crates/ralph-core/src/hooks/orchestrator/tests.rs",
+                &[
+                    (
+                        "unit test rejects invalid action shapes and phases",
+                        "fn test_parse_hook_mutation_stdout_rejects_invalid_action_shapes_and_phases() {",
+                    ),
+                    (
+                        "AC-19 integration test applies every action kind end to end",
+                        "fn test_ac19_pre_event_publish_mutation_actions_reject_rewrite_publish_and_guide() {",
+                    ),
+                    (
+                        "AC-19 integration test asserts mutation telemetry",
+                        "fn test_ac19_hook_mutation_actions_are_recorded_in_hook_run_telemetry() {",
+                    ),
+                ],
+            )?;
+
+            assert_workspace_source_contains(
+                "crates/ralph-core/src/event_loop/mod.rs",
+                &[
+                    (
+                        "event loop models hook mutations of unread events",
+                        "pub enum PendingEventMutation {",
+                    ),
+                    (
+                        "queued mutations are applied as the JSONL batch is read",
+                        "self.apply_pending_event_mutations(&mut result.events);",
+                    ),
+                    (
+                        "rejected events are fed back to the agent as event.rejected",
+                        "event.topic = \"event.rejected\".to_string();",
+                    ),
+                    (
+                        "hook guidance is consumed by the next prompt build",
+                        "guidance.append(&mut self.hook_guidance);",
+                    ),
+                ],
+            )?;
+
+            assert_workspace_source_contains(
+                "crates/ralph-core/src/diagnostics/hook_runs.rs",
+                &[(
+                    "telemetry entries carry mutation actions or validation errors",
+                    "pub mutation: Option<HookMutationTelemetry>,",
+                )],
+            )?;
+
+            Ok(())
+        },
+    )
+}
+
 fn hooks_feature_dir() -> Result<PathBuf, HooksBddError> {
     let manifest_candidate =
         PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(HOOKS_FEATURE_DIR_CRATE);
@@ -2597,9 +2764,10 @@ mod tests {
             .map(|scenario| scenario.scenario_id.as_str())
             .collect();
 
-        assert_eq!(scenarios.len(), 18);
+        assert_eq!(scenarios.len(), 19);
         assert!(scenario_ids.contains(&"AC-01"));
         assert!(scenario_ids.contains(&"AC-18"));
+        assert!(scenario_ids.contains(&"AC-19"));
     }
 
     #[test]
@@ -2850,6 +3018,16 @@ mod tests {
         assert!(results.results[0].passed);
     }
 
+    #[test]
+    fn run_hooks_bdd_suite_passes_ac_19_structured_mutation_actions() {
+        let config = HooksBddConfig::new(Some("AC-19".to_string()), true);
+        let results = run_hooks_bdd_suite(&config).expect("suite should run");
+
+        assert_eq!(results.total_count(), 1);
+        assert_eq!(results.passed_count(), 1);
+        assert!(results.results[0].passed);
+    }
+
     #[test]
     fn run_hooks_bdd_suite_uses_unmapped_fallback_evaluator() {
         // Test that unmapped AC IDs (not in dispatch map) use the fallback evaluator
@@ -0,0 +1,453 @@
+//! Payload conditions for conditional event routing.
+//!
+//! A hat trigger can carry a condition that must hold for an event's payload
+//! before the event is routed to the hat, e.g. `verdict == reject` on
+//! `review.done`. Conditions are evaluated over the structured form of the
+//! payload (see [`structured_payload`]).
+//!
+//! Grammar:
+//!
+//! ```text
+//! expr       := and ("||" and)*
+//! and        := comparison ("&&" comparison)*
+//! comparison := "!"? path (op literal)?
+//! path       := "$"? segment ("." segment | "[" index "]")*
+//! op         := "==" | "!=" | "<" | "<=" | ">" | ">="
+//! literal    := 'text' | "text" | number | true | false | null | bareword
+//! ```
+//!
+//! A path without an operator tests that the value exists and is truthy.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt;
+
+/// Parses a payload into a structured value for condition evaluation.
+///
+/// JSON payloads (as written by `ralph emit --json`) are used as-is. Other
+/// payloads are read as `key: value` lines, the format used for evidence
+/// payloads such as `tests: pass`. Returns `None` when neither form applies.
+pub fn structured_payload(payload: &str) -> Option<Value> {
+    let trimmed = payload.trim();
+    if (trimmed.starts_with('{') || trimmed.starts_with('['))
+        && let Ok(value) = serde_json::from_str(trimmed)
+    {
+        return Some(value);
+    }
+
+    let mut fields = serde_json::Map::new();
+    for line in trimmed.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let key = key.trim();
+        if key.is_empty() || key.contains(char::is_whitespace) {
+            continue;
+        }
+        fields.insert(key.to_string(), Value::String(value.trim().to_string()));
+    }
+
+    (!fields.is_empty()).then_some(Value::Object(fields))
+}
+
+/// Error returned when a condition expression can't be parsed.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("invalid condition '{expression}': {reason}")]
+pub struct ConditionError {
+    pub expression: String,
+    pub reason: String,
+}
+
+/// A parsed payload condition.
+///
+/// Serializes as its source expression.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct PayloadCondition {
+    source: String,
+    /// Disjunction of conjunctions.
+    any: Vec<Vec<Comparison>>,
+}
+
+impl PayloadCondition {
+    /// Parses a condition expression.
+    pub fn parse(expression: &str) -> Result<Self, ConditionError> {
+        let error = |reason: String| ConditionError {
+            expression: expression.to_string(),
+            reason,
+        };
+
+        let mut any = Vec::new();
+        for clause in split_outside_quotes(expression, "||").map_err(error)? {
+            let mut all = Vec::new();
+            for term in split_outside_quotes(clause, "&&").map_err(error)? {
+                all.push(Comparison::parse(term.trim()).map_err(error)?);
+            }
+            any.push(all);
+        }
+
+        Ok(Self {
+            source: expression.trim().to_string(),
+            any,
+        })
+    }
+
+    /// Returns the source expression.
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    /// Returns true when the condition holds for a raw event payload.
+    ///
+    /// Payloads without structure never match.
+    pub fn matches(&self, payload: &str) -> bool {
+        structured_payload(payload).is_some_and(|value| self.matches_value(&value))
+    }
+
+    /// Returns true when the condition holds for an already structured payload.
+    pub fn matches_value(&self, value: &Value) -> bool {
+        self.any
+            .iter()
+            .any(|all| all.iter().all(|comparison| comparison.holds(value)))
+    }
+}
+
+impl fmt::Display for PayloadCondition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+impl TryFrom<String> for PayloadCondition {
+    type Error = ConditionError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::parse(&value)
+    }
+}
+
+impl From<PayloadCondition> for String {
+    fn from(condition: PayloadCondition) -> Self {
+        condition.source
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operator {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Comparison {
+    negated: bool,
+    path: Vec<PathSegment>,
+    test: Option<(Operator, Value)>,
+}
+
+impl Comparison {
+    fn parse(term: &str) -> Result<Self, String> {
+        if term.is_empty() {
+            return Err("empty comparison".to_string());
+        }
+
+        // Longest operators first so `<=` isn't read as `<`.
+        const OPERATORS: [(&str, Operator); 6] = [
+            ("==", Operator::Eq),
+            ("!=", Operator::Ne),
+            ("<=", Operator::Le),
+            (">=", Operator::Ge),
+            ("<", Operator::Lt),
+            (">", Operator::Gt),
+        ];
+        let split = OPERATORS
+            .iter()
+            .filter_map(|(token, op)| term.find(token).map(|at| (at, *token, *op)))
+            .min_by_key(|(at, token, _)| (*at, std::cmp::Reverse(token.len())));
+
+        let (lhs, test) = match split {
+            Some((at, token, op)) => {
+                let literal = parse_literal(term[at + token.len()..].trim())?;
+                (term[..at].trim(), Some((op, literal)))
+            }
+            None => (term, None),
+        };
+
+        let (negated, lhs) = match lhs.strip_prefix('!') {
+            Some(rest) if test.is_none() => (true, rest.trim()),
+            Some(_) => return Err("'!' can't be combined with a comparison".to_string()),
+            None => (false, lhs),
+        };
+
+        Ok(Self {
+            negated,
+            path: parse_path(lhs)?,
+            test,
+        })
+    }
+
+    fn holds(&self, root: &Value) -> bool {
+        let value = self
+            .path
+            .iter()
+            .try_fold(root, |value, segment| match segment {
+                PathSegment::Key(key) => value.get(key),
+                PathSegment::Index(index) => value.get(index),
+            });
+
+        let result = match (&self.test, value) {
+            (None, value) => value.is_some_and(is_truthy),
+            (Some((Operator::Ne, expected)), None) => !expected.is_null(),
+            (Some((Operator::Eq, expected)), None) => expected.is_null(),
+            (Some(_), None) => false,
+            (Some((op, expected)), Some(actual)) => compare(*op, actual, expected),
+        };
+        result != self.negated
+    }
+}
+
+/// Splits `text` on `separator`, ignoring separators inside quoted literals.
+fn split_outside_quotes<'a>(text: &'a str, separator: &str) -> Result<Vec<&'a str>, String> {
+    let mut parts = Vec::new();
+    let mut quote = None;
+    let mut start = 0;
+    let mut chars = text.char_indices();
+    while let Some((at, c)) = chars.next() {
+        match quote {
+            Some(open) if c == open => quote = None,
+            Some(_) => {}
+            None if c == '\'' || c == '"' => quote = Some(c),
+            None if text[at..].starts_with(separator) => {
+                parts.push(&text[start..at]);
+                start = at + separator.len();
+                for _ in 1..separator.chars().count() {
+                    chars.next();
+                }
+            }
+            None => {}
+        }
+    }
+    if let Some(open) = quote {
+        return Err(format!("unterminated {open} quote"));
+    }
+    parts.push(&text[start..]);
+    Ok(parts)
+}
+
+fn parse_path(path: &str) -> Result<Vec<PathSegment>, String> {
+    let path = path.strip_prefix('$').unwrap_or(path);
+    let path = path.strip_prefix('.').unwrap_or(path);
+    if path.is_empty() {
+        return Err("missing payload path".to_string());
+    }
+
+    let mut segments = Vec::new();
+    for part in path.split('.') {
+        let (key, mut rest) = part.find('[').map_or((part, ""), |at| part.split_at(at));
+        if !key.is_empty() {
+            if !key
+                .chars()
+                .all(|c| c.is_alphanumeric() || c == '_' || c == '-')
+            {
+                return Err(format!("invalid path segment '{key}'"));
+            }
+            segments.push(PathSegment::Key(key.to_string()));
+        } else if rest.is_empty() {
+            return Err(format!("empty path segment in '{path}'"));
+        }
+        while let Some(after) = rest.strip_prefix('[') {
+            let (index, remainder) = after
+                .split_once(']')
+                .ok_or_else(|| format!("unclosed '[' in '{path}'"))?;
+            let index = index
+                .trim()
+                .parse()
+                .map_err(|_| format!("invalid index '{index}' in '{path}'"))?;
+            segments.push(PathSegment::Index(index));
+            rest = remainder;
+        }
+        if !rest.is_empty() {
+            return Err(format!("unexpected '{rest}' in '{path}'"));
+        }
+    }
+    Ok(segments)
+}
+
+fn parse_literal(literal: &str) -> Result<Value, String> {
+    if literal.is_empty() {
+        return Err("missing value after operator".to_string());
+    }
+    for quote in ['\'', '"'] {
+        if let Some(inner) = literal
+            .strip_prefix(quote)
+            .and_then(|rest| rest.strip_suffix(quote))
+        {
+            return Ok(Value::String(inner.to_string()));
+        }
+    }
+    Ok(match literal {
+        "true" => Value::Bool(true),
+        "false" => Value::Bool(false),
+        "null" => Value::Null,
+        _ => serde_json::from_str::<serde_json::Number>(literal)
+            .map_or_else(|_| Value::String(literal.to_string()), Value::Number),
+    })
+}
+
+fn is_truthy(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Bool(b) => *b,
+        Value::Number(n) => n.as_f64() != Some(0.0),
+        Value::String(s) => !s.is_empty() && s != "false",
+        Value::Array(items) => !items.is_empty(),
+        Value::Object(fields) => !fields.is_empty(),
+    }
+}
+
+/// Reads numbers from JSON numbers or numeric strings like `82` or `82%`.
+fn as_number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().trim_end_matches('%').trim().parse().ok(),
+        _ => None,
+    }
+}
+
+fn compare(op: Operator, actual: &Value, expected: &Value) -> bool {
+    let ordering = match (as_number(actual), as_number(expected)) {
+        (Some(a), Some(b)) => a.partial_cmp(&b),
+        _ => match (actual, expected) {
+            (Value::String(a), Value::String(b)) => Some(a.cmp(b)),
+            (Value::String(a), Value::Bool(b)) => {
+                return matches!(op, Operator::Eq | Operator::Ne)
+                    && ((a == &b.to_string()) == (op == Operator::Eq));
+            }
+            _ => {
+                return match op {
+                    Operator::Eq => actual == expected,
+                    Operator::Ne => actual != expected,
+                    _ => false,
+                };
+            }
+        },
+    };
+    let Some(ordering) = ordering else {
+        return false;
+    };
+    match op {
+        Operator::Eq => ordering.is_eq(),
+        Operator::Ne => ordering.is_ne(),
+        Operator::Lt => ordering.is_lt(),
+        Operator::Le => ordering.is_le(),
+        Operator::Gt => ordering.is_gt(),
+        Operator::Ge => ordering.is_ge(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn holds(expression: &str, payload: &str) -> bool {
+        PayloadCondition::parse(expression)
+            .unwrap()
+            .matches(payload)
+    }
+
+    #[test]
+    fn test_equality_on_json_payload() {
+        let payload = r#"{"verdict": "reject", "issues": 3}"#;
+        assert!(holds("verdict == reject", payload));
+        assert!(holds("$.verdict == 'reject'", payload));
+        assert!(!holds("verdict == \"approve\"", payload));
+        assert!(holds("verdict != approve", payload));
+    }
+
+    #[test]
+    fn test_numeric_comparisons() {
+        let payload = r#"{"issues": 3, "coverage": "82%"}"#;
+        assert!(holds("issues > 0", payload));
+        assert!(holds("issues <= 3", payload));
+        assert!(!holds("issues < 3", payload));
+        assert!(holds("coverage >= 80", payload));
+    }
+
+    #[test]
+    fn test_key_value_payload() {
+        assert!(holds("tests == pass", "tests: pass\nlint: fail"));
+        assert!(holds(
+            "lint == fail && tests == pass",
+            "tests: pass\nlint: fail"
+        ));
+        assert!(!holds("tests == pass", "all good"));
+    }
+
+    #[test]
+    fn test_key_value_values_keep_commas() {
+        let payload = "summary: fixed parser, added tests\nstatus: done";
+        assert!(holds("summary == 'fixed parser, added tests'", payload));
+        assert!(holds("status == done", payload));
+    }
+
+    #[test]
+    fn test_operators_inside_quoted_literals() {
+        let payload = r#"{"reason": "a && b || c", "kind": "x"}"#;
+        assert!(holds("reason == 'a && b || c'", payload));
+        assert!(holds("kind == x && reason == \"a && b || c\"", payload));
+        assert!(!holds("reason == 'a && b'", payload));
+    }
+
+    #[test]
+    fn test_nested_paths_and_indexes() {
+        let payload = r#"{"review": {"findings": [{"severity": "high"}]}}"#;
+        assert!(holds("review.findings[0].severity == high", payload));
+        assert!(!holds("review.findings[1].severity == high", payload));
+    }
+
+    #[test]
+    fn test_truthiness_and_negation() {
+        let payload = r#"{"blocking": true, "notes": ""}"#;
+        assert!(holds("blocking", payload));
+        assert!(!holds("notes", payload));
+        assert!(holds("!notes", payload));
+        assert!(holds("!missing", payload));
+        assert!(holds("blocking == true", payload));
+    }
+
+    #[test]
+    fn test_or_binds_looser_than_and() {
+        let payload = r#"{"a": 1, "b": 0, "c": 1}"#;
+        assert!(holds("a == 2 && b == 0 || c == 1", payload));
+        assert!(!holds("a == 2 && c == 1 || b == 1", payload));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(PayloadCondition::parse("").is_err());
+        assert!(PayloadCondition::parse("verdict ==").is_err());
+        assert!(PayloadCondition::parse("!verdict == reject").is_err());
+        assert!(PayloadCondition::parse("findings[x] == 1").is_err());
+        assert!(PayloadCondition::parse("has space == 1").is_err());
+        assert!(PayloadCondition::parse("reason == 'a && b").is_err());
+    }
+
+    #[test]
+    fn test_serializes_as_source_expression() {
+        let condition = PayloadCondition::parse("verdict == reject").unwrap();
+        let json = serde_json::to_string(&condition).unwrap();
+        assert_eq!(json, "\"verdict == reject\"");
+        let parsed: PayloadCondition = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, condition);
+    }
+}
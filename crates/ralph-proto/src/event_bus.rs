@@ -109,11 +109,12 @@ impl EventBus {
         let mut specific_recipients = Vec::new();
         let mut fallback_recipients = Vec::new();
 
+        // Subscriptions with payload conditions only count when the condition holds.
         for (id, hat) in &self.hats {
-            if hat.has_specific_subscription(&event.topic) {
+            if hat.is_specifically_triggered_by(&event) {
                 // Hat has a specific subscription for this topic
                 specific_recipients.push(id.clone());
-            } else if hat.is_subscribed(&event.topic) {
+            } else if hat.is_triggered_by(&event) {
                 // Hat matches only via global wildcard (fallback)
                 fallback_recipients.push(id.clone());
             }
@@ -398,4 +399,22 @@ mod tests {
         let peeked_after_take = bus.peek_pending(&hat_id);
         assert!(peeked_after_take.is_none() || peeked_after_take.unwrap().is_empty());
     }
+
+    #[test]
+    fn test_conditional_subscription_falls_back_when_condition_fails() {
+        let mut bus = EventBus::new();
+
+        let fixer = Hat::new("fixer", "Fixer").subscribe_when(
+            "review.done",
+            crate::PayloadCondition::parse("verdict == reject").unwrap(),
+        );
+        bus.register(fixer);
+        bus.register(Hat::new("ralph", "Ralph").subscribe("*"));
+
+        let recipients = bus.publish(Event::new("review.done", r#"{"verdict": "reject"}"#));
+        assert_eq!(recipients, vec![HatId::new("fixer")]);
+
+        let recipients = bus.publish(Event::new("review.done", r#"{"verdict": "approve"}"#));
+        assert_eq!(recipients, vec![HatId::new("ralph")]);
+    }
 }
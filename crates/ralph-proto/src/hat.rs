@@ -2,8 +2,9 @@
 //!
 //! A hat defines how the CLI agent should behave for a given iteration.
 
-use crate::Topic;
+use crate::{Event, PayloadCondition, Topic};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 /// Unique identifier for a hat.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
@@ -55,6 +56,13 @@ pub struct Hat {
     /// Topic patterns this hat subscribes to.
     pub subscriptions: Vec<Topic>,
 
+    /// Payload conditions keyed by subscription pattern.
+    ///
+    /// An event matching a subscription with a condition only triggers the
+    /// hat when the condition holds for the event's payload.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub conditions: BTreeMap<String, PayloadCondition>,
+
     /// Topics this hat is expected to publish.
     pub publishes: Vec<Topic>,
 
@@ -70,6 +78,7 @@ impl Hat {
             name: name.into(),
             description: String::new(),
             subscriptions: Vec::new(),
+            conditions: BTreeMap::new(),
             publishes: Vec::new(),
             instructions: String::new(),
         }
@@ -90,6 +99,7 @@ impl Hat {
             name: "Default".to_string(),
             description: "Default single-hat mode handler".to_string(),
             subscriptions: vec![Topic::new("*")],
+            conditions: BTreeMap::new(),
             publishes: vec![Topic::new("task.done")],
             instructions: String::new(),
         }
@@ -110,6 +120,7 @@ impl Hat {
                 Topic::new("build.done"),
                 Topic::new("build.blocked"),
             ],
+            conditions: BTreeMap::new(),
             publishes: vec![Topic::new("build.task")],
             instructions: String::new(),
         }
@@ -125,6 +136,7 @@ impl Hat {
             name: "Builder".to_string(),
             description: "Implements code changes, runs backpressure".to_string(),
             subscriptions: vec![Topic::new("build.task")],
+            conditions: BTreeMap::new(),
             publishes: vec![Topic::new("build.done"), Topic::new("build.blocked")],
             instructions: String::new(),
         }
@@ -137,6 +149,21 @@ impl Hat {
         self
     }
 
+    /// Adds a subscription that only triggers when `condition` holds for the payload.
+    #[must_use]
+    pub fn subscribe_when(mut self, topic: impl Into<Topic>, condition: PayloadCondition) -> Self {
+        let topic = topic.into();
+        self.conditions
+            .insert(topic.as_str().to_string(), condition);
+        self.subscriptions.push(topic);
+        self
+    }
+
+    /// Returns the payload condition attached to a subscription pattern, if any.
+    pub fn condition_for(&self, subscription: &Topic) -> Option<&PayloadCondition> {
+        self.conditions.get(subscription.as_str())
+    }
+
     /// Sets the instructions for this hat.
     #[must_use]
     pub fn with_instructions(mut self, instructions: impl Into<String>) -> Self {
@@ -174,6 +201,29 @@ impl Hat {
             .any(|sub| !sub.is_global_wildcard() && sub.matches(topic))
     }
 
+    /// Checks if an event triggers this hat, honouring payload conditions.
+    pub fn is_triggered_by(&self, event: &Event) -> bool {
+        self.triggering_subscriptions(event).next().is_some()
+    }
+
+    /// Checks if an event triggers this hat through a specific (non-global-wildcard)
+    /// subscription, honouring payload conditions.
+    ///
+    /// Event-aware counterpart of `has_specific_subscription()` used for routing.
+    pub fn is_specifically_triggered_by(&self, event: &Event) -> bool {
+        self.triggering_subscriptions(event)
+            .any(|sub| !sub.is_global_wildcard())
+    }
+
+    fn triggering_subscriptions<'a>(&'a self, event: &'a Event) -> impl Iterator<Item = &'a Topic> {
+        self.subscriptions.iter().filter(move |sub| {
+            sub.matches(&event.topic)
+                && self
+                    .condition_for(sub)
+                    .is_none_or(|condition| condition.matches(&event.payload))
+        })
+    }
+
     /// Returns true if all subscriptions are global wildcards (`*`).
     ///
     /// Used to identify fallback handlers like Ralph.
@@ -197,6 +247,23 @@ mod tests {
         assert!(!hat.is_subscribed(&Topic::new("review.done")));
     }
 
+    #[test]
+    fn test_conditional_subscription() {
+        let hat = Hat::new("fixer", "Fixer").subscribe_when(
+            "review.done",
+            PayloadCondition::parse("verdict == reject").unwrap(),
+        );
+
+        let reject = Event::new("review.done", r#"{"verdict": "reject"}"#);
+        let approve = Event::new("review.done", r#"{"verdict": "approve"}"#);
+
+        assert!(hat.is_subscribed(&Topic::new("review.done")));
+        assert!(hat.is_triggered_by(&reject));
+        assert!(hat.is_specifically_triggered_by(&reject));
+        assert!(!hat.is_triggered_by(&approve));
+        assert!(!hat.is_triggered_by(&Event::new("review.done", "looks good")));
+    }
+
     #[test]
     #[allow(deprecated)]
     fn test_default_single_hat() {
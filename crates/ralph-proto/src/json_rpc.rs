@@ -284,6 +284,20 @@ pub enum RpcEvent {
         ready: usize,
     },
 
+    /// A fan-out branch started or finished.
+    FanOutBranch {
+        /// Iteration number of the fan-out hat.
+        iteration: u32,
+        /// Branch hat ID.
+        hat: String,
+        /// Branch status: "running", "completed", "failed" or "timed_out".
+        status: String,
+        /// Estimated cost of the branch in USD.
+        cost_usd: f64,
+        /// Tokens used by the branch.
+        tokens: u64,
+    },
+
     /// Acknowledgment that guidance was received.
     GuidanceAck {
         /// The guidance message that was received.
@@ -724,6 +738,21 @@ mod tests {
         assert_eq!(event, parsed);
     }
 
+    #[test]
+    fn test_fan_out_branch_event_roundtrip() {
+        let event = RpcEvent::FanOutBranch {
+            iteration: 3,
+            hat: "security".to_string(),
+            status: "timed_out".to_string(),
+            cost_usd: 0.12,
+            tokens: 4200,
+        };
+        let json = serde_json::to_string(&event).unwrap();
+        assert!(json.contains("\"type\":\"fan_out_branch\""));
+        let parsed: RpcEvent = serde_json::from_str(&json).unwrap();
+        assert_eq!(event, parsed);
+    }
+
     #[test]
     fn test_task_counts_updated_event_roundtrip() {
         let event = RpcEvent::TaskCountsUpdated {
@@ -6,9 +6,10 @@
 //! including:
 //! - Event and `EventBus` types for pub/sub messaging
 //! - Hat definitions for agent personas
-//! - Topic matching for event routing
+//! - Topic matching and payload conditions for event routing
 //! - Common error types
 
+mod condition;
 pub mod daemon;
 mod error;
 mod event;
@@ -19,6 +20,7 @@ pub mod robot;
 mod topic;
 mod ux_event;
 
+pub use condition::{ConditionError, PayloadCondition, structured_payload};
 pub use daemon::{DaemonAdapter, StartLoopFn};
 pub use error::{Error, Result};
 pub use event::Event;
@@ -28,7 +30,7 @@ pub use json_rpc::{
     GuidanceTarget, RpcCommand, RpcEvent, RpcIterationInfo, RpcState, RpcTaskCounts,
     RpcTaskSummary, TerminationReason, emit_event, emit_event_line, parse_command,
 };
-pub use robot::{CheckinContext, RobotService};
+pub use robot::{CheckinContext, LoopOutcome, RobotService};
 pub use topic::Topic;
 pub use ux_event::{
     FrameCapture, TerminalColorMode, TerminalResize, TerminalWrite, TuiFrame, UxEvent,
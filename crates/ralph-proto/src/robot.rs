@@ -27,6 +27,23 @@ pub struct CheckinContext {
     pub cumulative_cost: f64,
 }
 
+/// Final outcome of a loop, sent to the human when the loop terminates.
+#[derive(Debug, Clone)]
+pub struct LoopOutcome {
+    /// Termination reason (e.g., "completed", "max_iterations").
+    pub reason: String,
+    /// Whether the loop completed successfully.
+    pub success: bool,
+    /// Process exit code the loop will report.
+    pub exit_code: i32,
+    /// Number of iterations that ran.
+    pub iterations: u32,
+    /// Wall-clock duration of the loop.
+    pub elapsed: Duration,
+    /// Cumulative cost in USD.
+    pub cumulative_cost: f64,
+}
+
 /// A communication service for human-in-the-loop interaction.
 ///
 /// Implementors handle platform-specific concerns: sending messages,
@@ -57,6 +74,14 @@ pub trait RobotService: Send + Sync {
         context: Option<&CheckinContext>,
     ) -> anyhow::Result<i32>;
 
+    /// Notify the human that the loop finished or failed.
+    ///
+    /// Called once, before [`RobotService::stop`]. The default implementation
+    /// sends nothing and returns `Ok(0)`.
+    fn send_outcome(&self, _outcome: &LoopOutcome) -> anyhow::Result<i32> {
+        Ok(0)
+    }
+
     /// Get the configured response timeout in seconds.
     fn timeout_secs(&self) -> u64;
 
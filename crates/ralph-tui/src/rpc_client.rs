@@ -308,6 +308,7 @@ fn is_mutating(method: &str) -> bool {
             | "task.retry"
             | "task.cancel"
             | "loop.process"
+            | "loop.schedule"
             | "loop.prune"
             | "loop.retry"
             | "loop.discard"
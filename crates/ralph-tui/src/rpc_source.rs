@@ -18,7 +18,7 @@ use tracing::{debug, warn};
 
 use ralph_proto::json_rpc::RpcEvent;
 
-use crate::state::{TaskCounts, TuiState};
+use crate::state::{FanOutBranchStatus, TaskCounts, TuiState};
 use crate::state_mutations::{
     append_error_line, apply_loop_completed, apply_task_active, apply_task_close,
 };
@@ -380,6 +380,19 @@ fn apply_rpc_event(event: &RpcEvent, state: &Arc<Mutex<TuiState>>, acc: &mut Tex
             s.last_event_at = Some(Instant::now());
         }
 
+        RpcEvent::FanOutBranch {
+            hat,
+            status,
+            cost_usd,
+            tokens,
+            ..
+        } => {
+            s.update_fan_out_branch(FanOutBranchStatus::new(hat, status, *cost_usd, *tokens));
+
+            s.last_event = Some("fan_out_branch".to_string());
+            s.last_event_at = Some(Instant::now());
+        }
+
         RpcEvent::GuidanceAck { .. } => {
             // Just update liveness
             s.last_event = Some("guidance_ack".to_string());
@@ -60,6 +60,40 @@ impl TaskCounts {
     }
 }
 
+// ============================================================================
+// FanOutBranchStatus - Per-branch progress of a fan-out hat
+// ============================================================================
+
+/// Progress of a single fan-out branch for TUI display.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FanOutBranchStatus {
+    /// Branch hat ID.
+    pub hat: String,
+    /// Branch status: "running", "completed", "failed" or "timed_out".
+    pub status: String,
+    /// Estimated cost of the branch in USD.
+    pub cost_usd: f64,
+    /// Tokens used by the branch.
+    pub tokens: u64,
+}
+
+impl FanOutBranchStatus {
+    /// Creates a new branch status.
+    pub fn new(
+        hat: impl Into<String>,
+        status: impl Into<String>,
+        cost_usd: f64,
+        tokens: u64,
+    ) -> Self {
+        Self {
+            hat: hat.into(),
+            status: status.into(),
+            cost_usd,
+            tokens,
+        }
+    }
+}
+
 // ============================================================================
 // SearchState - Search functionality for TUI content
 // ============================================================================
@@ -197,6 +231,8 @@ pub struct TuiState {
     pub task_counts: TaskCounts,
     /// Currently active task (if any) for display in TUI widgets.
     pub active_task: Option<TaskSummary>,
+    /// Branches of the current iteration's fan-out, in launch order.
+    pub fan_out_branches: Vec<FanOutBranchStatus>,
 
     // ========================================================================
     // Guidance State
@@ -267,6 +303,7 @@ impl TuiState {
             // Task tracking state
             task_counts: TaskCounts::default(),
             active_task: None,
+            fan_out_branches: Vec::new(),
             // Guidance state
             guidance_mode: None,
             guidance_input: String::new(),
@@ -315,6 +352,7 @@ impl TuiState {
             // Task tracking state
             task_counts: TaskCounts::default(),
             active_task: None,
+            fan_out_branches: Vec::new(),
             // Guidance state
             guidance_mode: None,
             guidance_input: String::new(),
@@ -496,6 +534,18 @@ impl TuiState {
         self.task_counts.open > 0
     }
 
+    /// Records a fan-out branch's progress, replacing any earlier entry for the same hat.
+    pub fn update_fan_out_branch(&mut self, branch: FanOutBranchStatus) {
+        match self
+            .fan_out_branches
+            .iter_mut()
+            .find(|b| b.hat == branch.hat)
+        {
+            Some(existing) => *existing = branch,
+            None => self.fan_out_branches.push(branch),
+        }
+    }
+
     /// Returns a formatted string for task progress display (e.g., "3/5 tasks").
     pub fn get_task_progress_display(&self) -> String {
         if self.task_counts.total == 0 {
@@ -528,6 +578,7 @@ impl TuiState {
         // Reset text accumulation buffer for the new iteration
         self.rpc_text_buffer.clear();
         self.rpc_text_line_count = 0;
+        self.fan_out_branches.clear();
 
         let hat_display = hat_display.or_else(|| {
             self.pending_hat
@@ -1446,6 +1497,25 @@ mod tests {
         );
     }
 
+    #[test]
+    fn fan_out_branches_update_in_place_and_reset_per_iteration() {
+        let mut state = TuiState::new();
+        state.start_new_iteration();
+
+        state.update_fan_out_branch(FanOutBranchStatus::new("security", "running", 0.0, 0));
+        state.update_fan_out_branch(FanOutBranchStatus::new("perf", "running", 0.0, 0));
+        state.update_fan_out_branch(FanOutBranchStatus::new("security", "timed_out", 0.05, 1200));
+
+        assert_eq!(state.fan_out_branches.len(), 2);
+        assert_eq!(state.fan_out_branches[0].hat, "security");
+        assert_eq!(state.fan_out_branches[0].status, "timed_out");
+        assert_eq!(state.fan_out_branches[0].tokens, 1200);
+        assert_eq!(state.fan_out_branches[1].status, "running");
+
+        state.start_new_iteration();
+        assert!(state.fan_out_branches.is_empty());
+    }
+
     #[test]
     fn task_start_preserves_iterations_across_reset() {
         // Regression test: task.start used to do *self = Self::new() which wiped
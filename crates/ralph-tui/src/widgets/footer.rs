@@ -130,6 +130,27 @@ impl Widget for Footer<'_> {
         };
         left_spans.push(Span::raw(elapsed_display));
 
+        // Show per-branch progress while a fan-out hat is running
+        if !self.state.fan_out_branches.is_empty() {
+            left_spans.push(Span::raw(" │ fan-out:"));
+            for branch in &self.state.fan_out_branches {
+                let (glyph, color) = match branch.status.as_str() {
+                    "completed" => ("✓", Color::Green),
+                    "failed" => ("✗", Color::Red),
+                    "timed_out" => ("⏱", Color::Yellow),
+                    _ => ("…", Color::Cyan),
+                };
+                left_spans.push(Span::raw(format!(" {} ", branch.hat)));
+                left_spans.push(Span::styled(glyph, Style::default().fg(color)));
+                if branch.cost_usd > 0.0 {
+                    left_spans.push(Span::styled(
+                        format!(" ${:.2}", branch.cost_usd),
+                        Style::default().fg(Color::DarkGray),
+                    ));
+                }
+            }
+        }
+
         let indicator_text = if self.state.loop_completed {
             "■ DONE"
         } else {
@@ -223,6 +244,24 @@ mod tests {
         );
     }
 
+    #[test]
+    fn footer_shows_fan_out_branch_progress() {
+        use crate::state::FanOutBranchStatus;
+
+        let mut state = TuiState::new();
+        state.update_fan_out_branch(FanOutBranchStatus::new("security", "completed", 0.12, 900));
+        state.update_fan_out_branch(FanOutBranchStatus::new("perf", "timed_out", 0.0, 0));
+        state.update_fan_out_branch(FanOutBranchStatus::new("docs", "running", 0.0, 0));
+
+        let text = render_to_string_with_width(&state, 120);
+
+        assert!(
+            text.contains("fan-out: security ✓ $0.12 perf ⏱ docs …"),
+            "should show per-branch fan-out progress, got: {}",
+            text
+        );
+    }
+
     #[test]
     fn footer_no_alert_when_following() {
         // Given following_latest = true (even if new_iteration_alert has a value)
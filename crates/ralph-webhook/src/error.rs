@@ -0,0 +1,28 @@
+use thiserror::Error;
+
+/// Result type alias for webhook operations.
+pub type WebhookResult<T> = std::result::Result<T, WebhookError>;
+
+/// Errors that can occur while delivering webhook notifications.
+#[derive(Debug, Error)]
+pub enum WebhookError {
+    /// No webhook URL is configured.
+    #[error("no webhook URLs configured: set RObot.webhook.urls")]
+    NoUrls,
+
+    /// Failed to build the HTTP client or runtime used for delivery.
+    #[error("failed to start webhook delivery: {0}")]
+    Startup(String),
+
+    /// No endpoint accepted the message; it has been spooled for redelivery.
+    #[error("failed to deliver webhook after {attempts} attempts: {reason}")]
+    Send { attempts: u32, reason: String },
+
+    /// Failed to read or write the spool or events file.
+    #[error("webhook spool error: {0}")]
+    Spool(#[from] std::io::Error),
+
+    /// Failed to encode or decode JSON.
+    #[error("webhook serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+}
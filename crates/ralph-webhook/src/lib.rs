@@ -0,0 +1,29 @@
+//! # ralph-webhook
+//!
+//! Signed webhook notifications for human-in-the-loop orchestration in Ralph.
+//!
+//! [`WebhookService`] implements [`ralph_proto::RobotService`] by POSTing JSON
+//! messages to configured URLs, so chat tools and incident systems other than
+//! Telegram can follow a loop:
+//!
+//! - **AI → Human**: `human.interact` questions, periodic check-ins, and loop
+//!   completion/failure notices are POSTed with an HMAC-SHA256 signature
+//! - **Human → AI**: receivers POST answers to `ralph-api`'s
+//!   `/hooks/human-response` endpoint, which appends `human.response` to the
+//!   loop's events file
+//!
+//! ## Key Components
+//!
+//! - [`WebhookService`] — Delivery with retry/backoff and the response wait
+//! - [`Spool`] — On-disk queue of messages that could not be delivered
+//! - [`signing`] — Request signing and verification shared with `ralph-api`
+//! - [`error`] — Error types for delivery and spool failures
+
+pub mod error;
+mod service;
+pub mod signing;
+mod spool;
+
+pub use error::{WebhookError, WebhookResult};
+pub use service::{MessageKind, WebhookMessage, WebhookService};
+pub use spool::{Spool, SpooledMessage};
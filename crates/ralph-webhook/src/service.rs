@@ -0,0 +1,481 @@
+use std::future::Future;
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::time::{Duration, Instant};
+
+use chrono::Utc;
+use ralph_proto::{CheckinContext, LoopOutcome};
+use reqwest::header::CONTENT_TYPE;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use tracing::{debug, info, warn};
+
+use crate::error::{WebhookError, WebhookResult};
+use crate::signing::{EVENT_HEADER, SIGNATURE_HEADER, TIMESTAMP_HEADER, sign};
+use crate::spool::{Spool, SpooledMessage};
+
+/// Default delivery attempts per endpoint before a message is spooled.
+pub const DEFAULT_MAX_ATTEMPTS: u32 = 3;
+
+/// Base delay for exponential backoff between attempts (1s, 2s, 4s, ...).
+pub const BASE_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// Per-request HTTP timeout.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// What a webhook message is about; also sent as the `X-Ralph-Event` header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MessageKind {
+    #[serde(rename = "question")]
+    Question,
+    #[serde(rename = "checkin")]
+    Checkin,
+    #[serde(rename = "loop.completed")]
+    LoopCompleted,
+    #[serde(rename = "loop.failed")]
+    LoopFailed,
+}
+
+impl MessageKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Question => "question",
+            Self::Checkin => "checkin",
+            Self::LoopCompleted => "loop.completed",
+            Self::LoopFailed => "loop.failed",
+        }
+    }
+}
+
+/// JSON body POSTed to every webhook URL.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookMessage {
+    /// Unique message ID (stable across redeliveries, usable for dedup).
+    pub id: String,
+    #[serde(rename = "type")]
+    pub kind: MessageKind,
+    pub loop_id: String,
+    pub timestamp: String,
+    /// Where to POST the human's answer (questions only).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response_url: Option<String>,
+    pub data: Value,
+}
+
+/// Robot service that notifies humans through signed webhooks.
+///
+/// Each message is POSTed to every configured URL with exponential backoff.
+/// Deliveries that still fail are spooled to `.ralph/webhook-spool.jsonl`
+/// and retried on the next start or send. Responses arrive as
+/// `human.response` events in the loop's events file (written by
+/// `ralph-api`), which [`WebhookService::wait_for_response`] polls for.
+pub struct WebhookService {
+    urls: Vec<String>,
+    secret: String,
+    timeout_secs: u64,
+    loop_id: String,
+    max_attempts: u32,
+    retry_delay: Duration,
+    response_url: Option<String>,
+    client: reqwest::Client,
+    spool: Spool,
+    sequence: AtomicU32,
+    shutdown: Arc<AtomicBool>,
+}
+
+impl WebhookService {
+    /// Create a new WebhookService that spools under `workspace_root`.
+    pub fn new(
+        workspace_root: &Path,
+        urls: Vec<String>,
+        secret: String,
+        timeout_secs: u64,
+        loop_id: String,
+    ) -> WebhookResult<Self> {
+        if urls.is_empty() {
+            return Err(WebhookError::NoUrls);
+        }
+
+        // Each delivery runs on a short-lived runtime, so pooled connections
+        // would outlive the runtime that owns them.
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .pool_max_idle_per_host(0)
+            .build()
+            .map_err(|error| WebhookError::Startup(error.to_string()))?;
+
+        Ok(Self {
+            urls,
+            secret,
+            timeout_secs,
+            loop_id,
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            retry_delay: BASE_RETRY_DELAY,
+            response_url: None,
+            client,
+            spool: Spool::for_workspace(workspace_root),
+            sequence: AtomicU32::new(0),
+            shutdown: Arc::new(AtomicBool::new(false)),
+        })
+    }
+
+    /// Set the delivery attempts per endpoint (at least 1).
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Set the base backoff delay between attempts.
+    pub fn with_retry_delay(mut self, delay: Duration) -> Self {
+        self.retry_delay = delay;
+        self
+    }
+
+    /// Set the URL advertised to receivers for posting human responses.
+    pub fn with_response_url(mut self, response_url: Option<String>) -> Self {
+        self.response_url = response_url;
+        self
+    }
+
+    /// Get the configured timeout in seconds.
+    pub fn timeout_secs(&self) -> u64 {
+        self.timeout_secs
+    }
+
+    /// Get the loop ID this service is associated with.
+    pub fn loop_id(&self) -> &str {
+        &self.loop_id
+    }
+
+    /// Get the spool holding undelivered messages.
+    pub fn spool(&self) -> &Spool {
+        &self.spool
+    }
+
+    /// Returns a clone of the shutdown flag.
+    pub fn shutdown_flag(&self) -> Arc<AtomicBool> {
+        self.shutdown.clone()
+    }
+
+    /// Start the service by redelivering anything left in the spool.
+    pub fn start(&self) -> WebhookResult<()> {
+        let remaining = self.flush_spool()?;
+        info!(
+            urls = self.urls.len(),
+            spooled = remaining,
+            "Webhook robot service started"
+        );
+        Ok(())
+    }
+
+    /// Makes one redelivery attempt per spooled message.
+    ///
+    /// Returns how many messages remain spooled.
+    pub fn flush_spool(&self) -> WebhookResult<usize> {
+        let pending = self.spool.load()?;
+        if pending.is_empty() {
+            return Ok(0);
+        }
+
+        let remaining = run_blocking(self.redeliver(pending))?;
+        self.spool.replace(&remaining)?;
+        Ok(remaining.len())
+    }
+
+    /// Send a `human.interact` question.
+    pub fn send_question(&self, payload: &str) -> WebhookResult<i32> {
+        self.send(MessageKind::Question, json!({ "question": payload }))
+    }
+
+    /// Send a periodic check-in.
+    pub fn send_checkin(
+        &self,
+        iteration: u32,
+        elapsed: Duration,
+        context: Option<&CheckinContext>,
+    ) -> WebhookResult<i32> {
+        let mut data = json!({
+            "iteration": iteration,
+            "elapsedSecs": elapsed.as_secs(),
+        });
+        if let Some(context) = context {
+            data["currentHat"] = json!(context.current_hat);
+            data["openTasks"] = json!(context.open_tasks);
+            data["closedTasks"] = json!(context.closed_tasks);
+            data["cumulativeCost"] = json!(context.cumulative_cost);
+        }
+        self.send(MessageKind::Checkin, data)
+    }
+
+    /// Send a loop completion or failure notice.
+    pub fn send_outcome(&self, outcome: &LoopOutcome) -> WebhookResult<i32> {
+        let kind = if outcome.success {
+            MessageKind::LoopCompleted
+        } else {
+            MessageKind::LoopFailed
+        };
+        self.send(
+            kind,
+            json!({
+                "reason": outcome.reason,
+                "success": outcome.success,
+                "exitCode": outcome.exit_code,
+                "iterations": outcome.iterations,
+                "elapsedSecs": outcome.elapsed.as_secs(),
+                "cumulativeCost": outcome.cumulative_cost,
+            }),
+        )
+    }
+
+    /// Deliver a message to every URL, spooling per-URL failures.
+    ///
+    /// Returns the message sequence number when at least one endpoint
+    /// accepted it, or `WebhookError::Send` when none did.
+    fn send(&self, kind: MessageKind, data: Value) -> WebhookResult<i32> {
+        let sequence = self.sequence.fetch_add(1, Ordering::Relaxed) + 1;
+        let now = Utc::now();
+        let message = WebhookMessage {
+            id: format!("{}-{}-{sequence}", self.loop_id, now.timestamp_millis()),
+            kind,
+            loop_id: self.loop_id.clone(),
+            timestamp: now.to_rfc3339(),
+            response_url: match kind {
+                MessageKind::Question => self.response_url.clone(),
+                _ => None,
+            },
+            data,
+        };
+
+        let pending = self.spool.load()?;
+        let (mut spooled, failures) = run_blocking(async {
+            let remaining = self.redeliver(pending).await;
+            let mut failures = Vec::new();
+            for url in &self.urls {
+                if let Err(error) = self.deliver_with_retry(url, &message).await {
+                    failures.push(SpooledMessage {
+                        url: url.clone(),
+                        message: message.clone(),
+                        attempts: self.max_attempts,
+                        last_error: error,
+                    });
+                }
+            }
+            (remaining, failures)
+        })?;
+
+        let all_failed = failures.len() == self.urls.len();
+        let reason = failures
+            .last()
+            .map(|failure| failure.last_error.clone())
+            .unwrap_or_default();
+        if !failures.is_empty() {
+            warn!(
+                message_id = %message.id,
+                failed_urls = failures.len(),
+                spool = %self.spool.path().display(),
+                "Webhook delivery failed — spooled for redelivery"
+            );
+        }
+        spooled.extend(failures);
+        self.spool.replace(&spooled)?;
+
+        if all_failed {
+            return Err(WebhookError::Send {
+                attempts: self.max_attempts,
+                reason,
+            });
+        }
+
+        debug!(message_id = %message.id, kind = kind.as_str(), "Webhook delivered");
+        Ok(sequence as i32)
+    }
+
+    async fn redeliver(&self, pending: Vec<SpooledMessage>) -> Vec<SpooledMessage> {
+        let mut remaining = Vec::new();
+        for mut entry in pending {
+            entry.attempts += 1;
+            match self.deliver(&entry.url, &entry.message).await {
+                Ok(()) => info!(message_id = %entry.message.id, "Redelivered spooled webhook"),
+                Err(error) => {
+                    entry.last_error = error;
+                    remaining.push(entry);
+                }
+            }
+        }
+        remaining
+    }
+
+    async fn deliver_with_retry(&self, url: &str, message: &WebhookMessage) -> Result<(), String> {
+        let mut last_error = String::new();
+        for attempt in 1..=self.max_attempts {
+            match self.deliver(url, message).await {
+                Ok(()) => return Ok(()),
+                Err(error) => {
+                    warn!(
+                        attempt,
+                        max_attempts = self.max_attempts,
+                        url,
+                        error = %error,
+                        "Webhook delivery failed"
+                    );
+                    last_error = error;
+                    if attempt < self.max_attempts {
+                        tokio::time::sleep(self.retry_delay * 2u32.pow(attempt - 1)).await;
+                    }
+                }
+            }
+        }
+        Err(last_error)
+    }
+
+    async fn deliver(&self, url: &str, message: &WebhookMessage) -> Result<(), String> {
+        let body = serde_json::to_vec(message).map_err(|error| error.to_string())?;
+        let timestamp = Utc::now().timestamp();
+        let signature = sign(&self.secret, timestamp, &body);
+
+        let response = self
+            .client
+            .post(url)
+            .header(CONTENT_TYPE, "application/json")
+            .header(TIMESTAMP_HEADER, timestamp.to_string())
+            .header(SIGNATURE_HEADER, signature)
+            .header(EVENT_HEADER, message.kind.as_str())
+            .body(body)
+            .send()
+            .await
+            .map_err(|error| error.to_string())?;
+
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(format!("endpoint returned HTTP {}", response.status()))
+        }
+    }
+
+    /// Poll the events file for a `human.response` event, blocking until one
+    /// arrives, the configured timeout expires, or shutdown is requested.
+    pub fn wait_for_response(&self, events_path: &Path) -> WebhookResult<Option<String>> {
+        let deadline = Instant::now() + Duration::from_secs(self.timeout_secs);
+        let poll_interval = Duration::from_millis(250);
+        let mut file_pos = std::fs::metadata(events_path).map_or(0, |m| m.len());
+
+        info!(
+            loop_id = %self.loop_id,
+            timeout_secs = self.timeout_secs,
+            events_path = %events_path.display(),
+            "Waiting for human.response"
+        );
+
+        loop {
+            if let Some(response) = check_for_response(events_path, &mut file_pos)? {
+                info!(loop_id = %self.loop_id, "Received human.response");
+                return Ok(Some(response));
+            }
+            if self.shutdown.load(Ordering::Relaxed) {
+                info!(loop_id = %self.loop_id, "Interrupted while waiting for human.response");
+                return Ok(None);
+            }
+            if Instant::now() >= deadline {
+                warn!(
+                    loop_id = %self.loop_id,
+                    timeout_secs = self.timeout_secs,
+                    "Timed out waiting for human.response"
+                );
+                return Ok(None);
+            }
+            std::thread::sleep(poll_interval);
+        }
+    }
+}
+
+/// Reads new lines from `file_pos` and returns the first `human.response`
+/// payload. Updates `file_pos` to the end of what was read.
+fn check_for_response(events_path: &Path, file_pos: &mut u64) -> WebhookResult<Option<String>> {
+    use std::io::{BufRead, BufReader, Seek, SeekFrom};
+
+    if !events_path.exists() {
+        return Ok(None);
+    }
+
+    let mut file = std::fs::File::open(events_path)?;
+    file.seek(SeekFrom::Start(*file_pos))?;
+
+    for line in BufReader::new(file).lines() {
+        let line = line?;
+        *file_pos += line.len() as u64 + 1;
+
+        if let Ok(event) = serde_json::from_str::<Value>(&line)
+            && event.get("topic").and_then(Value::as_str) == Some("human.response")
+        {
+            let message = event
+                .get("payload")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string();
+            return Ok(Some(message));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Runs `future` to completion on a dedicated thread with its own runtime,
+/// so delivery works from sync code and from inside any tokio runtime flavor.
+fn run_blocking<F>(future: F) -> WebhookResult<F::Output>
+where
+    F: Future + Send,
+    F::Output: Send,
+{
+    std::thread::scope(|scope| {
+        scope
+            .spawn(|| {
+                let runtime = tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build()
+                    .map_err(|error| WebhookError::Startup(error.to_string()))?;
+                Ok(runtime.block_on(future))
+            })
+            .join()
+            .map_err(|_| WebhookError::Startup("webhook delivery thread panicked".to_string()))?
+    })
+}
+
+impl ralph_proto::RobotService for WebhookService {
+    fn send_question(&self, payload: &str) -> anyhow::Result<i32> {
+        Ok(WebhookService::send_question(self, payload)?)
+    }
+
+    fn wait_for_response(&self, events_path: &Path) -> anyhow::Result<Option<String>> {
+        Ok(WebhookService::wait_for_response(self, events_path)?)
+    }
+
+    fn send_checkin(
+        &self,
+        iteration: u32,
+        elapsed: Duration,
+        context: Option<&CheckinContext>,
+    ) -> anyhow::Result<i32> {
+        Ok(WebhookService::send_checkin(
+            self, iteration, elapsed, context,
+        )?)
+    }
+
+    fn send_outcome(&self, outcome: &LoopOutcome) -> anyhow::Result<i32> {
+        Ok(WebhookService::send_outcome(self, outcome)?)
+    }
+
+    fn timeout_secs(&self) -> u64 {
+        self.timeout_secs
+    }
+
+    fn shutdown_flag(&self) -> Arc<AtomicBool> {
+        self.shutdown.clone()
+    }
+
+    fn stop(self: Box<Self>) {
+        self.shutdown.store(true, Ordering::Relaxed);
+        info!(loop_id = %self.loop_id, "Webhook robot service stopped");
+    }
+}
@@ -0,0 +1,139 @@
+//! HMAC-SHA256 request signing.
+//!
+//! Every request carries a [`TIMESTAMP_HEADER`] (unix seconds) and a
+//! [`SIGNATURE_HEADER`] of the form `sha256=<hex>`, computed over
+//! `"{timestamp}.{body}"` with the shared secret. Binding the timestamp into
+//! the signature lets receivers reject replayed requests.
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+/// Header carrying the request signature.
+pub const SIGNATURE_HEADER: &str = "X-Ralph-Signature";
+
+/// Header carrying the unix timestamp (seconds) the signature was made at.
+pub const TIMESTAMP_HEADER: &str = "X-Ralph-Timestamp";
+
+/// Header carrying the message type (`question`, `checkin`, ...).
+pub const EVENT_HEADER: &str = "X-Ralph-Event";
+
+/// Maximum accepted clock skew between signer and verifier.
+pub const MAX_SKEW_SECS: i64 = 300;
+
+const SIGNATURE_PREFIX: &str = "sha256=";
+
+type HmacSha256 = Hmac<Sha256>;
+
+fn mac(secret: &str, timestamp: i64, body: &[u8]) -> HmacSha256 {
+    let mut mac =
+        HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    mac
+}
+
+/// Signs `body` at `timestamp`, returning the [`SIGNATURE_HEADER`] value.
+pub fn sign(secret: &str, timestamp: i64, body: &[u8]) -> String {
+    let digest = mac(secret, timestamp, body).finalize().into_bytes();
+    format!("{SIGNATURE_PREFIX}{digest:x}")
+}
+
+/// Verifies a signature in constant time and checks the timestamp is within
+/// [`MAX_SKEW_SECS`] of `now`.
+pub fn verify(secret: &str, timestamp: i64, body: &[u8], signature: &str, now: i64) -> bool {
+    if now.abs_diff(timestamp) > MAX_SKEW_SECS.unsigned_abs() {
+        return false;
+    }
+
+    let Some(expected) = signature
+        .strip_prefix(SIGNATURE_PREFIX)
+        .and_then(decode_hex)
+    else {
+        return false;
+    };
+
+    mac(secret, timestamp, body).verify_slice(&expected).is_ok()
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(hex.get(index..index + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signature_round_trips() {
+        let signature = sign("secret", 1_700_000_000, b"{\"a\":1}");
+        assert!(signature.starts_with("sha256="));
+        assert!(verify(
+            "secret",
+            1_700_000_000,
+            b"{\"a\":1}",
+            &signature,
+            1_700_000_010
+        ));
+    }
+
+    #[test]
+    fn rejects_tampering_wrong_secret_and_stale_timestamps() {
+        let signature = sign("secret", 1_700_000_000, b"body");
+        assert!(!verify(
+            "secret",
+            1_700_000_000,
+            b"bodY",
+            &signature,
+            1_700_000_000
+        ));
+        assert!(!verify(
+            "other",
+            1_700_000_000,
+            b"body",
+            &signature,
+            1_700_000_000
+        ));
+        assert!(!verify(
+            "secret",
+            1_700_000_001,
+            b"body",
+            &signature,
+            1_700_000_001
+        ));
+        assert!(!verify(
+            "secret",
+            1_700_000_000,
+            b"body",
+            &signature,
+            1_700_000_000 + MAX_SKEW_SECS + 1
+        ));
+        assert!(!verify(
+            "secret",
+            1_700_000_000,
+            b"body",
+            "sha256=zz",
+            1_700_000_000
+        ));
+    }
+
+    #[test]
+    fn rejects_extreme_timestamps_without_overflowing() {
+        for timestamp in [i64::MIN, i64::MAX] {
+            let signature = sign("secret", timestamp, b"body");
+            assert!(!verify(
+                "secret",
+                timestamp,
+                b"body",
+                &signature,
+                1_700_000_000
+            ));
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::service::WebhookMessage;
+
+/// A message that could not be delivered to one endpoint.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpooledMessage {
+    pub url: String,
+    pub message: WebhookMessage,
+    /// Delivery attempts made so far, across redeliveries.
+    pub attempts: u32,
+    pub last_error: String,
+}
+
+/// Undelivered messages, persisted as JSONL (`.ralph/webhook-spool.jsonl`).
+///
+/// Messages are appended once retries are exhausted and redelivered the
+/// next time the service starts or sends.
+#[derive(Debug, Clone)]
+pub struct Spool {
+    path: PathBuf,
+}
+
+impl Spool {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Spool location for a workspace.
+    pub fn for_workspace(workspace_root: &Path) -> Self {
+        Self::new(workspace_root.join(".ralph/webhook-spool.jsonl"))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn load(&self) -> io::Result<Vec<SpooledMessage>> {
+        let content = match fs::read_to_string(&self.path) {
+            Ok(content) => content,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(error) => return Err(error),
+        };
+
+        Ok(content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .filter_map(|line| match serde_json::from_str(line) {
+                Ok(entry) => Some(entry),
+                Err(error) => {
+                    warn!(path = %self.path.display(), %error, "skipping unreadable spooled webhook");
+                    None
+                }
+            })
+            .collect())
+    }
+
+    pub fn push(&self, entry: &SpooledMessage) -> io::Result<()> {
+        let mut entries = self.load()?;
+        entries.push(entry.clone());
+        self.replace(&entries)
+    }
+
+    /// Atomically replaces the spool contents; an empty list removes the file.
+    pub fn replace(&self, entries: &[SpooledMessage]) -> io::Result<()> {
+        if entries.is_empty() {
+            return match fs::remove_file(&self.path) {
+                Err(error) if error.kind() != io::ErrorKind::NotFound => Err(error),
+                _ => Ok(()),
+            };
+        }
+
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut payload = Vec::new();
+        for entry in entries {
+            serde_json::to_writer(&mut payload, entry).map_err(io::Error::other)?;
+            payload.push(b'\n');
+        }
+        let tmp_path = self.path.with_extension("jsonl.tmp");
+        fs::write(&tmp_path, payload)?;
+        fs::rename(tmp_path, &self.path)
+    }
+}
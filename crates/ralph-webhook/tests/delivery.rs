@@ -0,0 +1,219 @@
+//! Delivery tests against a local HTTP stub.
+
+use std::collections::{HashMap, VecDeque};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use ralph_proto::{LoopOutcome, RobotService};
+use ralph_webhook::signing::{SIGNATURE_HEADER, TIMESTAMP_HEADER, verify};
+use ralph_webhook::{WebhookError, WebhookService};
+use serde_json::Value;
+
+const SECRET: &str = "test-secret";
+
+struct Received {
+    headers: HashMap<String, String>,
+    body: Vec<u8>,
+}
+
+impl Received {
+    fn json(&self) -> Value {
+        serde_json::from_slice(&self.body).expect("stub received JSON")
+    }
+
+    fn header(&self, name: &str) -> &str {
+        self.headers
+            .get(&name.to_ascii_lowercase())
+            .map_or("", String::as_str)
+    }
+}
+
+/// Minimal HTTP/1.1 server that records requests and replies with queued
+/// status codes (200 once the queue is empty).
+struct Stub {
+    url: String,
+    received: Arc<Mutex<Vec<Received>>>,
+    statuses: Arc<Mutex<VecDeque<u16>>>,
+}
+
+impl Stub {
+    fn start(statuses: &[u16]) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind stub");
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let statuses = Arc::new(Mutex::new(
+            statuses.iter().copied().collect::<VecDeque<u16>>(),
+        ));
+
+        let thread_received = received.clone();
+        let thread_statuses = statuses.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else { continue };
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+                let mut headers = HashMap::new();
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                loop {
+                    line.clear();
+                    reader.read_line(&mut line).unwrap();
+                    let trimmed = line.trim_end();
+                    if trimmed.is_empty() {
+                        break;
+                    }
+                    if let Some((name, value)) = trimmed.split_once(':') {
+                        headers.insert(name.trim().to_ascii_lowercase(), value.trim().to_string());
+                    }
+                }
+                let length = headers
+                    .get("content-length")
+                    .and_then(|value| value.parse().ok())
+                    .unwrap_or(0);
+                let mut body = vec![0; length];
+                reader.read_exact(&mut body).unwrap();
+
+                thread_received
+                    .lock()
+                    .unwrap()
+                    .push(Received { headers, body });
+                let status = thread_statuses.lock().unwrap().pop_front().unwrap_or(200);
+                let _ = write!(
+                    stream,
+                    "HTTP/1.1 {status} Stub\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                );
+            }
+        });
+
+        Self {
+            url,
+            received,
+            statuses,
+        }
+    }
+
+    fn take(&self) -> Vec<Received> {
+        std::mem::take(&mut *self.received.lock().unwrap())
+    }
+}
+
+fn service(workspace: &std::path::Path, stub: &Stub) -> WebhookService {
+    WebhookService::new(
+        workspace,
+        vec![stub.url.clone()],
+        SECRET.to_string(),
+        2,
+        "primary-test".to_string(),
+    )
+    .expect("service")
+    .with_max_attempts(2)
+    .with_retry_delay(Duration::from_millis(1))
+    .with_response_url(Some(
+        "http://127.0.0.1:3000/hooks/human-response".to_string(),
+    ))
+}
+
+#[test]
+fn delivers_signed_questions_and_outcomes() {
+    let workspace = tempfile::tempdir().unwrap();
+    let stub = Stub::start(&[]);
+    let service = service(workspace.path(), &stub);
+
+    assert_eq!(service.send_question("Ship it?").unwrap(), 1);
+    RobotService::send_outcome(
+        &service,
+        &LoopOutcome {
+            reason: "max_iterations".to_string(),
+            success: false,
+            exit_code: 2,
+            iterations: 10,
+            elapsed: Duration::from_secs(90),
+            cumulative_cost: 1.5,
+        },
+    )
+    .unwrap();
+
+    let received = stub.take();
+    assert_eq!(received.len(), 2);
+
+    let question = &received[0];
+    let timestamp: i64 = question.header(TIMESTAMP_HEADER).parse().unwrap();
+    assert!(verify(
+        SECRET,
+        timestamp,
+        &question.body,
+        question.header(SIGNATURE_HEADER),
+        timestamp
+    ));
+    assert_eq!(question.header("X-Ralph-Event"), "question");
+    let body = question.json();
+    assert_eq!(body["type"], "question");
+    assert_eq!(body["loopId"], "primary-test");
+    assert_eq!(body["data"]["question"], "Ship it?");
+    assert_eq!(
+        body["responseUrl"],
+        "http://127.0.0.1:3000/hooks/human-response"
+    );
+
+    let outcome = received[1].json();
+    assert_eq!(outcome["type"], "loop.failed");
+    assert_eq!(outcome["data"]["exitCode"], 2);
+    assert!(outcome.get("responseUrl").is_none());
+}
+
+#[test]
+fn retries_then_spools_and_redelivers_undelivered_messages() {
+    let workspace = tempfile::tempdir().unwrap();
+    let stub = Stub::start(&[500, 503]);
+    let service = service(workspace.path(), &stub);
+
+    let error = service
+        .send_checkin(3, Duration::from_secs(30), None)
+        .unwrap_err();
+    assert!(matches!(error, WebhookError::Send { attempts: 2, .. }));
+    assert_eq!(stub.take().len(), 2);
+
+    let spooled = service.spool().load().unwrap();
+    assert_eq!(spooled.len(), 1);
+    assert_eq!(spooled[0].url, stub.url);
+
+    // A restarted service redelivers the spooled message on start.
+    let restarted = self::service(workspace.path(), &stub);
+    restarted.start().unwrap();
+    let redelivered = stub.take();
+    assert_eq!(redelivered.len(), 1);
+    assert_eq!(redelivered[0].json()["id"], spooled[0].message.id);
+    assert!(restarted.spool().load().unwrap().is_empty());
+    assert!(stub.statuses.lock().unwrap().is_empty());
+}
+
+#[test]
+fn wait_for_response_reads_human_response_events() {
+    let workspace = tempfile::tempdir().unwrap();
+    let stub = Stub::start(&[]);
+    let service = service(workspace.path(), &stub);
+
+    let events_path = workspace.path().join(".ralph/events.jsonl");
+    std::fs::create_dir_all(events_path.parent().unwrap()).unwrap();
+    std::fs::write(
+        &events_path,
+        "{\"topic\":\"human.response\",\"payload\":\"old\"}\n",
+    )
+    .unwrap();
+
+    let writer_path = events_path.clone();
+    let writer = std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(300));
+        let mut file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(writer_path)
+            .unwrap();
+        writeln!(file, "{{\"topic\":\"human.response\",\"payload\":\"yes\"}}").unwrap();
+    });
+
+    let response = service.wait_for_response(&events_path).unwrap();
+    writer.join().unwrap();
+    assert_eq!(response.as_deref(), Some("yes"));
+}
@@ -13,6 +13,7 @@ const MUTATING_METHODS = new Set<string>([
   "task.retry",
   "task.cancel",
   "loop.process",
+  "loop.schedule",
   "loop.prune",
   "loop.retry",
   "loop.discard",